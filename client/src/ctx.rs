@@ -40,6 +40,7 @@ pub struct SessionConfig {
     pub precision: String,
     pub target_partitions: Option<usize>,
     pub stream_trigger_interval: Option<String>,
+    pub consistency: Option<String>,
     pub accept_encoding: Option<Encoding>,
     pub content_encoding: Option<Encoding>,
     pub fmt: PrintFormat,
@@ -64,6 +65,7 @@ impl SessionConfig {
             precision: DEFAULT_PRECISION.to_string(),
             target_partitions: None,
             stream_trigger_interval: None,
+            consistency: None,
             accept_encoding: None,
             content_encoding: None,
             config_options,
@@ -115,6 +117,11 @@ impl SessionConfig {
         self
     }
 
+    pub fn with_consistency(mut self, consistency: Option<String>) -> Self {
+        self.consistency = consistency;
+        self
+    }
+
     pub fn with_accept_encoding(mut self, accept_encoding: Option<Encoding>) -> Self {
         self.accept_encoding = accept_encoding;
         self
@@ -292,6 +299,7 @@ impl SessionContext {
         let db = self.session_config.database.clone();
         let target_partitions = self.session_config.target_partitions;
         let stream_trigger_interval = self.session_config.stream_trigger_interval.clone();
+        let consistency = self.session_config.consistency.clone();
         let chunked = self.session_config.chunked;
         let param = SqlParam {
             tenant: Some(tenant),
@@ -299,6 +307,7 @@ impl SessionContext {
             chunked: Some(chunked),
             target_partitions,
             stream_trigger_interval,
+            consistency,
         };
 
         // let param = &[("db", &self.session_config.database)];
@@ -415,6 +424,7 @@ impl SessionContext {
             precision: Some(precision),
             tenant: Some(tenant),
             db: Some(db),
+            consistency: self.session_config.consistency.clone(),
         };
 
         let mut builder = self
@@ -64,6 +64,11 @@ struct CliArgs {
     #[arg(short, long)]
     stream_trigger_interval: Option<String>,
 
+    /// Consistency level of reads and writes, will be used as the url param 'consistency',
+    /// writes at 'one' are acknowledged like 'quorum'
+    #[arg(long, value_parser = PossibleValuesParser::new(["any", "one", "quorum", "all"]))]
+    consistency: Option<String>,
+
     /// Path to your data, default to current directory
     #[arg(long, value_parser = try_parse_data_dir)]
     data_path: Option<String>,
@@ -144,6 +149,7 @@ impl CliArgs {
             .with_database(self.database.clone())
             .with_target_partitions(self.target_partitions)
             .with_stream_trigger_interval(self.stream_trigger_interval.clone())
+            .with_consistency(self.consistency.clone())
             .with_accept_encoding(self.receive_data_encoding)
             .with_content_encoding(self.send_data_encoding)
             .with_result_format(self.format)
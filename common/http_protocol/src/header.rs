@@ -19,6 +19,8 @@ pub const STAR_STAR: &str = "*/*";
 // basic auth
 pub const BASIC_PREFIX: &str = "Basic ";
 pub const BEARER_PREFIX: &str = "Bearer ";
+// token auth of InfluxDB
+pub const TOKEN_PREFIX: &str = "Token ";
 
 // parameters
 pub const TENANT: &str = "tenant";
@@ -26,6 +28,7 @@ pub const DB: &str = "db";
 pub const TABLE: &str = "table";
 pub const TARGET_PARTITIONS: &str = "target_partitions";
 pub const STREAM_TRIGGER_INTERVAL: &str = "stream_trigger_interval";
+pub const CONSISTENCY: &str = "consistency";
 
 // encoding
 pub const GZIP: &str = "gzip";
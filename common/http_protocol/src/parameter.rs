@@ -9,6 +9,9 @@ pub struct SqlParam {
     // Number of partitions for query execution. Increasing partitions can increase concurrency.
     pub target_partitions: Option<usize>,
     pub stream_trigger_interval: Option<String>,
+    // Consistency level of reads and writes: any, one, quorum or all, writes at one are
+    // acknowledged like quorum.
+    pub consistency: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -17,6 +20,30 @@ pub struct WriteParam {
     pub precision: Option<String>,
     pub tenant: Option<String>,
     pub db: Option<String>,
+    // Consistency level of writes: any, one, quorum or all, one is acknowledged like quorum.
+    pub consistency: Option<String>,
+}
+
+/// Parameters of the `/write` api of InfluxDB 1.x.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct InfluxWriteV1Param {
+    pub db: Option<String>,
+    pub rp: Option<String>,
+    pub precision: Option<String>,
+    pub u: Option<String>,
+    pub p: Option<String>,
+    // Consistency level of writes: any, one, quorum or all, one is acknowledged like quorum.
+    pub consistency: Option<String>,
+}
+
+/// Parameters of the `/api/v2/write` api of InfluxDB 2.x.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct InfluxWriteV2Param {
+    pub org: Option<String>,
+    #[serde(rename = "orgID")]
+    pub org_id: Option<String>,
+    pub bucket: Option<String>,
+    pub precision: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -1,10 +1,14 @@
 use reqwest::StatusCode;
 
 pub const OK: StatusCode = StatusCode::OK;
+/// 请求成功，没有返回内容
+pub const NO_CONTENT: StatusCode = StatusCode::NO_CONTENT;
 /// 请求参数非法
 pub const BAD_REQUEST: StatusCode = StatusCode::BAD_REQUEST;
 /// 用户密码错误 或 用户不存在
-pub const _UNAUTHORIZED: StatusCode = StatusCode::UNAUTHORIZED;
+pub const UNAUTHORIZED: StatusCode = StatusCode::UNAUTHORIZED;
+/// 用户权限不足
+pub const FORBIDDEN: StatusCode = StatusCode::FORBIDDEN;
 /// 路径不存在
 pub const NOT_FOUND: StatusCode = StatusCode::NOT_FOUND;
 /// 路径不支持对应的请求方式
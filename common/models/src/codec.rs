@@ -2,26 +2,28 @@ use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
-pub const BIGINT_CODEC: [Encoding; 5] = [
+pub const BIGINT_CODEC: [Encoding; 6] = [
     Encoding::Default,
     Encoding::Null,
     Encoding::Delta,
     Encoding::DeltaTs,
     Encoding::Quantile,
+    Encoding::Auto,
 ];
 // Because timestamp, bigint, and unsigned bigint are all integers,
 // so their compression algorithms are the same
-pub const TIMESTAMP_CODEC: [Encoding; 5] = BIGINT_CODEC;
-pub const UNSIGNED_BIGINT_CODEC: [Encoding; 5] = BIGINT_CODEC;
+pub const TIMESTAMP_CODEC: [Encoding; 6] = BIGINT_CODEC;
+pub const UNSIGNED_BIGINT_CODEC: [Encoding; 6] = BIGINT_CODEC;
 
-pub const DOUBLE_CODEC: [Encoding; 4] = [
+pub const DOUBLE_CODEC: [Encoding; 5] = [
     Encoding::Default,
     Encoding::Null,
     Encoding::Gorilla,
     Encoding::Quantile,
+    Encoding::Auto,
 ];
 
-pub const STRING_CODEC: [Encoding; 7] = [
+pub const STRING_CODEC: [Encoding; 9] = [
     Encoding::Default,
     Encoding::Null,
     Encoding::Gzip,
@@ -29,9 +31,16 @@ pub const STRING_CODEC: [Encoding; 7] = [
     Encoding::Zstd,
     Encoding::Snappy,
     Encoding::Zlib,
+    Encoding::Dictionary,
+    Encoding::Auto,
 ];
 
-pub const BOOLEAN_CODEC: [Encoding; 3] = [Encoding::Default, Encoding::Null, Encoding::BitPack];
+pub const BOOLEAN_CODEC: [Encoding; 4] = [
+    Encoding::Default,
+    Encoding::Null,
+    Encoding::BitPack,
+    Encoding::Auto,
+];
 
 #[derive(
     Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Hash, Default, Ord, PartialOrd,
@@ -50,6 +59,12 @@ pub enum Encoding {
     Zlib = 9,
     BitPack = 10,
     DeltaTs = 11,
+    /// Choose the encoding that compresses best for every page, the chosen encoding
+    /// is recorded in the page data, so it is never written itself.
+    Auto = 12,
+    /// Store the distinct values of a string page in a dictionary and the values
+    /// as bit-packed indices of the dictionary, for low-cardinality strings.
+    Dictionary = 13,
     Unknown = 15,
 }
 
@@ -92,6 +107,8 @@ impl Encoding {
             Encoding::Zstd => "ZSTD",
             Encoding::Zlib => "ZLIB",
             Encoding::BitPack => "BITPACK",
+            Encoding::Auto => "AUTO",
+            Encoding::Dictionary => "DICTIONARY",
             Encoding::Unknown => "UNKNOWN",
         }
     }
@@ -113,6 +130,8 @@ impl FromStr for Encoding {
             "ZSTD" => Ok(Self::Zstd),
             "ZLIB" => Ok(Self::Zlib),
             "BITPACK" => Ok(Self::BitPack),
+            "AUTO" => Ok(Self::Auto),
+            "DICTIONARY" => Ok(Self::Dictionary),
             _ => Err(s.to_string()),
         }
     }
@@ -133,6 +152,8 @@ impl From<u8> for Encoding {
             9 => Encoding::Zlib,
             10 => Encoding::BitPack,
             11 => Encoding::DeltaTs,
+            12 => Encoding::Auto,
+            13 => Encoding::Dictionary,
             _ => Encoding::Unknown,
         }
     }
@@ -1,12 +1,128 @@
-#[allow(dead_code)]
-#[derive(Debug)]
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// How many replicas should acknowledge a write, or which replica a read may be served by.
+///
+/// `Quorum` is encoded as 0 so that a request without consistency level keeps
+/// the default behavior of raft.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(u8)]
 pub enum ConsistencyLevel {
     /// allows for hinted handoff, potentially no write happened yet.
-    Any,
-    /// at least one data node acknowledged a write or read.
-    One,
+    Any = 1,
+    /// at least one data node to serve a read, a read may be served by a follower.
+    /// A raft leader can't acknowledge a write before it's committed, so a write at
+    /// this level is acknowledged like `Quorum`.
+    One = 2,
     /// a quorum of data nodes to acknowledge a write or read.
-    Quorum,
+    #[default]
+    Quorum = 0,
     /// requires all data nodes to acknowledge a write or read.
-    All,
+    All = 3,
+}
+
+impl ConsistencyLevel {
+    /// The level a write is acknowledged at, `One` is acknowledged like `Quorum`.
+    pub fn write_level(self) -> Self {
+        match self {
+            ConsistencyLevel::One => ConsistencyLevel::Quorum,
+            level => level,
+        }
+    }
+}
+
+impl From<u32> for ConsistencyLevel {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => ConsistencyLevel::Any,
+            2 => ConsistencyLevel::One,
+            3 => ConsistencyLevel::All,
+            _ => ConsistencyLevel::Quorum,
+        }
+    }
+}
+
+impl FromStr for ConsistencyLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "any" => Ok(ConsistencyLevel::Any),
+            "one" => Ok(ConsistencyLevel::One),
+            "quorum" => Ok(ConsistencyLevel::Quorum),
+            "all" => Ok(ConsistencyLevel::All),
+            _ => Err(format!(
+                "invalid consistency level '{s}', expected one of: any, one, quorum, all"
+            )),
+        }
+    }
+}
+
+impl Display for ConsistencyLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConsistencyLevel::Any => f.write_str("any"),
+            ConsistencyLevel::One => f.write_str("one"),
+            ConsistencyLevel::Quorum => f.write_str("quorum"),
+            ConsistencyLevel::All => f.write_str("all"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::ConsistencyLevel;
+
+    #[test]
+    fn test_parse_consistency_level() {
+        assert_eq!(
+            ConsistencyLevel::from_str("ANY").unwrap(),
+            ConsistencyLevel::Any
+        );
+        assert_eq!(
+            ConsistencyLevel::from_str("one").unwrap(),
+            ConsistencyLevel::One
+        );
+        assert_eq!(
+            ConsistencyLevel::from_str("Quorum").unwrap(),
+            ConsistencyLevel::Quorum
+        );
+        assert_eq!(
+            ConsistencyLevel::from_str("all").unwrap(),
+            ConsistencyLevel::All
+        );
+        assert!(ConsistencyLevel::from_str("two").is_err());
+    }
+
+    #[test]
+    fn test_consistency_level_code() {
+        for level in [
+            ConsistencyLevel::Any,
+            ConsistencyLevel::One,
+            ConsistencyLevel::Quorum,
+            ConsistencyLevel::All,
+        ] {
+            assert_eq!(ConsistencyLevel::from(level as u32), level);
+        }
+        assert_eq!(ConsistencyLevel::from(0), ConsistencyLevel::Quorum);
+        assert_eq!(ConsistencyLevel::from(100), ConsistencyLevel::Quorum);
+    }
+
+    #[test]
+    fn test_write_level() {
+        assert_eq!(ConsistencyLevel::Any.write_level(), ConsistencyLevel::Any);
+        assert_eq!(
+            ConsistencyLevel::One.write_level(),
+            ConsistencyLevel::Quorum
+        );
+        assert_eq!(
+            ConsistencyLevel::Quorum.write_level(),
+            ConsistencyLevel::Quorum
+        );
+        assert_eq!(ConsistencyLevel::All.write_level(), ConsistencyLevel::All);
+    }
 }
@@ -0,0 +1,143 @@
+use std::fmt::Display;
+
+use geo::BoundingRect;
+use geozero::wkt::WktStr;
+use geozero::ToGeo;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Copy, Clone)]
+pub struct BoundingBox {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+impl BoundingBox {
+    pub fn new(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Self {
+        Self {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        }
+    }
+
+    /// The bounding box of the geometry in WKT, `None` if the WKT is invalid or empty.
+    pub fn from_wkt(wkt: &str) -> Option<Self> {
+        let rect = WktStr(wkt).to_geo().ok()?.bounding_rect()?;
+        Some(Self::new(
+            rect.min().x,
+            rect.min().y,
+            rect.max().x,
+            rect.max().y,
+        ))
+    }
+
+    /// The bounding box of all the geometries in WKT, `None` if any of them is invalid
+    /// or all of them are empty, nulls are skipped.
+    pub fn from_wkts<'a>(wkts: impl IntoIterator<Item = Option<&'a str>>) -> Option<Self> {
+        let mut result: Option<Self> = None;
+        for wkt in wkts.into_iter().flatten() {
+            // Empty geometries have no bounding boxes, but they are not in any area either.
+            if wkt.trim_end().to_ascii_uppercase().ends_with("EMPTY") {
+                continue;
+            }
+            let bbox = Self::from_wkt(wkt)?;
+            result = Some(match result {
+                Some(result) => result.union(&bbox),
+                None => bbox,
+            });
+        }
+        result
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::new(
+            self.min_x.min(other.min_x),
+            self.min_y.min(other.min_y),
+            self.max_x.max(other.max_x),
+            self.max_y.max(other.max_y),
+        )
+    }
+
+    /// Expands the bounding box by `distance` in all directions.
+    pub fn expand(&self, distance: f64) -> Self {
+        Self::new(
+            self.min_x - distance,
+            self.min_y - distance,
+            self.max_x + distance,
+            self.max_y + distance,
+        )
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min_x <= other.max_x
+            && other.min_x <= self.max_x
+            && self.min_y <= other.max_y
+            && other.min_y <= self.max_y
+    }
+}
+
+impl Display for BoundingBox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "BOX({} {}, {} {})",
+            self.min_x, self.min_y, self.max_x, self.max_y
+        )
+    }
+}
+
+/// A filter on a geometry column, which is only satisfied by the geometries whose
+/// bounding boxes intersect `bbox`, e.g. `ST_Within(column, 'POLYGON(...)')`.
+/// It's used to skip the pages by their bounding boxes.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct SpatialFilter {
+    pub column: String,
+    pub bbox: BoundingBox,
+}
+
+impl SpatialFilter {
+    pub fn new(column: impl Into<String>, bbox: BoundingBox) -> Self {
+        Self {
+            column: column.into(),
+            bbox,
+        }
+    }
+}
+
+impl Display for SpatialFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} && {}", self.column, self.bbox)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoundingBox;
+
+    #[test]
+    fn test_bounding_box() {
+        let bbox = BoundingBox::from_wkt("LINESTRING(1 2, 3 -4)").unwrap();
+        assert_eq!(bbox, BoundingBox::new(1.0, -4.0, 3.0, 2.0));
+        assert!(BoundingBox::from_wkt("POINT EMPTY").is_none());
+        assert!(BoundingBox::from_wkt("not wkt").is_none());
+
+        let bbox = BoundingBox::from_wkts([
+            Some("POINT(1 1)"),
+            None,
+            Some("POINT EMPTY"),
+            Some("POINT(5 3)"),
+        ]);
+        assert_eq!(bbox, Some(BoundingBox::new(1.0, 1.0, 5.0, 3.0)));
+        assert_eq!(
+            BoundingBox::from_wkts([Some("POINT(1 1)"), Some("not wkt")]),
+            None
+        );
+
+        let other = BoundingBox::new(5.5, 0.0, 6.0, 1.0);
+        assert!(!bbox.unwrap().intersects(&other));
+        assert!(bbox.unwrap().expand(0.5).intersects(&other));
+    }
+}
@@ -1 +1,2 @@
+pub mod bounding_box;
 pub mod data_type;
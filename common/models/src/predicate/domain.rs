@@ -26,6 +26,7 @@ use super::transformation::RowExpressionToDomainsVisitor;
 use super::utils::filter_to_time_ranges;
 use super::PlacedSplit;
 use crate::errors::{InternalSnafu, InvalidQueryExprMsgSnafu, InvalidSerdeMessageSnafu};
+use crate::gis::bounding_box::SpatialFilter;
 use crate::schema::tskv_table_schema::{ColumnType, TskvTableSchemaRef};
 use crate::{ModelResult, Timestamp};
 
@@ -1124,10 +1125,207 @@ impl Display for EqutableValueSet {
     }
 }
 
+/// A pattern a string value is matched against.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ValuePattern {
+    /// `LIKE` / `ILIKE` pattern, `%` matches any sequence of characters and `_` matches any one.
+    Like {
+        pattern: String,
+        escape_char: Option<char>,
+        case_insensitive: bool,
+    },
+    /// `~` / `~*` pattern, matches if the regular expression matches any part of the value.
+    Regex {
+        pattern: String,
+        case_insensitive: bool,
+    },
+}
+
+impl ValuePattern {
+    /// The literal prefix every matched value starts with, may be empty.
+    pub fn literal_prefix(&self) -> String {
+        let mut prefix = String::new();
+        match self {
+            Self::Like {
+                case_insensitive: true,
+                ..
+            }
+            | Self::Regex {
+                case_insensitive: true,
+                ..
+            } => {}
+            Self::Like {
+                pattern,
+                escape_char,
+                ..
+            } => {
+                let mut chars = pattern.chars();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '%' | '_' => break,
+                        c if Some(c) == *escape_char => match chars.next() {
+                            Some(escaped) => prefix.push(escaped),
+                            None => break,
+                        },
+                        c => prefix.push(c),
+                    }
+                }
+            }
+            Self::Regex { pattern, .. } => {
+                // Only an anchored regex without alternation has a prefix,
+                // e.g. `^db[0-9]+` => `db`, `^abc?` => `ab`.
+                let Some(body) = pattern.strip_prefix('^') else {
+                    return prefix;
+                };
+                if pattern.contains('|') {
+                    return prefix;
+                }
+                for c in body.chars() {
+                    match c {
+                        '?' | '*' | '{' => {
+                            prefix.pop();
+                            break;
+                        }
+                        c if is_regex_meta_character(c) => break,
+                        c => prefix.push(c),
+                    }
+                }
+            }
+        }
+        prefix
+    }
+
+    /// Translates the pattern to a regular expression matching the whole value.
+    pub fn to_regex(&self) -> String {
+        match self {
+            Self::Like {
+                pattern,
+                escape_char,
+                case_insensitive,
+            } => {
+                let mut regex = String::with_capacity(pattern.len() + 8);
+                regex.push_str(if *case_insensitive { "(?is)^" } else { "(?s)^" });
+                let mut chars = pattern.chars();
+                while let Some(c) = chars.next() {
+                    let literal = match c {
+                        '%' => {
+                            regex.push_str(".*");
+                            continue;
+                        }
+                        '_' => {
+                            regex.push('.');
+                            continue;
+                        }
+                        c if Some(c) == *escape_char => match chars.next() {
+                            Some(escaped) => escaped,
+                            None => break,
+                        },
+                        c => c,
+                    };
+                    if is_regex_meta_character(literal) {
+                        regex.push('\\');
+                    }
+                    regex.push(literal);
+                }
+                regex.push('$');
+                regex
+            }
+            Self::Regex {
+                pattern,
+                case_insensitive,
+            } => {
+                if *case_insensitive {
+                    format!("(?i){pattern}")
+                } else {
+                    pattern.clone()
+                }
+            }
+        }
+    }
+}
+
+impl Display for ValuePattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Like {
+                pattern,
+                case_insensitive,
+                ..
+            } => {
+                let op = if *case_insensitive { "ilike" } else { "like" };
+                write!(f, "{op} '{pattern}'")
+            }
+            Self::Regex {
+                pattern,
+                case_insensitive,
+            } => {
+                let op = if *case_insensitive { "~*" } else { "~" };
+                write!(f, "{op} '{pattern}'")
+            }
+        }
+    }
+}
+
+fn is_regex_meta_character(c: char) -> bool {
+    matches!(
+        c,
+        '\\' | '.'
+            | '+'
+            | '*'
+            | '?'
+            | '('
+            | ')'
+            | '|'
+            | '['
+            | ']'
+            | '{'
+            | '}'
+            | '^'
+            | '$'
+            | '#'
+            | '&'
+            | '-'
+            | '~'
+    )
+}
+
+/// A set of string values matching all the patterns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatternValueSet {
+    patterns: Vec<ValuePattern>,
+}
+
+impl PatternValueSet {
+    pub fn patterns(&self) -> &[ValuePattern] {
+        &self.patterns
+    }
+
+    /// The longest literal prefix of the patterns, every value of the set starts with it.
+    pub fn literal_prefix(&self) -> String {
+        self.patterns
+            .iter()
+            .map(|p| p.literal_prefix())
+            .max_by_key(|p| p.len())
+            .unwrap_or_default()
+    }
+}
+
+impl Display for PatternValueSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let patterns = self
+            .patterns
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "[ {} ]", patterns.join(" and "))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Domain {
     Range(RangeValueSet),
     Equtable(EqutableValueSet),
+    Pattern(PatternValueSet),
     None,
     All,
 }
@@ -1195,6 +1393,12 @@ impl Domain {
             entries,
         })
     }
+    /// Return new ValueSet(PatternValueSet) which contains the values matching the pattern
+    pub fn of_pattern(pattern: ValuePattern) -> Domain {
+        Domain::Pattern(PatternValueSet {
+            patterns: vec![pattern],
+        })
+    }
     /// Calculates the intersection of two ranges, and returns None if the intersection does not exist
     ///
     /// This method returns the new value without changing the old value
@@ -1206,9 +1410,17 @@ impl Domain {
             (Self::Equtable(ref self_val_set), Self::Equtable(ref other_val_set)) => {
                 Domain::value_intersect(self_val_set, other_val_set)
             }
+            (Self::Pattern(ref self_val_set), Self::Pattern(ref other_val_set)) => {
+                let mut patterns = self_val_set.patterns.clone();
+                patterns.extend(other_val_set.patterns.iter().cloned());
+                Ok(Self::Pattern(PatternValueSet { patterns }))
+            }
             (Self::None, _) | (_, Self::None) => Ok(Self::None),
             (Self::All, _) => Ok(other.clone()),
             (_, Self::All) => Ok(self.clone()),
+            // The patterns can't be combined with the values, keep the values which is a superset of the intersection
+            (Self::Pattern(_), _) => Ok(other.clone()),
+            (_, Self::Pattern(_)) => Ok(self.clone()),
             _ => Err(InternalSnafu {
                 err: "mismatched ValueSet type".to_string(),
             }
@@ -1233,6 +1445,8 @@ impl Domain {
             (Self::None, _) => Ok(other.clone()),
             (_, Self::None) => Ok(self.clone()),
             (Self::All, _) | (_, Self::All) => Ok(Self::All),
+            // The union with patterns can't be represented, so all values are possible
+            (Self::Pattern(_), _) | (_, Self::Pattern(_)) => Ok(Self::All),
             _ => Err(InternalSnafu {
                 err: "mismatched ValueSet type".to_string(),
             }
@@ -1431,6 +1645,7 @@ impl Display for Domain {
         match self {
             Domain::Range(s) => write!(f, "range({s})"),
             Domain::Equtable(s) => write!(f, "equtable({s})"),
+            Domain::Pattern(s) => write!(f, "pattern({s})"),
             Domain::None => write!(f, "none"),
             Domain::All => write!(f, "all"),
         }
@@ -1670,6 +1885,7 @@ pub struct ResolvedPredicate {
     time_ranges: Arc<TimeRanges>,
     tags_filter: ColumnDomains<String>,
     physical_expr: PhysicalExprNodeWrap,
+    spatial_filters: Vec<SpatialFilter>,
 }
 
 impl ResolvedPredicate {
@@ -1687,9 +1903,15 @@ impl ResolvedPredicate {
             time_ranges,
             tags_filter,
             physical_expr: PhysicalExprNodeWrap(node),
+            spatial_filters: vec![],
         })
     }
 
+    pub fn with_spatial_filters(mut self, spatial_filters: Vec<SpatialFilter>) -> Self {
+        self.spatial_filters = spatial_filters;
+        self
+    }
+
     pub fn time_ranges(&self) -> Arc<TimeRanges> {
         self.time_ranges.clone()
     }
@@ -1701,6 +1923,10 @@ impl ResolvedPredicate {
     pub fn filter(&self) -> &PhysicalExprNode {
         &self.physical_expr.0
     }
+
+    pub fn spatial_filters(&self) -> &[SpatialFilter] {
+        &self.spatial_filters
+    }
 }
 
 #[derive(Debug)]
@@ -2168,6 +2394,48 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_value_pattern() {
+        let like = |pattern: &str, escape_char: Option<char>| ValuePattern::Like {
+            pattern: pattern.to_string(),
+            escape_char,
+            case_insensitive: false,
+        };
+        let regex = |pattern: &str| ValuePattern::Regex {
+            pattern: pattern.to_string(),
+            case_insensitive: false,
+        };
+
+        assert_eq!(like("web-%", None).literal_prefix(), "web-");
+        assert_eq!(like("web_1%", None).literal_prefix(), "web");
+        assert_eq!(like("%web", None).literal_prefix(), "");
+        assert_eq!(like("a!%b%", Some('!')).literal_prefix(), "a%b");
+        assert_eq!(regex("^db[0-9]+").literal_prefix(), "db");
+        assert_eq!(regex("^dbs?").literal_prefix(), "db");
+        assert_eq!(regex("^db|web").literal_prefix(), "");
+        assert_eq!(regex("db").literal_prefix(), "");
+
+        assert_eq!(like("web-%", None).to_regex(), "(?s)^web\\-.*$");
+        assert_eq!(like("a.b_", None).to_regex(), "(?s)^a\\.b.$");
+        assert_eq!(like("a!%b%", Some('!')).to_regex(), "(?s)^a%b.*$");
+
+        let set = Domain::of_pattern(like("web%", None))
+            .intersect(&Domain::of_pattern(regex("^web-[0-9]+")))
+            .unwrap();
+        match set {
+            Domain::Pattern(val_set) => {
+                assert_eq!(val_set.patterns().len(), 2);
+                assert_eq!(val_set.literal_prefix(), "web-");
+            }
+            _ => panic!("excepted Domain::Pattern"),
+        }
+
+        let union = Domain::of_pattern(like("web%", None))
+            .union(&Domain::of_pattern(regex("^db")))
+            .unwrap();
+        assert_eq!(union, Domain::All);
+    }
+
     #[test]
     fn test_serialize_physical_expr_node_wrap() {
         let expr = create_physical_expr(
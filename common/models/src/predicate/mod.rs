@@ -5,6 +5,7 @@ use datafusion_proto::protobuf::PhysicalExprNode;
 use serde::{Deserialize, Serialize};
 
 use self::domain::{ColumnDomains, PredicateRef, TimeRange, TimeRanges};
+use crate::gis::bounding_box::SpatialFilter;
 use crate::meta_data::{ReplicationSet, ReplicationSetId, VnodeInfo};
 use crate::predicate::domain::{ResolvedPredicate, ResolvedPredicateRef};
 use crate::schema::tskv_table_schema::{ColumnType, TskvTableSchemaRef};
@@ -70,9 +71,21 @@ impl Split {
         self.predicate.filter()
     }
 
+    pub fn spatial_filters(&self) -> &[SpatialFilter] {
+        self.predicate.spatial_filters()
+    }
+
     pub fn limit(&self) -> Option<usize> {
         self.limit
     }
+
+    /// Set the filters on the geometry columns, which are used to skip the pages
+    /// by their bounding boxes.
+    pub fn with_spatial_filters(mut self, spatial_filters: Vec<SpatialFilter>) -> Self {
+        let predicate = self.predicate.as_ref().clone();
+        self.predicate = Arc::new(predicate.with_spatial_filters(spatial_filters));
+        self
+    }
 }
 
 impl From<PlacedSplit> for Split {
@@ -125,10 +138,21 @@ impl PlacedSplit {
         self.split.filter()
     }
 
+    pub fn spatial_filters(&self) -> &[SpatialFilter] {
+        self.split.spatial_filters()
+    }
+
     pub fn limit(&self) -> Option<usize> {
         self.split.limit
     }
 
+    pub fn with_spatial_filters(self, spatial_filters: Vec<SpatialFilter>) -> Self {
+        Self {
+            split: self.split.with_spatial_filters(spatial_filters),
+            repl_set: self.repl_set,
+        }
+    }
+
     pub fn pop_front(&mut self) -> Option<VnodeInfo> {
         if self.repl_set.vnodes.is_empty() {
             None
@@ -140,4 +164,8 @@ impl PlacedSplit {
     pub fn replica_id(&self) -> ReplicationSetId {
         self.repl_set.id
     }
+
+    pub fn replica_set(&self) -> &ReplicationSet {
+        &self.repl_set
+    }
 }
@@ -5,11 +5,11 @@ use datafusion::arrow::datatypes::DataType;
 use datafusion::common::tree_node::{TreeNode, TreeNodeVisitor, VisitRecursion};
 use datafusion::common::Result as DFResult;
 use datafusion::error::DataFusionError;
-use datafusion::logical_expr::{BinaryExpr, Operator};
+use datafusion::logical_expr::{BinaryExpr, Like, Operator};
 use datafusion::prelude::{Column, Expr};
 use datafusion::scalar::ScalarValue;
 
-use super::domain::{ColumnDomains, Domain, Range};
+use super::domain::{ColumnDomains, Domain, Range, ValuePattern};
 use crate::schema::TIME_FIELD_NAME;
 
 type Result<T> = result::Result<T, DataFusionError>;
@@ -121,6 +121,56 @@ impl NormalizedSimpleComparison {
     }
 }
 
+/// A pattern match between a string Column and a pattern literal, i.e. `LIKE`, `ILIKE`, `~` and `~*`.
+struct NormalizedPatternMatch {
+    column: Column,
+    pattern: ValuePattern,
+}
+
+impl NormalizedPatternMatch {
+    /// Extract a normalized pattern match if possible, otherwise return None
+    fn of(expr: &Expr) -> Option<NormalizedPatternMatch> {
+        let (column, pattern) = match expr {
+            Expr::Like(like) | Expr::ILike(like) if !like.negated => {
+                match (like.expr.as_ref(), like.pattern.as_ref()) {
+                    (Expr::Column(column), Expr::Literal(ScalarValue::Utf8(Some(pattern)))) => (
+                        column,
+                        ValuePattern::Like {
+                            pattern: pattern.clone(),
+                            escape_char: like.escape_char,
+                            case_insensitive: matches!(expr, Expr::ILike(_)),
+                        },
+                    ),
+                    _ => return None,
+                }
+            }
+            Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
+                let case_insensitive = match op {
+                    Operator::RegexMatch => false,
+                    Operator::RegexIMatch => true,
+                    _ => return None,
+                };
+                match (left.as_ref(), right.as_ref()) {
+                    (Expr::Column(column), Expr::Literal(ScalarValue::Utf8(Some(pattern)))) => (
+                        column,
+                        ValuePattern::Regex {
+                            pattern: pattern.clone(),
+                            case_insensitive,
+                        },
+                    ),
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+
+        Some(Self {
+            column: column.clone(),
+            pattern,
+        })
+    }
+}
+
 pub struct RowExpressionToDomainsVisitor<'a> {
     ctx: &'a mut RowExpressionToDomainsVisitorContext,
 }
@@ -181,6 +231,8 @@ impl TreeNodeVisitor for RowExpressionToDomainsVisitor<'_> {
                     | Operator::LtEq
                     | Operator::Gt
                     | Operator::GtEq
+                    | Operator::RegexMatch
+                    | Operator::RegexIMatch
                     | Operator::And
                     | Operator::Or => {
                         // support
@@ -195,6 +247,10 @@ impl TreeNodeVisitor for RowExpressionToDomainsVisitor<'_> {
                     }
                 }
             }
+            Expr::Like(Like { negated: false, .. }) | Expr::ILike(Like { negated: false, .. }) => {
+                // support
+                Ok(VisitRecursion::Continue)
+            }
             // TODO Currently not supported, follow-up support needs to implement the corresponding expression in post_visit
             Expr::Like(_)
             | Expr::ILike(_)
@@ -234,6 +290,9 @@ impl TreeNodeVisitor for RowExpressionToDomainsVisitor<'_> {
                             self.ctx, left, op, right,
                         );
                     }
+                    Operator::RegexMatch | Operator::RegexIMatch => {
+                        Self::construct_pattern_and_push_current_domain_stack(self.ctx, expr);
+                    }
                     // The stack is domain, pop it, and generate a new domain
                     Operator::And => {
                         let domain1_opt = self.ctx.current_domain_stack.pop_back();
@@ -258,6 +317,9 @@ impl TreeNodeVisitor for RowExpressionToDomainsVisitor<'_> {
                     _ => {}
                 }
             }
+            Expr::Like(_) | Expr::ILike(_) => {
+                Self::construct_pattern_and_push_current_domain_stack(self.ctx, expr);
+            }
             // TODO The stack is the domain, and the domain is generated
             Expr::Not(_) | Expr::Between { .. } | Expr::InList { .. } => {}
             _ => {}
@@ -330,6 +392,19 @@ impl RowExpressionToDomainsVisitor<'_> {
             ctx.current_domain_stack.push_back(domains);
         }
     }
+    /// Construct pattern matches as PatternValueSet of the column and push it into the stack.
+    ///
+    /// If the expression is not a simple column-pattern match, push ColumnDomains::all()
+    fn construct_pattern_and_push_current_domain_stack(
+        ctx: &mut RowExpressionToDomainsVisitorContext,
+        expr: &Expr,
+    ) {
+        let domains = NormalizedPatternMatch::of(expr)
+            .map(|npm| ColumnDomains::of(npm.column, &Domain::of_pattern(npm.pattern)))
+            .unwrap_or_else(ColumnDomains::all);
+
+        ctx.current_domain_stack.push_back(domains);
+    }
 }
 
 #[derive(Clone, Default)]
@@ -749,6 +824,7 @@ mod tests {
     /// eg.
     ///   s1 like '%上证180' and time >= '2022-10-10 00:00:00'
     ///   ===>
+    ///   s1: like '%上证180'
     ///   time: ['2022-10-10 00:00:00', _)
     #[test]
     fn test_simple_and_to_domain_0() {
@@ -771,7 +847,14 @@ mod tests {
 
         let i1_domain = Domain::of_ranges(&[i1]).unwrap();
 
-        let except_column_domains = ColumnDomains::of(Column::from_name("time"), &i1_domain);
+        let s1_domain = Domain::of_pattern(ValuePattern::Like {
+            pattern: "%上证180".to_string(),
+            escape_char: None,
+            case_insensitive: false,
+        });
+
+        let except_column_domains = &mut ColumnDomains::of(Column::from_name("time"), &i1_domain);
+        except_column_domains.insert_or_intersect(Column::from_name("s1"), &s1_domain);
 
         let result = get_domains(&and);
 
@@ -792,6 +875,45 @@ mod tests {
         );
     }
 
+    /// pattern match test
+    /// eg.
+    ///   host ~ '^db[0-9]+' and host like 'db-%' and region not like 'us%'
+    ///   ===>
+    ///   host: ~ '^db[0-9]+' and like 'db-%'
+    #[test]
+    fn test_pattern_match_to_domain() {
+        let filter1 = binary_expr(col("host"), Operator::RegexMatch, lit("^db[0-9]+"));
+        let filter2 = Expr::Like(Like::new(
+            false,
+            Box::new(col("host")),
+            Box::new(lit("db-%")),
+            None,
+        ));
+        let filter3 = Expr::Like(Like::new(
+            true,
+            Box::new(col("region")),
+            Box::new(lit("us%")),
+            None,
+        ));
+        let expr = and(and(filter1, filter2), filter3);
+
+        let regex_domain = Domain::of_pattern(ValuePattern::Regex {
+            pattern: "^db[0-9]+".to_string(),
+            case_insensitive: false,
+        });
+        let like_domain = Domain::of_pattern(ValuePattern::Like {
+            pattern: "db-%".to_string(),
+            escape_char: None,
+            case_insensitive: false,
+        });
+        let except_column_domains = &mut ColumnDomains::of(Column::from_name("host"), &like_domain);
+        except_column_domains.insert_or_intersect(Column::from_name("host"), &regex_domain);
+
+        let column_domain = get_domains(&expr).unwrap();
+
+        assert_eq!(except_column_domains, &column_domain);
+    }
+
     /// simple and test - 1
     /// eg.
     ///   i1 < -1000000 and i2 = 2147483647 and i3 > 3333333333333333
@@ -11,6 +11,8 @@ pub mod database_schema;
 pub mod external_table_schema;
 pub mod query_info;
 pub mod resource_info;
+pub mod rollup_info;
+pub mod stream_info;
 pub mod stream_table_schema;
 pub mod table_schema;
 pub mod tenant;
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use utils::duration::CnosDuration;
+
+use crate::oid::Oid;
+
+/// A continuous downsampling policy of a database.
+///
+/// The rollup is run by the stream of the same name, which reads `source_table` through
+/// the stream table [`RollupInfo::stream_table`], and aggregates the windows that are
+/// closed by the watermark into `target_table`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RollupInfo {
+    pub tenant_id: Oid,
+    pub tenant_name: String,
+    pub database_name: String,
+    pub name: String,
+
+    pub source_table: String,
+    pub target_table: String,
+    /// How often the closed windows are aggregated.
+    pub interval: CnosDuration,
+    /// How long to wait for late data before a window is closed.
+    pub delay: CnosDuration,
+    /// Aggregated data older than ttl is removed from the target table,
+    /// `None` means it's kept as long as the database ttl.
+    pub ttl: Option<CnosDuration>,
+    /// The `INSERT INTO target SELECT ... FROM source` statement.
+    pub query: String,
+}
+
+impl RollupInfo {
+    /// Returns the name of the stream table that the rollup reads the source table through.
+    pub fn stream_table(name: &str) -> String {
+        format!("__rollup_{name}")
+    }
+
+    /// Returns the timestamp before which the aggregated data is expired at time `now_ns`.
+    pub fn expired_before(&self, now_ns: i64) -> Option<i64> {
+        self.ttl
+            .as_ref()
+            .map(|ttl| now_ns.saturating_sub(ttl.to_nanoseconds()))
+    }
+
+    pub fn owner(&self) -> String {
+        format!("{}.{}.{}", self.tenant_name, self.database_name, self.name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use utils::duration::CnosDuration;
+
+    use super::RollupInfo;
+
+    #[test]
+    fn test_expired_before() {
+        let mut r = RollupInfo {
+            tenant_id: 0,
+            tenant_name: "cnosdb".to_string(),
+            database_name: "public".to_string(),
+            name: "cpu_1m".to_string(),
+            source_table: "cpu".to_string(),
+            target_table: "cpu_1m".to_string(),
+            interval: CnosDuration::new_with_duration(Duration::from_secs(60)),
+            delay: CnosDuration::new_with_duration(Duration::ZERO),
+            ttl: None,
+            query: String::new(),
+        };
+        assert_eq!(r.expired_before(7_200_000_000_000), None);
+
+        r.ttl = Some(CnosDuration::new_with_duration(Duration::from_secs(3600)));
+        assert_eq!(r.expired_before(7_200_000_000_000), Some(3_600_000_000_000));
+    }
+}
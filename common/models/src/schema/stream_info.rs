@@ -0,0 +1,104 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::auth::user::User;
+use crate::meta_data::NodeId;
+use crate::oid::Oid;
+use crate::schema::query_info::QueryId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StreamStatus {
+    /// The stream is waiting to be started by its node.
+    Starting,
+    Running,
+    /// The stream failed to start, it's retried by its node later.
+    Failed,
+}
+
+impl fmt::Display for StreamStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StreamStatus::Starting => write!(f, "Starting"),
+            StreamStatus::Running => write!(f, "Running"),
+            StreamStatus::Failed => write!(f, "Failed"),
+        }
+    }
+}
+
+/// A continuous query of a database, created by `CREATE STREAM`.
+///
+/// The stream runs on the query node `node_id`, it's started again after the node
+/// restarts and moved to another node after the node is dead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamInfo {
+    pub tenant_id: Oid,
+    pub tenant_name: String,
+    pub database_name: String,
+    pub name: String,
+
+    /// `once` or the interval between two micro-batches, e.g. `10s`,
+    /// `None` means the default interval of the session.
+    pub trigger: Option<String>,
+    /// The `INSERT INTO ... SELECT ... FROM stream_table` statement.
+    pub query: String,
+
+    pub user: User,
+    pub node_id: NodeId,
+    /// The id of the stream query, it's kept across restarts so that the
+    /// saved watermark of the query is resumed.
+    pub query_id: QueryId,
+
+    pub status: StreamStatus,
+    /// Timestamp in nanoseconds, the event time the stream has processed until.
+    pub watermark_ns: Option<i64>,
+    pub processed_count: u64,
+    pub error_count: u64,
+    /// The last error of starting the stream or executing a micro-batch.
+    pub error: Option<String>,
+}
+
+impl StreamInfo {
+    /// Returns how far the watermark is behind `now_ns`, `None` if nothing is processed.
+    pub fn lag_ns(&self, now_ns: i64) -> Option<i64> {
+        self.watermark_ns
+            .map(|watermark_ns| now_ns.saturating_sub(watermark_ns).max(0))
+    }
+
+    pub fn owner(&self) -> String {
+        format!("{}.{}.{}", self.tenant_name, self.database_name, self.name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{StreamInfo, StreamStatus};
+    use crate::auth::user::{User, UserDesc, UserOptions};
+
+    #[test]
+    fn test_lag_ns() {
+        let desc = UserDesc::new(0, "root".to_string(), UserOptions::default(), true);
+        let mut stream = StreamInfo {
+            tenant_id: 0,
+            tenant_name: "cnosdb".to_string(),
+            database_name: "public".to_string(),
+            name: "s".to_string(),
+            trigger: Some("once".to_string()),
+            query: String::new(),
+            user: User::new(desc, Default::default(), None),
+            node_id: 1,
+            query_id: 1.into(),
+            status: StreamStatus::Starting,
+            watermark_ns: None,
+            processed_count: 0,
+            error_count: 0,
+            error: None,
+        };
+        assert_eq!(stream.lag_ns(100), None);
+
+        stream.watermark_ns = Some(40);
+        assert_eq!(stream.lag_ns(100), Some(60));
+        assert_eq!(stream.lag_ns(10), Some(0));
+        assert_eq!(stream.owner(), "cnosdb.public.s");
+    }
+}
@@ -4,7 +4,8 @@ use datafusion::arrow::array::{
     Array, ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray,
     TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray, UInt64Array,
 };
-use datafusion::arrow::datatypes::{SchemaRef, TimeUnit};
+use datafusion::arrow::datatypes::TimeUnit;
+use datafusion::arrow::record_batch::RecordBatch;
 use flatbuffers::{FlatBufferBuilder, WIPOffset};
 use models::column_data_ref::PrimaryColumnDataRef;
 use models::mutable_batch::MutableBatch;
@@ -280,53 +281,56 @@ pub fn mutable_batches_to_point(db: &str, batches: HashMap<String, MutableBatch>
     data
 }
 
-pub fn arrow_array_to_points(
-    columns: Vec<ArrayRef>,
-    schema: SchemaRef,
-    table_schema: TskvTableSchemaRef,
-    len: usize,
+/// Encodes the record batches of the tables in the same database into one `Points`.
+pub fn record_batches_to_points(
+    db: &str,
+    batches: &[(TskvTableSchemaRef, RecordBatch)],
 ) -> Result<Vec<u8>> {
     let mut fbb = FlatBufferBuilder::new();
-    let table_name = table_schema.name.as_str();
-    let mut fb_columns = Vec::new();
-    for (column, schema) in columns.iter().zip(schema.fields.iter()) {
-        let col_name = schema.name().as_str();
-        let column_schema = table_schema.column(col_name).ok_or_else(|| Error::Common {
-            content: format!("column {} not found in table {}", col_name, table_name),
-        })?;
-        let fb_column = match column_schema.column_type.to_physical_type() {
-            PhysicalCType::Tag => {
-                build_string_column(column, col_name, FbColumnType::Tag, &mut fbb)?
-            }
-            PhysicalCType::Time(ref time_unit) => {
-                build_timestamp_column(column, col_name, time_unit, &mut fbb)?
-            }
-            PhysicalCType::Field(value_type) => match value_type {
-                ValueType::Unknown => {
-                    return Err(Error::Common {
-                        content: format!("column {} type is unknown", col_name),
-                    });
+    let mut tables = Vec::with_capacity(batches.len());
+    for (table_schema, record_batch) in batches {
+        let table_name = table_schema.name.as_str();
+        let schema = record_batch.schema();
+        let mut fb_columns = Vec::new();
+        for (column, schema) in record_batch.columns().iter().zip(schema.fields.iter()) {
+            let col_name = schema.name().as_str();
+            let column_schema = table_schema.column(col_name).ok_or_else(|| Error::Common {
+                content: format!("column {} not found in table {}", col_name, table_name),
+            })?;
+            let fb_column = match column_schema.column_type.to_physical_type() {
+                PhysicalCType::Tag => {
+                    build_string_column(column, col_name, FbColumnType::Tag, &mut fbb)?
                 }
-                ValueType::Float => build_f64_column(column, col_name, &mut fbb)?,
-                ValueType::Integer => build_i64_column(column, col_name, &mut fbb)?,
-                ValueType::Unsigned => build_u64_column(column, col_name, &mut fbb)?,
-                ValueType::Boolean => build_bool_column(column, col_name, &mut fbb)?,
-                ValueType::String => {
-                    build_string_column(column, col_name, FbColumnType::Field, &mut fbb)?
+                PhysicalCType::Time(ref time_unit) => {
+                    build_timestamp_column(column, col_name, time_unit, &mut fbb)?
                 }
-            },
-        };
-        fb_columns.push(fb_column);
+                PhysicalCType::Field(value_type) => match value_type {
+                    ValueType::Unknown => {
+                        return Err(Error::Common {
+                            content: format!("column {} type is unknown", col_name),
+                        });
+                    }
+                    ValueType::Float => build_f64_column(column, col_name, &mut fbb)?,
+                    ValueType::Integer => build_i64_column(column, col_name, &mut fbb)?,
+                    ValueType::Unsigned => build_u64_column(column, col_name, &mut fbb)?,
+                    ValueType::Boolean => build_bool_column(column, col_name, &mut fbb)?,
+                    ValueType::String => {
+                        build_string_column(column, col_name, FbColumnType::Field, &mut fbb)?
+                    }
+                },
+            };
+            fb_columns.push(fb_column);
+        }
+        let columns = fbb.create_vector(&fb_columns);
+        let table_name = fbb.create_string(table_name);
+        let mut table_builder = TableBuilder::new(&mut fbb);
+        table_builder.add_columns(columns);
+        table_builder.add_tab(table_name);
+        table_builder.add_num_rows(record_batch.num_rows() as u64);
+        tables.push(table_builder.finish());
     }
-    let columns = fbb.create_vector(&fb_columns);
-    let table_name = fbb.create_string(table_name);
-    let mut table_builder = TableBuilder::new(&mut fbb);
-    table_builder.add_columns(columns);
-    table_builder.add_tab(table_name);
-    table_builder.add_num_rows(len as u64);
-    let table = table_builder.finish();
-    let tables = fbb.create_vector(&[table]);
-    let db = fbb.create_string(table_schema.db.as_str());
+    let tables = fbb.create_vector(&tables);
+    let db = fbb.create_string(db);
     let mut point_builder = PointsBuilder::new(&mut fbb);
     point_builder.add_tables(tables);
     point_builder.add_db(db);
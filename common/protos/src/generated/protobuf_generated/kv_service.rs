@@ -109,6 +109,12 @@ pub struct RaftWriteCommand {
     pub db_name: ::prost::alloc::string::String,
     #[prost(uint32, tag = "3")]
     pub replica_id: u32,
+    /// models::consistency_level::ConsistencyLevel, 0 is quorum.
+    #[prost(uint32, tag = "9")]
+    pub consistency_level: u32,
+    /// Nanoseconds since epoch when the command is proposed, used by point-in-time recovery.
+    #[prost(int64, tag = "10")]
+    pub create_time: i64,
     #[prost(oneof = "raft_write_command::Command", tags = "4, 5, 6, 7, 8")]
     pub command: ::core::option::Option<raft_write_command::Command>,
 }
@@ -138,12 +144,86 @@ pub struct CompactVnodeRequest {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReencodeVnodeRequest {
+    #[prost(uint32, repeated, tag = "1")]
+    pub vnode_ids: ::prost::alloc::vec::Vec<u32>,
+    #[prost(string, tag = "2")]
+    pub db_name: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub table: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BackupVnodeRequest {
+    #[prost(uint32, tag = "1")]
+    pub vnode_id: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StageVnodeFileRequest {
+    #[prost(uint32, tag = "1")]
+    pub vnode_id: u32,
+    #[prost(string, tag = "2")]
+    pub relative_path: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub offset: u64,
+    #[prost(bytes = "vec", tag = "4")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RestoreVnodeRequest {
+    #[prost(string, tag = "1")]
+    pub db_name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+    #[prost(uint32, tag = "3")]
+    pub replica_id: u32,
+    #[prost(bytes = "vec", tag = "4")]
+    pub snapshot: ::prost::alloc::vec::Vec<u8>,
+    /// Replay the archived raft logs of the snapshot's vnode proposed until this time.
+    #[prost(int64, optional, tag = "5")]
+    pub until: ::core::option::Option<i64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FetchChecksumRequest {
     #[prost(uint32, tag = "1")]
     pub vnode_id: u32,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SeriesCardinalityRequest {
+    #[prost(string, tag = "1")]
+    pub db_name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+    #[prost(string, optional, tag = "3")]
+    pub table: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag = "4")]
+    pub group_by: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(bool, tag = "5")]
+    pub exact: bool,
+    #[prost(bool, tag = "6")]
+    pub tag_keys: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchReplicaVersionRequest {
+    #[prost(uint32, tag = "1")]
+    pub replica_id: u32,
+}
+/// Archive the WAL file that the vnode is writing.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ArchiveVnodeWalRequest {
+    #[prost(uint32, tag = "1")]
+    pub replica_id: u32,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct OpenRaftNodeRequest {
     #[prost(string, tag = "1")]
     pub tenant: ::prost::alloc::string::String,
@@ -225,7 +305,7 @@ pub struct BuildRaftGroupRequest {
 pub struct AdminCommand {
     #[prost(string, tag = "1")]
     pub tenant: ::prost::alloc::string::String,
-    #[prost(oneof = "admin_command::Command", tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11")]
+    #[prost(oneof = "admin_command::Command", tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18")]
     pub command: ::core::option::Option<admin_command::Command>,
 }
 /// Nested message and enum types in `AdminCommand`.
@@ -253,6 +333,20 @@ pub mod admin_command {
         LearnerToFollower(super::LearnerToFollowerRequest),
         #[prost(message, tag = "11")]
         BuildRaftGroup(super::BuildRaftGroupRequest),
+        #[prost(message, tag = "12")]
+        ReencodeVnode(super::ReencodeVnodeRequest),
+        #[prost(message, tag = "13")]
+        BackupVnode(super::BackupVnodeRequest),
+        #[prost(message, tag = "14")]
+        StageVnodeFile(super::StageVnodeFileRequest),
+        #[prost(message, tag = "15")]
+        RestoreVnode(super::RestoreVnodeRequest),
+        #[prost(message, tag = "16")]
+        SeriesCardinality(super::SeriesCardinalityRequest),
+        #[prost(message, tag = "17")]
+        FetchReplicaVersion(super::FetchReplicaVersionRequest),
+        #[prost(message, tag = "18")]
+        ArchiveVnodeWal(super::ArchiveVnodeWalRequest),
     }
 }
 /// --------------------------------------------------------------------
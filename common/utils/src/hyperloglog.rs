@@ -0,0 +1,101 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+use twox_hash::XxHash64;
+
+/// Number of the bits of a hash that select the register.
+const PRECISION: u32 = 12;
+const REGISTERS: usize = 1 << PRECISION;
+
+/// HyperLogLog sketch that estimates the number of the distinct items with
+/// 4096 registers, the standard error is about 1.6%. Sketches are merged by
+/// the max of the registers, the merged sketch estimates the items of all.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HyperLogLog {
+    pub fn new() -> Self {
+        Self {
+            registers: vec![0; REGISTERS],
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.registers.capacity()
+    }
+
+    /// The hash of the data that is inserted, it's the same on all the nodes.
+    pub fn hash(data: &[u8]) -> u64 {
+        let mut hasher = XxHash64::default();
+        hasher.write(data);
+        hasher.finish()
+    }
+
+    pub fn insert(&mut self, data: &[u8]) {
+        self.insert_hash(Self::hash(data))
+    }
+
+    pub fn insert_hash(&mut self, hash: u64) {
+        let index = (hash >> (64 - PRECISION)) as usize;
+        // The bit set at the end makes the rank at most `64 - PRECISION + 1`.
+        let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() + 1;
+        self.registers[index] = self.registers[index].max(rank as u8);
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        for (r, o) in self.registers.iter_mut().zip(other.registers.iter()) {
+            *r = (*r).max(*o);
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        let m = REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let (mut sum, mut zeros) = (0.0, 0);
+        for r in self.registers.iter() {
+            sum += 1.0 / (1_u64 << r) as f64;
+            if *r == 0 {
+                zeros += 1;
+            }
+        }
+
+        let estimate = alpha * m * m / sum;
+        // Linear counting is more accurate for the small cardinalities.
+        if estimate <= 2.5 * m && zeros > 0 {
+            return (m * (m / zeros as f64).ln()).round() as u64;
+        }
+        estimate.round() as u64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HyperLogLog;
+
+    #[test]
+    fn test_hyperloglog() {
+        let mut hll = HyperLogLog::new();
+        assert_eq!(hll.count(), 0);
+        for i in 0..100_u64 {
+            hll.insert(&i.to_be_bytes());
+            hll.insert(&i.to_be_bytes());
+        }
+        assert!(hll.count().abs_diff(100) <= 2);
+
+        let mut other = HyperLogLog::new();
+        for i in 50..100_000_u64 {
+            other.insert(&i.to_be_bytes());
+        }
+        hll.merge(&other);
+        let error = hll.count().abs_diff(100_000) as f64 / 100_000.0;
+        assert!(error < 0.05, "error: {error}");
+    }
+}
@@ -15,6 +15,13 @@ pub struct TenantObjectLimiterConfig {
     pub max_shard_number: Option<usize>,
     pub max_replicate_number: Option<usize>,
     pub max_retention_time: Option<usize>,
+    /// max number of the series of a database, the writes of new series are rejected by
+    /// the leader before they are proposed, counting the series of the vnodes on its node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_series_per_database: Option<usize>,
+    /// max number of the series of a table, checked like `max_series_per_database`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_series_per_table: Option<usize>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
@@ -61,6 +68,8 @@ max_databases = 3
 max_shard_number = 2
 max_replicate_number = 2
 max_retention_time = 30
+max_series_per_database = 1000000
+max_series_per_table = 100000
 
 
 [request_config.coord_data_in]
@@ -98,5 +107,8 @@ remote_bucket = {max = 100, initial = 0, refill = 100, interval = 100}
 "#;
 
     let config: TenantLimiterConfig = toml::from_str(config_str).unwrap();
+    let object_config = config.object_config.unwrap();
+    assert_eq!(object_config.max_series_per_database, Some(1000000));
+    assert_eq!(object_config.max_series_per_table, Some(100000));
     dbg!(config);
 }
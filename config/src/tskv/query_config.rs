@@ -27,6 +27,13 @@ pub struct QueryConfig {
     pub stream_executor_cpu: usize,
     #[serde(with = "duration", default = "QueryConfig::default_sql_record_timeout")]
     pub sql_record_timeout: Duration,
+    #[serde(default = "QueryConfig::default_result_cache_enabled")]
+    pub result_cache_enabled: bool,
+    #[serde(
+        with = "bytes_num",
+        default = "QueryConfig::default_result_cache_max_size"
+    )]
+    pub result_cache_max_size: u64,
 }
 
 impl QueryConfig {
@@ -65,6 +72,14 @@ impl QueryConfig {
     fn default_sql_record_timeout() -> Duration {
         Duration::from_secs(10)
     }
+
+    fn default_result_cache_enabled() -> bool {
+        false
+    }
+
+    fn default_result_cache_max_size() -> u64 {
+        256 * 1024 * 1024
+    }
 }
 
 impl Default for QueryConfig {
@@ -79,6 +94,8 @@ impl Default for QueryConfig {
             stream_trigger_cpu: Self::default_stream_trigger_cpu(),
             stream_executor_cpu: Self::default_stream_executor_cpu(),
             sql_record_timeout: Self::default_sql_record_timeout(),
+            result_cache_enabled: Self::default_result_cache_enabled(),
+            result_cache_max_size: Self::default_result_cache_max_size(),
         }
     }
 }
@@ -143,12 +160,20 @@ impl CheckConfig for QueryConfig {
 
         if self.sql_record_timeout.as_secs() < 1 {
             ret.add_warn(CheckConfigItemResult {
-                config: config_name,
+                config: config_name.clone(),
                 item: "sql_record_timeout".to_string(),
                 message: "'sql_record_timeout' maybe too small(less than 1)".to_string(),
             })
         }
 
+        if self.result_cache_enabled && self.result_cache_max_size < 1024 * 1024 {
+            ret.add_warn(CheckConfigItemResult {
+                config: config_name,
+                item: "result_cache_max_size".to_string(),
+                message: "'result_cache_max_size' maybe too small(less than 1M)".to_string(),
+            })
+        }
+
         if ret.is_empty() {
             None
         } else {
@@ -71,6 +71,9 @@ pub struct StorageConfig {
 
     #[serde(default = "StorageConfig::default_index_cache_capacity")]
     pub index_cache_capacity: u64,
+
+    #[serde(default = "Default::default")]
+    pub tiered: TieredStorageConfig,
 }
 
 impl StorageConfig {
@@ -165,6 +168,118 @@ impl Default for StorageConfig {
             copyinto_trigger_flush_size: Self::default_copyinto_trigger_flush_size(),
             max_datablock_size: Self::default_max_datablock_size(),
             index_cache_capacity: Self::default_index_cache_capacity(),
+            tiered: TieredStorageConfig::default(),
+        }
+    }
+}
+
+/// Offloads cold column files from `StorageConfig.path` to an object store.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnvKeys)]
+pub struct TieredStorageConfig {
+    #[serde(default = "TieredStorageConfig::default_enable")]
+    pub enable: bool,
+
+    /// Location of the cold tier, e.g. `s3://bucket/prefix`, `gcs://bucket/prefix`,
+    /// `azblob://container/prefix` or `file:///path/to/dir`.
+    #[serde(default = "TieredStorageConfig::default_url")]
+    pub url: String,
+
+    /// Only files in this level or higher are offloaded.
+    #[serde(default = "TieredStorageConfig::default_min_level")]
+    pub min_level: u16,
+
+    /// Files whose newest data is older than this are offloaded.
+    #[serde(
+        with = "duration",
+        default = "TieredStorageConfig::default_offload_after"
+    )]
+    pub offload_after: Duration,
+
+    #[serde(
+        with = "duration",
+        default = "TieredStorageConfig::default_check_interval"
+    )]
+    pub check_interval: Duration,
+
+    /// Size of a block read from the object store and kept in the local block cache.
+    #[serde(
+        with = "bytes_num",
+        default = "TieredStorageConfig::default_block_size"
+    )]
+    pub block_size: u64,
+
+    /// Memory used by the local block cache.
+    #[serde(
+        with = "bytes_num",
+        default = "TieredStorageConfig::default_cache_size"
+    )]
+    pub cache_size: u64,
+
+    #[serde(default)]
+    pub region: Option<String>,
+    #[serde(default)]
+    pub endpoint_url: Option<String>,
+    #[serde(default)]
+    pub access_key_id: Option<String>,
+    #[serde(default)]
+    pub secret_access_key: Option<String>,
+    #[serde(default)]
+    pub gcs_service_account_path: Option<String>,
+    #[serde(default)]
+    pub azure_account_name: Option<String>,
+    #[serde(default)]
+    pub azure_access_key: Option<String>,
+}
+
+impl TieredStorageConfig {
+    pub const SUPPORTED_SCHEMES: [&'static str; 4] = ["s3", "gcs", "azblob", "file"];
+
+    fn default_enable() -> bool {
+        false
+    }
+
+    fn default_url() -> String {
+        String::new()
+    }
+
+    fn default_min_level() -> u16 {
+        4
+    }
+
+    fn default_offload_after() -> Duration {
+        Duration::from_secs(7 * 24 * 60 * 60)
+    }
+
+    fn default_check_interval() -> Duration {
+        Duration::from_secs(10 * 60)
+    }
+
+    fn default_block_size() -> u64 {
+        1024 * 1024
+    }
+
+    fn default_cache_size() -> u64 {
+        512 * 1024 * 1024
+    }
+}
+
+impl Default for TieredStorageConfig {
+    fn default() -> Self {
+        Self {
+            enable: Self::default_enable(),
+            url: Self::default_url(),
+            min_level: Self::default_min_level(),
+            offload_after: Self::default_offload_after(),
+            check_interval: Self::default_check_interval(),
+            block_size: Self::default_block_size(),
+            cache_size: Self::default_cache_size(),
+            region: None,
+            endpoint_url: None,
+            access_key_id: None,
+            secret_access_key: None,
+            gcs_service_account_path: None,
+            azure_account_name: None,
+            azure_access_key: None,
         }
     }
 }
@@ -219,11 +334,46 @@ impl CheckConfig for StorageConfig {
         }
         if self.max_compact_size < 1024 * 1024 {
             ret.add_warn(CheckConfigItemResult {
-                config: config_name,
+                config: config_name.clone(),
                 item: "max_compact_size".to_string(),
                 message: "'max_compact_size' maybe too small(less than 1M)".to_string(),
             });
         }
+        if self.tiered.enable {
+            if self.tiered.url.is_empty() {
+                ret.add_error(CheckConfigItemResult {
+                    config: config_name.clone(),
+                    item: "tiered.url".to_string(),
+                    message: "'tiered.url' is empty while tiered storage is enabled".to_string(),
+                });
+            } else if !TieredStorageConfig::SUPPORTED_SCHEMES
+                .iter()
+                .any(|s| self.tiered.url.starts_with(&format!("{s}://")))
+            {
+                ret.add_error(CheckConfigItemResult {
+                    config: config_name.clone(),
+                    item: "tiered.url".to_string(),
+                    message: format!(
+                        "'tiered.url' must start with one of {:?}",
+                        TieredStorageConfig::SUPPORTED_SCHEMES
+                    ),
+                });
+            }
+            if self.tiered.min_level == 0 || self.tiered.min_level > self.max_level {
+                ret.add_error(CheckConfigItemResult {
+                    config: config_name.clone(),
+                    item: "tiered.min_level".to_string(),
+                    message: "'tiered.min_level' must be between 1 and 'max_level'".to_string(),
+                });
+            }
+            if self.tiered.block_size < 64 * 1024 {
+                ret.add_warn(CheckConfigItemResult {
+                    config: config_name,
+                    item: "tiered.block_size".to_string(),
+                    message: "'tiered.block_size' maybe too small(less than 64K)".to_string(),
+                });
+            }
+        }
 
         if ret.is_empty() {
             None
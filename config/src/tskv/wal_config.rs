@@ -1,10 +1,12 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use macros::EnvKeys;
 use serde::{Deserialize, Serialize};
 
 use crate::check::{CheckConfig, CheckConfigItemResult, CheckConfigResult};
-use crate::codec::bytes_num;
+use crate::codec::{bytes_num, duration};
+use crate::tskv::TieredStorageConfig;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnvKeys)]
 pub struct WalConfig {
@@ -20,8 +22,24 @@ pub struct WalConfig {
     #[serde(default = "WalConfig::default_sync")]
     pub sync: bool,
 
+    /// If not zero and `sync` is true, the fsync of the WAL writes are batched,
+    /// the writes are acknowledged after the batch is synced.
+    #[serde(with = "duration", default = "WalConfig::default_sync_interval")]
+    pub sync_interval: Duration,
+
+    /// A batch of WAL writes is synced before `sync_interval` if it reaches this size.
+    #[serde(with = "bytes_num", default = "WalConfig::default_sync_max_size")]
+    pub sync_max_size: u64,
+
     #[serde(default = "WalConfig::default_compress")]
     pub compress: String,
+
+    /// If not empty, the WAL files are archived to this object store for the
+    /// point-in-time recovery, e.g. `s3://bucket/prefix` or `file:///path/to/dir`.
+    /// It must be reachable by all the data nodes, the credentials are those of
+    /// `storage.tiered`.
+    #[serde(default = "WalConfig::default_archive_url")]
+    pub archive_url: String,
 }
 
 impl WalConfig {
@@ -42,9 +60,21 @@ impl WalConfig {
         false
     }
 
+    pub fn default_sync_interval() -> Duration {
+        Duration::ZERO
+    }
+
+    pub fn default_sync_max_size() -> u64 {
+        4 * 1024 * 1024
+    }
+
     fn default_compress() -> String {
         "zstd".to_string()
     }
+
+    fn default_archive_url() -> String {
+        String::new()
+    }
 }
 
 impl Default for WalConfig {
@@ -54,7 +84,10 @@ impl Default for WalConfig {
             wal_req_channel_cap: Self::default_wal_req_channel_cap(),
             max_file_size: Self::default_max_file_size(),
             sync: Self::default_sync(),
+            sync_interval: Self::default_sync_interval(),
+            sync_max_size: Self::default_sync_max_size(),
             compress: Self::default_compress(),
+            archive_url: Self::default_archive_url(),
         }
     }
 }
@@ -79,6 +112,30 @@ impl CheckConfig for WalConfig {
             });
         }
 
+        if self.sync && self.sync_interval > Duration::from_secs(1) {
+            ret.add_warn(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "sync_interval".to_string(),
+                message: "'sync_interval' maybe too large(more than 1s), writes wait for it"
+                    .to_string(),
+            });
+        }
+
+        if !self.archive_url.is_empty()
+            && !TieredStorageConfig::SUPPORTED_SCHEMES
+                .iter()
+                .any(|s| self.archive_url.starts_with(&format!("{s}://")))
+        {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "archive_url".to_string(),
+                message: format!(
+                    "'archive_url' must start with one of {:?}",
+                    TieredStorageConfig::SUPPORTED_SCHEMES
+                ),
+            });
+        }
+
         if ret.is_empty() {
             None
         } else {
@@ -1,5 +1,6 @@
 #![recursion_limit = "256"]
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::pin::Pin;
 use std::sync::atomic::AtomicUsize;
@@ -10,8 +11,9 @@ use datafusion::arrow::record_batch::RecordBatch;
 use errors::CoordinatorError;
 use futures::Stream;
 use meta::model::{MetaClientRef, MetaRef};
+use models::consistency_level::ConsistencyLevel;
 use models::meta_data::{
-    NodeId, ReplicaAllInfo, ReplicationSet, ReplicationSetId, VnodeAllInfo, VnodeId,
+    NodeId, ReplicaAllInfo, ReplicationSet, ReplicationSetId, VnodeAllInfo, VnodeId, VnodeInfo,
 };
 use models::object_reference::ResolvedTable;
 use models::predicate::domain::{ResolvedPredicate, ResolvedPredicateRef};
@@ -21,9 +23,11 @@ use protos::kv_service::{RaftWriteCommand, UpdateSetValue};
 use raft::manager::RaftNodesManager;
 use raft::writer::TskvRaftWriter;
 use snafu::ResultExt;
+use tokio::io::AsyncWrite;
 use trace::SpanContext;
+use tskv::index::cardinality::CardinalityOptions;
 use tskv::reader::QueryOption;
-use tskv::EngineRef;
+use tskv::{EngineRef, VnodeSnapshot};
 use utils::precision::Precision;
 
 use crate::errors::{CoordinatorResult, MetaSnafu};
@@ -68,6 +72,7 @@ pub trait Coordinator: Send + Sync {
         &self,
         table: &ResolvedTable,
         predicate: ResolvedPredicateRef,
+        consistency: ConsistencyLevel,
     ) -> CoordinatorResult<Vec<ReplicationSet>>;
 
     async fn write_replica_by_raft(
@@ -83,6 +88,7 @@ pub trait Coordinator: Send + Sync {
         db: &str,
         precision: Precision,
         lines: Vec<Line<'a>>,
+        consistency: ConsistencyLevel,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize>;
 
@@ -91,6 +97,16 @@ pub trait Coordinator: Send + Sync {
         table_schema: TskvTableSchemaRef,
         record_batch: RecordBatch,
         db_precision: Precision,
+        consistency: ConsistencyLevel,
+        span_ctx: Option<&SpanContext>,
+    ) -> CoordinatorResult<usize>;
+
+    /// Write the record batches of several tables,
+    /// the rows of the same replication set are committed by a single raft write.
+    async fn write_record_batches<'a>(
+        &self,
+        batches: Vec<(TskvTableSchemaRef, RecordBatch)>,
+        consistency: ConsistencyLevel,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize>;
 
@@ -114,6 +130,80 @@ pub trait Coordinator: Send + Sync {
 
     async fn compact_vnodes(&self, tenant: &str, vnode_ids: Vec<VnodeId>) -> CoordinatorResult<()>;
 
+    /// Rewrite the data of the table in the vnodes with the current encodings of its columns.
+    async fn reencode_vnodes(
+        &self,
+        tenant: &str,
+        db: &str,
+        table: &str,
+        vnode_ids: Vec<VnodeId>,
+    ) -> CoordinatorResult<()>;
+
+    /// Flush the vnode and take a snapshot of its files, return `None` if the vnode
+    /// has never been opened on its node.
+    async fn backup_vnode(
+        &self,
+        tenant: &str,
+        vnode: &VnodeInfo,
+    ) -> CoordinatorResult<Option<VnodeSnapshot>>;
+
+    /// Count the distinct series or tag keys of the tables in the database, the vnodes
+    /// of a replication set are counted once. Returns the table, the value of the
+    /// GROUP BY tag and the number.
+    async fn series_cardinality(
+        &self,
+        tenant: &str,
+        db: &str,
+        options: &CardinalityOptions,
+    ) -> CoordinatorResult<Vec<(String, Option<String>, u64)>>;
+
+    /// Get the data versions of the vnodes of the replication sets, it's the last applied
+    /// raft log index of the vnode. A scan may read any of the vnodes by failing over,
+    /// so the versions of all of them are returned.
+    /// The version changes after every write or delete to the replication set.
+    async fn replica_versions(
+        &self,
+        tenant: &str,
+        replica_sets: &[ReplicationSet],
+    ) -> CoordinatorResult<HashMap<VnodeId, u64>>;
+
+    /// Download a file of the data node into `writer`, the path is relative to the
+    /// storage path of the node. Return the length of the file.
+    async fn download_file(
+        &self,
+        node_id: NodeId,
+        path: &str,
+        writer: &mut (dyn AsyncWrite + Send + Unpin),
+    ) -> CoordinatorResult<u64>;
+
+    /// Write a chunk of a file of the snapshot that the vnode is going to be restored from.
+    async fn stage_vnode_file(
+        &self,
+        tenant: &str,
+        vnode: &VnodeInfo,
+        relative_path: &str,
+        offset: u64,
+        data: Vec<u8>,
+    ) -> CoordinatorResult<()>;
+
+    /// Replace the data of the vnode with the snapshot, all the files of the snapshot
+    /// must have been written by `stage_vnode_file`. The raft group of the vnode must
+    /// not be opened yet. If `until` is set, the archived raft logs of the vnode that
+    /// took the snapshot are replayed until the logs proposed after it.
+    async fn restore_vnode(
+        &self,
+        tenant: &str,
+        db: &str,
+        replica_id: ReplicationSetId,
+        vnode: &VnodeInfo,
+        snapshot: &VnodeSnapshot,
+        until: Option<i64>,
+    ) -> CoordinatorResult<()>;
+
+    /// Archive the WAL file that the vnode is writing, so that all the raft logs of it
+    /// proposed before are in the archive. Returns false if the vnode is not found.
+    async fn archive_vnode_wal(&self, tenant: &str, vnode_id: VnodeId) -> CoordinatorResult<bool>;
+
     /// A manager to manage vnode.
     async fn replication_manager(
         &self,
@@ -19,6 +19,7 @@ use snafu::ResultExt;
 use tokio::runtime::Runtime;
 use tokio::sync::RwLock;
 use tracing::info;
+use tskv::wal::syncer::{WalSyncer, WalSyncerRef};
 use tskv::wal::wal_store::RaftEntryStorage;
 use tskv::{wal, EngineRef};
 
@@ -36,6 +37,8 @@ pub struct RaftNodesManager {
     kv_inst: Option<EngineRef>,
     raft_state: Arc<StateStorage>,
     raft_nodes: Arc<RwLock<MultiRaft>>,
+    /// Group commit of the raft logs of all the vnodes.
+    wal_syncer: Option<WalSyncerRef>,
 
     register: Arc<MetricsRegister>,
 }
@@ -50,6 +53,10 @@ impl RaftNodesManager {
         let path = PathBuf::from(config.storage.path.clone()).join("raft-state");
         let state =
             StateStorage::open(path, config.cluster.lmdb_max_map_size.try_into().unwrap()).unwrap();
+        let wal_option = tskv::kv_option::WalOptions::from(&config);
+        let wal_syncer = wal_option
+            .group_commit()
+            .then(|| WalSyncer::start(wal_option.sync_interval, wal_option.sync_max_size));
 
         Self {
             meta,
@@ -58,6 +65,7 @@ impl RaftNodesManager {
             register,
             raft_state: Arc::new(state),
             raft_nodes: Arc::new(RwLock::new(MultiRaft::new())),
+            wal_syncer,
         }
     }
 
@@ -69,6 +77,24 @@ impl RaftNodesManager {
         self.raft_nodes.clone()
     }
 
+    /// Checks the series limits of the points written to the vnode before they are proposed,
+    /// the raft logs are applied without the limits so that the replicas never diverge.
+    pub async fn check_series_limits(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        vnode_id: VnodeId,
+        points: &[u8],
+    ) -> CoordinatorResult<()> {
+        if let Some(kv_inst) = &self.kv_inst {
+            kv_inst
+                .check_series_limits(tenant, db_name, vnode_id, points)
+                .await
+                .context(TskvSnafu)?;
+        }
+        Ok(())
+    }
+
     pub async fn metrics(&self, group_id: u32) -> String {
         if let Ok(Some(node)) = self.raft_nodes.read().await.get_node(group_id) {
             let res = node.metrics().await;
@@ -83,6 +109,36 @@ impl RaftNodesManager {
         }
     }
 
+    /// The last applied log index of the raft node of the group on this node.
+    pub async fn replica_version(&self, group_id: ReplicationSetId) -> CoordinatorResult<u64> {
+        let node = self
+            .raft_nodes
+            .read()
+            .await
+            .get_node(group_id)
+            .context(ReplicatSnafu)?
+            .ok_or(CoordinatorError::ReplicationSetNotFound { id: group_id })?;
+
+        Ok(node
+            .raft_metrics()
+            .last_applied
+            .map(|log_id| log_id.index)
+            .unwrap_or_default())
+    }
+
+    /// Archives the WAL file that the raft node of the group on this node is writing.
+    pub async fn archive_wal(&self, group_id: ReplicationSetId) -> CoordinatorResult<()> {
+        let node = self
+            .raft_nodes
+            .read()
+            .await
+            .get_node(group_id)
+            .context(ReplicatSnafu)?
+            .ok_or(CoordinatorError::ReplicationSetNotFound { id: group_id })?;
+
+        node.archive_wal().await.context(ReplicatSnafu)
+    }
+
     pub async fn start_all_raft_node(
         runtime: Arc<Runtime>,
         manager: Arc<RaftNodesManager>,
@@ -635,9 +691,14 @@ impl RaftNodesManager {
         // 2. open raft logs storage
         let owner = make_owner(tenant, db_name);
         let wal_option = tskv::kv_option::WalOptions::from(&self.config);
-        let wal = wal::VnodeWal::new(Arc::new(wal_option), Arc::new(owner), vnode_id)
-            .await
-            .context(TskvSnafu)?;
+        let wal = wal::VnodeWal::new(
+            Arc::new(wal_option),
+            Arc::new(owner),
+            vnode_id,
+            self.wal_syncer.clone(),
+        )
+        .await
+        .context(TskvSnafu)?;
         let mut raft_logs = RaftEntryStorage::new(wal);
 
         // 3. recover data...
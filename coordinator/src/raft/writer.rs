@@ -4,6 +4,7 @@ use std::time::Duration;
 
 use memory_pool::MemoryPoolRef;
 use meta::model::MetaRef;
+use models::consistency_level::ConsistencyLevel;
 use models::meta_data::*;
 use protos::kv_service::{raft_write_command, RaftWriteCommand};
 use protos::models_helper::to_prost_bytes;
@@ -112,7 +113,45 @@ impl TskvRaftWriter {
         Ok(())
     }
 
-    async fn write_to_raft(&self, raft: Arc<RaftNode>, data: Vec<u8>) -> CoordinatorResult<()> {
+    async fn write_to_raft(
+        &self,
+        raft: Arc<RaftNode>,
+        data: Vec<u8>,
+        consistency: ConsistencyLevel,
+    ) -> CoordinatorResult<()> {
+        match consistency.write_level() {
+            ConsistencyLevel::All => {
+                let log_index = Self::client_write(&raft, data).await?;
+                raft.wait_condition(
+                    move |m| match &m.replication {
+                        Some(replication) => replication
+                            .values()
+                            .all(|log_id| log_id.as_ref().is_some_and(|id| id.index >= log_index)),
+                        None => false,
+                    },
+                    self.timeout,
+                    format!(
+                        "replica: {} replicate log {log_index} to all",
+                        raft.group_id()
+                    ),
+                )
+                .await
+                .context(ReplicatSnafu)?;
+            }
+            // A raft leader only applies the proposal after it's committed, it can't
+            // acknowledge the proposal earlier without losing it on a leader change.
+            // So a write at `One` is acknowledged like `Quorum`, and `Any` is acknowledged
+            // before this by `write_replica_by_raft`.
+            ConsistencyLevel::Any | ConsistencyLevel::One | ConsistencyLevel::Quorum => {
+                Self::client_write(&raft, data).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write data to raft and wait for it to be applied on leader, returns the log index.
+    async fn client_write(raft: &RaftNode, data: Vec<u8>) -> CoordinatorResult<u64> {
         match raft.raw_raft().client_write(data).await {
             Err(err) => {
                 if let Some(openraft::error::ForwardToLeader {
@@ -144,7 +183,7 @@ impl TskvRaftWriter {
 
                 let _data = apply_result.map_err(|e| CommonSnafu { msg: e }.build())?;
 
-                Ok(())
+                Ok(resp.log_id.index)
             }
         }
     }
@@ -156,8 +195,19 @@ impl TskvRaftWriter {
             .await?;
 
         self.pre_check_write_to_raft(&self.request).await?;
+        if let Some(raft_write_command::Command::WriteData(request)) = &self.request.command {
+            self.raft_manager
+                .check_series_limits(
+                    &self.request.tenant,
+                    &self.request.db_name,
+                    raft.raft_id() as VnodeId,
+                    &request.data,
+                )
+                .await?;
+        }
         let raft_data = to_prost_bytes(&self.request);
-        self.write_to_raft(raft, raft_data).await?;
+        let consistency = ConsistencyLevel::from(self.request.consistency_level);
+        self.write_to_raft(raft, raft_data, consistency).await?;
 
         Ok(vec![])
     }
@@ -1,9 +1,11 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use models::consistency_level::ConsistencyLevel;
 use models::meta_data::ReplicationSet;
 use models::schema::resource_info::{ResourceInfo, ResourceOperator, ResourceStatus};
 use models::schema::table_schema::TableSchema;
+use models::utils::now_timestamp_nanos;
 use protos::kv_service::{
     raft_write_command, DropColumnRequest, DropTableRequest, RaftWriteCommand, UpdateSetValue,
     UpdateTagsRequest,
@@ -171,6 +173,8 @@ impl ResourceManager {
                     replica_id: replica.id,
                     tenant: tenant_name.to_string(),
                     db_name: db_name.to_string(),
+                    consistency_level: ConsistencyLevel::Quorum as u32,
+                    create_time: now_timestamp_nanos(),
                     command: Some(raft_write_command::Command::DropTable(request)),
                 };
 
@@ -235,6 +239,8 @@ impl ResourceManager {
                             replica_id: replica.id,
                             tenant: tenant_name.to_string(),
                             db_name: table_schema.db.to_string(),
+                            consistency_level: ConsistencyLevel::Quorum as u32,
+                            create_time: now_timestamp_nanos(),
                             command: Some(raft_write_command::Command::DropColumn(request)),
                         };
 
@@ -297,6 +303,8 @@ impl ResourceManager {
                 replica_id: replica.id,
                 tenant: tenant_name.to_string(),
                 db_name: db_name.to_string(),
+                consistency_level: ConsistencyLevel::Quorum as u32,
+                create_time: now_timestamp_nanos(),
                 command: Some(raft_write_command::Command::UpdateTags(
                     update_tags_request.clone(),
                 )),
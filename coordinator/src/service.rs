@@ -24,8 +24,9 @@ use metrics::count::U64Counter;
 use metrics::label::Labels;
 use metrics::metric::Metric;
 use metrics::metric_register::MetricsRegister;
+use models::consistency_level::ConsistencyLevel;
 use models::meta_data::{
-    ExpiredBucketInfo, NodeId, ReplicationSet, ReplicationSetId, VnodeId, VnodeStatus,
+    ExpiredBucketInfo, NodeId, ReplicationSet, ReplicationSetId, VnodeId, VnodeInfo, VnodeStatus,
 };
 use models::object_reference::ResolvedTable;
 use models::oid::Identifier;
@@ -36,23 +37,27 @@ use models::schema::{DEFAULT_CATALOG, TIME_FIELD_NAME, USAGE_SCHEMA};
 use models::utils::now_timestamp_nanos;
 use models::{record_batch_decode, SeriesKey, Tag};
 use protocol_parser::lines_convert::{
-    arrow_array_to_points, line_to_batches, mutable_batches_to_point,
+    line_to_batches, mutable_batches_to_point, record_batches_to_points,
 };
 use protocol_parser::Line;
 use protos::kv_service::admin_command::Command::*;
 use protos::kv_service::*;
+use protos::{tskv_service_time_out_client, DEFAULT_GRPC_SERVER_MESSAGE_LEN};
 use replication::multi_raft::MultiRaft;
 use snafu::{IntoError, OptionExt, ResultExt};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 use tokio::runtime::Runtime;
+use tokio_stream::StreamExt;
 use trace::span_ext::SpanExt;
 use trace::{debug, error, info, Span, SpanContext};
-use tskv::EngineRef;
+use tskv::index::cardinality::{merge_cardinality_groups, CardinalityGroup, CardinalityOptions};
+use tskv::{EngineRef, VnodeSnapshot};
 use utils::precision::{timestamp_convert, Precision};
 use utils::BkdrHasher;
 
 use crate::errors::{
     ArrowSnafu, BincodeSerdeSnafu, ColumnNotFoundSnafu, CommonSnafu, CoordinatorError,
-    CoordinatorResult, FieldsIsEmptySnafu, MetaSnafu,
+    CoordinatorResult, FieldsIsEmptySnafu, IOErrorsSnafu, MetaSnafu,
 };
 use crate::metrics::LPReporter;
 use crate::raft::manager::RaftNodesManager;
@@ -93,6 +98,9 @@ pub struct CoordServiceMetrics {
     sql_data_in: Metric<U64Counter>,
     sql_write_row: Metric<U64Counter>,
     sql_points_data_in: Metric<U64Counter>,
+
+    query_result_cache_hits: Metric<U64Counter>,
+    query_result_cache_misses: Metric<U64Counter>,
 }
 
 macro_rules! generate_coord_metrics_gets {
@@ -111,6 +119,8 @@ generate_coord_metrics_gets!(coord_writes);
 generate_coord_metrics_gets!(sql_data_in);
 generate_coord_metrics_gets!(sql_write_row);
 generate_coord_metrics_gets!(sql_points_data_in);
+generate_coord_metrics_gets!(query_result_cache_hits);
+generate_coord_metrics_gets!(query_result_cache_misses);
 
 impl CoordServiceMetrics {
     pub fn new(register: &MetricsRegister) -> Self {
@@ -123,6 +133,15 @@ impl CoordServiceMetrics {
         let sql_write_row = register.metric("sql_write_row", "sql write row");
         let sql_points_data_in = register.metric("sql_points_data_in", "sql points data in");
 
+        let query_result_cache_hits = register.metric(
+            "query_result_cache_hits",
+            "query results and table scans read from the result cache",
+        );
+        let query_result_cache_misses = register.metric(
+            "query_result_cache_misses",
+            "query results and table scans not found in the result cache",
+        );
+
         Self {
             coord_data_in,
             coord_data_out,
@@ -132,6 +151,9 @@ impl CoordServiceMetrics {
             sql_data_in,
             sql_write_row,
             sql_points_data_in,
+
+            query_result_cache_hits,
+            query_result_cache_misses,
         }
     }
 
@@ -256,7 +278,14 @@ impl CoordService {
 
             let lines = lines_buffer.iter().map(|l| l.to_line()).collect::<Vec<_>>();
             if let Err(e) = coord
-                .write_lines(DEFAULT_CATALOG, USAGE_SCHEMA, Precision::NS, lines, None)
+                .write_lines(
+                    DEFAULT_CATALOG,
+                    USAGE_SCHEMA,
+                    Precision::NS,
+                    lines,
+                    ConsistencyLevel::Quorum,
+                    None,
+                )
                 .await
             {
                 error!("write metrics to {DEFAULT_CATALOG} fail. {e}")
@@ -352,6 +381,7 @@ impl CoordService {
         precision: Precision,
         info: ReplicationSet,
         points: Arc<Vec<u8>>,
+        consistency: ConsistencyLevel,
         span_ctx: Option<&'a SpanContext>,
     ) -> CoordinatorResult<Vec<impl Future<Output = CoordinatorResult<()>> + Sized + 'a>> {
         {
@@ -388,7 +418,8 @@ impl CoordService {
             replica_id: info.id,
             db_name: db.to_string(),
             tenant: tenant.to_string(),
-
+            consistency_level: consistency as u32,
+            create_time: now_timestamp_nanos(),
             command: Some(raft_write_command::Command::WriteData(request)),
         };
 
@@ -398,6 +429,119 @@ impl CoordService {
         Ok(requests)
     }
 
+    /// Routes the rows of the record batch to the replication sets by series and timestamp,
+    /// returns the precision of the time column and the rows of each replication set.
+    async fn split_record_batch(
+        &self,
+        meta_client: &MetaClientRef,
+        table_schema: &TskvTableSchemaRef,
+        record_batch: &RecordBatch,
+        db_precision: Precision,
+    ) -> CoordinatorResult<(Precision, Vec<(ReplicationSet, RecordBatch)>)> {
+        let mut precision = Precision::NS;
+        let mut repl_idx: HashMap<ReplicationSet, Vec<u32>> = HashMap::new();
+        let schema = record_batch.schema().fields.clone();
+        let table_name = table_schema.name.as_str();
+        let db = table_schema.db.as_str();
+        let columns = record_batch.columns();
+        for idx in 0..record_batch.num_rows() {
+            let mut hasher = BkdrHasher::new();
+            hasher.hash_with(table_name.as_bytes());
+            let mut ts = i64::MAX;
+            let mut has_ts = false;
+            let mut has_fileds = false;
+            for (column, schema) in columns.iter().zip(schema.iter()) {
+                let name = schema.name().as_str();
+                let tskv_schema_column = table_schema.column(name).ok_or_else(|| {
+                    CommonSnafu {
+                        msg: format!("column {} not found in table {}", name, table_name),
+                    }
+                    .build()
+                })?;
+                if name == TIME_FIELD_NAME {
+                    let precsion_and_value =
+                        get_precision_and_value_from_arrow_column(column, idx)?;
+                    precision = precsion_and_value.0;
+                    ts = timestamp_convert(precision, db_precision, precsion_and_value.1)
+                        .ok_or_else(|| {
+                            CommonSnafu {
+                                msg: "timestamp overflow".to_string(),
+                            }
+                            .build()
+                        })?;
+                    has_ts = true;
+                }
+                if matches!(tskv_schema_column.column_type, ColumnType::Tag) {
+                    let value = column
+                        .as_any()
+                        .downcast_ref::<StringArray>()
+                        .ok_or_else(|| {
+                            CommonSnafu {
+                                msg: format!("column {} is not StringArray", name),
+                            }
+                            .build()
+                        })?
+                        .value(idx);
+                    hasher.hash_with(name.as_bytes());
+                    hasher.hash_with(value.as_bytes());
+                }
+
+                if let ColumnType::Field(_) = tskv_schema_column.column_type {
+                    if !column.is_null(idx) {
+                        has_fileds = true;
+                    }
+                }
+            }
+
+            if !has_ts {
+                return Err(CommonSnafu {
+                    msg: format!(
+                        "column {} not found in table {}",
+                        TIME_FIELD_NAME, table_name
+                    ),
+                }
+                .build());
+            }
+
+            if !has_fileds {
+                return Err(FieldsIsEmptySnafu.build());
+            }
+
+            let hash = hasher.number();
+            let info = meta_client
+                .locate_replication_set_for_write(db, hash, ts)
+                .await
+                .context(MetaSnafu)?;
+            repl_idx.entry(info).or_default().push(idx as u32);
+        }
+
+        let mut batches = Vec::with_capacity(repl_idx.len());
+        for (repl, idxs) in repl_idx {
+            let indices = UInt32Array::from(idxs);
+            let columns = record_batch
+                .columns()
+                .iter()
+                .map(|column| {
+                    take(column, &indices, None).map_err(|e| {
+                        CommonSnafu {
+                            msg: format!("take column error: {}", e),
+                        }
+                        .build()
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let batch = RecordBatch::try_new(record_batch.schema(), columns).map_err(|e| {
+                CommonSnafu {
+                    msg: format!("build record batch error: {}", e),
+                }
+                .build()
+            })?;
+            batches.push((repl, batch));
+        }
+
+        Ok((precision, batches))
+    }
+
     async fn admin_command_on_leader(
         &self,
         replica: ReplicationSet,
@@ -417,6 +561,44 @@ impl CoordService {
         Ok(())
     }
 
+    /// Group the vnodes by the node they are on, and send the command built
+    /// from the vnode ids of each group to the node.
+    async fn vnodes_admin_command(
+        &self,
+        tenant: &str,
+        vnode_ids: Vec<VnodeId>,
+        command: impl Fn(Vec<VnodeId>) -> admin_command::Command,
+    ) -> CoordinatorResult<()> {
+        // Group vnode ids by node id.
+        let mut node_vnode_ids_map: HashMap<u64, Vec<u32>> = HashMap::new();
+        for vnode_id in vnode_ids.iter() {
+            let vnode = get_vnode_all_info(self.meta.clone(), tenant, *vnode_id).await?;
+            node_vnode_ids_map
+                .entry(vnode.node_id)
+                .or_default()
+                .push(*vnode_id);
+        }
+        let nodes = self.meta.data_nodes().await;
+
+        // Send grouped vnode ids to nodes.
+        let mut req_futures = vec![];
+        for node in nodes {
+            if let Some(vnode_ids) = node_vnode_ids_map.remove(&node.id) {
+                let cmd = AdminCommand {
+                    tenant: tenant.to_string(),
+                    command: Some(command(vnode_ids)),
+                };
+                req_futures.push(self.admin_command_on_node(node.id, cmd));
+            }
+        }
+
+        for res in futures::future::join_all(req_futures).await {
+            res?;
+        }
+
+        Ok(())
+    }
+
     pub async fn admin_command_on_node(
         &self,
         node_id: u64,
@@ -498,6 +680,7 @@ impl Coordinator for CoordService {
         &self,
         table: &ResolvedTable,
         predicate: ResolvedPredicateRef,
+        consistency: ConsistencyLevel,
     ) -> CoordinatorResult<Vec<ReplicationSet>> {
         // 1. 根据传入的过滤条件获取表的分片信息（包括副本）
         let mut replica_sets = self
@@ -512,7 +695,13 @@ impl Coordinator for CoordService {
         for replica_set in replica_sets.iter_mut() {
             replica_set.vnodes.sort_by_key(|vnode| {
                 // The smaller the score, the easier it is to be selected
-                if vnode.id == replica_set.leader_vnode_id {
+                if consistency == ConsistencyLevel::One
+                    && vnode.node_id == self.node_id
+                    && vnode.status == VnodeStatus::Running
+                {
+                    // Follower reads, the local replica may be behind the leader.
+                    -1
+                } else if vnode.id == replica_set.leader_vnode_id {
                     0
                 } else {
                     match vnode.status {
@@ -540,11 +729,25 @@ impl Coordinator for CoordService {
         _span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<()> {
         let tenant = request.tenant.clone();
+        let consistency = ConsistencyLevel::from(request.consistency_level);
         let writer = self.tskv_raft_writer(request);
         let executor = TskvLeaderExecutor {
             meta: self.meta.clone(),
         };
 
+        if consistency == ConsistencyLevel::Any {
+            // Acknowledge the client immediately, the write may not happen yet.
+            tokio::spawn(async move {
+                if let Err(e) = executor.do_request(&tenant, &replica, &writer).await {
+                    error!(
+                        "write to replica {} with consistency level {consistency} failed: {e}",
+                        replica.id
+                    );
+                }
+            });
+            return Ok(());
+        }
+
         executor.do_request(&tenant, &replica, &writer).await?;
 
         Ok(())
@@ -556,6 +759,7 @@ impl Coordinator for CoordService {
         db: &str,
         precision: Precision,
         lines: Vec<Line<'a>>,
+        consistency: ConsistencyLevel,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize> {
         let mut write_bytes: usize = 0;
@@ -608,8 +812,16 @@ impl Coordinator for CoordService {
             let points = Arc::new(mutable_batches_to_point(db, batches));
             write_bytes += points.len();
             requests.extend(
-                self.push_points_to_requests(tenant, db, precision, lines.info, points, span_ctx)
-                    .await?,
+                self.push_points_to_requests(
+                    tenant,
+                    db,
+                    precision,
+                    lines.info,
+                    points,
+                    consistency,
+                    span_ctx,
+                )
+                .await?,
             );
         }
         let now = tokio::time::Instant::now();
@@ -630,10 +842,10 @@ impl Coordinator for CoordService {
         table_schema: TskvTableSchemaRef,
         record_batch: RecordBatch,
         db_precision: Precision,
+        consistency: ConsistencyLevel,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize> {
         let mut write_bytes: usize = 0;
-        let mut precision = Precision::NS;
         let tenant = table_schema.tenant.as_str();
         let db = table_schema.db.as_str();
         let meta_client = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
@@ -642,110 +854,121 @@ impl Coordinator for CoordService {
             }
         })?;
 
-        let mut repl_idx: HashMap<ReplicationSet, Vec<u32>> = HashMap::new();
-        let schema = record_batch.schema().fields.clone();
-        let table_name = table_schema.name.as_str();
-        let columns = record_batch.columns();
-        for idx in 0..record_batch.num_rows() {
-            let mut hasher = BkdrHasher::new();
-            hasher.hash_with(table_name.as_bytes());
-            let mut ts = i64::MAX;
-            let mut has_ts = false;
-            let mut has_fileds = false;
-            for (column, schema) in columns.iter().zip(schema.iter()) {
-                let name = schema.name().as_str();
-                let tskv_schema_column = table_schema.column(name).ok_or_else(|| {
+        let (precision, batches) = self
+            .split_record_batch(&meta_client, &table_schema, &record_batch, db_precision)
+            .await?;
+
+        let mut requests = Vec::new();
+        for (repl, batch) in batches {
+            let points = Arc::new(
+                record_batches_to_points(db, &[(table_schema.clone(), batch)]).map_err(|e| {
                     CommonSnafu {
-                        msg: format!("column {} not found in table {}", name, table_name),
+                        msg: format!("arrow array to points error: {}", e),
                     }
                     .build()
-                })?;
-                if name == TIME_FIELD_NAME {
-                    let precsion_and_value =
-                        get_precision_and_value_from_arrow_column(column, idx)?;
-                    precision = precsion_and_value.0;
-                    ts = timestamp_convert(precision, db_precision, precsion_and_value.1)
-                        .ok_or_else(|| {
-                            CommonSnafu {
-                                msg: "timestamp overflow".to_string(),
-                            }
-                            .build()
-                        })?;
-                    has_ts = true;
-                }
-                if matches!(tskv_schema_column.column_type, ColumnType::Tag) {
-                    let value = column
-                        .as_any()
-                        .downcast_ref::<StringArray>()
-                        .ok_or_else(|| {
-                            CommonSnafu {
-                                msg: format!("column {} is not StringArray", name),
-                            }
-                            .build()
-                        })?
-                        .value(idx);
-                    hasher.hash_with(name.as_bytes());
-                    hasher.hash_with(value.as_bytes());
-                }
+                })?,
+            );
+            write_bytes += points.len();
+            requests.extend(
+                self.push_points_to_requests(
+                    tenant,
+                    db,
+                    precision,
+                    repl,
+                    points,
+                    consistency,
+                    span_ctx,
+                )
+                .await?,
+            );
+        }
+        let now = tokio::time::Instant::now();
+        for res in futures::future::join_all(requests).await {
+            debug!(
+                "Parallel write points on vnode over, start at: {:?}, elapsed: {} millis, result: {:?}",
+                now,
+                now.elapsed().as_millis(),
+                res
+            );
+            res?
+        }
+        Ok(write_bytes)
+    }
 
-                if let ColumnType::Field(_) = tskv_schema_column.column_type {
-                    if !column.is_null(idx) {
-                        has_fileds = true;
-                    }
-                }
+    async fn write_record_batches<'a>(
+        &self,
+        batches: Vec<(TskvTableSchemaRef, RecordBatch)>,
+        consistency: ConsistencyLevel,
+        span_ctx: Option<&SpanContext>,
+    ) -> CoordinatorResult<usize> {
+        let mut write_bytes: usize = 0;
+        // All rows of a replication set are merged into one raft write,
+        // so they are applied or rejected together.
+        let mut repl_batches: HashMap<
+            (String, String, Precision, ReplicationSet),
+            Vec<(TskvTableSchemaRef, RecordBatch)>,
+        > = HashMap::new();
+        for (table_schema, record_batch) in batches {
+            if record_batch.num_rows() == 0 {
+                continue;
             }
-
-            if !has_ts {
-                return Err(CommonSnafu {
-                    msg: format!(
-                        "column {} not found in table {}",
-                        TIME_FIELD_NAME, table_name
-                    ),
+            let tenant = table_schema.tenant.as_str();
+            let db = table_schema.db.as_str();
+            let meta_client = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
+                CoordinatorError::TenantNotFound {
+                    name: tenant.to_string(),
                 }
-                .build());
-            }
+            })?;
+            let db_schema = meta_client
+                .get_db_schema(db)
+                .context(MetaSnafu)?
+                .filter(|schema| !schema.is_hidden())
+                .ok_or_else(|| MetaError::DatabaseNotFound {
+                    database: db.to_string(),
+                })
+                .context(MetaSnafu)?;
 
-            if !has_fileds {
-                return Err(FieldsIsEmptySnafu.build());
+            let (precision, batches) = self
+                .split_record_batch(
+                    &meta_client,
+                    &table_schema,
+                    &record_batch,
+                    *db_schema.config.precision(),
+                )
+                .await?;
+            for (repl, batch) in batches {
+                repl_batches
+                    .entry((tenant.to_string(), db.to_string(), precision, repl))
+                    .or_default()
+                    .push((table_schema.clone(), batch));
             }
+        }
 
-            let hash = hasher.number();
-            let info = meta_client
-                .locate_replication_set_for_write(db, hash, ts)
-                .await
-                .context(MetaSnafu)?;
-            repl_idx.entry(info).or_default().push(idx as u32);
+        let mut repl_points = Vec::with_capacity(repl_batches.len());
+        for ((tenant, db, precision, repl), batches) in repl_batches {
+            let points = record_batches_to_points(&db, &batches).map_err(|e| {
+                CommonSnafu {
+                    msg: format!("arrow array to points error: {}", e),
+                }
+                .build()
+            })?;
+            write_bytes += points.len();
+            repl_points.push((tenant, db, precision, repl, Arc::new(points)));
         }
 
         let mut requests = Vec::new();
-        for (repl, idxs) in repl_idx {
-            let indices = UInt32Array::from(idxs);
-            let columns = record_batch
-                .columns()
-                .iter()
-                .map(|column| {
-                    take(column, &indices, None).map_err(|e| {
-                        CommonSnafu {
-                            msg: format!("take column error: {}", e),
-                        }
-                        .build()
-                    })
-                })
-                .collect::<Result<Vec<_>, _>>()?;
-            let schema = record_batch.schema();
-            let points = Arc::new(
-                arrow_array_to_points(columns, schema, table_schema.clone(), indices.len())
-                    .map_err(|e| {
-                        CommonSnafu {
-                            msg: format!("arrow array to points error: {}", e),
-                        }
-                        .build()
-                    })?,
-            );
-            write_bytes += points.len();
+        for (tenant, db, precision, repl, points) in repl_points.iter() {
             requests.extend(
-                self.push_points_to_requests(tenant, db, precision, repl, points, span_ctx)
-                    .await?,
+                self.push_points_to_requests(
+                    tenant,
+                    db,
+                    *precision,
+                    repl.clone(),
+                    points.clone(),
+                    consistency,
+                    span_ctx,
+                )
+                .await?,
             );
         }
         let now = tokio::time::Instant::now();
@@ -838,6 +1061,8 @@ impl Coordinator for CoordService {
                 replica_id: replica.id,
                 tenant: table.tenant().to_string(),
                 db_name: table.database().to_string(),
+                consistency_level: ConsistencyLevel::Quorum as u32,
+                create_time: now_timestamp_nanos(),
                 command: Some(raft_write_command::Command::DeleteFromTable(request)),
             };
 
@@ -939,34 +1164,223 @@ impl Coordinator for CoordService {
     }
 
     async fn compact_vnodes(&self, tenant: &str, vnode_ids: Vec<VnodeId>) -> CoordinatorResult<()> {
-        // Group vnode ids by node id.
-        let mut node_vnode_ids_map: HashMap<u64, Vec<u32>> = HashMap::new();
-        for vnode_id in vnode_ids.iter() {
-            let vnode = get_vnode_all_info(self.meta.clone(), tenant, *vnode_id).await?;
-            node_vnode_ids_map
-                .entry(vnode.node_id)
-                .or_default()
-                .push(*vnode_id);
+        self.vnodes_admin_command(tenant, vnode_ids, |vnode_ids| {
+            CompactVnode(CompactVnodeRequest { vnode_ids })
+        })
+        .await
+    }
+
+    async fn reencode_vnodes(
+        &self,
+        tenant: &str,
+        db: &str,
+        table: &str,
+        vnode_ids: Vec<VnodeId>,
+    ) -> CoordinatorResult<()> {
+        self.vnodes_admin_command(tenant, vnode_ids, |vnode_ids| {
+            ReencodeVnode(ReencodeVnodeRequest {
+                vnode_ids,
+                db_name: db.to_string(),
+                table: table.to_string(),
+            })
+        })
+        .await
+    }
+
+    async fn backup_vnode(
+        &self,
+        tenant: &str,
+        vnode: &VnodeInfo,
+    ) -> CoordinatorResult<Option<VnodeSnapshot>> {
+        let request = AdminCommand {
+            tenant: tenant.to_string(),
+            command: Some(BackupVnode(BackupVnodeRequest { vnode_id: vnode.id })),
+        };
+        let data = self.admin_command_on_node(vnode.node_id, request).await?;
+        if data.is_empty() {
+            return Ok(None);
         }
-        let nodes = self.meta.data_nodes().await;
 
-        // Send grouped vnode ids to nodes.
+        let snapshot = bincode::deserialize(&data).context(BincodeSerdeSnafu)?;
+        Ok(Some(snapshot))
+    }
+
+    async fn series_cardinality(
+        &self,
+        tenant: &str,
+        db: &str,
+        options: &CardinalityOptions,
+    ) -> CoordinatorResult<Vec<(String, Option<String>, u64)>> {
+        let meta = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
+            CoordinatorError::TenantNotFound {
+                name: tenant.to_string(),
+            }
+        })?;
+        let db_info = match meta.get_db_info(db).context(MetaSnafu)? {
+            Some(db_info) => db_info,
+            None => return Ok(vec![]),
+        };
+
         let mut req_futures = vec![];
-        for node in nodes {
-            if let Some(vnode_ids) = node_vnode_ids_map.remove(&node.id) {
-                let cmd = AdminCommand {
+        for replica in db_info.buckets.iter().flat_map(|b| b.shard_group.iter()) {
+            let vnode = match replica
+                .vnodes
+                .iter()
+                .find(|v| v.id == replica.leader_vnode_id)
+                .or_else(|| replica.vnodes.first())
+            {
+                Some(vnode) => vnode,
+                None => continue,
+            };
+            let request = AdminCommand {
+                tenant: tenant.to_string(),
+                command: Some(SeriesCardinality(SeriesCardinalityRequest {
+                    db_name: db.to_string(),
+                    vnode_id: vnode.id,
+                    table: options.table.clone(),
+                    group_by: options.group_by.clone(),
+                    exact: options.exact,
+                    tag_keys: options.tag_keys,
+                })),
+            };
+            req_futures.push(self.admin_command_on_node(vnode.node_id, request));
+        }
+
+        let mut groups = vec![];
+        for data in futures::future::try_join_all(req_futures).await? {
+            let vnode_groups: Vec<CardinalityGroup> =
+                bincode::deserialize(&data).context(BincodeSerdeSnafu)?;
+            groups.extend(vnode_groups);
+        }
+
+        Ok(merge_cardinality_groups(groups))
+    }
+
+    async fn replica_versions(
+        &self,
+        tenant: &str,
+        replica_sets: &[ReplicationSet],
+    ) -> CoordinatorResult<HashMap<VnodeId, u64>> {
+        let mut req_futures = vec![];
+        for replica in replica_sets {
+            if replica.vnodes.is_empty() {
+                return Err(CoordinatorError::ReplicationSetNotFound { id: replica.id });
+            }
+            for vnode in replica.vnodes.iter() {
+                let request = AdminCommand {
                     tenant: tenant.to_string(),
-                    command: Some(CompactVnode(CompactVnodeRequest { vnode_ids })),
+                    command: Some(FetchReplicaVersion(FetchReplicaVersionRequest {
+                        replica_id: replica.id,
+                    })),
                 };
-                req_futures.push(self.admin_command_on_node(node.id, cmd));
+                let vnode_id = vnode.id;
+                let response = self.admin_command_on_node(vnode.node_id, request);
+                req_futures.push(async move { response.await.map(|data| (vnode_id, data)) });
             }
         }
 
-        for res in futures::future::join_all(req_futures).await {
-            res?;
+        let mut versions = HashMap::with_capacity(req_futures.len());
+        for (vnode_id, data) in futures::future::try_join_all(req_futures).await? {
+            let version: u64 = bincode::deserialize(&data).context(BincodeSerdeSnafu)?;
+            versions.insert(vnode_id, version);
         }
 
-        return Ok(());
+        Ok(versions)
+    }
+
+    async fn download_file(
+        &self,
+        node_id: NodeId,
+        path: &str,
+        writer: &mut (dyn AsyncWrite + Send + Unpin),
+    ) -> CoordinatorResult<u64> {
+        let channel = self.meta.get_node_conn(node_id).await.context(MetaSnafu)?;
+        let mut client = tskv_service_time_out_client(
+            channel,
+            Duration::from_secs(60 * 60),
+            DEFAULT_GRPC_SERVER_MESSAGE_LEN,
+            self.config.service.grpc_enable_gzip,
+        );
+
+        let request = tonic::Request::new(DownloadFileRequest {
+            filename: path.to_string(),
+        });
+        let mut resp_stream = client.download_file(request).await?.into_inner();
+        let mut length = 0;
+        while let Some(received) = resp_stream.next().await {
+            let data = crate::errors::decode_grpc_response(received?)?;
+            writer.write_all(&data).await.context(IOErrorsSnafu)?;
+            length += data.len() as u64;
+        }
+
+        Ok(length)
+    }
+
+    async fn stage_vnode_file(
+        &self,
+        tenant: &str,
+        vnode: &VnodeInfo,
+        relative_path: &str,
+        offset: u64,
+        data: Vec<u8>,
+    ) -> CoordinatorResult<()> {
+        let request = AdminCommand {
+            tenant: tenant.to_string(),
+            command: Some(StageVnodeFile(StageVnodeFileRequest {
+                vnode_id: vnode.id,
+                relative_path: relative_path.to_string(),
+                offset,
+                data,
+            })),
+        };
+        self.admin_command_on_node(vnode.node_id, request).await?;
+
+        Ok(())
+    }
+
+    async fn restore_vnode(
+        &self,
+        tenant: &str,
+        db: &str,
+        replica_id: ReplicationSetId,
+        vnode: &VnodeInfo,
+        snapshot: &VnodeSnapshot,
+        until: Option<i64>,
+    ) -> CoordinatorResult<()> {
+        let snapshot = bincode::serialize(snapshot).context(BincodeSerdeSnafu)?;
+        let request = AdminCommand {
+            tenant: tenant.to_string(),
+            command: Some(RestoreVnode(RestoreVnodeRequest {
+                db_name: db.to_string(),
+                vnode_id: vnode.id,
+                replica_id,
+                snapshot,
+                until,
+            })),
+        };
+        self.admin_command_on_node(vnode.node_id, request).await?;
+
+        Ok(())
+    }
+
+    async fn archive_vnode_wal(&self, tenant: &str, vnode_id: VnodeId) -> CoordinatorResult<bool> {
+        let vnode = match get_vnode_all_info(self.meta.clone(), tenant, vnode_id).await {
+            Ok(vnode) => vnode,
+            Err(
+                CoordinatorError::TenantNotFound { .. } | CoordinatorError::VnodeNotFound { .. },
+            ) => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        let request = AdminCommand {
+            tenant: tenant.to_string(),
+            command: Some(ArchiveVnodeWal(ArchiveVnodeWalRequest {
+                replica_id: vnode.repl_set_id,
+                vnode_id,
+            })),
+        };
+        self.admin_command_on_node(vnode.node_id, request).await?;
+
+        Ok(true)
     }
 
     async fn replica_checksum(
@@ -1107,6 +1521,8 @@ impl Coordinator for CoordService {
                 replica_id: replica.id,
                 tenant: tenant.to_string(),
                 db_name: db.to_string(),
+                consistency_level: ConsistencyLevel::Quorum as u32,
+                create_time: now_timestamp_nanos(),
                 command: Some(raft_write_command::Command::UpdateTags(
                     update_tags_request.clone(),
                 )),
@@ -1,5 +1,6 @@
 #![allow(dead_code, unused_variables)]
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
@@ -10,16 +11,21 @@ use datafusion::arrow::record_batch::RecordBatch;
 use meta::model::meta_admin::AdminMeta;
 use meta::model::meta_tenant::TenantMeta;
 use meta::model::{MetaClientRef, MetaRef};
-use models::meta_data::{ReplicationSet, ReplicationSetId, VnodeId, VnodeInfo, VnodeStatus};
+use models::consistency_level::ConsistencyLevel;
+use models::meta_data::{
+    NodeId, ReplicationSet, ReplicationSetId, VnodeId, VnodeInfo, VnodeStatus,
+};
 use models::object_reference::ResolvedTable;
 use models::predicate::domain::{ResolvedPredicate, ResolvedPredicateRef};
 use models::schema::tskv_table_schema::TskvTableSchemaRef;
 use protocol_parser::Line;
 use protos::kv_service::{RaftWriteCommand, UpdateSetValue};
+use tokio::io::AsyncWrite;
 use trace::SpanContext;
 use tskv::engine_mock::MockEngine;
+use tskv::index::cardinality::CardinalityOptions;
 use tskv::reader::QueryOption;
-use tskv::EngineRef;
+use tskv::{EngineRef, VnodeSnapshot};
 use utils::precision::Precision;
 
 use crate::errors::CoordinatorResult;
@@ -59,6 +65,77 @@ impl Coordinator for MockCoordinator {
         todo!()
     }
 
+    async fn reencode_vnodes(
+        &self,
+        tenant: &str,
+        db: &str,
+        table: &str,
+        vnode_ids: Vec<VnodeId>,
+    ) -> CoordinatorResult<()> {
+        todo!()
+    }
+
+    async fn backup_vnode(
+        &self,
+        tenant: &str,
+        vnode: &VnodeInfo,
+    ) -> CoordinatorResult<Option<VnodeSnapshot>> {
+        todo!()
+    }
+
+    async fn series_cardinality(
+        &self,
+        tenant: &str,
+        db: &str,
+        options: &CardinalityOptions,
+    ) -> CoordinatorResult<Vec<(String, Option<String>, u64)>> {
+        todo!()
+    }
+
+    async fn replica_versions(
+        &self,
+        tenant: &str,
+        replica_sets: &[ReplicationSet],
+    ) -> CoordinatorResult<HashMap<VnodeId, u64>> {
+        Ok(HashMap::new())
+    }
+
+    async fn download_file(
+        &self,
+        node_id: NodeId,
+        path: &str,
+        writer: &mut (dyn AsyncWrite + Send + Unpin),
+    ) -> CoordinatorResult<u64> {
+        todo!()
+    }
+
+    async fn stage_vnode_file(
+        &self,
+        tenant: &str,
+        vnode: &VnodeInfo,
+        relative_path: &str,
+        offset: u64,
+        data: Vec<u8>,
+    ) -> CoordinatorResult<()> {
+        todo!()
+    }
+
+    async fn restore_vnode(
+        &self,
+        tenant: &str,
+        db: &str,
+        replica_id: ReplicationSetId,
+        vnode: &VnodeInfo,
+        snapshot: &VnodeSnapshot,
+        until: Option<i64>,
+    ) -> CoordinatorResult<()> {
+        todo!()
+    }
+
+    async fn archive_vnode_wal(&self, tenant: &str, vnode_id: VnodeId) -> CoordinatorResult<bool> {
+        todo!()
+    }
+
     fn tskv_raft_writer(&self, request: RaftWriteCommand) -> TskvRaftWriter {
         todo!()
     }
@@ -76,6 +153,7 @@ impl Coordinator for MockCoordinator {
         &self,
         table: &ResolvedTable,
         _predicate: ResolvedPredicateRef,
+        _consistency: ConsistencyLevel,
     ) -> CoordinatorResult<Vec<ReplicationSet>> {
         if table.database() == WITH_NONEMPTY_DATABASE_FOR_TEST {
             return Ok(vec![
@@ -170,6 +248,7 @@ impl Coordinator for MockCoordinator {
         db: &str,
         precision: Precision,
         line: Vec<Line<'a>>,
+        _consistency: ConsistencyLevel,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize> {
         todo!()
@@ -180,6 +259,16 @@ impl Coordinator for MockCoordinator {
         table_schema: TskvTableSchemaRef,
         record_batch: RecordBatch,
         db_precision: Precision,
+        _consistency: ConsistencyLevel,
+        span_ctx: Option<&SpanContext>,
+    ) -> CoordinatorResult<usize> {
+        todo!()
+    }
+
+    async fn write_record_batches<'a>(
+        &self,
+        batches: Vec<(TskvTableSchemaRef, RecordBatch)>,
+        _consistency: ConsistencyLevel,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize> {
         todo!()
@@ -1,20 +1,30 @@
 #![cfg(test)]
 
+use std::sync::Arc;
 use std::time::Duration;
 
+use arrow_flight::flight_service_client::FlightServiceClient;
 use arrow_flight::sql::client::FlightSqlServiceClient;
 use arrow_flight::sql::{
+    ActionBeginTransactionRequest, ActionBeginTransactionResult, ActionCancelQueryRequest,
+    ActionCancelQueryResult, ActionEndTransactionRequest, Any, CancelResult,
     CommandGetCrossReference, CommandGetDbSchemas, CommandGetExportedKeys, CommandGetImportedKeys,
-    CommandGetPrimaryKeys, CommandGetTables, SqlInfo,
+    CommandGetPrimaryKeys, CommandGetTables, CommandStatementUpdate, DoPutUpdateResult,
+    EndTransaction, ProstMessageExt, SqlInfo,
 };
-use arrow_flight::utils::flight_data_to_batches;
-use arrow_flight::FlightInfo;
+use arrow_flight::utils::{batches_to_flight_data, flight_data_to_batches};
+use arrow_flight::{Action, FlightData, FlightDescriptor, FlightInfo, PutResult};
+use datafusion::arrow::array::{Float64Array, StringArray, TimestampNanosecondArray};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
 use datafusion::arrow::error::ArrowError;
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::arrow::util::pretty;
 use datafusion::{arrow, assert_batches_eq};
 use futures::TryStreamExt;
+use prost::bytes::Bytes;
+use prost::Message;
 use tonic::transport::{Channel, Endpoint};
+use tonic::Request;
 
 use crate::assert_batches_one_of;
 
@@ -1525,3 +1535,326 @@ async fn test_select() {
 
     assert_batches_eq!(expected, &actual);
 }
+
+#[tokio::test]
+async fn test_flight_do_put_record_batches() {
+    let mut client = authed_client().await;
+
+    // clean env
+    let db_name = "do_put_db_test";
+    clean_env(&mut client, db_name).await;
+
+    let flight_info = client
+        .execute(
+            "CREATE DATABASE do_put_db_test WITH TTL '100000d';".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+    let actual = fetch_result_and_print(flight_info, &mut client).await;
+    assert!(actual.is_empty());
+    client.set_header("db", db_name);
+
+    let flight_info = client
+        .execute(
+            "CREATE TABLE put_air (visibility DOUBLE, TAGS(station));".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+    let actual = fetch_result_and_print(flight_info, &mut client).await;
+    assert!(actual.is_empty());
+
+    // write two record batches into the table named by the path of the descriptor
+    let schema = Arc::new(Schema::new(vec![
+        Field::new(
+            "time",
+            DataType::Timestamp(TimeUnit::Nanosecond, None),
+            false,
+        ),
+        Field::new("station", DataType::Utf8, true),
+        Field::new("visibility", DataType::Float64, true),
+    ]));
+    let batches = [("XiaoMaiDao", 1_i64), ("LianYunGang", 2_i64)]
+        .into_iter()
+        .map(|(station, time)| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(TimestampNanosecondArray::from(vec![time, time + 1])),
+                    Arc::new(StringArray::from(vec![station, station])),
+                    Arc::new(Float64Array::from(vec![56.0, 57.0])),
+                ],
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+    let mut flight_data = batches_to_flight_data(schema.as_ref().clone(), batches).unwrap();
+    flight_data[0].flight_descriptor = Some(FlightDescriptor::new_path(vec![
+        db_name.to_string(),
+        "put_air".to_string(),
+    ]));
+
+    let mut request = Request::new(futures::stream::iter(flight_data));
+    request
+        .metadata_mut()
+        .insert("authorization", "Basic cm9vdDo=".parse().unwrap());
+    let channel = flight_channel("localhost", 8904).await.unwrap();
+    let put_results = FlightServiceClient::new(channel)
+        .do_put(request)
+        .await
+        .unwrap()
+        .into_inner()
+        .try_collect::<Vec<PutResult>>()
+        .await
+        .unwrap();
+    let record_counts = put_results
+        .iter()
+        .map(|r| {
+            DoPutUpdateResult::decode(r.app_metadata.clone())
+                .unwrap()
+                .record_count
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(record_counts, vec![2, 2]);
+
+    let flight_info = client
+        .execute("SELECT count(visibility) FROM put_air;".to_string(), None)
+        .await
+        .unwrap();
+    let actual = fetch_result_and_print(flight_info, &mut client).await;
+    let expected = [
+        "+---------------------------+",
+        "| COUNT(put_air.visibility) |",
+        "+---------------------------+",
+        "| 4                         |",
+        "+---------------------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    //clean env
+    clean_env(&mut client, db_name).await;
+    check_close(&mut client).await;
+}
+
+async fn do_flight_sql_action(
+    client: &mut FlightServiceClient<Channel>,
+    r#type: &str,
+    body: Vec<u8>,
+) -> Option<Any> {
+    let mut request = Request::new(Action {
+        r#type: r#type.to_string(),
+        body: body.into(),
+    });
+    request
+        .metadata_mut()
+        .insert("authorization", "Basic cm9vdDo=".parse().unwrap());
+    let results = client
+        .do_action(request)
+        .await
+        .unwrap()
+        .into_inner()
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    results
+        .first()
+        .map(|result| Any::decode(result.body.clone()).unwrap())
+}
+
+async fn flight_transaction_update(
+    client: &mut FlightServiceClient<Channel>,
+    db: &str,
+    sql: &str,
+    transaction_id: Bytes,
+) -> i64 {
+    let command = CommandStatementUpdate {
+        query: sql.to_string(),
+        transaction_id: Some(transaction_id),
+    };
+    let flight_data = FlightData::new()
+        .with_descriptor(FlightDescriptor::new_cmd(command.as_any().encode_to_vec()));
+    let mut request = Request::new(futures::stream::iter(vec![flight_data]));
+    request
+        .metadata_mut()
+        .insert("authorization", "Basic cm9vdDo=".parse().unwrap());
+    request.metadata_mut().insert("db", db.parse().unwrap());
+    let put_results = client
+        .do_put(request)
+        .await
+        .unwrap()
+        .into_inner()
+        .try_collect::<Vec<PutResult>>()
+        .await
+        .unwrap();
+    DoPutUpdateResult::decode(put_results[0].app_metadata.clone())
+        .unwrap()
+        .record_count
+}
+
+#[tokio::test]
+async fn test_flight_transaction() {
+    let mut client = authed_client().await;
+
+    // clean env
+    let db_name = "transaction_db_test";
+    clean_env(&mut client, db_name).await;
+
+    let flight_info = client
+        .execute(
+            "CREATE DATABASE transaction_db_test WITH TTL '100000d';".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+    let actual = fetch_result_and_print(flight_info, &mut client).await;
+    assert!(actual.is_empty());
+    client.set_header("db", db_name);
+
+    let flight_info = client
+        .execute(
+            "CREATE TABLE tx_air (visibility DOUBLE, TAGS(station));".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+    let actual = fetch_result_and_print(flight_info, &mut client).await;
+    assert!(actual.is_empty());
+
+    let channel = flight_channel("localhost", 8904).await.unwrap();
+    let mut raw_client = FlightServiceClient::new(channel);
+    let begin_transaction = ActionBeginTransactionRequest {}.as_any().encode_to_vec();
+    let count_sql = "SELECT count(visibility) FROM tx_air;";
+
+    // the writes of a committed transaction are visible after the commit
+    let transaction_id = do_flight_sql_action(
+        &mut raw_client,
+        "BeginTransaction",
+        begin_transaction.clone(),
+    )
+    .await
+    .unwrap()
+    .unpack::<ActionBeginTransactionResult>()
+    .unwrap()
+    .unwrap()
+    .transaction_id;
+    for sql in [
+        "INSERT INTO tx_air (TIME, station, visibility) VALUES (1, 'XiaoMaiDao', 56);",
+        "INSERT INTO tx_air (TIME, station, visibility) VALUES (2, 'LianYunGang', 57);",
+    ] {
+        let affected_rows =
+            flight_transaction_update(&mut raw_client, db_name, sql, transaction_id.clone()).await;
+        assert_eq!(affected_rows, 1);
+    }
+
+    let flight_info = client.execute(count_sql.to_string(), None).await.unwrap();
+    let actual = fetch_result_and_print(flight_info, &mut client).await;
+    let expected = [
+        "+--------------------------+",
+        "| COUNT(tx_air.visibility) |",
+        "+--------------------------+",
+        "| 0                        |",
+        "+--------------------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let end_transaction = ActionEndTransactionRequest {
+        transaction_id,
+        action: EndTransaction::Commit as i32,
+    };
+    do_flight_sql_action(
+        &mut raw_client,
+        "EndTransaction",
+        end_transaction.as_any().encode_to_vec(),
+    )
+    .await;
+
+    let flight_info = client.execute(count_sql.to_string(), None).await.unwrap();
+    let actual = fetch_result_and_print(flight_info, &mut client).await;
+    let expected = [
+        "+--------------------------+",
+        "| COUNT(tx_air.visibility) |",
+        "+--------------------------+",
+        "| 2                        |",
+        "+--------------------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // the writes of a rolled back transaction are discarded
+    let transaction_id =
+        do_flight_sql_action(&mut raw_client, "BeginTransaction", begin_transaction)
+            .await
+            .unwrap()
+            .unpack::<ActionBeginTransactionResult>()
+            .unwrap()
+            .unwrap()
+            .transaction_id;
+    let affected_rows = flight_transaction_update(
+        &mut raw_client,
+        db_name,
+        "INSERT INTO tx_air (TIME, station, visibility) VALUES (3, 'XiaoMaiDao', 58);",
+        transaction_id.clone(),
+    )
+    .await;
+    assert_eq!(affected_rows, 1);
+
+    let end_transaction = ActionEndTransactionRequest {
+        transaction_id,
+        action: EndTransaction::Rollback as i32,
+    };
+    do_flight_sql_action(
+        &mut raw_client,
+        "EndTransaction",
+        end_transaction.as_any().encode_to_vec(),
+    )
+    .await;
+
+    let flight_info = client.execute(count_sql.to_string(), None).await.unwrap();
+    let actual = fetch_result_and_print(flight_info, &mut client).await;
+    assert_batches_eq!(expected, &actual);
+
+    //clean env
+    clean_env(&mut client, db_name).await;
+    check_close(&mut client).await;
+}
+
+#[tokio::test]
+async fn test_flight_cancel_query() {
+    let mut client = authed_client().await;
+
+    let flight_info = client.execute("SELECT 1;".to_string(), None).await.unwrap();
+
+    let channel = flight_channel("localhost", 8904).await.unwrap();
+    let mut raw_client = FlightServiceClient::new(channel);
+    let cancel_query = ActionCancelQueryRequest {
+        info: flight_info.encode_to_vec().into(),
+    };
+    let result = do_flight_sql_action(
+        &mut raw_client,
+        "CancelQuery",
+        cancel_query.as_any().encode_to_vec(),
+    )
+    .await
+    .unwrap()
+    .unpack::<ActionCancelQueryResult>()
+    .unwrap()
+    .unwrap();
+    assert_eq!(result.result, CancelResult::Cancelled as i32);
+
+    // the result set of the cancelled query is discarded
+    let ticket = flight_info.endpoint[0].ticket.clone().unwrap();
+    assert!(client.do_get(ticket).await.is_err());
+
+    // nothing to cancel any more
+    let result = do_flight_sql_action(
+        &mut raw_client,
+        "CancelQuery",
+        cancel_query.as_any().encode_to_vec(),
+    )
+    .await
+    .unwrap()
+    .unpack::<ActionCancelQueryResult>()
+    .unwrap()
+    .unwrap();
+    assert_eq!(result.result, CancelResult::NotCancellable as i32);
+}
@@ -0,0 +1,192 @@
+use std::pin::Pin;
+
+use arrow_flight::flight_descriptor::DescriptorType;
+use arrow_flight::flight_service_server::FlightService;
+use arrow_flight::sql::server::FlightSqlService;
+use arrow_flight::sql::{
+    Any, CommandPreparedStatementQuery, CommandPreparedStatementUpdate,
+    CommandStatementSubstraitPlan, CommandStatementUpdate, DoPutUpdateResult, ProstMessageExt,
+};
+use arrow_flight::{
+    Action, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo, HandshakeRequest, PutResult,
+    SchemaResult, Ticket,
+};
+use futures::Stream;
+use prost::Message;
+use tonic::{Request, Response, Status, Streaming};
+use trace::debug;
+
+use super::auth_middleware::CallHeaderAuthenticator;
+use super::flight_sql_server::FlightSqlServiceImpl;
+
+type PutResultStream = Pin<Box<dyn Stream<Item = Result<PutResult, Status>> + Send>>;
+
+/// Serves flight sql, and the bulk ingestion of arrow record batches.
+///
+/// `DoPut` with a `PATH` descriptor writes the record batches into the table named by the path,
+/// all the other requests are handled by [`FlightSqlServiceImpl`].
+pub struct FlightServiceImpl<T> {
+    sql_service: FlightSqlServiceImpl<T>,
+}
+
+impl<T> FlightServiceImpl<T> {
+    pub fn new(sql_service: FlightSqlServiceImpl<T>) -> Self {
+        Self { sql_service }
+    }
+}
+
+impl<T> FlightServiceImpl<T>
+where
+    T: CallHeaderAuthenticator + Send + Sync + 'static,
+{
+    /// The `DoPut` of flight sql, the first message of the request has been taken.
+    async fn do_put_sql_command(
+        &self,
+        descriptor: FlightDescriptor,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<<Self as FlightService>::DoPutStream>, Status> {
+        let message = Any::decode(&*descriptor.cmd)
+            .map_err(|e| Status::invalid_argument(format!("Decode flight descriptor: {}", e)))?;
+
+        if let Some(command) = unpack_command::<CommandStatementUpdate>(&message)? {
+            let record_count = self
+                .sql_service
+                .do_put_statement_update(command, request)
+                .await?;
+            return Ok(update_result_response(record_count));
+        }
+        if let Some(command) = unpack_command::<CommandStatementSubstraitPlan>(&message)? {
+            let record_count = self
+                .sql_service
+                .do_put_substrait_plan(command, request)
+                .await?;
+            return Ok(update_result_response(record_count));
+        }
+        if let Some(command) = unpack_command::<CommandPreparedStatementQuery>(&message)? {
+            return self
+                .sql_service
+                .do_put_prepared_statement_query(command, request)
+                .await;
+        }
+        if let Some(command) = unpack_command::<CommandPreparedStatementUpdate>(&message)? {
+            let record_count = self
+                .sql_service
+                .do_put_prepared_statement_update(command, request)
+                .await?;
+            return Ok(update_result_response(record_count));
+        }
+
+        Err(Status::invalid_argument(format!(
+            "do_put: The defined request is invalid: {}",
+            message.type_url
+        )))
+    }
+}
+
+#[tonic::async_trait]
+impl<T> FlightService for FlightServiceImpl<T>
+where
+    T: CallHeaderAuthenticator + Send + Sync + 'static,
+{
+    type HandshakeStream = <FlightSqlServiceImpl<T> as FlightService>::HandshakeStream;
+    type ListFlightsStream = <FlightSqlServiceImpl<T> as FlightService>::ListFlightsStream;
+    type DoGetStream = <FlightSqlServiceImpl<T> as FlightService>::DoGetStream;
+    type DoPutStream = <FlightSqlServiceImpl<T> as FlightService>::DoPutStream;
+    type DoActionStream = <FlightSqlServiceImpl<T> as FlightService>::DoActionStream;
+    type ListActionsStream = <FlightSqlServiceImpl<T> as FlightService>::ListActionsStream;
+    type DoExchangeStream = <FlightSqlServiceImpl<T> as FlightService>::DoExchangeStream;
+
+    async fn handshake(
+        &self,
+        request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        self.sql_service.handshake(request).await
+    }
+
+    async fn list_flights(
+        &self,
+        request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        self.sql_service.list_flights(request).await
+    }
+
+    async fn get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        self.sql_service.get_flight_info(request).await
+    }
+
+    async fn get_schema(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        self.sql_service.get_schema(request).await
+    }
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        self.sql_service.do_get(request).await
+    }
+
+    async fn do_put(
+        &self,
+        mut request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        let first_message = request
+            .get_mut()
+            .message()
+            .await?
+            .ok_or_else(|| Status::invalid_argument("do_put: Empty request"))?;
+        let descriptor = first_message.flight_descriptor.clone().ok_or_else(|| {
+            Status::invalid_argument("do_put: The first message must contain a flight descriptor")
+        })?;
+
+        if descriptor.r#type == DescriptorType::Path as i32 {
+            debug!("do_put: record batches, path: {:?}", descriptor.path);
+            return self
+                .sql_service
+                .do_put_record_batches(descriptor, first_message, request)
+                .await;
+        }
+
+        self.do_put_sql_command(descriptor, request).await
+    }
+
+    async fn do_exchange(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        self.sql_service.do_exchange(request).await
+    }
+
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        self.sql_service.do_action(request).await
+    }
+
+    async fn list_actions(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        self.sql_service.list_actions(request).await
+    }
+}
+
+fn unpack_command<M: ProstMessageExt>(message: &Any) -> Result<Option<M>, Status> {
+    message
+        .unpack()
+        .map_err(|e| Status::invalid_argument(format!("Decode command: {}", e)))
+}
+
+fn update_result_response(record_count: i64) -> Response<PutResultStream> {
+    let result = DoPutUpdateResult { record_count };
+    let output = futures::stream::iter(vec![Ok(PutResult {
+        app_metadata: result.encode_to_vec().into(),
+    })]);
+    Response::new(Box::pin(output))
+}
@@ -2,6 +2,9 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 
+use ::utils::precision::Precision;
+use arrow_flight::decode::FlightRecordBatchStream;
+use arrow_flight::error::FlightError;
 use arrow_flight::flight_service_server::FlightService;
 use arrow_flight::sql::server::FlightSqlService;
 use arrow_flight::sql::{
@@ -9,30 +12,38 @@ use arrow_flight::sql::{
     ActionBeginTransactionResult, ActionCancelQueryRequest, ActionCancelQueryResult,
     ActionClosePreparedStatementRequest, ActionCreatePreparedStatementRequest,
     ActionCreatePreparedStatementResult, ActionCreatePreparedSubstraitPlanRequest,
-    ActionEndSavepointRequest, ActionEndTransactionRequest, CommandGetCatalogs,
+    ActionEndSavepointRequest, ActionEndTransactionRequest, Any, CancelResult, CommandGetCatalogs,
     CommandGetCrossReference, CommandGetDbSchemas, CommandGetExportedKeys, CommandGetImportedKeys,
     CommandGetPrimaryKeys, CommandGetSqlInfo, CommandGetTableTypes, CommandGetTables,
     CommandGetXdbcTypeInfo, CommandPreparedStatementQuery, CommandPreparedStatementUpdate,
-    CommandStatementQuery, CommandStatementSubstraitPlan, CommandStatementUpdate, ProstMessageExt,
-    SqlInfo, TicketStatementQuery,
+    CommandStatementQuery, CommandStatementSubstraitPlan, CommandStatementUpdate,
+    DoPutUpdateResult, EndSavepoint, EndTransaction, ProstMessageExt, SqlInfo, SubstraitPlan,
+    TicketStatementQuery,
 };
 use arrow_flight::{
     utils as flight_utils, Action, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo,
-    HandshakeRequest, HandshakeResponse, IpcMessage, Ticket,
+    HandshakeRequest, HandshakeResponse, IpcMessage, PutResult, Ticket,
 };
+use coordinator::service::CoordinatorRef;
 use datafusion::arrow::datatypes::{Schema, SchemaRef, ToByteSlice};
-use futures::Stream;
-use http_protocol::header::{DB, STREAM_TRIGGER_INTERVAL, TARGET_PARTITIONS, TENANT};
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::{Stream, StreamExt, TryStreamExt};
+use http_protocol::header::{CONSISTENCY, DB, STREAM_TRIGGER_INTERVAL, TARGET_PARTITIONS, TENANT};
+use models::auth::privilege::{DatabasePrivilege, Privilege, TenantObjectPrivilege};
 use models::auth::user::User;
+use models::consistency_level::ConsistencyLevel;
 use models::oid::UuidGenerator;
+use models::schema::tskv_table_schema::TskvTableSchemaRef;
 use moka::sync::Cache;
 use prost::bytes::Bytes;
 use prost::Message;
 use spi::query::config::StreamTriggerInterval;
 use spi::query::execution::{Output, QueryStateMachineRef};
 use spi::query::logical_planner::Plan;
+use spi::query::transaction::TransactionWriteBuffer;
 use spi::server::dbms::DBMSRef;
 use spi::service::protocol::{Context, ContextBuilder, Query, QueryHandle};
+use spi::QueryError;
 use tonic::metadata::MetadataMap;
 use tonic::{Extensions, Request, Response, Status, Streaming};
 use trace::span_ext::SpanExt;
@@ -44,27 +55,60 @@ use crate::flight_sql::utils;
 use crate::status;
 
 const UNKNOWN_AFFECTED_ROWS_COUNT: i64 = -1;
+/// The max number of the open transactions.
+const MAX_TRANSACTIONS: u64 = 1024;
+/// The max memory size of the buffered writes of a transaction.
+const MAX_TRANSACTION_BUFFER_SIZE: usize = 64 * 1024 * 1024;
 
 pub struct FlightSqlServiceImpl<T> {
     instance: DBMSRef,
+    coord: CoordinatorRef,
     authenticator: T,
     id_generator: UuidGenerator,
     result_cache: Cache<Vec<u8>, (Option<Plan>, QueryStateMachineRef)>,
+    transactions: Cache<TransactionKey, Arc<TransactionWriteBuffer>>,
+}
+
+/// A transaction is identified by the tenant and the user that began it together with its id,
+/// so it can't be used by the other sessions even if they know the id.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct TransactionKey {
+    tenant: String,
+    user: String,
+    transaction_id: Vec<u8>,
+}
+
+impl TransactionKey {
+    fn new(ctx: &Context, transaction_id: &[u8]) -> Self {
+        Self {
+            tenant: ctx.tenant().to_string(),
+            user: ctx.user().desc().name().to_string(),
+            transaction_id: transaction_id.to_vec(),
+        }
+    }
 }
 
 impl<T> FlightSqlServiceImpl<T> {
-    pub fn new(instance: DBMSRef, authenticator: T) -> Self {
+    pub fn new(instance: DBMSRef, coord: CoordinatorRef, authenticator: T) -> Self {
         let result_cache = Cache::builder()
             // Time to live (TTL): 2 minutes
             // The query results are only cached for 2 minutes and expire after 2 minutes
             .time_to_live(Duration::from_secs(2 * 60))
             .build();
+        let transactions = Cache::builder()
+            // The transactions idle for 10 minutes are rolled back
+            .time_to_idle(Duration::from_secs(10 * 60))
+            // The transactions are rolled back after 1 hour even if they are still in use
+            .time_to_live(Duration::from_secs(60 * 60))
+            .build();
 
         Self {
             instance,
+            coord,
             authenticator,
             id_generator: Default::default(),
             result_cache,
+            transactions,
         }
     }
 }
@@ -77,8 +121,55 @@ where
         &self,
         sql: impl Into<String>,
         req_headers: &MetadataMap,
+        transaction: Option<Arc<TransactionWriteBuffer>>,
         span_ctx: Option<&SpanContext>,
     ) -> Result<(Option<Plan>, QueryStateMachineRef), Status> {
+        let query_state_machine = self
+            .authenticate_and_build_query_state_machine(sql, req_headers, transaction, span_ctx)
+            .await?;
+
+        // build logical plan
+        let logical_plan = self.build_logical_plan(query_state_machine.clone()).await?;
+
+        Ok((logical_plan, query_state_machine))
+    }
+
+    async fn pre_precess_substrait_plan_req(
+        &self,
+        substrait_plan: Option<SubstraitPlan>,
+        req_headers: &MetadataMap,
+        transaction: Option<Arc<TransactionWriteBuffer>>,
+        span_ctx: Option<&SpanContext>,
+    ) -> Result<(Option<Plan>, QueryStateMachineRef), Status> {
+        let SubstraitPlan { plan, version } = substrait_plan
+            .ok_or_else(|| Status::invalid_argument("The substrait plan is missing"))?;
+
+        // There is no sql text, the query is displayed as the version of the substrait plan
+        let query_state_machine = self
+            .authenticate_and_build_query_state_machine(
+                format!("SUBSTRAIT PLAN (version: {})", version),
+                req_headers,
+                transaction,
+                span_ctx,
+            )
+            .await?;
+
+        let logical_plan = self
+            .instance
+            .build_substrait_plan(&plan, query_state_machine.clone())
+            .await
+            .map_err(|e| status!("Build substrait plan", e))?;
+
+        Ok((Some(logical_plan), query_state_machine))
+    }
+
+    async fn authenticate_and_build_query_state_machine(
+        &self,
+        sql: impl Into<String>,
+        req_headers: &MetadataMap,
+        transaction: Option<Arc<TransactionWriteBuffer>>,
+        span_ctx: Option<&SpanContext>,
+    ) -> Result<QueryStateMachineRef, Status> {
         // auth request
         let auth_result = {
             let _span = Span::from_context("authenticate", span_ctx);
@@ -89,7 +180,7 @@ where
         // construct context by user_info and headers(parse tenant & default database)
         let ctx = {
             let _span = Span::from_context("construct context", span_ctx);
-            self.construct_context(user, req_headers)?
+            self.construct_context(user, req_headers, transaction)?
         };
 
         // build query state machine
@@ -99,22 +190,30 @@ where
                 .await?
         };
 
-        // build logical plan
-        let logical_plan = self.build_logical_plan(query_state_machine.clone()).await?;
-
-        Ok((logical_plan, query_state_machine))
+        Ok(query_state_machine)
     }
 
     async fn pre_precess_statement_query_req_and_save(
         &self,
         sql: impl Into<String>,
         req_headers: &MetadataMap,
+        transaction: Option<Arc<TransactionWriteBuffer>>,
         span_ctx: Option<&SpanContext>,
     ) -> Result<(Vec<u8>, SchemaRef), Status> {
         let (logical_plan, query_state_machine) = self
-            .pre_precess_statement_query_req(sql, req_headers, span_ctx)
+            .pre_precess_statement_query_req(sql, req_headers, transaction, span_ctx)
             .await?;
 
+        Ok(self.save_plan(logical_plan, query_state_machine))
+    }
+
+    /// Cache the plan to be executed by the following requests,
+    /// returns the identifier of the plan and the schema of the result.
+    fn save_plan(
+        &self,
+        logical_plan: Option<Plan>,
+        query_state_machine: QueryStateMachineRef,
+    ) -> (Vec<u8>, SchemaRef) {
         let schema = logical_plan
             .as_ref()
             .map(|e| e.schema())
@@ -127,17 +226,36 @@ where
         self.result_cache
             .insert(result_ident.clone(), (logical_plan, query_state_machine));
 
-        Ok((result_ident, schema))
+        (result_ident, schema)
+    }
+
+    /// Returns the identifier of the cached plan that the ticket refers to,
+    /// and whether the plan is of an ad-hoc query rather than a prepared statement.
+    fn statement_handle_of_ticket(ticket: &Ticket) -> Option<(Bytes, bool)> {
+        let message = Any::decode(&*ticket.ticket).ok()?;
+        if let Ok(Some(ticket)) = message.unpack::<TicketStatementQuery>() {
+            return Some((ticket.statement_handle, true));
+        }
+        if let Ok(Some(query)) = message.unpack::<CommandPreparedStatementQuery>() {
+            return Some((query.prepared_statement_handle, false));
+        }
+        None
     }
 
     async fn precess_flight_info_req(
         &self,
         sql: impl Into<String>,
         request: Request<FlightDescriptor>,
+        transaction: Option<Arc<TransactionWriteBuffer>>,
         span_ctx: Option<&SpanContext>,
     ) -> Result<Response<FlightInfo>, Status> {
         let (result_ident, schema) = self
-            .pre_precess_statement_query_req_and_save(sql, request.metadata(), span_ctx)
+            .pre_precess_statement_query_req_and_save(
+                sql,
+                request.metadata(),
+                transaction,
+                span_ctx,
+            )
             .await?;
 
         let ticket = TicketStatementQuery {
@@ -178,7 +296,12 @@ where
         Ok(flight_info)
     }
 
-    fn construct_context(&self, user: User, metadata: &MetadataMap) -> Result<Context, Status> {
+    fn construct_context(
+        &self,
+        user: User,
+        metadata: &MetadataMap,
+        transaction: Option<Arc<TransactionWriteBuffer>>,
+    ) -> Result<Context, Status> {
         // parse tenant & default database
         let tenant = utils::get_value_from_header(metadata, TENANT, "");
         let db = utils::get_value_from_header(metadata, DB, "");
@@ -201,11 +324,19 @@ where
                         STREAM_TRIGGER_INTERVAL, e
                     ))
                 })?;
+        let consistency = utils::get_value_from_header(metadata, CONSISTENCY, "")
+            .map(|e| e.parse::<ConsistencyLevel>())
+            .transpose()
+            .map_err(|e| {
+                Status::invalid_argument(format!("parse {} failed, error: {}", CONSISTENCY, e))
+            })?;
         let ctx = ContextBuilder::new(user)
             .with_tenant(tenant)
             .with_database(db)
             .with_target_partitions(target_partitions)
             .with_stream_trigger_interval(stream_trigger_interval)
+            .with_consistency_level(consistency)
+            .with_transaction(transaction)
             .build();
 
         Ok(ctx)
@@ -276,6 +407,135 @@ where
         Ok((logical_plan, query_state_machine))
     }
 
+    /// Authenticate the request and identify the transaction by the tenant and the user of the request.
+    async fn transaction_key(
+        &self,
+        req_headers: &MetadataMap,
+        transaction_id: &[u8],
+    ) -> Result<TransactionKey, Status> {
+        let auth_result = self.authenticator.authenticate(req_headers).await?;
+        let ctx = self.construct_context(auth_result.identity(), req_headers, None)?;
+        Ok(TransactionKey::new(&ctx, transaction_id))
+    }
+
+    /// Get the write buffer of the transaction that the statement is executed in,
+    /// the transaction must be begun by the same user of the same tenant.
+    async fn get_transaction(
+        &self,
+        req_headers: &MetadataMap,
+        transaction_id: Option<&Bytes>,
+    ) -> Result<Option<Arc<TransactionWriteBuffer>>, Status> {
+        let transaction_id = match transaction_id {
+            Some(id) if !id.is_empty() => id,
+            _ => return Ok(None),
+        };
+        let key = self.transaction_key(req_headers, transaction_id).await?;
+        self.transactions.get(&key).map(Some).ok_or_else(|| {
+            Status::not_found(format!(
+                "The transaction({:?}) does not exist or has expired",
+                transaction_id
+            ))
+        })
+    }
+
+    /// Write the record batches of a `DoPut` request into the table named by the path of the descriptor,
+    /// the path is `[[tenant, ]database, ]table`, the omitted parts are taken from the request headers.
+    ///
+    /// Every written record batch is acknowledged by a `DoPutUpdateResult` with the number of rows.
+    pub async fn do_put_record_batches(
+        &self,
+        descriptor: FlightDescriptor,
+        first_message: FlightData,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<<Self as FlightService>::DoPutStream>, Status> {
+        let span = get_span(request.extensions(), "flight sql do_put_record_batches");
+        let span_ctx = span.context();
+
+        let req_headers = request.metadata();
+        let auth_result = {
+            let _span = Span::from_context("authenticate", span_ctx.as_ref());
+            self.authenticator.authenticate(req_headers).await?
+        };
+        let ctx = self.construct_context(auth_result.identity(), req_headers, None)?;
+
+        let (tenant, db, table) = match descriptor.path.as_slice() {
+            [table] => (ctx.tenant(), ctx.database(), table.as_str()),
+            [db, table] => (ctx.tenant(), db.as_str(), table.as_str()),
+            [tenant, db, table] => (tenant.as_str(), db.as_str(), table.as_str()),
+            _ => {
+                return Err(Status::invalid_argument(format!(
+                "The path of flight descriptor must be [[tenant, ]database, ]table, found: {:?}",
+                descriptor.path
+            )))
+            }
+        };
+
+        let meta_client = self
+            .coord
+            .tenant_meta(tenant)
+            .await
+            .ok_or_else(|| Status::not_found(format!("Tenant {} not found", tenant)))?;
+
+        if self.coord.get_config().query.auth_enabled
+            && ctx
+                .user()
+                .desc()
+                .options()
+                .must_change_password()
+                .is_some_and(|x| x)
+        {
+            return Err(Status::permission_denied(
+                QueryError::InsufficientPrivileges {
+                    privilege: "change password".to_string(),
+                }
+                .to_string(),
+            ));
+        }
+        let privilege = Privilege::TenantObject(
+            TenantObjectPrivilege::Database(DatabasePrivilege::Write, Some(db.to_string())),
+            Some(*meta_client.tenant().id()),
+        );
+        if !ctx.user().check_privilege(&privilege) {
+            return Err(Status::permission_denied(
+                QueryError::InsufficientPrivileges {
+                    privilege: privilege.to_string(),
+                }
+                .to_string(),
+            ));
+        }
+
+        let db_schema = meta_client
+            .get_db_schema(db)
+            .map_err(|e| status!("Get database schema", e))?
+            .filter(|schema| !schema.is_hidden())
+            .ok_or_else(|| Status::not_found(format!("Database {} not found", db)))?;
+        let table_schema = meta_client
+            .get_tskv_table_schema(db, table)
+            .map_err(|e| status!("Get table schema", e))?
+            .ok_or_else(|| Status::not_found(format!("Table {}.{} not found", db, table)))?;
+
+        let writer = RecordBatchWriter {
+            coord: self.coord.clone(),
+            table_schema,
+            precision: *db_schema.config.precision(),
+            consistency: ctx.consistency_level(),
+            span_ctx,
+        };
+
+        // The first message carries the descriptor and the schema of the record batches
+        let flight_data = futures::stream::once(async move { Ok::<_, Status>(first_message) })
+            .chain(request.into_inner())
+            .map_err(FlightError::Tonic);
+        let stream = FlightRecordBatchStream::new_from_flight_data(flight_data)
+            .map_err(|e| Status::invalid_argument(format!("Decode record batch: {}", e)))
+            .and_then(move |record_batch| {
+                let writer = writer.clone();
+                async move { writer.write(record_batch).await }
+            });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
     async fn execute_and_fetch_result_set(
         &self,
         statement_handle: &[u8],
@@ -403,10 +663,15 @@ where
 
         let span = get_span(request.extensions(), "flight sql get_flight_info_statement");
 
-        // ignore transaction_id
-        let CommandStatementQuery { query: sql, .. } = query;
+        let CommandStatementQuery {
+            query: sql,
+            transaction_id,
+        } = query;
+        let transaction = self
+            .get_transaction(request.metadata(), transaction_id.as_ref())
+            .await?;
 
-        self.precess_flight_info_req(sql, request, span.context().as_ref())
+        self.precess_flight_info_req(sql, request, transaction, span.context().as_ref())
             .await
     }
 
@@ -465,6 +730,7 @@ where
             ORDER BY 
                 CATALOG_NAME",
             request,
+            None,
             span.context().as_ref(),
         )
         .await
@@ -510,6 +776,7 @@ where
                     CATALOG_NAME, DB_SCHEMA_NAME"
             ),
             request,
+            None,
             span.context().as_ref(),
         )
         .await
@@ -568,7 +835,7 @@ where
                 TABLE_TYPE, CATALOG_NAME, DB_SCHEMA_NAME, TABLE_NAME"
         );
 
-        self.precess_flight_info_req(sql, request, span.context().as_ref())
+        self.precess_flight_info_req(sql, request, None, span.context().as_ref())
             .await
     }
 
@@ -592,6 +859,7 @@ where
             FROM 
                 (VALUES('TABLE'),('VIEW'),('LOCAL TEMPORARY')) t(TABLE_TYPE)",
             request,
+            None,
             span.context().as_ref(),
         )
         .await
@@ -866,12 +1134,17 @@ where
 
         let span = get_span(request.extensions(), "flight sql do_put_statement_update");
         let span_ctx = span.context();
-        // ignore transaction_id
-        let CommandStatementUpdate { query, .. } = ticket;
+        let CommandStatementUpdate {
+            query,
+            transaction_id,
+        } = ticket;
+        let transaction = self
+            .get_transaction(request.metadata(), transaction_id.as_ref())
+            .await?;
         let req_headers = request.metadata();
 
         let (logical_plan, query_state_machine) = self
-            .pre_precess_statement_query_req(query, req_headers, span_ctx.as_ref())
+            .pre_precess_statement_query_req(query, req_headers, transaction, span_ctx.as_ref())
             .await?;
 
         // execute plan
@@ -944,13 +1217,19 @@ where
             request.extensions(),
             "flight sql do_action_create_prepared_statement",
         );
-        // ignore transaction_id
-        let ActionCreatePreparedStatementRequest { query: sql, .. } = query;
+        let ActionCreatePreparedStatementRequest {
+            query: sql,
+            transaction_id,
+        } = query;
+        let transaction = self
+            .get_transaction(request.metadata(), transaction_id.as_ref())
+            .await?;
 
         let (result_ident, schema) = self
             .pre_precess_statement_query_req_and_save(
                 sql,
                 request.metadata(),
+                transaction,
                 span.context().as_ref(),
             )
             .await?;
@@ -990,76 +1269,371 @@ where
         debug!("register_sql_info: _id: {:?}, request: {:?}", _id, _result);
     }
 
+    /// Like [`Self::do_action_create_prepared_statement`], the plan is cached to be executed.
     async fn do_action_create_prepared_substrait_plan(
         &self,
-        _query: ActionCreatePreparedSubstraitPlanRequest,
-        _request: Request<Action>,
+        query: ActionCreatePreparedSubstraitPlanRequest,
+        request: Request<Action>,
     ) -> Result<ActionCreatePreparedStatementResult, Status> {
-        Err(Status::unimplemented(
-            "Implement do_action_create_prepared_substrait_plan",
-        ))
+        debug!(
+            "do_action_create_prepared_substrait_plan: query: {:?}, request: {:?}",
+            query, request
+        );
+
+        let span = get_span(
+            request.extensions(),
+            "flight sql do_action_create_prepared_substrait_plan",
+        );
+        let ActionCreatePreparedSubstraitPlanRequest {
+            plan,
+            transaction_id,
+        } = query;
+        let transaction = self
+            .get_transaction(request.metadata(), transaction_id.as_ref())
+            .await?;
+
+        let (logical_plan, query_state_machine) = self
+            .pre_precess_substrait_plan_req(
+                plan,
+                request.metadata(),
+                transaction,
+                span.context().as_ref(),
+            )
+            .await?;
+        let (result_ident, schema) = self.save_plan(logical_plan, query_state_machine);
+
+        let IpcMessage(dataset_schema) = utils::schema_to_ipc_message(schema.as_ref())
+            .map_err(|e| status!("Schema to ipc message", e))?;
+        let result = ActionCreatePreparedStatementResult {
+            prepared_statement_handle: result_ident.into(),
+            dataset_schema,
+            ..Default::default()
+        };
+
+        Ok(result)
     }
 
+    /// Begin a transaction, the writes of the statements executed in the transaction
+    /// are buffered until the transaction is committed.
+    ///
+    /// Only the writes are transactional, the other statements take effect immediately,
+    /// and the buffered writes are not visible to the queries.
     async fn do_action_begin_transaction(
         &self,
-        _query: ActionBeginTransactionRequest,
-        _request: Request<Action>,
+        query: ActionBeginTransactionRequest,
+        request: Request<Action>,
     ) -> Result<ActionBeginTransactionResult, Status> {
-        Err(Status::unimplemented(
-            "Implement do_action_begin_transaction",
-        ))
+        debug!(
+            "do_action_begin_transaction: query: {:?}, request: {:?}",
+            query, request
+        );
+
+        let span = get_span(
+            request.extensions(),
+            "flight sql do_action_begin_transaction",
+        );
+        let transaction_id = self.id_generator.next_id().to_le_bytes().to_vec();
+        let key = {
+            let _span = Span::from_context("authenticate", span.context().as_ref());
+            self.transaction_key(request.metadata(), &transaction_id)
+                .await?
+        };
+
+        if self.transactions.entry_count() >= MAX_TRANSACTIONS {
+            return Err(Status::resource_exhausted(format!(
+                "Too many open transactions, the limit is {}",
+                MAX_TRANSACTIONS
+            )));
+        }
+        self.transactions.insert(
+            key,
+            Arc::new(TransactionWriteBuffer::new(MAX_TRANSACTION_BUFFER_SIZE)),
+        );
+
+        Ok(ActionBeginTransactionResult {
+            transaction_id: transaction_id.into(),
+        })
     }
 
+    /// Commit or rollback the transaction.
+    ///
+    /// On commit the buffered writes are written together,
+    /// the rows of a replication set are committed by a single raft write.
     async fn do_action_end_transaction(
         &self,
-        _query: ActionEndTransactionRequest,
-        _request: Request<Action>,
+        query: ActionEndTransactionRequest,
+        request: Request<Action>,
     ) -> Result<(), Status> {
-        Err(Status::unimplemented("Implement do_action_end_transaction"))
+        debug!(
+            "do_action_end_transaction: query: {:?}, request: {:?}",
+            query, request
+        );
+
+        let span = get_span(request.extensions(), "flight sql do_action_end_transaction");
+        let span_ctx = span.context();
+        let auth_result = {
+            let _span = Span::from_context("authenticate", span_ctx.as_ref());
+            self.authenticator.authenticate(request.metadata()).await?
+        };
+        let ctx = self.construct_context(auth_result.identity(), request.metadata(), None)?;
+
+        let ActionEndTransactionRequest {
+            transaction_id,
+            action,
+        } = query;
+        let action = EndTransaction::from_i32(action).unwrap_or(EndTransaction::Unspecified);
+        let key = TransactionKey::new(&ctx, &transaction_id);
+        let transaction = self.transactions.remove(&key).ok_or_else(|| {
+            Status::not_found(format!(
+                "The transaction({:?}) does not exist or has expired",
+                transaction_id
+            ))
+        })?;
+
+        match action {
+            EndTransaction::Commit => {
+                let batches = transaction.close();
+                let span = Span::from_context("commit transaction", span_ctx.as_ref());
+                self.coord
+                    .write_record_batches(batches, ctx.consistency_level(), span.context().as_ref())
+                    .await
+                    .map_err(|e| status!("Commit transaction", e))?;
+                Ok(())
+            }
+            EndTransaction::Rollback => {
+                transaction.close();
+                Ok(())
+            }
+            EndTransaction::Unspecified => {
+                // Keep the transaction for the retry with a valid action
+                self.transactions.insert(key, transaction);
+                Err(Status::invalid_argument(
+                    "The action of ending transaction is unspecified",
+                ))
+            }
+        }
     }
 
+    /// Create a savepoint in the transaction,
+    /// the writes after the savepoint can be discarded by rolling back to it.
     async fn do_action_begin_savepoint(
         &self,
-        _query: ActionBeginSavepointRequest,
-        _request: Request<Action>,
+        query: ActionBeginSavepointRequest,
+        request: Request<Action>,
     ) -> Result<ActionBeginSavepointResult, Status> {
-        Err(Status::unimplemented("Implement do_action_begin_savepoint"))
+        debug!(
+            "do_action_begin_savepoint: query: {:?}, request: {:?}",
+            query, request
+        );
+
+        let span = get_span(request.extensions(), "flight sql do_action_begin_savepoint");
+        let transaction = {
+            let _span = Span::from_context("authenticate", span.context().as_ref());
+            self.get_transaction(request.metadata(), Some(&query.transaction_id))
+                .await?
+        }
+        .ok_or_else(|| Status::invalid_argument("The transaction id is empty"))?;
+
+        // The savepoint id is prefixed with the id of the transaction it belongs to
+        let mut savepoint_id = query.transaction_id.to_vec();
+        savepoint_id.extend_from_slice(&self.id_generator.next_id().to_le_bytes());
+        transaction.begin_savepoint(savepoint_id.clone());
+
+        Ok(ActionBeginSavepointResult {
+            savepoint_id: savepoint_id.into(),
+        })
     }
 
+    /// Release the savepoint, or rollback the transaction to the savepoint.
     async fn do_action_end_savepoint(
         &self,
-        _query: ActionEndSavepointRequest,
-        _request: Request<Action>,
+        query: ActionEndSavepointRequest,
+        request: Request<Action>,
     ) -> Result<(), Status> {
-        Err(Status::unimplemented("Implement do_action_end_savepoint"))
+        debug!(
+            "do_action_end_savepoint: query: {:?}, request: {:?}",
+            query, request
+        );
+
+        let span = get_span(request.extensions(), "flight sql do_action_end_savepoint");
+
+        let ActionEndSavepointRequest {
+            savepoint_id,
+            action,
+        } = query;
+        let not_found = || {
+            Status::not_found(format!(
+                "The savepoint({:?}) does not exist or has expired",
+                savepoint_id
+            ))
+        };
+        let transaction_id_len = savepoint_id
+            .len()
+            .checked_sub(std::mem::size_of::<u128>())
+            .ok_or_else(not_found)?;
+        let key = {
+            let _span = Span::from_context("authenticate", span.context().as_ref());
+            self.transaction_key(request.metadata(), &savepoint_id[..transaction_id_len])
+                .await?
+        };
+        let transaction = self.transactions.get(&key).ok_or_else(not_found)?;
+
+        let found = match EndSavepoint::from_i32(action).unwrap_or(EndSavepoint::Unspecified) {
+            EndSavepoint::Release => transaction.release_savepoint(&savepoint_id),
+            EndSavepoint::Rollback => transaction.rollback_to_savepoint(&savepoint_id),
+            EndSavepoint::Unspecified => {
+                return Err(Status::invalid_argument(
+                    "The action of ending savepoint is unspecified",
+                ))
+            }
+        };
+        if !found {
+            return Err(not_found());
+        }
+
+        Ok(())
     }
 
+    /// Cancel the query of the flight info, like `KILL QUERY`.
+    ///
+    /// The result set that has not been fetched is discarded,
+    /// the running query is killed through the query tracker.
     async fn do_action_cancel_query(
         &self,
-        _query: ActionCancelQueryRequest,
-        _request: Request<Action>,
+        query: ActionCancelQueryRequest,
+        request: Request<Action>,
     ) -> Result<ActionCancelQueryResult, Status> {
-        Err(Status::unimplemented("Implement do_action_cancel_query"))
+        debug!(
+            "do_action_cancel_query: query: {:?}, request: {:?}",
+            query, request
+        );
+
+        let span = get_span(request.extensions(), "flight sql do_action_cancel_query");
+        {
+            let _span = Span::from_context("authenticate", span.context().as_ref());
+            self.authenticator.authenticate(request.metadata()).await?;
+        }
+
+        let flight_info = FlightInfo::decode(query.info)
+            .map_err(|e| Status::invalid_argument(format!("Decode flight info: {}", e)))?;
+
+        let mut result = CancelResult::NotCancellable;
+        for ticket in flight_info
+            .endpoint
+            .iter()
+            .filter_map(|e| e.ticket.as_ref())
+        {
+            let (statement_handle, is_statement) = match Self::statement_handle_of_ticket(ticket) {
+                Some(handle) => handle,
+                None => continue,
+            };
+            let (_, query_state_machine) = match self.result_cache.get(statement_handle.as_ref()) {
+                Some(cached) => cached,
+                None => continue,
+            };
+
+            if is_statement {
+                // The result set of an ad-hoc query can't be fetched after cancellation
+                self.result_cache.invalidate(statement_handle.as_ref());
+                result = CancelResult::Cancelled;
+            }
+            if self.instance.cancel(&query_state_machine.query_id) {
+                result = CancelResult::Cancelled;
+            }
+        }
+
+        Ok(ActionCancelQueryResult {
+            result: result as i32,
+        })
     }
 
+    /// Execute a substrait plan and return the number of affected rows.
     async fn do_put_substrait_plan(
         &self,
-        _ticket: CommandStatementSubstraitPlan,
-        _request: Request<Streaming<FlightData>>,
+        ticket: CommandStatementSubstraitPlan,
+        request: Request<Streaming<FlightData>>,
     ) -> Result<i64, Status> {
-        Err(Status::unimplemented(
-            "do_put_substrait_plan not implemented",
-        ))
+        debug!(
+            "do_put_substrait_plan: query: {:?}, request: {:?}",
+            ticket, request
+        );
+
+        let span = get_span(request.extensions(), "flight sql do_put_substrait_plan");
+        let span_ctx = span.context();
+        let CommandStatementSubstraitPlan {
+            plan,
+            transaction_id,
+        } = ticket;
+        let transaction = self
+            .get_transaction(request.metadata(), transaction_id.as_ref())
+            .await?;
+
+        let (logical_plan, query_state_machine) = self
+            .pre_precess_substrait_plan_req(
+                plan,
+                request.metadata(),
+                transaction,
+                span_ctx.as_ref(),
+            )
+            .await?;
+
+        // execute plan
+        let query_result = self
+            .execute_logical_plan(logical_plan, query_state_machine)
+            .await?;
+
+        let affected_rows = query_result.result().affected_rows().await;
+
+        Ok(affected_rows)
     }
 
+    /// Plan a substrait plan.
+    ///
+    /// Return the address of the result set,
+    /// waiting to call [`Self::do_get_statement`] to get the result set.
     async fn get_flight_info_substrait_plan(
         &self,
-        _query: CommandStatementSubstraitPlan,
-        _request: Request<FlightDescriptor>,
+        query: CommandStatementSubstraitPlan,
+        request: Request<FlightDescriptor>,
     ) -> Result<Response<FlightInfo>, Status> {
-        Err(Status::unimplemented(
-            "get_flight_info_substrait_plan not implemented",
-        ))
+        debug!(
+            "get_flight_info_substrait_plan: query: {:?}, request: {:?}",
+            query, request
+        );
+
+        let span = get_span(
+            request.extensions(),
+            "flight sql get_flight_info_substrait_plan",
+        );
+        let CommandStatementSubstraitPlan {
+            plan,
+            transaction_id,
+        } = query;
+        let transaction = self
+            .get_transaction(request.metadata(), transaction_id.as_ref())
+            .await?;
+
+        let (logical_plan, query_state_machine) = self
+            .pre_precess_substrait_plan_req(
+                plan,
+                request.metadata(),
+                transaction,
+                span.context().as_ref(),
+            )
+            .await?;
+        let (result_ident, schema) = self.save_plan(logical_plan, query_state_machine);
+
+        let ticket = TicketStatementQuery {
+            statement_handle: result_ident.into(),
+        };
+        let flight_info = self.construct_flight_info(
+            ticket.as_any().encode_to_vec(),
+            schema.as_ref(),
+            UNKNOWN_AFFECTED_ROWS_COUNT,
+            request.into_inner(),
+        )?;
+
+        Ok(Response::new(flight_info))
     }
 
     async fn get_flight_info_xdbc_type_info(
@@ -1083,6 +1657,42 @@ where
     }
 }
 
+/// Writes the record batches of a `DoPut` request into a table.
+#[derive(Clone)]
+struct RecordBatchWriter {
+    coord: CoordinatorRef,
+    table_schema: TskvTableSchemaRef,
+    precision: Precision,
+    consistency: ConsistencyLevel,
+    span_ctx: Option<SpanContext>,
+}
+
+impl RecordBatchWriter {
+    async fn write(&self, record_batch: RecordBatch) -> Result<PutResult, Status> {
+        let record_count = record_batch.num_rows();
+        if record_count > 0 {
+            let span = Span::from_context("write record batch", self.span_ctx.as_ref());
+            self.coord
+                .write_record_batch(
+                    self.table_schema.clone(),
+                    record_batch,
+                    self.precision,
+                    self.consistency,
+                    span.context().as_ref(),
+                )
+                .await
+                .map_err(|e| status!("Write record batch", e))?;
+        }
+
+        let result = DoPutUpdateResult {
+            record_count: record_count as i64,
+        };
+        Ok(PutResult {
+            app_metadata: result.encode_to_vec().into(),
+        })
+    }
+}
+
 fn get_span(extensions: &Extensions, child_span_name: &'static str) -> Span {
     let span_context = extensions.get::<SpanContext>();
     Span::from_context(child_span_name, span_context)
@@ -1099,6 +1709,7 @@ mod test {
     use arrow_flight::sql::{Any, CommandStatementQuery};
     use arrow_flight::utils::flight_data_to_batches;
     use arrow_flight::{FlightDescriptor, HandshakeRequest, IpcMessage};
+    use coordinator::service_mock::MockCoordinator;
     use datafusion::arrow::buffer::Buffer;
     use datafusion::arrow::datatypes::Schema;
     use datafusion::arrow::{self, ipc};
@@ -1124,7 +1735,9 @@ mod test {
             BasicCallHeaderAuthenticator::new(instance.clone()),
         );
 
-        let svc = FlightServiceServer::new(FlightSqlServiceImpl::new(instance, authenticator));
+        let coord = Arc::new(MockCoordinator {});
+        let svc =
+            FlightServiceServer::new(FlightSqlServiceImpl::new(instance, coord, authenticator));
 
         println!("Listening on {:?}", addr);
 
@@ -10,11 +10,14 @@ pub enum HttpApiType {
 
     ApiV1Sql,
     ApiV1PromRead,
+    ApiV1PromQuery,
     ApiV1ESLogWrite,
 
     ApiV1Ping,
     DebugBacktrace,
     Write,
+    Query,
+    ApiV2Write,
     ApiV1metaleader,
     ApiV1Meta,
     ApiV1Raft,
@@ -51,6 +54,9 @@ impl Display for HttpApiType {
             HttpApiType::ApiV1PromRead => {
                 write!(f, "api/v1/prom/read")
             }
+            HttpApiType::ApiV1PromQuery => {
+                write!(f, "api/v1/prom/query")
+            }
             HttpApiType::ApiV1ESLogWrite => {
                 write!(f, "api/v1/es/write")
             }
@@ -63,6 +69,12 @@ impl Display for HttpApiType {
             HttpApiType::Write => {
                 write!(f, "write")
             }
+            HttpApiType::Query => {
+                write!(f, "query")
+            }
+            HttpApiType::ApiV2Write => {
+                write!(f, "api/v2/write")
+            }
             HttpApiType::ApiV1metaleader => {
                 write!(f, "api/v1/meta_leader")
             }
@@ -114,7 +126,10 @@ pub fn metrics_record_db(api: &HttpApiType) -> bool {
         | HttpApiType::ApiV1OpenTsDBWrite
         | HttpApiType::ApiV1PromWrite
         | HttpApiType::ApiV1ESLogWrite
+        | HttpApiType::Write
+        | HttpApiType::ApiV2Write
         | HttpApiType::ApiV1PromRead
+        | HttpApiType::ApiV1PromQuery
         | HttpApiType::ApiV1Traces
         | HttpApiType::ApiTraces
         | HttpApiType::ApiTracesID
@@ -122,9 +137,9 @@ pub fn metrics_record_db(api: &HttpApiType) -> bool {
         | HttpApiType::ApiOperations
         | HttpApiType::ApiServicesOperations => true,
         HttpApiType::ApiV1Sql
+        | HttpApiType::Query
         | HttpApiType::ApiV1Ping
         | HttpApiType::DebugBacktrace
-        | HttpApiType::Write
         | HttpApiType::ApiV1metaleader
         | HttpApiType::ApiV1Meta
         | HttpApiType::ApiV1Raft
@@ -14,14 +14,13 @@ use coordinator::service::CoordinatorRef;
 use datafusion::arrow::array::{Array, StringArray};
 use futures::TryStreamExt;
 use http_protocol::encoding::Encoding;
-use http_protocol::header::{
-    ACCEPT, APPLICATION_JSON, AUTHORIZATION, DB, PRIVATE_KEY, TABLE, TENANT,
-};
+use http_protocol::header::{ACCEPT, AUTHORIZATION, DB, IDENTITY, PRIVATE_KEY, TABLE, TENANT};
 use http_protocol::parameter::{
-    DebugParam, DumpParam, FindTracesParam, GetOperationParam, LogParam, SqlParam, WriteParam,
+    DebugParam, DumpParam, FindTracesParam, GetOperationParam, InfluxWriteV1Param,
+    InfluxWriteV2Param, LogParam, SqlParam, WriteParam,
 };
 use http_protocol::response::ErrorResponse;
-use http_protocol::status_code::OK;
+use http_protocol::status_code::{BAD_REQUEST, INTERNAL_SERVER_ERROR, OK, UNPROCESSABLE_ENTITY};
 use meta::error::{MetaError, MetaResult};
 use meta::limiter::RequestLimiter;
 use meta::model::MetaRef;
@@ -29,6 +28,8 @@ use metrics::count::U64Counter;
 use metrics::metric_register::MetricsRegister;
 use metrics::prom_reporter::PromReporter;
 use models::auth::privilege::{DatabasePrivilege, Privilege, TenantObjectPrivilege};
+use models::auth::user::UserInfo;
+use models::consistency_level::ConsistencyLevel;
 use models::error_code::UnknownCodeWithMessage;
 use models::oid::{Identifier, Oid};
 use models::schema::{DEFAULT_CATALOG, DEFAULT_DATABASE};
@@ -41,12 +42,16 @@ use protocol_parser::json_protocol::JsonType;
 use protocol_parser::line_protocol::line_protocol_to_lines;
 use protocol_parser::open_tsdb::open_tsdb_to_lines;
 use protocol_parser::{DataPoint, Line};
+use query::influxql::server::InfluxQLSqlServer;
+use query::prom::promql;
 use query::prom::remote_server::PromRemoteSqlServer;
 use reqwest::header::{HeaderName, HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE};
+use reqwest::StatusCode;
 use snafu::{IntoError, ResultExt};
 use spi::query::config::StreamTriggerInterval;
 use spi::server::dbms::DBMSRef;
-use spi::server::prom::PromRemoteServerRef;
+use spi::server::influxql::InfluxQLServerRef;
+use spi::server::prom::{PromMetadataRequest, PromQueryRequest, PromRemoteServerRef};
 use spi::service::protocol::{Context, ContextBuilder, Query};
 use spi::QueryError;
 use tokio::sync::oneshot;
@@ -66,6 +71,10 @@ use super::header::Header;
 use super::{ContextSnafu, CoordinatorSnafu, DecodeRequestSnafu, Error as HttpError, MetaSnafu};
 use crate::http::api_type::{metrics_record_db, HttpApiType};
 use crate::http::encoding::{get_accept_encoding_from_header, get_content_encoding_from_header};
+use crate::http::influxdb::{
+    check_retention_policy, influx_query_response, influx_user_info, influx_write_response,
+    split_bucket, InfluxApi, InfluxError, InfluxPrecision, InfluxQueryParam,
+};
 use crate::http::metrics::HttpMetrics;
 use crate::http::response::{HttpResponse, ResponseBuilder};
 use crate::http::result_format::{get_result_format_from_header, ResultFormat};
@@ -108,6 +117,7 @@ pub struct HttpService {
     dbms: DBMSRef,
     coord: CoordinatorRef,
     prs: PromRemoteServerRef,
+    influxql: InfluxQLServerRef,
     handle: Option<ServiceHandle<()>>,
     query_body_limit: u64,
     write_body_limit: u64,
@@ -132,6 +142,7 @@ impl HttpService {
         let http_metrics = Arc::new(HttpMetrics::new(&metrics_register));
 
         let prs = Arc::new(PromRemoteSqlServer::new(dbms.clone(), coord.clone()));
+        let influxql = Arc::new(InfluxQLSqlServer::new(dbms.clone(), coord.clone()));
 
         Self {
             tls_config,
@@ -139,6 +150,7 @@ impl HttpService {
             dbms,
             coord,
             prs,
+            influxql,
             handle: None,
             query_body_limit,
             write_body_limit,
@@ -233,6 +245,13 @@ impl HttpService {
         warp::any().map(move || prs.clone())
     }
 
+    fn with_influxql_server(
+        &self,
+    ) -> impl Filter<Extract = (InfluxQLServerRef,), Error = Infallible> + Clone {
+        let influxql = self.influxql.clone();
+        warp::any().map(move || influxql.clone())
+    }
+
     fn with_metrics_register(
         &self,
     ) -> impl Filter<Extract = (Arc<MetricsRegister>,), Error = Infallible> + Clone {
@@ -257,7 +276,9 @@ impl HttpService {
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         self.ping()
             .or(self.query())
-            .or(self.mock_influxdb_write())
+            .or(self.influxdb_v1_write())
+            .or(self.influxdb_v2_write())
+            .or(self.influxdb_query())
             .or(self.metrics())
             .or(self.print_meta())
             .or(self.meta_leader_addr())
@@ -268,6 +289,7 @@ impl HttpService {
             .or(self.print_raft())
             .or(self.dump_ddl_sql())
             .or(self.prom_remote_write())
+            .or(self.prom_query_api())
             .or(self.write_open_tsdb())
             .or(self.put_open_tsdb())
             .or(self.write_line_protocol())
@@ -551,6 +573,7 @@ impl HttpService {
                         ctx.database(),
                         precision,
                         write_points_lines,
+                        ctx.consistency_level(),
                         span_context.as_ref(),
                     )
                     .await;
@@ -585,86 +608,262 @@ impl HttpService {
             )
     }
 
-    fn mock_influxdb_write(
+    /// The `/write` api of InfluxDB 1.x.
+    fn influxdb_v1_write(
         &self,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("write")
             .and(warp::post())
+            .and(warp::body::content_length_limit(self.write_body_limit))
             .and(warp::body::bytes())
-            .and(warp::query::query())
+            .and(header::optional::<String>(AUTHORIZATION.as_str()))
+            .and(header::optional::<String>(CONTENT_ENCODING.as_str()))
+            .and(warp::query::<InfluxWriteV1Param>())
             .and(self.with_dbms())
             .and(self.with_coord())
             .and(self.with_http_metrics())
             .and(self.with_hostaddr())
+            .and(self.handle_span_header())
             .and_then(
                 |req: Bytes,
-                 mut query: HashMap<String, String>,
+                 authorization: Option<String>,
+                 content_encoding: Option<String>,
+                 param: InfluxWriteV1Param,
                  dbms: DBMSRef,
                  coord: CoordinatorRef,
                  metrics: Arc<HttpMetrics>,
-                 addr: String| async move {
+                 addr: String,
+                 parent_span_ctx: Option<SpanContext>| async move {
                     let start = Instant::now();
-                    let db = query
-                        .remove("db")
-                        .unwrap_or_else(|| DEFAULT_DATABASE.to_string());
-                    let header = Header::with(
-                        Some(APPLICATION_JSON.to_string()),
-                        None,
-                        None,
-                        "Basic cm9vdDo=".to_string(),
-                    );
-                    let param = WriteParam {
-                        db: Some(db),
-                        precision: None,
-                        tenant: None,
-                    };
-                    let precision = Precision::NS;
+                    let span = Span::from_context("influxdb v1 write", parent_span_ctx.as_ref());
+                    let req_len = req.len();
 
-                    let ctx = construct_write_context_and_check_privilege(
-                        header,
-                        param,
-                        dbms,
-                        coord.clone(),
-                    )
-                    .await
-                    .map_err(|e| {
-                        error!("Failed to construct write context, err: {:?}", e);
-                        reject::custom(e)
-                    })?;
+                    let result = async {
+                        let user_info =
+                            influx_user_info(param.u, param.p, authorization.as_deref())?;
+                        let db = param
+                            .db
+                            .ok_or_else(|| InfluxError::invalid("database is required"))?;
+                        check_retention_policy(param.rp.as_deref())?;
+                        let precision = InfluxPrecision::parse(param.precision.as_deref())?;
+                        let write_param = WriteParam {
+                            precision: None,
+                            tenant: None,
+                            db: Some(db),
+                            consistency: param.consistency,
+                        };
+                        influx_write_handle(
+                            req,
+                            content_encoding,
+                            user_info,
+                            write_param,
+                            precision,
+                            dbms,
+                            coord,
+                            &metrics,
+                            &addr,
+                            start,
+                            HttpApiType::Write,
+                            span.context().as_ref(),
+                        )
+                        .await
+                    }
+                    .await;
+                    if let Err(e) = &result {
+                        span.error(format!("{e:?}"));
+                    }
 
-                    let lines = try_parse_req_to_lines(&req).map_err(|e| {
-                        error!("Failed to parse request to lines, err: {:?}", e);
-                        reject::custom(e)
-                    })?;
+                    http_response_time_and_flow_metrics(
+                        &metrics,
+                        &addr,
+                        req_len,
+                        start,
+                        HttpApiType::Write,
+                    );
+                    Ok::<_, Rejection>(influx_write_response(result, InfluxApi::V1))
+                },
+            )
+    }
 
-                    let resp = coord_write_points_with_span_recorder(
-                        &coord,
-                        ctx.tenant(),
-                        ctx.database(),
-                        precision,
-                        lines,
-                        None,
-                    )
+    /// The `/query` api of InfluxDB 1.x, the statements are written in InfluxQL.
+    fn influxdb_query(
+        &self,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        // The parameters are in the url, the POST requests may also put them in the body.
+        let params = warp::get()
+            .and(warp::query::<Vec<(String, String)>>())
+            .or(warp::post()
+                .and(warp::query::<Vec<(String, String)>>())
+                .and(warp::body::content_length_limit(self.query_body_limit))
+                .and(warp::body::form::<Vec<(String, String)>>())
+                .map(
+                    |mut params: Vec<(String, String)>, form: Vec<(String, String)>| {
+                        params.extend(form);
+                        params
+                    },
+                ))
+            .unify()
+            .or(warp::post().and(warp::query::<Vec<(String, String)>>()))
+            .unify();
+
+        warp::path!("query")
+            .and(params)
+            .and(header::optional::<String>(AUTHORIZATION.as_str()))
+            .and(self.with_dbms())
+            .and(self.with_coord())
+            .and(self.with_http_metrics())
+            .and(self.with_influxql_server())
+            .and(self.with_hostaddr())
+            .and(self.handle_span_header())
+            .and_then(
+                |params: Vec<(String, String)>,
+                 authorization: Option<String>,
+                 dbms: DBMSRef,
+                 coord: CoordinatorRef,
+                 metrics: Arc<HttpMetrics>,
+                 influxql: InfluxQLServerRef,
+                 addr: String,
+                 parent_span_ctx: Option<SpanContext>| async move {
+                    let start = Instant::now();
+                    debug!("Receive influxdb query request, param: {:?}", params);
+                    let span = Span::from_context("influxdb query", parent_span_ctx.as_ref());
+
+                    let req_len = params.iter().map(|(k, v)| k.len() + v.len()).sum::<usize>();
+                    let param = InfluxQueryParam::from_pairs(params);
+                    let pretty = param.pretty;
+                    let result = async {
+                        let request = param.influxql_request()?;
+                        let user_info =
+                            influx_user_info(param.u, param.p, authorization.as_deref())?;
+                        let context = {
+                            let mut span = Span::enter_with_parent("construct context", &span);
+                            let sql_param = SqlParam {
+                                tenant: None,
+                                db: param.db,
+                                chunked: None,
+                                target_partitions: None,
+                                stream_trigger_interval: None,
+                                consistency: None,
+                            };
+                            let ctx = construct_read_context_with_user(
+                                &user_info,
+                                sql_param,
+                                dbms,
+                                coord.clone(),
+                                false,
+                            )
+                            .await?;
+                            record_context_in_span(&mut span, &ctx);
+                            ctx
+                        };
+                        http_limiter_check_query(&coord.meta_manager(), context.tenant(), req_len)
+                            .await?;
+
+                        let span = Span::enter_with_parent("influxql query", &span);
+                        let results = influxql
+                            .query(&context, request, span.context().as_ref())
+                            .await;
+                        http_record_query_metrics(
+                            &metrics,
+                            &context,
+                            &addr,
+                            req_len,
+                            start,
+                            HttpApiType::Query,
+                        );
+                        Ok::<_, InfluxError>(results?)
+                    }
                     .await;
+                    if let Err(e) = &result {
+                        span.error(format!("{e:?}"));
+                    }
 
-                    let result_size = size_of_val(&resp);
-                    let value_size = match &resp {
-                        Ok(value) => size_of_val(value),
-                        Err(error) => size_of_val(error),
-                    };
+                    http_response_time_and_flow_metrics(
+                        &metrics,
+                        &addr,
+                        req_len,
+                        start,
+                        HttpApiType::Query,
+                    );
+                    Ok::<_, Rejection>(influx_query_response(result, pretty))
+                },
+            )
+    }
+
+    /// The `/api/v2/write` api of InfluxDB 2.x, the organization is the tenant
+    /// and the bucket `database[/retention_policy]` is the database.
+    fn influxdb_v2_write(
+        &self,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        warp::path!("api" / "v2" / "write")
+            .and(warp::post())
+            .and(warp::body::content_length_limit(self.write_body_limit))
+            .and(warp::body::bytes())
+            .and(header::optional::<String>(AUTHORIZATION.as_str()))
+            .and(header::optional::<String>(CONTENT_ENCODING.as_str()))
+            .and(warp::query::<InfluxWriteV2Param>())
+            .and(self.with_dbms())
+            .and(self.with_coord())
+            .and(self.with_http_metrics())
+            .and(self.with_hostaddr())
+            .and(self.handle_span_header())
+            .and_then(
+                |req: Bytes,
+                 authorization: Option<String>,
+                 content_encoding: Option<String>,
+                 param: InfluxWriteV2Param,
+                 dbms: DBMSRef,
+                 coord: CoordinatorRef,
+                 metrics: Arc<HttpMetrics>,
+                 addr: String,
+                 parent_span_ctx: Option<SpanContext>| async move {
+                    let start = Instant::now();
+                    let span = Span::from_context("influxdb v2 write", parent_span_ctx.as_ref());
+                    let req_len = req.len();
+
+                    let result = async {
+                        let user_info = influx_user_info(None, None, authorization.as_deref())?;
+                        let bucket = param
+                            .bucket
+                            .ok_or_else(|| InfluxError::invalid("bucket not specified"))?;
+                        let (db, rp) = split_bucket(&bucket);
+                        check_retention_policy(rp)?;
+                        let precision = InfluxPrecision::parse(param.precision.as_deref())?;
+                        let write_param = WriteParam {
+                            precision: None,
+                            tenant: param.org.or(param.org_id),
+                            db: Some(db.to_string()),
+                            consistency: None,
+                        };
+                        influx_write_handle(
+                            req,
+                            content_encoding,
+                            user_info,
+                            write_param,
+                            precision,
+                            dbms,
+                            coord,
+                            &metrics,
+                            &addr,
+                            start,
+                            HttpApiType::ApiV2Write,
+                            span.context().as_ref(),
+                        )
+                        .await
+                    }
+                    .await;
+                    if let Err(e) = &result {
+                        span.error(format!("{e:?}"));
+                    }
 
-                    let total_size = result_size + value_size + req.len();
                     http_response_time_and_flow_metrics(
                         &metrics,
                         &addr,
-                        total_size,
+                        req_len,
                         start,
-                        HttpApiType::Write,
+                        HttpApiType::ApiV2Write,
                     );
-                    resp.map(|_| ResponseBuilder::ok()).map_err(|e| {
-                        error!("Failed to handle http write request, err: {:?}", e);
-                        reject::custom(e)
-                    })
+                    Ok::<_, Rejection>(influx_write_response(result, InfluxApi::V2))
                 },
             )
     }
@@ -749,6 +948,7 @@ impl HttpService {
                         ctx.database(),
                         precision,
                         write_points_req,
+                        ctx.consistency_level(),
                         span_context.as_ref(),
                     )
                     .await;
@@ -872,6 +1072,7 @@ impl HttpService {
                         ctx.database(),
                         precision,
                         write_points_req,
+                        ctx.consistency_level(),
                         span_context.as_ref(),
                     )
                     .await;
@@ -1319,6 +1520,7 @@ impl HttpService {
                         ctx.database(),
                         Precision::NS,
                         write_request,
+                        ctx.consistency_level(),
                         span_context.as_ref(),
                     )
                     .await;
@@ -1353,6 +1555,134 @@ impl HttpService {
             )
     }
 
+    /// The query apis of prometheus: query, query_range, series, labels and label values.
+    fn prom_query_api(
+        &self,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        let api = warp::path!("api" / "v1" / "prom" / "query")
+            .map(|| PromApi::Query)
+            .or(warp::path!("api" / "v1" / "prom" / "query_range").map(|| PromApi::QueryRange))
+            .unify()
+            .or(warp::path!("api" / "v1" / "prom" / "series").map(|| PromApi::Series))
+            .unify()
+            .or(warp::path!("api" / "v1" / "prom" / "labels").map(|| PromApi::Labels))
+            .unify()
+            .or(
+                warp::path!("api" / "v1" / "prom" / "label" / String / "values")
+                    .map(PromApi::LabelValues),
+            )
+            .unify();
+        // The parameters are in the url of GET requests, or form encoded in POST requests.
+        let params = warp::get()
+            .and(warp::query::<Vec<(String, String)>>())
+            .or(warp::post()
+                .and(warp::body::content_length_limit(self.query_body_limit))
+                .and(warp::body::form::<Vec<(String, String)>>()))
+            .unify();
+
+        api.and(params)
+            .and(self.handle_header())
+            .and(self.with_dbms())
+            .and(self.with_meta())
+            .and(self.with_coord())
+            .and(self.with_http_metrics())
+            .and(self.with_prom_remote_server())
+            .and(self.with_hostaddr())
+            .and(self.handle_span_header())
+            .and_then(
+                |api: PromApi,
+                 params: Vec<(String, String)>,
+                 header: Header,
+                 dbms: DBMSRef,
+                 meta: MetaRef,
+                 coord: CoordinatorRef,
+                 metrics: Arc<HttpMetrics>,
+                 prs: PromRemoteServerRef,
+                 addr: String,
+                 parent_span_ctx: Option<SpanContext>| async move {
+                    let start = Instant::now();
+                    debug!(
+                        "Receive rest prom query request, api: {:?}, header: {:?}, param: {:?}",
+                        api, header, params
+                    );
+                    let span = Span::from_context("rest prom query", parent_span_ctx.as_ref());
+
+                    let req_len = params.iter().map(|(k, v)| k.len() + v.len()).sum::<usize>();
+                    let param = PromApiParam::from_pairs(params);
+                    let context = {
+                        let mut span = Span::enter_with_parent("construct context", &span);
+                        let sql_param = SqlParam {
+                            tenant: param.tenant.clone().or_else(|| header.get_tenant().clone()),
+                            db: param.db.clone().or_else(|| header.get_db().clone()),
+                            chunked: None,
+                            target_partitions: None,
+                            stream_trigger_interval: None,
+                            consistency: None,
+                        };
+                        let ctx = construct_read_context(&header, sql_param, dbms, coord, false)
+                            .await
+                            .map_err(|e| {
+                                error!("Failed to construct read context, err: {:?}", e);
+                                reject::custom(e)
+                            })?;
+                        record_context_in_span(&mut span, &ctx);
+                        ctx
+                    };
+
+                    http_limiter_check_query(&meta, context.tenant(), req_len)
+                        .await
+                        .map_err(|e| {
+                            error!("Failed to check query limiter, err: {:?}", e);
+                            reject::custom(e)
+                        })?;
+
+                    let result = {
+                        let span = Span::enter_with_parent("prom query", &span);
+                        let result =
+                            prom_api_handle(&prs, &context, api, param, span.context().as_ref())
+                                .await;
+                        if let Err(e) = &result {
+                            span.error(e.to_string());
+                            error!("Failed to handle prom query request, err: {:?}", e);
+                        }
+                        result
+                    };
+
+                    http_record_query_metrics(
+                        &metrics,
+                        &context,
+                        &addr,
+                        req_len,
+                        start,
+                        HttpApiType::ApiV1PromQuery,
+                    );
+                    let (status, body) = prom_api_response(result);
+                    let body = serde_json::to_vec(&body).unwrap_or_default();
+                    metrics
+                        .http_data_out(
+                            context.tenant(),
+                            context.user().desc().name(),
+                            Some(context.database()),
+                            addr.as_str(),
+                            HttpApiType::ApiV1PromQuery,
+                        )
+                        .inc(body.len() as u64);
+                    http_response_time_and_flow_metrics(
+                        &metrics,
+                        &addr,
+                        req_len + body.len(),
+                        start,
+                        HttpApiType::ApiV1PromQuery,
+                    );
+
+                    let response = ResponseBuilder::new(status)
+                        .insert_header((CONTENT_TYPE, HeaderValue::from_static("application/json")))
+                        .build(body);
+                    Ok::<_, Rejection>(response)
+                },
+            )
+    }
+
     fn dump_ddl_sql(&self) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
         async fn dump_sql_ddl_impl(meta: MetaRef, tenant: Option<String>) -> MetaResult<String> {
             let cluster = meta.cluster();
@@ -1545,6 +1875,7 @@ impl HttpService {
                         precision: None,
                         tenant: param.tenant,
                         db: param.db,
+                        consistency: None,
                     };
 
                     if param.table.is_none() {
@@ -1708,6 +2039,7 @@ impl HttpService {
                         precision: None,
                         tenant: header.get_tenant(),
                         db: header.get_db(),
+                        consistency: None,
                     };
                     let ctx = {
                         let mut span = Span::enter_with_parent("construct write context", &span);
@@ -1823,6 +2155,7 @@ impl HttpService {
                         chunked: None,
                         target_partitions: None,
                         stream_trigger_interval: None,
+                        consistency: None,
                     };
                     let _ = construct_read_context(&header, sql_param, dbms, coord.clone(), false)
                         .await
@@ -2067,6 +2400,7 @@ impl HttpService {
                         chunked: None,
                         target_partitions: None,
                         stream_trigger_interval: None,
+                        consistency: None,
                     };
                     let _ = construct_read_context(&header, sql_param, dbms, coord.clone(), false)
                         .await
@@ -2141,6 +2475,7 @@ impl HttpService {
                         chunked: None,
                         target_partitions: None,
                         stream_trigger_interval: None,
+                        consistency: None,
                     };
                     let _ = construct_read_context(&header, sql_param, dbms, coord.clone(), false)
                         .await
@@ -2267,6 +2602,7 @@ impl HttpService {
                         chunked: None,
                         target_partitions: None,
                         stream_trigger_interval: None,
+                        consistency: None,
                     };
                     let _ = construct_read_context(&header, sql_param, dbms, coord.clone(), false)
                         .await
@@ -2411,6 +2747,7 @@ impl HttpService {
                         chunked: None,
                         target_partitions: None,
                         stream_trigger_interval: None,
+                        consistency: None,
                     };
                     let _ = construct_read_context(&header, sql_param, dbms, coord.clone(), false)
                         .await
@@ -2619,10 +2956,19 @@ async fn construct_read_context(
     is_sql: bool,
 ) -> Result<Context, HttpError> {
     let user_info = header.try_get_basic_auth()?;
+    construct_read_context_with_user(&user_info, param, dbms, coord, is_sql).await
+}
 
+async fn construct_read_context_with_user(
+    user_info: &UserInfo,
+    param: SqlParam,
+    dbms: DBMSRef,
+    coord: CoordinatorRef,
+    is_sql: bool,
+) -> Result<Context, HttpError> {
     let tenant = param.tenant;
     let user = dbms
-        .authenticate(&user_info, tenant.as_deref().unwrap_or(DEFAULT_CATALOG))
+        .authenticate(user_info, tenant.as_deref().unwrap_or(DEFAULT_CATALOG))
         .await
         .context(QuerySnafu)?;
 
@@ -2655,6 +3001,7 @@ async fn construct_read_context(
                 })
                 .transpose()?,
         )
+        .with_consistency_level(parse_consistency_level(param.consistency)?)
         .build();
 
     Ok(context)
@@ -2666,12 +3013,21 @@ async fn construct_write_context(
     dbms: DBMSRef,
 ) -> Result<Context, HttpError> {
     let user_info = header.try_get_basic_auth()?;
+    construct_write_context_with_user(&user_info, param, dbms).await
+}
+
+async fn construct_write_context_with_user(
+    user_info: &UserInfo,
+    param: WriteParam,
+    dbms: DBMSRef,
+) -> Result<Context, HttpError> {
     let tenant = param.tenant;
     let db = param.db;
     let precision = param.precision;
+    let consistency = parse_consistency_level(param.consistency)?;
 
     let user = dbms
-        .authenticate(&user_info, tenant.as_deref().unwrap_or(DEFAULT_CATALOG))
+        .authenticate(user_info, tenant.as_deref().unwrap_or(DEFAULT_CATALOG))
         .await
         .context(QuerySnafu)?;
 
@@ -2679,11 +3035,23 @@ async fn construct_write_context(
         .with_tenant(tenant)
         .with_database(db)
         .with_precision(precision)
+        .with_consistency_level(consistency)
         .build();
 
     Ok(context)
 }
 
+fn parse_consistency_level(
+    consistency: Option<String>,
+) -> Result<Option<ConsistencyLevel>, HttpError> {
+    consistency
+        .map(|ref e| {
+            e.parse::<ConsistencyLevel>()
+                .map_err(|reason| HttpError::InvalidHeader { reason })
+        })
+        .transpose()
+}
+
 fn _construct_write_db_privilege(tenant_id: Oid, database: &str) -> Privilege<Oid> {
     Privilege::TenantObject(
         TenantObjectPrivilege::Database(DatabasePrivilege::Write, Some(database.to_string())),
@@ -2699,7 +3067,11 @@ async fn construct_write_context_and_check_privilege(
     coord: CoordinatorRef,
 ) -> Result<Context, HttpError> {
     let context = construct_write_context(&header, param, dbms).await?;
+    check_write_privilege(&context, &coord).await?;
+    Ok(context)
+}
 
+async fn check_write_privilege(context: &Context, coord: &CoordinatorRef) -> Result<(), HttpError> {
     let tenant_id = *coord
         .tenant_meta(context.tenant())
         .await
@@ -2738,7 +3110,55 @@ async fn construct_write_context_and_check_privilege(
             },
         });
     }
-    Ok(context)
+    Ok(())
+}
+
+/// Writes the line protocol of the InfluxDB compatible write apis.
+async fn influx_write_handle(
+    mut req: Bytes,
+    content_encoding: Option<String>,
+    user_info: UserInfo,
+    param: WriteParam,
+    precision: InfluxPrecision,
+    dbms: DBMSRef,
+    coord: CoordinatorRef,
+    metrics: &HttpMetrics,
+    addr: &str,
+    start: Instant,
+    api_type: HttpApiType,
+    span_context: Option<&SpanContext>,
+) -> Result<(), InfluxError> {
+    let req_len = req.len();
+    match content_encoding.as_deref() {
+        None | Some(IDENTITY) => {}
+        Some(s) => {
+            let encoding = Encoding::from_str_opt(s).ok_or_else(|| {
+                InfluxError::invalid(format!("content encoding not support: {s}"))
+            })?;
+            req = encoding
+                .decode(req)
+                .map_err(|e| HttpError::DecodeRequest { source: e })?;
+        }
+    }
+
+    let ctx = construct_write_context_with_user(&user_info, param, dbms).await?;
+    check_write_privilege(&ctx, &coord).await?;
+    http_limiter_check_write(&coord.meta_manager(), ctx.tenant(), req_len).await?;
+
+    let lines = precision.parse_lines(&req)?;
+    coord_write_points_with_span_recorder(
+        &coord,
+        ctx.tenant(),
+        ctx.database(),
+        precision.precision(),
+        lines,
+        ctx.consistency_level(),
+        span_context,
+    )
+    .await?;
+
+    http_record_write_metrics(metrics, &ctx, addr, req_len, start, api_type);
+    Ok(())
 }
 
 fn try_parse_req_to_lines(req: &Bytes) -> Result<Vec<Line>, HttpError> {
@@ -2849,7 +3269,14 @@ async fn coord_write_log(
     }
 
     coord
-        .write_lines(tenant, db, Precision::NS, lines, span.context().as_ref())
+        .write_lines(
+            tenant,
+            db,
+            Precision::NS,
+            lines,
+            ConsistencyLevel::Quorum,
+            span.context().as_ref(),
+        )
         .await
         .map_err(|e| {
             span.error(e.to_string());
@@ -2866,6 +3293,7 @@ async fn coord_write_points_with_span_recorder(
     db: &str,
     precision: Precision,
     write_points_lines: Vec<Line<'_>>,
+    consistency: ConsistencyLevel,
     span_context: Option<&SpanContext>,
 ) -> Result<usize, HttpError> {
     let span = Span::from_context("write points", span_context);
@@ -2875,6 +3303,7 @@ async fn coord_write_points_with_span_recorder(
             db,
             precision,
             write_points_lines,
+            consistency,
             span.context().as_ref(),
         )
         .await
@@ -3041,6 +3470,149 @@ fn http_response_time_and_flow_metrics(
     metrics.http_flow(addr, api_type).inc(flow as u64);
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PromApi {
+    Query,
+    QueryRange,
+    Series,
+    Labels,
+    LabelValues(String),
+}
+
+/// Parameters of the prometheus query apis, `match[]` may be repeated.
+#[derive(Debug, Default)]
+struct PromApiParam {
+    tenant: Option<String>,
+    db: Option<String>,
+    query: Option<String>,
+    time: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    step: Option<String>,
+    matches: Vec<String>,
+}
+
+impl PromApiParam {
+    fn from_pairs(pairs: Vec<(String, String)>) -> Self {
+        let mut param = Self::default();
+        for (key, value) in pairs {
+            match key.as_str() {
+                "tenant" => param.tenant = Some(value),
+                "db" => param.db = Some(value),
+                "query" => param.query = Some(value),
+                "time" => param.time = Some(value),
+                "start" => param.start = Some(value),
+                "end" => param.end = Some(value),
+                "step" => param.step = Some(value),
+                "match[]" => param.matches.push(value),
+                _ => {}
+            }
+        }
+        param
+    }
+}
+
+fn required_prom_param(value: Option<String>, name: &str) -> Result<String, QueryError> {
+    value.ok_or_else(|| QueryError::InvalidPromQL {
+        err: format!("missing parameter '{name}'"),
+    })
+}
+
+fn optional_prom_time(value: Option<&str>, default: i64) -> Result<i64, QueryError> {
+    value.map(promql::parse_time_ms).unwrap_or(Ok(default))
+}
+
+async fn prom_api_handle(
+    prs: &PromRemoteServerRef,
+    ctx: &Context,
+    api: PromApi,
+    param: PromApiParam,
+    span_ctx: Option<&SpanContext>,
+) -> Result<serde_json::Value, QueryError> {
+    let now = now_timestamp_nanos() / 1_000_000;
+
+    match api {
+        PromApi::Query => {
+            let query = required_prom_param(param.query, "query")?;
+            let time = optional_prom_time(param.time.as_deref(), now)?;
+            let data = prs
+                .query(ctx, PromQueryRequest::instant(query, time), span_ctx)
+                .await?;
+            to_json(&data)
+        }
+        PromApi::QueryRange => {
+            let query = required_prom_param(param.query, "query")?;
+            let start = promql::parse_time_ms(&required_prom_param(param.start, "start")?)?;
+            let end = promql::parse_time_ms(&required_prom_param(param.end, "end")?)?;
+            let step = promql::parse_step_ms(&required_prom_param(param.step, "step")?)?;
+            let data = prs
+                .query(
+                    ctx,
+                    PromQueryRequest::range(query, start, end, step),
+                    span_ctx,
+                )
+                .await?;
+            to_json(&data)
+        }
+        PromApi::Series => {
+            let req = prom_metadata_request(param)?;
+            to_json(&prs.series(ctx, req, span_ctx).await?)
+        }
+        PromApi::Labels => {
+            let req = prom_metadata_request(param)?;
+            to_json(&prs.label_names(ctx, req, span_ctx).await?)
+        }
+        PromApi::LabelValues(label) => {
+            let req = prom_metadata_request(param)?;
+            to_json(&prs.label_values(ctx, &label, req, span_ctx).await?)
+        }
+    }
+}
+
+fn prom_metadata_request(param: PromApiParam) -> Result<PromMetadataRequest, QueryError> {
+    Ok(PromMetadataRequest {
+        start: optional_prom_time(param.start.as_deref(), promql::MIN_TIME_MS)?,
+        end: optional_prom_time(param.end.as_deref(), promql::MAX_TIME_MS)?,
+        matchers: param.matches,
+    })
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<serde_json::Value, QueryError> {
+    serde_json::to_value(value).map_err(|e| QueryError::Internal {
+        reason: e.to_string(),
+    })
+}
+
+/// Builds the response body in the format of the prometheus http api.
+fn prom_api_response(
+    result: Result<serde_json::Value, QueryError>,
+) -> (StatusCode, serde_json::Value) {
+    match result {
+        Ok(data) => (
+            OK,
+            serde_json::json!({
+                "status": "success",
+                "data": data,
+            }),
+        ),
+        Err(e) => {
+            let (status, error_type) = match &e {
+                QueryError::InvalidPromQL { .. } => (BAD_REQUEST, "bad_data"),
+                QueryError::PromQLExecution { .. } => (UNPROCESSABLE_ENTITY, "execution"),
+                _ => (INTERNAL_SERVER_ERROR, "internal"),
+            };
+            (
+                status,
+                serde_json::json!({
+                    "status": "error",
+                    "errorType": error_type,
+                    "error": e.to_string(),
+                }),
+            )
+        }
+    }
+}
+
 /*************** top ****************/
 // Custom rejection handler that maps rejections into responses.
 async fn handle_rejection(err: Rejection) -> Result<impl Reply, std::convert::Infallible> {
@@ -0,0 +1,458 @@
+//! The http apis compatible with InfluxDB 1.x and 2.x, so that Telegraf and
+//! the client libraries of InfluxDB are able to work with CnosDB unmodified.
+
+use std::fmt::Display;
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use coordinator::errors::CoordinatorError;
+use http_protocol::header::{BASIC_PREFIX, TOKEN_PREFIX};
+use http_protocol::status_code::{
+    BAD_REQUEST, FORBIDDEN, INTERNAL_SERVER_ERROR, NOT_FOUND, NO_CONTENT, OK, UNAUTHORIZED,
+};
+use meta::error::MetaError;
+use models::auth::user::{UserInfo, ROOT};
+use models::utils::now_timestamp_nanos;
+use protocol_parser::line_protocol::line_protocol_to_lines;
+use protocol_parser::Line;
+use serde::Serialize;
+use spi::server::influxql::{InfluxQLRequest, InfluxQLStatementResult};
+use spi::QueryError;
+use trace::error;
+use utils::precision::Precision;
+use warp::http::header::CONTENT_TYPE;
+use warp::http::{HeaderValue, StatusCode};
+use warp::hyper::body::Bytes;
+use warp::reply::Response;
+
+use super::response::ResponseBuilder;
+use super::Error as HttpError;
+
+const INFLUXDB_ERROR_HEADER: &str = "x-influxdb-error";
+/// The retention policy names that are accepted, the data of a database in CnosDB
+/// is kept according to the TTL of the database, there is no other retention policy.
+const DEFAULT_RETENTION_POLICIES: [&str; 2] = ["autogen", "default"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InfluxApi {
+    V1,
+    V2,
+}
+
+/// An error in the format of InfluxDB.
+#[derive(Debug)]
+pub struct InfluxError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+    line: Option<usize>,
+}
+
+impl InfluxError {
+    fn new(status: StatusCode, code: &'static str, message: impl Display) -> Self {
+        Self {
+            status,
+            code,
+            message: message.to_string(),
+            line: None,
+        }
+    }
+
+    pub fn invalid(message: impl Display) -> Self {
+        Self::new(BAD_REQUEST, "invalid", message)
+    }
+
+    pub fn unauthorized(message: impl Display) -> Self {
+        Self::new(UNAUTHORIZED, "unauthorized", message)
+    }
+
+    pub fn forbidden(message: impl Display) -> Self {
+        Self::new(FORBIDDEN, "forbidden", message)
+    }
+
+    pub fn not_found(message: impl Display) -> Self {
+        Self::new(NOT_FOUND, "not found", message)
+    }
+
+    pub fn internal(message: impl Display) -> Self {
+        Self::new(INTERNAL_SERVER_ERROR, "internal error", message)
+    }
+
+    /// The line (starts from 1) of the request body that caused the error.
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn into_response(self, api: InfluxApi) -> Response {
+        let mut resp = match api {
+            InfluxApi::V1 => {
+                #[derive(Serialize)]
+                struct V1Error<'a> {
+                    error: &'a str,
+                }
+                ResponseBuilder::new(self.status).json(&V1Error {
+                    error: &self.message,
+                })
+            }
+            InfluxApi::V2 => {
+                #[derive(Serialize)]
+                struct V2Error<'a> {
+                    code: &'a str,
+                    message: &'a str,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    line: Option<usize>,
+                }
+                ResponseBuilder::new(self.status).json(&V2Error {
+                    code: self.code,
+                    message: &self.message,
+                    line: self.line,
+                })
+            }
+        };
+        if let Ok(value) = HeaderValue::from_str(&self.message) {
+            resp.headers_mut().insert(INFLUXDB_ERROR_HEADER, value);
+        }
+        resp
+    }
+}
+
+impl From<HttpError> for InfluxError {
+    fn from(e: HttpError) -> Self {
+        match e {
+            HttpError::ParseAuth { .. } => Self::unauthorized(e),
+            HttpError::Query {
+                source: QueryError::Auth { .. },
+            } => Self::unauthorized(e),
+            HttpError::Query {
+                source: QueryError::InsufficientPrivileges { .. },
+            } => Self::forbidden(e),
+            HttpError::Meta {
+                source: MetaError::TenantNotFound { .. } | MetaError::DatabaseNotFound { .. },
+            }
+            | HttpError::Coordinator {
+                source:
+                    CoordinatorError::TenantNotFound { .. }
+                    | CoordinatorError::Meta {
+                        source:
+                            MetaError::TenantNotFound { .. } | MetaError::DatabaseNotFound { .. },
+                    },
+            } => Self::not_found(e),
+            HttpError::InvalidHeader { .. }
+            | HttpError::DecodeRequest { .. }
+            | HttpError::InvalidUTF8 { .. }
+            | HttpError::ParseLineProtocol { .. } => Self::invalid(e),
+            _ => Self::internal(e),
+        }
+    }
+}
+
+impl From<QueryError> for InfluxError {
+    fn from(e: QueryError) -> Self {
+        match e {
+            QueryError::InvalidInfluxQL { err } => {
+                Self::invalid(format!("error parsing query: {err}"))
+            }
+            e => Self::from(HttpError::Query { source: e }),
+        }
+    }
+}
+
+/// Response of the write apis, InfluxDB responds `204 No Content` when the points are written.
+pub fn influx_write_response(result: Result<(), InfluxError>, api: InfluxApi) -> Response {
+    match result {
+        Ok(_) => ResponseBuilder::new(NO_CONTENT).build(vec![]),
+        Err(e) => {
+            error!("Failed to handle influxdb write request, err: {:?}", e);
+            e.into_response(api)
+        }
+    }
+}
+
+/// Parameters of the `/query` api of InfluxDB 1.x, they are in the url,
+/// or form encoded in the body of POST requests.
+#[derive(Debug, Default)]
+pub struct InfluxQueryParam {
+    pub db: Option<String>,
+    pub rp: Option<String>,
+    pub q: Option<String>,
+    pub epoch: Option<String>,
+    pub u: Option<String>,
+    pub p: Option<String>,
+    pub pretty: bool,
+}
+
+impl InfluxQueryParam {
+    pub fn from_pairs(pairs: Vec<(String, String)>) -> Self {
+        let mut param = Self::default();
+        for (key, value) in pairs {
+            match key.as_str() {
+                "db" => param.db = Some(value),
+                "rp" => param.rp = Some(value),
+                "q" => param.q = Some(value),
+                "epoch" => param.epoch = Some(value),
+                "u" => param.u = Some(value),
+                "p" => param.p = Some(value),
+                "pretty" => param.pretty = value == "true",
+                _ => {}
+            }
+        }
+        param
+    }
+
+    pub fn influxql_request(&self) -> Result<InfluxQLRequest, InfluxError> {
+        check_retention_policy(self.rp.as_deref())?;
+        let query = self
+            .q
+            .clone()
+            .filter(|q| !q.trim().is_empty())
+            .ok_or_else(|| InfluxError::invalid("missing required parameter \"q\""))?;
+        Ok(InfluxQLRequest {
+            query,
+            epoch: self.epoch.clone(),
+        })
+    }
+}
+
+/// Response of the `/query` api, the errors of the statements are in the results,
+/// the request fails only if the query can't be parsed or executed at all.
+pub fn influx_query_response(
+    result: Result<Vec<InfluxQLStatementResult>, InfluxError>,
+    pretty: bool,
+) -> Response {
+    #[derive(Serialize)]
+    struct QueryResults {
+        results: Vec<InfluxQLStatementResult>,
+    }
+
+    match result {
+        Ok(results) => {
+            let body = QueryResults { results };
+            let body = if pretty {
+                serde_json::to_vec_pretty(&body)
+            } else {
+                serde_json::to_vec(&body)
+            };
+            match body {
+                Ok(body) => ResponseBuilder::new(OK)
+                    .insert_header((CONTENT_TYPE, HeaderValue::from_static("application/json")))
+                    .build(body),
+                Err(e) => InfluxError::internal(e).into_response(InfluxApi::V1),
+            }
+        }
+        Err(e) => {
+            error!("Failed to handle influxdb query request, err: {:?}", e);
+            e.into_response(InfluxApi::V1)
+        }
+    }
+}
+
+/// Gets the user from the request, in the order of the `u` and `p` parameters,
+/// the `Basic` authorization and the `Token` authorization, the token is `username:password`.
+///
+/// If there are no credentials in the request, the request is sent by the root user
+/// without password, it is rejected when the authentication is enabled.
+pub fn influx_user_info(
+    user: Option<String>,
+    password: Option<String>,
+    authorization: Option<&str>,
+) -> Result<UserInfo, InfluxError> {
+    if let Some(user) = user {
+        return Ok(UserInfo {
+            user,
+            password: password.unwrap_or_default(),
+            private_key: None,
+        });
+    }
+
+    let credentials = match authorization {
+        Some(auth) if auth.starts_with(BASIC_PREFIX) => BASE64_STANDARD
+            .decode(&auth[BASIC_PREFIX.len()..])
+            .ok()
+            .and_then(|content| String::from_utf8(content).ok()),
+        Some(auth) if auth.starts_with(TOKEN_PREFIX) => {
+            Some(auth[TOKEN_PREFIX.len()..].trim().to_string())
+        }
+        Some(_) => None,
+        None => {
+            return Ok(UserInfo {
+                user: ROOT.to_string(),
+                password: String::new(),
+                private_key: None,
+            })
+        }
+    };
+
+    credentials
+        .and_then(|c| {
+            c.split_once(':').map(|(user, password)| UserInfo {
+                user: user.to_string(),
+                password: password.to_string(),
+                private_key: None,
+            })
+        })
+        .ok_or_else(|| InfluxError::unauthorized("unable to parse authentication credentials"))
+}
+
+/// Gets the database and the retention policy from the bucket `database[/retention_policy]`.
+pub fn split_bucket(bucket: &str) -> (&str, Option<&str>) {
+    match bucket.split_once('/') {
+        Some((db, rp)) => (db, Some(rp)),
+        None => (bucket, None),
+    }
+}
+
+pub fn check_retention_policy(rp: Option<&str>) -> Result<(), InfluxError> {
+    match rp {
+        Some(rp) if !rp.is_empty() && !DEFAULT_RETENTION_POLICIES.contains(&rp) => Err(
+            InfluxError::not_found(format!("retention policy not found: {rp}")),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// The precision of the timestamps of InfluxDB, the precisions that CnosDB does not support
+/// (second, minute and hour) are converted to millisecond.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InfluxPrecision {
+    precision: Precision,
+    multiplier: i64,
+    unit_nanos: i64,
+}
+
+impl InfluxPrecision {
+    pub fn parse(text: Option<&str>) -> Result<Self, InfluxError> {
+        let (precision, multiplier, unit_nanos) = match text.unwrap_or_default() {
+            "" | "n" | "ns" => (Precision::NS, 1, 1),
+            "u" | "us" | "µ" | "µs" => (Precision::US, 1, 1_000),
+            "ms" => (Precision::MS, 1, 1_000_000),
+            "s" => (Precision::MS, 1_000, 1_000_000_000),
+            "m" => (Precision::MS, 60_000, 60_000_000_000),
+            "h" => (Precision::MS, 3_600_000, 3_600_000_000_000),
+            other => return Err(InfluxError::invalid(format!("invalid precision: {other}"))),
+        };
+        Ok(Self {
+            precision,
+            multiplier,
+            unit_nanos,
+        })
+    }
+
+    pub fn precision(&self) -> Precision {
+        self.precision
+    }
+
+    /// Parses the line protocol, the points without timestamp are written at the current time.
+    pub fn parse_lines<'a>(&self, req: &'a Bytes) -> Result<Vec<Line<'a>>, InfluxError> {
+        let data = simdutf8::basic::from_utf8(req.as_ref())
+            .map_err(|e| InfluxError::from(HttpError::InvalidUTF8 { source: e }))?;
+
+        let default_time = now_timestamp_nanos() / self.unit_nanos;
+        let mut lines = line_protocol_to_lines(data, default_time).map_err(|e| {
+            match locate_parse_error(data) {
+                Some((line, err)) => InfluxError::invalid(err).with_line(line),
+                None => InfluxError::invalid(format!("unable to parse points: {e}")),
+            }
+        })?;
+
+        if self.multiplier != 1 {
+            for line in lines.iter_mut() {
+                line.timestamp = line
+                    .timestamp
+                    .checked_mul(self.multiplier)
+                    .ok_or_else(|| InfluxError::invalid("timestamp out of range"))?;
+            }
+        }
+        Ok(lines)
+    }
+}
+
+/// Finds the first line (starts from 1) that failed to be parsed.
+fn locate_parse_error(data: &str) -> Option<(usize, String)> {
+    data.lines().enumerate().find_map(|(idx, line)| {
+        line_protocol_to_lines(line, 0)
+            .err()
+            .map(|e| (idx + 1, format!("unable to parse '{line}': {e}")))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_influx_user_info() {
+        let user = influx_user_info(Some("u1".to_string()), Some("p1".to_string()), None).unwrap();
+        assert_eq!((user.user.as_str(), user.password.as_str()), ("u1", "p1"));
+
+        let basic = format!("Basic {}", BASE64_STANDARD.encode("u2:p2"));
+        let user = influx_user_info(None, None, Some(&basic)).unwrap();
+        assert_eq!((user.user.as_str(), user.password.as_str()), ("u2", "p2"));
+
+        let user = influx_user_info(None, None, Some("Token u3:p3")).unwrap();
+        assert_eq!((user.user.as_str(), user.password.as_str()), ("u3", "p3"));
+
+        let user = influx_user_info(None, None, None).unwrap();
+        assert_eq!((user.user.as_str(), user.password.as_str()), (ROOT, ""));
+
+        let err = influx_user_info(None, None, Some("Token abc")).unwrap_err();
+        assert_eq!(err.status(), UNAUTHORIZED);
+        let err = influx_user_info(None, None, Some("Bearer abc")).unwrap_err();
+        assert_eq!(err.status(), UNAUTHORIZED);
+    }
+
+    #[test]
+    fn test_bucket_and_retention_policy() {
+        assert_eq!(split_bucket("db1"), ("db1", None));
+        assert_eq!(split_bucket("db1/autogen"), ("db1", Some("autogen")));
+        assert!(check_retention_policy(None).is_ok());
+        assert!(check_retention_policy(Some("")).is_ok());
+        assert!(check_retention_policy(Some("autogen")).is_ok());
+        assert_eq!(
+            check_retention_policy(Some("one_week"))
+                .unwrap_err()
+                .status(),
+            NOT_FOUND
+        );
+    }
+
+    #[test]
+    fn test_precision() {
+        let req = Bytes::from("m1,t=a f=1 10\nm1,t=b f=2 20");
+
+        let precision = InfluxPrecision::parse(None).unwrap();
+        assert_eq!(precision.precision(), Precision::NS);
+        let lines = precision.parse_lines(&req).unwrap();
+        assert_eq!(lines[1].timestamp, 20);
+
+        let precision = InfluxPrecision::parse(Some("s")).unwrap();
+        assert_eq!(precision.precision(), Precision::MS);
+        let lines = precision.parse_lines(&req).unwrap();
+        assert_eq!(lines[0].timestamp, 10_000);
+        assert_eq!(lines[1].timestamp, 20_000);
+
+        let precision = InfluxPrecision::parse(Some("h")).unwrap();
+        let lines = precision
+            .parse_lines(&Bytes::from(format!("m1 f=1 {}", i64::MAX / 1000)))
+            .unwrap_err();
+        assert_eq!(lines.status(), BAD_REQUEST);
+
+        assert!(InfluxPrecision::parse(Some("d")).is_err());
+    }
+
+    #[test]
+    fn test_parse_error_line() {
+        let precision = InfluxPrecision::parse(None).unwrap();
+        let req = Bytes::from("m1,t=a f=1 1\n# comment\nm1,t=a f=x 2\nm1,t=a f=1 3");
+        let err = precision.parse_lines(&req).unwrap_err();
+        assert_eq!(err.status(), BAD_REQUEST);
+        assert_eq!(err.line, Some(3));
+
+        let resp = err.into_response(InfluxApi::V2);
+        assert_eq!(resp.status(), BAD_REQUEST);
+        assert!(resp.headers().contains_key(INFLUXDB_ERROR_HEADER));
+    }
+}
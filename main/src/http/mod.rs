@@ -16,6 +16,7 @@ mod api_type;
 mod encoding;
 pub mod header;
 pub mod http_service;
+mod influxdb;
 mod metrics;
 mod response;
 mod result_format;
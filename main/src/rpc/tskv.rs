@@ -1,21 +1,23 @@
+use std::path::{Component, Path, PathBuf};
 use std::pin::Pin;
 use std::sync::Arc;
 
 use coordinator::errors::{
-    encode_grpc_response, ArrowSnafu, CommonSnafu, CoordinatorResult, TskvSnafu,
+    encode_grpc_response, ArrowSnafu, BincodeSerdeSnafu, CommonSnafu, CoordinatorResult,
+    IOErrorsSnafu, ReplicatSnafu, TskvSnafu,
 };
 use coordinator::service::CoordinatorRef;
-use futures::{Stream, TryStreamExt};
+use futures::{Stream, StreamExt, TryStreamExt};
 use meta::model::MetaRef;
 use metrics::metric_register::MetricsRegister;
-use models::meta_data::VnodeInfo;
+use models::meta_data::{VnodeId, VnodeInfo};
 use models::predicate::domain::{self, PushedAggregateFunction, QueryArgs, QueryExpr};
 use models::record_batch_encode;
 use protos::kv_service::tskv_service_server::TskvService;
 use protos::kv_service::*;
 use protos::models::{PingBody, PingBodyBuilder};
 use snafu::ResultExt;
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
@@ -23,6 +25,7 @@ use tonic::{Extensions, Request, Response, Status};
 use trace::span_ext::SpanExt;
 use trace::{debug, error, info, Span, SpanContext};
 use tskv::error::TskvResult;
+use tskv::index::cardinality::CardinalityOptions;
 use tskv::reader::query_executor::QueryExecutor;
 use tskv::reader::serialize::TonicRecordBatchEncoder;
 use tskv::reader::{QueryOption, SendableTskvRecordBatchStream};
@@ -58,6 +61,36 @@ impl TskvServiceImpl {
                 Ok(vec![])
             }
 
+            admin_command::Command::ReencodeVnode(req) => {
+                self.kv_inst
+                    .reencode(tenant, &req.db_name, &req.table, req.vnode_ids.clone())
+                    .await
+                    .context(TskvSnafu)?;
+                Ok(vec![])
+            }
+
+            admin_command::Command::BackupVnode(req) => {
+                let snapshot = self
+                    .kv_inst
+                    .backup_vnode(req.vnode_id)
+                    .await
+                    .context(TskvSnafu)?;
+                match snapshot {
+                    Some(snapshot) => bincode::serialize(&snapshot).context(BincodeSerdeSnafu),
+                    None => Ok(vec![]),
+                }
+            }
+
+            admin_command::Command::StageVnodeFile(req) => {
+                self.stage_vnode_file(req).await?;
+                Ok(vec![])
+            }
+
+            admin_command::Command::RestoreVnode(req) => {
+                self.restore_vnode(tenant, req).await?;
+                Ok(vec![])
+            }
+
             admin_command::Command::OpenRaftNode(req) => {
                 self.coord
                     .raft_manager()
@@ -84,6 +117,38 @@ impl TskvServiceImpl {
                 Ok(data)
             }
 
+            admin_command::Command::SeriesCardinality(req) => {
+                let options = CardinalityOptions {
+                    table: req.table.clone(),
+                    group_by: req.group_by.clone(),
+                    exact: req.exact,
+                    tag_keys: req.tag_keys,
+                };
+                let groups = self
+                    .kv_inst
+                    .series_cardinality(tenant, &req.db_name, req.vnode_id, &options)
+                    .await
+                    .context(TskvSnafu)?;
+                bincode::serialize(&groups).context(BincodeSerdeSnafu)
+            }
+
+            admin_command::Command::FetchReplicaVersion(req) => {
+                let version = self
+                    .coord
+                    .raft_manager()
+                    .replica_version(req.replica_id)
+                    .await?;
+                bincode::serialize(&version).context(BincodeSerdeSnafu)
+            }
+
+            admin_command::Command::ArchiveVnodeWal(req) => {
+                self.coord
+                    .raft_manager()
+                    .archive_wal(req.replica_id)
+                    .await?;
+                Ok(vec![])
+            }
+
             admin_command::Command::AddRaftFollower(command) => {
                 self.coord
                     .raft_manager()
@@ -154,6 +219,99 @@ impl TskvServiceImpl {
         }
     }
 
+    /// The directory where the files of a snapshot are written before the vnode is restored.
+    fn restore_dir(&self, vnode_id: VnodeId) -> PathBuf {
+        self.kv_inst
+            .get_storage_options()
+            .path()
+            .join(format!("restore_{vnode_id}"))
+    }
+
+    async fn stage_vnode_file(&self, req: &StageVnodeFileRequest) -> CoordinatorResult<()> {
+        let relative_path = Path::new(&req.relative_path);
+        if !relative_path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return Err(CommonSnafu {
+                msg: format!("invalid snapshot file path '{}'", req.relative_path),
+            }
+            .build());
+        }
+
+        let path = self.restore_dir(req.vnode_id).join(relative_path);
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .context(IOErrorsSnafu)?;
+        }
+        let mut options = tokio::fs::OpenOptions::new();
+        if req.offset == 0 {
+            options.create(true).write(true).truncate(true);
+        } else {
+            options.append(true);
+        }
+        let mut file = options.open(&path).await.context(IOErrorsSnafu)?;
+
+        let length = file.metadata().await.context(IOErrorsSnafu)?.len();
+        if length != req.offset {
+            return Err(CommonSnafu {
+                msg: format!(
+                    "snapshot file '{}' has {} bytes, but the chunk starts at {}",
+                    req.relative_path, length, req.offset
+                ),
+            }
+            .build());
+        }
+        file.write_all(&req.data).await.context(IOErrorsSnafu)?;
+        file.sync_data().await.context(IOErrorsSnafu)?;
+
+        Ok(())
+    }
+
+    async fn restore_vnode(
+        &self,
+        tenant: &str,
+        req: &RestoreVnodeRequest,
+    ) -> CoordinatorResult<()> {
+        // The raft node keeps its own handle of the vnode, which can't see the restored data.
+        let raft_node = self
+            .coord
+            .raft_manager()
+            .multi_raft()
+            .read()
+            .await
+            .get_node(req.replica_id)
+            .context(ReplicatSnafu)?;
+        if raft_node.is_some() {
+            return Err(CommonSnafu {
+                msg: format!(
+                    "can't restore vnode {}, raft group {} is already opened",
+                    req.vnode_id, req.replica_id
+                ),
+            }
+            .build());
+        }
+
+        let snapshot = bincode::deserialize(&req.snapshot).context(BincodeSerdeSnafu)?;
+        let dir = self.restore_dir(req.vnode_id);
+        let result = self
+            .kv_inst
+            .restore_vnode(
+                tenant,
+                &req.db_name,
+                req.vnode_id,
+                snapshot,
+                &dir,
+                req.until,
+            )
+            .await
+            .context(TskvSnafu);
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+
+        result
+    }
+
     fn query_record_batch_exec(
         self,
         args: QueryArgs,
@@ -295,7 +453,7 @@ impl TskvService for TskvServiceImpl {
 
         let (send, recv) = mpsc::channel(1024);
         tokio::spawn(async move {
-            if let Ok(mut file) = tokio::fs::File::open(filename).await {
+            if let Ok(mut file) = tokio::fs::File::open(&filename).await {
                 let mut buffer = vec![0; 8 * 1024];
                 while let Ok(len) = file.read(&mut buffer).await {
                     if len == 0 {
@@ -309,6 +467,32 @@ impl TskvService for TskvServiceImpl {
                         }))
                         .await;
                 }
+            } else if let Some(remote) = opt.tiered.as_ref() {
+                // The file may have been offloaded to the remote file system.
+                let mut stream = match remote.open_stream(&filename).await {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        info!("download file {:?} from remote failed: {e}", filename);
+                        let _ = send.send(Err(tonic::Status::internal(e.to_string()))).await;
+                        return;
+                    }
+                };
+                while let Some(data) = stream.next().await {
+                    let resp = match data {
+                        Ok(data) => Ok(BatchBytesResponse {
+                            code: coordinator::errors::SUCCESS_RESPONSE_CODE,
+                            data: data.to_vec(),
+                        }),
+                        Err(e) => {
+                            info!("download file {:?} from remote failed: {e}", filename);
+                            Err(tonic::Status::internal(e.to_string()))
+                        }
+                    };
+                    let failed = resp.is_err();
+                    if send.send(resp).await.is_err() || failed {
+                        break;
+                    }
+                }
             }
         });
 
@@ -185,7 +185,9 @@ impl ServiceBuilder {
             server.add_service(Box::new(http_service));
         }
 
-        if let Some(flight_sql_service) = self.create_flight_sql_if_enabled(dbms.clone()) {
+        if let Some(flight_sql_service) =
+            self.create_flight_sql_if_enabled(dbms.clone(), coord.clone())
+        {
             server.add_service(Box::new(flight_sql_service));
         }
 
@@ -223,7 +225,9 @@ impl ServiceBuilder {
             server.add_service(Box::new(grpc_service));
         }
 
-        if let Some(flight_sql_service) = self.create_flight_sql_if_enabled(dbms.clone()) {
+        if let Some(flight_sql_service) =
+            self.create_flight_sql_if_enabled(dbms.clone(), coord.clone())
+        {
             server.add_service(Box::new(flight_sql_service));
         }
 
@@ -391,7 +395,11 @@ impl ServiceBuilder {
         Some(TcpService::new(coord, default_tcp_addr))
     }
 
-    fn create_flight_sql_if_enabled(&self, dbms: DBMSRef) -> Option<FlightSqlServiceAdapter> {
+    fn create_flight_sql_if_enabled(
+        &self,
+        dbms: DBMSRef,
+        coord: CoordinatorRef,
+    ) -> Option<FlightSqlServiceAdapter> {
         let default_flight_sql_addr = match self.config.service.flight_rpc_listen_port {
             Some(port) => build_default_address(port),
             None => return None,
@@ -414,6 +422,7 @@ impl ServiceBuilder {
 
         Some(FlightSqlServiceAdapter::new(
             dbms,
+            coord,
             addr,
             tls_config,
             self.config.trace.auto_generate_span,
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use coordinator::service::CoordinatorRef;
+use models::consistency_level::ConsistencyLevel;
 use models::schema::{DEFAULT_CATALOG, DEFAULT_DATABASE};
 use models::utils::now_timestamp_millis;
 use protocol_parser::open_tsdb::parser::Parser;
@@ -80,6 +81,7 @@ impl Service for TcpService {
                                     DEFAULT_DATABASE,
                                     Precision::NS,
                                     lines,
+                                    ConsistencyLevel::Quorum,
                                     None,
                                 )
                                 .await
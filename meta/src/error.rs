@@ -182,6 +182,22 @@ pub enum MetaError {
     #[snafu(display("cannot revoke the privilege {privilege} of role"))]
     #[error_code(code = 56)]
     PrivilegeCannotRevoke { privilege: TenantObjectPrivilege },
+
+    #[snafu(display("The rollup {} already exists", name))]
+    #[error_code(code = 57)]
+    RollupAlreadyExists { name: String },
+
+    #[snafu(display("The rollup {} not found", name))]
+    #[error_code(code = 58)]
+    RollupNotFound { name: String },
+
+    #[snafu(display("The stream {} already exists", name))]
+    #[error_code(code = 59)]
+    StreamAlreadyExists { name: String },
+
+    #[snafu(display("The stream {} not found", name))]
+    #[error_code(code = 60)]
+    StreamNotFound { name: String },
 }
 
 impl MetaError {
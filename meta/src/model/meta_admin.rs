@@ -13,6 +13,7 @@ use models::node_info::NodeStatus;
 use models::oid::{Identifier, Oid, UuidGenerator};
 use models::schema::query_info::QueryInfo;
 use models::schema::resource_info::{ResourceInfo, ResourceStatus};
+use models::schema::stream_info::StreamInfo;
 use models::schema::table_schema::TableSchema;
 use models::schema::tenant::{Tenant, TenantOptions};
 use models::utils::{build_address_with_optional_addr, now_timestamp_secs};
@@ -847,6 +848,29 @@ impl AdminMeta {
         self.client.write::<()>(&req).await
     }
 
+    pub async fn read_streams_by_nodeid(&self, node_id: NodeId) -> MetaResult<Vec<StreamInfo>> {
+        let req = command::ReadCommand::StreamsByNodeid(self.cluster(), node_id);
+
+        self.client.read::<Vec<StreamInfo>>(&req).await
+    }
+
+    /// Updates the status of the streams run by a node in one write.
+    pub async fn update_streams(&self, streams: Vec<StreamInfo>) -> MetaResult<()> {
+        let req = command::WriteCommand::UpdateStreams(self.cluster(), streams);
+
+        self.client.write::<()>(&req).await
+    }
+
+    pub async fn move_streams(
+        &self,
+        source_node_id: NodeId,
+        dest_node_id: NodeId,
+    ) -> MetaResult<()> {
+        let req = command::WriteCommand::MoveStreams(self.cluster(), source_node_id, dest_node_id);
+
+        self.client.write::<()>(&req).await
+    }
+
     pub async fn read_tableschema(
         &self,
         tenant: &str,
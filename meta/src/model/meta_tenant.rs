@@ -15,6 +15,8 @@ use models::oid::{Identifier, Oid};
 use models::schema::database_schema::DatabaseSchema;
 use models::schema::external_table_schema::ExternalTableSchema;
 use models::schema::resource_info::ResourceInfo;
+use models::schema::rollup_info::RollupInfo;
+use models::schema::stream_info::StreamInfo;
 use models::schema::table_schema::TableSchema;
 use models::schema::tenant::Tenant;
 use models::schema::tskv_table_schema::TskvTableSchemaRef;
@@ -369,6 +371,75 @@ impl TenantMeta {
     }
     // tenant role end
 
+    // tenant rollup start
+
+    pub async fn create_rollup(&self, rollup: RollupInfo) -> MetaResult<()> {
+        let req = command::WriteCommand::CreateRollup(self.cluster.clone(), rollup);
+
+        self.client.write::<()>(&req).await
+    }
+
+    pub async fn drop_rollup(&self, db_name: &str, name: &str) -> MetaResult<bool> {
+        let req = command::WriteCommand::DropRollup(
+            self.cluster.clone(),
+            self.tenant_name(),
+            db_name.to_string(),
+            name.to_string(),
+        );
+
+        self.drop_object(&req, |e| matches!(e, MetaError::RollupNotFound { .. }))
+            .await
+    }
+
+    pub async fn rollups(&self) -> MetaResult<Vec<RollupInfo>> {
+        let req = command::ReadCommand::Rollups(self.cluster.clone(), self.tenant_name());
+
+        self.client.read::<Vec<RollupInfo>>(&req).await
+    }
+
+    // tenant rollup end
+
+    // tenant stream start
+
+    pub async fn create_stream(&self, stream: StreamInfo) -> MetaResult<()> {
+        let req = command::WriteCommand::CreateStream(self.cluster.clone(), stream);
+
+        self.client.write::<()>(&req).await
+    }
+
+    pub async fn drop_stream(&self, db_name: &str, name: &str) -> MetaResult<bool> {
+        let req = command::WriteCommand::DropStream(
+            self.cluster.clone(),
+            self.tenant_name(),
+            db_name.to_string(),
+            name.to_string(),
+        );
+
+        self.drop_object(&req, |e| matches!(e, MetaError::StreamNotFound { .. }))
+            .await
+    }
+
+    pub async fn streams(&self) -> MetaResult<Vec<StreamInfo>> {
+        let req = command::ReadCommand::Streams(self.cluster.clone(), self.tenant_name());
+
+        self.client.read::<Vec<StreamInfo>>(&req).await
+    }
+
+    // tenant stream end
+
+    /// Drops an object of the tenant, returns false if it doesn't exist.
+    async fn drop_object(
+        &self,
+        req: &command::WriteCommand,
+        is_not_found: impl Fn(&MetaError) -> bool,
+    ) -> MetaResult<bool> {
+        match self.client.write::<()>(req).await {
+            Ok(_) => Ok(true),
+            Err(e) if is_not_found(&e) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
     async fn write_with_data(&self, req: &command::WriteCommand) -> MetaResult<()> {
         let rsp = self.client.write::<TenantMetaData>(req).await?;
 
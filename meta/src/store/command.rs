@@ -10,6 +10,8 @@ use models::oid::Oid;
 use models::schema::database_schema::DatabaseSchema;
 use models::schema::query_info::QueryInfo;
 use models::schema::resource_info::ResourceInfo;
+use models::schema::rollup_info::RollupInfo;
+use models::schema::stream_info::StreamInfo;
 use models::schema::table_schema::TableSchema;
 use models::schema::tenant::{Tenant, TenantOptions};
 use parking_lot::RwLock;
@@ -153,6 +155,20 @@ pub enum WriteCommand {
 
     // cluster, source_node_id, dest_node_id
     MoveQueryInfo(String, NodeId, NodeId),
+
+    // cluster, rollup_info
+    CreateRollup(String, RollupInfo),
+    // cluster, tenant, db, rollup_name
+    DropRollup(String, String, String, String),
+
+    // cluster, stream_info
+    CreateStream(String, StreamInfo),
+    // cluster, stream_infos
+    UpdateStreams(String, Vec<StreamInfo>),
+    // cluster, tenant, db, stream_name
+    DropStream(String, String, String, String),
+    // cluster, source_node_id, dest_node_id
+    MoveStreams(String, NodeId, NodeId),
 }
 
 /******************* read command *************************/
@@ -198,6 +214,14 @@ pub enum ReadCommand {
 
     // cluster, tenant, db, table
     ReadTableSchema(String, String, String, String),
+
+    // cluster, tenant
+    Rollups(String, String),
+
+    // cluster, tenant
+    Streams(String, String),
+    // cluster, node_id
+    StreamsByNodeid(String, NodeId),
 }
 
 pub const ENTRY_LOG_TYPE_SET: i32 = 1;
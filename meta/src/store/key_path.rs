@@ -7,17 +7,22 @@ use models::oid::Oid;
 // **    /cluster_name/tenants/tenant/members/user_id ->
 // **    /cluster_name/tenants/tenant/limiter ->
 // **    /cluster_name/auto_incr_id -> id
+// **    /cluster_name/node_streams/node_id -> [String] keys of the streams run by the node
 // **    /cluster_name/data_nodes/node_id -> [NodeInfo] 集群、数据节点等信息
 
 // **    /cluster_name/tenant_name/dbs/db_name -> [DatabaseInfo] db相关信息、保留策略等
 // **    /cluster_name/tenant_name/dbs/db_name/buckets/id -> [BucketInfo] bucket相关信息
 // **    /cluster_name/tenant_name/dbs/db_name/schemas/name -> [TskvTableSchema] schema相关信息
+// **    /cluster_name/tenant_name/dbs/db_name/rollups/name -> [RollupInfo] rollup policies
+// **    /cluster_name/tenant_name/dbs/db_name/streams/name -> [StreamInfo] stream queries
 
 pub const DBS: &str = "dbs";
 pub const USERS: &str = "users";
 pub const ROLES: &str = "roles";
 pub const BUCKETS: &str = "buckets";
 pub const SCHEMAS: &str = "schemas";
+pub const ROLLUPS: &str = "rollups";
+pub const STREAMS: &str = "streams";
 pub const TENANTS: &str = "tenants";
 pub const MEMBERS: &str = "members";
 pub const LIMITER: &str = "limiter";
@@ -104,6 +109,32 @@ impl KeyPath {
         )
     }
 
+    pub fn tenant_rollups(cluster: &str, tenant: &str, db: &str) -> String {
+        format!("/{}/tenants/{}/dbs/{}/rollups", cluster, tenant, db)
+    }
+
+    pub fn tenant_rollup_name(cluster: &str, tenant: &str, db: &str, name: &str) -> String {
+        format!(
+            "/{}/tenants/{}/dbs/{}/rollups/{}",
+            cluster, tenant, db, name
+        )
+    }
+
+    pub fn tenant_streams(cluster: &str, tenant: &str, db: &str) -> String {
+        format!("/{}/tenants/{}/dbs/{}/streams", cluster, tenant, db)
+    }
+
+    pub fn tenant_stream_name(cluster: &str, tenant: &str, db: &str, name: &str) -> String {
+        format!(
+            "/{}/tenants/{}/dbs/{}/streams/{}",
+            cluster, tenant, db, name
+        )
+    }
+
+    pub fn node_streams(cluster: &str, node_id: u64) -> String {
+        format!("/{}/node_streams/{}", cluster, node_id)
+    }
+
     pub fn tenants(cluster: &str) -> String {
         format!("/{}/tenants/", cluster)
     }
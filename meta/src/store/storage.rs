@@ -12,6 +12,8 @@ use models::oid::{Identifier, Oid, UuidGenerator};
 use models::schema::database_schema::DatabaseSchema;
 use models::schema::query_info::QueryInfo;
 use models::schema::resource_info::ResourceInfo;
+use models::schema::rollup_info::RollupInfo;
+use models::schema::stream_info::{StreamInfo, StreamStatus};
 use models::schema::table_schema::TableSchema;
 use models::schema::tenant::{Tenant, TenantOptions};
 use replication::errors::{HeedSnafu, MsgInvalidSnafu, ReplicationResult, SnapshotErrSnafu};
@@ -469,7 +471,66 @@ impl StateMachine {
             ReadCommand::ReadTableSchema(cluster, tenant, db_name, table_name) => {
                 response_encode(self.process_read_table(cluster, tenant, db_name, table_name))
             }
+            ReadCommand::Rollups(cluster, tenant) => {
+                response_encode(self.process_read_rollups(cluster, tenant))
+            }
+            ReadCommand::Streams(cluster, tenant) => {
+                response_encode(self.process_read_streams(cluster, tenant))
+            }
+            ReadCommand::StreamsByNodeid(cluster, node_id) => {
+                response_encode(self.process_read_streams_by_nodeid(cluster, *node_id))
+            }
+        }
+    }
+
+    pub fn process_read_rollups(&self, cluster: &str, tenant: &str) -> MetaResult<Vec<RollupInfo>> {
+        self.read_db_objects(cluster, tenant, KeyPath::tenant_rollups)
+    }
+
+    pub fn process_read_streams(&self, cluster: &str, tenant: &str) -> MetaResult<Vec<StreamInfo>> {
+        self.read_db_objects(cluster, tenant, KeyPath::tenant_streams)
+    }
+
+    /// Reads the streams run by the node through the keys indexed by the node.
+    pub fn process_read_streams_by_nodeid(
+        &self,
+        cluster: &str,
+        node_id: NodeId,
+    ) -> MetaResult<Vec<StreamInfo>> {
+        let mut streams = vec![];
+        for key in self.node_stream_keys(cluster, node_id)? {
+            if let Some(stream) = self.get_struct::<StreamInfo>(&key)? {
+                if stream.node_id == node_id {
+                    streams.push(stream);
+                }
+            }
         }
+
+        Ok(streams)
+    }
+
+    /// Reads the objects of all databases of the tenant, e.g. rollups and streams.
+    fn read_db_objects<T>(
+        &self,
+        cluster: &str,
+        tenant: &str,
+        objects_path: fn(&str, &str, &str) -> String,
+    ) -> MetaResult<Vec<T>>
+    where
+        for<'a> T: Deserialize<'a>,
+    {
+        let mut objects = vec![];
+        let dbs_path = KeyPath::tenant_dbs(cluster, tenant);
+        for db_path in self.children_fullpath(&dbs_path)? {
+            let db_name = match db_path.rsplit('/').next() {
+                Some(name) => name,
+                None => continue,
+            };
+            let path = objects_path(cluster, tenant, db_name);
+            objects.extend(self.children_data::<T>(&path)?.into_values());
+        }
+
+        Ok(objects)
     }
 
     pub fn process_read_queries(
@@ -804,7 +865,208 @@ impl StateMachine {
             WriteCommand::MoveQueryInfo(cluster, source_node_id, dest_node_id) => response_encode(
                 self.process_move_queryinfo(cluster, *source_node_id, *dest_node_id),
             ),
+            WriteCommand::CreateRollup(cluster, rollup) => {
+                response_encode(self.process_create_rollup(cluster, rollup))
+            }
+            WriteCommand::DropRollup(cluster, tenant, db_name, name) => {
+                response_encode(self.process_drop_rollup(cluster, tenant, db_name, name))
+            }
+            WriteCommand::CreateStream(cluster, stream) => {
+                response_encode(self.process_create_stream(cluster, stream))
+            }
+            WriteCommand::UpdateStreams(cluster, streams) => {
+                response_encode(self.process_update_streams(cluster, streams))
+            }
+            WriteCommand::DropStream(cluster, tenant, db_name, name) => {
+                response_encode(self.process_drop_stream(cluster, tenant, db_name, name))
+            }
+            WriteCommand::MoveStreams(cluster, source_node_id, dest_node_id) => {
+                response_encode(self.process_move_streams(cluster, *source_node_id, *dest_node_id))
+            }
+        }
+    }
+
+    fn process_create_rollup(&self, cluster: &str, rollup: &RollupInfo) -> MetaResult<()> {
+        let key = KeyPath::tenant_rollup_name(
+            cluster,
+            &rollup.tenant_name,
+            &rollup.database_name,
+            &rollup.name,
+        );
+        self.create_db_object(
+            cluster,
+            &rollup.tenant_name,
+            &rollup.database_name,
+            &key,
+            rollup,
+            || MetaError::RollupAlreadyExists {
+                name: rollup.name.clone(),
+            },
+        )
+    }
+
+    fn process_drop_rollup(
+        &self,
+        cluster: &str,
+        tenant: &str,
+        db_name: &str,
+        name: &str,
+    ) -> MetaResult<()> {
+        let key = KeyPath::tenant_rollup_name(cluster, tenant, db_name, name);
+        if !self.contains_key(&key)? {
+            return Err(MetaError::RollupNotFound {
+                name: name.to_string(),
+            });
+        }
+
+        self.remove(&key)
+    }
+
+    fn process_create_stream(&self, cluster: &str, stream: &StreamInfo) -> MetaResult<()> {
+        let key = KeyPath::tenant_stream_name(
+            cluster,
+            &stream.tenant_name,
+            &stream.database_name,
+            &stream.name,
+        );
+        self.create_db_object(
+            cluster,
+            &stream.tenant_name,
+            &stream.database_name,
+            &key,
+            stream,
+            || MetaError::StreamAlreadyExists {
+                name: stream.name.clone(),
+            },
+        )?;
+
+        self.index_node_stream(cluster, stream.node_id, &key)
+    }
+
+    /// Updates the status of the streams in one write, the dropped streams are skipped.
+    fn process_update_streams(&self, cluster: &str, streams: &[StreamInfo]) -> MetaResult<()> {
+        for stream in streams {
+            let key = KeyPath::tenant_stream_name(
+                cluster,
+                &stream.tenant_name,
+                &stream.database_name,
+                &stream.name,
+            );
+            // The stream may be dropped while it's running, don't create it again.
+            let current = match self.get_struct::<StreamInfo>(&key)? {
+                Some(current) => current,
+                None => continue,
+            };
+            // The stream may be moved to another node by the meta.
+            if current.node_id != stream.node_id {
+                continue;
+            }
+
+            self.insert(&key, &value_encode(stream)?)?;
+        }
+
+        Ok(())
+    }
+
+    fn process_drop_stream(
+        &self,
+        cluster: &str,
+        tenant: &str,
+        db_name: &str,
+        name: &str,
+    ) -> MetaResult<()> {
+        let key = KeyPath::tenant_stream_name(cluster, tenant, db_name, name);
+        let stream = match self.get_struct::<StreamInfo>(&key)? {
+            Some(stream) => stream,
+            None => {
+                return Err(MetaError::StreamNotFound {
+                    name: name.to_string(),
+                })
+            }
+        };
+
+        self.remove(&key)?;
+        self.unindex_node_stream(cluster, stream.node_id, &key)
+    }
+
+    fn process_move_streams(
+        &self,
+        cluster: &str,
+        source_node_id: NodeId,
+        dest_node_id: NodeId,
+    ) -> MetaResult<()> {
+        for key in self.node_stream_keys(cluster, source_node_id)? {
+            let mut stream = match self.get_struct::<StreamInfo>(&key)? {
+                Some(stream) if stream.node_id == source_node_id => stream,
+                _ => continue,
+            };
+            stream.node_id = dest_node_id;
+            stream.status = StreamStatus::Starting;
+            self.insert(&key, &value_encode(&stream)?)?;
+            self.index_node_stream(cluster, dest_node_id, &key)?;
+        }
+
+        self.remove(&KeyPath::node_streams(cluster, source_node_id))
+    }
+
+    /// Creates an object of the database, e.g. a rollup or a stream.
+    fn create_db_object<T: Serialize>(
+        &self,
+        cluster: &str,
+        tenant: &str,
+        db_name: &str,
+        key: &str,
+        object: &T,
+        already_exists: impl FnOnce() -> MetaError,
+    ) -> MetaResult<()> {
+        let db_key = KeyPath::tenant_db_name(cluster, tenant, db_name);
+        if !self.contains_key(&db_key)? {
+            return Err(MetaError::DatabaseNotFound {
+                database: db_name.to_string(),
+            });
+        }
+
+        if self.contains_key(key)? {
+            return Err(already_exists());
+        }
+
+        self.insert(key, &value_encode(object)?)
+    }
+
+    /// The keys of the streams run by the node.
+    fn node_stream_keys(&self, cluster: &str, node_id: NodeId) -> MetaResult<Vec<String>> {
+        let keys = self
+            .get_struct::<Vec<String>>(&KeyPath::node_streams(cluster, node_id))?
+            .unwrap_or_default();
+
+        Ok(keys)
+    }
+
+    fn index_node_stream(&self, cluster: &str, node_id: NodeId, key: &str) -> MetaResult<()> {
+        let mut keys = self.node_stream_keys(cluster, node_id)?;
+        if keys.iter().any(|k| k == key) {
+            return Ok(());
+        }
+        keys.push(key.to_string());
+
+        self.insert(
+            &KeyPath::node_streams(cluster, node_id),
+            &value_encode(&keys)?,
+        )
+    }
+
+    fn unindex_node_stream(&self, cluster: &str, node_id: NodeId, key: &str) -> MetaResult<()> {
+        let mut keys = self.node_stream_keys(cluster, node_id)?;
+        let len = keys.len();
+        keys.retain(|k| k != key);
+        if keys.len() == len {
+            return Ok(());
         }
+
+        self.insert(
+            &KeyPath::node_streams(cluster, node_id),
+            &value_encode(&keys)?,
+        )
     }
 
     fn process_move_queryinfo(
@@ -1011,6 +1273,18 @@ impl StateMachine {
             let _ = self.remove(it);
         }
 
+        let rollups_path = KeyPath::tenant_rollups(cluster, tenant, db_name);
+        for it in self.children_fullpath(&rollups_path)?.iter() {
+            let _ = self.remove(it);
+        }
+
+        let streams_path = KeyPath::tenant_streams(cluster, tenant, db_name);
+        for (name, stream) in self.children_data::<StreamInfo>(&streams_path)? {
+            let key = KeyPath::tenant_stream_name(cluster, tenant, db_name, &name);
+            let _ = self.remove(&key);
+            self.unindex_node_stream(cluster, stream.node_id, &key)?;
+        }
+
         Ok(())
     }
 
@@ -1708,8 +1982,96 @@ mod test {
     use std::collections::BTreeMap;
     use std::println;
 
+    use models::auth::user::{User, UserDesc, UserOptions};
+    use models::schema::stream_info::{StreamInfo, StreamStatus};
     use serde::{Deserialize, Serialize};
 
+    use super::StateMachine;
+    use crate::store::key_path::KeyPath;
+
+    fn stream(name: &str, node_id: u64) -> StreamInfo {
+        let desc = UserDesc::new(0, "root".to_string(), UserOptions::default(), true);
+        StreamInfo {
+            tenant_id: 0,
+            tenant_name: "cnosdb".to_string(),
+            database_name: "public".to_string(),
+            name: name.to_string(),
+            trigger: None,
+            query: String::new(),
+            user: User::new(desc, Default::default(), None),
+            node_id,
+            query_id: 1.into(),
+            status: StreamStatus::Starting,
+            watermark_ns: None,
+            processed_count: 0,
+            error_count: 0,
+            error: None,
+        }
+    }
+
+    fn names(mut streams: Vec<StreamInfo>) -> Vec<String> {
+        streams.sort_by(|a, b| a.name.cmp(&b.name));
+        streams.into_iter().map(|s| s.name).collect()
+    }
+
+    #[test]
+    fn test_node_streams() {
+        let path = "/tmp/test/meta/test_node_streams";
+        let _ = std::fs::remove_dir_all(path);
+        let storage = StateMachine::open(path, 1024 * 1024 * 1024).unwrap();
+        let cluster = "cluster_xxx";
+        storage
+            .insert(&KeyPath::tenant_db_name(cluster, "cnosdb", "public"), "{}")
+            .unwrap();
+
+        storage
+            .process_create_stream(cluster, &stream("s1", 1))
+            .unwrap();
+        storage
+            .process_create_stream(cluster, &stream("s2", 1))
+            .unwrap();
+        storage
+            .process_create_stream(cluster, &stream("s3", 2))
+            .unwrap();
+        let streams = storage.process_read_streams_by_nodeid(cluster, 1).unwrap();
+        assert_eq!(names(streams), vec!["s1", "s2"]);
+
+        let mut s1 = stream("s1", 1);
+        s1.status = StreamStatus::Running;
+        s1.watermark_ns = Some(100);
+        storage
+            .process_update_streams(cluster, &[s1, stream("dropped", 1)])
+            .unwrap();
+        let streams = storage.process_read_streams(cluster, "cnosdb").unwrap();
+        assert_eq!(names(streams.clone()), vec!["s1", "s2", "s3"]);
+        let s1 = streams.iter().find(|s| s.name == "s1").unwrap();
+        assert_eq!(s1.watermark_ns, Some(100));
+
+        storage
+            .process_drop_stream(cluster, "cnosdb", "public", "s2")
+            .unwrap();
+        storage.process_move_streams(cluster, 1, 2).unwrap();
+        assert!(storage
+            .process_read_streams_by_nodeid(cluster, 1)
+            .unwrap()
+            .is_empty());
+        let streams = storage.process_read_streams_by_nodeid(cluster, 2).unwrap();
+        assert_eq!(names(streams.clone()), vec!["s1", "s3"]);
+        assert!(streams.iter().all(|s| s.status == StreamStatus::Starting));
+
+        storage
+            .process_drop_db(cluster, "cnosdb", "public")
+            .unwrap();
+        assert!(storage
+            .process_read_streams_by_nodeid(cluster, 2)
+            .unwrap()
+            .is_empty());
+        assert!(storage
+            .get(&KeyPath::node_streams(cluster, 2))
+            .unwrap()
+            .map_or(true, |keys| keys == "[]"));
+    }
+
     #[test]
     fn test_btree_map() {
         let mut map = BTreeMap::new();
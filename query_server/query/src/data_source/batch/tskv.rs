@@ -14,7 +14,8 @@ use datafusion::execution::context::SessionState;
 use datafusion::logical_expr::expr::AggregateFunction;
 use datafusion::logical_expr::logical_plan::AggWithGrouping;
 use datafusion::logical_expr::{
-    aggregate_function, Expr, TableProviderAggregationPushDown, TableProviderFilterPushDown,
+    aggregate_function, BinaryExpr, Expr, Operator, TableProviderAggregationPushDown,
+    TableProviderFilterPushDown,
 };
 use datafusion::optimizer::utils::{conjunction, split_conjunction};
 use datafusion::physical_expr::PhysicalExpr;
@@ -56,6 +57,7 @@ impl ClusterTable {
         ctx: &SessionState,
         projection: Option<&Vec<usize>>,
         predicate: PredicateRef,
+        filter_digest: String,
     ) -> Result<Arc<dyn ExecutionPlan>> {
         let proj_schema = self.project_schema(projection)?;
 
@@ -79,6 +81,7 @@ impl ClusterTable {
             predicate,
             self.coord.clone(),
             splits,
+            filter_digest,
         )))
     }
 
@@ -330,6 +333,7 @@ impl TableProvider for ClusterTable {
         };
 
         let filters = rewrite_filters(filters, df_schema.clone())?;
+        let filter_digest = filter_digest(filters.as_ref(), &self.schema.time_column().name);
         // Generate physical expressions using projected schema
         let filter = Arc::new(
             Predicate::push_down_filter(filters, &df_schema, &arrow_schema, limit)
@@ -344,7 +348,7 @@ impl TableProvider for ClusterTable {
         }
 
         return self
-            .create_table_scan_physical_plan(ctx, projection, filter)
+            .create_table_scan_physical_plan(ctx, projection, filter, filter_digest)
             .await;
     }
 
@@ -508,3 +512,58 @@ pub fn valid_project(
 
     Ok(())
 }
+
+/// Describes the filter of a table scan except the comparisons between the time column
+/// and timestamps, which are covered by the time ranges of the splits.
+fn filter_digest(filter: Option<&Expr>, time_column: &str) -> String {
+    filter
+        .map(split_conjunction)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|expr| !is_time_range_filter(expr, time_column))
+        .map(|expr| expr.to_string())
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+fn is_time_range_filter(expr: &Expr, time_column: &str) -> bool {
+    let is_time_column = |e: &Expr| matches!(e, Expr::Column(c) if c.name == time_column);
+    let is_timestamp = |e: &Expr| match e {
+        Expr::Literal(v) => !v.is_null() && matches!(v.get_datatype(), DataType::Timestamp(_, _)),
+        _ => false,
+    };
+
+    match expr {
+        Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
+            matches!(
+                op,
+                Operator::Eq | Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq
+            ) && ((is_time_column(left) && is_timestamp(right))
+                || (is_timestamp(left) && is_time_column(right)))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::logical_expr::{col, lit};
+    use datafusion::scalar::ScalarValue;
+
+    use super::filter_digest;
+
+    #[test]
+    fn test_filter_digest() {
+        let ts = |v: i64| lit(ScalarValue::TimestampNanosecond(Some(v), None));
+        let filter = col("time")
+            .gt_eq(ts(1))
+            .and(col("host").eq(lit("a")))
+            .and(ts(100).gt(col("time")));
+        assert_eq!(filter_digest(Some(&filter), "time"), "host = Utf8(\"a\")");
+
+        // The time range of a complex expression can't be covered exactly.
+        let filter = col("time").gt(ts(1)).or(col("host").eq(lit("a")));
+        assert_eq!(filter_digest(Some(&filter), "time"), filter.to_string());
+        assert_eq!(filter_digest(None, "time"), "");
+    }
+}
@@ -18,6 +18,7 @@ use crate::extension::physical::plan_node::update_tag::UpdateTagExec;
 use crate::extension::DropEmptyRecordBatchStream;
 
 pub mod batch;
+pub mod result_cache;
 pub mod sink;
 pub mod split;
 pub mod stream;
@@ -0,0 +1,287 @@
+use std::collections::{BTreeMap, HashMap};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use coordinator::service::CoordinatorRef;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::{DataFusionError, Result as DFResult};
+use datafusion::physical_plan::{RecordBatchStream, SendableRecordBatchStream};
+use futures::{Stream, StreamExt};
+use models::meta_data::{ReplicationSet, VnodeId};
+use parking_lot::Mutex;
+
+pub type ResultCacheRef = Arc<ResultCache>;
+
+/// The data versions of the vnodes that a result may be read from, sorted by the vnode id.
+pub type DataVersions = Vec<(VnodeId, u64)>;
+
+/// Caches the record batches of query results and table scans, limited by their memory size.
+///
+/// An entry is only returned if the data versions of the vnodes it may be read
+/// from are not changed, otherwise the entry is removed. The least recently used
+/// entries are evicted when the cache is full.
+pub struct ResultCache {
+    coord: CoordinatorRef,
+    max_size: usize,
+    inner: Mutex<ResultCacheInner>,
+}
+
+#[derive(Debug, Default)]
+struct ResultCacheInner {
+    entries: HashMap<String, CacheEntry>,
+    /// Last access tick -> key of the entry.
+    lru: BTreeMap<u64, String>,
+    size: usize,
+    tick: u64,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    versions: DataVersions,
+    batches: Vec<RecordBatch>,
+    size: usize,
+    last_access: u64,
+}
+
+impl ResultCache {
+    pub fn new(coord: CoordinatorRef, max_size: usize) -> Self {
+        Self {
+            coord,
+            max_size,
+            inner: Mutex::new(ResultCacheInner::default()),
+        }
+    }
+
+    /// Get the current data versions of the replication sets to be read.
+    pub async fn versions(
+        &self,
+        tenant: &str,
+        replica_sets: &[ReplicationSet],
+    ) -> DFResult<DataVersions> {
+        let mut versions = self
+            .coord
+            .replica_versions(tenant, replica_sets)
+            .await
+            .map_err(|err| DataFusionError::External(Box::new(err)))?
+            .into_iter()
+            .collect::<Vec<_>>();
+        versions.sort_unstable();
+
+        Ok(versions)
+    }
+
+    pub fn record_hit(&self, tenant: &str, db: &str) {
+        self.coord
+            .metrics()
+            .query_result_cache_hits(tenant, db)
+            .inc_one();
+    }
+
+    pub fn record_miss(&self, tenant: &str, db: &str) {
+        self.coord
+            .metrics()
+            .query_result_cache_misses(tenant, db)
+            .inc_one();
+    }
+
+    /// The maximum size of an entry, so that a large result can't evict the whole cache.
+    pub fn max_entry_size(&self) -> usize {
+        self.max_size / 4
+    }
+
+    pub fn size(&self) -> usize {
+        self.inner.lock().size
+    }
+
+    pub fn get(&self, key: &str, versions: &[(VnodeId, u64)]) -> Option<Vec<RecordBatch>> {
+        let mut inner = self.inner.lock();
+        let entry = inner.entries.get(key)?;
+        if entry.versions != versions {
+            // The data is changed since the result is cached.
+            inner.remove(key);
+            return None;
+        }
+
+        inner.tick += 1;
+        let tick = inner.tick;
+        let entry = inner.entries.get_mut(key)?;
+        let last_access = std::mem::replace(&mut entry.last_access, tick);
+        let batches = entry.batches.clone();
+        inner.lru.remove(&last_access);
+        inner.lru.insert(tick, key.to_string());
+
+        Some(batches)
+    }
+
+    pub fn insert(&self, key: String, versions: DataVersions, batches: Vec<RecordBatch>) {
+        let size = batches_size(&batches);
+        if size > self.max_entry_size() {
+            return;
+        }
+
+        let mut inner = self.inner.lock();
+        inner.remove(&key);
+        while inner.size + size > self.max_size {
+            let oldest = match inner.lru.values().next() {
+                Some(key) => key.clone(),
+                None => break,
+            };
+            inner.remove(&oldest);
+        }
+
+        inner.tick += 1;
+        let tick = inner.tick;
+        inner.size += size;
+        inner.lru.insert(tick, key.clone());
+        inner.entries.insert(
+            key,
+            CacheEntry {
+                versions,
+                batches,
+                size,
+                last_access: tick,
+            },
+        );
+    }
+}
+
+impl ResultCacheInner {
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.lru.remove(&entry.last_access);
+            self.size -= entry.size;
+        }
+    }
+}
+
+fn batches_size(batches: &[RecordBatch]) -> usize {
+    batches.iter().map(|b| b.get_array_memory_size()).sum()
+}
+
+/// Passes through the batches of the input stream and puts them into the cache
+/// once the stream is finished, unless they are too large to be cached.
+pub struct CachingStream {
+    input: SendableRecordBatchStream,
+    cache: ResultCacheRef,
+    key: String,
+    versions: DataVersions,
+    /// `None` if the batches are too large to be cached or the input fails.
+    batches: Option<Vec<RecordBatch>>,
+    size: usize,
+}
+
+impl CachingStream {
+    pub fn new(
+        input: SendableRecordBatchStream,
+        cache: ResultCacheRef,
+        key: String,
+        versions: DataVersions,
+    ) -> Self {
+        Self {
+            input,
+            cache,
+            key,
+            versions,
+            batches: Some(vec![]),
+            size: 0,
+        }
+    }
+}
+
+impl Stream for CachingStream {
+    type Item = DFResult<RecordBatch>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let poll = this.input.poll_next_unpin(cx);
+        match &poll {
+            Poll::Ready(Some(Ok(batch))) => {
+                if let Some(batches) = this.batches.as_mut() {
+                    this.size += batch.get_array_memory_size();
+                    if this.size > this.cache.max_entry_size() {
+                        this.batches = None;
+                    } else {
+                        batches.push(batch.clone());
+                    }
+                }
+            }
+            Poll::Ready(Some(Err(_))) => this.batches = None,
+            Poll::Ready(None) => {
+                if let Some(batches) = this.batches.take() {
+                    this.cache.insert(
+                        std::mem::take(&mut this.key),
+                        std::mem::take(&mut this.versions),
+                        batches,
+                    );
+                }
+            }
+            Poll::Pending => {}
+        }
+
+        poll
+    }
+}
+
+impl RecordBatchStream for CachingStream {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::Int64Array;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::arrow::record_batch::RecordBatch;
+
+    use coordinator::service_mock::MockCoordinator;
+
+    use super::ResultCache;
+
+    fn batch(len: usize) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Int64, false)]));
+        let array = Int64Array::from((0..len as i64).collect::<Vec<_>>());
+        RecordBatch::try_new(schema, vec![Arc::new(array)]).unwrap()
+    }
+
+    #[test]
+    fn test_result_cache_versions() {
+        let cache = ResultCache::new(Arc::new(MockCoordinator::default()), 1024 * 1024);
+        cache.insert("q".to_string(), vec![(1, 10), (2, 20)], vec![batch(10)]);
+
+        let batches = cache.get("q", &[(1, 10), (2, 20)]).unwrap();
+        assert_eq!(batches[0].num_rows(), 10);
+
+        // Written to the replication set 2.
+        assert!(cache.get("q", &[(1, 10), (2, 21)]).is_none());
+        assert!(cache.get("q", &[(1, 10), (2, 20)]).is_none());
+        assert_eq!(cache.size(), 0);
+    }
+
+    #[test]
+    fn test_result_cache_eviction() {
+        let entry_size = batch(1000).get_array_memory_size();
+        let cache = ResultCache::new(Arc::new(MockCoordinator::default()), entry_size * 4);
+
+        cache.insert("a".to_string(), vec![], vec![batch(1000)]);
+        cache.insert("b".to_string(), vec![], vec![batch(1000)]);
+        cache.insert("c".to_string(), vec![], vec![batch(1000)]);
+        assert!(cache.get("a", &[]).is_some());
+
+        // "b" is the least recently used one.
+        cache.insert("d".to_string(), vec![], vec![batch(1000)]);
+        cache.insert("e".to_string(), vec![], vec![batch(1000)]);
+        assert!(cache.get("b", &[]).is_none());
+        assert!(cache.get("a", &[]).is_some());
+        assert!(cache.get("e", &[]).is_some());
+        assert!(cache.size() <= entry_size * 4);
+
+        // Too large to be cached.
+        cache.insert("f".to_string(), vec![], vec![batch(2000)]);
+        assert!(cache.get("f", &[]).is_none());
+    }
+}
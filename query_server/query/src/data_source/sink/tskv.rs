@@ -6,8 +6,10 @@ use datafusion::arrow::datatypes::SchemaRef;
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::execution::context::TaskContext;
 use datafusion::physical_plan::metrics::{self, Count, ExecutionPlanMetricsSet, MetricBuilder};
+use models::consistency_level::ConsistencyLevel;
 use models::schema::tskv_table_schema::TskvTableSchemaRef;
 use snafu::ResultExt;
+use spi::query::transaction::TransactionWriteBuffer;
 use spi::{CoordinatorSnafu, MetaSnafu, QueryResult};
 use trace::span_ext::SpanExt;
 use trace::{Span, SpanContext};
@@ -18,6 +20,8 @@ pub struct TskvRecordBatchSink {
     coord: CoordinatorRef,
     partition: usize,
     schema: TskvTableSchemaRef,
+    consistency: ConsistencyLevel,
+    transaction: Option<Arc<TransactionWriteBuffer>>,
 
     metrics: TskvSinkMetrics,
     span: Span,
@@ -40,6 +44,14 @@ impl RecordBatchSink for TskvRecordBatchSink {
 
         let rows_writed = record_batch.num_rows();
 
+        if let Some(transaction) = &self.transaction {
+            // Written to tskv when the transaction is committed
+            span.add_property(|| ("buffered_rows", rows_writed.to_string()));
+            transaction.append(self.schema.clone(), record_batch)?;
+            self.metrics.record_output_batches(1);
+            return Ok(SinkMetadata::new(rows_writed, 0));
+        }
+
         let timer = self.metrics.elapsed_record_batch_write().timer();
         let record_batch_size = record_batch.get_array_memory_size() as u64;
 
@@ -75,6 +87,7 @@ impl RecordBatchSink for TskvRecordBatchSink {
                 self.schema.clone(),
                 record_batch,
                 *db_precision,
+                self.consistency,
                 span.context().as_ref(),
             )
             .await
@@ -124,6 +137,14 @@ impl RecordBatchSinkProvider for TskvRecordBatchSinkProvider {
         partition: usize,
     ) -> Box<dyn RecordBatchSink> {
         let parent_span_ctx = context.session_config().get_extension::<SpanContext>();
+        let consistency = context
+            .session_config()
+            .get_extension::<ConsistencyLevel>()
+            .map(|e| *e)
+            .unwrap_or_default();
+        let transaction = context
+            .session_config()
+            .get_extension::<TransactionWriteBuffer>();
         let span = Span::from_context(
             format!("TskvRecordBatchSink ({partition})"),
             parent_span_ctx.as_deref(),
@@ -133,6 +154,8 @@ impl RecordBatchSinkProvider for TskvRecordBatchSinkProvider {
             coord: self.coord.clone(),
             partition,
             schema: self.schema.clone(),
+            consistency,
+            transaction,
             metrics: TskvSinkMetrics::new(metrics, partition),
             span,
         })
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use coordinator::service::CoordinatorRef;
 use datafusion::execution::context::SessionState;
 use datafusion::sql::TableReference;
+use models::consistency_level::ConsistencyLevel;
 use models::object_reference::Resolve;
 use models::predicate::PlacedSplit;
 use snafu::ResultExt;
@@ -33,7 +34,7 @@ impl SplitManager {
 
     pub async fn splits(
         &self,
-        _ctx: &SessionState,
+        ctx: &SessionState,
         table_layout: TableLayoutHandle,
     ) -> QueryResult<Vec<PlacedSplit>> {
         let TableLayoutHandle {
@@ -48,6 +49,11 @@ impl SplitManager {
         );
 
         let limit = predicate.limit();
+        let consistency = ctx
+            .config()
+            .get_extension::<ConsistencyLevel>()
+            .map(|e| *e)
+            .unwrap_or_default();
 
         let resolved_predicate = predicate
             .resolve(&table)
@@ -55,7 +61,7 @@ impl SplitManager {
 
         let shards = self
             .coord
-            .table_vnodes(&table_name, resolved_predicate.clone())
+            .table_vnodes(&table_name, resolved_predicate.clone(), consistency)
             .await
             .context(CoordinatorSnafu)?;
 
@@ -5,6 +5,7 @@ use spi::QueryError;
 
 use crate::utils::duration::parse_duration;
 
+pub mod obj_store;
 pub mod tskv;
 
 // Table option keys
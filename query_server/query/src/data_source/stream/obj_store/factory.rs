@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use datafusion::logical_expr::type_coercion::is_timestamp;
+use meta::model::MetaClientRef;
+use models::schema::stream_table_schema::StreamTable;
+use object_store::local::LocalFileSystem;
+use spi::query::datasource::stream::checker::SchemaChecker;
+use spi::query::datasource::stream::{StreamProviderFactory, StreamProviderRef};
+use spi::QueryError;
+
+use super::provider::ObjectStoreStreamProvider;
+use super::{parse_stream_options, ObjectStoreStreamOptions, LOCATION_OPTION};
+use crate::data_source::stream::EVENT_TIME_COLUMN_OPTION;
+use crate::sql::planner::{build_file_format, build_object_store};
+
+pub const OBJECT_STORE_STREAM_PROVIDER: &str = "object_store";
+
+#[derive(Default)]
+pub struct ObjectStoreStreamProviderFactory {}
+
+impl SchemaChecker<StreamTable> for ObjectStoreStreamProviderFactory {
+    fn check(&self, client: &MetaClientRef, table: &StreamTable) -> Result<(), QueryError> {
+        if table.stream_type() != OBJECT_STORE_STREAM_PROVIDER {
+            return Err(QueryError::Internal { reason: format!("The {OBJECT_STORE_STREAM_PROVIDER} stream data source cannot handle the {} stream table", table.stream_type()) });
+        }
+
+        let table_name = table.name();
+        let schema = table.schema();
+        // The schema of the files can't be inferred before they arrive.
+        if schema.fields().is_empty() {
+            return Err(QueryError::InvalidTableOption {
+                option_name: LOCATION_OPTION.to_string(),
+                table_name: table_name.to_string(),
+                reason: "The columns of the files must be specified.".to_string(),
+            });
+        }
+
+        // check 'event_time_column'
+        let field = schema.field_with_name(&table.watermark().column)?;
+        if !is_timestamp(field.data_type()) {
+            return Err(QueryError::InvalidTableOption {
+                option_name: EVENT_TIME_COLUMN_OPTION.to_string(),
+                table_name: table_name.to_string(),
+                reason: format!(
+                    "The data type of column '{}' is not timestamp.",
+                    table.watermark().column
+                ),
+            });
+        }
+
+        // check the location, the file format and the connection options
+        let _ = self.create(client.clone(), table)?;
+
+        Ok(())
+    }
+}
+
+impl StreamProviderFactory for ObjectStoreStreamProviderFactory {
+    fn create(
+        &self,
+        _meta: MetaClientRef,
+        table: &StreamTable,
+    ) -> Result<StreamProviderRef, QueryError> {
+        let ObjectStoreStreamOptions {
+            location,
+            start_from,
+            file_format,
+            connection_options,
+        } = parse_stream_options(table.name(), table.extra_options())?;
+
+        let url: &url::Url = location.as_ref();
+        let object_store = build_object_store(url.scheme(), url.host_str(), connection_options)?
+            .unwrap_or_else(|| Arc::new(LocalFileSystem::new()));
+        let file_extension = file_format
+            .file_type
+            .get_ext_with_compression(file_format.file_compression_type.to_owned())?;
+        let file_format = build_file_format(file_format)?;
+
+        Ok(Arc::new(ObjectStoreStreamProvider::new(
+            format!("{}.{}.{}", table.tenant(), table.db(), table.name()),
+            table.watermark().clone(),
+            table.schema(),
+            location,
+            object_store,
+            file_format,
+            file_extension,
+            start_from,
+        )))
+    }
+}
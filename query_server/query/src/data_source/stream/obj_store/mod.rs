@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use datafusion::datasource::listing::ListingTableUrl;
+use datafusion::sql::sqlparser::ast::{Ident, SqlOption, Value};
+use spi::query::logical_planner::{FileFormatOptions, FileFormatOptionsBuilder};
+use spi::QueryError;
+
+use super::{EVENT_TIME_COLUMN_OPTION, WATERMARK_DELAY_OPTION};
+
+pub mod factory;
+pub mod provider;
+
+const LOCATION_OPTION: &str = "location";
+const START_FROM_OPTION: &str = "start_from";
+const FILE_FORMAT_OPTIONS: [&str; 4] =
+    ["type", "delimiter", "with_header", "file_compression_type"];
+
+/// Which files under the location are ingested when the stream starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartFrom {
+    /// All the files that exist.
+    Earliest,
+    /// Only the files that arrive after the first listing.
+    Latest,
+}
+
+pub struct ObjectStoreStreamOptions {
+    pub location: ListingTableUrl,
+    pub start_from: StartFrom,
+    pub file_format: FileFormatOptions,
+    pub connection_options: Vec<SqlOption>,
+}
+
+/// Split the options of the stream table into the location, the file format options
+/// (the same as `FILE_FORMAT` of `COPY INTO`) and the connection options of the object store.
+pub fn parse_stream_options(
+    table: &str,
+    options: &HashMap<String, String>,
+) -> Result<ObjectStoreStreamOptions, QueryError> {
+    let location = options
+        .get(LOCATION_OPTION)
+        .ok_or_else(|| QueryError::MissingTableOptions {
+            option_name: LOCATION_OPTION.into(),
+            table_name: table.into(),
+        })?;
+    let location =
+        ListingTableUrl::parse(location).map_err(|e| QueryError::InvalidTableOption {
+            option_name: LOCATION_OPTION.into(),
+            table_name: table.into(),
+            reason: e.to_string(),
+        })?;
+    if !location.as_str().ends_with('/') {
+        return Err(QueryError::InvalidTableOption {
+            option_name: LOCATION_OPTION.into(),
+            table_name: table.into(),
+            reason: "The location must be a directory ending with '/'.".to_string(),
+        });
+    }
+
+    let start_from = match options.get(START_FROM_OPTION).map(|e| e.as_str()) {
+        None | Some("earliest") => StartFrom::Earliest,
+        Some("latest") => StartFrom::Latest,
+        Some(other) => {
+            return Err(QueryError::InvalidTableOption {
+                option_name: START_FROM_OPTION.into(),
+                table_name: table.into(),
+                reason: format!("Expected 'earliest' or 'latest', but found '{other}'."),
+            })
+        }
+    };
+
+    let mut file_format_options = vec![];
+    let mut connection_options = vec![];
+    for (name, value) in options {
+        match name.as_str() {
+            LOCATION_OPTION
+            | START_FROM_OPTION
+            | EVENT_TIME_COLUMN_OPTION
+            | WATERMARK_DELAY_OPTION => {}
+            name if FILE_FORMAT_OPTIONS.contains(&name) => {
+                file_format_options.push(sql_option(name, value))
+            }
+            name => connection_options.push(sql_option(name, value)),
+        }
+    }
+    let file_format = FileFormatOptionsBuilder::default()
+        .apply_options(file_format_options)?
+        .build();
+
+    Ok(ObjectStoreStreamOptions {
+        location,
+        start_from,
+        file_format,
+        connection_options,
+    })
+}
+
+/// The options of the table are saved as strings, restore the boolean values.
+fn sql_option(name: &str, value: &str) -> SqlOption {
+    let value = match value {
+        "true" => Value::Boolean(true),
+        "false" => Value::Boolean(false),
+        _ => Value::SingleQuotedString(value.to_string()),
+    };
+    SqlOption {
+        name: Ident::new(name),
+        value,
+    }
+}
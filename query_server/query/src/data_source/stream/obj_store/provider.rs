@@ -0,0 +1,416 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::common::Result as DFResult;
+use datafusion::datasource::file_format::FileFormat;
+use datafusion::datasource::listing::{
+    ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl,
+};
+use datafusion::datasource::TableProvider;
+use datafusion::error::DataFusionError;
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::logical_plan::AggWithGrouping;
+use datafusion::physical_plan::empty::EmptyExec;
+use datafusion::physical_plan::{project_schema, ExecutionPlan};
+use datafusion::prelude::Expr;
+use futures::TryStreamExt;
+use models::schema::stream_table_schema::Watermark;
+use object_store::path::Path;
+use object_store::{DynObjectStore, ObjectMeta};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use spi::query::datasource::stream::{Offset, StreamProvider};
+use trace::debug;
+
+use super::StartFrom;
+
+/// The directory under the location that the checkpoints of the streams are saved in.
+const CHECKPOINT_DIR: &str = "_checkpoints";
+
+/// The state of a [`FileTracker`] that is saved after each commit, so that the files
+/// are not read again after the stream is restarted, even on another node.
+#[derive(Debug, Serialize, Deserialize)]
+struct Checkpoint {
+    last_offset: Offset,
+    /// The files that are committed or skipped and still exist.
+    done: Vec<String>,
+}
+
+/// The files under the location that are not committed yet, each new file is
+/// assigned an increasing offset in the order of the modification time.
+struct FileTracker {
+    start_from: StartFrom,
+    /// Whether the checkpoint is loaded.
+    loaded: bool,
+    /// Whether the stream has listed the location, or started before the checkpoint
+    /// was saved. Only the files that exist when the stream first starts are skipped.
+    started: bool,
+    last_offset: Offset,
+    /// The files that are committed or skipped, they are never read again.
+    done: HashSet<Path>,
+    pending: BTreeMap<Offset, ObjectMeta>,
+    /// The files that are not assigned offsets yet, as of the last listing. A file may
+    /// be still being written until its size and modification time stop changing.
+    unstable: HashMap<Path, ObjectMeta>,
+}
+
+impl FileTracker {
+    fn new(start_from: StartFrom) -> Self {
+        Self {
+            start_from,
+            loaded: false,
+            started: false,
+            last_offset: 0,
+            done: HashSet::new(),
+            pending: BTreeMap::new(),
+            unstable: HashMap::new(),
+        }
+    }
+
+    fn restore(&mut self, checkpoint: Option<Checkpoint>) {
+        self.loaded = true;
+        if let Some(checkpoint) = checkpoint {
+            self.started = true;
+            self.last_offset = checkpoint.last_offset;
+            self.done = checkpoint.done.into_iter().map(Path::from).collect();
+        }
+    }
+
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            last_offset: self.last_offset,
+            done: self.done.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    fn track(&mut self, files: Vec<ObjectMeta>) {
+        let listed: HashSet<Path> = files.iter().map(|f| f.location.clone()).collect();
+        // The files that were removed before being read are skipped.
+        self.pending.retain(|_, f| listed.contains(&f.location));
+        self.done.retain(|p| listed.contains(p));
+
+        if !self.started {
+            self.started = true;
+            if self.start_from == StartFrom::Latest {
+                self.done = listed;
+                return;
+            }
+        }
+
+        let pending: HashSet<Path> = self.pending.values().map(|f| f.location.clone()).collect();
+        let mut new_files = vec![];
+        let mut unstable = HashMap::new();
+        for file in files {
+            if self.done.contains(&file.location) || pending.contains(&file.location) {
+                continue;
+            }
+            match self.unstable.get(&file.location) {
+                Some(last)
+                    if last.size == file.size && last.last_modified == file.last_modified =>
+                {
+                    new_files.push(file)
+                }
+                _ => {
+                    unstable.insert(file.location.clone(), file);
+                }
+            }
+        }
+        self.unstable = unstable;
+
+        new_files
+            .sort_by(|a, b| (a.last_modified, &a.location).cmp(&(b.last_modified, &b.location)));
+        for file in new_files {
+            self.last_offset += 1;
+            self.pending.insert(self.last_offset, file);
+        }
+    }
+
+    fn commit(&mut self, end: Offset) {
+        let pending = self.pending.split_off(&(end + 1));
+        let committed = std::mem::replace(&mut self.pending, pending);
+        self.done
+            .extend(committed.into_values().map(|file| file.location));
+    }
+}
+
+/// Streaming source of the CSV/Parquet/NDJSON files that arrive in a directory of
+/// an object store, each micro-batch reads the files that arrived since the last one.
+pub struct ObjectStoreStreamProvider {
+    id: String,
+    watermark: Watermark,
+    schema: SchemaRef,
+    location: ListingTableUrl,
+    object_store: Arc<DynObjectStore>,
+    file_format: Arc<dyn FileFormat>,
+    file_extension: String,
+    checkpoint_dir: Path,
+    files: Mutex<FileTracker>,
+}
+
+impl ObjectStoreStreamProvider {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: String,
+        watermark: Watermark,
+        schema: SchemaRef,
+        location: ListingTableUrl,
+        object_store: Arc<DynObjectStore>,
+        file_format: Arc<dyn FileFormat>,
+        file_extension: String,
+        start_from: StartFrom,
+    ) -> Self {
+        let checkpoint_dir = location.prefix().child(CHECKPOINT_DIR);
+        Self {
+            id,
+            watermark,
+            schema,
+            location,
+            object_store,
+            file_format,
+            file_extension,
+            checkpoint_dir,
+            files: Mutex::new(FileTracker::new(start_from)),
+        }
+    }
+
+    fn checkpoint_path(&self) -> Path {
+        self.checkpoint_dir.child(format!("{}.json", self.id))
+    }
+
+    async fn load_checkpoint(&self) -> DFResult<Option<Checkpoint>> {
+        let data = match self.object_store.get(&self.checkpoint_path()).await {
+            Ok(result) => result.bytes().await?,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let checkpoint =
+            serde_json::from_slice(&data).map_err(|e| DataFusionError::External(Box::new(e)))?;
+        Ok(Some(checkpoint))
+    }
+
+    async fn list_files(&self) -> DFResult<Vec<ObjectMeta>> {
+        let files = self
+            .object_store
+            .list(Some(self.location.prefix()))
+            .await?
+            // Empty files may be still being written.
+            .try_filter(|f| {
+                futures::future::ready(
+                    f.size > 0
+                        && f.location.as_ref().ends_with(&self.file_extension)
+                        && !f.location.prefix_matches(&self.checkpoint_dir),
+                )
+            })
+            .try_collect()
+            .await?;
+        Ok(files)
+    }
+
+    fn file_url(&self, path: &Path) -> DFResult<ListingTableUrl> {
+        ListingTableUrl::parse(format!("{}{}", self.location.object_store().as_str(), path))
+    }
+}
+
+#[async_trait]
+impl StreamProvider for ObjectStoreStreamProvider {
+    type Offset = Offset;
+
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn watermark(&self) -> &Watermark {
+        &self.watermark
+    }
+
+    /// Lists the location, returns the offset of the latest file that is not committed.
+    async fn latest_available_offset(&self) -> DFResult<Option<Self::Offset>> {
+        if !self.files.lock().loaded {
+            let checkpoint = self.load_checkpoint().await?;
+            self.files.lock().restore(checkpoint);
+        }
+        let files = self.list_files().await?;
+        let mut tracker = self.files.lock();
+        tracker.track(files);
+        Ok(tracker.pending.keys().next_back().copied())
+    }
+
+    async fn scan(
+        &self,
+        state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        agg_with_grouping: Option<&AggWithGrouping>,
+        range: Option<&(Option<Self::Offset>, Self::Offset)>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        if agg_with_grouping.is_some() {
+            return Err(DataFusionError::NotImplemented(
+                "ObjectStoreStreamProvider::scan with agg_with_grouping".to_string(),
+            ));
+        }
+
+        // The files before the committed offset are removed from the tracker, so only
+        // the end of the range is used.
+        let files = match range {
+            Some((_, end)) => self
+                .files
+                .lock()
+                .pending
+                .range(..=*end)
+                .map(|(_, f)| f.location.clone())
+                .collect::<Vec<_>>(),
+            None => vec![],
+        };
+        if files.is_empty() {
+            let projected_schema = project_schema(&self.schema, projection)?;
+            return Ok(Arc::new(EmptyExec::new(false, projected_schema)));
+        }
+        debug!("Stream source {} reads files: {:?}", self.id, files);
+
+        let table_paths = files
+            .iter()
+            .map(|f| self.file_url(f))
+            .collect::<DFResult<Vec<_>>>()?;
+        let _ = state
+            .runtime_env()
+            .register_object_store(self.location.as_ref(), self.object_store.clone());
+
+        let options = ListingOptions::new(self.file_format.clone())
+            .with_file_extension(&self.file_extension)
+            .with_target_partitions(state.config().target_partitions());
+        let config = ListingTableConfig::new_with_multi_paths(table_paths)
+            .with_listing_options(options)
+            .with_schema(self.schema.clone());
+        let table = ListingTable::try_new(config)?;
+
+        table.scan(state, projection, filters, None, None).await
+    }
+
+    /// The files with offsets not greater than `end` are read, they will not be read again.
+    async fn commit(&self, end: Self::Offset) -> DFResult<()> {
+        debug!("Stream source {} commit offset: {end}", self.id);
+        let checkpoint = {
+            let mut tracker = self.files.lock();
+            tracker.commit(end);
+            tracker.checkpoint()
+        };
+        let data =
+            serde_json::to_vec(&checkpoint).map_err(|e| DataFusionError::External(Box::new(e)))?;
+        self.object_store
+            .put(&self.checkpoint_path(), Bytes::from(data))
+            .await?;
+        Ok(())
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::datasource::file_format::csv::CsvFormat;
+    use datafusion::datasource::listing::ListingTableUrl;
+    use datafusion::physical_plan::collect;
+    use datafusion::prelude::SessionContext;
+    use models::schema::stream_table_schema::Watermark;
+    use object_store::local::LocalFileSystem;
+    use spi::query::datasource::stream::StreamProvider;
+
+    use super::ObjectStoreStreamProvider;
+    use crate::data_source::stream::obj_store::StartFrom;
+
+    fn new_provider(dir: &std::path::Path, start_from: StartFrom) -> ObjectStoreStreamProvider {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("value", DataType::Int64, false),
+        ]));
+        let location = ListingTableUrl::parse(format!("{}/", dir.display())).unwrap();
+        ObjectStoreStreamProvider::new(
+            "tenant.db.files".to_string(),
+            Watermark {
+                column: "time".into(),
+                delay: Duration::default(),
+            },
+            schema,
+            location,
+            Arc::new(LocalFileSystem::new()),
+            Arc::new(CsvFormat::default()),
+            ".csv".to_string(),
+            start_from,
+        )
+    }
+
+    async fn read(provider: &ObjectStoreStreamProvider, range: (Option<i64>, i64)) -> usize {
+        let ctx = SessionContext::new();
+        let plan = provider
+            .scan(&ctx.state(), None, &[], None, Some(&range))
+            .await
+            .unwrap();
+        let batches = collect(plan, ctx.task_ctx()).await.unwrap();
+        batches.iter().map(|b| b.num_rows()).sum()
+    }
+
+    #[tokio::test]
+    async fn test_object_store_stream_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, rows: &str| {
+            std::fs::write(dir.path().join(name), format!("time,value\n{rows}")).unwrap()
+        };
+        write("1.csv", "1970-01-01T00:00:00.000000001,1\n");
+        write("2.csv", "1970-01-01T00:00:00.000000002,2\n");
+        write("ignored.txt", "1970-01-01T00:00:00.000000003,3\n");
+
+        let provider = new_provider(dir.path(), StartFrom::Earliest);
+        // The files are not read until they stop changing.
+        assert_eq!(provider.latest_available_offset().await.unwrap(), None);
+        let end = provider.latest_available_offset().await.unwrap().unwrap();
+        assert_eq!(end, 2);
+        assert_eq!(read(&provider, (None, end)).await, 2);
+
+        // The committed files are not read again.
+        provider.commit(end).await.unwrap();
+        assert_eq!(provider.latest_available_offset().await.unwrap(), None);
+
+        write("3.csv", "1970-01-01T00:00:00.000000003,3\n");
+        assert_eq!(provider.latest_available_offset().await.unwrap(), None);
+        // The file is still being written.
+        write(
+            "3.csv",
+            "1970-01-01T00:00:00.000000003,3\n1970-01-01T00:00:00.000000004,4\n",
+        );
+        assert_eq!(provider.latest_available_offset().await.unwrap(), None);
+        let end = provider.latest_available_offset().await.unwrap().unwrap();
+        assert_eq!(end, 3);
+        assert_eq!(read(&provider, (Some(2), end)).await, 2);
+
+        // The committed files are not read again after the stream is restarted, the
+        // files that are not committed are read.
+        let provider = new_provider(dir.path(), StartFrom::Latest);
+        assert_eq!(provider.latest_available_offset().await.unwrap(), None);
+        let end = provider.latest_available_offset().await.unwrap().unwrap();
+        assert_eq!(end, 3);
+        assert_eq!(read(&provider, (None, end)).await, 2);
+        provider.commit(end).await.unwrap();
+
+        // The files that exist when the stream first starts are skipped.
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("1.csv"), "time,value\n1,1\n").unwrap();
+        let provider = new_provider(dir.path(), StartFrom::Latest);
+        assert_eq!(provider.latest_available_offset().await.unwrap(), None);
+        std::fs::write(dir.path().join("2.csv"), "time,value\n2,2\n").unwrap();
+        assert_eq!(provider.latest_available_offset().await.unwrap(), None);
+        assert_eq!(provider.latest_available_offset().await.unwrap(), Some(1));
+    }
+}
@@ -128,6 +128,11 @@ impl StreamProvider for TskvStreamProvider {
         Ok(())
     }
 
+    /// The offset of tskv is the event time.
+    fn resumed_offset(&self, event_time_ns: i64) -> Option<Self::Offset> {
+        Some(event_time_ns)
+    }
+
     fn schema(&self) -> SchemaRef {
         self.used_schema.clone()
     }
@@ -39,6 +39,8 @@ use crate::metadata::{
     BaseTableProvider, ContextProviderExtension, MetadataProvider, TableHandleProviderRef,
 };
 use crate::sql::logical::planner::DefaultLogicalPlanner;
+use crate::sql::substrait::substrait_to_plan;
+use crate::stream::manager::StreamJobManager;
 
 #[derive(Clone)]
 pub struct SimpleQueryDispatcher {
@@ -140,6 +142,24 @@ impl QueryDispatcher for SimpleQueryDispatcher {
         Ok(Some(logical_plan))
     }
 
+    async fn build_substrait_plan(
+        &self,
+        substrait_plan: &[u8],
+        query_state_machine: Arc<QueryStateMachine>,
+    ) -> QueryResult<Plan> {
+        let session = &query_state_machine.session;
+        let scheme_provider = Arc::new(self.build_scheme_provider(session).await?);
+
+        query_state_machine.begin_analyze();
+        let logical_plan = {
+            let _span = session.get_child_span("substrait plan to logical plan");
+            substrait_to_plan(scheme_provider, substrait_plan, session).await?
+        };
+        query_state_machine.end_analyze();
+
+        Ok(logical_plan)
+    }
+
     async fn execute_logical_plan(
         &self,
         logical_plan: Plan,
@@ -190,12 +210,26 @@ impl QueryDispatcher for SimpleQueryDispatcher {
             .collect()
     }
 
-    fn cancel_query(&self, id: &QueryId) {
-        self.query_tracker.query(id).map(|e| e.cancel());
+    fn cancel_query(&self, id: &QueryId) -> bool {
+        match self.query_tracker.expire_query(id) {
+            Some(query) => {
+                let _ = query.cancel();
+                true
+            }
+            None => false,
+        }
     }
 }
 
 impl SimpleQueryDispatcher {
+    pub fn coord(&self) -> &CoordinatorRef {
+        &self.coord
+    }
+
+    pub fn query_tracker(&self) -> &Arc<QueryTracker> {
+        &self.query_tracker
+    }
+
     async fn execute_persister_query(&self, node_id: NodeId) -> QueryResult<()> {
         // 执行被持久化的任务
         let queries = self.query_tracker.persistent_queries(node_id).await?;
@@ -438,6 +472,14 @@ impl SimpleQueryDispatcher {
                         .move_queryinfo(node_metrics.id, dispatcher.coord.node_id())
                         .await
                         .context(MetaSnafu)?;
+
+                    // 3.take over the streams and rollups of the dead node
+                    dispatcher
+                        .coord
+                        .meta_manager()
+                        .move_streams(node_metrics.id, dispatcher.coord.node_id())
+                        .await
+                        .context(MetaSnafu)?;
                 }
                 Ok(())
             }
@@ -716,6 +758,7 @@ impl SimpleQueryDispatcherBuilder {
             dispatcher.clone(),
             meta_task_receiver,
         ));
+        tokio::spawn(StreamJobManager::new(dispatcher.clone()).run());
 
         Ok(dispatcher)
     }
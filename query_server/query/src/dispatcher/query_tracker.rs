@@ -12,6 +12,7 @@ use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::common::Result as DFResult;
 use datafusion::physical_plan::{RecordBatchStream, SendableRecordBatchStream};
 use futures::{Stream, StreamExt};
+use models::consistency_level::ConsistencyLevel;
 use models::meta_data::NodeId;
 use models::schema::query_info::{QueryId, QueryInfo};
 use models::schema::{CLUSTER_SCHEMA, DEFAULT_CATALOG};
@@ -201,6 +202,7 @@ impl QueryTracker {
                             CLUSTER_SCHEMA,
                             Precision::NS,
                             vec![line],
+                            ConsistencyLevel::Quorum,
                             None,
                         )
                         .await
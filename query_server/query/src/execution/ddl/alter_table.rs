@@ -119,6 +119,31 @@ impl DDLDefinitionTask for AlterTableTask {
                 alter_schema_func(&mut schema, old_column_name, new_column_name)?;
                 None
             }
+            AlterTableAction::Reencode => {
+                let db_info = client
+                    .get_db_info(table_name.database())
+                    .context(MetaSnafu)?
+                    .ok_or_else(|| MetaError::DatabaseNotFound {
+                        database: table_name.database().to_string(),
+                    })
+                    .context(MetaSnafu)?;
+                let vnode_ids = db_info
+                    .buckets
+                    .iter()
+                    .flat_map(|bucket| {
+                        bucket
+                            .shard_group
+                            .iter()
+                            .flat_map(|group| group.vnodes.iter().map(|vnode| vnode.id))
+                    })
+                    .collect::<Vec<_>>();
+                query_state_machine
+                    .coord
+                    .reencode_vnodes(tenant, table_name.database(), table_name.table(), vnode_ids)
+                    .await
+                    .context(CoordinatorSnafu)?;
+                return Ok(Output::Nil(()));
+            }
         };
 
         if let Some(info) = operator_info {
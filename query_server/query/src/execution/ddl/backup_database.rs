@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use coordinator::service::CoordinatorRef;
+use meta::error::MetaError;
+use models::meta_data::ReplicationSet;
+use models::schema::database_schema::DatabaseSchema;
+use models::schema::table_schema::TableSchema;
+use models::schema::tskv_table_schema::TskvTableSchema;
+use models::utils::now_timestamp_nanos;
+use object_store::path::Path as ObjectStorePath;
+use object_store::ObjectStore;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::{BackupDatabase, BackupLocation};
+use spi::{
+    CoordinatorSnafu, MetaSnafu, ObjectStoreSnafu, QueryError, QueryResult, SerdeJsonSnafu,
+    StdIoSnafu,
+};
+use tokio::io::AsyncWriteExt;
+use trace::info;
+use tskv::kv_option::DATA_PATH;
+use tskv::VnodeSnapshot;
+
+use super::DDLDefinitionTask;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const DATA_DIR: &str = "data";
+
+/// The manifest of a backup, the files of a vnode snapshot are stored at
+/// `<location>/data/<vnode_id>/<relative path of the file>`.
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct BackupManifest {
+    pub create_time: i64,
+    pub database: DatabaseSchema,
+    pub tables: Vec<TskvTableSchema>,
+    pub buckets: Vec<BackupBucket>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct BackupBucket {
+    pub start_time: i64,
+    pub end_time: i64,
+    /// Snapshots of the shards in the order of the shard group, `None` if the shard has no data.
+    pub shards: Vec<Option<VnodeSnapshot>>,
+}
+
+impl BackupManifest {
+    /// Paths and sizes of all the files of the backup.
+    pub(super) fn files(&self, root: &ObjectStorePath) -> HashMap<ObjectStorePath, u64> {
+        let mut files = HashMap::new();
+        for snapshot in self.buckets.iter().flat_map(|b| b.shards.iter().flatten()) {
+            for file in snapshot.version_edit.add_files.iter() {
+                let path = file_path(root, snapshot, &file.relative_path());
+                files.insert(path, file.file_size);
+            }
+        }
+
+        files
+    }
+}
+
+pub(super) fn file_path(
+    root: &ObjectStorePath,
+    snapshot: &VnodeSnapshot,
+    relative_path: &Path,
+) -> ObjectStorePath {
+    let mut path = root.child(DATA_DIR).child(snapshot.vnode_id.to_string());
+    for part in relative_path.iter() {
+        path = path.child(part.to_string_lossy().as_ref());
+    }
+
+    path
+}
+
+pub(super) async fn read_manifest(
+    location: &BackupLocation,
+) -> QueryResult<Option<BackupManifest>> {
+    let path = location.path.child(MANIFEST_FILE);
+    let data = match location.store.get(&path).await {
+        Ok(result) => result
+            .bytes()
+            .await
+            .map_err(|e| ObjectStoreSnafu { msg: e.to_string() }.build())?,
+        Err(object_store::Error::NotFound { .. }) => return Ok(None),
+        Err(e) => return Err(ObjectStoreSnafu { msg: e.to_string() }.build()),
+    };
+
+    let manifest = serde_json::from_slice(&data).context(SerdeJsonSnafu)?;
+    Ok(Some(manifest))
+}
+
+pub struct BackupDatabaseTask {
+    stmt: BackupDatabase,
+}
+
+impl BackupDatabaseTask {
+    #[inline(always)]
+    pub fn new(stmt: BackupDatabase) -> Self {
+        Self { stmt }
+    }
+
+    /// Take a snapshot of the leader vnode of the replication set, then copy the files of
+    /// the snapshot that are not in `backed_up` to the location.
+    async fn backup_replica(
+        &self,
+        coord: &CoordinatorRef,
+        replica: &ReplicationSet,
+        backed_up: &HashMap<ObjectStorePath, u64>,
+    ) -> QueryResult<Option<VnodeSnapshot>> {
+        let BackupDatabase {
+            tenant_name,
+            location,
+            ..
+        } = &self.stmt;
+
+        let vnode = match replica
+            .vnodes
+            .iter()
+            .find(|v| v.id == replica.leader_vnode_id)
+            .or_else(|| replica.vnodes.first())
+        {
+            Some(vnode) => vnode,
+            None => return Ok(None),
+        };
+        let snapshot = match coord
+            .backup_vnode(tenant_name, vnode)
+            .await
+            .context(CoordinatorSnafu)?
+        {
+            Some(snapshot) => snapshot,
+            None => return Ok(None),
+        };
+
+        let src_dir = PathBuf::from(DATA_PATH)
+            .join(&snapshot.version_edit.tsf_name)
+            .join(snapshot.vnode_id.to_string());
+        for file in snapshot.version_edit.add_files.iter() {
+            let relative_path = file.relative_path();
+            let dst = file_path(&location.path, &snapshot, &relative_path);
+            if backed_up.get(&dst) == Some(&file.file_size) {
+                continue;
+            }
+
+            let src = src_dir.join(&relative_path).to_string_lossy().to_string();
+            let (id, mut writer) = location
+                .store
+                .put_multipart(&dst)
+                .await
+                .map_err(|e| ObjectStoreSnafu { msg: e.to_string() }.build())?;
+            let result = match coord
+                .download_file(vnode.node_id, &src, writer.as_mut())
+                .await
+            {
+                Ok(length) if length == file.file_size => Ok(()),
+                Ok(length) => Err(ObjectStoreSnafu {
+                    msg: format!(
+                        "download file {src} length not match {} -> {length}",
+                        file.file_size
+                    ),
+                }
+                .build()),
+                Err(e) => Err(QueryError::Coordinator { source: e }),
+            };
+            if let Err(e) = result {
+                let _ = location.store.abort_multipart(&dst, &id).await;
+                return Err(e);
+            }
+            writer.shutdown().await.context(StdIoSnafu)?;
+        }
+
+        Ok(Some(snapshot))
+    }
+}
+
+/// Remove the files of the previous backup that are not in the latest backup, other files
+/// under the location are never touched.
+async fn remove_obsolete_files(
+    store: &dyn ObjectStore,
+    previous: &HashMap<ObjectStorePath, u64>,
+    current: &HashMap<ObjectStorePath, u64>,
+) -> QueryResult<()> {
+    for path in previous.keys().filter(|path| !current.contains_key(*path)) {
+        match store.delete(path).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => {}
+            Err(e) => return Err(ObjectStoreSnafu { msg: e.to_string() }.build()),
+        }
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl DDLDefinitionTask for BackupDatabaseTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let BackupDatabase {
+            tenant_name,
+            db_name,
+            location,
+            incremental,
+        } = &self.stmt;
+
+        let meta = query_state_machine
+            .meta
+            .tenant_meta(tenant_name)
+            .await
+            .ok_or_else(|| QueryError::Meta {
+                source: MetaError::TenantNotFound {
+                    tenant: tenant_name.to_string(),
+                },
+            })?;
+        let db_info = meta
+            .get_db_info(db_name)
+            .context(MetaSnafu)?
+            .ok_or_else(|| QueryError::DatabaseNotFound {
+                name: db_name.to_string(),
+            })?;
+
+        let previous = read_manifest(location)
+            .await?
+            .map(|manifest| manifest.files(&location.path))
+            .unwrap_or_default();
+        // The first incremental backup of a location copies all the files.
+        let empty = HashMap::new();
+        let backed_up = match incremental {
+            true => &previous,
+            false => &empty,
+        };
+
+        let coord = query_state_machine.coord.clone();
+        let mut buckets = Vec::with_capacity(db_info.buckets.len());
+        for bucket in db_info.buckets.iter() {
+            let mut shards = Vec::with_capacity(bucket.shard_group.len());
+            for replica in bucket.shard_group.iter() {
+                shards.push(self.backup_replica(&coord, replica, backed_up).await?);
+            }
+            buckets.push(BackupBucket {
+                start_time: bucket.start_time,
+                end_time: bucket.end_time,
+                shards,
+            });
+        }
+
+        let tables = db_info
+            .tables
+            .values()
+            .filter_map(|table| match table {
+                TableSchema::TsKvTableSchema(schema) => Some(schema.as_ref().clone()),
+                _ => None,
+            })
+            .collect();
+        let manifest = BackupManifest {
+            create_time: now_timestamp_nanos(),
+            database: db_info.schema.clone(),
+            tables,
+            buckets,
+        };
+        let data = serde_json::to_vec_pretty(&manifest).context(SerdeJsonSnafu)?;
+        location
+            .store
+            .put(&location.path.child(MANIFEST_FILE), Bytes::from(data))
+            .await
+            .map_err(|e| ObjectStoreSnafu { msg: e.to_string() }.build())?;
+
+        remove_obsolete_files(
+            location.store.as_ref(),
+            &previous,
+            &manifest.files(&location.path),
+        )
+        .await?;
+        info!(
+            "Backup database {tenant_name}.{db_name} to {}, incremental: {incremental}",
+            location.path
+        );
+
+        Ok(Output::Nil(()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    use bytes::Bytes;
+    use object_store::local::LocalFileSystem;
+    use object_store::path::Path as ObjectStorePath;
+    use object_store::ObjectStore;
+    use tskv::{VersionEdit, VnodeSnapshot};
+
+    use super::{file_path, remove_obsolete_files, BackupBucket, BackupManifest};
+
+    #[test]
+    fn test_manifest() {
+        let snapshot = VnodeSnapshot {
+            node_id: 1,
+            vnode_id: 3,
+            last_seq_no: 10,
+            create_time: "20231018_120000_000".to_string(),
+            version_edit: VersionEdit::new_add_vnode(3, "cnosdb.db".to_string(), 10),
+            version: None,
+            active_time: 0,
+        };
+
+        let root = ObjectStorePath::from("backup/db");
+        let path = file_path(&root, &snapshot, Path::new("tsm/_000005.tsm"));
+        assert_eq!(path.as_ref(), "backup/db/data/3/tsm/_000005.tsm");
+
+        let manifest = BackupManifest {
+            create_time: 0,
+            database: Default::default(),
+            tables: vec![],
+            buckets: vec![BackupBucket {
+                start_time: 0,
+                end_time: 100,
+                shards: vec![Some(snapshot), None],
+            }],
+        };
+        let data = serde_json::to_vec(&manifest).unwrap();
+        let manifest: BackupManifest = serde_json::from_slice(&data).unwrap();
+        assert_eq!(manifest.buckets[0].shards.len(), 2);
+        assert_eq!(manifest.buckets[0].shards[0].as_ref().unwrap().vnode_id, 3);
+        assert!(manifest.buckets[0].shards[1].is_none());
+        assert!(manifest.files(&root).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_remove_obsolete_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalFileSystem::new_with_prefix(dir.path()).unwrap();
+        let obsolete = ObjectStorePath::from("backup/data/3/tsm/_000005.tsm");
+        let kept = ObjectStorePath::from("backup/data/3/tsm/_000006.tsm");
+        let foreign = ObjectStorePath::from("backup/data/other.txt");
+        for path in [&obsolete, &kept, &foreign] {
+            store.put(path, Bytes::from_static(b"data")).await.unwrap();
+        }
+
+        let previous = HashMap::from([(obsolete.clone(), 4), (kept.clone(), 4)]);
+        let current = HashMap::from([(kept.clone(), 4)]);
+        remove_obsolete_files(&store, &previous, &current)
+            .await
+            .unwrap();
+        assert!(store.head(&obsolete).await.is_err());
+        assert!(store.head(&kept).await.is_ok());
+        assert!(store.head(&foreign).await.is_ok());
+
+        // The files removed by another backup are ignored.
+        remove_obsolete_files(&store, &previous, &current)
+            .await
+            .unwrap();
+    }
+}
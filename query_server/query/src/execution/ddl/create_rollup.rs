@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::Schema;
+use meta::error::MetaError;
+use meta::model::MetaClientRef;
+use models::schema::query_info::QueryId;
+use models::schema::rollup_info::RollupInfo;
+use models::schema::stream_info::{StreamInfo, StreamStatus};
+use models::schema::stream_table_schema::{StreamTable, Watermark};
+use models::schema::table_schema::TableSchema;
+use models::schema::TIME_FIELD_NAME;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::CreateRollup;
+use spi::{MetaSnafu, QueryError, QueryResult};
+use trace::{debug, error};
+
+use super::DDLDefinitionTask;
+use crate::data_source::stream::tskv::factory::TSKV_STREAM_PROVIDER;
+use crate::data_source::stream::tskv::{STREAM_DB_KEY, STREAM_TABLE_KEY};
+
+pub struct CreateRollupTask {
+    stmt: CreateRollup,
+}
+
+impl CreateRollupTask {
+    #[inline(always)]
+    pub fn new(stmt: CreateRollup) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for CreateRollupTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let CreateRollup {
+            if_not_exists,
+            ref name,
+            ref source_table,
+            ref target_table,
+            ref interval,
+            ref delay,
+            ref ttl,
+            ref query,
+            ..
+        } = self.stmt;
+
+        let client = query_state_machine
+            .meta
+            .tenant_meta(name.tenant())
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: name.tenant().to_string(),
+            })
+            .context(MetaSnafu)?;
+
+        // The source table is read through a stream table of tskv.
+        let is_tskv_table = client
+            .get_tskv_table_schema(source_table.database(), source_table.table())
+            .context(MetaSnafu)?
+            .is_some();
+        if !is_tskv_table {
+            return Err(QueryError::Analyzer {
+                err: format!("The source table {source_table} of rollup must be a tskv table"),
+            });
+        }
+
+        let rollup = RollupInfo {
+            tenant_id: *query_state_machine.session.tenant_id(),
+            tenant_name: name.tenant().to_string(),
+            database_name: name.database().to_string(),
+            name: name.table().to_string(),
+            source_table: format!("{}.{}", source_table.database(), source_table.table()),
+            target_table: format!("{}.{}", target_table.database(), target_table.table()),
+            interval: interval.clone(),
+            delay: delay.clone(),
+            ttl: ttl.clone(),
+            query: query.clone(),
+        };
+
+        debug!("Create rollup {:?}", rollup);
+        match client.create_rollup(rollup).await {
+            Ok(_) => {}
+            Err(MetaError::RollupAlreadyExists { .. }) if if_not_exists => {
+                return Ok(Output::Nil(()))
+            }
+            Err(e) => return Err(e).context(MetaSnafu),
+        }
+
+        if let Err(err) = create_rollup_stream(&client, &query_state_machine, &self.stmt).await {
+            if let Err(e) = client.drop_rollup(name.database(), name.table()).await {
+                error!(
+                    "Failed to drop rollup {} after creating failed: {}",
+                    name, e
+                );
+            }
+            return Err(err);
+        }
+
+        Ok(Output::Nil(()))
+    }
+}
+
+/// Creates the stream table over the source table and the stream that runs the rollup,
+/// the stream is started by the stream job manager of the current node.
+async fn create_rollup_stream(
+    client: &MetaClientRef,
+    machine: &QueryStateMachineRef,
+    stmt: &CreateRollup,
+) -> QueryResult<()> {
+    let CreateRollup {
+        ref name,
+        ref source_table,
+        ref interval,
+        ref delay,
+        ref stream_query,
+        ..
+    } = stmt;
+
+    let stream_table_name = RollupInfo::stream_table(name.table());
+    let stream_table = StreamTable::new(
+        name.tenant(),
+        name.database(),
+        &stream_table_name,
+        Arc::new(Schema::empty()),
+        TSKV_STREAM_PROVIDER,
+        Watermark {
+            column: TIME_FIELD_NAME.to_string(),
+            delay: Duration::from_nanos(delay.to_nanoseconds() as u64),
+        },
+        HashMap::from([
+            (
+                STREAM_DB_KEY.to_string(),
+                source_table.database().to_string(),
+            ),
+            (
+                STREAM_TABLE_KEY.to_string(),
+                source_table.table().to_string(),
+            ),
+        ]),
+    );
+    client
+        .create_table(&TableSchema::StreamTableSchema(Arc::new(stream_table)))
+        .await
+        .context(MetaSnafu)?;
+
+    let stream = StreamInfo {
+        tenant_id: *machine.session.tenant_id(),
+        tenant_name: name.tenant().to_string(),
+        database_name: name.database().to_string(),
+        name: name.table().to_string(),
+        trigger: Some(format!("{}ns", interval.to_nanoseconds())),
+        query: stream_query.clone(),
+        user: machine.session.user().clone(),
+        node_id: machine.coord.node_id(),
+        query_id: QueryId::next_id(),
+        status: StreamStatus::Starting,
+        watermark_ns: None,
+        processed_count: 0,
+        error_count: 0,
+        error: None,
+    };
+    if let Err(err) = client.create_stream(stream).await {
+        if let Err(e) = client.drop_table(name.database(), &stream_table_name).await {
+            error!(
+                "Failed to drop stream table {} after creating failed: {}",
+                stream_table_name, e
+            );
+        }
+        return Err(err).context(MetaSnafu);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use async_trait::async_trait;
+use meta::error::MetaError;
+use models::schema::query_info::QueryId;
+use models::schema::stream_info::{StreamInfo, StreamStatus};
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::CreateStream;
+use spi::{MetaSnafu, QueryResult};
+use trace::debug;
+
+use super::DDLDefinitionTask;
+
+pub struct CreateStreamTask {
+    stmt: CreateStream,
+}
+
+impl CreateStreamTask {
+    #[inline(always)]
+    pub fn new(stmt: CreateStream) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for CreateStreamTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let CreateStream {
+            if_not_exists,
+            ref name,
+            ref trigger,
+            ref query,
+        } = self.stmt;
+
+        let client = query_state_machine
+            .meta
+            .tenant_meta(name.tenant())
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: name.tenant().to_string(),
+            })
+            .context(MetaSnafu)?;
+
+        // The stream is started by the stream job manager of the current node.
+        let stream = StreamInfo {
+            tenant_id: *query_state_machine.session.tenant_id(),
+            tenant_name: name.tenant().to_string(),
+            database_name: name.database().to_string(),
+            name: name.table().to_string(),
+            trigger: trigger.clone(),
+            query: query.clone(),
+            user: query_state_machine.session.user().clone(),
+            node_id: query_state_machine.coord.node_id(),
+            query_id: QueryId::next_id(),
+            status: StreamStatus::Starting,
+            watermark_ns: None,
+            processed_count: 0,
+            error_count: 0,
+            error: None,
+        };
+
+        debug!("Create stream {:?}", stream);
+        match client.create_stream(stream).await {
+            Ok(_) => Ok(Output::Nil(())),
+            Err(MetaError::StreamAlreadyExists { .. }) if if_not_exists => Ok(Output::Nil(())),
+            Err(e) => Err(e).context(MetaSnafu),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use async_trait::async_trait;
+use meta::error::MetaError;
+use meta::model::MetaClientRef;
+use models::schema::rollup_info::RollupInfo;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::DropRollup;
+use spi::{MetaSnafu, QueryResult};
+
+use super::DDLDefinitionTask;
+
+pub struct DropRollupTask {
+    stmt: DropRollup,
+}
+
+impl DropRollupTask {
+    #[inline(always)]
+    pub fn new(stmt: DropRollup) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for DropRollupTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let DropRollup { if_exist, ref name } = self.stmt;
+
+        let client = query_state_machine
+            .meta
+            .tenant_meta(name.tenant())
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: name.tenant().to_string(),
+            })
+            .context(MetaSnafu)?;
+
+        let dropped = drop_rollup(&client, name.database(), name.table()).await?;
+        if !dropped && !if_exist {
+            return Err(MetaError::RollupNotFound {
+                name: name.table().to_string(),
+            })
+            .context(MetaSnafu);
+        }
+
+        Ok(Output::Nil(()))
+    }
+}
+
+/// Drops the rollup, then its stream and stream table, returns whether the rollup exists.
+async fn drop_rollup(client: &MetaClientRef, db_name: &str, name: &str) -> QueryResult<bool> {
+    if !client.drop_rollup(db_name, name).await.context(MetaSnafu)? {
+        return Ok(false);
+    }
+
+    // The stream is stopped by the stream job manager of its node.
+    let _ = client.drop_stream(db_name, name).await.context(MetaSnafu)?;
+
+    let stream_table = RollupInfo::stream_table(name);
+    if client
+        .get_table_schema(db_name, &stream_table)
+        .context(MetaSnafu)?
+        .is_some()
+    {
+        client
+            .drop_table(db_name, &stream_table)
+            .await
+            .context(MetaSnafu)?;
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+use meta::error::MetaError;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::DropStream;
+use spi::{MetaSnafu, QueryResult};
+
+use super::DDLDefinitionTask;
+
+pub struct DropStreamTask {
+    stmt: DropStream,
+}
+
+impl DropStreamTask {
+    #[inline(always)]
+    pub fn new(stmt: DropStream) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for DropStreamTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let DropStream { if_exist, ref name } = self.stmt;
+
+        let client = query_state_machine
+            .meta
+            .tenant_meta(name.tenant())
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: name.tenant().to_string(),
+            })
+            .context(MetaSnafu)?;
+
+        // The running query is stopped by the stream job manager of its node.
+        let dropped = client
+            .drop_stream(name.database(), name.table())
+            .await
+            .context(MetaSnafu)?;
+        if !dropped && !if_exist {
+            return Err(MetaError::StreamNotFound {
+                name: name.table().to_string(),
+            })
+            .context(MetaSnafu);
+        }
+
+        Ok(Output::Nil(()))
+    }
+}
@@ -8,14 +8,19 @@ use spi::QueryResult;
 
 use self::alter_tenant::AlterTenantTask;
 use self::alter_user::AlterUserTask;
+use self::backup_database::BackupDatabaseTask;
 use self::create_external_table::CreateExternalTableTask;
 use self::create_role::CreateRoleTask;
+use self::create_rollup::CreateRollupTask;
+use self::create_stream::CreateStreamTask;
 use self::create_stream_table::CreateStreamTableTask;
 use self::create_table::CreateTableTask;
 use self::create_tenant::CreateTenantTask;
 use self::create_user::CreateUserTask;
 use self::drop_database_object::DropDatabaseObjectTask;
 use self::drop_global_object::DropGlobalObjectTask;
+use self::drop_rollup::DropRollupTask;
+use self::drop_stream::DropStreamTask;
 use self::drop_tenant_object::DropTenantObjectTask;
 use self::grant_revoke::GrantRevokeTask;
 use self::recover_database::RecoverDatabaseTask;
@@ -24,7 +29,11 @@ use self::replica_add::ReplicaAddTask;
 use self::replica_destory::ReplicaDestoryTask;
 use self::replica_promote::ReplicaPromoteTask;
 use self::replica_remove::ReplicaRemoveTask;
+use self::restore_database::RestoreDatabaseTask;
 use self::show_replica::ShowReplicasTask;
+use self::show_rollups::ShowRollupsTask;
+use self::show_series_cardinality::ShowSeriesCardinalityTask;
+use self::show_streams::ShowStreamsTask;
 use crate::execution::ddl::alter_database::AlterDatabaseTask;
 use crate::execution::ddl::alter_table::AlterTableTask;
 use crate::execution::ddl::checksum_group::ChecksumGroupTask;
@@ -38,18 +47,23 @@ mod alter_database;
 mod alter_table;
 mod alter_tenant;
 mod alter_user;
+mod backup_database;
 mod checksum_group;
 mod compact_vnode;
 mod copy_vnode;
 mod create_database;
 mod create_external_table;
 mod create_role;
+mod create_rollup;
+mod create_stream;
 mod create_stream_table;
 mod create_table;
 mod create_tenant;
 mod create_user;
 mod drop_database_object;
 mod drop_global_object;
+mod drop_rollup;
+mod drop_stream;
 mod drop_tenant_object;
 mod drop_vnode;
 mod grant_revoke;
@@ -60,7 +74,11 @@ mod replica_add;
 mod replica_destory;
 mod replica_promote;
 mod replica_remove;
+mod restore_database;
 mod show_replica;
+mod show_rollups;
+mod show_series_cardinality;
+mod show_streams;
 
 /// Traits that DDL tasks should implement
 #[async_trait]
@@ -181,6 +199,10 @@ impl DDLDefinitionTaskFactory {
             DDLPlan::ChecksumGroup(sub_plan) => {
                 Box::new(ChecksumGroupTask::new(sub_plan.clone(), self.plan.schema()))
             }
+            DDLPlan::ShowSeriesCardinality(sub_plan) => Box::new(ShowSeriesCardinalityTask::new(
+                sub_plan.clone(),
+                self.plan.schema(),
+            )),
             DDLPlan::CreateStreamTable(sub_plan) => {
                 let checker = self.stream_checker_manager.checker(&sub_plan.stream_type);
 
@@ -190,6 +212,12 @@ impl DDLDefinitionTaskFactory {
                 Box::new(RecoverDatabaseTask::new(sub_plan.clone()))
             }
             DDLPlan::RecoverTenant(sub_plan) => Box::new(RecoverTenantTask::new(sub_plan.clone())),
+            DDLPlan::BackupDatabase(sub_plan) => {
+                Box::new(BackupDatabaseTask::new(sub_plan.clone()))
+            }
+            DDLPlan::RestoreDatabase(sub_plan) => {
+                Box::new(RestoreDatabaseTask::new(sub_plan.clone()))
+            }
             DDLPlan::ShowReplicas => Box::new(ShowReplicasTask::new()),
             DDLPlan::ReplicaDestory(sub_plan) => {
                 Box::new(ReplicaDestoryTask::new(sub_plan.clone()))
@@ -199,6 +227,12 @@ impl DDLDefinitionTaskFactory {
             DDLPlan::ReplicaPromote(sub_plan) => {
                 Box::new(ReplicaPromoteTask::new(sub_plan.clone()))
             }
+            DDLPlan::CreateRollup(sub_plan) => Box::new(CreateRollupTask::new(sub_plan.clone())),
+            DDLPlan::DropRollup(sub_plan) => Box::new(DropRollupTask::new(sub_plan.clone())),
+            DDLPlan::ShowRollups => Box::new(ShowRollupsTask::new()),
+            DDLPlan::CreateStream(sub_plan) => Box::new(CreateStreamTask::new(sub_plan.clone())),
+            DDLPlan::DropStream(sub_plan) => Box::new(DropStreamTask::new(sub_plan.clone())),
+            DDLPlan::ShowStreams(sub_plan) => Box::new(ShowStreamsTask::new(sub_plan.clone())),
         }
     }
 }
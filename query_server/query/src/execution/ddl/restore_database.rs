@@ -0,0 +1,331 @@
+use std::collections::BTreeSet;
+use std::mem;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
+use coordinator::resource_manager::ResourceManager;
+use coordinator::service::CoordinatorRef;
+use futures::StreamExt;
+use meta::error::MetaError;
+use meta::model::MetaClientRef;
+use models::meta_data::{ReplicationSetId, VnodeInfo};
+use models::oid::Identifier;
+use models::schema::database_schema::{split_owner, DatabaseSchema};
+use models::schema::resource_info::{ResourceInfo, ResourceOperator};
+use models::schema::table_schema::TableSchema;
+use models::utils::now_timestamp_nanos;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::RestoreDatabase;
+use spi::{CoordinatorSnafu, MetaSnafu, ObjectStoreSnafu, QueryError, QueryResult};
+use trace::{error, info};
+use tskv::VnodeSnapshot;
+
+use super::backup_database::{file_path, read_manifest, BackupManifest};
+use super::DDLDefinitionTask;
+
+/// Max size of the chunks that the files are sent to the data nodes in.
+const FILE_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+pub struct RestoreDatabaseTask {
+    stmt: RestoreDatabase,
+}
+
+impl RestoreDatabaseTask {
+    #[inline(always)]
+    pub fn new(stmt: RestoreDatabase) -> Self {
+        Self { stmt }
+    }
+
+    /// Send the files of the snapshot to the node of the vnode, then restore the vnode.
+    async fn restore_vnode(
+        &self,
+        coord: &CoordinatorRef,
+        replica_id: ReplicationSetId,
+        vnode: &VnodeInfo,
+        snapshot: &VnodeSnapshot,
+    ) -> QueryResult<()> {
+        let RestoreDatabase {
+            tenant_name,
+            db_name,
+            location,
+            until,
+        } = &self.stmt;
+
+        for file in snapshot.version_edit.add_files.iter() {
+            let relative_path = file.relative_path();
+            let src = file_path(&location.path, snapshot, &relative_path);
+            let mut stream = location
+                .store
+                .get(&src)
+                .await
+                .map_err(|e| ObjectStoreSnafu { msg: e.to_string() }.build())?
+                .into_stream();
+
+            let relative_path = relative_path.to_string_lossy();
+            let mut offset = 0_u64;
+            let mut chunk = Vec::with_capacity(FILE_CHUNK_SIZE);
+            loop {
+                let bytes = stream.next().await.transpose().map_err(|e| {
+                    ObjectStoreSnafu {
+                        msg: format!("read {src}: {e}"),
+                    }
+                    .build()
+                })?;
+                let finished = bytes.is_none();
+                if let Some(bytes) = bytes {
+                    chunk.extend_from_slice(&bytes);
+                }
+                // An empty chunk is sent for empty files to create them.
+                if chunk.len() >= FILE_CHUNK_SIZE
+                    || (finished && (!chunk.is_empty() || offset == 0))
+                {
+                    let data = mem::replace(&mut chunk, Vec::with_capacity(FILE_CHUNK_SIZE));
+                    let length = data.len() as u64;
+                    coord
+                        .stage_vnode_file(tenant_name, vnode, &relative_path, offset, data)
+                        .await
+                        .context(CoordinatorSnafu)?;
+                    offset += length;
+                }
+                if finished {
+                    break;
+                }
+            }
+
+            if offset != file.file_size {
+                return Err(ObjectStoreSnafu {
+                    msg: format!(
+                        "backup file {src} length not match {} -> {offset}",
+                        file.file_size
+                    ),
+                }
+                .build());
+            }
+        }
+
+        coord
+            .restore_vnode(tenant_name, db_name, replica_id, vnode, snapshot, *until)
+            .await
+            .context(CoordinatorSnafu)
+    }
+
+    /// Check the backup before anything is created.
+    async fn check_backup(&self, manifest: &BackupManifest) -> QueryResult<()> {
+        let location = &self.stmt.location;
+        let shard_num = manifest.database.options().shard_num() as usize;
+        for bucket in manifest.buckets.iter() {
+            if bucket.shards.len() != shard_num {
+                return Err(QueryError::Semantic {
+                    err: format!(
+                        "Bucket {}~{} of the backup has {} shards, but the database has {shard_num}",
+                        bucket.start_time,
+                        bucket.end_time,
+                        bucket.shards.len(),
+                    ),
+                });
+            }
+        }
+
+        for (path, size) in manifest.files(&location.path) {
+            let object = location.store.head(&path).await.map_err(|e| {
+                ObjectStoreSnafu {
+                    msg: format!("read {path}: {e}"),
+                }
+                .build()
+            })?;
+            if object.size as u64 != size {
+                return Err(ObjectStoreSnafu {
+                    msg: format!(
+                        "backup file {path} length not match {size} -> {}",
+                        object.size
+                    ),
+                }
+                .build());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Create the tables and buckets of the created database, then restore the vnodes.
+    async fn restore_data(
+        &self,
+        meta: &MetaClientRef,
+        coord: &CoordinatorRef,
+        manifest: BackupManifest,
+    ) -> QueryResult<()> {
+        let RestoreDatabase {
+            tenant_name,
+            db_name,
+            ..
+        } = &self.stmt;
+
+        for mut table in manifest.tables {
+            table.tenant = tenant_name.to_string();
+            table.db = db_name.to_string();
+            meta.create_table(&TableSchema::TsKvTableSchema(Arc::new(table)))
+                .await
+                .context(MetaSnafu)?;
+        }
+
+        for bucket in manifest.buckets.iter() {
+            let new_bucket = meta
+                .create_bucket(db_name, bucket.start_time)
+                .await
+                .context(MetaSnafu)?;
+            if new_bucket.shard_group.len() != bucket.shards.len() {
+                return Err(QueryError::Semantic {
+                    err: format!(
+                        "Bucket {}~{} of the backup has {} shards, but the restored one has {}",
+                        bucket.start_time,
+                        bucket.end_time,
+                        bucket.shards.len(),
+                        new_bucket.shard_group.len()
+                    ),
+                });
+            }
+
+            for (replica, snapshot) in new_bucket.shard_group.iter().zip(bucket.shards.iter()) {
+                if let Some(snapshot) = snapshot {
+                    for vnode in replica.vnodes.iter() {
+                        self.restore_vnode(coord, replica.id, vnode, snapshot)
+                            .await?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Drop the database that failed to restore like `DROP DATABASE`, so that it can be
+    /// restored again.
+    async fn drop_database(&self, meta: &MetaClientRef, coord: &CoordinatorRef) -> QueryResult<()> {
+        let RestoreDatabase {
+            tenant_name,
+            db_name,
+            ..
+        } = &self.stmt;
+
+        meta.set_db_is_hidden(tenant_name, db_name, true)
+            .await
+            .context(MetaSnafu)?;
+        let resource_info = ResourceInfo::new(
+            (*meta.tenant().id(), db_name.to_string()),
+            tenant_name.clone() + "-" + db_name,
+            ResourceOperator::DropDatabase(tenant_name.clone(), db_name.clone()),
+            &None,
+            coord.node_id(),
+        );
+        ResourceManager::add_resource_task(coord.clone(), resource_info)
+            .await
+            .context(CoordinatorSnafu)
+    }
+
+    /// Archive the WAL files that the vnodes of the snapshots are writing, so that the raft
+    /// logs proposed until now can be replayed. The WAL files of the vnodes that are dropped
+    /// have been archived when they were closed.
+    async fn archive_source_wal(
+        &self,
+        coord: &CoordinatorRef,
+        manifest: &BackupManifest,
+    ) -> QueryResult<()> {
+        let sources = manifest
+            .buckets
+            .iter()
+            .flat_map(|b| b.shards.iter().flatten())
+            .map(|snapshot| (snapshot.version_edit.tsf_name.as_str(), snapshot.vnode_id))
+            .collect::<BTreeSet<_>>();
+        for (owner, vnode_id) in sources {
+            let (tenant, _) = split_owner(owner);
+            if !coord
+                .archive_vnode_wal(tenant, vnode_id)
+                .await
+                .context(CoordinatorSnafu)?
+            {
+                info!("Vnode {vnode_id} of {owner} is not found, its wal has been archived");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for RestoreDatabaseTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let RestoreDatabase {
+            tenant_name,
+            db_name,
+            location,
+            until,
+        } = &self.stmt;
+
+        let manifest = read_manifest(location)
+            .await?
+            .ok_or_else(|| QueryError::Semantic {
+                err: format!("No backup found in {}", location.path),
+            })?;
+        if until.is_some_and(|until| until < manifest.create_time) {
+            return Err(QueryError::Semantic {
+                err: format!(
+                    "Can't restore until a time before the backup was created at {}",
+                    Utc.timestamp_nanos(manifest.create_time).to_rfc3339()
+                ),
+            });
+        }
+        if until.is_some_and(|until| until > now_timestamp_nanos()) {
+            return Err(QueryError::Semantic {
+                err: "Can't restore until a time in the future".to_string(),
+            });
+        }
+        let meta = query_state_machine
+            .meta
+            .tenant_meta(tenant_name)
+            .await
+            .ok_or_else(|| QueryError::Meta {
+                source: MetaError::TenantNotFound {
+                    tenant: tenant_name.to_string(),
+                },
+            })?;
+
+        // Nothing is created until the backup is checked, a broken backup must not leave a
+        // partially restored database.
+        if meta.get_db_schema(db_name).context(MetaSnafu)?.is_some() {
+            return Err(QueryError::Meta {
+                source: MetaError::DatabaseAlreadyExists {
+                    database: db_name.to_string(),
+                },
+            });
+        }
+        self.check_backup(&manifest).await?;
+        let coord = query_state_machine.coord.clone();
+        if until.is_some() {
+            self.archive_source_wal(&coord, &manifest).await?;
+        }
+
+        // The database is restored with the options of the backup, so the buckets
+        // get the same time ranges and number of shards.
+        let schema = DatabaseSchema::new(
+            tenant_name,
+            db_name,
+            manifest.database.options().clone(),
+            manifest.database.config(),
+        );
+        meta.create_db(schema).await.context(MetaSnafu)?;
+        if let Err(e) = self.restore_data(&meta, &coord, manifest).await {
+            if let Err(drop_err) = self.drop_database(&meta, &coord).await {
+                error!("Failed to drop {tenant_name}.{db_name} after restore failed: {drop_err}");
+            }
+            return Err(e);
+        }
+        info!(
+            "Restore database {tenant_name}.{db_name} from {}, until: {until:?}",
+            location.path
+        );
+
+        Ok(Output::Nil(()))
+    }
+}
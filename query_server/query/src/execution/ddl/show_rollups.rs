@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{StringArray, UInt64Array};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use meta::error::MetaError;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{MetaSnafu, QueryResult};
+
+use crate::execution::ddl::DDLDefinitionTask;
+
+pub struct ShowRollupsTask {}
+
+impl ShowRollupsTask {
+    pub fn new() -> Self {
+        ShowRollupsTask {}
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for ShowRollupsTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        show_rollups(query_state_machine).await
+    }
+}
+
+async fn show_rollups(machine: QueryStateMachineRef) -> QueryResult<Output> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("rollup_name", DataType::Utf8, false),
+        Field::new("database_name", DataType::Utf8, false),
+        Field::new("source_table", DataType::Utf8, false),
+        Field::new("target_table", DataType::Utf8, false),
+        Field::new("interval", DataType::Utf8, false),
+        Field::new("watermark", DataType::Utf8, false),
+        Field::new("ttl", DataType::Utf8, true),
+        Field::new("node_id", DataType::UInt64, true),
+        Field::new("status", DataType::Utf8, true),
+        Field::new("aggregated_until", DataType::Utf8, true),
+        Field::new("query", DataType::Utf8, false),
+    ]));
+
+    let tenant = machine.session.tenant();
+    let client = machine
+        .meta
+        .tenant_meta(tenant)
+        .await
+        .ok_or_else(|| MetaError::TenantNotFound {
+            tenant: tenant.to_string(),
+        })
+        .context(MetaSnafu)?;
+
+    let mut rollups = client.rollups().await.context(MetaSnafu)?;
+    rollups.sort_by(|a, b| (&a.database_name, &a.name).cmp(&(&b.database_name, &b.name)));
+    // The rollups are run by the streams of the same names.
+    let streams = client
+        .streams()
+        .await
+        .context(MetaSnafu)?
+        .into_iter()
+        .map(|s| ((s.database_name.clone(), s.name.clone()), s))
+        .collect::<HashMap<_, _>>();
+    let streams = rollups
+        .iter()
+        .map(|r| streams.get(&(r.database_name.clone(), r.name.clone())))
+        .collect::<Vec<_>>();
+
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from_iter_values(
+                rollups.iter().map(|r| r.name.as_str()),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                rollups.iter().map(|r| r.database_name.as_str()),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                rollups.iter().map(|r| r.source_table.as_str()),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                rollups.iter().map(|r| r.target_table.as_str()),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                rollups.iter().map(|r| r.interval.to_string()),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                rollups.iter().map(|r| r.delay.to_string()),
+            )),
+            Arc::new(StringArray::from_iter(
+                rollups
+                    .iter()
+                    .map(|r| r.ttl.as_ref().map(|ttl| ttl.to_string())),
+            )),
+            Arc::new(UInt64Array::from_iter(
+                streams.iter().map(|s| s.map(|s| s.node_id)),
+            )),
+            Arc::new(StringArray::from_iter(
+                streams.iter().map(|s| s.map(|s| s.status.to_string())),
+            )),
+            Arc::new(StringArray::from_iter(streams.iter().map(|s| {
+                s.and_then(|s| s.watermark_ns).map(timestamp_to_string)
+            }))),
+            Arc::new(StringArray::from_iter_values(
+                rollups.iter().map(|r| r.query.as_str()),
+            )),
+        ],
+    )?;
+
+    Ok(Output::StreamData(Box::pin(RecordBatchStreamWrapper::new(
+        schema,
+        vec![batch],
+    ))))
+}
+
+pub(super) fn timestamp_to_string(nanos: i64) -> String {
+    if let Some(datetime) = chrono::NaiveDateTime::from_timestamp_nanos(nanos) {
+        format!("{}", datetime.and_utc())
+    } else {
+        nanos.to_string()
+    }
+}
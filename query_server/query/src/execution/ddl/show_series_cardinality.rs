@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{ArrayRef, StringArray, UInt64Array};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use meta::error::MetaError;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::ShowSeriesCardinality;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{CoordinatorSnafu, MetaSnafu, QueryError, QueryResult};
+use tskv::index::cardinality::CardinalityOptions;
+
+use super::DDLDefinitionTask;
+
+pub struct ShowSeriesCardinalityTask {
+    schema: SchemaRef,
+    stmt: ShowSeriesCardinality,
+}
+
+impl ShowSeriesCardinalityTask {
+    #[inline(always)]
+    pub fn new(stmt: ShowSeriesCardinality, schema: SchemaRef) -> Self {
+        Self { schema, stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for ShowSeriesCardinalityTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let ShowSeriesCardinality {
+            tenant_name,
+            db_name,
+            table,
+            group_by,
+            exact,
+            tag_keys,
+        } = &self.stmt;
+
+        let meta = query_state_machine
+            .meta
+            .tenant_meta(tenant_name)
+            .await
+            .ok_or_else(|| QueryError::Meta {
+                source: MetaError::TenantNotFound {
+                    tenant: tenant_name.to_string(),
+                },
+            })?;
+        if meta.get_db_info(db_name).context(MetaSnafu)?.is_none() {
+            return Err(QueryError::DatabaseNotFound {
+                name: db_name.to_string(),
+            });
+        }
+
+        let options = CardinalityOptions {
+            table: table.clone(),
+            group_by: group_by.clone(),
+            exact: *exact,
+            tag_keys: *tag_keys,
+        };
+        let groups = query_state_machine
+            .coord
+            .series_cardinality(tenant_name, db_name, &options)
+            .await
+            .context(CoordinatorSnafu)?;
+
+        let mut columns: Vec<ArrayRef> = vec![Arc::new(StringArray::from_iter_values(
+            groups.iter().map(|(table, _, _)| table.as_str()),
+        ))];
+        if group_by.is_some() {
+            columns.push(Arc::new(StringArray::from_iter(
+                groups.iter().map(|(_, tag_value, _)| tag_value.as_deref()),
+            )));
+        }
+        columns.push(Arc::new(UInt64Array::from_iter_values(
+            groups.iter().map(|(_, _, count)| *count),
+        )));
+        let batch = RecordBatch::try_new(self.schema.clone(), columns)?;
+
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), vec![batch]);
+        Ok(Output::StreamData(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,124 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{ArrayRef, StringArray, UInt64Array};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use meta::error::MetaError;
+use models::utils::now_timestamp_nanos;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::ShowStreams;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{MetaSnafu, QueryResult};
+use utils::duration::CnosDuration;
+
+use super::show_rollups::timestamp_to_string;
+use crate::execution::ddl::DDLDefinitionTask;
+
+pub struct ShowStreamsTask {
+    stmt: ShowStreams,
+}
+
+impl ShowStreamsTask {
+    pub fn new(stmt: ShowStreams) -> Self {
+        ShowStreamsTask { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for ShowStreamsTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        show_streams(query_state_machine, self.stmt.verbose).await
+    }
+}
+
+async fn show_streams(machine: QueryStateMachineRef, verbose: bool) -> QueryResult<Output> {
+    let mut fields = vec![
+        Field::new("stream_name", DataType::Utf8, false),
+        Field::new("database_name", DataType::Utf8, false),
+        Field::new("status", DataType::Utf8, false),
+        Field::new("node_id", DataType::UInt64, false),
+        Field::new("lag", DataType::Utf8, true),
+        Field::new("error", DataType::Utf8, true),
+    ];
+    if verbose {
+        fields.extend([
+            Field::new("trigger", DataType::Utf8, true),
+            Field::new("watermark", DataType::Utf8, true),
+            Field::new("processed_count", DataType::UInt64, false),
+            Field::new("error_count", DataType::UInt64, false),
+            Field::new("query_id", DataType::Utf8, false),
+            Field::new("query", DataType::Utf8, false),
+        ]);
+    }
+    let schema = Arc::new(Schema::new(fields));
+
+    let tenant = machine.session.tenant();
+    let client = machine
+        .meta
+        .tenant_meta(tenant)
+        .await
+        .ok_or_else(|| MetaError::TenantNotFound {
+            tenant: tenant.to_string(),
+        })
+        .context(MetaSnafu)?;
+
+    let mut streams = client.streams().await.context(MetaSnafu)?;
+    streams.sort_by(|a, b| (&a.database_name, &a.name).cmp(&(&b.database_name, &b.name)));
+
+    let now = now_timestamp_nanos();
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(
+            streams.iter().map(|s| s.name.as_str()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            streams.iter().map(|s| s.database_name.as_str()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            streams.iter().map(|s| s.status.to_string()),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            streams.iter().map(|s| s.node_id),
+        )),
+        Arc::new(StringArray::from_iter(streams.iter().map(|s| {
+            s.lag_ns(now).map(|lag| {
+                CnosDuration::new_with_duration(Duration::from_nanos(lag as u64)).to_string()
+            })
+        }))),
+        Arc::new(StringArray::from_iter(
+            streams.iter().map(|s| s.error.as_deref()),
+        )),
+    ];
+    if verbose {
+        columns.extend([
+            Arc::new(StringArray::from_iter(
+                streams.iter().map(|s| s.trigger.as_deref()),
+            )) as ArrayRef,
+            Arc::new(StringArray::from_iter(
+                streams
+                    .iter()
+                    .map(|s| s.watermark_ns.map(timestamp_to_string)),
+            )),
+            Arc::new(UInt64Array::from_iter_values(
+                streams.iter().map(|s| s.processed_count),
+            )),
+            Arc::new(UInt64Array::from_iter_values(
+                streams.iter().map(|s| s.error_count),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                streams.iter().map(|s| s.query_id.to_string()),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                streams.iter().map(|s| s.query.as_str()),
+            )),
+        ]);
+    }
+    let batch = RecordBatch::try_new(schema.clone(), columns)?;
+
+    Ok(Output::StreamData(Box::pin(RecordBatchStreamWrapper::new(
+        schema,
+        vec![batch],
+    ))))
+}
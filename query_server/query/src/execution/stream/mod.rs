@@ -27,11 +27,13 @@ use trace::error;
 
 use self::trigger::executor::{TriggerExecutorFactoryRef, TriggerExecutorRef};
 use crate::extension::analyse::stream_checker::UnsupportedOperationChecker;
+use crate::extension::analyse::transform_time_window::open_windows_start;
 use crate::extension::analyse::AnalyzerRule;
 use crate::extension::logical::utils::extract_stream_providers;
 use crate::extension::physical::optimizer_rule::add_state_store::AddStateStore;
 use crate::extension::physical::transform_rule::stream_scan::StreamScanPlanner;
 use crate::extension::physical::transform_rule::watermark::WatermarkPlanner;
+use crate::extension::physical::transform_rule::window_state::WindowStatePlanner;
 use crate::sql::logical::optimizer::{DefaultLogicalOptimizer, LogicalOptimizer};
 use crate::sql::physical::optimizer::PhysicalOptimizer;
 use crate::sql::physical::planner::DefaultPhysicalPlanner;
@@ -115,6 +117,16 @@ impl MicroBatchStreamExecutionBuilder {
             )
             .await?,
         );
+        if let Some(watermark_ns) = query_state_machine.query.context().stream_watermark_ns() {
+            watermark_tracker.resume(watermark_ns);
+        }
+        let offset_tracker = Arc::new(OffsetTracker::new());
+        resume_offsets(
+            &plan,
+            &stream_providers,
+            &watermark_tracker,
+            &offset_tracker,
+        )?;
 
         Ok(MicroBatchStreamExecution {
             query_state_machine,
@@ -123,7 +135,7 @@ impl MicroBatchStreamExecutionBuilder {
             scheduler,
             trigger_executor,
             watermark_tracker,
-            offset_tracker: Arc::new(OffsetTracker::new()),
+            offset_tracker,
             state_store_factory: Arc::new(MemoryStateStoreFactory::default()),
             runtime,
             abort_handle: Mutex::new(None),
@@ -187,6 +199,33 @@ impl MicroBatchStreamExecution {
     }
 }
 
+/// Skips the data that has been processed before the stream is resumed, the data of the
+/// windows still open is read again since their state is not kept.
+fn resume_offsets(
+    plan: &QueryPlan,
+    stream_providers: &[StreamProviderRef],
+    watermark_tracker: &WatermarkTracker,
+    offset_tracker: &OffsetTracker,
+) -> QueryResult<()> {
+    let watermark_ns = watermark_tracker.current_watermark_ns();
+    if watermark_ns == i64::MIN {
+        return Ok(());
+    }
+
+    let event_time_ns = match open_windows_start(&plan.df_plan, watermark_ns)? {
+        Some(event_time_ns) => event_time_ns,
+        None => return Ok(()),
+    };
+    for s in stream_providers {
+        if let Some(offset) = s.resumed_offset(event_time_ns) {
+            trace::debug!("Resume stream source {} from offset {}", s.id(), offset);
+            offset_tracker.resume_processed_offset(s.id(), offset);
+        }
+    }
+
+    Ok(())
+}
+
 async fn update_available_offsets(
     offset_tracker: OffsetTrackerRef,
     stream_providers: &[StreamProviderRef],
@@ -268,6 +307,10 @@ impl QueryExecution for MicroBatchStreamExecution {
         )
         .with_processed_count(self.trigger_executor.processed_count())
         .with_error_count(self.trigger_executor.error_count())
+        .with_last_error(self.trigger_executor.last_error())
+        .with_watermark_ns(
+            Some(self.watermark_tracker.current_watermark_ns()).filter(|ns| *ns != i64::MIN),
+        )
         .build()
     }
 
@@ -331,11 +374,16 @@ where
         trace::trace!(
             "Traverse and replace the TableScan nodes in the execution plan according to the mapping from the data source to the offset range"
         );
-        phy_planner
-            .inject_physical_transform_rule(Arc::new(StreamScanPlanner::new(available_offsets)));
+        phy_planner.inject_physical_transform_rule(Arc::new(StreamScanPlanner::new(
+            available_offsets.clone(),
+        )));
         phy_planner.inject_physical_transform_rule(Arc::new(WatermarkPlanner::new(
             self.watermark_tracker.clone(),
         )));
+        phy_planner.inject_physical_transform_rule(Arc::new(WindowStatePlanner::new(
+            current_watermark_ns,
+            self.state_store_factory.clone(),
+        )));
 
         phy_planner.inject_optimizer_rule(Arc::new(AddStateStore::new(
             current_watermark_ns,
@@ -359,6 +407,13 @@ where
             trace::trace!("Receive an item, num rows: {}", batch.num_rows());
         }
 
+        // 5. Inform the sources that the data of the offset ranges has been processed
+        for s in &self.stream_providers {
+            if let Some((_, end)) = available_offsets.get(&s.id()) {
+                s.commit(*end).await?;
+            }
+        }
+
         // 6. Record the commit log after the execution is complete
         trace::trace!("Record the commit log after the execution is complete");
         let after_process_watermark_ns = self.watermark_tracker.current_watermark_ns();
@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use futures::Future;
 use models::runtime::executor::{DedicatedExecutor, Job};
+use parking_lot::Mutex;
 use spi::query::config::StreamTriggerInterval;
 use spi::QueryError;
 
@@ -23,6 +24,7 @@ impl TriggerExecutorFactory {
             runtime: self.runtime.clone(),
             processed_count: Default::default(),
             err_counter: Default::default(),
+            last_error: Default::default(),
         })
     }
 }
@@ -40,6 +42,7 @@ pub struct TriggerExecutor {
     runtime: Arc<DedicatedExecutor>,
     processed_count: Arc<AtomicU64>,
     err_counter: Arc<AtomicU64>,
+    last_error: Arc<Mutex<Option<String>>>,
 }
 
 impl TriggerExecutor {
@@ -54,13 +57,15 @@ impl TriggerExecutor {
         let fetch_add_batch_id = move || current_batch_id.fetch_add(1, Ordering::Relaxed);
         let processed_count = self.processed_count.clone();
         let err_counter = self.err_counter.clone();
+        let last_error = self.last_error.clone();
 
         match self.trigger {
             StreamTriggerInterval::Once => self.runtime.spawn(async move {
                 match task(fetch_add_batch_id()).await {
                     Ok(_) => {}
-                    Err(_) => {
+                    Err(err) => {
                         let _ = err_counter.fetch_add(1, Ordering::Relaxed);
+                        *last_error.lock() = Some(err.to_string());
                     }
                 }
                 let _ = processed_count.fetch_add(1, Ordering::Relaxed);
@@ -68,12 +73,15 @@ impl TriggerExecutor {
             StreamTriggerInterval::Interval(d) => self.runtime.spawn(async move {
                 let mut ticker = tokio::time::interval(d);
                 loop {
-                    match runtime.spawn(task(fetch_add_batch_id())).await {
-                        Ok(Ok(_)) => {}
-                        _ => {
-                            // Record failed status
-                            let _ = err_counter.fetch_add(1, Ordering::Relaxed);
-                        }
+                    let err = match runtime.spawn(task(fetch_add_batch_id())).await {
+                        Ok(Ok(_)) => None,
+                        Ok(Err(err)) => Some(err.to_string()),
+                        Err(err) => Some(err),
+                    };
+                    if let Some(err) = err {
+                        // Record failed status
+                        let _ = err_counter.fetch_add(1, Ordering::Relaxed);
+                        *last_error.lock() = Some(err);
                     }
                     let _ = processed_count.fetch_add(1, Ordering::Relaxed);
                     ticker.tick().await;
@@ -89,4 +97,8 @@ impl TriggerExecutor {
     pub fn error_count(&self) -> u64 {
         self.err_counter.load(Ordering::Relaxed)
     }
+
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().clone()
+    }
 }
@@ -2,31 +2,50 @@ use std::time::Duration;
 
 use datafusion::arrow::datatypes::{DataType, TimeUnit};
 use datafusion::common::scalar::{dt_to_nano, mdn_to_nano, ym_to_nano};
-use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::tree_node::{Transformed, TreeNode, VisitRecursion};
 use datafusion::common::DFSchemaRef;
 use datafusion::config::ConfigOptions;
 use datafusion::error::{DataFusionError, Result};
 use datafusion::execution::context::ExecutionProps;
+use datafusion::logical_expr::expr::Sort;
 use datafusion::logical_expr::utils::expand_wildcard;
-use datafusion::logical_expr::{expr, GetIndexedField, LogicalPlan, LogicalPlanBuilder};
+use datafusion::logical_expr::{
+    expr, window_function, AggregateFunction, Extension, GetIndexedField, LogicalPlan,
+    LogicalPlanBuilder, WindowFrame,
+};
 use datafusion::optimizer::analyzer::AnalyzerRule;
 use datafusion::optimizer::simplify_expressions::{ExprSimplifier, SimplifyContext};
 use datafusion::optimizer::{OptimizerConfig, OptimizerContext};
-use datafusion::prelude::{and, cast, col, lit, Expr};
+use datafusion::prelude::{and, cast, col, lit, or, when, Expr};
 use datafusion::scalar::ScalarValue;
 use models::duration::DAY;
+use models::schema::TIME_FIELD_NAME;
 use spi::QueryError;
 use trace::debug;
 
-use crate::extension::expr::expr_fn::{ge, is_not_null, lt, minus, modulo, multiply, plus};
+use crate::extension::expr::expr_fn::{
+    divide, ge, gt, is_not_null, is_null, lt, minus, modulo, multiply, plus,
+};
 use crate::extension::expr::expr_utils::find_exprs_in_exprs_deeply_nested;
 use crate::extension::expr::{
-    DEFAULT_TIME_WINDOW_START, TIME_WINDOW, WINDOW_COL_NAME, WINDOW_END, WINDOW_START,
+    COUNT_WINDOW, DEFAULT_TIME_WINDOW_START, SESSION_WINDOW, TIME_WINDOW, WINDOW_COL_NAME,
+    WINDOW_END, WINDOW_START,
 };
 use crate::extension::logical::logical_plan_builder::LogicalPlanBuilderExt;
+use crate::extension::logical::plan_node::watermark::WatermarkNode;
+use crate::extension::logical::plan_node::window_state::StatefulWindow;
 use crate::extension::logical::plan_node::LogicalPlanExt;
+use crate::extension::utils::downcast_plan_node;
+
+/// Names of the intermediate columns of the session windows and count windows
+const PREV_TIME_COL_NAME: &str = "_prev_time";
+const SESSION_ID_COL_NAME: &str = "_session_id";
+const ROW_NUMBER_COL_NAME: &str = "_row_number";
+const WINDOW_START_COL_NAME: &str = "_window_start";
+const WINDOW_END_COL_NAME: &str = "_window_end";
 
-/// Convert the [`TIME_WINDOW`] function to Expand or project
+/// Convert the [`TIME_WINDOW`] function to Expand or project,
+/// and the [`SESSION_WINDOW`] and [`COUNT_WINDOW`] functions to Window operators
 pub struct TransformTimeWindowRule;
 
 impl AnalyzerRule for TransformTimeWindowRule {
@@ -52,17 +71,46 @@ fn analyze_internal(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
 
         if window_expressions.len() == 1 {
             let window_expr = window_expressions.remove(0);
-            let window = make_time_window(window_expr, plan.schema().clone())
-                .map_err(|e| DataFusionError::External(Box::new(e)))?;
-
-            debug!("Construct time window: {:?}", window);
-
-            let window_plan = if window.is_tumbling_window() {
-                // tumbling_window
-                build_tumbling_window_plan(&window, child.clone(), child_project_exprs)?
-            } else {
-                // sliding_window
-                build_sliding_window_plan(&window, child.clone(), child_project_exprs)?
+            let window_func_name = match &window_expr {
+                Expr::ScalarUDF(expr::ScalarUDF { fun, .. }) => fun.name.clone(),
+                _ => String::new(),
+            };
+            let (window_alias, window_plan) = match window_func_name.as_str() {
+                SESSION_WINDOW => {
+                    let window = make_session_window(window_expr, &plan)
+                        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+                    debug!("Construct session window: {:?}", window);
+
+                    let window_plan =
+                        build_session_window_plan(&window, child.clone(), child_project_exprs)?;
+                    (window.window_alias, window_plan)
+                }
+                COUNT_WINDOW => {
+                    let window = make_count_window(window_expr, &plan)
+                        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+                    debug!("Construct count window: {:?}", window);
+
+                    let window_plan =
+                        build_count_window_plan(&window, child.clone(), child_project_exprs)?;
+                    (window.window_alias, window_plan)
+                }
+                _ => {
+                    let window = make_time_window(window_expr, plan.schema().clone())
+                        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+                    debug!("Construct time window: {:?}", window);
+
+                    let window_plan = if window.is_tumbling_window() {
+                        // tumbling_window
+                        build_tumbling_window_plan(&window, child.clone(), child_project_exprs)?
+                    } else {
+                        // sliding_window
+                        build_sliding_window_plan(&window, child.clone(), child_project_exprs)?
+                    };
+                    (window.window_alias, window_plan)
+                }
             };
 
             debug!("Origin plan: {}", plan.display_indent_schema());
@@ -78,7 +126,7 @@ fn analyze_internal(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
 
             // replace new plan's exprs
             let final_plan = replace_window_expr(
-                col(WINDOW_COL_NAME).alias(&window.window_alias),
+                col(WINDOW_COL_NAME).alias(window_alias),
                 &wait_replaced_plan,
             )?;
 
@@ -91,14 +139,63 @@ fn analyze_internal(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
     Ok(Transformed::No(plan))
 }
 
+fn is_window_expr(expr: &Expr) -> bool {
+    matches!(expr, Expr::ScalarUDF(expr::ScalarUDF {
+        fun,
+        ..
+    }) if [TIME_WINDOW, SESSION_WINDOW, COUNT_WINDOW].contains(&fun.name.as_str()))
+}
+
 fn find_window_exprs(plan: &LogicalPlan) -> Vec<Expr> {
     let exprs = plan.expressions();
-    find_exprs_in_exprs_deeply_nested(&exprs, &|nested_expr| {
-        matches!(nested_expr, Expr::ScalarUDF(expr::ScalarUDF {
-            fun,
-            ..
-        }) if fun.name == TIME_WINDOW)
-    })
+    find_exprs_in_exprs_deeply_nested(&exprs, &is_window_expr)
+}
+
+/// Returns the event time after which the data is needed by the windows still open at the
+/// watermark, None if the windows of the plan are not bounded by time.
+pub fn open_windows_start(plan: &LogicalPlan, watermark_ns: i64) -> Result<Option<i64>> {
+    let mut window_exprs = vec![];
+    plan.apply(&mut |plan| {
+        window_exprs.extend(find_window_exprs(plan));
+        Ok(VisitRecursion::Continue)
+    })?;
+
+    let mut max_duration = Duration::ZERO;
+    for window_expr in window_exprs {
+        match window_expr {
+            Expr::ScalarUDF(expr::ScalarUDF { fun, args }) if fun.name == TIME_WINDOW => {
+                let duration = args
+                    .get(1)
+                    .map(parse_duration_arg)
+                    .transpose()
+                    .map_err(|e| DataFusionError::External(Box::new(e)))?
+                    .unwrap_or_default();
+                max_duration = max_duration.max(duration);
+            }
+            // session windows and count windows
+            _ => return Ok(None),
+        }
+    }
+
+    Ok(Some(
+        watermark_ns.saturating_sub(max_duration.as_nanos() as i64),
+    ))
+}
+
+/// The windows are computed separately for each group of the other group keys.
+fn window_partition_keys(plan: &LogicalPlan) -> Vec<Expr> {
+    match plan {
+        LogicalPlan::Aggregate(aggregate) => aggregate
+            .group_expr
+            .iter()
+            .filter(|e| !matches!(e, Expr::GroupingSet(_)))
+            .filter(|e| {
+                find_exprs_in_exprs_deeply_nested(&[(*e).clone()], &is_window_expr).is_empty()
+            })
+            .cloned()
+            .collect(),
+        _ => vec![],
+    }
 }
 
 fn make_time_window(expr: Expr, schema: DFSchemaRef) -> Result<TimeWindow, QueryError> {
@@ -140,6 +237,62 @@ fn make_time_window(expr: Expr, schema: DFSchemaRef) -> Result<TimeWindow, Query
     }
 }
 
+fn make_session_window(expr: Expr, plan: &LogicalPlan) -> Result<SessionWindow, QueryError> {
+    let window_alias = expr.display_name()?;
+    match expr {
+        Expr::ScalarUDF(expr::ScalarUDF { fun, args }) if fun.name == SESSION_WINDOW => {
+            // session_window(time, interval '5 minutes')
+            let [time_column, gap]: [Expr; 2] =
+                args.try_into().map_err(|_| QueryError::Internal {
+                    reason: format!("Invalid signature of {SESSION_WINDOW}"),
+                })?;
+            let gap = simplify_expr(gap, plan.schema().clone())?;
+            let gap = valid_duration(parse_duration_arg(&gap)?)?;
+
+            Ok(SessionWindow {
+                window_alias,
+                time_column,
+                gap,
+                partition_by: window_partition_keys(plan),
+            })
+        }
+        _ => Err(QueryError::Internal {
+            reason: format!("Expected SessionWindow, but found {expr}"),
+        }),
+    }
+}
+
+fn make_count_window(expr: Expr, plan: &LogicalPlan) -> Result<CountWindow, QueryError> {
+    let window_alias = expr.display_name()?;
+    match expr {
+        Expr::ScalarUDF(expr::ScalarUDF { fun, args }) if fun.name == COUNT_WINDOW => {
+            // count_window(100)
+            let [size]: [Expr; 1] = args.try_into().map_err(|_| QueryError::Internal {
+                reason: format!("Invalid signature of {COUNT_WINDOW}"),
+            })?;
+            let size = match simplify_expr(size, plan.schema().clone())? {
+                Expr::Literal(ScalarValue::Int64(Some(size))) if size > 0 => size,
+                other => {
+                    return Err(QueryError::InvalidTimeWindowParam {
+                        reason: format!("Expected positive window size, but found {other}"),
+                    })
+                }
+            };
+
+            Ok(CountWindow {
+                window_alias,
+                // The points are counted in the order of time
+                time_column: col(TIME_FIELD_NAME),
+                size,
+                partition_by: window_partition_keys(plan),
+            })
+        }
+        _ => Err(QueryError::Internal {
+            reason: format!("Expected CountWindow, but found {expr}"),
+        }),
+    }
+}
+
 fn valid_duration(dur: Duration) -> Result<Duration, QueryError> {
     if dur.as_millis() > (365 * DAY) as u128 || dur.as_millis() == 0 {
         return Err(QueryError::InvalidTimeWindowParam {
@@ -392,14 +545,252 @@ fn build_sliding_window_plan(
     Ok(expand_node)
 }
 
-/// Replace udf [`TIME_WINDOW`] with the specified expression
+/// A session window groups the points until there is no point for `gap`,
+/// it starts at the first point and ends `gap` after the last point.
+#[derive(Debug)]
+pub struct SessionWindow {
+    window_alias: String,
+    time_column: Expr,
+    gap: Duration,
+    partition_by: Vec<Expr>,
+}
+
+/// A count window groups every `size` points in the order of time,
+/// it starts at the first point and ends at the last point.
+#[derive(Debug)]
+pub struct CountWindow {
+    window_alias: String,
+    time_column: Expr,
+    size: i64,
+    partition_by: Vec<Expr>,
+}
+
+/// The frame of the window function ends at the current row if `order_by` is not empty.
+pub fn window_func_expr(
+    fun: window_function::WindowFunction,
+    args: Vec<Expr>,
+    partition_by: Vec<Expr>,
+    order_by: Vec<Expr>,
+) -> Expr {
+    let window_frame = WindowFrame::new(!order_by.is_empty());
+    Expr::WindowFunction(expr::WindowFunction::new(
+        fun,
+        args,
+        partition_by,
+        order_by,
+        window_frame,
+    ))
+}
+
+fn time_to_i64(time: Expr) -> Expr {
+    let ns_type = DataType::Timestamp(TimeUnit::Nanosecond, None);
+    cast(cast(time, ns_type), DataType::Int64)
+}
+
+/// The first and last time of the points with the same `partition_by` and `window_id`
+fn window_bounds_exprs(
+    time_column: &Expr,
+    mut partition_by: Vec<Expr>,
+    window_id: Expr,
+) -> Vec<Expr> {
+    partition_by.push(window_id);
+    let min = window_function::WindowFunction::AggregateFunction(AggregateFunction::Min);
+    let max = window_function::WindowFunction::AggregateFunction(AggregateFunction::Max);
+
+    vec![
+        window_func_expr(min, vec![time_column.clone()], partition_by.clone(), vec![])
+            .alias(WINDOW_START_COL_NAME),
+        window_func_expr(max, vec![time_column.clone()], partition_by, vec![])
+            .alias(WINDOW_END_COL_NAME),
+    ]
+}
+
+/// Project the window struct(alias name [`WINDOW_COL_NAME`]) and the child exprs
+fn project_window(
+    builder: LogicalPlanBuilder,
+    end_offset_ns: i64,
+    child_project_exprs: Vec<Expr>,
+) -> Result<LogicalPlan> {
+    let ns_type = DataType::Timestamp(TimeUnit::Nanosecond, None);
+    let window_start = cast(time_to_i64(col(WINDOW_START_COL_NAME)), ns_type.clone());
+    let window_end = cast(
+        plus(time_to_i64(col(WINDOW_END_COL_NAME)), lit(end_offset_ns)),
+        ns_type,
+    );
+    let args = vec![
+        (WINDOW_START.to_string(), window_start),
+        (WINDOW_END.to_string(), window_end),
+    ];
+    let window_expr = Expr::NamedStruct(Box::new(args)).alias(WINDOW_COL_NAME);
+
+    let mut window_projection: Vec<Expr> = Vec::with_capacity(child_project_exprs.len() + 1);
+    window_projection.push(window_expr);
+    window_projection.extend(child_project_exprs);
+
+    builder.project(window_projection)?.build()
+}
+
+/// Whether the plan reads a stream table
+fn is_stream(plan: &LogicalPlan) -> Result<bool> {
+    let mut is_stream = false;
+    plan.apply(&mut |plan| {
+        if let LogicalPlan::Extension(Extension { node }) = plan {
+            if downcast_plan_node::<WatermarkNode>(node.as_ref()).is_some() {
+                is_stream = true;
+                return Ok(VisitRecursion::Stop);
+            }
+        }
+        Ok(VisitRecursion::Continue)
+    })?;
+    Ok(is_stream)
+}
+
+/// Filter out the points without time, and in a stream, replay the points of the windows
+/// still open at the end of the previous micro-batch, the windows of the points are assigned
+/// by the window functions which only see the points of the current micro-batch.
+fn window_input(
+    child: LogicalPlan,
+    window: StatefulWindow,
+    time_column: &Expr,
+    partition_by: &[Expr],
+) -> Result<LogicalPlanBuilder> {
+    let is_stream = is_stream(&child)?;
+    let builder = LogicalPlanBuilder::from(child).filter(is_not_null(time_column.clone()))?;
+    if is_stream {
+        builder.window_state(window, time_column.clone(), partition_by.to_vec())
+    } else {
+        Ok(builder)
+    }
+}
+
+/// Convert session window to new plan
+///
+/// Original Schema[c1, c2, c3]
+///
+/// New Schema[_start, _end, c1, c2, c3]
+fn build_session_window_plan(
+    window: &SessionWindow,
+    child: LogicalPlan,
+    child_project_exprs: Vec<Expr>,
+) -> Result<LogicalPlan> {
+    let SessionWindow {
+        time_column,
+        gap,
+        partition_by,
+        ..
+    } = window;
+    let gap_ns = gap.as_nanos() as i64;
+    let order_by = vec![Expr::Sort(Sort::new(
+        Box::new(time_column.clone()),
+        true,
+        false,
+    ))];
+
+    // The time of the previous point
+    let lag = window_function::WindowFunction::BuiltInWindowFunction(
+        window_function::BuiltInWindowFunction::Lag,
+    );
+    let prev_time = window_func_expr(
+        lag,
+        vec![time_column.clone()],
+        partition_by.clone(),
+        order_by.clone(),
+    )
+    .alias(PREV_TIME_COL_NAME);
+
+    // A new session starts if there is no point for longer than the gap,
+    // the sessions are numbered by counting the starts of the sessions.
+    let is_session_start = or(
+        is_null(col(PREV_TIME_COL_NAME)),
+        gt(
+            minus(
+                time_to_i64(time_column.clone()),
+                time_to_i64(col(PREV_TIME_COL_NAME)),
+            ),
+            lit(gap_ns),
+        ),
+    );
+    let sum = window_function::WindowFunction::AggregateFunction(AggregateFunction::Sum);
+    let session_id = window_func_expr(
+        sum,
+        vec![when(is_session_start, lit(1_i64)).otherwise(lit(0_i64))?],
+        partition_by.clone(),
+        order_by,
+    )
+    .alias(SESSION_ID_COL_NAME);
+
+    let builder = window_input(
+        child,
+        StatefulWindow::Session { gap: *gap },
+        time_column,
+        partition_by,
+    )?;
+    let builder = builder
+        .window(vec![prev_time])?
+        .window(vec![session_id])?
+        .window(window_bounds_exprs(
+            time_column,
+            partition_by.clone(),
+            col(SESSION_ID_COL_NAME),
+        ))?;
+
+    project_window(builder, gap_ns, child_project_exprs)
+}
+
+/// Convert count window to new plan
+///
+/// Original Schema[c1, c2, c3]
+///
+/// New Schema[_start, _end, c1, c2, c3]
+fn build_count_window_plan(
+    window: &CountWindow,
+    child: LogicalPlan,
+    child_project_exprs: Vec<Expr>,
+) -> Result<LogicalPlan> {
+    let CountWindow {
+        time_column,
+        size,
+        partition_by,
+        ..
+    } = window;
+    let order_by = vec![Expr::Sort(Sort::new(
+        Box::new(time_column.clone()),
+        true,
+        false,
+    ))];
+
+    let row_number = window_function::WindowFunction::BuiltInWindowFunction(
+        window_function::BuiltInWindowFunction::RowNumber,
+    );
+    let row_number = window_func_expr(row_number, vec![], partition_by.clone(), order_by)
+        .alias(ROW_NUMBER_COL_NAME);
+    // (row_number - 1) / size
+    let window_id = divide(
+        minus(cast(col(ROW_NUMBER_COL_NAME), DataType::Int64), lit(1_i64)),
+        lit(*size),
+    );
+
+    let builder = window_input(
+        child,
+        StatefulWindow::Count { size: *size },
+        time_column,
+        partition_by,
+    )?;
+    let builder = builder
+        .window(vec![row_number])?
+        .window(window_bounds_exprs(
+            time_column,
+            partition_by.clone(),
+            window_id,
+        ))?;
+
+    project_window(builder, 0, child_project_exprs)
+}
+
+/// Replace udf [`TIME_WINDOW`], [`SESSION_WINDOW`] and [`COUNT_WINDOW`] with the specified expression
 fn replace_window_expr(new_expr: Expr, plan: &LogicalPlan) -> Result<LogicalPlan> {
     plan.transform_expressions_down(&|expr: &Expr| {
-        if matches!(expr, Expr::ScalarUDF(expr::ScalarUDF {
-            fun,
-            ..
-        }) if fun.name == TIME_WINDOW)
-        {
+        if is_window_expr(expr) {
             Some(new_expr.clone())
         } else {
             None
@@ -420,8 +811,49 @@ fn simplify_expr(expr: Expr, schema: DFSchemaRef) -> Result<Expr> {
 mod tests {
     use std::time::Duration;
 
+    use datafusion::arrow::datatypes::IntervalDayTimeType;
+    use datafusion::logical_expr::{expr, LogicalPlanBuilder};
+    use datafusion::prelude::{col, lit, Expr};
+    use datafusion::scalar::ScalarValue;
+
+    use super::open_windows_start;
+    use crate::extension::expr::TIME_WINDOW_UDF;
     use crate::utils::duration::parse_duration;
 
+    fn values_plan() -> LogicalPlanBuilder {
+        LogicalPlanBuilder::values(vec![vec![lit(ScalarValue::TimestampNanosecond(
+            Some(0),
+            None,
+        ))]])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_open_windows_start() {
+        let time_window = Expr::ScalarUDF(expr::ScalarUDF::new(
+            TIME_WINDOW_UDF.clone(),
+            vec![
+                col("column1"),
+                lit(ScalarValue::IntervalDayTime(Some(
+                    IntervalDayTimeType::make_value(0, 60_000),
+                ))),
+            ],
+        ));
+        let plan = values_plan()
+            .aggregate(vec![time_window], Vec::<Expr>::new())
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            open_windows_start(&plan, 100_000_000_000).unwrap(),
+            Some(40_000_000_000)
+        );
+
+        // The stream without windows doesn't read the data before the watermark again.
+        let plan = values_plan().build().unwrap();
+        assert_eq!(open_windows_start(&plan, 100).unwrap(), Some(100));
+    }
+
     #[test]
     fn test_parse_duration() {
         assert!(parse_duration("0.001ms").is_err());
@@ -15,6 +15,7 @@ use datafusion::logical_expr::{
 };
 use datafusion::physical_plan::Accumulator;
 use datafusion::scalar::ScalarValue;
+use once_cell::sync::Lazy;
 use spi::query::function::FunctionMetadataManager;
 use spi::{QueryError, QueryResult};
 
@@ -22,6 +23,8 @@ use super::TSPoint;
 use crate::extension::expr::aggregate_function::LAST_UDAF_NAME;
 use crate::extension::expr::BINARYS;
 
+pub static LAST_UDAF: Lazy<Arc<AggregateUDF>> = Lazy::new(|| Arc::new(new()));
+
 pub fn register_udaf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<AggregateUDF> {
     let udf = new();
     func_manager.register_udaf(udf.clone())?;
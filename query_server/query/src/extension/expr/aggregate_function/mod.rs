@@ -8,6 +8,8 @@ mod increase;
 mod last;
 mod mode;
 mod sample;
+mod sketch;
+mod st_makeline;
 mod state_agg;
 
 use std::sync::Arc;
@@ -33,7 +35,18 @@ pub const CONSISTENCY_UDF_NAME: &str = "consistency";
 pub const TIMELINESS_UDF_NAME: &str = "timeliness";
 pub const VALIDITY_UDF_NAME: &str = "validity";
 pub const EXACT_COUNT_UDAF_NAME: &str = "exact_count";
+pub const TDIGEST_UDAF_NAME: &str = "tdigest";
+pub const UDDSKETCH_UDAF_NAME: &str = "uddsketch";
+pub const HYPERLOGLOG_UDAF_NAME: &str = "hyperloglog";
+pub const ROLLUP_UDAF_NAME: &str = "rollup";
+pub const APPROX_PERCENTILE_TDIGEST_UDAF_NAME: &str = "approx_percentile_tdigest";
+pub const APPROX_DISTINCT_HLL_UDAF_NAME: &str = "approx_distinct_hll";
+pub const ST_MAKELINE_UDAF_NAME: &str = "ST_MakeLine";
+pub use first::FIRST_UDAF;
 pub use gauge::GaugeData;
+pub use last::LAST_UDAF;
+pub use mode::MODE_UDAF;
+pub use sketch::{Sketch, ROLLUP_UDAF};
 pub use state_agg::StateAggData;
 
 pub fn register_udafs(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<()> {
@@ -49,6 +62,8 @@ pub fn register_udafs(func_manager: &mut dyn FunctionMetadataManager) -> QueryRe
     increase::register_udaf(func_manager)?;
     data_quality::register_udafs(func_manager)?;
     exact_count_agg::register_udaf(func_manager)?;
+    sketch::register_udafs(func_manager)?;
+    st_makeline::register_udaf(func_manager)?;
     Ok(())
 }
 
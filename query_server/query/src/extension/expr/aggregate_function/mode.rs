@@ -11,11 +11,14 @@ use datafusion::logical_expr::{
 };
 use datafusion::physical_plan::Accumulator;
 use datafusion::scalar::ScalarValue;
+use once_cell::sync::Lazy;
 use spi::query::function::FunctionMetadataManager;
 use spi::QueryResult;
 
 use crate::extension::expr::aggregate_function::MODE_UDAF_NAME;
 
+pub static MODE_UDAF: Lazy<Arc<AggregateUDF>> = Lazy::new(|| Arc::new(new()));
+
 pub fn register_udaf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<AggregateUDF> {
     let udf = new();
     func_manager.register_udaf(udf.clone())?;
@@ -0,0 +1,183 @@
+mod sketch_agg;
+mod tdigest;
+mod uddsketch;
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use datafusion::arrow::array::{ArrayRef, BinaryArray};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::{downcast_value, Result as DFResult};
+use datafusion::error::DataFusionError;
+use datafusion::scalar::ScalarValue;
+use serde::{Deserialize, Serialize};
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryError;
+pub use sketch_agg::ROLLUP_UDAF;
+pub use tdigest::TDigest;
+pub use uddsketch::UddSketch;
+use utils::HyperLogLog;
+
+use super::AggState;
+
+pub fn register_udafs(func_manager: &mut dyn FunctionMetadataManager) -> Result<(), QueryError> {
+    sketch_agg::register_udafs(func_manager)?;
+    Ok(())
+}
+
+/// Mergeable sketch of the values. The sketch is returned as a base64 encoded string, so
+/// that it can be stored in the string columns, e.g. by the stream rollups, and merged
+/// again by `rollup`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Sketch {
+    TDigest(TDigest),
+    UddSketch(UddSketch),
+    HyperLogLog(HyperLogLog),
+}
+
+impl Sketch {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::TDigest(_) => "tdigest",
+            Self::UddSketch(_) => "uddsketch",
+            Self::HyperLogLog(_) => "hyperloglog",
+        }
+    }
+
+    pub fn merge(&mut self, other: &Self) -> DFResult<()> {
+        match (self, other) {
+            (Self::TDigest(s), Self::TDigest(o)) => s.merge(o),
+            (Self::UddSketch(s), Self::UddSketch(o)) => s.merge(o),
+            (Self::HyperLogLog(s), Self::HyperLogLog(o)) => s.merge(o),
+            (s, o) => {
+                return Err(DataFusionError::Execution(format!(
+                    "Can't merge {} sketch with {} sketch",
+                    s.kind(),
+                    o.kind()
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    /// Estimates the value at the percentile in `[0, 1]`, `None` if the sketch is empty.
+    pub fn approx_percentile(&self, percentile: f64) -> DFResult<Option<f64>> {
+        check_percentile(percentile)?;
+        match self {
+            Self::TDigest(s) => Ok(s.quantile(percentile)),
+            Self::UddSketch(s) => Ok(s.quantile(percentile)),
+            Self::HyperLogLog(_) => Err(DataFusionError::Execution(
+                "approx_percentile requires tdigest or uddsketch sketch, got hyperloglog"
+                    .to_string(),
+            )),
+        }
+    }
+
+    /// Estimates the number of the distinct values.
+    pub fn distinct_count(&self) -> DFResult<u64> {
+        match self {
+            Self::HyperLogLog(s) => Ok(s.count()),
+            s => Err(DataFusionError::Execution(format!(
+                "distinct_count requires hyperloglog sketch, got {}",
+                s.kind()
+            ))),
+        }
+    }
+
+    /// The memory size of the sketch.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::TDigest(s) => s.size(),
+            Self::UddSketch(s) => s.size(),
+            Self::HyperLogLog(s) => s.size(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> DFResult<Vec<u8>> {
+        let sketch = match self {
+            // The buffered values are merged to make the sketch smaller.
+            Self::TDigest(s) => {
+                let mut s = s.clone();
+                s.compress();
+                Self::TDigest(s)
+            }
+            s => s.clone(),
+        };
+        bincode::serialize(&sketch).map_err(|e| DataFusionError::External(Box::new(e)))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> DFResult<Self> {
+        bincode::deserialize(bytes)
+            .map_err(|e| DataFusionError::Execution(format!("Invalid sketch: {e}")))
+    }
+
+    pub fn encode(&self) -> DFResult<String> {
+        Ok(BASE64_STANDARD.encode(self.to_bytes()?))
+    }
+
+    pub fn decode(encoded: &str) -> DFResult<Self> {
+        let bytes = BASE64_STANDARD
+            .decode(encoded)
+            .map_err(|e| DataFusionError::Execution(format!("Invalid sketch: {e}")))?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+pub fn check_percentile(percentile: f64) -> DFResult<()> {
+    if !(0.0..=1.0).contains(&percentile) {
+        return Err(DataFusionError::Execution(format!(
+            "Percentile must be in [0, 1], got {percentile}"
+        )));
+    }
+    Ok(())
+}
+
+impl AggState for Sketch {
+    fn try_to_state(&self) -> DFResult<Vec<ScalarValue>> {
+        Ok(vec![ScalarValue::Binary(Some(self.to_bytes()?))])
+    }
+
+    fn try_from_arrays(
+        input_data_types: &[DataType],
+        arrays: &[ArrayRef],
+    ) -> DFResult<Option<Self>> {
+        debug_assert!(arrays.len() == 1, "Sketch requires 1 array.");
+        debug_assert!(
+            input_data_types.len() == 1,
+            "Sketch requires 1 input data type."
+        );
+
+        let bytes_array = downcast_value!(arrays[0].as_ref(), BinaryArray);
+
+        let mut result: Option<Sketch> = None;
+        for bytes in bytes_array.iter().flatten() {
+            let sketch = Sketch::from_bytes(bytes)?;
+            match result.as_mut() {
+                Some(result) => result.merge(&sketch)?,
+                None => result = Some(sketch),
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Sketch, TDigest};
+
+    #[test]
+    fn test_sketch_encode() {
+        let mut digest = TDigest::new();
+        for i in 0..1000 {
+            digest.insert(i as f64);
+        }
+        let sketch = Sketch::TDigest(digest);
+
+        let decoded = Sketch::decode(&sketch.encode().unwrap()).unwrap();
+        assert_eq!(
+            decoded.approx_percentile(0.5).unwrap(),
+            sketch.approx_percentile(0.5).unwrap()
+        );
+        assert!(decoded.distinct_count().is_err());
+        assert!(Sketch::decode("not a sketch").is_err());
+    }
+}
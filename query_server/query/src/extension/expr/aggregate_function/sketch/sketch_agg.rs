@@ -0,0 +1,283 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, Float64Array, StringArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::{downcast_value, DataFusionError, Result as DFResult};
+use datafusion::logical_expr::type_coercion::aggregates::NUMERICS;
+use datafusion::logical_expr::{
+    AccumulatorFactoryFunction, AggregateUDF, ReturnTypeFunction, Signature, StateTypeFunction,
+    TypeSignature, Volatility,
+};
+use datafusion::physical_plan::Accumulator;
+use datafusion::scalar::ScalarValue;
+use once_cell::sync::Lazy;
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryError;
+use utils::HyperLogLog;
+
+use super::{check_percentile, Sketch, TDigest, UddSketch};
+use crate::extension::expr::aggregate_function::{
+    AggState, APPROX_DISTINCT_HLL_UDAF_NAME, APPROX_PERCENTILE_TDIGEST_UDAF_NAME,
+    HYPERLOGLOG_UDAF_NAME, ROLLUP_UDAF_NAME, TDIGEST_UDAF_NAME, UDDSKETCH_UDAF_NAME,
+};
+
+/// Merges the pushed down sketches of the vnodes.
+pub static ROLLUP_UDAF: Lazy<Arc<AggregateUDF>> =
+    Lazy::new(|| Arc::new(new(ROLLUP_UDAF_NAME, SketchAgg::Rollup)));
+
+pub fn register_udafs(func_manager: &mut dyn FunctionMetadataManager) -> Result<(), QueryError> {
+    // tdigest(value DOUBLE) RETURNS STRING
+    func_manager.register_udaf(new(TDIGEST_UDAF_NAME, SketchAgg::TDigest))?;
+    // uddsketch(value DOUBLE) RETURNS STRING
+    func_manager.register_udaf(new(UDDSKETCH_UDAF_NAME, SketchAgg::UddSketch))?;
+    // hyperloglog(value ANY) RETURNS STRING
+    func_manager.register_udaf(new(HYPERLOGLOG_UDAF_NAME, SketchAgg::HyperLogLog))?;
+    // rollup(sketch STRING) RETURNS STRING
+    func_manager.register_udaf(new(ROLLUP_UDAF_NAME, SketchAgg::Rollup))?;
+    // approx_percentile_tdigest(value DOUBLE, percentile DOUBLE) RETURNS DOUBLE
+    func_manager.register_udaf(new(
+        APPROX_PERCENTILE_TDIGEST_UDAF_NAME,
+        SketchAgg::ApproxPercentileTDigest,
+    ))?;
+    // approx_distinct_hll(value ANY) RETURNS BIGINT UNSIGNED
+    func_manager.register_udaf(new(
+        APPROX_DISTINCT_HLL_UDAF_NAME,
+        SketchAgg::ApproxDistinctHll,
+    ))?;
+    Ok(())
+}
+
+fn new(name: &str, agg: SketchAgg) -> AggregateUDF {
+    let return_type_func: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(agg.return_type())));
+
+    let state_type_func: StateTypeFunction = Arc::new(move |_, _| {
+        let mut state_data_types = vec![DataType::Binary];
+        if agg == SketchAgg::ApproxPercentileTDigest {
+            state_data_types.push(DataType::Float64);
+        }
+        Ok(Arc::new(state_data_types))
+    });
+
+    let accumulator: AccumulatorFactoryFunction =
+        Arc::new(move |_, _| Ok(Box::new(SketchAccumulator::new(agg))));
+
+    AggregateUDF::new(
+        name,
+        &agg.signature(),
+        &return_type_func,
+        &accumulator,
+        &state_type_func,
+    )
+}
+
+/// The sketch aggregate functions, which only differ in the way of building the sketch
+/// and the result.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SketchAgg {
+    /// Returns the encoded t-digest of the values.
+    TDigest,
+    /// Returns the encoded UDDSketch of the values.
+    UddSketch,
+    /// Returns the encoded HyperLogLog of the values.
+    HyperLogLog,
+    /// Returns the encoded sketch merged from the encoded sketches.
+    Rollup,
+    /// Returns the value at the percentile estimated by the t-digest of the values.
+    ApproxPercentileTDigest,
+    /// Returns the number of the distinct values estimated by the HyperLogLog.
+    ApproxDistinctHll,
+}
+
+impl SketchAgg {
+    fn signature(&self) -> Signature {
+        match self {
+            Self::TDigest | Self::UddSketch => {
+                Signature::uniform(1, NUMERICS.to_vec(), Volatility::Immutable)
+            }
+            Self::HyperLogLog | Self::ApproxDistinctHll => Signature::any(1, Volatility::Immutable),
+            Self::Rollup => Signature::exact(vec![DataType::Utf8], Volatility::Immutable),
+            Self::ApproxPercentileTDigest => {
+                let type_signatures = NUMERICS
+                    .iter()
+                    .map(|t| TypeSignature::Exact(vec![t.clone(), DataType::Float64]))
+                    .collect();
+                Signature::one_of(type_signatures, Volatility::Immutable)
+            }
+        }
+    }
+
+    fn return_type(&self) -> DataType {
+        match self {
+            Self::TDigest | Self::UddSketch | Self::HyperLogLog | Self::Rollup => DataType::Utf8,
+            Self::ApproxPercentileTDigest => DataType::Float64,
+            Self::ApproxDistinctHll => DataType::UInt64,
+        }
+    }
+
+    /// The empty sketch that the values are inserted into, `None` for `rollup`.
+    fn new_sketch(&self) -> Option<Sketch> {
+        match self {
+            Self::TDigest | Self::ApproxPercentileTDigest => Some(Sketch::TDigest(TDigest::new())),
+            Self::UddSketch => Some(Sketch::UddSketch(UddSketch::new())),
+            Self::HyperLogLog | Self::ApproxDistinctHll => {
+                Some(Sketch::HyperLogLog(HyperLogLog::new()))
+            }
+            Self::Rollup => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SketchAccumulator {
+    agg: SketchAgg,
+    /// `None` if no value is aggregated.
+    sketch: Option<Sketch>,
+    /// The percentile of `approx_percentile_tdigest`, it's constant in a query.
+    percentile: Option<f64>,
+}
+
+impl SketchAccumulator {
+    fn new(agg: SketchAgg) -> Self {
+        Self {
+            agg,
+            sketch: None,
+            percentile: None,
+        }
+    }
+
+    fn merge_sketch(&mut self, sketch: Sketch) -> DFResult<()> {
+        match self.sketch.as_mut() {
+            Some(s) => s.merge(&sketch),
+            None => {
+                self.sketch = Some(sketch);
+                Ok(())
+            }
+        }
+    }
+
+    fn set_percentile(&mut self, percentiles: &Float64Array) -> DFResult<()> {
+        if self.percentile.is_none() {
+            if let Some(percentile) = percentiles.iter().flatten().next() {
+                check_percentile(percentile)?;
+                self.percentile = Some(percentile);
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_values(&mut self, values: &ArrayRef) -> DFResult<()> {
+        if values.null_count() == values.len() {
+            return Ok(());
+        }
+
+        let mut sketch = match self.sketch.take().or_else(|| self.agg.new_sketch()) {
+            Some(sketch) => sketch,
+            None => return Ok(()),
+        };
+        match &mut sketch {
+            Sketch::TDigest(s) => {
+                let values = cast(values, &DataType::Float64)?;
+                let values = downcast_value!(values, Float64Array);
+                values.iter().flatten().for_each(|v| s.insert(v));
+            }
+            Sketch::UddSketch(s) => {
+                let values = cast(values, &DataType::Float64)?;
+                let values = downcast_value!(values, Float64Array);
+                values.iter().flatten().for_each(|v| s.insert(v));
+            }
+            Sketch::HyperLogLog(s) => {
+                // Hash the values by their string forms, which are the same on all the nodes.
+                let values = cast(values, &DataType::Utf8)?;
+                let values = downcast_value!(values, StringArray);
+                values.iter().flatten().for_each(|v| s.insert(v.as_bytes()));
+            }
+        }
+        self.sketch = Some(sketch);
+
+        Ok(())
+    }
+}
+
+impl Accumulator for SketchAccumulator {
+    fn state(&self) -> DFResult<Vec<ScalarValue>> {
+        let mut state = match &self.sketch {
+            Some(sketch) => sketch.try_to_state()?,
+            None => vec![ScalarValue::Binary(None)],
+        };
+        if self.agg == SketchAgg::ApproxPercentileTDigest {
+            state.push(ScalarValue::Float64(self.percentile));
+        }
+        Ok(state)
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("update_batch: {:?}", values);
+
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        match self.agg {
+            SketchAgg::Rollup => {
+                let sketches = downcast_value!(values[0], StringArray);
+                for encoded in sketches.iter().flatten() {
+                    self.merge_sketch(Sketch::decode(encoded)?)?;
+                }
+                Ok(())
+            }
+            SketchAgg::ApproxPercentileTDigest => {
+                debug_assert!(
+                    values.len() == 2,
+                    "approx_percentile_tdigest can only take 2 param, but found {}",
+                    values.len()
+                );
+                self.set_percentile(downcast_value!(values[1], Float64Array))?;
+                self.insert_values(&values[0])
+            }
+            _ => self.insert_values(&values[0]),
+        }
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("merge_batch: {:?}", states);
+
+        if self.agg == SketchAgg::ApproxPercentileTDigest {
+            self.set_percentile(downcast_value!(states[1], Float64Array))?;
+        }
+        if let Some(sketch) = Sketch::try_from_arrays(&[DataType::Binary], &states[..1])? {
+            self.merge_sketch(sketch)?;
+        }
+
+        Ok(())
+    }
+
+    fn evaluate(&self) -> DFResult<ScalarValue> {
+        let sketch = match &self.sketch {
+            Some(sketch) => sketch,
+            None => {
+                return match self.agg {
+                    SketchAgg::ApproxDistinctHll => Ok(ScalarValue::UInt64(Some(0))),
+                    agg => ScalarValue::try_from(&agg.return_type()),
+                }
+            }
+        };
+
+        match self.agg {
+            SketchAgg::ApproxPercentileTDigest => {
+                let percentile = self.percentile.ok_or_else(|| {
+                    DataFusionError::Execution(
+                        "approx_percentile_tdigest requires a percentile".to_string(),
+                    )
+                })?;
+                Ok(ScalarValue::Float64(sketch.approx_percentile(percentile)?))
+            }
+            SketchAgg::ApproxDistinctHll => Ok(ScalarValue::UInt64(Some(sketch.distinct_count()?))),
+            _ => Ok(ScalarValue::Utf8(Some(sketch.encode()?))),
+        }
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.sketch.as_ref().map(Sketch::size).unwrap_or_default()
+    }
+}
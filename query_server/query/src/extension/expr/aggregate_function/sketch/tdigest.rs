@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+
+/// The compression of the t-digest, the number of the centroids is about `COMPRESSION / 2`.
+const COMPRESSION: f64 = 100.0;
+/// The number of the buffered values to be merged into the centroids at once.
+const BUFFER_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// Merging t-digest, which estimates the quantiles of the values with high accuracy
+/// near the extremes. Digests are merged by merging their centroids.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TDigest {
+    centroids: Vec<Centroid>,
+    buffer: Vec<f64>,
+    count: u64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for TDigest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TDigest {
+    pub fn new() -> Self {
+        Self {
+            centroids: vec![],
+            buffer: vec![],
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.centroids.capacity() * std::mem::size_of::<Centroid>()
+            + self.buffer.capacity() * std::mem::size_of::<f64>()
+    }
+
+    pub fn insert(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        self.buffer.push(value);
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        if self.buffer.len() >= BUFFER_SIZE {
+            self.compress();
+        }
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        let mut other = other.clone();
+        other.compress();
+        self.compress();
+        self.centroids.extend(other.centroids);
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.merge_centroids(vec![]);
+    }
+
+    /// Merges the buffered values into the centroids.
+    pub fn compress(&mut self) {
+        if !self.buffer.is_empty() {
+            let buffer = std::mem::take(&mut self.buffer);
+            self.merge_centroids(buffer);
+        }
+    }
+
+    fn merge_centroids(&mut self, values: Vec<f64>) {
+        let mut centroids = std::mem::take(&mut self.centroids);
+        centroids.extend(
+            values
+                .into_iter()
+                .map(|mean| Centroid { mean, weight: 1.0 }),
+        );
+        centroids.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+
+        let total = centroids.iter().map(|c| c.weight).sum::<f64>();
+        let mut merged = Vec::with_capacity(centroids.len());
+        let mut iter = centroids.into_iter();
+        let mut current = match iter.next() {
+            Some(c) => c,
+            None => return,
+        };
+        let mut weight_so_far = 0.0;
+        let mut weight_limit = total * q_limit(weight_so_far / total);
+        for next in iter {
+            if weight_so_far + current.weight + next.weight <= weight_limit {
+                let weight = current.weight + next.weight;
+                current.mean += (next.mean - current.mean) * next.weight / weight;
+                current.weight = weight;
+            } else {
+                weight_so_far += current.weight;
+                weight_limit = total * q_limit(weight_so_far / total);
+                merged.push(current);
+                current = next;
+            }
+        }
+        merged.push(current);
+
+        self.centroids = merged;
+    }
+
+    /// Estimates the value at the quantile `q` in `[0, 1]`, `None` if there are no values.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        let mut digest = self.clone();
+        digest.compress();
+        let centroids = &digest.centroids;
+        if centroids.is_empty() {
+            return None;
+        }
+        if centroids.len() == 1 {
+            return Some(centroids[0].mean);
+        }
+
+        let total = digest.count as f64;
+        let target = q.clamp(0.0, 1.0) * total;
+        let first = centroids[0];
+        if target < first.weight / 2.0 {
+            return Some(interpolate(
+                digest.min,
+                first.mean,
+                target / (first.weight / 2.0),
+            ));
+        }
+
+        let mut weight_so_far = 0.0;
+        for pair in centroids.windows(2) {
+            let left_center = weight_so_far + pair[0].weight / 2.0;
+            let right_center = weight_so_far + pair[0].weight + pair[1].weight / 2.0;
+            if target <= right_center {
+                let fraction = (target - left_center) / (right_center - left_center);
+                return Some(interpolate(pair[0].mean, pair[1].mean, fraction));
+            }
+            weight_so_far += pair[0].weight;
+        }
+
+        let last = centroids[centroids.len() - 1];
+        let last_center = total - last.weight / 2.0;
+        let fraction = (target - last_center) / (total - last_center);
+        Some(interpolate(last.mean, digest.max, fraction))
+    }
+}
+
+fn interpolate(left: f64, right: f64, fraction: f64) -> f64 {
+    left + (right - left) * fraction.clamp(0.0, 1.0)
+}
+
+/// The maximum quantile that the centroid starting at the quantile `q` can reach, by
+/// the scale function `k(q) = compression / (2 * PI) * asin(2q - 1)`.
+fn q_limit(q: f64) -> f64 {
+    use std::f64::consts::PI;
+
+    let k = COMPRESSION / (2.0 * PI) * (2.0 * q - 1.0).asin() + 1.0;
+    if k >= COMPRESSION / 4.0 {
+        1.0
+    } else {
+        ((2.0 * PI * k / COMPRESSION).sin() + 1.0) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TDigest;
+
+    #[test]
+    fn test_tdigest_quantile() {
+        let mut digest = TDigest::new();
+        assert_eq!(digest.quantile(0.5), None);
+
+        for i in 1..=10_000 {
+            digest.insert(i as f64);
+        }
+        assert_eq!(digest.quantile(0.0), Some(1.0));
+        assert_eq!(digest.quantile(1.0), Some(10_000.0));
+        for q in [0.01, 0.1, 0.5, 0.9, 0.99] {
+            let estimate = digest.quantile(q).unwrap();
+            let error = (estimate - q * 10_000.0).abs() / 10_000.0;
+            assert!(error < 0.01, "q: {q}, estimate: {estimate}");
+        }
+    }
+
+    #[test]
+    fn test_tdigest_merge() {
+        let mut left = TDigest::new();
+        let mut right = TDigest::new();
+        for i in 0..5_000 {
+            left.insert(i as f64);
+            right.insert((i + 5_000) as f64);
+        }
+        left.merge(&right);
+        assert_eq!(left.count, 10_000);
+
+        let median = left.quantile(0.5).unwrap();
+        assert!((median - 5_000.0).abs() < 100.0, "median: {median}");
+    }
+}
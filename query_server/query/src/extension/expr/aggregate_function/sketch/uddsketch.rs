@@ -0,0 +1,215 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// The initial maximum relative error of the estimated quantiles.
+const INITIAL_ERROR: f64 = 0.001;
+/// The maximum number of the buckets, the buckets are collapsed when exceeded.
+const MAX_BUCKETS: usize = 200;
+
+/// Uniform collapsing DDSketch, which estimates the quantiles of the values with bounded
+/// relative error. Value `v > 0` is counted in the bucket `ceil(log_gamma(v))`, where
+/// `gamma = (1 + error) / (1 - error)`, the buckets of negative values are kept separately.
+///
+/// When there are more than [`MAX_BUCKETS`] buckets, every two adjacent buckets are
+/// collapsed into one, which squares `gamma` and raises the error to `2e / (1 + e^2)`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UddSketch {
+    /// The number of the collapses since the sketch is created.
+    compactions: u32,
+    positive: BTreeMap<i64, u64>,
+    negative: BTreeMap<i64, u64>,
+    zero: u64,
+    count: u64,
+}
+
+impl Default for UddSketch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UddSketch {
+    pub fn new() -> Self {
+        Self {
+            compactions: 0,
+            positive: BTreeMap::new(),
+            negative: BTreeMap::new(),
+            zero: 0,
+            count: 0,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + (self.positive.len() + self.negative.len()) * std::mem::size_of::<(i64, u64)>()
+    }
+
+    /// The current maximum relative error of the estimated quantiles.
+    pub fn error(&self) -> f64 {
+        (0..self.compactions).fold(INITIAL_ERROR, |e, _| 2.0 * e / (1.0 + e * e))
+    }
+
+    fn gamma(&self) -> f64 {
+        let error = self.error();
+        (1.0 + error) / (1.0 - error)
+    }
+
+    fn key(&self, value: f64) -> i64 {
+        (value.ln() / self.gamma().ln()).ceil() as i64
+    }
+
+    /// The estimated value of the bucket, which has the same relative error to both ends.
+    fn estimate(&self, key: i64) -> f64 {
+        let gamma = self.gamma();
+        2.0 * gamma.powf(key as f64) / (gamma + 1.0)
+    }
+
+    pub fn insert(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        if value > 0.0 {
+            let key = self.key(value);
+            *self.positive.entry(key).or_default() += 1;
+        } else if value < 0.0 {
+            let key = self.key(-value);
+            *self.negative.entry(key).or_default() += 1;
+        } else {
+            self.zero += 1;
+        }
+        self.count += 1;
+        self.compact_to_fit();
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        let mut other = other.clone();
+        while self.compactions < other.compactions {
+            self.compact();
+        }
+        while other.compactions < self.compactions {
+            other.compact();
+        }
+
+        for (key, count) in other.positive {
+            *self.positive.entry(key).or_default() += count;
+        }
+        for (key, count) in other.negative {
+            *self.negative.entry(key).or_default() += count;
+        }
+        self.zero += other.zero;
+        self.count += other.count;
+        self.compact_to_fit();
+    }
+
+    fn compact_to_fit(&mut self) {
+        while self.positive.len() + self.negative.len() > MAX_BUCKETS {
+            self.compact();
+        }
+    }
+
+    /// Collapses the buckets `2k - 1` and `2k` into the bucket `k`.
+    fn compact(&mut self) {
+        let collapse = |buckets: &BTreeMap<i64, u64>| {
+            let mut collapsed = BTreeMap::new();
+            for (key, count) in buckets {
+                *collapsed.entry((key + 1).div_euclid(2)).or_default() += count;
+            }
+            collapsed
+        };
+        self.positive = collapse(&self.positive);
+        self.negative = collapse(&self.negative);
+        self.compactions += 1;
+    }
+
+    /// Estimates the value at the quantile `q` in `[0, 1]`, `None` if there are no values.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let rank = ((q.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (key, count) in self.negative.iter().rev() {
+            seen += count;
+            if seen >= rank {
+                return Some(-self.estimate(*key));
+            }
+        }
+        seen += self.zero;
+        if seen >= rank {
+            return Some(0.0);
+        }
+        for (key, count) in self.positive.iter() {
+            seen += count;
+            if seen >= rank {
+                return Some(self.estimate(*key));
+            }
+        }
+
+        self.positive
+            .keys()
+            .next_back()
+            .map(|key| self.estimate(*key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UddSketch;
+
+    #[test]
+    fn test_uddsketch_quantile() {
+        let mut sketch = UddSketch::new();
+        assert_eq!(sketch.quantile(0.5), None);
+
+        for i in 1..=10_000 {
+            sketch.insert(i as f64);
+        }
+        let error = sketch.error();
+        assert!(error < 0.05, "error: {error}");
+        for q in [0.01, 0.1, 0.5, 0.9, 0.99, 1.0] {
+            let expected = q * 10_000.0;
+            let estimate = sketch.quantile(q).unwrap();
+            assert!(
+                (estimate - expected).abs() <= expected * error,
+                "q: {q}, estimate: {estimate}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_uddsketch_negative_and_zero() {
+        let mut sketch = UddSketch::new();
+        for value in [-100.0, -10.0, 0.0, 10.0, 100.0] {
+            sketch.insert(value);
+        }
+
+        let error = sketch.error();
+        assert!((sketch.quantile(0.0).unwrap() + 100.0).abs() <= 100.0 * error);
+        assert!((sketch.quantile(0.4).unwrap() + 10.0).abs() <= 10.0 * error);
+        assert_eq!(sketch.quantile(0.6), Some(0.0));
+        assert!((sketch.quantile(1.0).unwrap() - 100.0).abs() <= 100.0 * error);
+    }
+
+    #[test]
+    fn test_uddsketch_merge() {
+        let mut left = UddSketch::new();
+        let mut right = UddSketch::new();
+        for i in 1..=100 {
+            left.insert(i as f64);
+        }
+        for i in 1..=1_000_000 {
+            right.insert(i as f64);
+        }
+        left.merge(&right);
+        assert_eq!(left.count, 1_000_100);
+
+        let error = left.error();
+        let median = left.quantile(0.5).unwrap();
+        assert!(
+            (median - 499_950.0).abs() <= 499_950.0 * error,
+            "median: {median}"
+        );
+    }
+}
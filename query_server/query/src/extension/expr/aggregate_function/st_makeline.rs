@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{ArrayRef, Int64Array, StringArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::common::cast::as_list_array;
+use datafusion::common::{downcast_value, DataFusionError, Result as DFResult};
+use datafusion::logical_expr::type_coercion::aggregates::TIMESTAMPS;
+use datafusion::logical_expr::{
+    AccumulatorFactoryFunction, AggregateUDF, ReturnTypeFunction, Signature, StateTypeFunction,
+    TypeSignature, Volatility,
+};
+use datafusion::physical_plan::Accumulator;
+use datafusion::scalar::ScalarValue;
+use geo::{Geometry, LineString};
+use geozero::wkt::WktStr;
+use geozero::{ToGeo, ToWkt};
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use crate::extension::expr::aggregate_function::ST_MAKELINE_UDAF_NAME;
+
+pub fn register_udaf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<AggregateUDF> {
+    let udf = new();
+    func_manager.register_udaf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> AggregateUDF {
+    let return_type_func: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Utf8)));
+
+    let state_type_func: StateTypeFunction = Arc::new(move |_, _| {
+        let time_list_dt = DataType::List(Arc::new(Field::new("item", DataType::Int64, true)));
+        let point_list_dt = DataType::List(Arc::new(Field::new("item", DataType::Utf8, true)));
+
+        Ok(Arc::new(vec![time_list_dt, point_list_dt]))
+    });
+
+    let accumulator: AccumulatorFactoryFunction =
+        Arc::new(|_, _| Ok(Box::<MakeLineAccumulator>::default()));
+
+    // ST_MakeLine(
+    //     time TIMESTAMP,
+    //     point GEOMETRY
+    //   )
+    let type_signatures = TIMESTAMPS
+        .iter()
+        .map(|t| TypeSignature::Exact(vec![t.clone(), DataType::Utf8]))
+        .collect();
+
+    AggregateUDF::new(
+        ST_MAKELINE_UDAF_NAME,
+        &Signature::one_of(type_signatures, Volatility::Immutable),
+        &return_type_func,
+        &accumulator,
+        &state_type_func,
+    )
+}
+
+/// Collects the points with their timestamps, and connects them into a LINESTRING
+/// in the order of time.
+#[derive(Debug, Default)]
+struct MakeLineAccumulator {
+    points: Vec<(i64, String)>,
+}
+
+impl MakeLineAccumulator {
+    fn push_arrays(&mut self, times: &ArrayRef, points: &ArrayRef) -> DFResult<()> {
+        let times = cast(times.as_ref(), &DataType::Int64)?;
+        let times = downcast_value!(times.as_ref(), Int64Array);
+        let points = downcast_value!(points.as_ref(), StringArray);
+
+        for (time, point) in times.iter().zip(points.iter()) {
+            if let (Some(time), Some(point)) = (time, point) {
+                self.points.push((time, point.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Accumulator for MakeLineAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("update_batch: {:?}", values);
+
+        debug_assert!(
+            values.len() == 2,
+            "st_makeline can only take 2 params, but found {}",
+            values.len()
+        );
+
+        self.push_arrays(&values[0], &values[1])
+    }
+
+    fn evaluate(&self) -> DFResult<ScalarValue> {
+        let mut points = self.points.iter().collect::<Vec<_>>();
+        points.sort_by_key(|(time, _)| *time);
+
+        let coords = points
+            .into_iter()
+            .map(|(_, wkt)| {
+                let geo = WktStr(wkt.as_str())
+                    .to_geo()
+                    .map_err(|err| DataFusionError::Execution(err.to_string()))?;
+                match geo {
+                    Geometry::Point(p) => Ok(p.0),
+                    other => Err(DataFusionError::Execution(format!(
+                        "ST_MakeLine only supports POINT, got {other:?}"
+                    ))),
+                }
+            })
+            .collect::<DFResult<Vec<_>>>()?;
+
+        // A line requires at least 2 points.
+        if coords.len() < 2 {
+            return Ok(ScalarValue::Utf8(None));
+        }
+
+        let line: Geometry = LineString::new(coords).into();
+        let wkt = line
+            .to_wkt()
+            .map_err(|err| DataFusionError::Execution(err.to_string()))?;
+        Ok(ScalarValue::Utf8(Some(wkt)))
+    }
+
+    fn size(&self) -> usize {
+        let points_size: usize = self.points.iter().map(|(_, point)| point.capacity()).sum();
+
+        std::mem::size_of_val(self)
+            + self.points.capacity() * std::mem::size_of::<(i64, String)>()
+            + points_size
+    }
+
+    fn state(&self) -> DFResult<Vec<ScalarValue>> {
+        let (times, points): (Vec<_>, Vec<_>) = self
+            .points
+            .iter()
+            .map(|(time, point)| (ScalarValue::from(*time), ScalarValue::from(point.as_str())))
+            .unzip();
+
+        let times = ScalarValue::new_list(Some(times), DataType::Int64);
+        let points = ScalarValue::new_list(Some(points), DataType::Utf8);
+
+        Ok(vec![times, points])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DFResult<()> {
+        trace::trace!("merge_batch: {:?}", states);
+
+        let time_list_records = as_list_array(states[0].as_ref())?;
+        let point_list_records = as_list_array(states[1].as_ref())?;
+
+        for (times, points) in time_list_records
+            .iter()
+            .flatten()
+            .zip(point_list_records.iter().flatten())
+        {
+            self.push_arrays(&times, &points)?;
+        }
+
+        Ok(())
+    }
+}
@@ -31,6 +31,16 @@ pub fn is_not_null(expr: Expr) -> Expr {
     Expr::IsNotNull(Box::new(expr))
 }
 
+/// Create is null expression
+pub fn is_null(expr: Expr) -> Expr {
+    Expr::IsNull(Box::new(expr))
+}
+
+/// Return a new expression `left > right`
+pub fn gt(left: Expr, right: Expr) -> Expr {
+    binary_expr(left, Operator::Gt, right)
+}
+
 /// Return a new expression `left >= right`
 pub fn ge(left: Expr, right: Expr) -> Expr {
     binary_expr(left, Operator::GtEq, right)
@@ -40,3 +50,8 @@ pub fn ge(left: Expr, right: Expr) -> Expr {
 pub fn lt(left: Expr, right: Expr) -> Expr {
     binary_expr(left, Operator::Lt, right)
 }
+
+/// Return a new expression `left <= right`
+pub fn le(left: Expr, right: Expr) -> Expr {
+    binary_expr(left, Operator::LtEq, right)
+}
@@ -10,6 +10,10 @@ mod session_function;
 mod ts_gen_func;
 mod window;
 
+pub use aggregate_function::{
+    FIRST_UDAF, HYPERLOGLOG_UDAF_NAME, LAST_UDAF, MODE_UDAF, ROLLUP_UDAF, TDIGEST_UDAF_NAME,
+    UDDSKETCH_UDAF_NAME,
+};
 use datafusion::arrow::datatypes::{DataType, IntervalUnit};
 pub use scalar_function::{INTERPOLATE, LOCF, TIME_WINDOW_GAPFILL};
 pub use selector_function::{BOTTOM, TOPK};
@@ -18,8 +22,9 @@ use spi::query::function::FunctionMetadataManager;
 use spi::QueryResult;
 pub use ts_gen_func::TSGenFunc;
 pub use window::{
-    ceil_sliding_window, floor_sliding_window, time_window_signature, DEFAULT_TIME_WINDOW_START,
-    TIME_WINDOW, TIME_WINDOW_UDF, WINDOW_COL_NAME, WINDOW_END, WINDOW_START,
+    ceil_sliding_window, floor_sliding_window, time_window_signature, COUNT_WINDOW,
+    DEFAULT_TIME_WINDOW_START, SESSION_WINDOW, TIME_WINDOW, TIME_WINDOW_UDF, WINDOW_COL_NAME,
+    WINDOW_END, WINDOW_START,
 };
 
 pub static INTERVALS: &[DataType] = &[
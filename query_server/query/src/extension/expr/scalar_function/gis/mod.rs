@@ -1,13 +1,21 @@
 mod st_area;
 mod st_asbinary;
+mod st_astext;
 mod st_binary_op;
+mod st_buffer;
+mod st_centroid;
 mod st_distance;
+mod st_dwithin;
+mod st_envelope;
+mod st_geohash;
 mod st_geomfromwkb;
+mod st_length;
+mod st_makepoint;
 
 use datafusion::error::DataFusionError;
-use geo::Geometry;
+use geo::{coord, Geometry, LineString, Polygon, Rect};
 use geozero::wkt::WktStr;
-use geozero::ToGeo;
+use geozero::{ToGeo, ToWkt};
 use spi::query::function::FunctionMetadataManager;
 use spi::QueryResult;
 
@@ -17,6 +25,14 @@ pub fn register_udfs(func_manager: &mut dyn FunctionMetadataManager) -> QueryRes
     st_asbinary::register_udf(func_manager)?;
     st_area::register_udf(func_manager)?;
     st_binary_op::register_udf(func_manager)?;
+    st_astext::register_udf(func_manager)?;
+    st_buffer::register_udf(func_manager)?;
+    st_centroid::register_udf(func_manager)?;
+    st_length::register_udf(func_manager)?;
+    st_dwithin::register_udf(func_manager)?;
+    st_envelope::register_udf(func_manager)?;
+    st_makepoint::register_udf(func_manager)?;
+    st_geohash::register_udf(func_manager)?;
     Ok(())
 }
 
@@ -26,6 +42,24 @@ pub fn str_to_geo(wkt: &str) -> Result<Geometry, DataFusionError> {
         .map_err(|err| DataFusionError::Execution(err.to_string()))
 }
 
+pub fn geo_to_wkt(geo: &Geometry) -> Result<String, DataFusionError> {
+    geo.to_wkt()
+        .map_err(|err| DataFusionError::Execution(err.to_string()))
+}
+
+/// The polygon `((MINX MINY, MINX MAXY, MAXX MAXY, MAXX MINY, MINX MINY))` of the rect.
+pub fn rect_to_polygon(rect: Rect) -> Polygon {
+    let (min, max) = (rect.min(), rect.max());
+    let exterior = LineString::from(vec![
+        min,
+        coord! { x: min.x, y: max.y },
+        max,
+        coord! { x: max.x, y: min.y },
+        min,
+    ]);
+    Polygon::new(exterior, vec![])
+}
+
 /// $FUNC_NAME: &str
 /// $RES_TYPE: datafusion::DataType
 /// $OP: fn(&Geometry) -> Result<$RES_TYPE, DataFusionError>
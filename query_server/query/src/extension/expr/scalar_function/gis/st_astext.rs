@@ -0,0 +1,22 @@
+use datafusion::arrow::array::StringBuilder;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::error::DataFusionError;
+use geo::Geometry;
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use super::geo_to_wkt;
+use crate::geometry_unary_op;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<()> {
+    // Geometries are stored as WKT, both functions validate and normalize the WKT.
+    let udf = geometry_unary_op!("ST_GeomFromText", to_wkt, DataType::Utf8, StringBuilder);
+    func_manager.register_udf(udf)?;
+    let udf = geometry_unary_op!("ST_AsText", to_wkt, DataType::Utf8, StringBuilder);
+    func_manager.register_udf(udf)?;
+    Ok(())
+}
+
+fn to_wkt(geo: &Geometry) -> Result<String, DataFusionError> {
+    geo_to_wkt(geo)
+}
@@ -0,0 +1,172 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    downcast_array, ArrayRef, Float64Array, StringArray, StringBuilder,
+};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::Result as DFResult;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{ReturnTypeFunction, ScalarUDF, Signature, Volatility};
+use datafusion::physical_plan::functions::make_scalar_function;
+use geo::{
+    coord, BooleanOps, ConvexHull, Coord, Geometry, LineString, MultiPoint, MultiPolygon, Point,
+    Polygon,
+};
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use super::{geo_to_wkt, str_to_geo};
+
+/// The number of the segments used to approximate a circle.
+const CIRCLE_SEGMENTS: usize = 32;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> ScalarUDF {
+    let fun = make_scalar_function(func);
+
+    // ST_Buffer(geom, distance)
+    let signature = Signature::exact(
+        vec![DataType::Utf8, DataType::Float64],
+        Volatility::Immutable,
+    );
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Utf8)));
+
+    ScalarUDF::new("ST_Buffer", &signature, &return_type, &fun)
+}
+
+fn func(args: &[ArrayRef]) -> DFResult<ArrayRef> {
+    let geo_arr = downcast_array::<StringArray>(args[0].as_ref());
+    let dist_arr = downcast_array::<Float64Array>(args[1].as_ref());
+
+    let mut builder = StringBuilder::new();
+    for (geo, dist) in geo_arr.iter().zip(dist_arr.iter()) {
+        match (geo, dist) {
+            (Some(geo), Some(dist)) => {
+                if dist <= 0.0 {
+                    return Err(DataFusionError::Execution(format!(
+                        "ST_Buffer requires a positive distance, got {dist}"
+                    )));
+                }
+                let geo = str_to_geo(geo)?;
+                builder.append_value(geo_to_wkt(&buffer(&geo, dist))?);
+            }
+            _ => builder.append_null(),
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+/// The area within `distance` of the geometry, which is the union of the circles around
+/// the points, the capsules around the line segments and the polygons themselves.
+fn buffer(geo: &Geometry, distance: f64) -> Geometry {
+    let mut parts = vec![];
+    buffer_parts(geo, distance, &mut parts);
+
+    let result = parts
+        .into_iter()
+        .fold(MultiPolygon::new(vec![]), |acc, part| {
+            acc.union(&MultiPolygon::new(vec![part]))
+        });
+    match result.0.as_slice() {
+        [polygon] => polygon.clone().into(),
+        _ => result.into(),
+    }
+}
+
+fn buffer_parts(geo: &Geometry, distance: f64, parts: &mut Vec<Polygon>) {
+    match geo {
+        Geometry::Point(p) => parts.push(circle(p.0, distance)),
+        Geometry::MultiPoint(mp) => parts.extend(mp.iter().map(|p| circle(p.0, distance))),
+        Geometry::Line(l) => parts.push(capsule(l.start, l.end, distance)),
+        Geometry::LineString(ls) => line_string_parts(ls, distance, parts),
+        Geometry::MultiLineString(mls) => mls
+            .iter()
+            .for_each(|ls| line_string_parts(ls, distance, parts)),
+        Geometry::Polygon(p) => polygon_parts(p, distance, parts),
+        Geometry::MultiPolygon(mp) => mp.iter().for_each(|p| polygon_parts(p, distance, parts)),
+        Geometry::Rect(r) => polygon_parts(&r.to_polygon(), distance, parts),
+        Geometry::Triangle(t) => polygon_parts(&t.to_polygon(), distance, parts),
+        Geometry::GeometryCollection(gc) => {
+            gc.iter().for_each(|g| buffer_parts(g, distance, parts))
+        }
+    }
+}
+
+fn line_string_parts(ls: &LineString, distance: f64, parts: &mut Vec<Polygon>) {
+    match ls.0.as_slice() {
+        [] => {}
+        [c] => parts.push(circle(*c, distance)),
+        _ => parts.extend(ls.lines().map(|l| capsule(l.start, l.end, distance))),
+    }
+}
+
+fn polygon_parts(p: &Polygon, distance: f64, parts: &mut Vec<Polygon>) {
+    parts.push(p.clone());
+    line_string_parts(p.exterior(), distance, parts);
+    p.interiors()
+        .iter()
+        .for_each(|ls| line_string_parts(ls, distance, parts));
+}
+
+fn circle_coords(center: Coord, radius: f64) -> impl Iterator<Item = Coord> {
+    (0..CIRCLE_SEGMENTS).map(move |i| {
+        let angle = 2.0 * PI * i as f64 / CIRCLE_SEGMENTS as f64;
+        coord! {
+            x: center.x + radius * angle.cos(),
+            y: center.y + radius * angle.sin(),
+        }
+    })
+}
+
+fn circle(center: Coord, radius: f64) -> Polygon {
+    // The exterior is closed by `Polygon::new`.
+    Polygon::new(LineString::from_iter(circle_coords(center, radius)), vec![])
+}
+
+/// The convex hull of the circles around the ends of the line segment.
+fn capsule(start: Coord, end: Coord, radius: f64) -> Polygon {
+    circle_coords(start, radius)
+        .chain(circle_coords(end, radius))
+        .map(Point::from)
+        .collect::<MultiPoint>()
+        .convex_hull()
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Area, Contains, Geometry, Point};
+
+    use super::buffer;
+    use crate::extension::expr::scalar_function::gis::str_to_geo;
+
+    #[test]
+    fn test_buffer() {
+        let circle = buffer(&Point::new(0.0, 0.0).into(), 1.0);
+        assert!(matches!(circle, Geometry::Polygon(_)));
+        // The area of the regular polygon with 32 sides inscribed in the unit circle
+        let area = 16.0 * (std::f64::consts::PI / 16.0).sin();
+        assert!((circle.unsigned_area() - area).abs() < 1e-9);
+
+        let line = str_to_geo("LINESTRING(0 0, 3 0, 3 3)").unwrap();
+        let capsules = buffer(&line, 1.0);
+        assert!(capsules.contains(&Point::new(3.5, 1.5)));
+        assert!(capsules.contains(&Point::new(-0.5, 0.0)));
+        assert!(!capsules.contains(&Point::new(1.5, 1.5)));
+
+        let polygon = str_to_geo("POLYGON((0 0, 2 0, 2 2, 0 2, 0 0))").unwrap();
+        let buffered = buffer(&polygon, 1.0);
+        assert!(buffered.contains(&Point::new(1.0, 1.0)));
+        assert!(buffered.contains(&Point::new(2.5, 1.0)));
+        assert!(!buffered.contains(&Point::new(3.5, 1.0)));
+
+        let points = str_to_geo("MULTIPOINT(0 0, 10 0)").unwrap();
+        assert!(matches!(buffer(&points, 1.0), Geometry::MultiPolygon(mp) if mp.0.len() == 2));
+    }
+}
@@ -0,0 +1,24 @@
+use datafusion::arrow::array::StringBuilder;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::ScalarUDF;
+use geo::{Centroid, Geometry};
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use super::geo_to_wkt;
+use crate::geometry_unary_op;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<ScalarUDF> {
+    let udf = geometry_unary_op!("ST_Centroid", centroid, DataType::Utf8, StringBuilder);
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn centroid(geo: &Geometry) -> Result<String, DataFusionError> {
+    match geo.centroid() {
+        Some(p) => geo_to_wkt(&p.into()),
+        // The centroid of an empty geometry
+        None => Ok("POINT EMPTY".to_string()),
+    }
+}
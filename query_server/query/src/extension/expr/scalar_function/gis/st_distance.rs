@@ -18,7 +18,7 @@ pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResu
     Ok(udf)
 }
 
-fn distance(geo_l: &Geometry, geo_r: &Geometry) -> DFResult<f64> {
+pub(super) fn distance(geo_l: &Geometry, geo_r: &Geometry) -> DFResult<f64> {
     let distance = match (geo_l, geo_r) {
         (Geometry::Point(p), other) => point_distance(p, other)?,
         (Geometry::Line(p), other) => line_distance(p, other)?,
@@ -0,0 +1,36 @@
+use datafusion::arrow::array::StringBuilder;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::ScalarUDF;
+use geo::{BoundingRect, Geometry, LineString, Point};
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use super::{geo_to_wkt, rect_to_polygon};
+use crate::geometry_unary_op;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<ScalarUDF> {
+    let udf = geometry_unary_op!("ST_Envelope", envelope, DataType::Utf8, StringBuilder);
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+/// The bounding box of the geometry, which is a POINT or LINESTRING if the bounding box
+/// is degenerate, otherwise a POLYGON.
+fn envelope(geo: &Geometry) -> Result<String, DataFusionError> {
+    let rect = match geo.bounding_rect() {
+        Some(rect) => rect,
+        None => return Ok("POLYGON EMPTY".to_string()),
+    };
+    let (min, max) = (rect.min(), rect.max());
+
+    let envelope: Geometry = if min == max {
+        Point::from(min).into()
+    } else if min.x == max.x || min.y == max.y {
+        LineString::from(vec![min, max]).into()
+    } else {
+        rect_to_polygon(rect).into()
+    };
+
+    geo_to_wkt(&envelope)
+}
@@ -0,0 +1,198 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{downcast_array, ArrayRef, Int64Array, StringArray, StringBuilder};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::Result as DFResult;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{
+    ReturnTypeFunction, ScalarUDF, Signature, TypeSignature, Volatility,
+};
+use datafusion::physical_plan::functions::make_scalar_function;
+use geo::{coord, Geometry, Point, Rect};
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use super::{geo_to_wkt, rect_to_polygon, str_to_geo};
+
+const BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+const MAX_PRECISION: i64 = 12;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<()> {
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Utf8)));
+
+    // ST_GeoHash(point[, precision])
+    let signature = Signature::one_of(
+        vec![
+            TypeSignature::Exact(vec![DataType::Utf8]),
+            TypeSignature::Exact(vec![DataType::Utf8, DataType::Int64]),
+        ],
+        Volatility::Immutable,
+    );
+    let fun = make_scalar_function(geohash);
+    let udf = ScalarUDF::new("ST_GeoHash", &signature, &return_type, &fun);
+    func_manager.register_udf(udf)?;
+
+    let signature = Signature::exact(vec![DataType::Utf8], Volatility::Immutable);
+    let fun = make_scalar_function(point_from_geohash);
+    let udf = ScalarUDF::new("ST_PointFromGeoHash", &signature, &return_type, &fun);
+    func_manager.register_udf(udf)?;
+
+    let fun = make_scalar_function(geom_from_geohash);
+    let udf = ScalarUDF::new("ST_GeomFromGeoHash", &signature, &return_type, &fun);
+    func_manager.register_udf(udf)?;
+
+    Ok(())
+}
+
+fn geohash(args: &[ArrayRef]) -> DFResult<ArrayRef> {
+    let geo_arr = downcast_array::<StringArray>(args[0].as_ref());
+    let precision_arr = args
+        .get(1)
+        .map(|arr| downcast_array::<Int64Array>(arr.as_ref()));
+
+    let mut builder = StringBuilder::new();
+    for (idx, geo) in geo_arr.iter().enumerate() {
+        let precision = match &precision_arr {
+            Some(arr) if arr.is_null(idx) => None,
+            Some(arr) => Some(arr.value(idx)),
+            None => Some(MAX_PRECISION),
+        };
+        match (geo, precision) {
+            (Some(geo), Some(precision)) => {
+                let point = match str_to_geo(geo)? {
+                    Geometry::Point(p) => p,
+                    other => {
+                        return Err(DataFusionError::Execution(format!(
+                            "ST_GeoHash only supports POINT, got {other:?}"
+                        )))
+                    }
+                };
+                builder.append_value(encode(point, precision)?);
+            }
+            _ => builder.append_null(),
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+fn point_from_geohash(args: &[ArrayRef]) -> DFResult<ArrayRef> {
+    map_geohash(args, |rect| Point::from(rect.center()).into())
+}
+
+fn geom_from_geohash(args: &[ArrayRef]) -> DFResult<ArrayRef> {
+    map_geohash(args, |rect| rect_to_polygon(rect).into())
+}
+
+fn map_geohash(args: &[ArrayRef], f: impl Fn(Rect) -> Geometry) -> DFResult<ArrayRef> {
+    let hash_arr = downcast_array::<StringArray>(args[0].as_ref());
+
+    let mut builder = StringBuilder::new();
+    for hash in hash_arr.iter() {
+        match hash {
+            Some(hash) => builder.append_value(geo_to_wkt(&f(decode(hash)?))?),
+            None => builder.append_null(),
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Encodes the point `(longitude, latitude)` into a geohash of `precision` characters.
+fn encode(point: Point, precision: i64) -> DFResult<String> {
+    if !(1..=MAX_PRECISION).contains(&precision) {
+        return Err(DataFusionError::Execution(format!(
+            "Geohash precision must be in [1, {MAX_PRECISION}], got {precision}"
+        )));
+    }
+    let (lon, lat) = point.x_y();
+    if !(-180.0..=180.0).contains(&lon) || !(-90.0..=90.0).contains(&lat) {
+        return Err(DataFusionError::Execution(format!(
+            "Invalid longitude or latitude for geohash: POINT({lon} {lat})"
+        )));
+    }
+
+    let (mut lon_range, mut lat_range) = ((-180.0, 180.0), (-90.0, 90.0));
+    let mut hash = String::with_capacity(precision as usize);
+    let mut is_lon = true;
+    for _ in 0..precision {
+        let mut idx = 0;
+        for _ in 0..5 {
+            let (range, value) = if is_lon {
+                (&mut lon_range, lon)
+            } else {
+                (&mut lat_range, lat)
+            };
+            let mid = (range.0 + range.1) / 2.0;
+            idx <<= 1;
+            if value >= mid {
+                idx |= 1;
+                range.0 = mid;
+            } else {
+                range.1 = mid;
+            }
+            is_lon = !is_lon;
+        }
+        hash.push(BASE32[idx] as char);
+    }
+
+    Ok(hash)
+}
+
+/// Decodes the geohash into the cell, whose x is longitude and y is latitude.
+fn decode(hash: &str) -> DFResult<Rect> {
+    if hash.is_empty() {
+        return Err(DataFusionError::Execution("Empty geohash".to_string()));
+    }
+
+    let (mut lon_range, mut lat_range) = ((-180.0, 180.0), (-90.0, 90.0));
+    let mut is_lon = true;
+    for c in hash.bytes() {
+        let idx = BASE32
+            .iter()
+            .position(|b| *b == c.to_ascii_lowercase())
+            .ok_or_else(|| DataFusionError::Execution(format!("Invalid geohash: {hash}")))?;
+        for bit in (0..5).rev() {
+            let range: &mut (f64, f64) = if is_lon {
+                &mut lon_range
+            } else {
+                &mut lat_range
+            };
+            let mid = (range.0 + range.1) / 2.0;
+            if idx & (1 << bit) != 0 {
+                range.0 = mid;
+            } else {
+                range.1 = mid;
+            }
+            is_lon = !is_lon;
+        }
+    }
+
+    Ok(Rect::new(
+        coord! { x: lon_range.0, y: lat_range.0 },
+        coord! { x: lon_range.1, y: lat_range.1 },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::Point;
+
+    use super::{decode, encode};
+
+    #[test]
+    fn test_geohash() {
+        let point = Point::new(-5.603, 42.605);
+        assert_eq!(encode(point, 5).unwrap(), "ezs42");
+        assert_eq!(encode(point, 12).unwrap().len(), 12);
+        assert!(encode(point, 0).is_err());
+        assert!(encode(Point::new(181.0, 0.0), 5).is_err());
+
+        let cell = decode("ezs42").unwrap();
+        assert!(cell.min().x <= point.x() && point.x() <= cell.max().x);
+        assert!(cell.min().y <= point.y() && point.y() <= cell.max().y);
+        assert!((cell.width() - 360.0 / 2f64.powi(13)).abs() < 1e-12);
+        assert!(decode("ezs4a").is_err());
+        assert!(decode("").is_err());
+    }
+}
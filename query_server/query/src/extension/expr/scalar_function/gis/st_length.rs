@@ -0,0 +1,33 @@
+use datafusion::arrow::array::Float64Builder;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::ScalarUDF;
+use geo::{EuclideanLength, Geometry};
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use crate::geometry_unary_op;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<ScalarUDF> {
+    let udf = geometry_unary_op!("ST_Length", length, DataType::Float64, Float64Builder);
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+/// The 2D length of the linear geometries, 0 for the points and polygons.
+fn length(geo: &Geometry) -> Result<f64, DataFusionError> {
+    let len = match geo {
+        Geometry::Line(l) => l.euclidean_length(),
+        Geometry::LineString(l) => l.euclidean_length(),
+        Geometry::MultiLineString(l) => l.euclidean_length(),
+        Geometry::GeometryCollection(c) => c.iter().map(length).sum::<Result<f64, _>>()?,
+        Geometry::Point(_)
+        | Geometry::MultiPoint(_)
+        | Geometry::Polygon(_)
+        | Geometry::MultiPolygon(_)
+        | Geometry::Rect(_)
+        | Geometry::Triangle(_) => 0.0,
+    };
+
+    Ok(len)
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{downcast_array, ArrayRef, Float64Array, StringBuilder};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::Result as DFResult;
+use datafusion::logical_expr::{ReturnTypeFunction, ScalarUDF, Signature, Volatility};
+use datafusion::physical_plan::functions::make_scalar_function;
+use geo::Point;
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use super::geo_to_wkt;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> ScalarUDF {
+    let fun = make_scalar_function(func);
+
+    // ST_MakePoint(x, y)
+    let signature = Signature::exact(
+        vec![DataType::Float64, DataType::Float64],
+        Volatility::Immutable,
+    );
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(Arc::new(DataType::Utf8)));
+
+    ScalarUDF::new("ST_MakePoint", &signature, &return_type, &fun)
+}
+
+fn func(args: &[ArrayRef]) -> DFResult<ArrayRef> {
+    let x_arr = downcast_array::<Float64Array>(args[0].as_ref());
+    let y_arr = downcast_array::<Float64Array>(args[1].as_ref());
+
+    let mut builder = StringBuilder::new();
+    for (x, y) in x_arr.iter().zip(y_arr.iter()) {
+        match (x, y) {
+            (Some(x), Some(y)) => builder.append_value(geo_to_wkt(&Point::new(x, y).into())?),
+            _ => builder.append_null(),
+        }
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
@@ -6,6 +6,7 @@ mod gauge;
 mod gis;
 mod interpolate;
 mod locf;
+mod sketch;
 mod state_at;
 mod utils;
 
@@ -23,6 +24,8 @@ pub const LOCF: &str = "locf";
 pub const INTERPOLATE: &str = "interpolate";
 pub const DURATION_IN: &str = "duration_in";
 pub const STATE_AT: &str = "state_at";
+pub const APPROX_PERCENTILE: &str = "approx_percentile";
+pub const DISTINCT_COUNT: &str = "distinct_count";
 
 pub fn register_udfs(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<()> {
     // extend function...
@@ -35,6 +38,7 @@ pub fn register_udfs(func_manager: &mut dyn FunctionMetadataManager) -> QueryRes
     duration_in::register_udf(func_manager)?;
     state_at::register_udf(func_manager)?;
     gis::register_udfs(func_manager)?;
+    sketch::register_udfs(func_manager)?;
     TSGenFunc::register_all_udf(func_manager)?;
     Ok(())
 }
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{ArrayRef, StringArray, UInt64Array};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::downcast_value;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{ReturnTypeFunction, ScalarUDF, Signature, Volatility};
+use datafusion::physical_expr::functions::make_scalar_function;
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use crate::extension::expr::aggregate_function::Sketch;
+use crate::extension::expr::scalar_function::DISTINCT_COUNT;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> ScalarUDF {
+    let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::UInt64)));
+
+    let distinct_count = make_scalar_function(distinct_count_implement);
+
+    // distinct_count(sketch STRING) RETURNS BIGINT UNSIGNED
+    ScalarUDF::new(
+        DISTINCT_COUNT,
+        &Signature::exact(vec![DataType::Utf8], Volatility::Immutable),
+        &return_type_fn,
+        &distinct_count,
+    )
+}
+
+fn distinct_count_implement(input: &[ArrayRef]) -> Result<ArrayRef, DataFusionError> {
+    let sketches = downcast_value!(input[0], StringArray);
+
+    let array = sketches
+        .iter()
+        .map(|sketch| {
+            sketch
+                .map(|sketch| Sketch::decode(sketch)?.distinct_count())
+                .transpose()
+        })
+        .collect::<Result<UInt64Array, DataFusionError>>()?;
+
+    Ok(Arc::new(array))
+}
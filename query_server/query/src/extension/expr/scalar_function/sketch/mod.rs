@@ -0,0 +1,11 @@
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+mod approx_percentile;
+mod distinct_count;
+
+pub fn register_udfs(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<()> {
+    approx_percentile::register_udf(func_manager)?;
+    distinct_count::register_udf(func_manager)?;
+    Ok(())
+}
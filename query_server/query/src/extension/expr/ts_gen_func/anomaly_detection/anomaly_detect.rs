@@ -0,0 +1,258 @@
+use serde::Deserialize;
+use spi::DFResult;
+
+use crate::extension::expr::ts_gen_func::utils::get_arg;
+
+/// Returns the points whose values are anomalies.
+pub fn compute(
+    timestamps: &mut [i64],
+    fields: &mut [Vec<f64>],
+    arg_str: Option<&str>,
+) -> DFResult<(Vec<i64>, Vec<f64>)> {
+    let arg = get_arg(arg_str)?;
+    let method = get_method_from_arg(&arg)?;
+    let values = &fields[0];
+
+    // Skip the missing values, they are not anomalies.
+    let points = values
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| v.is_finite())
+        .map(|(i, &v)| (i, v))
+        .collect::<Vec<_>>();
+    let anomalies = match method {
+        DetectMethod::ZScore { threshold } => zscore(&points, threshold),
+        DetectMethod::Iqr { threshold } => iqr(&points, threshold),
+        DetectMethod::Esd {
+            max_anomalies,
+            alpha,
+        } => esd(&points, max_anomalies, alpha),
+    };
+
+    let mut anomalies = anomalies.into_iter().map(|(i, _)| i).collect::<Vec<_>>();
+    anomalies.sort_unstable();
+    Ok(anomalies
+        .into_iter()
+        .map(|i| (timestamps[i], values[i]))
+        .unzip())
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct Arg {
+    method: Option<String>,
+    threshold: Option<f64>,
+    max_anomalies: Option<usize>,
+    alpha: Option<f64>,
+}
+
+enum DetectMethod {
+    ZScore {
+        threshold: f64,
+    },
+    Iqr {
+        threshold: f64,
+    },
+    Esd {
+        max_anomalies: Option<usize>,
+        alpha: f64,
+    },
+}
+
+fn get_method_from_arg(arg: &Arg) -> DFResult<DetectMethod> {
+    if let Some(threshold) = arg.threshold {
+        if !threshold.is_finite() || threshold <= 0.0 {
+            return Err(datafusion::error::DataFusionError::Execution(
+                "threshold must be positive".to_string(),
+            ));
+        }
+    }
+    if let Some(alpha) = arg.alpha {
+        if !(alpha > 0.0 && alpha < 1.0) {
+            return Err(datafusion::error::DataFusionError::Execution(
+                "alpha must be between 0 and 1".to_string(),
+            ));
+        }
+    }
+    if arg.max_anomalies == Some(0) {
+        return Err(datafusion::error::DataFusionError::Execution(
+            "max_anomalies must be positive".to_string(),
+        ));
+    }
+
+    let method = arg.method.as_deref().unwrap_or("zscore");
+    Ok(match method.to_ascii_lowercase().as_str() {
+        "zscore" => DetectMethod::ZScore {
+            threshold: arg.threshold.unwrap_or(3.0),
+        },
+        "iqr" => DetectMethod::Iqr {
+            threshold: arg.threshold.unwrap_or(1.5),
+        },
+        "esd" => DetectMethod::Esd {
+            max_anomalies: arg.max_anomalies,
+            alpha: arg.alpha.unwrap_or(0.05),
+        },
+        _ => Err(datafusion::error::DataFusionError::Execution(format!(
+            "Invalid method: {method}"
+        )))?,
+    })
+}
+
+fn mean_std(points: &[(usize, f64)]) -> (f64, f64) {
+    let n = points.len() as f64;
+    let mean = points.iter().map(|(_, v)| v).sum::<f64>() / n;
+    let var = points.iter().map(|(_, v)| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, var.sqrt())
+}
+
+/// The points whose distances to the mean are larger than `threshold` standard deviations.
+fn zscore(points: &[(usize, f64)], threshold: f64) -> Vec<(usize, f64)> {
+    if points.len() < 2 {
+        return vec![];
+    }
+    let (mean, std) = mean_std(points);
+    if std == 0.0 {
+        return vec![];
+    }
+
+    points
+        .iter()
+        .filter(|(_, v)| ((v - mean) / std).abs() > threshold)
+        .copied()
+        .collect()
+}
+
+/// The points out of `[Q1 - threshold * IQR, Q3 + threshold * IQR]`.
+fn iqr(points: &[(usize, f64)], threshold: f64) -> Vec<(usize, f64)> {
+    if points.is_empty() {
+        return vec![];
+    }
+    let mut sorted = points.iter().map(|(_, v)| *v).collect::<Vec<_>>();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let q1 = quantile(&sorted, 0.25);
+    let q3 = quantile(&sorted, 0.75);
+    let lower = q1 - threshold * (q3 - q1);
+    let upper = q3 + threshold * (q3 - q1);
+
+    points
+        .iter()
+        .filter(|(_, v)| *v < lower || *v > upper)
+        .copied()
+        .collect()
+}
+
+/// Quantile of the sorted values with linear interpolation.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let lower = pos.floor() as usize;
+    let upper = pos.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (pos - lower as f64)
+}
+
+/// Generalized extreme studentized deviate test, which detects up to `max_anomalies`
+/// anomalies in the approximately normally distributed values.
+fn esd(points: &[(usize, f64)], max_anomalies: Option<usize>, alpha: f64) -> Vec<(usize, f64)> {
+    let n = points.len();
+    if n < 3 {
+        return vec![];
+    }
+    let max_anomalies = max_anomalies.unwrap_or((n / 10).max(1)).min(n - 2);
+
+    let mut remaining = points.to_vec();
+    let mut removed = Vec::with_capacity(max_anomalies);
+    let mut num_anomalies = 0;
+    for i in 1..=max_anomalies {
+        let (mean, std) = mean_std(&remaining);
+        if std == 0.0 {
+            break;
+        }
+        let (idx, r) = remaining
+            .iter()
+            .enumerate()
+            .map(|(idx, (_, v))| (idx, (v - mean).abs() / std))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or_default();
+        removed.push(remaining.swap_remove(idx));
+
+        let dof = (n - i - 1) as f64;
+        let p = 1.0 - alpha / (2.0 * (n - i + 1) as f64);
+        let t = student_t_quantile(p, dof);
+        let lambda = (n - i) as f64 * t / ((dof + t * t) * (n - i + 1) as f64).sqrt();
+        if r > lambda {
+            num_anomalies = i;
+        }
+    }
+
+    removed.truncate(num_anomalies);
+    removed
+}
+
+/// Approximated quantile of the Student's t-distribution, by the Cornish-Fisher expansion.
+fn student_t_quantile(p: f64, dof: f64) -> f64 {
+    if dof == 1.0 {
+        return (std::f64::consts::PI * (p - 0.5)).tan();
+    }
+    if dof == 2.0 {
+        return (2.0 * p - 1.0) / (2.0 * p * (1.0 - p)).sqrt();
+    }
+
+    let z = normal_quantile(p);
+    let z3 = z.powi(3);
+    let z5 = z.powi(5);
+    let z7 = z.powi(7);
+    let z9 = z.powi(9);
+    let g1 = (z3 + z) / 4.0;
+    let g2 = (5.0 * z5 + 16.0 * z3 + 3.0 * z) / 96.0;
+    let g3 = (3.0 * z7 + 19.0 * z5 + 17.0 * z3 - 15.0 * z) / 384.0;
+    let g4 = (79.0 * z9 + 776.0 * z7 + 1482.0 * z5 - 1920.0 * z3 - 945.0 * z) / 92160.0;
+    z + g1 / dof + g2 / dof.powi(2) + g3 / dof.powi(3) + g4 / dof.powi(4)
+}
+
+/// Quantile of the standard normal distribution, by the algorithm of Peter J. Acklam.
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    if p < P_LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        let q = (-2.0 * (1.0 - p).ln()).sqrt();
+        -(((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    }
+}
@@ -0,0 +1 @@
+pub mod anomaly_detect;
@@ -0,0 +1,193 @@
+use serde::Deserialize;
+use spi::DFResult;
+
+use crate::extension::expr::ts_gen_func::forecasting::seasonal_decompose::check_values;
+use crate::extension::expr::ts_gen_func::utils::get_arg;
+
+/// The candidates of the smoothing parameters which are not specified.
+const SMOOTHING_PARAMS: [f64; 9] = [0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9];
+
+/// Returns the forecasted values of the `horizon` timestamps after the last one.
+pub fn compute(
+    timestamps: &mut [i64],
+    fields: &mut [Vec<f64>],
+    arg_str: Option<&str>,
+) -> DFResult<(Vec<i64>, Vec<f64>)> {
+    let arg: Arg = get_arg(arg_str)?;
+    let method = get_method_from_arg(&arg)?;
+    let values = &fields[0];
+
+    if arg.horizon == Some(0) {
+        return Err(datafusion::error::DataFusionError::Execution(
+            "horizon must be positive".to_string(),
+        ));
+    }
+    let horizon = arg.horizon.unwrap_or(10);
+    let interval = match arg.interval {
+        Some(interval) if interval <= 0 => {
+            return Err(datafusion::error::DataFusionError::Execution(
+                "interval must be positive".to_string(),
+            ));
+        }
+        Some(interval) => interval * 1_000_000,
+        None => interval_median(timestamps)?,
+    };
+
+    let forecasted = match method {
+        ForecastMethod::HoltWinters => {
+            let period = arg.period.filter(|p| *p > 1);
+            check_values(values, period.unwrap_or(1))?;
+            let params = [arg.alpha, arg.beta, arg.gamma];
+            for param in params.iter().flatten() {
+                if !(*param > 0.0 && *param <= 1.0) {
+                    return Err(datafusion::error::DataFusionError::Execution(
+                        "alpha, beta and gamma must be in (0, 1]".to_string(),
+                    ));
+                }
+            }
+            holt_winters(values, period, params, horizon)
+        }
+    };
+
+    let last = timestamps[timestamps.len() - 1];
+    let forecasted_timestamps = (1..=horizon as i64).map(|h| last + h * interval).collect();
+    Ok((forecasted_timestamps, forecasted))
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct Arg {
+    method: Option<String>,
+    horizon: Option<usize>,
+    interval: Option<i64>,
+    period: Option<usize>,
+    alpha: Option<f64>,
+    beta: Option<f64>,
+    gamma: Option<f64>,
+}
+
+enum ForecastMethod {
+    HoltWinters,
+}
+
+fn get_method_from_arg(arg: &Arg) -> DFResult<ForecastMethod> {
+    Ok(match arg.method.as_deref() {
+        Some(method) => match method.to_ascii_lowercase().as_str() {
+            "holt_winters" => ForecastMethod::HoltWinters,
+            _ => Err(datafusion::error::DataFusionError::Execution(format!(
+                "Invalid method: {method}"
+            )))?,
+        },
+        None => ForecastMethod::HoltWinters,
+    })
+}
+
+fn interval_median(timestamps: &[i64]) -> DFResult<i64> {
+    if timestamps.len() < 2 {
+        return Err(datafusion::error::DataFusionError::Execution(
+            "At least 2 values are required".to_string(),
+        ));
+    }
+    let mut intervals = timestamps
+        .windows(2)
+        .map(|w| w[1] - w[0])
+        .collect::<Vec<_>>();
+    intervals.sort_unstable();
+    let interval = intervals[intervals.len() / 2];
+    if interval <= 0 {
+        return Err(datafusion::error::DataFusionError::Execution(
+            "Timestamps must be distinct".to_string(),
+        ));
+    }
+    Ok(interval)
+}
+
+/// Additive Holt-Winters method, the seasonal component is ignored if `period` is `None`.
+///
+/// The smoothing parameters `[alpha, beta, gamma]` which are not specified are chosen to
+/// minimize the squared errors of the one-step-ahead forecasts.
+fn holt_winters(
+    values: &[f64],
+    period: Option<usize>,
+    params: [Option<f64>; 3],
+    horizon: usize,
+) -> Vec<f64> {
+    let candidates = |param: Option<f64>| match param {
+        Some(p) => vec![p],
+        None => SMOOTHING_PARAMS.to_vec(),
+    };
+    let gammas = match period {
+        Some(_) => candidates(params[2]),
+        None => vec![0.0],
+    };
+
+    let mut best: Option<HoltWinters> = None;
+    for &alpha in candidates(params[0]).iter() {
+        for &beta in candidates(params[1]).iter() {
+            for &gamma in gammas.iter() {
+                let model = HoltWinters::fit(values, period, alpha, beta, gamma);
+                if best.as_ref().map_or(true, |best| model.sse < best.sse) {
+                    best = Some(model);
+                }
+            }
+        }
+    }
+
+    let model = best.unwrap_or_default();
+    (1..=horizon)
+        .map(|h| {
+            let seasonal = match period {
+                Some(m) => model.seasonal[model.seasonal.len() - m + (h - 1) % m],
+                None => 0.0,
+            };
+            model.level + h as f64 * model.trend + seasonal
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct HoltWinters {
+    level: f64,
+    trend: f64,
+    seasonal: Vec<f64>,
+    /// Sum of the squared errors of the one-step-ahead forecasts.
+    sse: f64,
+}
+
+impl HoltWinters {
+    fn fit(values: &[f64], period: Option<usize>, alpha: f64, beta: f64, gamma: f64) -> Self {
+        let (mut level, mut trend, mut seasonal, start) = match period {
+            Some(m) => {
+                let first = values[..m].iter().sum::<f64>() / m as f64;
+                let second = values[m..2 * m].iter().sum::<f64>() / m as f64;
+                let seasonal = values[..m].iter().map(|v| v - first).collect::<Vec<_>>();
+                (first, (second - first) / m as f64, seasonal, m)
+            }
+            None => (values[0], values[1] - values[0], vec![], 1),
+        };
+
+        let mut sse = 0.0;
+        for (t, &value) in values.iter().enumerate().skip(start) {
+            let last_seasonal = match period {
+                Some(m) => seasonal[t - m],
+                None => 0.0,
+            };
+            let error = value - (level + trend + last_seasonal);
+            sse += error * error;
+
+            let last_level = level;
+            level = alpha * (value - last_seasonal) + (1.0 - alpha) * (level + trend);
+            trend = beta * (level - last_level) + (1.0 - beta) * trend;
+            if period.is_some() {
+                seasonal.push(gamma * (value - level) + (1.0 - gamma) * last_seasonal);
+            }
+        }
+
+        Self {
+            level,
+            trend,
+            seasonal,
+            sse,
+        }
+    }
+}
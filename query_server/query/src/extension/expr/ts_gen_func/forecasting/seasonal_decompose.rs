@@ -0,0 +1,250 @@
+use serde::Deserialize;
+use spi::DFResult;
+
+use crate::extension::expr::ts_gen_func::utils::get_arg;
+
+/// Returns one of the trend, seasonal and residual components of the values, which are
+/// decomposed by STL (Seasonal-Trend decomposition using LOESS).
+pub fn compute(
+    timestamps: &mut Vec<i64>,
+    fields: &mut [Vec<f64>],
+    arg_str: Option<&str>,
+) -> DFResult<(Vec<i64>, Vec<f64>)> {
+    let arg: Arg = get_arg(arg_str)?;
+    let component = get_component_from_arg(&arg)?;
+    let values = std::mem::take(&mut fields[0]);
+    let period = arg.period.ok_or_else(|| {
+        datafusion::error::DataFusionError::Execution("period is required".to_string())
+    })?;
+    if period < 2 {
+        return Err(datafusion::error::DataFusionError::Execution(
+            "period must be at least 2".to_string(),
+        ));
+    }
+    check_values(&values, period)?;
+
+    let (seasonal, trend) = stl(
+        &values,
+        period,
+        arg.seasonal_smoother.unwrap_or(7),
+        arg.robust.unwrap_or(false),
+    );
+    let result = match component {
+        Component::Trend => trend,
+        Component::Seasonal => seasonal,
+        Component::Residual => values
+            .iter()
+            .zip(seasonal.iter().zip(trend.iter()))
+            .map(|(v, (s, t))| v - s - t)
+            .collect(),
+    };
+
+    Ok((std::mem::take(timestamps), result))
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct Arg {
+    period: Option<usize>,
+    component: Option<String>,
+    seasonal_smoother: Option<usize>,
+    robust: Option<bool>,
+}
+
+enum Component {
+    Trend,
+    Seasonal,
+    Residual,
+}
+
+fn get_component_from_arg(arg: &Arg) -> DFResult<Component> {
+    Ok(match arg.component.as_deref() {
+        Some(component) => match component.to_ascii_lowercase().as_str() {
+            "trend" => Component::Trend,
+            "seasonal" => Component::Seasonal,
+            "residual" => Component::Residual,
+            _ => Err(datafusion::error::DataFusionError::Execution(format!(
+                "Invalid component: {component}"
+            )))?,
+        },
+        None => Component::Trend,
+    })
+}
+
+/// Checks that the values have no missing values and cover at least two periods.
+pub fn check_values(values: &[f64], period: usize) -> DFResult<()> {
+    if values.iter().any(|v| !v.is_finite()) {
+        return Err(datafusion::error::DataFusionError::Execution(
+            "Values must not be missing, try value_fill first".to_string(),
+        ));
+    }
+    if values.len() < 2 * period {
+        return Err(datafusion::error::DataFusionError::Execution(format!(
+            "At least {} values are required for period {period}, got {}",
+            2 * period,
+            values.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Decomposes the values into the seasonal and trend components.
+///
+/// `period` is the number of values in a period, `seasonal_smoother` is the span of the
+/// LOESS smoothing the cycle-subseries, and the robust decomposition lowers the weights
+/// of the outliers.
+pub fn stl(
+    values: &[f64],
+    period: usize,
+    seasonal_smoother: usize,
+    robust: bool,
+) -> (Vec<f64>, Vec<f64>) {
+    let n = values.len();
+    let ns = next_odd(seasonal_smoother.max(3));
+    let nl = next_odd(period);
+    let nt = next_odd((1.5 * period as f64 / (1.0 - 1.5 / ns as f64)).ceil() as usize);
+    let (inner_loops, outer_loops) = if robust { (1, 15) } else { (2, 0) };
+
+    let mut weights = vec![1.0; n];
+    let mut seasonal = vec![0.0; n];
+    let mut trend = vec![0.0; n];
+    for outer in 0..=outer_loops {
+        for _ in 0..inner_loops {
+            let detrended = values
+                .iter()
+                .zip(trend.iter())
+                .map(|(v, t)| v - t)
+                .collect::<Vec<_>>();
+
+            // Smooth the cycle-subseries, each of them is extended by one period at both ends.
+            let mut cycle = vec![0.0; n + 2 * period];
+            for k in 0..period {
+                let sub_values = detrended.iter().skip(k).step_by(period);
+                let sub_values = sub_values.copied().collect::<Vec<_>>();
+                let sub_weights = weights.iter().skip(k).step_by(period);
+                let sub_weights = sub_weights.copied().collect::<Vec<_>>();
+                for j in -1..=sub_values.len() as i64 {
+                    cycle[((j + 1) as usize) * period + k] =
+                        loess(&sub_values, Some(&sub_weights), ns, j as f64);
+                }
+            }
+
+            // Remove the low-frequency part of the smoothed cycle-subseries.
+            let low_pass =
+                moving_average(&moving_average(&moving_average(&cycle, period), period), 3);
+            for (i, s) in seasonal.iter_mut().enumerate() {
+                *s = cycle[period + i] - loess(&low_pass, None, nl, i as f64);
+            }
+
+            let deseasonalized = values
+                .iter()
+                .zip(seasonal.iter())
+                .map(|(v, s)| v - s)
+                .collect::<Vec<_>>();
+            for (i, t) in trend.iter_mut().enumerate() {
+                *t = loess(&deseasonalized, Some(&weights), nt, i as f64);
+            }
+        }
+
+        if outer < outer_loops {
+            let residuals = (0..n)
+                .map(|i| (values[i] - seasonal[i] - trend[i]).abs())
+                .collect::<Vec<_>>();
+            let mut sorted = residuals.clone();
+            sorted.sort_by(|a, b| a.total_cmp(b));
+            let h = 6.0 * (sorted[(n - 1) / 2] + sorted[n / 2]) / 2.0;
+            for (w, r) in weights.iter_mut().zip(residuals) {
+                *w = if h == 0.0 { 1.0 } else { bisquare(r / h) };
+            }
+        }
+    }
+
+    (seasonal, trend)
+}
+
+fn next_odd(n: usize) -> usize {
+    if n % 2 == 0 {
+        n + 1
+    } else {
+        n
+    }
+}
+
+fn moving_average(values: &[f64], len: usize) -> Vec<f64> {
+    let mut sum = values[..len].iter().sum::<f64>();
+    let mut result = Vec::with_capacity(values.len() - len + 1);
+    result.push(sum / len as f64);
+    for (new, old) in values[len..].iter().zip(values) {
+        sum += new - old;
+        result.push(sum / len as f64);
+    }
+    result
+}
+
+fn tricube(u: f64) -> f64 {
+    if u <= 0.001 {
+        1.0
+    } else if u <= 0.999 {
+        (1.0 - u.powi(3)).powi(3)
+    } else {
+        0.0
+    }
+}
+
+fn bisquare(u: f64) -> f64 {
+    if u <= 0.001 {
+        1.0
+    } else if u <= 0.999 {
+        (1.0 - u * u).powi(2)
+    } else {
+        0.0
+    }
+}
+
+/// Locally weighted linear regression of the `span` values nearest to the position `x`,
+/// the values are at the positions `0..values.len()`.
+fn loess(values: &[f64], weights: Option<&[f64]>, span: usize, x: f64) -> f64 {
+    let n = values.len();
+    let q = span.min(n);
+    let left = (x.floor() as i64 - (q as i64 - 1) / 2).clamp(0, (n - q) as i64) as usize;
+    let right = left + q - 1;
+    let mut h = (x - left as f64).max(right as f64 - x);
+    if span > n {
+        h += ((span - n) / 2) as f64;
+    }
+
+    let mut w = (left..=right)
+        .map(|j| {
+            let u = if h > 0.0 {
+                (j as f64 - x).abs() / h
+            } else {
+                0.0
+            };
+            tricube(u) * weights.map(|w| w[j]).unwrap_or(1.0)
+        })
+        .collect::<Vec<_>>();
+    let sum = w.iter().sum::<f64>();
+    if sum <= 0.0 {
+        let nearest = x.round().clamp(0.0, (n - 1) as f64) as usize;
+        return values[nearest];
+    }
+    w.iter_mut().for_each(|w| *w /= sum);
+
+    // Fit a line instead of a constant if the positions are spread enough.
+    let mean = (left..=right)
+        .zip(&w)
+        .map(|(j, w)| j as f64 * w)
+        .sum::<f64>();
+    let var = (left..=right)
+        .zip(&w)
+        .map(|(j, w)| w * (j as f64 - mean).powi(2))
+        .sum::<f64>();
+    if var.sqrt() > 0.001 * (n - 1) as f64 {
+        let slope = (x - mean) / var;
+        for (j, w) in (left..=right).zip(w.iter_mut()) {
+            *w *= slope * (j as f64 - mean) + 1.0;
+        }
+    }
+
+    (left..=right).zip(&w).map(|(j, w)| w * values[j]).sum()
+}
@@ -1,8 +1,12 @@
+mod anomaly_detection;
 mod data_repair;
+mod forecasting;
 mod utils;
 
+use anomaly_detection::anomaly_detect;
 use data_repair::{timestamp_repair, value_fill, value_repair};
 use datafusion::logical_expr::ScalarUDF;
+use forecasting::{forecast, seasonal_decompose};
 use spi::query::function::FunctionMetadataManager;
 use spi::{DFResult, QueryResult};
 use strum::IntoEnumIterator;
@@ -13,6 +17,9 @@ pub enum TSGenFunc {
     TimestampRepair,
     ValueFill,
     ValueRepair,
+    AnomalyDetect,
+    SeasonalDecompose,
+    Forecast,
 }
 
 impl TSGenFunc {
@@ -21,6 +28,9 @@ impl TSGenFunc {
             TSGenFunc::TimestampRepair => "timestamp_repair",
             TSGenFunc::ValueFill => "value_fill",
             TSGenFunc::ValueRepair => "value_repair",
+            TSGenFunc::AnomalyDetect => "anomaly_detect",
+            TSGenFunc::SeasonalDecompose => "seasonal_decompose",
+            TSGenFunc::Forecast => "forecast",
         }
     }
 
@@ -28,11 +38,21 @@ impl TSGenFunc {
         TSGenFunc::iter().find(|func| func.name() == name)
     }
 
+    /// Whether the function generates timestamps different from the input ones.
+    pub fn generates_timestamps(&self) -> bool {
+        matches!(
+            self,
+            TSGenFunc::TimestampRepair | TSGenFunc::AnomalyDetect | TSGenFunc::Forecast
+        )
+    }
+
     fn scalar_udf(&self) -> ScalarUDF {
         match self {
-            TSGenFunc::TimestampRepair | TSGenFunc::ValueFill | TSGenFunc::ValueRepair => {
-                utils::common_udf(self.name())
-            }
+            TSGenFunc::TimestampRepair
+            | TSGenFunc::ValueFill
+            | TSGenFunc::ValueRepair
+            | TSGenFunc::AnomalyDetect => utils::common_udf(self.name()),
+            TSGenFunc::SeasonalDecompose | TSGenFunc::Forecast => utils::float64_udf(self.name()),
         }
     }
 
@@ -53,6 +73,11 @@ impl TSGenFunc {
             TSGenFunc::TimestampRepair => timestamp_repair::compute(timestamps, fields, arg_str),
             TSGenFunc::ValueFill => value_fill::compute(timestamps, fields, arg_str),
             TSGenFunc::ValueRepair => value_repair::compute(timestamps, fields, arg_str),
+            TSGenFunc::AnomalyDetect => anomaly_detect::compute(timestamps, fields, arg_str),
+            TSGenFunc::SeasonalDecompose => {
+                seasonal_decompose::compute(timestamps, fields, arg_str)
+            }
+            TSGenFunc::Forecast => forecast::compute(timestamps, fields, arg_str),
         }
     }
 }
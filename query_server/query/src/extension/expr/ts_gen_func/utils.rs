@@ -12,17 +12,47 @@ use crate::extension::expr::scalar_function::unimplemented_scalar_impl;
 
 pub fn common_udf(name: &'static str) -> ScalarUDF {
     let return_type_func: ReturnTypeFunction = Arc::new(|args| {
-        if args.len() < 2 || args.len() > 3 {
-            Err(datafusion::error::DataFusionError::Plan(format!(
-                "Expected 2 or 3 arguments, got {}",
-                args.len()
-            )))
-        } else {
-            Ok(Arc::new(args[1].clone()))
-        }
+        check_arg_num(args)?;
+        Ok(Arc::new(args[1].clone()))
     });
 
-    let type_signatures = TIMESTAMPS
+    ScalarUDF::new(
+        name,
+        &Signature::one_of(type_signatures(), Volatility::Immutable),
+        &return_type_func,
+        &unimplemented_scalar_impl(name),
+    )
+}
+
+/// The udf of the functions generating values of the Float64 type, whatever the type of
+/// the input values is.
+pub fn float64_udf(name: &'static str) -> ScalarUDF {
+    let return_type_func: ReturnTypeFunction = Arc::new(|args| {
+        check_arg_num(args)?;
+        Ok(Arc::new(DataType::Float64))
+    });
+
+    ScalarUDF::new(
+        name,
+        &Signature::one_of(type_signatures(), Volatility::Immutable),
+        &return_type_func,
+        &unimplemented_scalar_impl(name),
+    )
+}
+
+fn check_arg_num(args: &[DataType]) -> DFResult<()> {
+    if args.len() < 2 || args.len() > 3 {
+        Err(datafusion::error::DataFusionError::Plan(format!(
+            "Expected 2 or 3 arguments, got {}",
+            args.len()
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+fn type_signatures() -> Vec<TypeSignature> {
+    TIMESTAMPS
         .iter()
         .flat_map(|t| {
             NUMERICS.iter().flat_map(|v| {
@@ -32,14 +62,7 @@ pub fn common_udf(name: &'static str) -> ScalarUDF {
                 ]
             })
         })
-        .collect();
-
-    ScalarUDF::new(
-        name,
-        &Signature::one_of(type_signatures, Volatility::Immutable),
-        &return_type_func,
-        &unimplemented_scalar_impl(name),
-    )
+        .collect()
 }
 
 pub fn get_arg<T: Default + for<'a> Deserialize<'a>>(arg_str: Option<&str>) -> DFResult<T> {
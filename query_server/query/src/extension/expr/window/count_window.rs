@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::ArrayRef;
+use datafusion::arrow::datatypes::{DataType, Field, Fields, TimeUnit};
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{ReturnTypeFunction, ScalarUDF, Signature, Volatility};
+use datafusion::physical_expr::functions::make_scalar_function;
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use super::{COUNT_WINDOW, WINDOW_END, WINDOW_START};
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> ScalarUDF {
+    let func = |_: &[ArrayRef]| {
+        Err(DataFusionError::Execution(format!(
+            "{} has no specific implementation, should be converted to Window operator.",
+            COUNT_WINDOW
+        )))
+    };
+    let func = make_scalar_function(func);
+
+    // count_window
+    // - windowSize
+    //
+    // group by count_window(100)
+    let signature = Signature::exact(vec![DataType::Int64], Volatility::Immutable);
+
+    // Struct(_start, _end), the time of the first and the last point in the window
+    let return_type: ReturnTypeFunction = Arc::new(move |_| {
+        let time_type = DataType::Timestamp(TimeUnit::Nanosecond, None);
+        let window = DataType::Struct(Fields::from(vec![
+            Field::new(WINDOW_START, time_type.clone(), false),
+            Field::new(WINDOW_END, time_type, false),
+        ]));
+
+        Ok(Arc::new(window))
+    });
+
+    ScalarUDF::new(COUNT_WINDOW, &signature, &return_type, &func)
+}
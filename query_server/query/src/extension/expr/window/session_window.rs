@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::ArrayRef;
+use datafusion::arrow::datatypes::{DataType, Field, Fields};
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::type_coercion::aggregates::TIMESTAMPS;
+use datafusion::logical_expr::{
+    ReturnTypeFunction, ScalarUDF, Signature, TypeSignature, Volatility,
+};
+use datafusion::physical_expr::functions::make_scalar_function;
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use super::{SESSION_WINDOW, WINDOW_END, WINDOW_START};
+use crate::extension::expr::INTERVALS;
+
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> ScalarUDF {
+    let func = |_: &[ArrayRef]| {
+        Err(DataFusionError::Execution(format!(
+            "{} has no specific implementation, should be converted to Window operator.",
+            SESSION_WINDOW
+        )))
+    };
+    let func = make_scalar_function(func);
+
+    // session_window
+    // - timeColumn
+    // - gapDuration
+    //
+    // group by session_window(time, interval '5 minutes')
+    let type_signatures = TIMESTAMPS
+        .iter()
+        .flat_map(|first| {
+            INTERVALS
+                .iter()
+                .map(|second| TypeSignature::Exact(vec![first.clone(), second.clone()]))
+        })
+        .collect();
+    let signature = Signature::one_of(type_signatures, Volatility::Immutable);
+
+    // Struct(_start, _end)
+    let return_type: ReturnTypeFunction = Arc::new(move |input_expr_types| {
+        let window = DataType::Struct(Fields::from(vec![
+            Field::new(WINDOW_START, input_expr_types[0].clone(), false),
+            Field::new(WINDOW_END, input_expr_types[0].clone(), false),
+        ]));
+
+        Ok(Arc::new(window))
+    });
+
+    ScalarUDF::new(SESSION_WINDOW, &signature, &return_type, &func)
+}
@@ -17,6 +17,7 @@ use super::plan_node::expand::ExpandNode;
 use super::plan_node::stream_scan::StreamScanPlanNode;
 use super::plan_node::table_writer_merge::TableWriterMergePlanNode;
 use super::plan_node::watermark::WatermarkNode;
+use super::plan_node::window_state::{StatefulWindow, WindowStateNode};
 use crate::extension::logical::plan_node::table_writer::TableWriterPlanNode;
 
 /// Used to extend the function of datafusion's [`LogicalPlanBuilder`]
@@ -24,6 +25,13 @@ pub trait LogicalPlanBuilderExt: Sized {
     /// Apply a expand with specific projections
     fn expand(self, projections: Vec<Vec<Expr>>) -> Result<Self>;
     fn watermark(self, watermark: Watermark) -> Result<Self>;
+    /// Keep the points of the windows still open between the micro-batches of a stream
+    fn window_state(
+        self,
+        window: StatefulWindow,
+        time_column: Expr,
+        partition_by: Vec<Expr>,
+    ) -> Result<Self>;
     fn stream_scan(
         table_name: impl Into<OwnedTableReference>,
         table_source: StreamProviderRef,
@@ -59,6 +67,28 @@ impl LogicalPlanBuilderExt for LogicalPlanBuilder {
         Ok(Self::from(plan))
     }
 
+    fn window_state(
+        self,
+        window: StatefulWindow,
+        time_column: Expr,
+        partition_by: Vec<Expr>,
+    ) -> Result<Self> {
+        let input = Arc::new(self.build()?);
+
+        let window_state_node = Arc::new(WindowStateNode::new(
+            window,
+            time_column,
+            partition_by,
+            input,
+        ));
+
+        let plan = LogicalPlan::Extension(Extension {
+            node: window_state_node,
+        });
+
+        Ok(Self::from(plan))
+    }
+
     /// Convert a stream provider into a builder with a [`StreamScanPlanNode`]
     fn stream_scan(
         table_name: impl Into<OwnedTableReference>,
@@ -19,7 +19,7 @@ use std::ops::Deref;
 // use std::sync::Arc;
 use datafusion::common::Column;
 use datafusion::error::{DataFusionError, Result};
-use datafusion::logical_expr::expr::AggregateFunction;
+use datafusion::logical_expr::expr::{AggregateFunction, AggregateUDF};
 use datafusion::logical_expr::utils::{exprlist_to_columns, grouping_set_to_exprlist};
 use datafusion::logical_expr::{
     AggWithGrouping, Aggregate, AggregateFunction as AggregateFunctionName, LogicalPlan,
@@ -28,6 +28,17 @@ use datafusion::logical_expr::{
 use datafusion::optimizer::{optimize_children, OptimizerConfig, OptimizerRule};
 use datafusion::prelude::Expr;
 
+use crate::extension::expr::{
+    HYPERLOGLOG_UDAF_NAME, ROLLUP_UDAF, TDIGEST_UDAF_NAME, UDDSKETCH_UDAF_NAME,
+};
+
+/// The aggregate functions returning the sketches, which are merged by `rollup`
+const SKETCH_UDAF_NAMES: [&str; 3] = [
+    TDIGEST_UDAF_NAME,
+    UDDSKETCH_UDAF_NAME,
+    HYPERLOGLOG_UDAF_NAME,
+];
+
 /// Push Down Aggregation optimizer rule pushes aggregation clauses down the plan
 /// # Introduction
 /// TODO
@@ -153,6 +164,20 @@ impl OptimizerRule for PushDownAggregation {
 
                                         Ok(Expr::AggregateFunction(new_agg_func))
                                     },
+                                    // The sketches of the vnodes are merged
+                                    Expr::AggregateUDF(AggregateUDF {
+                                        fun: _,
+                                        args: _,
+                                        filter,
+                                        order_by,
+                                    }) => {
+                                        Ok(Expr::AggregateUDF(AggregateUDF {
+                                            fun: ROLLUP_UDAF.clone(),
+                                            args: vec![Expr::Column(column)],
+                                            filter: filter.clone(),
+                                            order_by: order_by.clone(),
+                                        }))
+                                    },
                                     _ => Err(DataFusionError::Internal("Invalid logical plan, Aggregate's aggr_expr contains non-aggregate expr.".to_string())),
                                 }?;
 
@@ -247,6 +272,120 @@ fn determine_whether_support_push_down(aggr_expr: &[Expr]) -> bool {
 
             support_agg_func && !distinct
         }
+        Expr::AggregateUDF(AggregateUDF { fun, filter, .. }) => {
+            SKETCH_UDAF_NAMES.contains(&fun.name.as_str()) && filter.is_none()
+        }
         _ => false,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::any::Any;
+    use std::sync::Arc;
+
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion::logical_expr::TableSource;
+    use datafusion::optimizer::OptimizerContext;
+    use datafusion::prelude::{col, lit};
+    use spi::query::function::FunctionMetadataManager;
+
+    use super::*;
+    use crate::extension::expr::load_all_functions;
+    use crate::function::simple_func_manager::SimpleFunctionMetadataManager;
+
+    struct TestSource {
+        schema: SchemaRef,
+    }
+
+    impl TableSource for TestSource {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn schema(&self) -> SchemaRef {
+            self.schema.clone()
+        }
+
+        fn supports_aggregate_pushdown(
+            &self,
+            _group_expr: &[Expr],
+            _aggr_expr: &[Expr],
+        ) -> Result<TableProviderAggregationPushDown> {
+            Ok(TableProviderAggregationPushDown::Ungrouped)
+        }
+    }
+
+    fn aggregate(aggr_expr: Vec<Expr>) -> LogicalPlan {
+        let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Float64, true)]));
+        LogicalPlanBuilder::scan("t", Arc::new(TestSource { schema }), None)
+            .unwrap()
+            .aggregate(Vec::<Expr>::new(), aggr_expr)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    fn udaf(name: &str) -> Arc<datafusion::logical_expr::AggregateUDF> {
+        let mut func_manager = SimpleFunctionMetadataManager::default();
+        load_all_functions(&mut func_manager).unwrap();
+        func_manager.udaf(name).unwrap()
+    }
+
+    #[test]
+    fn test_push_down_sketch() {
+        for name in SKETCH_UDAF_NAMES {
+            let plan = aggregate(vec![udaf(name).call(vec![col("v")])]);
+            let optimized = PushDownAggregation::new()
+                .try_optimize(&plan, &OptimizerContext::new())
+                .unwrap()
+                .unwrap();
+
+            // Projection: rollup(sketch(t.v)) AS sketch(t.v)
+            //   Aggregate: groupBy=[[]], aggr=[[rollup(sketch(t.v))]]
+            //     TableScan: t groupBy=[[]], aggr=[[sketch(t.v)]]
+            let LogicalPlan::Projection(Projection { input, .. }) = &optimized else {
+                panic!("expect projection, got {optimized:?}");
+            };
+            let LogicalPlan::Aggregate(Aggregate {
+                input, aggr_expr, ..
+            }) = input.as_ref()
+            else {
+                panic!("expect aggregate, got {input:?}");
+            };
+            assert!(matches!(
+                &aggr_expr[..],
+                [Expr::AggregateUDF(AggregateUDF { fun, .. })] if fun.name == ROLLUP_UDAF.name
+            ));
+            let LogicalPlan::TableScan(TableScan {
+                agg_with_grouping: Some(agg_with_grouping),
+                ..
+            }) = input.as_ref()
+            else {
+                panic!("expect table scan with pushed down aggregate, got {input:?}");
+            };
+            assert_eq!(agg_with_grouping.agg_expr, plan.expressions());
+            assert_eq!(optimized.schema(), plan.schema());
+        }
+    }
+
+    #[test]
+    fn test_not_push_down_other_udaf() {
+        let plan = aggregate(vec![udaf("approx_distinct_hll").call(vec![col("v")])]);
+        let optimized = PushDownAggregation::new()
+            .try_optimize(&plan, &OptimizerContext::new())
+            .unwrap();
+        assert!(optimized.is_none());
+
+        let plan = aggregate(vec![Expr::AggregateUDF(AggregateUDF {
+            fun: udaf(TDIGEST_UDAF_NAME),
+            args: vec![col("v")],
+            filter: Some(Box::new(col("v").gt(lit(0_f64)))),
+            order_by: None,
+        })]);
+        let optimized = PushDownAggregation::new()
+            .try_optimize(&plan, &OptimizerContext::new())
+            .unwrap();
+        assert!(optimized.is_none());
+    }
+}
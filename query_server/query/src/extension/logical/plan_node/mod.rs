@@ -15,6 +15,7 @@ pub mod ts_gen_func;
 pub mod update;
 pub mod update_tag;
 pub mod watermark;
+pub mod window_state;
 
 pub trait LogicalPlanExt: Sized {
     type Error;
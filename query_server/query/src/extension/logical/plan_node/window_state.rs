@@ -0,0 +1,106 @@
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+use std::time::Duration;
+
+use datafusion::common::DFSchemaRef;
+use datafusion::logical_expr::{LogicalPlan, UserDefinedLogicalNodeCore};
+use datafusion::prelude::Expr;
+
+/// The windows whose points are assigned by the points before them,
+/// so the points of the windows still open are kept between the micro-batches of a stream.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StatefulWindow {
+    Session { gap: Duration },
+    Count { size: i64 },
+}
+
+impl fmt::Display for StatefulWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Session { gap } => write!(f, "session, gap={}ns", gap.as_nanos()),
+            Self::Count { size } => write!(f, "count, size={size}"),
+        }
+    }
+}
+
+/// Keeps the points of the session windows and count windows still open at the end of
+/// a micro-batch, and replays them in the next micro-batch before its points.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct WindowStateNode {
+    pub window: StatefulWindow,
+    pub time_column: Expr,
+    pub partition_by: Vec<Expr>,
+    /// The incoming logical plan
+    pub input: Arc<LogicalPlan>,
+    /// The schema description of the output
+    pub schema: DFSchemaRef,
+}
+
+impl WindowStateNode {
+    pub fn new(
+        window: StatefulWindow,
+        time_column: Expr,
+        partition_by: Vec<Expr>,
+        input: Arc<LogicalPlan>,
+    ) -> Self {
+        let schema = input.schema().clone();
+        Self {
+            window,
+            time_column,
+            partition_by,
+            input,
+            schema,
+        }
+    }
+}
+
+impl Debug for WindowStateNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_for_explain(f)
+    }
+}
+
+impl UserDefinedLogicalNodeCore for WindowStateNode {
+    fn name(&self) -> &str {
+        "WindowState"
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![self.input.as_ref()]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        &self.schema
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        let mut exprs = Vec::with_capacity(self.partition_by.len() + 1);
+        exprs.push(self.time_column.clone());
+        exprs.extend(self.partition_by.iter().cloned());
+        exprs
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "WindowState: window={}, time={}, partition_by={:?}",
+            self.window, self.time_column, self.partition_by
+        )
+    }
+
+    fn from_template(&self, exprs: &[Expr], inputs: &[LogicalPlan]) -> Self {
+        assert_eq!(inputs.len(), 1, "input size inconsistent");
+        assert_eq!(
+            exprs.len(),
+            self.partition_by.len() + 1,
+            "expression size inconsistent"
+        );
+
+        Self::new(
+            self.window.clone(),
+            exprs[0].clone(),
+            exprs[1..].to_vec(),
+            Arc::new(inputs[0].clone()),
+        )
+    }
+}
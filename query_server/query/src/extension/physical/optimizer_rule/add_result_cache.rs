@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use datafusion::common::tree_node::{Transformed, TreeNode, VisitRecursion};
+use datafusion::common::Result as DFResult;
+use datafusion::config::ConfigOptions;
+use datafusion::logical_expr::expr::{ScalarFunction, ScalarUDF};
+use datafusion::logical_expr::{Expr, LogicalPlan, Volatility};
+use datafusion::physical_optimizer::PhysicalOptimizerRule;
+use datafusion::physical_plan::analyze::AnalyzeExec;
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::empty::EmptyExec;
+use datafusion::physical_plan::ExecutionPlan;
+use models::meta_data::{ReplicationSet, ReplicationSetId};
+
+use crate::data_source::result_cache::ResultCacheRef;
+use crate::extension::physical::plan_node::aggregate_filter_scan::AggregateFilterTskvExec;
+use crate::extension::physical::plan_node::result_cache::ResultCacheExec;
+use crate::extension::physical::plan_node::table_writer::TableWriterExec;
+use crate::extension::physical::plan_node::table_writer_merge::TableWriterMergeExec;
+use crate::extension::physical::plan_node::tag_scan::TagScanExec;
+use crate::extension::physical::plan_node::tskv_exec::TskvExec;
+use crate::extension::physical::plan_node::update_tag::UpdateTagExec;
+use crate::extension::utils::downcast_execution_plan;
+
+/// Functions whose result depends on the session instead of their arguments.
+const SESSION_FUNCTIONS: [&str; 2] = ["current_user", "current_role"];
+
+/// Returns the key of the query result in the result cache, or `None` if the result of
+/// the plan can't be cached, e.g. it writes data or calls volatile functions.
+pub fn result_cache_key(plan: &LogicalPlan, tenant: &str, database: &str) -> Option<String> {
+    let plan = match plan {
+        LogicalPlan::Analyze(analyze) => analyze.input.as_ref(),
+        plan => plan,
+    };
+
+    let mut cacheable = true;
+    let _ = plan.apply(&mut |plan| {
+        cacheable = match plan {
+            LogicalPlan::Dml(_)
+            | LogicalPlan::Copy(_)
+            | LogicalPlan::Ddl(_)
+            | LogicalPlan::Statement(_)
+            | LogicalPlan::Explain(_)
+            | LogicalPlan::Analyze(_)
+            | LogicalPlan::DescribeTable(_) => false,
+            plan => plan.expressions().iter().all(is_deterministic_expr),
+        };
+
+        Ok(if cacheable {
+            VisitRecursion::Continue
+        } else {
+            VisitRecursion::Stop
+        })
+    });
+
+    cacheable.then(|| format!("query:{tenant}.{database}:{}", plan.display_indent_schema()))
+}
+
+fn is_deterministic_expr(expr: &Expr) -> bool {
+    let mut deterministic = true;
+    let _ = expr.apply(&mut |expr| {
+        deterministic = match expr {
+            Expr::ScalarFunction(ScalarFunction { fun, .. }) => {
+                fun.volatility() == Volatility::Immutable
+            }
+            Expr::ScalarUDF(ScalarUDF { fun, .. }) => {
+                fun.signature.volatility == Volatility::Immutable
+                    && !SESSION_FUNCTIONS.contains(&fun.name.as_str())
+            }
+            Expr::ScalarVariable(..)
+            | Expr::ScalarSubquery(_)
+            | Expr::InSubquery(_)
+            | Expr::Exists(_) => false,
+            _ => true,
+        };
+
+        Ok(if deterministic {
+            VisitRecursion::Continue
+        } else {
+            VisitRecursion::Stop
+        })
+    });
+
+    deterministic
+}
+
+/// Caches the result of the scans on tskv, and wraps the whole plan with
+/// [`ResultCacheExec`] if all the data it reads is from tskv.
+pub struct AddResultCache {
+    /// Key of the query result, `None` if the query result can't be cached.
+    key: Option<String>,
+    tenant: String,
+    database: String,
+    result_cache: ResultCacheRef,
+}
+
+impl AddResultCache {
+    pub fn new(
+        key: Option<String>,
+        tenant: impl Into<String>,
+        database: impl Into<String>,
+        result_cache: ResultCacheRef,
+    ) -> Self {
+        Self {
+            key,
+            tenant: tenant.into(),
+            database: database.into(),
+            result_cache,
+        }
+    }
+
+    fn add_result_cache_exec(
+        &self,
+        key: String,
+        plan: Arc<dyn ExecutionPlan>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        if plan.as_any().is::<AnalyzeExec>() {
+            let input = self.add_result_cache_exec(key, plan.children()[0].clone())?;
+            return plan.with_new_children(vec![input]);
+        }
+
+        let replica_sets = match collect_replica_sets(&plan) {
+            Some(replica_sets) => replica_sets,
+            None => return Ok(plan),
+        };
+
+        let input = if plan.output_partitioning().partition_count() > 1 {
+            Arc::new(CoalescePartitionsExec::new(plan))
+        } else {
+            plan
+        };
+
+        Ok(Arc::new(ResultCacheExec::new(
+            input,
+            key,
+            self.tenant.clone(),
+            self.database.clone(),
+            replica_sets,
+            self.result_cache.clone(),
+        )))
+    }
+}
+
+impl PhysicalOptimizerRule for AddResultCache {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        if writes_data(&plan) {
+            return Ok(plan);
+        }
+
+        let plan = plan.transform_up(&|plan| {
+            if let Some(exec) = downcast_execution_plan::<TskvExec>(plan.as_ref()) {
+                let new_plan = exec.with_result_cache(self.result_cache.clone());
+                return Ok(Transformed::Yes(Arc::new(new_plan)));
+            }
+
+            Ok(Transformed::No(plan))
+        })?;
+
+        match &self.key {
+            Some(key) => self.add_result_cache_exec(key.clone(), plan),
+            None => Ok(plan),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "add_result_cache"
+    }
+
+    fn schema_check(&self) -> bool {
+        false
+    }
+}
+
+fn writes_data(plan: &Arc<dyn ExecutionPlan>) -> bool {
+    let mut writes = false;
+    let _ = plan.apply(&mut |plan| {
+        let any = plan.as_any();
+        writes = any.is::<TableWriterExec>()
+            || any.is::<TableWriterMergeExec>()
+            || any.is::<UpdateTagExec>();
+
+        Ok(if writes {
+            VisitRecursion::Stop
+        } else {
+            VisitRecursion::Continue
+        })
+    });
+
+    writes
+}
+
+/// Returns the replication sets read by the plan, or `None` if the plan reads data
+/// from somewhere else than tskv.
+fn collect_replica_sets(plan: &Arc<dyn ExecutionPlan>) -> Option<Vec<ReplicationSet>> {
+    let mut replica_sets = BTreeMap::<ReplicationSetId, ReplicationSet>::new();
+    let mut only_tskv = true;
+    let _ = plan.apply(&mut |plan| {
+        let splits = if let Some(exec) = downcast_execution_plan::<TskvExec>(plan.as_ref()) {
+            exec.splits()
+        } else if let Some(exec) = downcast_execution_plan::<AggregateFilterTskvExec>(plan.as_ref())
+        {
+            exec.splits()
+        } else if let Some(exec) = downcast_execution_plan::<TagScanExec>(plan.as_ref()) {
+            exec.splits()
+        } else {
+            only_tskv = !plan.children().is_empty() || plan.as_any().is::<EmptyExec>();
+            &[]
+        };
+
+        for split in splits {
+            let replica_set = split.replica_set();
+            replica_sets.insert(replica_set.id, replica_set.clone());
+        }
+
+        Ok(if only_tskv {
+            VisitRecursion::Continue
+        } else {
+            VisitRecursion::Stop
+        })
+    });
+
+    only_tskv.then(|| replica_sets.into_values().collect())
+}
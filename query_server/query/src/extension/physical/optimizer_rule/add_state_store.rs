@@ -1,7 +1,7 @@
 use core::fmt::Debug;
 use std::sync::Arc;
 
-use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::tree_node::{Transformed, TreeNode, VisitRecursion};
 use datafusion::common::Result as DFResult;
 use datafusion::config::ConfigOptions;
 use datafusion::physical_optimizer::PhysicalOptimizerRule;
@@ -10,6 +10,7 @@ use datafusion::physical_plan::{with_new_children_if_necessary, ExecutionPlan};
 
 use crate::extension::physical::plan_node::state_restore::StateRestoreExec;
 use crate::extension::physical::plan_node::state_save::StateSaveExec;
+use crate::extension::physical::plan_node::window_state::WindowStateExec;
 use crate::extension::utils::downcast_execution_plan;
 use crate::stream::state_store::StateStoreFactory;
 
@@ -42,6 +43,12 @@ where
         plan.transform_up(&|plan| {
             if let Some(aggregate_exec) = downcast_execution_plan::<AggregateExec>(plan.as_ref()) {
                 match aggregate_exec.mode() {
+                    // The windows kept by WindowStateExec are aggregated again with all their points
+                    AggregateMode::Final | AggregateMode::FinalPartitioned
+                        if has_window_state::<T>(aggregate_exec.input())? =>
+                    {
+                        return Ok(Transformed::No(plan));
+                    }
                     AggregateMode::Final | AggregateMode::FinalPartitioned => {
                         // Original plan
                         // ```
@@ -98,3 +105,15 @@ where
         true
     }
 }
+
+fn has_window_state<T: 'static>(plan: &Arc<dyn ExecutionPlan>) -> DFResult<bool> {
+    let mut found = false;
+    plan.apply(&mut |plan| {
+        if downcast_execution_plan::<WindowStateExec<T>>(plan.as_ref()).is_some() {
+            found = true;
+            return Ok(VisitRecursion::Stop);
+        }
+        Ok(VisitRecursion::Continue)
+    })?;
+    Ok(found)
+}
@@ -1,5 +1,7 @@
 //! physical plan optimizer rule
 pub mod add_assert;
+pub mod add_result_cache;
 pub mod add_sort;
 pub mod add_state_store;
 pub mod add_traced_proxy;
+pub mod push_down_spatial_filter;
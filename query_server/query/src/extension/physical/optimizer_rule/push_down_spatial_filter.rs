@@ -0,0 +1,154 @@
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::Result as DFResult;
+use datafusion::config::ConfigOptions;
+use datafusion::physical_expr::{split_conjunction, ScalarFunctionExpr};
+use datafusion::physical_optimizer::PhysicalOptimizerRule;
+use datafusion::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use datafusion::physical_plan::expressions::{Column, Literal};
+use datafusion::physical_plan::filter::FilterExec;
+use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::{ExecutionPlan, PhysicalExpr};
+use datafusion::scalar::ScalarValue;
+use models::gis::bounding_box::{BoundingBox, SpatialFilter};
+use models::schema::tskv_table_schema::{ColumnType, TskvTableSchema};
+use models::ValueType;
+
+use crate::extension::physical::plan_node::tskv_exec::TskvExec;
+use crate::extension::utils::downcast_execution_plan;
+
+/// Pushes the spatial predicates of [`FilterExec`] like `ST_Within(column, 'POLYGON(...)')`
+/// down to the [`TskvExec`] below it, which skips the pages whose bounding boxes of the
+/// geometry column don't intersect the bounding box of the literal geometry.
+///
+/// The [`FilterExec`] is kept, since the bounding boxes can only rule out the pages.
+#[non_exhaustive]
+pub struct PushDownSpatialFilter {}
+
+impl PushDownSpatialFilter {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for PushDownSpatialFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PhysicalOptimizerRule for PushDownSpatialFilter {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        plan.transform_up(&|plan| {
+            if let Some(filter_exec) = downcast_execution_plan::<FilterExec>(plan.as_ref()) {
+                if let Some(input) = push_down(filter_exec.predicate(), filter_exec.input())? {
+                    return Ok(Transformed::Yes(plan.with_new_children(vec![input])?));
+                }
+            }
+
+            Ok(Transformed::No(plan))
+        })
+    }
+
+    fn name(&self) -> &str {
+        "push_down_spatial_filter"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Returns the input with the spatial filters pushed down, or `None` if there is nothing
+/// to push down.
+fn push_down(
+    predicate: &Arc<dyn PhysicalExpr>,
+    input: &Arc<dyn ExecutionPlan>,
+) -> DFResult<Option<Arc<dyn ExecutionPlan>>> {
+    if let Some(tskv_exec) = downcast_execution_plan::<TskvExec>(input.as_ref()) {
+        let spatial_filters = extract_spatial_filters(predicate, tskv_exec.table_schema());
+        if spatial_filters.is_empty() {
+            return Ok(None);
+        }
+        return Ok(Some(Arc::new(
+            tskv_exec.with_spatial_filters(spatial_filters),
+        )));
+    }
+
+    // These plans neither change the columns nor filter the rows.
+    if input.as_any().is::<RepartitionExec>() || input.as_any().is::<CoalesceBatchesExec>() {
+        if let Some(child) = push_down(predicate, &input.children()[0])? {
+            return input.clone().with_new_children(vec![child]).map(Some);
+        }
+    }
+
+    Ok(None)
+}
+
+/// Extracts the filters on the geometry columns from the conjuncts like
+/// `ST_Within(column, 'POLYGON(...)')`, which can only be satisfied by the geometries
+/// whose bounding boxes intersect the bounding box of the literal geometry.
+fn extract_spatial_filters(
+    predicate: &Arc<dyn PhysicalExpr>,
+    table: &TskvTableSchema,
+) -> Vec<SpatialFilter> {
+    split_conjunction(predicate)
+        .into_iter()
+        .filter_map(|expr| {
+            let func = expr.as_any().downcast_ref::<ScalarFunctionExpr>()?;
+            spatial_filter(func.name(), func.args(), table)
+        })
+        .collect()
+}
+
+fn spatial_filter(
+    func_name: &str,
+    args: &[Arc<dyn PhysicalExpr>],
+    table: &TskvTableSchema,
+) -> Option<SpatialFilter> {
+    let literal = |e: &Arc<dyn PhysicalExpr>| {
+        e.as_any()
+            .downcast_ref::<Literal>()
+            .map(|l| l.value().clone())
+    };
+    let geometry_column = |e: &Arc<dyn PhysicalExpr>| {
+        let column = e.as_any().downcast_ref::<Column>()?;
+        table.column(column.name()).and_then(|col| {
+            matches!(col.column_type, ColumnType::Field(ValueType::Geometry(_)))
+                .then(|| col.name.clone())
+        })
+    };
+    let geometry_bbox = |e: &Arc<dyn PhysicalExpr>| match literal(e)? {
+        ScalarValue::Utf8(Some(wkt)) => BoundingBox::from_wkt(&wkt),
+        _ => None,
+    };
+    let column_and_bbox = |l: &Arc<dyn PhysicalExpr>, r: &Arc<dyn PhysicalExpr>| {
+        geometry_column(l)
+            .zip(geometry_bbox(r))
+            .or_else(|| geometry_column(r).zip(geometry_bbox(l)))
+    };
+
+    let func_name = func_name.to_ascii_lowercase();
+    let (column, bbox) = match (func_name.as_str(), args) {
+        ("st_within" | "st_contains" | "st_intersects" | "st_equals", [l, r]) => {
+            column_and_bbox(l, r)?
+        }
+        ("st_dwithin", [l, r, distance]) => {
+            let distance = match literal(distance)?.cast_to(&DataType::Float64).ok()? {
+                ScalarValue::Float64(Some(d)) if d >= 0.0 => d,
+                _ => return None,
+            };
+            let (column, bbox) = column_and_bbox(l, r)?;
+            (column, bbox.expand(distance))
+        }
+        _ => return None,
+    };
+
+    Some(SpatialFilter::new(column, bbox))
+}
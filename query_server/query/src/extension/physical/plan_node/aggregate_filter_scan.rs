@@ -53,6 +53,10 @@ impl AggregateFilterTskvExec {
             metrics: ExecutionPlanMetricsSet::new(),
         }
     }
+
+    pub fn splits(&self) -> &[PlacedSplit] {
+        &self.splits
+    }
 }
 
 impl ExecutionPlan for AggregateFilterTskvExec {
@@ -7,6 +7,7 @@ use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSe
 pub mod aggregate_filter_scan;
 pub mod assert;
 pub mod expand;
+pub mod result_cache;
 pub mod state_restore;
 pub mod state_save;
 pub mod table_writer;
@@ -17,6 +18,7 @@ pub mod ts_gen_func;
 pub mod tskv_exec;
 pub mod update_tag;
 pub mod watermark;
+pub mod window_state;
 
 /// Stores metrics about the table writer execution.
 #[derive(Debug)]
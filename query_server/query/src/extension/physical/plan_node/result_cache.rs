@@ -0,0 +1,214 @@
+use std::any::Any;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::memory::MemoryStream;
+use datafusion::physical_plan::metrics::{
+    Count, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet,
+};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+    Statistics,
+};
+use futures::TryStreamExt;
+use models::meta_data::ReplicationSet;
+use trace::warn;
+
+use crate::data_source::result_cache::{CachingStream, ResultCacheRef};
+
+/// Returns the result of the input from the result cache if the data of the replication
+/// sets read by the input is not changed since the result is cached, otherwise executes
+/// the input and caches its result.
+pub struct ResultCacheExec {
+    input: Arc<dyn ExecutionPlan>,
+    /// The normalized logical plan of the input.
+    key: String,
+    tenant: String,
+    database: String,
+    replica_sets: Vec<ReplicationSet>,
+    result_cache: ResultCacheRef,
+
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl ResultCacheExec {
+    pub fn new(
+        input: Arc<dyn ExecutionPlan>,
+        key: String,
+        tenant: String,
+        database: String,
+        replica_sets: Vec<ReplicationSet>,
+        result_cache: ResultCacheRef,
+    ) -> Self {
+        Self {
+            input,
+            key,
+            tenant,
+            database,
+            replica_sets,
+            result_cache,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+}
+
+impl Debug for ResultCacheExec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResultCacheExec")
+            .field("input", &self.input)
+            .field("key", &self.key)
+            .field("replica_sets", &self.replica_sets)
+            .finish()
+    }
+}
+
+impl ExecutionPlan for ResultCacheExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::SinglePartition]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(Self {
+            input: children[0].clone(),
+            key: self.key.clone(),
+            tenant: self.tenant.clone(),
+            database: self.database.clone(),
+            replica_sets: self.replica_sets.clone(),
+            result_cache: self.result_cache.clone(),
+            metrics: self.metrics.clone(),
+        }))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "ResultCacheExec invalid partition {partition}"
+            )));
+        }
+
+        let stream = futures::stream::once(do_execute(
+            self.input.clone(),
+            self.key.clone(),
+            self.tenant.clone(),
+            self.database.clone(),
+            self.replica_sets.clone(),
+            self.result_cache.clone(),
+            ResultCacheMetrics::new(&self.metrics, partition),
+            context,
+        ))
+        .try_flatten();
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "ResultCacheExec: replica_sets={}",
+                    self.replica_sets.len()
+                )
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.input.statistics()
+    }
+}
+
+struct ResultCacheMetrics {
+    hits: Count,
+    misses: Count,
+}
+
+impl ResultCacheMetrics {
+    fn new(metrics: &ExecutionPlanMetricsSet, partition: usize) -> Self {
+        Self {
+            hits: MetricBuilder::new(metrics).counter("result_cache_hits", partition),
+            misses: MetricBuilder::new(metrics).counter("result_cache_misses", partition),
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn do_execute(
+    input: Arc<dyn ExecutionPlan>,
+    key: String,
+    tenant: String,
+    database: String,
+    replica_sets: Vec<ReplicationSet>,
+    result_cache: ResultCacheRef,
+    metrics: ResultCacheMetrics,
+    context: Arc<TaskContext>,
+) -> Result<SendableRecordBatchStream> {
+    let versions = match result_cache.versions(&tenant, &replica_sets).await {
+        Ok(versions) => versions,
+        Err(err) => {
+            warn!(
+                "Failed to get data versions of {tenant}.{database}, skip the result cache: {err}"
+            );
+            return input.execute(0, context);
+        }
+    };
+
+    if let Some(batches) = result_cache.get(&key, &versions) {
+        metrics.hits.add(1);
+        result_cache.record_hit(&tenant, &database);
+        return Ok(Box::pin(MemoryStream::try_new(
+            batches,
+            input.schema(),
+            None,
+        )?));
+    }
+    metrics.misses.add(1);
+    result_cache.record_miss(&tenant, &database);
+
+    let stream = input.execute(0, context)?;
+    Ok(Box::pin(CachingStream::new(
+        stream,
+        result_cache,
+        key,
+        versions,
+    )))
+}
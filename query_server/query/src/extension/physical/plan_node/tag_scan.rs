@@ -65,6 +65,10 @@ impl TagScanExec {
     pub fn predicate(&self) -> PredicateRef {
         self.predicate.clone()
     }
+
+    pub fn splits(&self) -> &[PlacedSplit] {
+        &self.splits
+    }
 }
 
 impl ExecutionPlan for TagScanExec {
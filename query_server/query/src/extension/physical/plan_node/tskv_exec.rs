@@ -10,15 +10,20 @@ use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::error::{DataFusionError, Result as DFResult};
 use datafusion::execution::context::TaskContext;
 use datafusion::physical_expr::PhysicalSortExpr;
-use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::memory::MemoryStream;
+use datafusion::physical_plan::metrics::{
+    Count, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet,
+};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use datafusion::physical_plan::{
     DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream, SendableRecordBatchStream,
     Statistics,
 };
-use futures::{Stream, StreamExt};
+use futures::{Stream, StreamExt, TryStreamExt};
 use models::codec::Encoding;
 use models::datafusion::limit_record_batch::limit_record_batch;
-use models::predicate::domain::PredicateRef;
+use models::gis::bounding_box::SpatialFilter;
+use models::predicate::domain::{PredicateRef, TimeRange};
 use models::predicate::PlacedSplit;
 use models::schema::tskv_table_schema::{
     ColumnType, TableColumn, TskvTableSchema, TskvTableSchemaRef,
@@ -27,9 +32,10 @@ use models::schema::TIME_FIELD_NAME;
 use snafu::ResultExt;
 use spi::{CommonSnafu, CoordinatorSnafu, QueryResult};
 use trace::span_ext::SpanExt;
-use trace::{debug, Span, SpanContext};
+use trace::{debug, warn, Span, SpanContext};
 use tskv::reader::QueryOption;
 
+use crate::data_source::result_cache::{CachingStream, ResultCacheRef};
 use crate::extension::physical::plan_node::TableScanMetrics;
 
 #[derive(Clone)]
@@ -41,6 +47,12 @@ pub struct TskvExec {
     filter: PredicateRef,
     coord: CoordinatorRef,
     splits: Vec<PlacedSplit>,
+    /// The filters except the time ranges, used to build the cache key of the splits.
+    filter_digest: String,
+    /// The filters on the geometry columns pushed down to skip the pages of the splits.
+    spatial_filters: Vec<SpatialFilter>,
+    /// Caches the splits of the buckets before the newest one.
+    result_cache: Option<ResultCacheRef>,
 
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
@@ -53,6 +65,7 @@ impl TskvExec {
         filter: PredicateRef,
         coord: CoordinatorRef,
         splits: Vec<PlacedSplit>,
+        filter_digest: String,
     ) -> Self {
         let metrics = ExecutionPlanMetricsSet::new();
 
@@ -62,12 +75,50 @@ impl TskvExec {
             filter,
             coord,
             splits,
+            filter_digest,
+            spatial_filters: vec![],
+            result_cache: None,
             metrics,
         }
     }
+
+    pub fn table_schema(&self) -> &TskvTableSchema {
+        &self.table_schema
+    }
+
     pub fn filter(&self) -> PredicateRef {
         self.filter.clone()
     }
+
+    pub fn splits(&self) -> &[PlacedSplit] {
+        &self.splits
+    }
+
+    pub fn spatial_filters(&self) -> &[SpatialFilter] {
+        &self.spatial_filters
+    }
+
+    pub fn with_spatial_filters(&self, spatial_filters: Vec<SpatialFilter>) -> Self {
+        let splits = self
+            .splits
+            .iter()
+            .cloned()
+            .map(|split| split.with_spatial_filters(spatial_filters.clone()))
+            .collect();
+
+        Self {
+            splits,
+            spatial_filters,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_result_cache(&self, result_cache: ResultCacheRef) -> Self {
+        Self {
+            result_cache: Some(result_cache),
+            ..self.clone()
+        }
+    }
 }
 
 impl ExecutionPlan for TskvExec {
@@ -101,6 +152,9 @@ impl ExecutionPlan for TskvExec {
             filter: self.filter.clone(),
             coord: self.coord.clone(),
             splits: self.splits.clone(),
+            filter_digest: self.filter_digest.clone(),
+            spatial_filters: self.spatial_filters.clone(),
+            result_cache: self.result_cache.clone(),
             metrics: self.metrics.clone(),
         }))
     }
@@ -129,6 +183,30 @@ impl ExecutionPlan for TskvExec {
         let metrics = TableScanMetrics::new(&self.metrics, partition);
 
         let span_ctx = context.session_config().get_extension::<SpanContext>();
+        let span = Span::from_context(
+            format!("TableScanStream ({partition})"),
+            span_ctx.as_deref(),
+        );
+
+        if let Some(result_cache) = self.result_cache.clone() {
+            let stream = futures::stream::once(execute_with_cache(
+                self.table_schema.clone(),
+                self.schema(),
+                self.coord.clone(),
+                split,
+                self.filter_digest.clone(),
+                result_cache,
+                batch_size,
+                metrics,
+                SplitCacheMetrics::new(&self.metrics, partition),
+                span,
+            ))
+            .try_flatten();
+            return Ok(Box::pin(RecordBatchStreamAdapter::new(
+                self.schema(),
+                stream,
+            )));
+        }
 
         let table_stream = TableScanStream::new(
             self.table_schema.clone(),
@@ -137,10 +215,7 @@ impl ExecutionPlan for TskvExec {
             split,
             batch_size,
             metrics,
-            Span::from_context(
-                format!("TableScanStream ({partition})"),
-                span_ctx.as_deref(),
-            ),
+            span,
         )
         .map_err(|err| DataFusionError::External(Box::new(err)))?;
 
@@ -163,7 +238,19 @@ impl ExecutionPlan for TskvExec {
                     PredicateDisplay(&filter),
                     self.splits.len(),
                     fields.join(","),
-                )
+                )?;
+                if !self.spatial_filters.is_empty() {
+                    let spatial_filters = self
+                        .spatial_filters
+                        .iter()
+                        .map(|f| f.to_string())
+                        .collect::<Vec<_>>();
+                    write!(f, ", spatial_filters=[{}]", spatial_filters.join(", "))?;
+                }
+                if self.result_cache.is_some() {
+                    write!(f, ", result_cache=true")?;
+                }
+                Ok(())
             }
         }
     }
@@ -185,10 +272,149 @@ impl std::fmt::Debug for TskvExec {
             .field("proj_schema", &self.proj_schema)
             .field("filter", &self.filter)
             .field("splits", &self.splits)
+            .field("spatial_filters", &self.spatial_filters)
             .finish()
     }
 }
 
+struct SplitCacheMetrics {
+    hits: Count,
+    misses: Count,
+}
+
+impl SplitCacheMetrics {
+    fn new(metrics: &ExecutionPlanMetricsSet, partition: usize) -> Self {
+        Self {
+            hits: MetricBuilder::new(metrics).counter("result_cache_hits", partition),
+            misses: MetricBuilder::new(metrics).counter("result_cache_misses", partition),
+        }
+    }
+}
+
+/// Reads the split from the result cache if its data is not changed since it's cached.
+///
+/// Only the splits of the buckets before the newest one are cached, which are rarely
+/// written. The time ranges of the split are cut by its bucket, so that the queries of
+/// different time ranges covering the whole bucket share the cached split.
+#[allow(clippy::too_many_arguments)]
+async fn execute_with_cache(
+    table_schema: TskvTableSchemaRef,
+    proj_schema: SchemaRef,
+    coord: CoordinatorRef,
+    split: PlacedSplit,
+    filter_digest: String,
+    result_cache: ResultCacheRef,
+    batch_size: usize,
+    metrics: TableScanMetrics,
+    cache_metrics: SplitCacheMetrics,
+    span: Span,
+) -> DFResult<SendableRecordBatchStream> {
+    let key = split_cache_key(&table_schema, &proj_schema, &coord, &split, &filter_digest).await;
+
+    let cached = match key {
+        Some(key) => match result_cache
+            .versions(&table_schema.tenant, &[split.replica_set().clone()])
+            .await
+        {
+            Ok(versions) => Some((key, versions)),
+            Err(err) => {
+                warn!("Failed to get data versions of split, scan without result cache: {err}");
+                None
+            }
+        },
+        None => None,
+    };
+
+    if let Some((key, versions)) = &cached {
+        if let Some(batches) = result_cache.get(key, versions) {
+            cache_metrics.hits.add(1);
+            result_cache.record_hit(&table_schema.tenant, &table_schema.db);
+            metrics.done();
+            return Ok(Box::pin(MemoryStream::try_new(batches, proj_schema, None)?));
+        }
+        cache_metrics.misses.add(1);
+        result_cache.record_miss(&table_schema.tenant, &table_schema.db);
+    }
+
+    let table_stream = TableScanStream::new(
+        table_schema,
+        proj_schema,
+        coord,
+        split,
+        batch_size,
+        metrics,
+        span,
+    )
+    .map_err(|err| DataFusionError::External(Box::new(err)))?;
+
+    match cached {
+        Some((key, versions)) => Ok(Box::pin(CachingStream::new(
+            Box::pin(table_stream),
+            result_cache,
+            key,
+            versions,
+        ))),
+        None => Ok(Box::pin(table_stream)),
+    }
+}
+
+/// Returns `None` if the split is of the newest bucket of the database.
+async fn split_cache_key(
+    table_schema: &TskvTableSchema,
+    proj_schema: &SchemaRef,
+    coord: &CoordinatorRef,
+    split: &PlacedSplit,
+    filter_digest: &str,
+) -> Option<String> {
+    let db_info = coord
+        .tenant_meta(&table_schema.tenant)
+        .await?
+        .get_db_info(&table_schema.db)
+        .ok()??;
+    let newest_start_time = db_info.buckets.iter().map(|b| b.start_time).max()?;
+    let bucket = db_info
+        .buckets
+        .iter()
+        .find(|b| b.shard_group.iter().any(|r| r.id == split.replica_id()))?;
+    if bucket.start_time >= newest_start_time {
+        return None;
+    }
+
+    let bucket_range = TimeRange::new(bucket.start_time, bucket.end_time - 1);
+    let time_ranges = split
+        .time_ranges()
+        .intersect(&bucket_range)
+        .map(|t| t.to_string())
+        .unwrap_or_default();
+    let projection = proj_schema
+        .fields()
+        .iter()
+        .map(|f| f.name().as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    // The pages skipped by the spatial filters are not in the result of the split.
+    let spatial_filters = split
+        .spatial_filters()
+        .iter()
+        .map(|f| f.to_string())
+        .collect::<Vec<_>>()
+        .join(" AND ");
+
+    Some(format!(
+        "scan:{}.{}.{}.{}:{}:[{}]:{}:{}:{}:{:?}",
+        table_schema.tenant,
+        table_schema.db,
+        table_schema.name,
+        table_schema.schema_version,
+        split.replica_id(),
+        projection,
+        time_ranges,
+        filter_digest,
+        spatial_filters,
+        split.limit(),
+    ))
+}
+
 /// A wrapper to customize PredicateRef display
 struct PredicateDisplay<'a>(&'a PredicateRef);
 
@@ -0,0 +1,417 @@
+use core::fmt;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use datafusion::arrow::array::{as_primitive_array, UInt32Array};
+use datafusion::arrow::compute::{self, concat_batches};
+use datafusion::arrow::datatypes::{DataType, Int64Type, SchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::Result as DFResult;
+use datafusion::execution::context::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::{
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning, PhysicalExpr, RecordBatchStream,
+    SendableRecordBatchStream, Statistics,
+};
+use datafusion::scalar::ScalarValue;
+use futures::{Stream, StreamExt};
+use trace::debug;
+
+use crate::extension::logical::plan_node::window_state::StatefulWindow;
+use crate::stream::state_store::{StateStore, StateStoreFactory};
+
+/// The operator id of the state store of [`WindowStateExec`],
+/// the state store of the aggregations is 0.
+const WINDOW_STATE_OPERATOR_ID: usize = 1;
+
+/// Execution plan for a WindowStateExec
+///
+/// Outputs the points kept by the previous micro-batch before the points of the input,
+/// and keeps the points of the windows still open after the input is exhausted.
+#[derive(Debug)]
+pub struct WindowStateExec<T> {
+    window: StatefulWindow,
+    time_expr: Arc<dyn PhysicalExpr>,
+    partition_exprs: Vec<Arc<dyn PhysicalExpr>>,
+    watermark_ns: i64,
+    state_store_factory: Arc<T>,
+    input: Arc<dyn ExecutionPlan>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl<T> WindowStateExec<T> {
+    pub fn try_new(
+        window: StatefulWindow,
+        time_expr: Arc<dyn PhysicalExpr>,
+        partition_exprs: Vec<Arc<dyn PhysicalExpr>>,
+        watermark_ns: i64,
+        state_store_factory: Arc<T>,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> DFResult<Self> {
+        Ok(Self {
+            window,
+            time_expr,
+            partition_exprs,
+            watermark_ns,
+            state_store_factory,
+            input,
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+}
+
+impl<T> ExecutionPlan for WindowStateExec<T>
+where
+    T: StateStoreFactory + Send + Sync + Debug + 'static,
+    T::SS: Send + Sync + Debug,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    /// The points of a window may be read by any partition of the input,
+    /// they are kept in a single state store.
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::SinglePartition]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> bool {
+        false
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        assert_eq!(children.len(), 1);
+
+        Ok(Arc::new(Self::try_new(
+            self.window.clone(),
+            self.time_expr.clone(),
+            self.partition_exprs.clone(),
+            self.watermark_ns,
+            self.state_store_factory.clone(),
+            children[0].clone(),
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> DFResult<SendableRecordBatchStream> {
+        let session_id = context.session_id();
+        debug!(
+            "Start WindowStateExec::execute for partition {} of context session_id {} and task_id {:?}",
+            partition,
+            session_id,
+            context.task_id(),
+        );
+
+        let input = self.input.execute(partition, context)?;
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+
+        let state_store = self.state_store_factory.get_or_default(
+            session_id,
+            partition,
+            WINDOW_STATE_OPERATOR_ID,
+        )?;
+        let restored = state_store.state()?;
+
+        Ok(Box::pin(WindowStateStream {
+            schema: self.schema(),
+            input,
+            restored,
+            batches: vec![],
+            finished: false,
+            window: self.window.clone(),
+            time_expr: self.time_expr.clone(),
+            partition_exprs: self.partition_exprs.clone(),
+            watermark_ns: self.watermark_ns,
+            state_store,
+            baseline_metrics,
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "WindowStateExec: window={}, watermark={}ns",
+                    self.window, self.watermark_ns
+                )
+            }
+        }
+    }
+}
+
+struct WindowStateStream<S> {
+    schema: SchemaRef,
+    input: SendableRecordBatchStream,
+    /// The points kept by the previous micro-batch
+    restored: Vec<RecordBatch>,
+    /// All the points of this micro-batch
+    batches: Vec<RecordBatch>,
+    finished: bool,
+    window: StatefulWindow,
+    time_expr: Arc<dyn PhysicalExpr>,
+    partition_exprs: Vec<Arc<dyn PhysicalExpr>>,
+    watermark_ns: i64,
+    state_store: Arc<S>,
+    baseline_metrics: BaselineMetrics,
+}
+
+impl<S> WindowStateStream<S>
+where
+    S: StateStore,
+{
+    /// Replace the kept points with the points of the windows still open.
+    fn save_open_windows(&mut self) -> DFResult<()> {
+        let _timer = self.baseline_metrics.elapsed_compute().timer();
+
+        let batches = std::mem::take(&mut self.batches);
+        let batch = concat_batches(&self.schema, batches.iter())?;
+        let indices = open_window_indices(
+            &self.window,
+            &self.time_expr,
+            &self.partition_exprs,
+            self.watermark_ns,
+            &batch,
+        )?;
+
+        if !indices.is_empty() {
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|c| compute::take(c.as_ref(), &indices, None))
+                .collect::<Result<Vec<_>, _>>()?;
+            let open_batch = RecordBatch::try_new(self.schema.clone(), columns)?;
+            trace::trace!(
+                "Keep the points of the open windows, num rows: {}",
+                open_batch.num_rows()
+            );
+            self.state_store.put(open_batch)?;
+        }
+
+        let _ = self.state_store.commit()?;
+
+        Ok(())
+    }
+}
+
+impl<S> Stream for WindowStateStream<S>
+where
+    S: StateStore,
+{
+    type Item = DFResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+
+        if let Some(batch) = self.restored.pop() {
+            self.batches.push(batch.clone());
+            return self
+                .baseline_metrics
+                .record_poll(Poll::Ready(Some(Ok(batch))));
+        }
+
+        let poll = match self.input.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(batch))) => {
+                self.batches.push(batch.clone());
+                Poll::Ready(Some(Ok(batch)))
+            }
+            Poll::Ready(None) => {
+                self.finished = true;
+                match self.save_open_windows() {
+                    Ok(_) => Poll::Ready(None),
+                    Err(err) => Poll::Ready(Some(Err(err))),
+                }
+            }
+            other => other,
+        };
+
+        self.baseline_metrics.record_poll(poll)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // same number of record batches
+        self.input.size_hint()
+    }
+}
+
+impl<S> RecordBatchStream for WindowStateStream<S>
+where
+    S: StateStore,
+{
+    /// Get the schema
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Returns the indices of the points of the windows still open, they are:
+/// - the last session of each partition if no point is later than the watermark for the gap.
+/// - the points of each partition after the last full count window.
+fn open_window_indices(
+    window: &StatefulWindow,
+    time_expr: &Arc<dyn PhysicalExpr>,
+    partition_exprs: &[Arc<dyn PhysicalExpr>],
+    watermark_ns: i64,
+    batch: &RecordBatch,
+) -> DFResult<UInt32Array> {
+    let num_rows = batch.num_rows();
+
+    let time = time_expr.evaluate(batch)?.into_array(num_rows);
+    let time = compute::cast(&time, &DataType::Timestamp(TimeUnit::Nanosecond, None))?;
+    let time = compute::cast(&time, &DataType::Int64)?;
+    let time = as_primitive_array::<Int64Type>(&time);
+
+    let partition_columns = partition_exprs
+        .iter()
+        .map(|e| Ok(e.evaluate(batch)?.into_array(num_rows)))
+        .collect::<DFResult<Vec<_>>>()?;
+    let mut partitions: HashMap<Vec<ScalarValue>, Vec<usize>> = HashMap::new();
+    for i in (0..num_rows).filter(|i| time.is_valid(*i)) {
+        let key = partition_columns
+            .iter()
+            .map(|c| ScalarValue::try_from_array(c, i))
+            .collect::<DFResult<Vec<_>>>()?;
+        partitions.entry(key).or_default().push(i);
+    }
+
+    let mut indices = vec![];
+    for mut rows in partitions.into_values() {
+        rows.sort_by_key(|i| time.value(*i));
+        let open_rows = match window {
+            StatefulWindow::Session { gap } => {
+                let gap_ns = gap.as_nanos() as i64;
+                let last_time = rows.last().map(|i| time.value(*i)).unwrap_or(i64::MIN);
+                let start = if last_time.saturating_add(gap_ns) > watermark_ns {
+                    // The last session starts after the last gap longer than `gap`
+                    rows.windows(2)
+                        .rposition(|w| time.value(w[1]) - time.value(w[0]) > gap_ns)
+                        .map(|p| p + 1)
+                        .unwrap_or(0)
+                } else {
+                    rows.len()
+                };
+                &rows[start..]
+            }
+            StatefulWindow::Count { size } => {
+                let start = rows.len() - rows.len() % (*size as usize);
+                &rows[start..]
+            }
+        };
+        indices.extend(open_rows.iter().map(|i| *i as u32));
+    }
+
+    Ok(UInt32Array::from(indices))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use datafusion::arrow::array::{StringArray, TimestampNanosecondArray, UInt32Array};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::physical_plan::expressions::Column;
+    use datafusion::physical_plan::PhysicalExpr;
+
+    use super::open_window_indices;
+    use crate::extension::logical::plan_node::window_state::StatefulWindow;
+
+    fn points() -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(TimestampNanosecondArray::from(vec![1, 2, 10, 12, 3, 20, 4])),
+                Arc::new(StringArray::from(vec!["a", "a", "a", "a", "b", "b", "b"])),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn sorted(indices: UInt32Array) -> Vec<u32> {
+        let mut indices = indices.values().to_vec();
+        indices.sort();
+        indices
+    }
+
+    #[test]
+    fn test_open_session_windows() {
+        let window = StatefulWindow::Session {
+            gap: Duration::from_nanos(5),
+        };
+        let time: Arc<dyn PhysicalExpr> = Arc::new(Column::new("time", 0));
+        let name: Arc<dyn PhysicalExpr> = Arc::new(Column::new("name", 1));
+        let partition_by = [name];
+
+        let indices = open_window_indices(&window, &time, &partition_by, 10, &points()).unwrap();
+        assert_eq!(sorted(indices), vec![2, 3, 5]);
+
+        // The session of `a` ending at 17 is closed by the watermark
+        let indices = open_window_indices(&window, &time, &partition_by, 17, &points()).unwrap();
+        assert_eq!(sorted(indices), vec![5]);
+
+        // All the points are in the same partition
+        let indices = open_window_indices(&window, &time, &[], 10, &points()).unwrap();
+        assert_eq!(sorted(indices), vec![5]);
+    }
+
+    #[test]
+    fn test_open_count_windows() {
+        let window = StatefulWindow::Count { size: 3 };
+        let time: Arc<dyn PhysicalExpr> = Arc::new(Column::new("time", 0));
+        let name: Arc<dyn PhysicalExpr> = Arc::new(Column::new("name", 1));
+
+        // The last point of `a` is not in a full window, the window of `b` is full
+        let indices = open_window_indices(&window, &time, &[name], 0, &points()).unwrap();
+        assert_eq!(sorted(indices), vec![3]);
+
+        let indices = open_window_indices(&window, &time, &[], 0, &points()).unwrap();
+        assert_eq!(sorted(indices), vec![5]);
+    }
+}
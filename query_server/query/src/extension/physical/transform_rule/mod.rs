@@ -6,3 +6,4 @@ pub mod tag_scan;
 pub mod ts_gen_func;
 pub mod update_tag;
 pub mod watermark;
+pub mod window_state;
@@ -0,0 +1,86 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::error::Result;
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::{LogicalPlan, UserDefinedLogicalNode};
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::physical_planner::{ExtensionPlanner, PhysicalPlanner};
+
+use crate::extension::logical::plan_node::window_state::WindowStateNode;
+use crate::extension::physical::plan_node::window_state::WindowStateExec;
+use crate::extension::utils::downcast_plan_node;
+use crate::stream::state_store::StateStoreFactory;
+
+/// Physical planner for WindowState nodes
+pub struct WindowStatePlanner<T> {
+    watermark_ns: i64,
+    state_store_factory: Arc<T>,
+}
+
+impl<T> WindowStatePlanner<T> {
+    pub fn new(watermark_ns: i64, state_store_factory: Arc<T>) -> Self {
+        Self {
+            watermark_ns,
+            state_store_factory,
+        }
+    }
+}
+
+#[async_trait]
+impl<T> ExtensionPlanner for WindowStatePlanner<T>
+where
+    T: StateStoreFactory + Send + Sync + Debug + 'static,
+    T::SS: Send + Sync + Debug,
+{
+    /// Create a physical plan for an extension node
+    async fn plan_extension(
+        &self,
+        planner: &dyn PhysicalPlanner,
+        node: &dyn UserDefinedLogicalNode,
+        _logical_inputs: &[&LogicalPlan],
+        physical_inputs: &[Arc<dyn ExecutionPlan>],
+        session_state: &SessionState,
+    ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        if let Some(WindowStateNode {
+            window,
+            time_column,
+            partition_by,
+            input,
+            ..
+        }) = downcast_plan_node(node)
+        {
+            assert!(physical_inputs.len() == 1);
+            let child = physical_inputs[0].clone();
+            let input_dfschema = input.schema();
+            let input_schema = child.schema();
+
+            let time_expr = planner.create_physical_expr(
+                time_column,
+                input_dfschema,
+                &input_schema,
+                session_state,
+            )?;
+            let partition_exprs = partition_by
+                .iter()
+                .map(|e| {
+                    planner.create_physical_expr(e, input_dfschema, &input_schema, session_state)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let plan = WindowStateExec::try_new(
+                window.clone(),
+                time_expr,
+                partition_exprs,
+                self.watermark_ns,
+                self.state_store_factory.clone(),
+                child,
+            )?;
+
+            Ok(Some(Arc::new(plan)))
+        } else {
+            Ok(None)
+        }
+    }
+}
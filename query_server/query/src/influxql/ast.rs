@@ -0,0 +1,221 @@
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    Select(Box<SelectStatement>),
+    ShowDatabases,
+    ShowMeasurements(ShowMeasurements),
+    ShowTagKeys(ShowTagKeys),
+    ShowTagValues(ShowTagValues),
+    ShowFieldKeys(ShowFieldKeys),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectStatement {
+    pub fields: Vec<Field>,
+    pub from: Measurement,
+    pub condition: Option<Expr>,
+    pub group_by: GroupBy,
+    pub fill: Fill,
+    /// `ORDER BY time DESC`
+    pub order_desc: bool,
+    /// The limit and offset of the points of each series.
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    /// The limit and offset of the series.
+    pub slimit: Option<usize>,
+    pub soffset: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub expr: Expr,
+    pub alias: Option<String>,
+}
+
+impl Field {
+    /// The column name of the field in the result, an aggregate is named after its function
+    /// like InfluxDB does, e.g. `mean(usage)` is named `mean`.
+    pub fn name(&self) -> String {
+        if let Some(alias) = &self.alias {
+            return alias.clone();
+        }
+        fn first_name(expr: &Expr) -> Option<String> {
+            match expr {
+                Expr::Call { name, .. } => Some(name.to_ascii_lowercase()),
+                Expr::VarRef(name) => Some(name.clone()),
+                Expr::Binary { lhs, rhs, .. } => first_name(lhs).or_else(|| first_name(rhs)),
+                _ => None,
+            }
+        }
+        first_name(&self.expr).unwrap_or_else(|| "expr".to_string())
+    }
+}
+
+/// A measurement, may be qualified by the database and the retention policy,
+/// e.g. `"db"."autogen"."cpu"` or `"db".."cpu"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Measurement {
+    pub database: Option<String>,
+    pub retention_policy: Option<String>,
+    pub name: String,
+}
+
+/// The sources of the `SHOW` statements.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MeasurementSource {
+    Name(Measurement),
+    Regex(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GroupBy {
+    pub time: Option<TimeDimension>,
+    /// `GROUP BY *` groups by all the tags.
+    pub all_tags: bool,
+    pub tags: Vec<String>,
+}
+
+/// `time(interval[, offset])`, in nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeDimension {
+    pub interval: i64,
+    pub offset: i64,
+}
+
+/// How the windows without data of `GROUP BY time()` are filled.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Fill {
+    #[default]
+    Null,
+    /// Windows without data are not returned.
+    None,
+    Previous,
+    Linear,
+    Value(f64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// `*`
+    Wildcard,
+    /// A field, a tag or `time`.
+    VarRef(String),
+    Call {
+        name: String,
+        args: Vec<Expr>,
+    },
+    Integer(i64),
+    Number(f64),
+    String(String),
+    Boolean(bool),
+    /// In nanoseconds.
+    Duration(i64),
+    Regex(String),
+    Binary {
+        op: BinaryOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    EqRegex,
+    NotEqRegex,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    pub fn precedence(&self) -> u8 {
+        match self {
+            BinaryOp::Or => 1,
+            BinaryOp::And => 2,
+            BinaryOp::Eq
+            | BinaryOp::NotEq
+            | BinaryOp::Lt
+            | BinaryOp::LtEq
+            | BinaryOp::Gt
+            | BinaryOp::GtEq
+            | BinaryOp::EqRegex
+            | BinaryOp::NotEqRegex => 3,
+            BinaryOp::Add | BinaryOp::Sub => 4,
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => 5,
+        }
+    }
+
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Mod => "%",
+            BinaryOp::Eq => "=",
+            BinaryOp::NotEq => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::LtEq => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::GtEq => ">=",
+            BinaryOp::EqRegex => "~",
+            BinaryOp::NotEqRegex => "!~",
+            BinaryOp::And => "AND",
+            BinaryOp::Or => "OR",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowMeasurements {
+    pub database: Option<String>,
+    /// `WITH MEASUREMENT = name` or `WITH MEASUREMENT =~ /regex/`
+    pub with_measurement: Option<MeasurementSource>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowTagKeys {
+    pub database: Option<String>,
+    pub from: Vec<MeasurementSource>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShowTagValues {
+    pub database: Option<String>,
+    pub from: Vec<MeasurementSource>,
+    pub key: TagKeyFilter,
+    pub condition: Option<Expr>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// The `WITH KEY` clause of `SHOW TAG VALUES`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagKeyFilter {
+    Eq(String),
+    NotEq(String),
+    In(Vec<String>),
+    Regex(String),
+    NotRegex(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowFieldKeys {
+    pub database: Option<String>,
+    pub from: Vec<MeasurementSource>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
@@ -0,0 +1,95 @@
+//! An InfluxQL engine for the `/query` api compatible with InfluxDB 1.x.
+//!
+//! The `SELECT` statements are planned as logical plans of DataFusion, including the windows
+//! of `GROUP BY time()` and their `fill()`, the rows of the result are grouped into series.
+
+use spi::{QueryError, QueryResult};
+
+pub mod ast;
+pub mod parser;
+mod planner;
+mod series;
+pub mod server;
+
+/// Max number of windows of a series that `fill()` generates.
+pub const MAX_FILL_WINDOWS: i64 = 100_000;
+
+/// Parses an InfluxQL duration like `1h30m` to nanoseconds.
+pub fn parse_duration_ns(text: &str) -> Result<i64, String> {
+    const UNITS: [(&str, i64); 9] = [
+        ("ns", 1),
+        ("u", 1_000),
+        ("µ", 1_000),
+        ("ms", 1_000_000),
+        ("s", 1_000_000_000),
+        ("m", 60_000_000_000),
+        ("h", 3_600_000_000_000),
+        ("d", 86_400_000_000_000),
+        ("w", 604_800_000_000_000),
+    ];
+
+    let invalid = || format!("invalid duration '{text}'");
+    let mut rest = text;
+    let mut total = 0_i64;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let value = rest[..digits].parse::<i64>().map_err(|_| invalid())?;
+        rest = &rest[digits..];
+        let (unit, nanos) = UNITS
+            .iter()
+            .filter(|(unit, _)| rest.starts_with(unit))
+            .max_by_key(|(unit, _)| unit.len())
+            .ok_or_else(invalid)?;
+        rest = &rest[unit.len()..];
+        total = value
+            .checked_mul(*nanos)
+            .and_then(|v| v.checked_add(total))
+            .ok_or_else(invalid)?;
+    }
+    if text.is_empty() {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+/// Parses the `epoch` parameter of the `/query` api, returns the nanoseconds of the unit.
+pub fn parse_epoch(epoch: &str) -> QueryResult<i64> {
+    match epoch {
+        "ns" | "n" => Ok(1),
+        "u" | "us" | "µ" | "µs" => Ok(1_000),
+        "ms" => Ok(1_000_000),
+        "s" => Ok(1_000_000_000),
+        "m" => Ok(60_000_000_000),
+        "h" => Ok(3_600_000_000_000),
+        _ => Err(QueryError::InvalidInfluxQL {
+            err: format!("invalid epoch '{epoch}'"),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_duration_ns, parse_epoch};
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration_ns("10s"), Ok(10_000_000_000));
+        assert_eq!(parse_duration_ns("1h30m"), Ok(5_400_000_000_000));
+        assert_eq!(parse_duration_ns("100ms"), Ok(100_000_000));
+        assert_eq!(parse_duration_ns("5u"), Ok(5_000));
+        assert_eq!(parse_duration_ns("5µ"), Ok(5_000));
+        assert_eq!(parse_duration_ns("1w"), Ok(604_800_000_000_000));
+        assert!(parse_duration_ns("").is_err());
+        assert!(parse_duration_ns("5").is_err());
+        assert!(parse_duration_ns("5y").is_err());
+    }
+
+    #[test]
+    fn test_parse_epoch() {
+        assert_eq!(parse_epoch("ms").unwrap(), 1_000_000);
+        assert_eq!(parse_epoch("n").unwrap(), 1);
+        assert!(parse_epoch("d").is_err());
+    }
+}
@@ -0,0 +1,886 @@
+use spi::{QueryError, QueryResult};
+
+use super::ast::{
+    BinaryOp, Expr, Field, Fill, GroupBy, Measurement, MeasurementSource, SelectStatement,
+    ShowFieldKeys, ShowMeasurements, ShowTagKeys, ShowTagValues, Statement, TagKeyFilter,
+    TimeDimension,
+};
+use super::parse_duration_ns;
+
+type Result<T> = std::result::Result<T, String>;
+
+/// Parses the `;` separated InfluxQL statements.
+pub fn parse(input: &str) -> QueryResult<Vec<Statement>> {
+    let tokens = Lexer::new(input)
+        .tokenize()
+        .map_err(|err| QueryError::InvalidInfluxQL { err })?;
+    let mut parser = Parser { tokens, pos: 0 };
+    parser
+        .parse_statements()
+        .map_err(|err| QueryError::InvalidInfluxQL { err })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    QuotedIdent(String),
+    Str(String),
+    Integer(i64),
+    Number(f64),
+    /// In nanoseconds.
+    Duration(i64),
+    Regex(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+    Dot,
+    Semicolon,
+    DoubleColon,
+    Eof,
+}
+
+struct Lexer<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    input: &'a str,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.char_indices().peekable(),
+            input,
+        }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<Token>> {
+        let mut tokens: Vec<Token> = vec![];
+        while let Some(&(start, c)) = self.chars.peek() {
+            let token = match c {
+                c if c.is_whitespace() => {
+                    self.chars.next();
+                    continue;
+                }
+                '-' if self.input[start..].starts_with("--") => {
+                    // comment until the end of line
+                    while matches!(self.chars.peek(), Some(&(_, c)) if c != '\n') {
+                        self.chars.next();
+                    }
+                    continue;
+                }
+                '(' => self.single(Token::LParen),
+                ')' => self.single(Token::RParen),
+                ',' => self.single(Token::Comma),
+                '.' if !self.input[start + 1..].starts_with(|c: char| c.is_ascii_digit()) => {
+                    self.single(Token::Dot)
+                }
+                ';' => self.single(Token::Semicolon),
+                '+' => self.single(Token::Op("+")),
+                '-' => self.single(Token::Op("-")),
+                '*' => self.single(Token::Op("*")),
+                '%' => self.single(Token::Op("%")),
+                // A slash is a division only if it follows an operand,
+                // otherwise it starts a regular expression, e.g. `=~ /cpu.*/` or `FROM /cpu.*/`.
+                '/' if follows_operand(tokens.last()) => self.single(Token::Op("/")),
+                '/' => self.regex()?,
+                ':' => {
+                    self.chars.next();
+                    if self.next_if(':') {
+                        Token::DoubleColon
+                    } else {
+                        return Err(format!("unexpected character ':' at {start}"));
+                    }
+                }
+                '=' => {
+                    self.chars.next();
+                    if self.next_if('~') {
+                        Token::Op("=~")
+                    } else {
+                        Token::Op("=")
+                    }
+                }
+                '!' => {
+                    self.chars.next();
+                    if self.next_if('=') {
+                        Token::Op("!=")
+                    } else if self.next_if('~') {
+                        Token::Op("!~")
+                    } else {
+                        return Err(format!("unexpected character after '!' at {start}"));
+                    }
+                }
+                '<' => {
+                    self.chars.next();
+                    if self.next_if('=') {
+                        Token::Op("<=")
+                    } else if self.next_if('>') {
+                        Token::Op("!=")
+                    } else {
+                        Token::Op("<")
+                    }
+                }
+                '>' => {
+                    self.chars.next();
+                    if self.next_if('=') {
+                        Token::Op(">=")
+                    } else {
+                        Token::Op(">")
+                    }
+                }
+                '"' => Token::QuotedIdent(self.quoted(c)?),
+                '\'' => Token::Str(self.quoted(c)?),
+                c if c.is_ascii_digit() || c == '.' => self.number_or_duration(start)?,
+                c if c.is_alphabetic() || c == '_' => {
+                    let end = self.take_while(|c| c.is_alphanumeric() || c == '_');
+                    Token::Ident(self.input[start..end].to_string())
+                }
+                c => return Err(format!("unexpected character '{c}' at {start}")),
+            };
+            tokens.push(token);
+        }
+        tokens.push(Token::Eof);
+        Ok(tokens)
+    }
+
+    fn single(&mut self, token: Token) -> Token {
+        self.chars.next();
+        token
+    }
+
+    fn next_if(&mut self, expected: char) -> bool {
+        self.chars.next_if(|&(_, c)| c == expected).is_some()
+    }
+
+    /// Consumes chars while `f` holds, returns the end offset.
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> usize {
+        while self.chars.next_if(|&(_, c)| f(c)).is_some() {}
+        self.chars
+            .peek()
+            .map(|&(i, _)| i)
+            .unwrap_or(self.input.len())
+    }
+
+    fn quoted(&mut self, quote: char) -> Result<String> {
+        self.chars.next();
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, c)) if c == quote => return Ok(value),
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, c)) => value.push(c),
+                    None => break,
+                },
+                Some((_, c)) => value.push(c),
+                None => break,
+            }
+        }
+        Err("unterminated quoted string".to_string())
+    }
+
+    fn regex(&mut self) -> Result<Token> {
+        self.chars.next();
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '/')) => return Ok(Token::Regex(value)),
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, '/')) => value.push('/'),
+                    Some((_, c)) => {
+                        value.push('\\');
+                        value.push(c);
+                    }
+                    None => break,
+                },
+                Some((_, c)) => value.push(c),
+                None => break,
+            }
+        }
+        Err("unterminated regular expression".to_string())
+    }
+
+    fn number_or_duration(&mut self, start: usize) -> Result<Token> {
+        let end = self.take_while(|c| c.is_ascii_digit() || c == '.');
+        if matches!(self.chars.peek(), Some(&(_, c)) if c.is_alphabetic()) {
+            let end = self.take_while(|c| c.is_alphanumeric());
+            let text = &self.input[start..end];
+            return parse_duration_ns(text).map(Token::Duration);
+        }
+        let text = &self.input[start..end];
+        if text.contains('.') {
+            text.parse::<f64>()
+                .map(Token::Number)
+                .map_err(|_| format!("invalid number '{text}'"))
+        } else {
+            text.parse::<i64>()
+                .map(Token::Integer)
+                .map_err(|_| format!("invalid integer '{text}'"))
+        }
+    }
+}
+
+fn follows_operand(token: Option<&Token>) -> bool {
+    match token {
+        Some(Token::Ident(ident)) => !ident.eq_ignore_ascii_case("from"),
+        Some(
+            Token::QuotedIdent(_)
+            | Token::Str(_)
+            | Token::Integer(_)
+            | Token::Number(_)
+            | Token::Duration(_)
+            | Token::Regex(_)
+            | Token::RParen,
+        ) => true,
+        _ => false,
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn peek_nth(&self, n: usize) -> &Token {
+        &self.tokens[(self.pos + n).min(self.tokens.len() - 1)]
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if self.pos < self.tokens.len() - 1 {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.next() {
+            token if token == expected => Ok(()),
+            token => Err(format!("expected {expected:?}, found {token:?}")),
+        }
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Ident(ident) if ident.eq_ignore_ascii_case(keyword))
+    }
+
+    fn consume_keyword(&mut self, keyword: &str) -> bool {
+        if self.peek_keyword(keyword) {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.consume_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(format!("expected {keyword}, found {:?}", self.peek()))
+        }
+    }
+
+    fn parse_statements(&mut self) -> Result<Vec<Statement>> {
+        let mut statements = vec![];
+        loop {
+            while self.peek() == &Token::Semicolon {
+                self.next();
+            }
+            if self.peek() == &Token::Eof {
+                break;
+            }
+            statements.push(self.parse_statement()?);
+            match self.peek() {
+                Token::Semicolon | Token::Eof => {}
+                token => return Err(format!("unexpected {token:?} at the end of statement")),
+            }
+        }
+        if statements.is_empty() {
+            return Err("empty query".to_string());
+        }
+        Ok(statements)
+    }
+
+    fn parse_statement(&mut self) -> Result<Statement> {
+        if self.consume_keyword("select") {
+            Ok(Statement::Select(Box::new(self.parse_select()?)))
+        } else if self.consume_keyword("show") {
+            self.parse_show()
+        } else {
+            Err(format!("expected SELECT or SHOW, found {:?}", self.peek()))
+        }
+    }
+
+    fn parse_select(&mut self) -> Result<SelectStatement> {
+        let fields = self.parse_comma_separated(Self::parse_field)?;
+        self.expect_keyword("from")?;
+        let from = self.parse_measurement()?;
+        let condition = self.parse_where()?;
+
+        let mut group_by = GroupBy::default();
+        if self.consume_keyword("group") {
+            self.expect_keyword("by")?;
+            group_by = self.parse_group_by()?;
+        }
+
+        let fill = self.parse_fill()?;
+
+        let mut order_desc = false;
+        if self.consume_keyword("order") {
+            self.expect_keyword("by")?;
+            if !self.consume_keyword("time") {
+                return Err("only ORDER BY time supported at this time".to_string());
+            }
+            if self.consume_keyword("desc") {
+                order_desc = true;
+            } else {
+                self.consume_keyword("asc");
+            }
+        }
+
+        let (limit, offset) = self.parse_limit_offset("limit", "offset")?;
+        let (slimit, soffset) = self.parse_limit_offset("slimit", "soffset")?;
+        if self.peek_keyword("tz") {
+            return Err("tz() is not supported".to_string());
+        }
+
+        Ok(SelectStatement {
+            fields,
+            from,
+            condition,
+            group_by,
+            fill,
+            order_desc,
+            limit,
+            offset,
+            slimit,
+            soffset,
+        })
+    }
+
+    fn parse_field(&mut self) -> Result<Field> {
+        let expr = self.parse_expr(0)?;
+        let alias = if self.consume_keyword("as") {
+            Some(self.parse_identifier()?)
+        } else {
+            None
+        };
+        Ok(Field { expr, alias })
+    }
+
+    fn parse_where(&mut self) -> Result<Option<Expr>> {
+        if self.consume_keyword("where") {
+            Ok(Some(self.parse_expr(0)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn parse_group_by(&mut self) -> Result<GroupBy> {
+        let mut group_by = GroupBy::default();
+        loop {
+            if self.peek_keyword("time") && self.peek_nth(1) == &Token::LParen {
+                if group_by.time.is_some() {
+                    return Err("multiple time dimensions".to_string());
+                }
+                self.next();
+                self.next();
+                let interval = self.parse_duration()?;
+                if interval <= 0 {
+                    return Err("time dimension must have a positive duration".to_string());
+                }
+                let mut offset = 0;
+                if self.peek() == &Token::Comma {
+                    self.next();
+                    offset = self.parse_duration()?;
+                }
+                self.expect(Token::RParen)?;
+                group_by.time = Some(TimeDimension { interval, offset });
+            } else if self.peek() == &Token::Op("*") {
+                self.next();
+                group_by.all_tags = true;
+            } else {
+                group_by.tags.push(self.parse_identifier()?);
+            }
+
+            if self.peek() != &Token::Comma {
+                break;
+            }
+            self.next();
+        }
+        Ok(group_by)
+    }
+
+    fn parse_fill(&mut self) -> Result<Fill> {
+        if !(self.peek_keyword("fill") && self.peek_nth(1) == &Token::LParen) {
+            return Ok(Fill::default());
+        }
+        self.next();
+        self.next();
+        let fill = match self.next() {
+            Token::Ident(option) => match option.to_ascii_lowercase().as_str() {
+                "null" => Fill::Null,
+                "none" => Fill::None,
+                "previous" => Fill::Previous,
+                "linear" => Fill::Linear,
+                _ => return Err(format!("unknown fill option '{option}'")),
+            },
+            Token::Integer(value) => Fill::Value(value as f64),
+            Token::Number(value) => Fill::Value(value),
+            Token::Op("-") => match self.next() {
+                Token::Integer(value) => Fill::Value(-value as f64),
+                Token::Number(value) => Fill::Value(-value),
+                token => return Err(format!("expected number, found {token:?}")),
+            },
+            token => return Err(format!("unexpected {token:?} in fill")),
+        };
+        self.expect(Token::RParen)?;
+        Ok(fill)
+    }
+
+    fn parse_duration(&mut self) -> Result<i64> {
+        match self.next() {
+            Token::Duration(value) => Ok(value),
+            Token::Op("-") => match self.next() {
+                Token::Duration(value) => Ok(-value),
+                token => Err(format!("expected duration, found {token:?}")),
+            },
+            token => Err(format!("expected duration, found {token:?}")),
+        }
+    }
+
+    fn parse_limit_offset(
+        &mut self,
+        limit_keyword: &str,
+        offset_keyword: &str,
+    ) -> Result<(Option<usize>, Option<usize>)> {
+        let mut limit = None;
+        let mut offset = None;
+        if self.consume_keyword(limit_keyword) {
+            limit = Some(self.parse_usize()?);
+        }
+        if self.consume_keyword(offset_keyword) {
+            offset = Some(self.parse_usize()?);
+        }
+        Ok((limit, offset))
+    }
+
+    fn parse_usize(&mut self) -> Result<usize> {
+        match self.next() {
+            Token::Integer(value) if value >= 0 => Ok(value as usize),
+            token => Err(format!("expected non-negative integer, found {token:?}")),
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Result<String> {
+        match self.next() {
+            Token::Ident(ident) | Token::QuotedIdent(ident) => Ok(ident),
+            token => Err(format!("expected identifier, found {token:?}")),
+        }
+    }
+
+    fn parse_comma_separated<T>(&mut self, f: impl Fn(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let mut values = vec![f(self)?];
+        while self.peek() == &Token::Comma {
+            self.next();
+            values.push(f(self)?);
+        }
+        Ok(values)
+    }
+
+    /// Parses `[database.[retention_policy].]measurement`.
+    fn parse_measurement(&mut self) -> Result<Measurement> {
+        let mut segments = vec![self.parse_identifier()?];
+        while self.peek() == &Token::Dot {
+            self.next();
+            if self.peek() == &Token::Dot {
+                segments.push(String::new());
+            } else {
+                segments.push(self.parse_identifier()?);
+            }
+        }
+
+        let name = segments.pop().unwrap_or_default();
+        let retention_policy = segments.pop().filter(|rp| !rp.is_empty());
+        let database = segments.pop();
+        if !segments.is_empty() || name.is_empty() {
+            return Err("invalid measurement".to_string());
+        }
+        Ok(Measurement {
+            database,
+            retention_policy,
+            name,
+        })
+    }
+
+    fn parse_sources(&mut self) -> Result<Vec<MeasurementSource>> {
+        if !self.consume_keyword("from") {
+            return Ok(vec![]);
+        }
+        self.parse_comma_separated(|parser| match parser.peek().clone() {
+            Token::Regex(regex) => {
+                parser.next();
+                Ok(MeasurementSource::Regex(regex))
+            }
+            _ => Ok(MeasurementSource::Name(parser.parse_measurement()?)),
+        })
+    }
+
+    fn parse_on_database(&mut self) -> Result<Option<String>> {
+        if self.consume_keyword("on") {
+            Ok(Some(self.parse_identifier()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn parse_show(&mut self) -> Result<Statement> {
+        if self.consume_keyword("databases") {
+            return Ok(Statement::ShowDatabases);
+        }
+
+        if self.consume_keyword("measurements") {
+            let database = self.parse_on_database()?;
+            let mut with_measurement = None;
+            if self.consume_keyword("with") {
+                self.expect_keyword("measurement")?;
+                with_measurement = Some(match (self.next(), self.next()) {
+                    (Token::Op("="), Token::Ident(name) | Token::QuotedIdent(name)) => {
+                        MeasurementSource::Name(Measurement {
+                            database: None,
+                            retention_policy: None,
+                            name,
+                        })
+                    }
+                    (Token::Op("=~"), Token::Regex(regex)) => MeasurementSource::Regex(regex),
+                    (_, token) => return Err(format!("unexpected {token:?} in WITH MEASUREMENT")),
+                });
+            }
+            let (limit, offset) = self.parse_limit_offset("limit", "offset")?;
+            return Ok(Statement::ShowMeasurements(ShowMeasurements {
+                database,
+                with_measurement,
+                limit,
+                offset,
+            }));
+        }
+
+        if self.consume_keyword("field") {
+            self.expect_keyword("keys")?;
+            let database = self.parse_on_database()?;
+            let from = self.parse_sources()?;
+            let (limit, offset) = self.parse_limit_offset("limit", "offset")?;
+            return Ok(Statement::ShowFieldKeys(ShowFieldKeys {
+                database,
+                from,
+                limit,
+                offset,
+            }));
+        }
+
+        if self.consume_keyword("tag") {
+            if self.consume_keyword("keys") {
+                let database = self.parse_on_database()?;
+                let from = self.parse_sources()?;
+                let (limit, offset) = self.parse_limit_offset("limit", "offset")?;
+                return Ok(Statement::ShowTagKeys(ShowTagKeys {
+                    database,
+                    from,
+                    limit,
+                    offset,
+                }));
+            }
+
+            self.expect_keyword("values")?;
+            let database = self.parse_on_database()?;
+            let from = self.parse_sources()?;
+            self.expect_keyword("with")?;
+            self.expect_keyword("key")?;
+            let key = match self.next() {
+                Token::Op("=") => TagKeyFilter::Eq(self.parse_identifier()?),
+                Token::Op("!=") => TagKeyFilter::NotEq(self.parse_identifier()?),
+                Token::Op(op @ ("=~" | "!~")) => match self.next() {
+                    Token::Regex(regex) if op == "=~" => TagKeyFilter::Regex(regex),
+                    Token::Regex(regex) => TagKeyFilter::NotRegex(regex),
+                    token => return Err(format!("expected regex, found {token:?}")),
+                },
+                Token::Ident(k) if k.eq_ignore_ascii_case("in") => {
+                    self.expect(Token::LParen)?;
+                    let keys = self.parse_comma_separated(Self::parse_identifier)?;
+                    self.expect(Token::RParen)?;
+                    TagKeyFilter::In(keys)
+                }
+                token => return Err(format!("unexpected {token:?} in WITH KEY")),
+            };
+            let condition = self.parse_where()?;
+            let (limit, offset) = self.parse_limit_offset("limit", "offset")?;
+            return Ok(Statement::ShowTagValues(ShowTagValues {
+                database,
+                from,
+                key,
+                condition,
+                limit,
+                offset,
+            }));
+        }
+
+        Err(format!("unsupported SHOW statement at {:?}", self.peek()))
+    }
+
+    fn peek_binary_op(&self) -> Option<BinaryOp> {
+        let op = match self.peek() {
+            Token::Op("+") => BinaryOp::Add,
+            Token::Op("-") => BinaryOp::Sub,
+            Token::Op("*") => BinaryOp::Mul,
+            Token::Op("/") => BinaryOp::Div,
+            Token::Op("%") => BinaryOp::Mod,
+            Token::Op("=") => BinaryOp::Eq,
+            Token::Op("!=") => BinaryOp::NotEq,
+            Token::Op("<") => BinaryOp::Lt,
+            Token::Op("<=") => BinaryOp::LtEq,
+            Token::Op(">") => BinaryOp::Gt,
+            Token::Op(">=") => BinaryOp::GtEq,
+            Token::Op("=~") => BinaryOp::EqRegex,
+            Token::Op("!~") => BinaryOp::NotEqRegex,
+            Token::Ident(ident) => match ident.to_ascii_lowercase().as_str() {
+                "and" => BinaryOp::And,
+                "or" => BinaryOp::Or,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    fn parse_expr(&mut self, min_precedence: u8) -> Result<Expr> {
+        let mut lhs = self.parse_unary()?;
+
+        while let Some(op) = self.peek_binary_op() {
+            if op.precedence() < min_precedence {
+                break;
+            }
+            self.next();
+            let rhs = self.parse_expr(op.precedence() + 1)?;
+            lhs = Expr::Binary {
+                op,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        match self.next() {
+            Token::LParen => {
+                let expr = self.parse_expr(0)?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Token::Op("*") => Ok(Expr::Wildcard),
+            Token::Op("-") => match self.next() {
+                Token::Integer(value) => Ok(Expr::Integer(-value)),
+                Token::Number(value) => Ok(Expr::Number(-value)),
+                Token::Duration(value) => Ok(Expr::Duration(-value)),
+                token => Err(format!("unexpected {token:?} after '-'")),
+            },
+            Token::Integer(value) => Ok(Expr::Integer(value)),
+            Token::Number(value) => Ok(Expr::Number(value)),
+            Token::Duration(value) => Ok(Expr::Duration(value)),
+            Token::Str(value) => Ok(Expr::String(value)),
+            Token::Regex(value) => Ok(Expr::Regex(value)),
+            Token::QuotedIdent(name) => self.parse_var_ref(name),
+            Token::Ident(name) => {
+                if self.peek() == &Token::LParen {
+                    self.next();
+                    let args = if self.peek() == &Token::RParen {
+                        vec![]
+                    } else {
+                        self.parse_comma_separated(|parser| parser.parse_expr(0))?
+                    };
+                    self.expect(Token::RParen)?;
+                    return Ok(Expr::Call { name, args });
+                }
+                match name.to_ascii_lowercase().as_str() {
+                    "true" => Ok(Expr::Boolean(true)),
+                    "false" => Ok(Expr::Boolean(false)),
+                    _ => self.parse_var_ref(name),
+                }
+            }
+            token => Err(format!("unexpected {token:?}")),
+        }
+    }
+
+    /// Parses the optional type cast of a variable, e.g. `usage::field`, which is ignored.
+    fn parse_var_ref(&mut self, name: String) -> Result<Expr> {
+        if self.peek() == &Token::DoubleColon {
+            self.next();
+            self.parse_identifier()?;
+        }
+        Ok(Expr::VarRef(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_one(input: &str) -> Statement {
+        let mut statements = parse(input).unwrap();
+        assert_eq!(statements.len(), 1);
+        statements.remove(0)
+    }
+
+    fn var(name: &str) -> Box<Expr> {
+        Box::new(Expr::VarRef(name.to_string()))
+    }
+
+    #[test]
+    fn test_parse_select() {
+        let Statement::Select(select) = parse_one(
+            r#"SELECT mean("usage") AS m, max(usage::field) FROM "db"."autogen"."cpu"
+               WHERE time > now() - 1h AND host =~ /^web\/\d+$/
+               GROUP BY time(1m), "host" fill(previous) ORDER BY time DESC LIMIT 10 SLIMIT 2"#,
+        ) else {
+            panic!("expected select statement");
+        };
+
+        assert_eq!(select.fields.len(), 2);
+        assert_eq!(select.fields[0].name(), "m");
+        assert_eq!(select.fields[1].name(), "max");
+        assert_eq!(
+            select.fields[1].expr,
+            Expr::Call {
+                name: "max".to_string(),
+                args: vec![Expr::VarRef("usage".to_string())],
+            }
+        );
+        assert_eq!(
+            select.from,
+            Measurement {
+                database: Some("db".to_string()),
+                retention_policy: Some("autogen".to_string()),
+                name: "cpu".to_string(),
+            }
+        );
+        assert_eq!(
+            select.condition,
+            Some(Expr::Binary {
+                op: BinaryOp::And,
+                lhs: Box::new(Expr::Binary {
+                    op: BinaryOp::Gt,
+                    lhs: var("time"),
+                    rhs: Box::new(Expr::Binary {
+                        op: BinaryOp::Sub,
+                        lhs: Box::new(Expr::Call {
+                            name: "now".to_string(),
+                            args: vec![],
+                        }),
+                        rhs: Box::new(Expr::Duration(3_600_000_000_000)),
+                    }),
+                }),
+                rhs: Box::new(Expr::Binary {
+                    op: BinaryOp::EqRegex,
+                    lhs: var("host"),
+                    rhs: Box::new(Expr::Regex(r"^web/\d+$".to_string())),
+                }),
+            })
+        );
+        assert_eq!(
+            select.group_by,
+            GroupBy {
+                time: Some(TimeDimension {
+                    interval: 60_000_000_000,
+                    offset: 0,
+                }),
+                all_tags: false,
+                tags: vec!["host".to_string()],
+            }
+        );
+        assert_eq!(select.fill, Fill::Previous);
+        assert!(select.order_desc);
+        assert_eq!(select.limit, Some(10));
+        assert_eq!(select.slimit, Some(2));
+
+        let Statement::Select(select) = parse_one("select * from db..cpu fill(-1.5)") else {
+            panic!("expected select statement");
+        };
+        assert_eq!(select.fields[0].expr, Expr::Wildcard);
+        assert_eq!(select.from.database.as_deref(), Some("db"));
+        assert_eq!(select.from.retention_policy, None);
+        assert_eq!(select.fill, Fill::Value(-1.5));
+    }
+
+    #[test]
+    fn test_parse_show() {
+        assert_eq!(
+            parse("SHOW DATABASES; SHOW MEASUREMENTS ON db WITH MEASUREMENT =~ /cpu.*/ LIMIT 5")
+                .unwrap(),
+            vec![
+                Statement::ShowDatabases,
+                Statement::ShowMeasurements(ShowMeasurements {
+                    database: Some("db".to_string()),
+                    with_measurement: Some(MeasurementSource::Regex("cpu.*".to_string())),
+                    limit: Some(5),
+                    offset: None,
+                }),
+            ]
+        );
+
+        assert_eq!(
+            parse_one(
+                r#"SHOW TAG VALUES FROM cpu, /mem/ WITH KEY IN ("host", region) WHERE region = 'us'"#
+            ),
+            Statement::ShowTagValues(ShowTagValues {
+                database: None,
+                from: vec![
+                    MeasurementSource::Name(Measurement {
+                        database: None,
+                        retention_policy: None,
+                        name: "cpu".to_string(),
+                    }),
+                    MeasurementSource::Regex("mem".to_string()),
+                ],
+                key: TagKeyFilter::In(vec!["host".to_string(), "region".to_string()]),
+                condition: Some(Expr::Binary {
+                    op: BinaryOp::Eq,
+                    lhs: var("region"),
+                    rhs: Box::new(Expr::String("us".to_string())),
+                }),
+                limit: None,
+                offset: None,
+            })
+        );
+
+        assert!(matches!(
+            parse_one("show tag keys from cpu"),
+            Statement::ShowTagKeys(ShowTagKeys { from, .. }) if from.len() == 1
+        ));
+        assert!(matches!(
+            parse_one("show field keys on db"),
+            Statement::ShowFieldKeys(ShowFieldKeys {
+                database: Some(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(parse("").is_err());
+        assert!(parse("DELETE FROM cpu").is_err());
+        assert!(parse("SELECT mean(x FROM cpu").is_err());
+        assert!(parse("SELECT x FROM cpu GROUP BY time(0s)").is_err());
+        assert!(parse("SELECT x FROM cpu ORDER BY host").is_err());
+        assert!(parse("SELECT x FROM cpu fill(nothing)").is_err());
+        assert!(parse("SHOW TAG VALUES FROM cpu").is_err());
+        assert!(parse("SELECT x FROM cpu WHERE host = 'a").is_err());
+    }
+}
@@ -0,0 +1,1349 @@
+//! Plans the InfluxQL statements as logical plans of DataFusion.
+
+use std::iter;
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use datafusion::arrow::datatypes::{DataType, IntervalMonthDayNanoType, TimeUnit};
+use datafusion::common::Column;
+use datafusion::logical_expr::expr::{self, ScalarFunction};
+use datafusion::logical_expr::{
+    binary_expr, window_function, AggregateFunction, BuiltinScalarFunction, Expr as DFExpr,
+    GetIndexedField, JoinType, LogicalPlan, LogicalPlanBuilder, Operator,
+};
+use datafusion::prelude::{
+    and, approx_percentile_cont, avg, cast, coalesce, count, lit, max, median, min, or, stddev,
+    sum, when,
+};
+use datafusion::scalar::ScalarValue;
+use models::schema::tskv_table_schema::TskvTableSchema;
+use spi::{QueryError, QueryResult};
+
+use super::ast::{BinaryOp, Expr, Field, Fill, Measurement, SelectStatement, TimeDimension};
+use super::series::{SeriesLayout, TIME_COLUMN};
+use super::MAX_FILL_WINDOWS;
+use crate::extension::analyse::transform_time_window::window_func_expr;
+use crate::extension::expr::expr_fn::{
+    divide, ge, gt, is_not_null, is_null, le, minus, multiply, plus,
+};
+use crate::extension::expr::{FIRST_UDAF, LAST_UDAF, MODE_UDAF, TIME_WINDOW_UDF, WINDOW_START};
+
+/// The functions of InfluxQL that aggregate the points of a window.
+const AGGREGATE_FUNCTIONS: [&str; 12] = [
+    "count",
+    "mean",
+    "median",
+    "mode",
+    "spread",
+    "stddev",
+    "sum",
+    "first",
+    "last",
+    "max",
+    "min",
+    "percentile",
+];
+
+/// The math functions that have the same name in sql.
+const SCALAR_FUNCTIONS: [&str; 17] = [
+    "abs", "acos", "asin", "atan", "atan2", "ceil", "cos", "exp", "floor", "ln", "log2", "log10",
+    "pow", "round", "sin", "sqrt", "tan",
+];
+
+/// The alias of the windows of `GROUP BY time()` when they are joined with the aggregated rows.
+const WINDOWS_ALIAS: &str = "windows";
+/// The alias of the aggregated rows when they are joined with the windows.
+const ROWS_ALIAS: &str = "rows";
+/// The column of the first window with data of a series.
+const FIRST_WINDOW: &str = "_first";
+/// The column of the number of points of an aggregation without `GROUP BY`.
+const POINTS: &str = "_points";
+
+pub struct SelectPlan {
+    pub plan: LogicalPlan,
+    pub layout: SeriesLayout,
+}
+
+/// The bounds of `time` in the `WHERE` clause, both are inclusive and in nanoseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeRange {
+    pub lower: Option<i64>,
+    pub upper: Option<i64>,
+}
+
+impl TimeRange {
+    /// Returns the conditions of the time column of the given type.
+    fn to_exprs(self, time_type: &DataType) -> QueryResult<Vec<DFExpr>> {
+        let mut exprs = vec![];
+        if let Some(lower) = self.lower {
+            exprs.push(ge(
+                column(TIME_COLUMN),
+                time_literal(lower, time_type, true)?,
+            ));
+        }
+        if let Some(upper) = self.upper {
+            exprs.push(le(
+                column(TIME_COLUMN),
+                time_literal(upper, time_type, false)?,
+            ));
+        }
+        Ok(exprs)
+    }
+}
+
+/// The windows of `GROUP BY time()`, in nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Windows {
+    interval: i64,
+    offset: i64,
+    /// The lower bound of time, the windows of a series start from its first window with data
+    /// if it's not specified.
+    lower: Option<i64>,
+    /// The time of the first point of all the series, used if the lower bound is not specified.
+    first_time: Option<i64>,
+    end: i64,
+}
+
+impl Windows {
+    fn start_of(&self, time: i64) -> i64 {
+        time - (time - self.offset).rem_euclid(self.interval)
+    }
+
+    /// Returns the `time_window` that groups the points by the windows.
+    fn to_expr(self) -> DFExpr {
+        let interval = lit(ScalarValue::IntervalMonthDayNano(Some(
+            IntervalMonthDayNanoType::make_value(0, 0, self.interval),
+        )));
+        let mut args = vec![column(TIME_COLUMN), interval.clone()];
+        if self.offset != 0 {
+            args.push(interval);
+            args.push(lit(ScalarValue::TimestampNanosecond(
+                Some(self.offset),
+                None,
+            )));
+        }
+        DFExpr::ScalarUDF(expr::ScalarUDF::new(TIME_WINDOW_UDF.clone(), args))
+    }
+
+    /// Returns the starts of all the windows, `None` if there is no point.
+    fn starts(&self) -> QueryResult<Option<Vec<i64>>> {
+        let Some(first) = self.lower.or(self.first_time).map(|t| self.start_of(t)) else {
+            return Ok(None);
+        };
+        let last = self.start_of(self.end);
+        if last < first {
+            return Ok(None);
+        }
+        if (last - first) / self.interval >= MAX_FILL_WINDOWS {
+            return Err(invalid(format!(
+                "too many windows in the group by interval, the max is {MAX_FILL_WINDOWS}, \
+                maybe you forgot to specify a where time clause"
+            )));
+        }
+        let starts = (0..=(last - first) / self.interval)
+            .map(|i| first + i * self.interval)
+            .collect();
+        Ok(Some(starts))
+    }
+}
+
+/// Plans a `SELECT` statement on the scan of all the columns of the measurement, `now` is in
+/// nanoseconds, `first_time` is the time returned by the plan of [`plan_first_time`].
+pub fn plan_select(
+    stmt: &SelectStatement,
+    schema: &TskvTableSchema,
+    scan: LogicalPlan,
+    now: i64,
+    first_time: Option<i64>,
+) -> QueryResult<SelectPlan> {
+    let (mut range, condition) = split_time_condition(stmt.condition.as_ref(), now)?;
+
+    let group_tags = if stmt.group_by.all_tags {
+        let mut tags = schema
+            .columns()
+            .iter()
+            .filter(|c| c.column_type.is_tag())
+            .map(|c| c.name.clone())
+            .collect::<Vec<_>>();
+        tags.sort();
+        tags
+    } else {
+        // Like InfluxDB, the dimensions that are not tags are ignored.
+        stmt.group_by
+            .tags
+            .iter()
+            .filter(|tag| schema.column(tag).is_some_and(|c| c.column_type.is_tag()))
+            .cloned()
+            .collect()
+    };
+
+    let fields = expand_wildcards(&stmt.fields, schema, &group_tags)?;
+    let is_aggregate = fields.iter().any(|f| contains_aggregate(&f.expr));
+    for field in fields.iter() {
+        check_field(&field.expr, is_aggregate, false)?;
+    }
+    if !is_aggregate && stmt.group_by.time.is_some() {
+        return Err(invalid("GROUP BY requires at least one aggregate function"));
+    }
+    if stmt.fill != Fill::default() && (!is_aggregate || stmt.group_by.time.is_none()) {
+        return Err(invalid("fill() requires GROUP BY time()"));
+    }
+
+    if is_aggregate && stmt.group_by.time.is_some() && range.upper.is_none() {
+        // The windows end at now if the upper bound is not specified, like InfluxDB.
+        range.upper = Some(now);
+    }
+    let time_type = time_type(&scan)?;
+    let mut conditions = selection(range, condition.as_ref(), &time_type)?;
+    if !is_aggregate {
+        // Points that have none of the selected fields are not returned.
+        let not_null = fields
+            .iter()
+            .flat_map(|f| var_refs(&f.expr))
+            .filter(|name| {
+                schema
+                    .column(name)
+                    .is_some_and(|c| c.column_type.is_field())
+            })
+            .map(|name| is_not_null(column(name)))
+            .reduce(or);
+        conditions.extend(not_null);
+    }
+    let mut builder = LogicalPlanBuilder::from(scan);
+    if let Some(predicate) = conditions.into_iter().reduce(and) {
+        builder = builder.filter(predicate)?;
+    }
+
+    let tags = group_tags.iter().map(|t| column(t)).collect::<Vec<_>>();
+    let mut aggregates = vec![];
+    let values = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| Ok(plan_expr(&f.expr, &mut aggregates)?.alias(value_alias(i))))
+        .collect::<QueryResult<Vec<_>>>()?;
+    let aggregates = aggregates
+        .into_iter()
+        .enumerate()
+        .map(|(i, a)| a.alias(aggregate_alias(i)))
+        .collect::<Vec<_>>();
+
+    let mut layout = SeriesLayout::new(stmt.from.name.clone(), column_names(&fields));
+    layout.tags = group_tags.clone();
+    layout.order_desc = stmt.order_desc;
+    layout.limit = stmt.limit;
+    layout.offset = stmt.offset;
+    layout.slimit = stmt.slimit;
+    layout.soffset = stmt.soffset;
+
+    let builder = match (is_aggregate, stmt.group_by.time) {
+        (false, _) => {
+            layout.has_time = true;
+            let mut builder = builder
+                .project(iter::once(column(TIME_COLUMN)).chain(tags).chain(values))?
+                .sort(sort_exprs(&group_tags, stmt.order_desc))?;
+            // The limit of InfluxQL applies to every series.
+            if group_tags.is_empty() {
+                if stmt.limit.is_some() || stmt.offset.is_some() {
+                    builder = builder.limit(stmt.offset.unwrap_or_default(), stmt.limit)?;
+                }
+                layout.limit = None;
+                layout.offset = None;
+            }
+            builder
+        }
+        (true, Some(TimeDimension { interval, offset })) => {
+            layout.has_time = true;
+            let windows = Windows {
+                interval,
+                offset: offset.rem_euclid(interval),
+                lower: range.lower,
+                first_time,
+                end: range.upper.unwrap_or(now),
+            };
+            let builder = builder.aggregate(
+                iter::once(windows.to_expr()).chain(tags.clone()),
+                aggregates,
+            )?;
+            // The window is the first column of the aggregation.
+            let window = DFExpr::Column(builder.schema().field(0).qualified_column());
+            let start = DFExpr::GetIndexedField(GetIndexedField::new(
+                Box::new(window),
+                ScalarValue::Utf8(Some(WINDOW_START.to_string())),
+            ));
+            let time = cast(start, DataType::Timestamp(TimeUnit::Nanosecond, None));
+            let rows = builder
+                .project(
+                    iter::once(time.alias(TIME_COLUMN))
+                        .chain(tags)
+                        .chain(values),
+                )?
+                .build()?;
+            plan_fill(rows, &group_tags, fields.len(), &windows, stmt.fill)?
+                .sort(sort_exprs(&group_tags, stmt.order_desc))?
+        }
+        (true, None) => {
+            layout.default_time = range.lower.unwrap_or_default();
+            let aggregates = aggregates
+                .into_iter()
+                .chain([count(lit(1_i64)).alias(POINTS)]);
+            let mut builder = builder.aggregate(tags.clone(), aggregates)?;
+            if group_tags.is_empty() {
+                // No series is returned if there are no points.
+                builder = builder.filter(gt(column(POINTS), lit(0_i64)))?;
+            }
+            builder = builder.project(tags.iter().cloned().chain(values))?;
+            if !group_tags.is_empty() {
+                builder = builder.sort(tags.into_iter().map(|t| t.sort(true, false)))?;
+            }
+            builder
+        }
+    };
+
+    Ok(SelectPlan {
+        plan: builder.build()?,
+        layout,
+    })
+}
+
+/// Plans the query of the time of the first point selected by a statement of `GROUP BY time()`
+/// that fills the windows without the lower bound of time, the windows start from it.
+pub fn plan_first_time(
+    stmt: &SelectStatement,
+    scan: LogicalPlan,
+    now: i64,
+) -> QueryResult<Option<LogicalPlan>> {
+    let is_aggregate = stmt.fields.iter().any(|f| contains_aggregate(&f.expr));
+    if !is_aggregate || stmt.group_by.time.is_none() || stmt.fill == Fill::None {
+        return Ok(None);
+    }
+    let (mut range, condition) = split_time_condition(stmt.condition.as_ref(), now)?;
+    if range.lower.is_some() {
+        return Ok(None);
+    }
+    range.upper.get_or_insert(now);
+
+    let time_type = time_type(&scan)?;
+    let mut builder = LogicalPlanBuilder::from(scan);
+    if let Some(predicate) = selection(range, condition.as_ref(), &time_type)?
+        .into_iter()
+        .reduce(and)
+    {
+        builder = builder.filter(predicate)?;
+    }
+    let plan = builder
+        .aggregate(Vec::<DFExpr>::new(), vec![min(column(TIME_COLUMN))])?
+        .build()?;
+    Ok(Some(plan))
+}
+
+/// Returns the conditions of the `WHERE` clause.
+fn selection(
+    range: TimeRange,
+    condition: Option<&Expr>,
+    time_type: &DataType,
+) -> QueryResult<Vec<DFExpr>> {
+    let mut conditions = vec![];
+    if let Some(condition) = condition {
+        if contains_aggregate(condition) {
+            return Err(invalid(
+                "aggregate functions are not allowed in the WHERE clause",
+            ));
+        }
+        conditions.push(plan_expr(condition, &mut vec![])?);
+    }
+    conditions.extend(range.to_exprs(time_type)?);
+    Ok(conditions)
+}
+
+/// Adds the windows without data to every series and fills the null values of the windows,
+/// the columns of `rows` are the time, the tags and the values.
+fn plan_fill(
+    rows: LogicalPlan,
+    tags: &[String],
+    num_values: usize,
+    windows: &Windows,
+    fill: Fill,
+) -> QueryResult<LogicalPlanBuilder> {
+    if fill == Fill::None {
+        return Ok(LogicalPlanBuilder::from(rows));
+    }
+
+    let partition_by = tags.iter().map(|t| column(t)).collect::<Vec<_>>();
+    let builder = match windows.starts()? {
+        Some(starts) => {
+            // The series that have data, with the first window that has data.
+            let series = LogicalPlanBuilder::from(rows.clone())
+                .aggregate(
+                    partition_by.clone(),
+                    vec![min(column(TIME_COLUMN)).alias(FIRST_WINDOW)],
+                )?
+                .filter(is_not_null(column(FIRST_WINDOW)))?
+                .build()?;
+            let starts = starts
+                .into_iter()
+                .map(|t| vec![lit(ScalarValue::TimestampNanosecond(Some(t), None))])
+                .collect::<Vec<_>>();
+            let mut all_windows = LogicalPlanBuilder::values(starts)?
+                .project(vec![column("column1").alias(TIME_COLUMN)])?
+                .cross_join(series)?;
+            if windows.lower.is_none() {
+                all_windows = all_windows.filter(ge(column(TIME_COLUMN), column(FIRST_WINDOW)))?;
+            }
+
+            let keys = iter::once(TIME_COLUMN)
+                .chain(tags.iter().map(|t| t.as_str()))
+                .collect::<Vec<_>>();
+            let join_keys = |alias: &'static str| {
+                keys.iter()
+                    .map(|k| Column::new(Some(alias), *k))
+                    .collect::<Vec<_>>()
+            };
+            let columns = keys
+                .iter()
+                .map(|k| DFExpr::Column(Column::new(Some(WINDOWS_ALIAS), *k)).alias(*k))
+                .chain((0..num_values).map(|i| {
+                    DFExpr::Column(Column::new(Some(ROWS_ALIAS), value_alias(i)))
+                        .alias(value_alias(i))
+                }));
+            all_windows
+                .alias(WINDOWS_ALIAS)?
+                .join_detailed(
+                    LogicalPlanBuilder::from(rows).alias(ROWS_ALIAS)?.build()?,
+                    JoinType::Left,
+                    (join_keys(WINDOWS_ALIAS), join_keys(ROWS_ALIAS)),
+                    None,
+                    // The points without a tag are a series too.
+                    true,
+                )?
+                .project(columns)?
+        }
+        None => LogicalPlanBuilder::from(rows),
+    };
+
+    let value_types = (0..num_values)
+        .map(|i| {
+            Ok(builder
+                .schema()
+                .field_with_unqualified_name(&value_alias(i))?
+                .data_type()
+                .clone())
+        })
+        .collect::<QueryResult<Vec<_>>>()?;
+    let (builder, values) = match fill {
+        Fill::Null | Fill::None => return Ok(builder),
+        Fill::Value(value) => {
+            let values = value_types
+                .iter()
+                .enumerate()
+                .map(|(i, value_type)| {
+                    let current = column(&value_alias(i));
+                    if !value_type.is_numeric() {
+                        current
+                    } else if value.fract() == 0.0 {
+                        coalesce(vec![current, cast(lit(value), value_type.clone())])
+                    } else {
+                        coalesce(vec![cast(current, DataType::Float64), lit(value)])
+                    }
+                })
+                .collect();
+            (builder, values)
+        }
+        Fill::Previous => fill_previous(builder, partition_by, num_values)?,
+        Fill::Linear => fill_linear(builder, partition_by, &value_types)?,
+    };
+
+    let columns = iter::once(column(TIME_COLUMN))
+        .chain(tags.iter().map(|t| column(t)))
+        .chain(
+            values
+                .into_iter()
+                .enumerate()
+                .map(|(i, v)| v.alias(value_alias(i))),
+        );
+    Ok(builder.project(columns)?)
+}
+
+/// Fills the null values by the previous values of the series.
+fn fill_previous(
+    mut builder: LogicalPlanBuilder,
+    partition_by: Vec<DFExpr>,
+    num_values: usize,
+) -> QueryResult<(LogicalPlanBuilder, Vec<DFExpr>)> {
+    // The rows are numbered by the count of the values up to them, so a null value has
+    // the same number as the previous value.
+    let groups = (0..num_values)
+        .map(|i| {
+            running_count(&value_alias(i), partition_by.clone(), true).alias(group_alias("p", i))
+        })
+        .collect::<Vec<_>>();
+    builder = builder.window(groups)?;
+
+    let mut values = Vec::with_capacity(num_values);
+    for i in 0..num_values {
+        let previous = format!("_previous{i}");
+        builder = builder.window(vec![group_aggregate(
+            AggregateFunction::Max,
+            column(&value_alias(i)),
+            &partition_by,
+            &group_alias("p", i),
+        )
+        .alias(&previous)])?;
+        values.push(column(&previous));
+    }
+    Ok((builder, values))
+}
+
+/// Fills the null values by the linear interpolation of the values before and after them,
+/// the values that are not numbers are not filled.
+fn fill_linear(
+    mut builder: LogicalPlanBuilder,
+    partition_by: Vec<DFExpr>,
+    value_types: &[DataType],
+) -> QueryResult<(LogicalPlanBuilder, Vec<DFExpr>)> {
+    let numbers = (0..value_types.len())
+        .filter(|i| value_types[*i].is_numeric())
+        .collect::<Vec<_>>();
+    if numbers.is_empty() {
+        let values = (0..value_types.len())
+            .map(|i| column(&value_alias(i)))
+            .collect();
+        return Ok((builder, values));
+    }
+
+    // A null value has the same number as the previous value if the rows are numbered
+    // in the ascending order of time, and the next value in the descending order.
+    let (previous_groups, next_groups): (Vec<_>, Vec<_>) = numbers
+        .iter()
+        .map(|i| {
+            (
+                running_count(&value_alias(*i), partition_by.clone(), true)
+                    .alias(group_alias("p", *i)),
+                running_count(&value_alias(*i), partition_by.clone(), false)
+                    .alias(group_alias("n", *i)),
+            )
+        })
+        .unzip();
+    builder = builder.window(previous_groups)?.window(next_groups)?;
+
+    let time = |name: &str| cast(column(name), DataType::Int64);
+    let number = |name: &str| cast(column(name), DataType::Float64);
+    let mut values = (0..value_types.len())
+        .map(|i| column(&value_alias(i)))
+        .collect::<Vec<_>>();
+    for i in numbers {
+        let value = value_alias(i);
+        let (previous_group, next_group) = (group_alias("p", i), group_alias("n", i));
+        let [previous_value, previous_time, next_value, next_time] =
+            ["_pv", "_pt", "_nv", "_nt"].map(|prefix| format!("{prefix}{i}"));
+        builder = builder
+            .window(vec![
+                group_aggregate(
+                    AggregateFunction::Max,
+                    column(&value),
+                    &partition_by,
+                    &previous_group,
+                )
+                .alias(&previous_value),
+                group_aggregate(
+                    AggregateFunction::Min,
+                    column(TIME_COLUMN),
+                    &partition_by,
+                    &previous_group,
+                )
+                .alias(&previous_time),
+            ])?
+            .window(vec![
+                group_aggregate(
+                    AggregateFunction::Max,
+                    column(&value),
+                    &partition_by,
+                    &next_group,
+                )
+                .alias(&next_value),
+                group_aggregate(
+                    AggregateFunction::Max,
+                    column(TIME_COLUMN),
+                    &partition_by,
+                    &next_group,
+                )
+                .alias(&next_time),
+            ])?;
+
+        // previous + (next - previous) * (time - previous_time) / (next_time - previous_time)
+        let ratio = divide(
+            cast(
+                minus(time(TIME_COLUMN), time(&previous_time)),
+                DataType::Float64,
+            ),
+            cast(
+                minus(time(&next_time), time(&previous_time)),
+                DataType::Float64,
+            ),
+        );
+        let interpolated = plus(
+            number(&previous_value),
+            multiply(minus(number(&next_value), number(&previous_value)), ratio),
+        );
+        values[i] = when(
+            is_null(column(&value)),
+            cast(interpolated, value_types[i].clone()),
+        )
+        .otherwise(column(&value))?;
+    }
+    Ok((builder, values))
+}
+
+/// The count of the non-null values of the column up to the current row of the series.
+fn running_count(name: &str, partition_by: Vec<DFExpr>, asc: bool) -> DFExpr {
+    window_func_expr(
+        window_function::WindowFunction::AggregateFunction(AggregateFunction::Count),
+        vec![column(name)],
+        partition_by,
+        vec![column(TIME_COLUMN).sort(asc, !asc)],
+    )
+}
+
+/// The aggregation of the rows with the same number in the series.
+fn group_aggregate(
+    fun: AggregateFunction,
+    arg: DFExpr,
+    partition_by: &[DFExpr],
+    group: &str,
+) -> DFExpr {
+    let partition_by = partition_by
+        .iter()
+        .cloned()
+        .chain([column(group)])
+        .collect();
+    window_func_expr(
+        window_function::WindowFunction::AggregateFunction(fun),
+        vec![arg],
+        partition_by,
+        vec![],
+    )
+}
+
+fn group_alias(prefix: &str, index: usize) -> String {
+    format!("_{prefix}g{index}")
+}
+
+/// Orders the rows by the tags and then the time.
+fn sort_exprs(tags: &[String], order_desc: bool) -> Vec<DFExpr> {
+    tags.iter()
+        .map(|t| column(t).sort(true, false))
+        .chain([column(TIME_COLUMN).sort(!order_desc, order_desc)])
+        .collect()
+}
+
+fn time_type(plan: &LogicalPlan) -> QueryResult<DataType> {
+    Ok(plan
+        .schema()
+        .field_with_unqualified_name(TIME_COLUMN)?
+        .data_type()
+        .clone())
+}
+
+/// Converts the nanoseconds to a literal of the time column, it's rounded down to the unit
+/// of the column, or up if `round_up` is true.
+fn time_literal(nanos: i64, time_type: &DataType, round_up: bool) -> QueryResult<DFExpr> {
+    let DataType::Timestamp(unit, tz) = time_type else {
+        return Err(QueryError::Internal {
+            reason: format!("unexpected type of the time column: {time_type}"),
+        });
+    };
+    let unit_nanos = match unit {
+        TimeUnit::Second => 1_000_000_000,
+        TimeUnit::Millisecond => 1_000_000,
+        TimeUnit::Microsecond => 1_000,
+        TimeUnit::Nanosecond => 1,
+    };
+    let value = Some(
+        nanos.div_euclid(unit_nanos) + i64::from(round_up && nanos.rem_euclid(unit_nanos) != 0),
+    );
+    let value = match unit {
+        TimeUnit::Second => ScalarValue::TimestampSecond(value, tz.clone()),
+        TimeUnit::Millisecond => ScalarValue::TimestampMillisecond(value, tz.clone()),
+        TimeUnit::Microsecond => ScalarValue::TimestampMicrosecond(value, tz.clone()),
+        TimeUnit::Nanosecond => ScalarValue::TimestampNanosecond(value, tz.clone()),
+    };
+    Ok(lit(value))
+}
+
+/// Converts an expression of InfluxQL, the aggregate functions are added to `aggregates`
+/// and replaced by the columns of their results.
+fn plan_expr(expr: &Expr, aggregates: &mut Vec<DFExpr>) -> QueryResult<DFExpr> {
+    let expr = match expr {
+        Expr::Wildcard => return Err(invalid("unexpected wildcard")),
+        Expr::VarRef(name) => column(name),
+        Expr::Integer(value) => lit(*value),
+        Expr::Number(value) => lit(*value),
+        Expr::String(value) | Expr::Regex(value) => lit(value.clone()),
+        Expr::Boolean(value) => lit(*value),
+        Expr::Duration(_) => return Err(invalid("durations can only be compared with time")),
+        Expr::Binary { op, lhs, rhs } => binary_expr(
+            plan_expr(lhs, aggregates)?,
+            operator(*op),
+            plan_expr(rhs, aggregates)?,
+        ),
+        Expr::Call { name, args } => plan_call(name, args, aggregates)?,
+    };
+    Ok(expr)
+}
+
+fn operator(op: BinaryOp) -> Operator {
+    match op {
+        BinaryOp::Add => Operator::Plus,
+        BinaryOp::Sub => Operator::Minus,
+        BinaryOp::Mul => Operator::Multiply,
+        BinaryOp::Div => Operator::Divide,
+        BinaryOp::Mod => Operator::Modulo,
+        BinaryOp::Eq => Operator::Eq,
+        BinaryOp::NotEq => Operator::NotEq,
+        BinaryOp::Lt => Operator::Lt,
+        BinaryOp::LtEq => Operator::LtEq,
+        BinaryOp::Gt => Operator::Gt,
+        BinaryOp::GtEq => Operator::GtEq,
+        BinaryOp::EqRegex => Operator::RegexMatch,
+        BinaryOp::NotEqRegex => Operator::RegexNotMatch,
+        BinaryOp::And => Operator::And,
+        BinaryOp::Or => Operator::Or,
+    }
+}
+
+fn plan_call(name: &str, args: &[Expr], aggregates: &mut Vec<DFExpr>) -> QueryResult<DFExpr> {
+    let name = name.to_ascii_lowercase();
+    check_args(&name, args)?;
+    if !is_aggregate_function(&name) {
+        if !SCALAR_FUNCTIONS.contains(&name.as_str()) {
+            return Err(invalid(format!("unsupported function {name}()")));
+        }
+        let fun = BuiltinScalarFunction::from_str(&name)?;
+        let args = args
+            .iter()
+            .map(|arg| plan_expr(arg, aggregates))
+            .collect::<QueryResult<Vec<_>>>()?;
+        return Ok(DFExpr::ScalarFunction(ScalarFunction::new(fun, args)));
+    }
+
+    let arg = plan_expr(&args[0], aggregates)?;
+    let aggregate = match name.as_str() {
+        "count" => count(arg),
+        "mean" => avg(arg),
+        "median" => median(arg),
+        "stddev" => stddev(arg),
+        "sum" => sum(arg),
+        "max" => max(arg),
+        "min" => min(arg),
+        "mode" => DFExpr::AggregateUDF(expr::AggregateUDF {
+            fun: MODE_UDAF.clone(),
+            args: vec![arg],
+            filter: None,
+            order_by: None,
+        }),
+        "first" | "last" => DFExpr::AggregateUDF(expr::AggregateUDF {
+            fun: if name == "first" {
+                FIRST_UDAF.clone()
+            } else {
+                LAST_UDAF.clone()
+            },
+            args: vec![column(TIME_COLUMN), arg],
+            filter: None,
+            order_by: None,
+        }),
+        "spread" => {
+            return Ok(minus(
+                aggregate_column(max(arg.clone()), aggregates),
+                aggregate_column(min(arg), aggregates),
+            ))
+        }
+        "percentile" => {
+            let percentile = match &args[1] {
+                Expr::Integer(v) => *v as f64,
+                Expr::Number(v) => *v,
+                _ => return Err(invalid("expected number of percentile")),
+            };
+            if !(0.0..=100.0).contains(&percentile) {
+                return Err(invalid("percentile must be in the range [0, 100]"));
+            }
+            approx_percentile_cont(cast(arg, DataType::Float64), lit(percentile / 100.0))
+        }
+        name => return Err(invalid(format!("unsupported function {name}()"))),
+    };
+    Ok(aggregate_column(aggregate, aggregates))
+}
+
+fn check_args(name: &str, args: &[Expr]) -> QueryResult<()> {
+    let expected_args = match name {
+        "percentile" | "atan2" | "pow" => 2,
+        _ => 1,
+    };
+    if args.len() != expected_args {
+        return Err(invalid(format!(
+            "invalid number of arguments for {name}, expected {expected_args}, got {}",
+            args.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Adds the aggregation to `aggregates` if it's not added, returns the column of its result.
+fn aggregate_column(aggregate: DFExpr, aggregates: &mut Vec<DFExpr>) -> DFExpr {
+    let index = match aggregates.iter().position(|a| a == &aggregate) {
+        Some(index) => index,
+        None => {
+            aggregates.push(aggregate);
+            aggregates.len() - 1
+        }
+    };
+    column(&aggregate_alias(index))
+}
+
+/// The column of the name, which is not parsed as a qualified name.
+fn column(name: &str) -> DFExpr {
+    DFExpr::Column(Column::from_name(name))
+}
+
+fn aggregate_alias(index: usize) -> String {
+    format!("_a{index}")
+}
+
+fn value_alias(index: usize) -> String {
+    format!("_v{index}")
+}
+
+/// The names of the columns, the duplicate names are suffixed with `_1`, `_2`...
+fn column_names(fields: &[Field]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(fields.len());
+    for field in fields {
+        let name = field.name();
+        let mut unique = name.clone();
+        let mut i = 0;
+        while names.contains(&unique) {
+            i += 1;
+            unique = format!("{name}_{i}");
+        }
+        names.push(unique);
+    }
+    names
+}
+
+/// Expands `*` to all the fields and tags except the grouped tags,
+/// and `mean(*)` to the function of every field.
+fn expand_wildcards(
+    fields: &[Field],
+    schema: &TskvTableSchema,
+    group_tags: &[String],
+) -> QueryResult<Vec<Field>> {
+    let mut columns = schema
+        .columns()
+        .iter()
+        .filter(|c| !c.column_type.is_time() && !group_tags.contains(&c.name))
+        .collect::<Vec<_>>();
+    columns.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut result = vec![];
+    for field in fields {
+        match &field.expr {
+            Expr::Wildcard => result.extend(columns.iter().map(|c| Field {
+                expr: Expr::VarRef(c.name.clone()),
+                alias: None,
+            })),
+            Expr::Call { name, args } if args.first() == Some(&Expr::Wildcard) => result.extend(
+                columns
+                    .iter()
+                    .filter(|c| c.column_type.is_field())
+                    .map(|c| {
+                        let mut args = args.clone();
+                        args[0] = Expr::VarRef(c.name.clone());
+                        Field {
+                            expr: Expr::Call {
+                                name: name.clone(),
+                                args,
+                            },
+                            alias: Some(format!("{}_{}", name.to_ascii_lowercase(), c.name)),
+                        }
+                    }),
+            ),
+            _ => result.push(field.clone()),
+        }
+    }
+    if result.is_empty() {
+        return Err(invalid("no fields are selected"));
+    }
+    Ok(result)
+}
+
+fn is_aggregate_function(name: &str) -> bool {
+    AGGREGATE_FUNCTIONS.contains(&name.to_ascii_lowercase().as_str())
+}
+
+fn contains_aggregate(expr: &Expr) -> bool {
+    match expr {
+        Expr::Call { name, args } => {
+            is_aggregate_function(name) || args.iter().any(contains_aggregate)
+        }
+        Expr::Binary { lhs, rhs, .. } => contains_aggregate(lhs) || contains_aggregate(rhs),
+        _ => false,
+    }
+}
+
+/// Checks that the variables of an aggregate query are all in aggregate functions.
+fn check_field(expr: &Expr, is_aggregate: bool, in_aggregate: bool) -> QueryResult<()> {
+    match expr {
+        Expr::VarRef(name) if is_aggregate && !in_aggregate => Err(invalid(format!(
+            "mixing aggregate and non-aggregate queries is not supported: {name}"
+        ))),
+        Expr::Call { name, args } => {
+            let is_agg = is_aggregate_function(name);
+            if is_agg && in_aggregate {
+                return Err(invalid(format!("nested aggregate function {name}")));
+            }
+            args.iter()
+                .try_for_each(|arg| check_field(arg, is_aggregate, in_aggregate || is_agg))
+        }
+        Expr::Binary { lhs, rhs, .. } => {
+            check_field(lhs, is_aggregate, in_aggregate)?;
+            check_field(rhs, is_aggregate, in_aggregate)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn var_refs(expr: &Expr) -> Vec<&str> {
+    match expr {
+        Expr::VarRef(name) => vec![name.as_str()],
+        Expr::Call { args, .. } => args.iter().flat_map(var_refs).collect(),
+        Expr::Binary { lhs, rhs, .. } => {
+            let mut refs = var_refs(lhs);
+            refs.extend(var_refs(rhs));
+            refs
+        }
+        _ => vec![],
+    }
+}
+
+fn is_time(expr: &Expr) -> bool {
+    matches!(expr, Expr::VarRef(name) if name.eq_ignore_ascii_case(TIME_COLUMN))
+}
+
+fn contains_time(expr: &Expr) -> bool {
+    match expr {
+        Expr::Binary { lhs, rhs, .. } => contains_time(lhs) || contains_time(rhs),
+        Expr::Call { args, .. } => args.iter().any(contains_time),
+        expr => is_time(expr),
+    }
+}
+
+/// Splits the conditions of time from the `WHERE` clause, they must be combined by `AND`.
+pub fn split_time_condition(
+    condition: Option<&Expr>,
+    now: i64,
+) -> QueryResult<(TimeRange, Option<Expr>)> {
+    let mut conjunctions = vec![];
+    if let Some(condition) = condition {
+        split_conjunction(condition, &mut conjunctions);
+    }
+
+    let mut range = TimeRange::default();
+    let mut rest: Option<Expr> = None;
+    for expr in conjunctions {
+        if !contains_time(expr) {
+            rest = Some(match rest {
+                Some(lhs) => Expr::Binary {
+                    op: BinaryOp::And,
+                    lhs: Box::new(lhs),
+                    rhs: Box::new(expr.clone()),
+                },
+                None => expr.clone(),
+            });
+            continue;
+        }
+
+        let Expr::Binary { op, lhs, rhs } = expr else {
+            return Err(invalid("invalid time condition"));
+        };
+        let (op, value) = match (is_time(lhs), is_time(rhs)) {
+            (true, false) => (*op, eval_time(rhs, now)?),
+            (false, true) => (flip(*op), eval_time(lhs, now)?),
+            _ => return Err(invalid(
+                "invalid time condition, time must be compared with a value, and combined by AND",
+            )),
+        };
+        let lower = range.lower.unwrap_or(i64::MIN);
+        let upper = range.upper.unwrap_or(i64::MAX);
+        match op {
+            BinaryOp::Gt => range.lower = Some(lower.max(value.saturating_add(1))),
+            BinaryOp::GtEq => range.lower = Some(lower.max(value)),
+            BinaryOp::Lt => range.upper = Some(upper.min(value.saturating_sub(1))),
+            BinaryOp::LtEq => range.upper = Some(upper.min(value)),
+            BinaryOp::Eq => {
+                range.lower = Some(lower.max(value));
+                range.upper = Some(upper.min(value));
+            }
+            op => {
+                return Err(invalid(format!(
+                    "invalid operator {} of time condition",
+                    op.as_sql()
+                )))
+            }
+        }
+    }
+    Ok((range, rest))
+}
+
+fn split_conjunction<'a>(expr: &'a Expr, result: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::Binary {
+            op: BinaryOp::And,
+            lhs,
+            rhs,
+        } => {
+            split_conjunction(lhs, result);
+            split_conjunction(rhs, result);
+        }
+        expr => result.push(expr),
+    }
+}
+
+fn flip(op: BinaryOp) -> BinaryOp {
+    match op {
+        BinaryOp::Gt => BinaryOp::Lt,
+        BinaryOp::GtEq => BinaryOp::LtEq,
+        BinaryOp::Lt => BinaryOp::Gt,
+        BinaryOp::LtEq => BinaryOp::GtEq,
+        op => op,
+    }
+}
+
+/// Evaluates the value compared with time to nanoseconds,
+/// e.g. `now() - 1h`, `'2023-01-01T00:00:00Z'` or `1672531200000000000`.
+fn eval_time(expr: &Expr, now: i64) -> QueryResult<i64> {
+    match expr {
+        Expr::Call { name, args } if name.eq_ignore_ascii_case("now") && args.is_empty() => Ok(now),
+        Expr::Integer(value) | Expr::Duration(value) => Ok(*value),
+        Expr::Number(value) => Ok(*value as i64),
+        Expr::String(value) => parse_time_literal(value),
+        Expr::Binary { op, lhs, rhs } => {
+            let (lhs, rhs) = (eval_time(lhs, now)?, eval_time(rhs, now)?);
+            match op {
+                BinaryOp::Add => lhs.checked_add(rhs),
+                BinaryOp::Sub => lhs.checked_sub(rhs),
+                _ => return Err(invalid("invalid time expression")),
+            }
+            .ok_or_else(|| invalid("time overflow"))
+        }
+        _ => Err(invalid("invalid time expression")),
+    }
+}
+
+fn parse_time_literal(value: &str) -> QueryResult<i64> {
+    let naive = DateTime::parse_from_rfc3339(value)
+        .map(|t| t.naive_utc())
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f"))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(|d| d.and_hms_opt(0, 0, 0).unwrap_or_default())
+        })
+        .map_err(|_| invalid(format!("invalid time '{value}'")))?;
+    naive
+        .timestamp_nanos_opt()
+        .ok_or_else(|| invalid(format!("time out of range '{value}'")))
+}
+
+/// Rewrites a condition of InfluxQL to sql, e.g. the condition of `SHOW TAG VALUES`.
+pub fn expr_to_sql(expr: &Expr) -> QueryResult<String> {
+    let sql = match expr {
+        Expr::Wildcard => return Err(invalid("unexpected wildcard")),
+        Expr::VarRef(name) => quote_identifier(name),
+        Expr::Integer(value) => value.to_string(),
+        Expr::Number(value) => format!("{value:?}"),
+        Expr::String(value) | Expr::Regex(value) => quote_literal(value),
+        Expr::Boolean(value) => value.to_string().to_uppercase(),
+        Expr::Duration(_) => return Err(invalid("durations can only be compared with time")),
+        Expr::Binary { op, lhs, rhs } => {
+            format!(
+                "({} {} {})",
+                expr_to_sql(lhs)?,
+                op.as_sql(),
+                expr_to_sql(rhs)?
+            )
+        }
+        Expr::Call { name, args } => {
+            let name = name.to_ascii_lowercase();
+            if !SCALAR_FUNCTIONS.contains(&name.as_str()) {
+                return Err(invalid(format!("unsupported function {name}()")));
+            }
+            check_args(&name, args)?;
+            let args = args
+                .iter()
+                .map(expr_to_sql)
+                .collect::<QueryResult<Vec<_>>>()?;
+            format!("{name}({})", args.join(", "))
+        }
+    };
+    Ok(sql)
+}
+
+pub fn table_name(measurement: &Measurement) -> String {
+    match &measurement.database {
+        Some(db) => format!(
+            "{}.{}",
+            quote_identifier(db),
+            quote_identifier(&measurement.name)
+        ),
+        None => quote_identifier(&measurement.name),
+    }
+}
+
+pub fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+pub fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+pub fn invalid(err: impl ToString) -> QueryError {
+    QueryError::InvalidInfluxQL {
+        err: err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::datasource::empty::EmptyTable;
+    use datafusion::datasource::provider_as_source;
+    use models::codec::Encoding;
+    use models::schema::tskv_table_schema::{ColumnType, TableColumn};
+    use models::ValueType;
+
+    use super::*;
+    use crate::influxql::ast::Statement;
+    use crate::influxql::parser::parse;
+
+    const NOW: i64 = 1_700_000_000_000_000_000;
+    const MINUTE: i64 = 60_000_000_000;
+
+    fn schema() -> TskvTableSchema {
+        TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            "cpu".to_string(),
+            vec![
+                TableColumn::new_time_column(0, TimeUnit::Nanosecond),
+                TableColumn::new_tag_column(1, "host".to_string()),
+                TableColumn::new_tag_column(2, "region".to_string()),
+                TableColumn::new(
+                    3,
+                    "usage".to_string(),
+                    ColumnType::Field(ValueType::Float),
+                    Encoding::Default,
+                ),
+                TableColumn::new(
+                    4,
+                    "idle".to_string(),
+                    ColumnType::Field(ValueType::Integer),
+                    Encoding::Default,
+                ),
+            ],
+        )
+    }
+
+    fn scan() -> LogicalPlan {
+        let table = EmptyTable::new(schema().to_arrow_schema());
+        LogicalPlanBuilder::scan("cpu", provider_as_source(Arc::new(table)), None)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    fn statement(query: &str) -> SelectStatement {
+        let Statement::Select(stmt) = parse(query).unwrap().remove(0) else {
+            panic!("expected select statement");
+        };
+        stmt
+    }
+
+    fn plan(query: &str) -> QueryResult<SelectPlan> {
+        plan_select(&statement(query), &schema(), scan(), NOW, None)
+    }
+
+    fn plan_columns(plan: &LogicalPlan) -> Vec<String> {
+        plan.schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_time_condition() {
+        let hour = 3_600_000_000_000;
+        let stmt = statement(
+            "SELECT x FROM m WHERE time > now() - 1h AND host = 'a' AND '2023-11-14T00:00:00Z' >= time",
+        );
+        let (range, rest) = split_time_condition(stmt.condition.as_ref(), NOW).unwrap();
+        assert_eq!(
+            range,
+            TimeRange {
+                lower: Some(NOW - hour + 1),
+                upper: Some(1_699_920_000_000_000_000),
+            }
+        );
+        assert_eq!(expr_to_sql(&rest.unwrap()).unwrap(), "(\"host\" = 'a')");
+
+        let ms = DataType::Timestamp(TimeUnit::Millisecond, None);
+        assert_eq!(
+            time_literal(range.lower.unwrap(), &ms, true).unwrap(),
+            lit(ScalarValue::TimestampMillisecond(
+                Some(1_699_996_400_001),
+                None
+            ))
+        );
+        assert_eq!(
+            time_literal(range.upper.unwrap(), &ms, false).unwrap(),
+            lit(ScalarValue::TimestampMillisecond(
+                Some(1_699_920_000_000),
+                None
+            ))
+        );
+
+        let stmt = statement("SELECT x FROM m WHERE time > now() - 1h OR host = 'a'");
+        assert!(split_time_condition(stmt.condition.as_ref(), NOW).is_err());
+    }
+
+    #[test]
+    fn test_plan_aggregate() {
+        let plan = plan(
+            "SELECT mean(usage), mean(idle), percentile(usage, 95) * 2 FROM cpu WHERE time >= 0 AND region =~ /us-.*/ GROUP BY time(1m), host fill(none)",
+        )
+        .unwrap();
+        assert_eq!(
+            plan_columns(&plan.plan),
+            vec!["time", "host", "_v0", "_v1", "_v2"]
+        );
+        let text = plan.plan.display_indent().to_string();
+        assert!(text.contains("TIME_WINDOW("), "{text}");
+        assert!(text.contains("AVG(cpu.usage) AS _a0"), "{text}");
+        assert!(!text.contains("Join"), "{text}");
+        assert_eq!(plan.layout.columns, vec!["mean", "mean_1", "percentile"]);
+        assert_eq!(plan.layout.tags, vec!["host"]);
+        assert!(plan.layout.has_time);
+
+        let plan = plan("SELECT count(*) FROM cpu GROUP BY *").unwrap();
+        assert_eq!(
+            plan_columns(&plan.plan),
+            vec!["host", "region", "_v0", "_v1"]
+        );
+        assert_eq!(plan.layout.columns, vec!["count_idle", "count_usage"]);
+        assert!(!plan.layout.has_time);
+
+        let plan = plan("SELECT spread(usage) FROM cpu").unwrap();
+        let text = plan.plan.display_indent().to_string();
+        assert!(text.contains("_points > Int64(0)"), "{text}");
+        assert_eq!(plan_columns(&plan.plan), vec!["_v0"]);
+    }
+
+    #[test]
+    fn test_plan_fill() {
+        let query = |fill: &str| {
+            format!(
+                "SELECT max(usage), last(idle) FROM cpu WHERE time >= 0 AND time < 5m GROUP BY time(1m), host fill({fill})"
+            )
+        };
+        for fill in ["null", "previous", "linear", "1.5"] {
+            let plan = plan(&query(fill)).unwrap();
+            let text = plan.plan.display_indent().to_string();
+            assert!(text.contains("Left Join"), "{text}");
+            assert_eq!(plan_columns(&plan.plan), vec!["time", "host", "_v0", "_v1"]);
+        }
+
+        let text = plan(&query("previous"))
+            .unwrap()
+            .plan
+            .display_indent()
+            .to_string();
+        assert!(text.contains("COUNT(_v0) PARTITION BY [host]"), "{text}");
+
+        // The windows start from the first point if the lower bound of time is not specified.
+        let stmt = statement(
+            "SELECT max(usage) FROM cpu WHERE time < 5m GROUP BY time(1m) fill(previous)",
+        );
+        assert!(plan_first_time(&stmt, scan(), NOW).unwrap().is_some());
+        let plan = plan_select(&stmt, &schema(), scan(), NOW, Some(MINUTE + 1)).unwrap();
+        let text = plan.plan.display_indent().to_string();
+        assert!(text.contains("_first"), "{text}");
+
+        let stmt = statement("SELECT max(usage) FROM cpu WHERE time >= 0 GROUP BY time(1m)");
+        assert!(plan_first_time(&stmt, scan(), NOW).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_windows() {
+        let windows = Windows {
+            interval: MINUTE,
+            offset: 0,
+            lower: None,
+            first_time: Some(MINUTE + 1),
+            end: 3 * MINUTE,
+        };
+        assert_eq!(
+            windows.starts().unwrap(),
+            Some(vec![MINUTE, 2 * MINUTE, 3 * MINUTE])
+        );
+        let windows = Windows {
+            lower: Some(-1),
+            offset: 1,
+            ..windows
+        };
+        assert_eq!(windows.starts().unwrap().unwrap()[0], -MINUTE + 1);
+        let windows = Windows {
+            first_time: None,
+            lower: None,
+            ..windows
+        };
+        assert_eq!(windows.starts().unwrap(), None);
+        let windows = Windows {
+            interval: 1,
+            lower: Some(0),
+            end: MAX_FILL_WINDOWS,
+            ..windows
+        };
+        assert!(windows.starts().is_err());
+    }
+
+    #[test]
+    fn test_plan_raw() {
+        let plan =
+            plan("SELECT * FROM public..cpu WHERE host = 'a' ORDER BY time DESC LIMIT 10").unwrap();
+        assert_eq!(
+            plan_columns(&plan.plan),
+            vec!["time", "_v0", "_v1", "_v2", "_v3"]
+        );
+        let text = plan.plan.display_indent().to_string();
+        assert!(text.contains("Limit: skip=0, fetch=10"), "{text}");
+        assert!(
+            text.contains("cpu.idle IS NOT NULL OR cpu.usage IS NOT NULL"),
+            "{text}"
+        );
+        assert_eq!(plan.layout.columns, vec!["host", "idle", "region", "usage"]);
+        assert_eq!(plan.layout.limit, None);
+
+        let plan = plan("SELECT usage FROM cpu GROUP BY host LIMIT 1").unwrap();
+        assert_eq!(plan.layout.limit, Some(1));
+        assert!(!plan.plan.display_indent().to_string().contains("Limit"));
+    }
+
+    #[test]
+    fn test_plan_error() {
+        assert!(plan("SELECT usage, mean(idle) FROM cpu").is_err());
+        assert!(plan("SELECT usage FROM cpu GROUP BY time(1m)").is_err());
+        assert!(plan("SELECT mean(max(usage)) FROM cpu").is_err());
+        assert!(plan("SELECT derivative(usage) FROM cpu").is_err());
+        assert!(plan("SELECT mean(usage) FROM cpu fill(0)").is_err());
+        assert!(plan("SELECT usage FROM cpu WHERE time != now()").is_err());
+        assert!(plan("SELECT usage FROM cpu WHERE mean(idle) > 0").is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::BTreeMap;
+
+use chrono::{SecondsFormat, TimeZone, Utc};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::scalar::ScalarValue;
+use serde_json::{Number, Value};
+use spi::server::influxql::InfluxQLSeries;
+use spi::{QueryError, QueryResult};
+
+pub const TIME_COLUMN: &str = "time";
+
+/// Describes how the columns of the result of a planned statement are grouped into series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeriesLayout {
+    pub name: String,
+    /// The first column is the time if it's true, otherwise the time of all rows is `default_time`.
+    pub has_time: bool,
+    /// In nanoseconds.
+    pub default_time: i64,
+    /// The tags of the series, the columns after the time.
+    pub tags: Vec<String>,
+    /// The names of the value columns, the columns after the tags.
+    pub columns: Vec<String>,
+    pub order_desc: bool,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    pub slimit: Option<usize>,
+    pub soffset: Option<usize>,
+}
+
+impl SeriesLayout {
+    pub fn new(name: String, columns: Vec<String>) -> Self {
+        Self {
+            name,
+            has_time: false,
+            default_time: 0,
+            tags: vec![],
+            columns,
+            order_desc: false,
+            limit: None,
+            offset: None,
+            slimit: None,
+            soffset: None,
+        }
+    }
+}
+
+type Row = (i64, Vec<Value>);
+
+/// Groups the rows into series by the tags, the series are ordered by the tags.
+pub fn build_series(
+    layout: &SeriesLayout,
+    batches: &[RecordBatch],
+    epoch: Option<i64>,
+) -> QueryResult<Vec<InfluxQLSeries>> {
+    let tags_offset = usize::from(layout.has_time);
+    let values_offset = tags_offset + layout.tags.len();
+
+    let mut groups: BTreeMap<Vec<String>, Vec<Row>> = BTreeMap::new();
+    for batch in batches {
+        if batch.num_columns() != values_offset + layout.columns.len() {
+            return Err(QueryError::Internal {
+                reason: format!(
+                    "unexpected number of columns of influxql result: {}",
+                    batch.num_columns()
+                ),
+            });
+        }
+
+        for row in 0..batch.num_rows() {
+            let time = if layout.has_time {
+                let value = ScalarValue::try_from_array(batch.column(0), row)?;
+                match timestamp_nanos(&value) {
+                    Some(time) => time,
+                    None => continue,
+                }
+            } else {
+                layout.default_time
+            };
+            let tags = (tags_offset..values_offset)
+                .map(|i| {
+                    ScalarValue::try_from_array(batch.column(i), row).map(|v| match v {
+                        ScalarValue::Utf8(Some(s)) | ScalarValue::LargeUtf8(Some(s)) => s,
+                        _ => String::new(),
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let values = (values_offset..batch.num_columns())
+                .map(|i| ScalarValue::try_from_array(batch.column(i), row).map(json_value))
+                .collect::<Result<Vec<_>, _>>()?;
+            groups.entry(tags).or_default().push((time, values));
+        }
+    }
+
+    let columns = std::iter::once(TIME_COLUMN.to_string())
+        .chain(layout.columns.iter().cloned())
+        .collect::<Vec<_>>();
+
+    let series = groups
+        .into_iter()
+        .skip(layout.soffset.unwrap_or_default())
+        .take(layout.slimit.unwrap_or(usize::MAX))
+        .map(|(tags, rows)| {
+            let values = rows
+                .into_iter()
+                .skip(layout.offset.unwrap_or_default())
+                .take(layout.limit.unwrap_or(usize::MAX))
+                .map(|(time, values)| {
+                    std::iter::once(format_time(time, epoch))
+                        .chain(values)
+                        .collect()
+                })
+                .collect();
+            InfluxQLSeries {
+                name: layout.name.clone(),
+                tags: layout.tags.iter().cloned().zip(tags).collect(),
+                columns: columns.clone(),
+                values,
+            }
+        })
+        .collect();
+    Ok(series)
+}
+
+pub fn json_value(value: ScalarValue) -> Value {
+    if value.is_null() {
+        return Value::Null;
+    }
+    match value {
+        ScalarValue::Boolean(Some(v)) => Value::Bool(v),
+        ScalarValue::Int8(Some(v)) => Value::from(v),
+        ScalarValue::Int16(Some(v)) => Value::from(v),
+        ScalarValue::Int32(Some(v)) => Value::from(v),
+        ScalarValue::Int64(Some(v)) => Value::from(v),
+        ScalarValue::UInt8(Some(v)) => Value::from(v),
+        ScalarValue::UInt16(Some(v)) => Value::from(v),
+        ScalarValue::UInt32(Some(v)) => Value::from(v),
+        ScalarValue::UInt64(Some(v)) => Value::from(v),
+        ScalarValue::Float32(Some(v)) => Number::from_f64(v as f64)
+            .map(Value::Number)
+            .unwrap_or_default(),
+        ScalarValue::Float64(Some(v)) => Number::from_f64(v).map(Value::Number).unwrap_or_default(),
+        ScalarValue::Utf8(Some(v)) | ScalarValue::LargeUtf8(Some(v)) => Value::String(v),
+        ref v => match timestamp_nanos(v) {
+            Some(time) => format_time(time, None),
+            None => Value::String(v.to_string()),
+        },
+    }
+}
+
+pub fn timestamp_nanos(value: &ScalarValue) -> Option<i64> {
+    match value {
+        ScalarValue::TimestampSecond(Some(v), _) => v.checked_mul(1_000_000_000),
+        ScalarValue::TimestampMillisecond(Some(v), _) => v.checked_mul(1_000_000),
+        ScalarValue::TimestampMicrosecond(Some(v), _) => v.checked_mul(1_000),
+        ScalarValue::TimestampNanosecond(Some(v), _) => Some(*v),
+        _ => None,
+    }
+}
+
+/// Formats the timestamp as the unit of `epoch`, or a RFC3339 string if it's `None`.
+pub fn format_time(nanos: i64, epoch: Option<i64>) -> Value {
+    match epoch {
+        Some(unit) => Value::from(nanos.div_euclid(unit)),
+        None => Value::String(
+            Utc.timestamp_nanos(nanos)
+                .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Float64Array, StringArray, TimestampNanosecondArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use serde_json::json;
+
+    use super::*;
+
+    const MINUTE: i64 = 60_000_000_000;
+
+    fn batch(times: Vec<i64>, hosts: Vec<&str>, values: Vec<Option<f64>>) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("host", DataType::Utf8, true),
+            Field::new("_v0", DataType::Float64, true),
+        ]));
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(TimestampNanosecondArray::from(times)),
+                Arc::new(StringArray::from(hosts)),
+                Arc::new(Float64Array::from(values)),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn layout() -> SeriesLayout {
+        let mut layout = SeriesLayout::new("cpu".to_string(), vec!["mean".to_string()]);
+        layout.has_time = true;
+        layout.tags = vec!["host".to_string()];
+        layout
+    }
+
+    #[test]
+    fn test_group_series() {
+        let batch = batch(
+            vec![MINUTE, 0, 2 * MINUTE],
+            vec!["b", "a", "b"],
+            vec![Some(1.0), Some(2.5), Some(3.0)],
+        );
+        let series = build_series(&layout(), &[batch], Some(MINUTE)).unwrap();
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].tags.get("host").map(|s| s.as_str()), Some("a"));
+        assert_eq!(series[0].columns, vec!["time", "mean"]);
+        assert_eq!(series[0].values, vec![vec![json!(0), json!(2.5)]]);
+        assert_eq!(
+            series[1].values,
+            vec![vec![json!(1), json!(1.0)], vec![json!(2), json!(3.0)]]
+        );
+
+        let batch = self::batch(vec![1_500_000_000], vec!["a"], vec![None]);
+        let series = build_series(&layout(), &[batch], None).unwrap();
+        assert_eq!(
+            series[0].values,
+            vec![vec![json!("1970-01-01T00:00:01.500Z"), Value::Null]]
+        );
+    }
+
+    #[test]
+    fn test_series_limit() {
+        let batch = batch(
+            vec![0, MINUTE, 2 * MINUTE, 0, MINUTE],
+            vec!["a", "a", "a", "b", "b"],
+            vec![Some(1.0), None, Some(3.0), Some(4.0), Some(5.0)],
+        );
+        let mut layout = layout();
+        layout.limit = Some(1);
+        layout.offset = Some(1);
+        let series = build_series(&layout, &[batch.clone()], Some(MINUTE)).unwrap();
+        assert_eq!(series[0].values, vec![vec![json!(1), Value::Null]]);
+        assert_eq!(series[1].values, vec![vec![json!(1), json!(5.0)]]);
+
+        layout.slimit = Some(1);
+        layout.soffset = Some(1);
+        let series = build_series(&layout, &[batch], Some(MINUTE)).unwrap();
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].tags.get("host").map(|s| s.as_str()), Some("b"));
+    }
+}
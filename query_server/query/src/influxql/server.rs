@@ -0,0 +1,465 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use coordinator::service::CoordinatorRef;
+use datafusion::arrow::array::{Array, StringArray};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::logical_expr::LogicalPlan;
+use datafusion::scalar::ScalarValue;
+use meta::error::MetaError;
+use meta::model::MetaClientRef;
+use models::oid::Identifier;
+use models::schema::tskv_table_schema::{ColumnType, TskvTableSchemaRef};
+use models::utils::now_timestamp_nanos;
+use models::ValueType;
+use regex::Regex;
+use serde_json::Value;
+use snafu::ResultExt;
+use spi::query::execution::QueryStateMachineRef;
+use spi::query::logical_planner::{Plan, QueryPlan};
+use spi::server::dbms::DBMSRef;
+use spi::server::influxql::{
+    InfluxQLRequest, InfluxQLSeries, InfluxQLServer, InfluxQLStatementResult,
+};
+use spi::service::protocol::{Context, Query};
+use spi::{MetaSnafu, QueryError, QueryResult};
+use trace::span_ext::SpanExt;
+use trace::{debug, Span, SpanContext};
+
+use super::ast::{
+    Measurement, MeasurementSource, SelectStatement, ShowFieldKeys, ShowMeasurements, ShowTagKeys,
+    ShowTagValues, Statement, TagKeyFilter,
+};
+use super::planner::{
+    expr_to_sql, invalid, plan_first_time, plan_select, quote_identifier, split_time_condition,
+    table_name,
+};
+use super::{parse_epoch, parser, series};
+
+pub struct InfluxQLSqlServer {
+    db: DBMSRef,
+    coord: CoordinatorRef,
+}
+
+impl InfluxQLSqlServer {
+    pub fn new(db: DBMSRef, coord: CoordinatorRef) -> Self {
+        Self { db, coord }
+    }
+}
+
+#[async_trait]
+impl InfluxQLServer for InfluxQLSqlServer {
+    async fn query(
+        &self,
+        ctx: &Context,
+        req: InfluxQLRequest,
+        span_ctx: Option<&SpanContext>,
+    ) -> QueryResult<Vec<InfluxQLStatementResult>> {
+        let epoch = req.epoch.as_deref().map(parse_epoch).transpose()?;
+        let statements = parser::parse(&req.query)?;
+        debug!("Received influxql query: {:?}", statements);
+
+        let span = Span::from_context("process influxql query", span_ctx);
+        let meta = self
+            .coord
+            .meta_manager()
+            .tenant_meta(ctx.tenant())
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: ctx.tenant().to_string(),
+            })
+            .context(MetaSnafu)?;
+        let executor = StatementExecutor {
+            db: &self.db,
+            ctx,
+            meta,
+            span_ctx: span.context(),
+            epoch,
+            now: now_timestamp_nanos(),
+        };
+
+        let mut results = Vec::with_capacity(statements.len());
+        for (id, statement) in statements.iter().enumerate() {
+            match executor.execute(statement).await {
+                Ok(series) => results.push(InfluxQLStatementResult::new(id, series)),
+                Err(e) => {
+                    // Like InfluxDB, the statements after the failed one are not executed.
+                    span.error(e.to_string());
+                    results.push(InfluxQLStatementResult::error(id, e.to_string()));
+                    break;
+                }
+            }
+        }
+        Ok(results)
+    }
+}
+
+struct StatementExecutor<'a> {
+    db: &'a DBMSRef,
+    ctx: &'a Context,
+    meta: MetaClientRef,
+    span_ctx: Option<SpanContext>,
+    /// The nanoseconds of the unit of the returned timestamps.
+    epoch: Option<i64>,
+    /// In nanoseconds.
+    now: i64,
+}
+
+impl StatementExecutor<'_> {
+    async fn execute(&self, statement: &Statement) -> QueryResult<Vec<InfluxQLSeries>> {
+        match statement {
+            Statement::Select(stmt) => self.select(stmt).await,
+            Statement::ShowDatabases => self.show_databases(),
+            Statement::ShowMeasurements(stmt) => self.show_measurements(stmt),
+            Statement::ShowTagKeys(stmt) => self.show_tag_keys(stmt),
+            Statement::ShowTagValues(stmt) => self.show_tag_values(stmt).await,
+            Statement::ShowFieldKeys(stmt) => self.show_field_keys(stmt),
+        }
+    }
+
+    async fn select(&self, stmt: &SelectStatement) -> QueryResult<Vec<InfluxQLSeries>> {
+        let db = stmt.from.database.as_deref().unwrap_or(self.ctx.database());
+        // The series of a measurement that doesn't exist is empty.
+        let Some(schema) = self.table_schema(db, &stmt.from.name)? else {
+            return Ok(vec![]);
+        };
+
+        let (query_state_machine, scan) = self.plan_scan(&stmt.from).await?;
+        let first_time = match plan_first_time(stmt, scan.clone(), self.now)? {
+            Some(plan) => {
+                let (query_state_machine, _) = self.plan_scan(&stmt.from).await?;
+                let batches = self.execute_plan(plan, query_state_machine).await?;
+                match batches.iter().find(|b| b.num_rows() > 0) {
+                    Some(batch) => {
+                        series::timestamp_nanos(&ScalarValue::try_from_array(batch.column(0), 0)?)
+                    }
+                    None => None,
+                }
+            }
+            None => None,
+        };
+
+        let plan = plan_select(stmt, &schema, scan, self.now, first_time)?;
+        debug!("Plan influxql: {}", plan.plan.display_indent());
+        let batches = self.execute_plan(plan.plan, query_state_machine).await?;
+        series::build_series(&plan.layout, &batches, self.epoch)
+    }
+
+    fn show_databases(&self) -> QueryResult<Vec<InfluxQLSeries>> {
+        let tenant_id = *self.meta.tenant().id();
+        let mut databases = self
+            .meta
+            .list_databases()
+            .context(MetaSnafu)?
+            .into_iter()
+            .filter(|(db, info)| {
+                !info.is_hidden() && self.ctx.user().can_read_database(tenant_id, db)
+            })
+            .map(|(db, _)| vec![Value::String(db)])
+            .collect::<Vec<_>>();
+        databases.sort_by(|a, b| a[0].as_str().cmp(&b[0].as_str()));
+        Ok(vec![InfluxQLSeries {
+            name: "databases".to_string(),
+            tags: BTreeMap::new(),
+            columns: vec!["name".to_string()],
+            values: databases,
+        }])
+    }
+
+    fn show_measurements(&self, stmt: &ShowMeasurements) -> QueryResult<Vec<InfluxQLSeries>> {
+        let db = stmt.database.as_deref().unwrap_or(self.ctx.database());
+        let sources = stmt.with_measurement.iter().cloned().collect::<Vec<_>>();
+        let values = self
+            .measurements(db, &sources)?
+            .into_iter()
+            .map(|(_, name)| vec![Value::String(name)])
+            .skip(stmt.offset.unwrap_or_default())
+            .take(stmt.limit.unwrap_or(usize::MAX))
+            .collect::<Vec<_>>();
+        if values.is_empty() {
+            return Ok(vec![]);
+        }
+        Ok(vec![InfluxQLSeries {
+            name: "measurements".to_string(),
+            tags: BTreeMap::new(),
+            columns: vec!["name".to_string()],
+            values,
+        }])
+    }
+
+    fn show_tag_keys(&self, stmt: &ShowTagKeys) -> QueryResult<Vec<InfluxQLSeries>> {
+        let db = stmt.database.as_deref().unwrap_or(self.ctx.database());
+        self.show_columns(
+            db,
+            &stmt.from,
+            vec!["tagKey".to_string()],
+            stmt.limit,
+            stmt.offset,
+            |column_type| column_type.is_tag().then(Vec::new),
+        )
+    }
+
+    fn show_field_keys(&self, stmt: &ShowFieldKeys) -> QueryResult<Vec<InfluxQLSeries>> {
+        let db = stmt.database.as_deref().unwrap_or(self.ctx.database());
+        self.show_columns(
+            db,
+            &stmt.from,
+            vec!["fieldKey".to_string(), "fieldType".to_string()],
+            stmt.limit,
+            stmt.offset,
+            |column_type| {
+                let field_type = match column_type {
+                    ColumnType::Field(ValueType::Float) => "float",
+                    ColumnType::Field(ValueType::Integer) => "integer",
+                    ColumnType::Field(ValueType::Unsigned) => "unsigned",
+                    ColumnType::Field(ValueType::Boolean) => "boolean",
+                    ColumnType::Field(_) => "string",
+                    _ => return None,
+                };
+                Some(vec![Value::String(field_type.to_string())])
+            },
+        )
+    }
+
+    /// Returns a series of the columns of every measurement, the values of a column are
+    /// its name and the values returned by `f`, the column is skipped if `f` returns `None`.
+    fn show_columns(
+        &self,
+        db: &str,
+        sources: &[MeasurementSource],
+        columns: Vec<String>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+        f: impl Fn(&ColumnType) -> Option<Vec<Value>>,
+    ) -> QueryResult<Vec<InfluxQLSeries>> {
+        let mut result = vec![];
+        for (db, name) in self.measurements(db, sources)? {
+            let Some(schema) = self.table_schema(&db, &name)? else {
+                continue;
+            };
+            let mut values = schema
+                .columns()
+                .iter()
+                .filter_map(|c| {
+                    f(&c.column_type).map(|mut values| {
+                        values.insert(0, Value::String(c.name.clone()));
+                        values
+                    })
+                })
+                .collect::<Vec<_>>();
+            values.sort_by(|a, b| a[0].as_str().cmp(&b[0].as_str()));
+            let values = values
+                .into_iter()
+                .skip(offset.unwrap_or_default())
+                .take(limit.unwrap_or(usize::MAX))
+                .collect::<Vec<_>>();
+            if !values.is_empty() {
+                result.push(InfluxQLSeries {
+                    name,
+                    tags: BTreeMap::new(),
+                    columns: columns.clone(),
+                    values,
+                });
+            }
+        }
+        Ok(result)
+    }
+
+    async fn show_tag_values(&self, stmt: &ShowTagValues) -> QueryResult<Vec<InfluxQLSeries>> {
+        let db = stmt.database.as_deref().unwrap_or(self.ctx.database());
+        let (range, condition) = split_time_condition(stmt.condition.as_ref(), self.now)?;
+        if range != Default::default() {
+            return Err(invalid("SHOW TAG VALUES doesn't support time conditions"));
+        }
+        let selection = condition
+            .as_ref()
+            .map(|c| expr_to_sql(c).map(|sql| format!(" WHERE {sql}")))
+            .transpose()?
+            .unwrap_or_default();
+        let key_regex = match &stmt.key {
+            TagKeyFilter::Regex(regex) | TagKeyFilter::NotRegex(regex) => {
+                Some(Regex::new(regex).map_err(|e| invalid(format!("invalid regex: {e}")))?)
+            }
+            _ => None,
+        };
+
+        let mut result = vec![];
+        for (db, name) in self.measurements(db, &stmt.from)? {
+            let Some(schema) = self.table_schema(&db, &name)? else {
+                continue;
+            };
+            let keys = schema
+                .columns()
+                .iter()
+                .filter(|c| c.column_type.is_tag())
+                .map(|c| c.name.as_str())
+                .filter(|key| match (&stmt.key, &key_regex) {
+                    (TagKeyFilter::Eq(k), _) => k.as_str() == *key,
+                    (TagKeyFilter::NotEq(k), _) => k.as_str() != *key,
+                    (TagKeyFilter::In(keys), _) => keys.iter().any(|k| k.as_str() == *key),
+                    (TagKeyFilter::Regex(_), Some(regex)) => regex.is_match(key),
+                    (TagKeyFilter::NotRegex(_), Some(regex)) => !regex.is_match(key),
+                    _ => false,
+                })
+                .map(quote_identifier)
+                .collect::<Vec<_>>();
+            if keys.is_empty() {
+                continue;
+            }
+
+            let sql = format!(
+                "SHOW TAG VALUES ON {} FROM {} WITH KEY IN ({}){}",
+                quote_identifier(&db),
+                quote_identifier(&name),
+                keys.join(", "),
+                selection
+            );
+            debug!("Rewrite influxql to sql: {}", sql);
+            let batches = self.execute_sql(sql).await?;
+
+            let mut values = vec![];
+            for batch in batches.iter() {
+                let (keys, tag_values) = (string_column(batch, 0)?, string_column(batch, 1)?);
+                for i in 0..batch.num_rows() {
+                    if keys.is_valid(i) && tag_values.is_valid(i) {
+                        values.push(vec![
+                            Value::String(keys.value(i).to_string()),
+                            Value::String(tag_values.value(i).to_string()),
+                        ]);
+                    }
+                }
+            }
+            values.sort_by(|a, b| {
+                (a[0].as_str(), a[1].as_str()).cmp(&(b[0].as_str(), b[1].as_str()))
+            });
+            values.dedup();
+            let values = values
+                .into_iter()
+                .skip(stmt.offset.unwrap_or_default())
+                .take(stmt.limit.unwrap_or(usize::MAX))
+                .collect::<Vec<_>>();
+            if !values.is_empty() {
+                result.push(InfluxQLSeries {
+                    name,
+                    tags: BTreeMap::new(),
+                    columns: vec!["key".to_string(), "value".to_string()],
+                    values,
+                });
+            }
+        }
+        Ok(result)
+    }
+
+    /// Returns the sorted `(database, measurement)` that match the sources,
+    /// all the measurements of the database if there are no sources.
+    fn measurements(
+        &self,
+        db: &str,
+        sources: &[MeasurementSource],
+    ) -> QueryResult<Vec<(String, String)>> {
+        let tenant_id = *self.meta.tenant().id();
+        if !self.ctx.user().can_read_database(tenant_id, db) {
+            return Err(QueryError::InsufficientPrivileges {
+                privilege: format!("read on database {db}"),
+            });
+        }
+
+        let mut tables = self.meta.list_tables(db).context(MetaSnafu)?;
+        tables.sort();
+        if sources.is_empty() {
+            return Ok(tables.into_iter().map(|t| (db.to_string(), t)).collect());
+        }
+
+        let mut result = vec![];
+        for source in sources {
+            match source {
+                MeasurementSource::Name(measurement) => {
+                    let source_db = measurement.database.as_deref().unwrap_or(db);
+                    if source_db != db && !self.ctx.user().can_read_database(tenant_id, source_db) {
+                        return Err(QueryError::InsufficientPrivileges {
+                            privilege: format!("read on database {source_db}"),
+                        });
+                    }
+                    if self.table_schema(source_db, &measurement.name)?.is_some() {
+                        result.push((source_db.to_string(), measurement.name.clone()));
+                    }
+                }
+                MeasurementSource::Regex(regex) => {
+                    let regex =
+                        Regex::new(regex).map_err(|e| invalid(format!("invalid regex: {e}")))?;
+                    result.extend(
+                        tables
+                            .iter()
+                            .filter(|t| regex.is_match(t))
+                            .map(|t| (db.to_string(), t.clone())),
+                    );
+                }
+            }
+        }
+        result.sort();
+        result.dedup();
+        Ok(result)
+    }
+
+    fn table_schema(&self, db: &str, table: &str) -> QueryResult<Option<TskvTableSchemaRef>> {
+        self.meta
+            .get_tskv_table_schema(db, table)
+            .context(MetaSnafu)
+    }
+
+    /// Plans the scan of all the columns of the measurement by the sql planner,
+    /// which checks the privileges of the user.
+    async fn plan_scan(
+        &self,
+        measurement: &Measurement,
+    ) -> QueryResult<(QueryStateMachineRef, LogicalPlan)> {
+        let sql = format!("SELECT * FROM {}", table_name(measurement));
+        let query = Query::new(self.ctx.clone(), sql);
+        let query_state_machine = self
+            .db
+            .build_query_state_machine(query, self.span_ctx.as_ref())
+            .await?;
+        match self
+            .db
+            .build_logical_plan(query_state_machine.clone())
+            .await?
+        {
+            Some(Plan::Query(QueryPlan { df_plan, .. })) => Ok((query_state_machine, df_plan)),
+            _ => Err(QueryError::Internal {
+                reason: format!("unexpected plan of the scan of {}", measurement.name),
+            }),
+        }
+    }
+
+    async fn execute_plan(
+        &self,
+        plan: LogicalPlan,
+        query_state_machine: QueryStateMachineRef,
+    ) -> QueryResult<Vec<RecordBatch>> {
+        let plan = Plan::Query(QueryPlan {
+            df_plan: plan,
+            is_tag_scan: false,
+        });
+        let result = self
+            .db
+            .execute_logical_plan(plan, query_state_machine)
+            .await?;
+        result.result().chunk_result().await
+    }
+
+    async fn execute_sql(&self, sql: String) -> QueryResult<Vec<RecordBatch>> {
+        let query = Query::new(self.ctx.clone(), sql);
+        let result = self.db.execute(&query, self.span_ctx.as_ref()).await?;
+        result.result().chunk_result().await
+    }
+}
+
+fn string_column(batch: &RecordBatch, index: usize) -> QueryResult<&StringArray> {
+    batch
+        .columns()
+        .get(index)
+        .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+        .ok_or_else(|| QueryError::Internal {
+            reason: "Tag values must be strings".to_string(),
+        })
+}
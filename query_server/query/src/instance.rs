@@ -25,7 +25,11 @@ use trace::{debug, SpanContext};
 use tskv::kv_option::Options;
 
 use crate::auth::auth_control::{AccessControlImpl, AccessControlNoCheck};
+use crate::data_source::result_cache::ResultCache;
 use crate::data_source::split::SplitManager;
+use crate::data_source::stream::obj_store::factory::{
+    ObjectStoreStreamProviderFactory, OBJECT_STORE_STREAM_PROVIDER,
+};
 use crate::data_source::stream::tskv::factory::{TskvStreamProviderFactory, TSKV_STREAM_PROVIDER};
 use crate::dispatcher::manager::SimpleQueryDispatcherBuilder;
 use crate::dispatcher::persister::MetaQueryPersister;
@@ -117,6 +121,16 @@ where
         Ok(logical_plan)
     }
 
+    async fn build_substrait_plan(
+        &self,
+        substrait_plan: &[u8],
+        query_state_machine: QueryStateMachineRef,
+    ) -> QueryResult<Plan> {
+        self.query_dispatcher
+            .build_substrait_plan(substrait_plan, query_state_machine)
+            .await
+    }
+
     async fn execute_logical_plan(
         &self,
         logical_plan: Plan,
@@ -151,8 +165,8 @@ where
         )
     }
 
-    fn cancel(&self, query_id: &QueryId) {
-        self.query_dispatcher.cancel_query(query_id);
+    fn cancel(&self, query_id: &QueryId) -> bool {
+        self.query_dispatcher.cancel_query(query_id)
     }
 }
 
@@ -189,7 +203,15 @@ pub async fn make_cnosdbms(
         Some(register_session_udfs),
     ));
     let parser = Arc::new(DefaultParser::default());
-    let optimizer = Arc::new(CascadeOptimizerBuilder::default().build());
+    let mut optimizer_builder = CascadeOptimizerBuilder::default();
+    if options.query.result_cache_enabled {
+        let result_cache = Arc::new(ResultCache::new(
+            coord.clone(),
+            options.query.result_cache_max_size as usize,
+        ));
+        optimizer_builder = optimizer_builder.with_result_cache(result_cache);
+    }
+    let optimizer = Arc::new(optimizer_builder.build());
     // TODO wrap, and num_threads configurable
     let scheduler = Arc::new(LocalScheduler {});
 
@@ -204,12 +226,23 @@ pub async fn make_cnosdbms(
         TSKV_STREAM_PROVIDER,
         tskv_stream_provider_factory.clone(),
     )?;
+    // stream provider factory of the files in object stores
+    let object_store_stream_provider_factory =
+        Arc::new(ObjectStoreStreamProviderFactory::default());
+    stream_provider_manager.register_stream_provider_factory(
+        OBJECT_STORE_STREAM_PROVIDER,
+        object_store_stream_provider_factory.clone(),
+    )?;
 
     // init stream checker manager
     let mut stream_checker_manager = StreamCheckerManager::default();
     // stream table checker of tskv
     stream_checker_manager
         .register_stream_checker(TSKV_STREAM_PROVIDER, tskv_stream_provider_factory)?;
+    stream_checker_manager.register_stream_checker(
+        OBJECT_STORE_STREAM_PROVIDER,
+        object_store_stream_provider_factory,
+    )?;
 
     let query_persister = Arc::new(MetaQueryPersister::new(coord.meta_manager()));
     let query_tracker = Arc::new(QueryTracker::new(
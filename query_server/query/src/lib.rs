@@ -7,6 +7,7 @@ pub mod dispatcher;
 mod execution;
 pub mod extension;
 pub mod function;
+pub mod influxql;
 pub mod instance;
 pub mod metadata;
 pub mod prom;
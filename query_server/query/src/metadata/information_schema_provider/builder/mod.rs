@@ -6,4 +6,5 @@ pub mod members;
 pub mod queries;
 pub mod resource_status;
 pub mod roles;
+pub mod streams;
 pub mod tables;
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{StringBuilder, UInt64Builder};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::DataFusionError;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    pub static ref STREAMS_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new("stream_name", DataType::Utf8, false),
+        Field::new("database_name", DataType::Utf8, false),
+        Field::new("status", DataType::Utf8, false),
+        Field::new("node_id", DataType::UInt64, false),
+        Field::new("trigger", DataType::Utf8, true),
+        Field::new("watermark", DataType::Utf8, true),
+        Field::new("lag", DataType::Utf8, true),
+        Field::new("processed_count", DataType::UInt64, false),
+        Field::new("error_count", DataType::UInt64, false),
+        Field::new("error", DataType::Utf8, true),
+        Field::new("query_id", DataType::Utf8, false),
+        Field::new("query", DataType::Utf8, false),
+    ]));
+}
+
+/// Builds the `information_schema.STREAMS` table row by row
+#[derive(Default)]
+pub struct InformationSchemaStreamsBuilder {
+    stream_names: StringBuilder,
+    database_names: StringBuilder,
+    statuses: StringBuilder,
+    node_ids: UInt64Builder,
+    triggers: StringBuilder,
+    watermarks: StringBuilder,
+    lags: StringBuilder,
+    processed_counts: UInt64Builder,
+    error_counts: UInt64Builder,
+    errors: StringBuilder,
+    query_ids: StringBuilder,
+    queries: StringBuilder,
+}
+
+impl InformationSchemaStreamsBuilder {
+    #[allow(clippy::too_many_arguments)]
+    pub fn append_row(
+        &mut self,
+        stream_name: impl AsRef<str>,
+        database_name: impl AsRef<str>,
+        status: impl AsRef<str>,
+        node_id: u64,
+        trigger: Option<impl AsRef<str>>,
+        watermark: Option<impl AsRef<str>>,
+        lag: Option<impl AsRef<str>>,
+        processed_count: u64,
+        error_count: u64,
+        error: Option<impl AsRef<str>>,
+        query_id: impl AsRef<str>,
+        query: impl AsRef<str>,
+    ) {
+        // Note: append_value is actually infallable.
+        self.stream_names.append_value(stream_name.as_ref());
+        self.database_names.append_value(database_name.as_ref());
+        self.statuses.append_value(status.as_ref());
+        self.node_ids.append_value(node_id);
+        self.triggers.append_option(trigger);
+        self.watermarks.append_option(watermark);
+        self.lags.append_option(lag);
+        self.processed_counts.append_value(processed_count);
+        self.error_counts.append_value(error_count);
+        self.errors.append_option(error);
+        self.query_ids.append_value(query_id.as_ref());
+        self.queries.append_value(query.as_ref());
+    }
+}
+
+impl TryFrom<InformationSchemaStreamsBuilder> for RecordBatch {
+    type Error = DataFusionError;
+
+    fn try_from(value: InformationSchemaStreamsBuilder) -> Result<Self, Self::Error> {
+        let InformationSchemaStreamsBuilder {
+            mut stream_names,
+            mut database_names,
+            mut statuses,
+            mut node_ids,
+            mut triggers,
+            mut watermarks,
+            mut lags,
+            mut processed_counts,
+            mut error_counts,
+            mut errors,
+            mut query_ids,
+            mut queries,
+        } = value;
+
+        let batch = RecordBatch::try_new(
+            STREAMS_SCHEMA.clone(),
+            vec![
+                Arc::new(stream_names.finish()),
+                Arc::new(database_names.finish()),
+                Arc::new(statuses.finish()),
+                Arc::new(node_ids.finish()),
+                Arc::new(triggers.finish()),
+                Arc::new(watermarks.finish()),
+                Arc::new(lags.finish()),
+                Arc::new(processed_counts.finish()),
+                Arc::new(error_counts.finish()),
+                Arc::new(errors.finish()),
+                Arc::new(query_ids.finish()),
+                Arc::new(queries.finish()),
+            ],
+        )?;
+
+        Ok(batch)
+    }
+}
@@ -0,0 +1,132 @@
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::{DataFusionError, Result as DFResult};
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::logical_plan::AggWithGrouping;
+use datafusion::logical_expr::Expr;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::ExecutionPlan;
+use meta::model::MetaClientRef;
+use models::auth::user::User;
+use models::oid::Identifier;
+use models::utils::now_timestamp_nanos;
+use utils::duration::CnosDuration;
+
+use crate::dispatcher::query_tracker::QueryTracker;
+use crate::metadata::information_schema_provider::builder::streams::{
+    InformationSchemaStreamsBuilder, STREAMS_SCHEMA,
+};
+use crate::metadata::information_schema_provider::InformationSchemaTableFactory;
+
+pub const INFORMATION_SCHEMA_STREAMS: &str = "STREAMS";
+
+/// This view shows the streams created by `CREATE STREAM` in the databases
+/// that the current user can read, with their status recorded by the query nodes.
+pub struct StreamsFactory {}
+
+impl InformationSchemaTableFactory for StreamsFactory {
+    fn table_name(&self) -> &'static str {
+        INFORMATION_SCHEMA_STREAMS
+    }
+
+    fn create(
+        &self,
+        user: &User,
+        metadata: MetaClientRef,
+        _query_tracker: Arc<QueryTracker>,
+    ) -> Arc<dyn TableProvider> {
+        Arc::new(InformationStreamsTable::new(metadata, user.clone()))
+    }
+}
+
+pub struct InformationStreamsTable {
+    user: User,
+    metadata: MetaClientRef,
+}
+
+impl InformationStreamsTable {
+    pub fn new(metadata: MetaClientRef, user: User) -> Self {
+        Self { user, metadata }
+    }
+}
+
+#[async_trait]
+impl TableProvider for InformationStreamsTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        STREAMS_SCHEMA.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _agg_with_grouping: Option<&AggWithGrouping>,
+        _limit: Option<usize>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        let mut builder = InformationSchemaStreamsBuilder::default();
+
+        let tenant_id = *self.metadata.tenant().id();
+        let streams = self
+            .metadata
+            .streams()
+            .await
+            .map_err(|e| DataFusionError::Internal(format!("Failed to read streams: {}", e)))?;
+
+        let now = now_timestamp_nanos();
+        for stream in streams {
+            // Check if the current user has at least read permission on this db, skip if not
+            if !self
+                .user
+                .can_read_database(tenant_id, &stream.database_name)
+            {
+                continue;
+            }
+
+            let watermark = stream.watermark_ns.map(|ns| {
+                chrono::NaiveDateTime::from_timestamp_nanos(ns)
+                    .map(|datetime| datetime.and_utc().to_string())
+                    .unwrap_or_else(|| ns.to_string())
+            });
+            let lag = stream.lag_ns(now).map(|lag| {
+                CnosDuration::new_with_duration(Duration::from_nanos(lag as u64)).to_string()
+            });
+
+            builder.append_row(
+                &stream.name,
+                &stream.database_name,
+                stream.status.to_string(),
+                stream.node_id,
+                stream.trigger.as_ref(),
+                watermark,
+                lag,
+                stream.processed_count,
+                stream.error_count,
+                stream.error.as_ref(),
+                stream.query_id.to_string(),
+                &stream.query,
+            );
+        }
+        let rb: RecordBatch = builder.try_into()?;
+
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![rb]],
+            self.schema(),
+            projection.cloned(),
+        )?))
+    }
+}
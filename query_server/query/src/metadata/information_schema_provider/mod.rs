@@ -33,6 +33,7 @@ use self::factory::members::MembersFactory;
 use self::factory::queries::QueriesFactory;
 use self::factory::resource_status::InformationSchemaResourceStatusFactory;
 use self::factory::roles::RolesFactory;
+use self::factory::streams::StreamsFactory;
 use super::INFORMATION_SCHEMA;
 use crate::dispatcher::query_tracker::QueryTracker;
 use crate::metadata::information_schema_provider::factory::tables::TablesFactory;
@@ -62,6 +63,7 @@ impl InformationSchemaProvider {
         provider.register_table_factory(Box::new(MembersFactory {}));
         provider.register_table_factory(Box::new(QueriesFactory {}));
         provider.register_table_factory(Box::new(InformationSchemaResourceStatusFactory {}));
+        provider.register_table_factory(Box::new(StreamsFactory {}));
 
         provider
     }
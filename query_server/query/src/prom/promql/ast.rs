@@ -0,0 +1,240 @@
+use std::fmt::{Display, Formatter};
+
+use crate::prom::METRIC_NAME_LABEL;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    String(String),
+    VectorSelector(VectorSelector),
+    /// `selector[range]`, only valid as the argument of a range function.
+    MatrixSelector {
+        selector: VectorSelector,
+        /// In milliseconds.
+        range: i64,
+    },
+    Call {
+        func: String,
+        args: Vec<Expr>,
+    },
+    Aggregate {
+        op: AggregateOp,
+        /// The parameter of `topk`, `bottomk` and `quantile`.
+        param: Option<Box<Expr>>,
+        expr: Box<Expr>,
+        grouping: Grouping,
+    },
+    Binary {
+        op: BinaryOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+        /// The `bool` modifier of the comparison operators.
+        return_bool: bool,
+        matching: VectorMatching,
+    },
+    Neg(Box<Expr>),
+    Paren(Box<Expr>),
+}
+
+impl Expr {
+    /// Visits all the vector selectors of the expression, including those of matrix selectors,
+    /// together with the range of the matrix selector (0 for instant selectors).
+    pub fn walk_selectors<'a>(&'a self, f: &mut impl FnMut(&'a VectorSelector, i64)) {
+        match self {
+            Expr::Number(_) | Expr::String(_) => {}
+            Expr::VectorSelector(selector) => f(selector, 0),
+            Expr::MatrixSelector { selector, range } => f(selector, *range),
+            Expr::Call { args, .. } => args.iter().for_each(|arg| arg.walk_selectors(f)),
+            Expr::Aggregate { param, expr, .. } => {
+                if let Some(param) = param {
+                    param.walk_selectors(f);
+                }
+                expr.walk_selectors(f);
+            }
+            Expr::Binary { lhs, rhs, .. } => {
+                lhs.walk_selectors(f);
+                rhs.walk_selectors(f);
+            }
+            Expr::Neg(expr) | Expr::Paren(expr) => expr.walk_selectors(f),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VectorSelector {
+    /// Includes the `__name__` matcher if the metric name is given.
+    pub matchers: Vec<LabelMatcher>,
+    /// In milliseconds.
+    pub offset: i64,
+}
+
+impl VectorSelector {
+    pub fn metric_name(&self) -> Option<&str> {
+        self.matchers
+            .iter()
+            .find(|m| m.name == METRIC_NAME_LABEL && m.op == MatchOp::Equal)
+            .map(|m| m.value.as_str())
+    }
+}
+
+impl Display for VectorSelector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let matchers = self
+            .matchers
+            .iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{{{}}}", matchers.join(","))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LabelMatcher {
+    pub name: String,
+    pub op: MatchOp,
+    pub value: String,
+}
+
+impl Display for LabelMatcher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}{:?}", self.name, self.op, self.value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MatchOp {
+    Equal,
+    NotEqual,
+    Re,
+    NotRe,
+}
+
+impl Display for MatchOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let op = match self {
+            MatchOp::Equal => "=",
+            MatchOp::NotEqual => "!=",
+            MatchOp::Re => "=~",
+            MatchOp::NotRe => "!~",
+        };
+        write!(f, "{op}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateOp {
+    Sum,
+    Avg,
+    Min,
+    Max,
+    Count,
+    Group,
+    Stddev,
+    Stdvar,
+    Topk,
+    Bottomk,
+    Quantile,
+}
+
+impl AggregateOp {
+    pub fn from_name(name: &str) -> Option<Self> {
+        let op = match name.to_ascii_lowercase().as_str() {
+            "sum" => Self::Sum,
+            "avg" => Self::Avg,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            "count" => Self::Count,
+            "group" => Self::Group,
+            "stddev" => Self::Stddev,
+            "stdvar" => Self::Stdvar,
+            "topk" => Self::Topk,
+            "bottomk" => Self::Bottomk,
+            "quantile" => Self::Quantile,
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    pub fn has_param(&self) -> bool {
+        matches!(self, Self::Topk | Self::Bottomk | Self::Quantile)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Grouping {
+    By(Vec<String>),
+    Without(Vec<String>),
+}
+
+impl Default for Grouping {
+    fn default() -> Self {
+        Self::By(vec![])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Pow,
+    Eq,
+    Ne,
+    Gt,
+    Lt,
+    Ge,
+    Le,
+    And,
+    Or,
+    Unless,
+}
+
+impl BinaryOp {
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Self::Eq | Self::Ne | Self::Gt | Self::Lt | Self::Ge | Self::Le
+        )
+    }
+
+    pub fn is_set_operator(&self) -> bool {
+        matches!(self, Self::And | Self::Or | Self::Unless)
+    }
+
+    /// Higher binds tighter.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Self::Or => 1,
+            Self::And | Self::Unless => 2,
+            Self::Eq | Self::Ne | Self::Gt | Self::Lt | Self::Ge | Self::Le => 3,
+            Self::Add | Self::Sub => 4,
+            Self::Mul | Self::Div | Self::Mod => 5,
+            Self::Pow => 6,
+        }
+    }
+
+    pub fn is_right_associative(&self) -> bool {
+        matches!(self, Self::Pow)
+    }
+}
+
+/// How the series of the two sides of a binary operation between vectors are matched.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VectorMatching {
+    pub card: Cardinality,
+    /// `true` for `on(labels)`, `false` for `ignoring(labels)`.
+    pub on: bool,
+    pub labels: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Cardinality {
+    #[default]
+    OneToOne,
+    /// `group_left(include)`
+    ManyToOne(Vec<String>),
+    /// `group_right(include)`
+    OneToMany(Vec<String>),
+}
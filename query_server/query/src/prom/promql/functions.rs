@@ -0,0 +1,710 @@
+//! The aggregate functions of the PromQL plans, which compute a value from the samples of
+//! a series in the range of a step, or from the values of the series at a step.
+
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef};
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::common::cast::{as_float64_array, as_int64_array, as_list_array, as_string_array};
+use datafusion::common::Result as DFResult;
+use datafusion::logical_expr::{
+    AccumulatorFactoryFunction, AggregateUDF, ReturnTypeFunction, Signature, StateTypeFunction,
+    Volatility,
+};
+use datafusion::physical_plan::Accumulator;
+use datafusion::scalar::ScalarValue;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    /// In milliseconds.
+    pub timestamp: i64,
+    pub value: f64,
+}
+
+/// The functions of PromQL whose argument is a range vector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeFunction {
+    Rate,
+    Increase,
+    Delta,
+    Irate,
+    Idelta,
+    Changes,
+    Resets,
+    AvgOverTime,
+    SumOverTime,
+    CountOverTime,
+    MinOverTime,
+    MaxOverTime,
+    LastOverTime,
+    PresentOverTime,
+    StddevOverTime,
+    StdvarOverTime,
+    /// The only one with a parameter, `quantile_over_time(φ, range-vector)`.
+    QuantileOverTime,
+}
+
+impl RangeFunction {
+    pub fn from_name(name: &str) -> Option<Self> {
+        let func = match name {
+            "rate" => Self::Rate,
+            "increase" => Self::Increase,
+            "delta" => Self::Delta,
+            "irate" => Self::Irate,
+            "idelta" => Self::Idelta,
+            "changes" => Self::Changes,
+            "resets" => Self::Resets,
+            "avg_over_time" => Self::AvgOverTime,
+            "sum_over_time" => Self::SumOverTime,
+            "count_over_time" => Self::CountOverTime,
+            "min_over_time" => Self::MinOverTime,
+            "max_over_time" => Self::MaxOverTime,
+            "last_over_time" => Self::LastOverTime,
+            "present_over_time" => Self::PresentOverTime,
+            "stddev_over_time" => Self::StddevOverTime,
+            "stdvar_over_time" => Self::StdvarOverTime,
+            "quantile_over_time" => Self::QuantileOverTime,
+            _ => return None,
+        };
+        Some(func)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rate => "rate",
+            Self::Increase => "increase",
+            Self::Delta => "delta",
+            Self::Irate => "irate",
+            Self::Idelta => "idelta",
+            Self::Changes => "changes",
+            Self::Resets => "resets",
+            Self::AvgOverTime => "avg_over_time",
+            Self::SumOverTime => "sum_over_time",
+            Self::CountOverTime => "count_over_time",
+            Self::MinOverTime => "min_over_time",
+            Self::MaxOverTime => "max_over_time",
+            Self::LastOverTime => "last_over_time",
+            Self::PresentOverTime => "present_over_time",
+            Self::StddevOverTime => "stddev_over_time",
+            Self::StdvarOverTime => "stdvar_over_time",
+            Self::QuantileOverTime => "quantile_over_time",
+        }
+    }
+
+    pub fn has_param(&self) -> bool {
+        *self == Self::QuantileOverTime
+    }
+
+    /// Computes the value of the samples in the window `(start, end]`, which are sorted by time.
+    pub fn evaluate(&self, samples: &[Sample], start: i64, end: i64, param: f64) -> Option<f64> {
+        if samples.is_empty() {
+            return None;
+        }
+        let values = || samples.iter().map(|s| s.value);
+        let value = match self {
+            Self::Rate => return extrapolated_rate(samples, start, end, true, true),
+            Self::Increase => return extrapolated_rate(samples, start, end, true, false),
+            Self::Delta => return extrapolated_rate(samples, start, end, false, false),
+            Self::Irate => return instant_delta(samples, true),
+            Self::Idelta => return instant_delta(samples, false),
+            Self::Changes => samples
+                .windows(2)
+                .filter(|w| w[0].value != w[1].value)
+                .count() as f64,
+            Self::Resets => samples
+                .windows(2)
+                .filter(|w| w[1].value < w[0].value)
+                .count() as f64,
+            Self::AvgOverTime => values().sum::<f64>() / samples.len() as f64,
+            Self::SumOverTime => values().sum(),
+            Self::CountOverTime => samples.len() as f64,
+            Self::MinOverTime => values().fold(f64::NAN, f64::min),
+            Self::MaxOverTime => values().fold(f64::NAN, f64::max),
+            Self::LastOverTime => samples[samples.len() - 1].value,
+            Self::PresentOverTime => 1.0,
+            Self::StddevOverTime => variance(&values().collect::<Vec<_>>()).sqrt(),
+            Self::StdvarOverTime => variance(&values().collect::<Vec<_>>()),
+            Self::QuantileOverTime => quantile(param, values().collect()),
+        };
+        Some(value)
+    }
+}
+
+/// The aggregate function of a range function, the arguments are the time and the value
+/// of the samples, the end and the length of the window, and the parameter of the function.
+pub fn range_udaf(func: RangeFunction) -> AggregateUDF {
+    let return_type: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Float64)));
+    let state_type: StateTypeFunction = Arc::new(|_, _| {
+        Ok(Arc::new(vec![
+            list_type(DataType::Int64),
+            list_type(DataType::Float64),
+            DataType::Int64,
+            DataType::Int64,
+            DataType::Float64,
+        ]))
+    });
+    let accumulator: AccumulatorFactoryFunction =
+        Arc::new(move |_, _| Ok(Box::new(RangeAccumulator::new(func))));
+
+    let mut args = vec![
+        DataType::Int64,
+        DataType::Float64,
+        DataType::Int64,
+        DataType::Int64,
+    ];
+    if func.has_param() {
+        args.push(DataType::Float64);
+    }
+    AggregateUDF::new(
+        func.name(),
+        &Signature::exact(args, Volatility::Immutable),
+        &return_type,
+        &accumulator,
+        &state_type,
+    )
+}
+
+#[derive(Debug)]
+struct RangeAccumulator {
+    func: RangeFunction,
+    samples: Vec<Sample>,
+    end: Option<i64>,
+    range: i64,
+    param: f64,
+}
+
+impl RangeAccumulator {
+    fn new(func: RangeFunction) -> Self {
+        Self {
+            func,
+            samples: vec![],
+            end: None,
+            range: 0,
+            param: f64::NAN,
+        }
+    }
+
+    /// The end, the range and the parameter are the same for all the rows of a window.
+    fn update_window(&mut self, end: Option<i64>, range: Option<i64>, param: Option<f64>) {
+        if self.end.is_none() {
+            self.end = end;
+            self.range = range.unwrap_or_default();
+            self.param = param.unwrap_or(f64::NAN);
+        }
+    }
+}
+
+impl Accumulator for RangeAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DFResult<()> {
+        let times = as_int64_array(&values[0])?;
+        let samples = as_float64_array(&values[1])?;
+        let ends = as_int64_array(&values[2])?;
+        let ranges = as_int64_array(&values[3])?;
+        let params = values
+            .get(4)
+            .map(|p| as_float64_array(p.as_ref()))
+            .transpose()?;
+
+        for i in 0..times.len() {
+            if times.is_null(i) || samples.is_null(i) || ends.is_null(i) {
+                continue;
+            }
+            self.update_window(
+                Some(ends.value(i)),
+                ranges.is_valid(i).then_some(ranges.value(i)),
+                params.and_then(|p| p.is_valid(i).then_some(p.value(i))),
+            );
+            self.samples.push(Sample {
+                timestamp: times.value(i),
+                value: samples.value(i),
+            });
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> DFResult<ScalarValue> {
+        let Some(end) = self.end else {
+            return Ok(ScalarValue::Float64(None));
+        };
+        let mut samples = self.samples.clone();
+        samples.sort_by_key(|s| s.timestamp);
+        Ok(ScalarValue::Float64(self.func.evaluate(
+            &samples,
+            end - self.range,
+            end,
+            self.param,
+        )))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.samples.capacity() * std::mem::size_of::<Sample>()
+    }
+
+    fn state(&self) -> DFResult<Vec<ScalarValue>> {
+        let times = self
+            .samples
+            .iter()
+            .map(|s| ScalarValue::Int64(Some(s.timestamp)))
+            .collect();
+        let values = self
+            .samples
+            .iter()
+            .map(|s| ScalarValue::Float64(Some(s.value)))
+            .collect();
+        Ok(vec![
+            ScalarValue::new_list(Some(times), DataType::Int64),
+            ScalarValue::new_list(Some(values), DataType::Float64),
+            ScalarValue::Int64(self.end),
+            ScalarValue::Int64(Some(self.range)),
+            ScalarValue::Float64(Some(self.param)),
+        ])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DFResult<()> {
+        let times = as_list_array(&states[0])?;
+        let values = as_list_array(&states[1])?;
+        let ends = as_int64_array(&states[2])?;
+        let ranges = as_int64_array(&states[3])?;
+        let params = as_float64_array(&states[4])?;
+
+        for i in 0..times.len() {
+            if ends.is_null(i) || times.is_null(i) || values.is_null(i) {
+                continue;
+            }
+            self.update_window(
+                Some(ends.value(i)),
+                ranges.is_valid(i).then_some(ranges.value(i)),
+                params.is_valid(i).then_some(params.value(i)),
+            );
+            let times = times.value(i);
+            let times = as_int64_array(&times)?;
+            let values = values.value(i);
+            let values = as_float64_array(&values)?;
+            self.samples
+                .extend(
+                    times
+                        .values()
+                        .iter()
+                        .zip(values.values())
+                        .map(|(timestamp, value)| Sample {
+                            timestamp: *timestamp,
+                            value: *value,
+                        }),
+                );
+        }
+        Ok(())
+    }
+}
+
+/// The aggregate function of the `quantile` aggregation, the arguments are φ and the value.
+pub fn quantile_udaf() -> AggregateUDF {
+    let return_type: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Float64)));
+    let state_type: StateTypeFunction = Arc::new(|_, _| {
+        Ok(Arc::new(vec![
+            DataType::Float64,
+            list_type(DataType::Float64),
+        ]))
+    });
+    let accumulator: AccumulatorFactoryFunction =
+        Arc::new(|_, _| Ok(Box::<QuantileAccumulator>::default()));
+
+    AggregateUDF::new(
+        "quantile",
+        &Signature::exact(
+            vec![DataType::Float64, DataType::Float64],
+            Volatility::Immutable,
+        ),
+        &return_type,
+        &accumulator,
+        &state_type,
+    )
+}
+
+#[derive(Debug, Default)]
+struct QuantileAccumulator {
+    q: Option<f64>,
+    values: Vec<f64>,
+}
+
+impl Accumulator for QuantileAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DFResult<()> {
+        let qs = as_float64_array(&values[0])?;
+        let values = as_float64_array(&values[1])?;
+        for i in 0..values.len() {
+            if values.is_null(i) {
+                continue;
+            }
+            if self.q.is_none() && qs.is_valid(i) {
+                self.q = Some(qs.value(i));
+            }
+            self.values.push(values.value(i));
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> DFResult<ScalarValue> {
+        if self.values.is_empty() {
+            return Ok(ScalarValue::Float64(None));
+        }
+        let q = self.q.unwrap_or(f64::NAN);
+        Ok(ScalarValue::Float64(Some(quantile(q, self.values.clone()))))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.values.capacity() * std::mem::size_of::<f64>()
+    }
+
+    fn state(&self) -> DFResult<Vec<ScalarValue>> {
+        let values = self
+            .values
+            .iter()
+            .map(|v| ScalarValue::Float64(Some(*v)))
+            .collect();
+        Ok(vec![
+            ScalarValue::Float64(self.q),
+            ScalarValue::new_list(Some(values), DataType::Float64),
+        ])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DFResult<()> {
+        let qs = as_float64_array(&states[0])?;
+        let values = as_list_array(&states[1])?;
+        for i in 0..values.len() {
+            if self.q.is_none() && qs.is_valid(i) {
+                self.q = Some(qs.value(i));
+            }
+            if values.is_valid(i) {
+                let values = values.value(i);
+                self.values
+                    .extend(as_float64_array(&values)?.values().iter().copied());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The aggregate function of `histogram_quantile`, the arguments are φ, the upper bound and
+/// the cumulative count of the buckets.
+pub fn histogram_quantile_udaf() -> AggregateUDF {
+    let return_type: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Float64)));
+    let state_type: StateTypeFunction = Arc::new(|_, _| {
+        Ok(Arc::new(vec![
+            DataType::Float64,
+            list_type(DataType::Float64),
+            list_type(DataType::Float64),
+        ]))
+    });
+    let accumulator: AccumulatorFactoryFunction =
+        Arc::new(|_, _| Ok(Box::<HistogramQuantileAccumulator>::default()));
+
+    let args = vec![DataType::Float64, DataType::Utf8, DataType::Float64];
+    AggregateUDF::new(
+        "histogram_quantile",
+        &Signature::exact(args, Volatility::Immutable),
+        &return_type,
+        &accumulator,
+        &state_type,
+    )
+}
+
+#[derive(Debug, Default)]
+struct HistogramQuantileAccumulator {
+    q: Option<f64>,
+    /// `(upper bound, count)`
+    buckets: Vec<(f64, f64)>,
+}
+
+impl Accumulator for HistogramQuantileAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DFResult<()> {
+        let qs = as_float64_array(&values[0])?;
+        let bounds = as_string_array(&values[1])?;
+        let counts = as_float64_array(&values[2])?;
+        for i in 0..counts.len() {
+            if bounds.is_null(i) || counts.is_null(i) {
+                continue;
+            }
+            // The series with an invalid `le` label are ignored.
+            let Some(bound) = parse_bound(bounds.value(i)) else {
+                continue;
+            };
+            if self.q.is_none() && qs.is_valid(i) {
+                self.q = Some(qs.value(i));
+            }
+            self.buckets.push((bound, counts.value(i)));
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> DFResult<ScalarValue> {
+        if self.buckets.is_empty() {
+            return Ok(ScalarValue::Float64(None));
+        }
+        let q = self.q.unwrap_or(f64::NAN);
+        Ok(ScalarValue::Float64(Some(bucket_quantile(
+            q,
+            self.buckets.clone(),
+        ))))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.buckets.capacity() * std::mem::size_of::<(f64, f64)>()
+    }
+
+    fn state(&self) -> DFResult<Vec<ScalarValue>> {
+        let (bounds, counts): (Vec<_>, Vec<_>) = self
+            .buckets
+            .iter()
+            .map(|(bound, count)| {
+                (
+                    ScalarValue::Float64(Some(*bound)),
+                    ScalarValue::Float64(Some(*count)),
+                )
+            })
+            .unzip();
+        Ok(vec![
+            ScalarValue::Float64(self.q),
+            ScalarValue::new_list(Some(bounds), DataType::Float64),
+            ScalarValue::new_list(Some(counts), DataType::Float64),
+        ])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DFResult<()> {
+        let qs = as_float64_array(&states[0])?;
+        let bounds = as_list_array(&states[1])?;
+        let counts = as_list_array(&states[2])?;
+        for i in 0..bounds.len() {
+            if self.q.is_none() && qs.is_valid(i) {
+                self.q = Some(qs.value(i));
+            }
+            if bounds.is_null(i) || counts.is_null(i) {
+                continue;
+            }
+            let bounds = bounds.value(i);
+            let counts = counts.value(i);
+            self.buckets.extend(
+                as_float64_array(&bounds)?
+                    .values()
+                    .iter()
+                    .copied()
+                    .zip(as_float64_array(&counts)?.values().iter().copied()),
+            );
+        }
+        Ok(())
+    }
+}
+
+fn list_type(item: DataType) -> DataType {
+    DataType::List(Arc::new(Field::new("item", item, true)))
+}
+
+/// The rate of counters or delta of gauges in the window `(start, end]`,
+/// extrapolated to the edges of the window in the same way as prometheus.
+fn extrapolated_rate(
+    samples: &[Sample],
+    start: i64,
+    end: i64,
+    is_counter: bool,
+    is_rate: bool,
+) -> Option<f64> {
+    if samples.len() < 2 {
+        return None;
+    }
+    let first = samples[0];
+    let last = samples[samples.len() - 1];
+
+    let mut result = last.value - first.value;
+    if is_counter {
+        // counter resets
+        for w in samples.windows(2) {
+            if w[1].value < w[0].value {
+                result += w[0].value;
+            }
+        }
+    }
+
+    let mut duration_to_start = (first.timestamp - start) as f64 / 1000.0;
+    let duration_to_end = (end - last.timestamp) as f64 / 1000.0;
+    let sampled_interval = (last.timestamp - first.timestamp) as f64 / 1000.0;
+    let average_duration_between_samples = sampled_interval / (samples.len() - 1) as f64;
+
+    if is_counter && result > 0.0 && first.value >= 0.0 {
+        // counters can't be negative, don't extrapolate before zero
+        let duration_to_zero = sampled_interval * (first.value / result);
+        if duration_to_zero < duration_to_start {
+            duration_to_start = duration_to_zero;
+        }
+    }
+
+    let extrapolation_threshold = average_duration_between_samples * 1.1;
+    let mut extrapolate_to_interval = sampled_interval;
+    if duration_to_start < extrapolation_threshold {
+        extrapolate_to_interval += duration_to_start;
+    } else {
+        extrapolate_to_interval += average_duration_between_samples / 2.0;
+    }
+    if duration_to_end < extrapolation_threshold {
+        extrapolate_to_interval += duration_to_end;
+    } else {
+        extrapolate_to_interval += average_duration_between_samples / 2.0;
+    }
+
+    let mut factor = extrapolate_to_interval / sampled_interval;
+    if is_rate {
+        factor /= (end - start) as f64 / 1000.0;
+    }
+    Some(result * factor)
+}
+
+/// `irate` and `idelta`, using the last two samples.
+fn instant_delta(samples: &[Sample], is_rate: bool) -> Option<f64> {
+    if samples.len() < 2 {
+        return None;
+    }
+    let last = samples[samples.len() - 1];
+    let previous = samples[samples.len() - 2];
+
+    let mut result = last.value - previous.value;
+    if is_rate {
+        if last.value < previous.value {
+            // counter reset
+            result = last.value;
+        }
+        let interval = (last.timestamp - previous.timestamp) as f64 / 1000.0;
+        if interval == 0.0 {
+            return None;
+        }
+        result /= interval;
+    }
+    Some(result)
+}
+
+fn variance(values: &[f64]) -> f64 {
+    let count = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / count;
+    values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count
+}
+
+/// The φ-quantile of the values with linear interpolation.
+fn quantile(q: f64, mut values: Vec<f64>) -> f64 {
+    if values.is_empty() || q.is_nan() {
+        return f64::NAN;
+    }
+    if q < 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if q > 1.0 {
+        return f64::INFINITY;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+
+    let rank = q * (values.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = (lower + 1).min(values.len() - 1);
+    let weight = rank - rank.floor();
+    values[lower] * (1.0 - weight) + values[upper] * weight
+}
+
+fn parse_bound(le: &str) -> Option<f64> {
+    match le {
+        "+Inf" | "Inf" | "+inf" | "inf" => Some(f64::INFINITY),
+        le => le.parse().ok(),
+    }
+}
+
+/// The quantile of cumulative histogram buckets `(upper bound, count)`.
+fn bucket_quantile(q: f64, mut buckets: Vec<(f64, f64)>) -> f64 {
+    if q.is_nan() {
+        return f64::NAN;
+    }
+    if q < 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if q > 1.0 {
+        return f64::INFINITY;
+    }
+    buckets.sort_by(|a, b| a.0.total_cmp(&b.0));
+    if buckets.len() < 2 || buckets[buckets.len() - 1].0 != f64::INFINITY {
+        return f64::NAN;
+    }
+    // the counts may be not monotonic because of the precision of the samples
+    for i in 1..buckets.len() {
+        if buckets[i].1 < buckets[i - 1].1 {
+            buckets[i].1 = buckets[i - 1].1;
+        }
+    }
+
+    let observations = buckets[buckets.len() - 1].1;
+    if observations == 0.0 {
+        return f64::NAN;
+    }
+    let mut rank = q * observations;
+    let b = buckets.partition_point(|(_, count)| *count < rank);
+
+    if b == buckets.len() - 1 {
+        return buckets[buckets.len() - 2].0;
+    }
+    if b == 0 && buckets[0].0 <= 0.0 {
+        return buckets[0].0;
+    }
+
+    let mut bucket_start = 0.0;
+    let bucket_end = buckets[b].0;
+    let mut count = buckets[b].1;
+    if b > 0 {
+        bucket_start = buckets[b - 1].0;
+        count -= buckets[b - 1].1;
+        rank -= buckets[b - 1].1;
+    }
+    bucket_start + (bucket_end - bucket_start) * (rank / count)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{bucket_quantile, extrapolated_rate, instant_delta, quantile, Sample};
+
+    fn samples(points: &[(i64, f64)]) -> Vec<Sample> {
+        points
+            .iter()
+            .map(|(timestamp, value)| Sample {
+                timestamp: *timestamp,
+                value: *value,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rate_with_counter_reset() {
+        let s = samples(&[
+            (10_000, 10.0),
+            (20_000, 20.0),
+            (30_000, 5.0),
+            (40_000, 15.0),
+        ]);
+        // increase: 20 - 10 + 15 = 25 in 30s, extrapolated to 40s
+        let increase = extrapolated_rate(&s, 0, 40_000, true, false).unwrap();
+        assert!((increase - 25.0 * 40.0 / 30.0).abs() < 1e-9);
+        let rate = extrapolated_rate(&s, 0, 40_000, true, true).unwrap();
+        assert!((rate - increase / 40.0).abs() < 1e-9);
+        assert_eq!(instant_delta(&s, true), Some(1.0));
+        assert_eq!(instant_delta(&s, false), Some(10.0));
+        assert_eq!(extrapolated_rate(&s[..1], 0, 40_000, true, true), None);
+    }
+
+    #[test]
+    fn test_bucket_quantile() {
+        let buckets = vec![(0.5, 80.0), (0.1, 50.0), (f64::INFINITY, 100.0)];
+        assert!((bucket_quantile(0.5, buckets.clone()) - 0.1).abs() < 1e-9);
+        assert!((bucket_quantile(0.65, buckets.clone()) - 0.3).abs() < 1e-9);
+        // in the +Inf bucket
+        assert_eq!(bucket_quantile(0.99, buckets.clone()), 0.5);
+        assert!(bucket_quantile(0.5, vec![(0.1, 1.0), (0.5, 2.0)]).is_nan());
+    }
+
+    #[test]
+    fn test_quantile() {
+        assert_eq!(quantile(0.5, vec![3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(quantile(0.25, vec![1.0, 2.0, 3.0]), 1.5);
+        assert!(quantile(0.5, vec![]).is_nan());
+        assert_eq!(quantile(2.0, vec![1.0]), f64::INFINITY);
+    }
+}
@@ -0,0 +1,115 @@
+//! A PromQL engine on top of the tables written by the prometheus remote write api.
+//!
+//! The expressions are planned as logical plans of DataFusion on the tables of the metrics,
+//! the functions of PromQL over the samples of a range are aggregate functions of the plans.
+
+use chrono::DateTime;
+use spi::{QueryError, QueryResult};
+
+pub mod ast;
+mod functions;
+pub mod parser;
+pub mod planner;
+
+/// The same default as prometheus, an instant selector looks back 5 minutes for the latest sample.
+pub const DEFAULT_LOOKBACK_DELTA_MS: i64 = 5 * 60 * 1000;
+
+/// The bounds of the time range when it's not specified, in milliseconds.
+pub const MIN_TIME_MS: i64 = i64::MIN / 1_000_000;
+pub const MAX_TIME_MS: i64 = i64::MAX / 1_000_000;
+
+/// Max number of steps of a range query.
+pub const MAX_POINTS_PER_SERIES: i64 = 11000;
+
+/// Parses a PromQL duration like `1h30m` to milliseconds.
+pub fn parse_duration_ms(text: &str) -> Result<i64, String> {
+    const UNITS: [(&str, i64); 7] = [
+        ("ms", 1),
+        ("s", 1000),
+        ("m", 60 * 1000),
+        ("h", 60 * 60 * 1000),
+        ("d", 24 * 60 * 60 * 1000),
+        ("w", 7 * 24 * 60 * 60 * 1000),
+        ("y", 365 * 24 * 60 * 60 * 1000),
+    ];
+
+    let invalid = || format!("invalid duration '{text}'");
+    let mut rest = text;
+    let mut total = 0_i64;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let value = rest[..digits].parse::<i64>().map_err(|_| invalid())?;
+        rest = &rest[digits..];
+        let (unit, millis) = UNITS
+            .iter()
+            .filter(|(unit, _)| rest.starts_with(unit))
+            .max_by_key(|(unit, _)| unit.len())
+            .ok_or_else(invalid)?;
+        rest = &rest[unit.len()..];
+        total = value
+            .checked_mul(*millis)
+            .and_then(|v| v.checked_add(total))
+            .ok_or_else(invalid)?;
+    }
+    if text.is_empty() {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+/// Parses a timestamp parameter of the http api, either a rfc3339 time or unix seconds,
+/// returns milliseconds.
+pub fn parse_time_ms(text: &str) -> QueryResult<i64> {
+    if let Ok(seconds) = text.parse::<f64>() {
+        return Ok((seconds * 1000.0).round() as i64);
+    }
+    DateTime::parse_from_rfc3339(text)
+        .map(|t| t.timestamp_millis())
+        .map_err(|_| QueryError::InvalidPromQL {
+            err: format!("cannot parse '{text}' to a valid timestamp"),
+        })
+}
+
+/// Parses the step parameter of the http api, either a duration or seconds, returns milliseconds.
+pub fn parse_step_ms(text: &str) -> QueryResult<i64> {
+    let step = match text.parse::<f64>() {
+        Ok(seconds) => (seconds * 1000.0).round() as i64,
+        Err(_) => parse_duration_ms(text).map_err(|err| QueryError::InvalidPromQL { err })?,
+    };
+    if step <= 0 {
+        return Err(QueryError::InvalidPromQL {
+            err: "zero or negative query resolution step widths are not accepted".to_string(),
+        });
+    }
+    Ok(step)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_duration_ms, parse_step_ms, parse_time_ms};
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration_ms("5m"), Ok(300_000));
+        assert_eq!(parse_duration_ms("1h30m"), Ok(5_400_000));
+        assert_eq!(parse_duration_ms("100ms"), Ok(100));
+        assert!(parse_duration_ms("").is_err());
+        assert!(parse_duration_ms("5").is_err());
+        assert!(parse_duration_ms("5x").is_err());
+    }
+
+    #[test]
+    fn test_parse_time_and_step() {
+        assert_eq!(parse_time_ms("1435781451.781").unwrap(), 1_435_781_451_781);
+        assert_eq!(
+            parse_time_ms("2015-07-01T20:10:51.781Z").unwrap(),
+            1_435_781_451_781
+        );
+        assert!(parse_time_ms("yesterday").is_err());
+        assert_eq!(parse_step_ms("15").unwrap(), 15_000);
+        assert_eq!(parse_step_ms("1m").unwrap(), 60_000);
+        assert!(parse_step_ms("0").is_err());
+    }
+}
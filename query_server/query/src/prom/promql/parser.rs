@@ -0,0 +1,725 @@
+use regex::Regex;
+use spi::{QueryError, QueryResult};
+
+use super::ast::{
+    AggregateOp, BinaryOp, Cardinality, Expr, Grouping, LabelMatcher, MatchOp, VectorMatching,
+    VectorSelector,
+};
+use super::parse_duration_ms;
+use crate::prom::METRIC_NAME_LABEL;
+
+type Result<T> = std::result::Result<T, String>;
+
+/// Parses a PromQL expression.
+pub fn parse(input: &str) -> QueryResult<Expr> {
+    let tokens = Lexer::new(input)
+        .tokenize()
+        .map_err(|err| QueryError::InvalidPromQL { err })?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser
+        .parse_expr(0)
+        .and_then(|expr| match parser.peek() {
+            Token::Eof => Ok(expr),
+            token => Err(format!("unexpected {token:?}")),
+        })
+        .map_err(|err| QueryError::InvalidPromQL { err })?;
+    Ok(expr)
+}
+
+/// Parses a series selector of the series and labels apis, e.g. `up{job="prometheus"}`.
+pub fn parse_selector(input: &str) -> QueryResult<VectorSelector> {
+    match parse(input)? {
+        Expr::VectorSelector(selector) => Ok(selector),
+        _ => Err(QueryError::InvalidPromQL {
+            err: format!("'{input}' is not a series selector"),
+        }),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    /// In milliseconds.
+    Duration(i64),
+    Str(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Comma,
+    Eof,
+}
+
+struct Lexer<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    input: &'a str,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.char_indices().peekable(),
+            input,
+        }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<Token>> {
+        let mut tokens = vec![];
+        while let Some(&(start, c)) = self.chars.peek() {
+            let token = match c {
+                c if c.is_whitespace() => {
+                    self.chars.next();
+                    continue;
+                }
+                '#' => {
+                    // comment until the end of line
+                    while matches!(self.chars.peek(), Some(&(_, c)) if c != '\n') {
+                        self.chars.next();
+                    }
+                    continue;
+                }
+                '(' => self.single(Token::LParen),
+                ')' => self.single(Token::RParen),
+                '{' => self.single(Token::LBrace),
+                '}' => self.single(Token::RBrace),
+                '[' => self.single(Token::LBracket),
+                ']' => self.single(Token::RBracket),
+                ',' => self.single(Token::Comma),
+                '+' => self.single(Token::Op("+")),
+                '-' => self.single(Token::Op("-")),
+                '*' => self.single(Token::Op("*")),
+                '/' => self.single(Token::Op("/")),
+                '%' => self.single(Token::Op("%")),
+                '^' => self.single(Token::Op("^")),
+                '=' => {
+                    self.chars.next();
+                    if self.next_if('=') {
+                        Token::Op("==")
+                    } else if self.next_if('~') {
+                        Token::Op("=~")
+                    } else {
+                        Token::Op("=")
+                    }
+                }
+                '!' => {
+                    self.chars.next();
+                    if self.next_if('=') {
+                        Token::Op("!=")
+                    } else if self.next_if('~') {
+                        Token::Op("!~")
+                    } else {
+                        return Err(format!("unexpected character after '!' at {start}"));
+                    }
+                }
+                '<' | '>' => {
+                    self.chars.next();
+                    match (c, self.next_if('=')) {
+                        ('<', true) => Token::Op("<="),
+                        ('<', false) => Token::Op("<"),
+                        (_, true) => Token::Op(">="),
+                        (_, false) => Token::Op(">"),
+                    }
+                }
+                '"' | '\'' | '`' => self.string(c)?,
+                c if c.is_ascii_digit() || c == '.' => self.number_or_duration(start)?,
+                c if c.is_ascii_alphabetic() || c == '_' || c == ':' => {
+                    let end =
+                        self.take_while(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':');
+                    Token::Ident(self.input[start..end].to_string())
+                }
+                c => return Err(format!("unexpected character '{c}' at {start}")),
+            };
+            tokens.push(token);
+        }
+        tokens.push(Token::Eof);
+        Ok(tokens)
+    }
+
+    fn single(&mut self, token: Token) -> Token {
+        self.chars.next();
+        token
+    }
+
+    fn next_if(&mut self, expected: char) -> bool {
+        self.chars.next_if(|&(_, c)| c == expected).is_some()
+    }
+
+    /// Consumes chars while `f` holds, returns the end offset.
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> usize {
+        while self.chars.next_if(|&(_, c)| f(c)).is_some() {}
+        self.chars
+            .peek()
+            .map(|&(i, _)| i)
+            .unwrap_or(self.input.len())
+    }
+
+    fn string(&mut self, quote: char) -> Result<Token> {
+        self.chars.next();
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, c)) if c == quote => return Ok(Token::Str(value)),
+                Some((_, '\\')) if quote != '`' => match self.chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, 'r')) => value.push('\r'),
+                    Some((_, c)) => value.push(c),
+                    None => break,
+                },
+                Some((_, c)) => value.push(c),
+                None => break,
+            }
+        }
+        Err("unterminated quoted string".to_string())
+    }
+
+    fn number_or_duration(&mut self, start: usize) -> Result<Token> {
+        let mut end = self.take_while(|c| c.is_ascii_digit() || c == '.');
+        // exponent
+        if let Some(&(_, 'e' | 'E')) = self.chars.peek() {
+            let rest = &self.input[end + 1..];
+            if rest.starts_with(|c: char| c.is_ascii_digit() || c == '+' || c == '-') {
+                self.chars.next();
+                self.chars.next_if(|&(_, c)| c == '+' || c == '-');
+                end = self.take_while(|c| c.is_ascii_digit());
+            }
+        }
+        if matches!(self.chars.peek(), Some(&(_, c)) if c.is_ascii_alphabetic()) {
+            end = self.take_while(|c| c.is_ascii_alphanumeric());
+            let text = &self.input[start..end];
+            return parse_duration_ms(text).map(Token::Duration);
+        }
+        let text = &self.input[start..end];
+        text.parse::<f64>()
+            .map(Token::Number)
+            .map_err(|_| format!("invalid number '{text}'"))
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if self.pos < self.tokens.len() - 1 {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.next() {
+            token if token == expected => Ok(()),
+            token => Err(format!("expected {expected:?}, found {token:?}")),
+        }
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Ident(ident) if ident.eq_ignore_ascii_case(keyword))
+    }
+
+    fn consume_keyword(&mut self, keyword: &str) -> bool {
+        if self.peek_keyword(keyword) {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn peek_binary_op(&self) -> Option<BinaryOp> {
+        let op = match self.peek() {
+            Token::Op("+") => BinaryOp::Add,
+            Token::Op("-") => BinaryOp::Sub,
+            Token::Op("*") => BinaryOp::Mul,
+            Token::Op("/") => BinaryOp::Div,
+            Token::Op("%") => BinaryOp::Mod,
+            Token::Op("^") => BinaryOp::Pow,
+            Token::Op("==") => BinaryOp::Eq,
+            Token::Op("!=") => BinaryOp::Ne,
+            Token::Op(">") => BinaryOp::Gt,
+            Token::Op("<") => BinaryOp::Lt,
+            Token::Op(">=") => BinaryOp::Ge,
+            Token::Op("<=") => BinaryOp::Le,
+            Token::Ident(ident) => match ident.to_ascii_lowercase().as_str() {
+                "and" => BinaryOp::And,
+                "or" => BinaryOp::Or,
+                "unless" => BinaryOp::Unless,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    fn parse_expr(&mut self, min_precedence: u8) -> Result<Expr> {
+        let mut lhs = self.parse_unary()?;
+
+        while let Some(op) = self.peek_binary_op() {
+            if op.precedence() < min_precedence {
+                break;
+            }
+            self.next();
+
+            let return_bool = self.consume_keyword("bool");
+            if return_bool && !op.is_comparison() {
+                return Err("bool modifier can only be used on comparison operators".to_string());
+            }
+            let matching = self.parse_vector_matching()?;
+            if op.is_set_operator() && matching.card != Cardinality::OneToOne {
+                return Err(format!("no grouping allowed for \"{op:?}\" operation"));
+            }
+
+            let next_precedence = if op.is_right_associative() {
+                op.precedence()
+            } else {
+                op.precedence() + 1
+            };
+            let rhs = self.parse_expr(next_precedence)?;
+            lhs = Expr::Binary {
+                op,
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+                return_bool,
+                matching,
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    fn parse_vector_matching(&mut self) -> Result<VectorMatching> {
+        let mut matching = VectorMatching::default();
+        if self.consume_keyword("on") {
+            matching.on = true;
+            matching.labels = self.parse_label_list()?;
+        } else if self.consume_keyword("ignoring") {
+            matching.labels = self.parse_label_list()?;
+        } else {
+            return Ok(matching);
+        }
+
+        if self.consume_keyword("group_left") {
+            matching.card = Cardinality::ManyToOne(self.parse_optional_label_list()?);
+        } else if self.consume_keyword("group_right") {
+            matching.card = Cardinality::OneToMany(self.parse_optional_label_list()?);
+        }
+        Ok(matching)
+    }
+
+    fn parse_optional_label_list(&mut self) -> Result<Vec<String>> {
+        if self.peek() == &Token::LParen {
+            self.parse_label_list()
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    fn parse_label_list(&mut self) -> Result<Vec<String>> {
+        self.expect(Token::LParen)?;
+        let mut labels = vec![];
+        loop {
+            match self.next() {
+                Token::RParen => break,
+                Token::Ident(label) => {
+                    labels.push(label);
+                    match self.next() {
+                        Token::Comma => continue,
+                        Token::RParen => break,
+                        token => return Err(format!("unexpected {token:?} in grouping labels")),
+                    }
+                }
+                token => return Err(format!("unexpected {token:?} in grouping labels")),
+            }
+        }
+        Ok(labels)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        match self.peek() {
+            Token::Op("-") => {
+                self.next();
+                // `-a ^ b` is `-(a ^ b)`
+                let expr = self.parse_expr(BinaryOp::Pow.precedence())?;
+                Ok(match expr {
+                    Expr::Number(n) => Expr::Number(-n),
+                    expr => Expr::Neg(Box::new(expr)),
+                })
+            }
+            Token::Op("+") => {
+                self.next();
+                self.parse_expr(BinaryOp::Pow.precedence())
+            }
+            _ => {
+                let expr = self.parse_primary()?;
+                self.parse_postfix(expr)
+            }
+        }
+    }
+
+    fn parse_postfix(&mut self, mut expr: Expr) -> Result<Expr> {
+        loop {
+            if self.peek() == &Token::LBracket {
+                self.next();
+                let selector = match expr {
+                    Expr::VectorSelector(selector) => selector,
+                    _ => {
+                        return Err(
+                            "ranges are only allowed for vector selectors, subqueries are not supported"
+                                .to_string(),
+                        )
+                    }
+                };
+                let range = match self.next() {
+                    Token::Duration(range) if range > 0 => range,
+                    token => return Err(format!("expected a positive range, found {token:?}")),
+                };
+                match self.next() {
+                    Token::RBracket => {}
+                    _ => return Err("subqueries are not supported".to_string()),
+                }
+                expr = Expr::MatrixSelector { selector, range };
+            } else if self.consume_keyword("offset") {
+                let negative = self.peek() == &Token::Op("-");
+                if negative {
+                    self.next();
+                }
+                let offset = match self.next() {
+                    Token::Duration(offset) if negative => -offset,
+                    Token::Duration(offset) => offset,
+                    token => {
+                        return Err(format!("expected a duration after offset, found {token:?}"))
+                    }
+                };
+                match &mut expr {
+                    Expr::VectorSelector(selector) | Expr::MatrixSelector { selector, .. } => {
+                        selector.offset = offset;
+                    }
+                    _ => return Err("offset modifier must be preceded by a selector".to_string()),
+                }
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr> {
+        match self.next() {
+            Token::Number(n) => Ok(Expr::Number(n)),
+            Token::Str(s) => Ok(Expr::String(s)),
+            Token::LParen => {
+                let expr = self.parse_expr(0)?;
+                self.expect(Token::RParen)?;
+                Ok(Expr::Paren(Box::new(expr)))
+            }
+            Token::LBrace => {
+                let matchers = self.parse_matchers()?;
+                new_vector_selector(matchers)
+            }
+            Token::Ident(ident) => self.parse_ident(ident),
+            token => Err(format!("unexpected {token:?}")),
+        }
+    }
+
+    fn parse_ident(&mut self, ident: String) -> Result<Expr> {
+        if ident.eq_ignore_ascii_case("inf") {
+            return Ok(Expr::Number(f64::INFINITY));
+        }
+        if ident.eq_ignore_ascii_case("nan") {
+            return Ok(Expr::Number(f64::NAN));
+        }
+
+        if let Some(op) = AggregateOp::from_name(&ident) {
+            let is_aggregate = match self.peek() {
+                Token::LParen => true,
+                _ => self.peek_keyword("by") || self.peek_keyword("without"),
+            };
+            if is_aggregate {
+                return self.parse_aggregate(op);
+            }
+        }
+
+        if self.peek() == &Token::LParen {
+            self.next();
+            let args = self.parse_args()?;
+            return Ok(Expr::Call { func: ident, args });
+        }
+
+        let mut matchers = vec![LabelMatcher {
+            name: METRIC_NAME_LABEL.to_string(),
+            op: MatchOp::Equal,
+            value: ident,
+        }];
+        if self.peek() == &Token::LBrace {
+            self.next();
+            matchers.extend(self.parse_matchers()?);
+        }
+        new_vector_selector(matchers)
+    }
+
+    fn parse_aggregate(&mut self, op: AggregateOp) -> Result<Expr> {
+        let mut grouping = self.parse_grouping()?;
+        self.expect(Token::LParen)?;
+        let mut args = self.parse_args()?;
+        if grouping.is_none() {
+            grouping = self.parse_grouping()?;
+        }
+
+        let expected = if op.has_param() { 2 } else { 1 };
+        if args.len() != expected {
+            return Err(format!(
+                "wrong number of arguments for aggregate expression {op:?}, expected {expected}, got {}",
+                args.len()
+            ));
+        }
+        let param = op.has_param().then(|| Box::new(args.remove(0)));
+        let expr = Box::new(args.remove(0));
+
+        Ok(Expr::Aggregate {
+            op,
+            param,
+            expr,
+            grouping: grouping.unwrap_or_default(),
+        })
+    }
+
+    fn parse_grouping(&mut self) -> Result<Option<Grouping>> {
+        if self.consume_keyword("by") {
+            Ok(Some(Grouping::By(self.parse_label_list()?)))
+        } else if self.consume_keyword("without") {
+            Ok(Some(Grouping::Without(self.parse_label_list()?)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Parses the arguments after '(' till ')'.
+    fn parse_args(&mut self) -> Result<Vec<Expr>> {
+        let mut args = vec![];
+        if self.peek() == &Token::RParen {
+            self.next();
+            return Ok(args);
+        }
+        loop {
+            args.push(self.parse_expr(0)?);
+            match self.next() {
+                Token::Comma => continue,
+                Token::RParen => return Ok(args),
+                token => return Err(format!("unexpected {token:?} in argument list")),
+            }
+        }
+    }
+
+    /// Parses the label matchers after '{' till '}'.
+    fn parse_matchers(&mut self) -> Result<Vec<LabelMatcher>> {
+        let mut matchers = vec![];
+        loop {
+            let name = match self.next() {
+                Token::RBrace => break,
+                Token::Ident(name) => name,
+                token => return Err(format!("unexpected {token:?} in label matching")),
+            };
+            let op = match self.next() {
+                Token::Op("=") => MatchOp::Equal,
+                Token::Op("!=") => MatchOp::NotEqual,
+                Token::Op("=~") => MatchOp::Re,
+                Token::Op("!~") => MatchOp::NotRe,
+                token => return Err(format!("unexpected {token:?} in label matching")),
+            };
+            let value = match self.next() {
+                Token::Str(value) => value,
+                token => {
+                    return Err(format!(
+                        "expected a string in label matching, found {token:?}"
+                    ))
+                }
+            };
+            if matches!(op, MatchOp::Re | MatchOp::NotRe) {
+                Regex::new(&value).map_err(|e| format!("invalid regular expression: {e}"))?;
+            }
+            matchers.push(LabelMatcher { name, op, value });
+
+            match self.next() {
+                Token::Comma => continue,
+                Token::RBrace => break,
+                token => return Err(format!("unexpected {token:?} in label matching")),
+            }
+        }
+        Ok(matchers)
+    }
+}
+
+fn new_vector_selector(matchers: Vec<LabelMatcher>) -> Result<Expr> {
+    let names = matchers
+        .iter()
+        .filter(|m| m.name == METRIC_NAME_LABEL)
+        .count();
+    if names > 1 && matchers[0].name == METRIC_NAME_LABEL && matchers[0].op == MatchOp::Equal {
+        return Err("metric name must not be set twice".to_string());
+    }
+    if matchers.iter().all(matches_empty) {
+        return Err("vector selector must contain at least one non-empty matcher".to_string());
+    }
+    Ok(Expr::VectorSelector(VectorSelector {
+        matchers,
+        offset: 0,
+    }))
+}
+
+fn matches_empty(matcher: &LabelMatcher) -> bool {
+    match matcher.op {
+        MatchOp::Equal => matcher.value.is_empty(),
+        MatchOp::NotEqual => !matcher.value.is_empty(),
+        MatchOp::Re | MatchOp::NotRe => {
+            let matched = Regex::new(&format!("^(?:{})$", matcher.value))
+                .map(|re| re.is_match(""))
+                .unwrap_or(false);
+            matched == (matcher.op == MatchOp::Re)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse;
+    use crate::prom::promql::ast::{
+        AggregateOp, BinaryOp, Cardinality, Expr, Grouping, LabelMatcher, MatchOp, VectorMatching,
+        VectorSelector,
+    };
+
+    fn selector(name: &str, matchers: &[(&str, MatchOp, &str)]) -> VectorSelector {
+        let mut all = vec![LabelMatcher {
+            name: "__name__".to_string(),
+            op: MatchOp::Equal,
+            value: name.to_string(),
+        }];
+        all.extend(matchers.iter().map(|(n, op, v)| LabelMatcher {
+            name: n.to_string(),
+            op: *op,
+            value: v.to_string(),
+        }));
+        VectorSelector {
+            matchers: all,
+            offset: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_selector() {
+        let expr = parse(r#"http_requests_total{job="api", path=~"/v1/.*", code!~"5.."}"#).unwrap();
+        assert_eq!(
+            expr,
+            Expr::VectorSelector(selector(
+                "http_requests_total",
+                &[
+                    ("job", MatchOp::Equal, "api"),
+                    ("path", MatchOp::Re, "/v1/.*"),
+                    ("code", MatchOp::NotRe, "5.."),
+                ]
+            ))
+        );
+
+        let mut expected = selector("up", &[]);
+        expected.offset = 300_000;
+        assert_eq!(
+            parse("up[1h30m] offset 5m").unwrap(),
+            Expr::MatrixSelector {
+                selector: expected,
+                range: 5_400_000,
+            }
+        );
+
+        assert!(parse(r#"{job=""}"#).is_err());
+        assert!(parse("up[5m:1m]").is_err());
+        assert!(parse("rate(up[5m]").is_err());
+    }
+
+    #[test]
+    fn test_parse_aggregate_and_binary() {
+        let expr = parse("sum by (job) (rate(http_requests_total[5m])) / 2 ^ 3 ^ 2").unwrap();
+        let Expr::Binary { op, lhs, rhs, .. } = expr else {
+            panic!("expected binary expression");
+        };
+        assert_eq!(op, BinaryOp::Div);
+        assert_eq!(
+            *lhs,
+            Expr::Aggregate {
+                op: AggregateOp::Sum,
+                param: None,
+                expr: Box::new(Expr::Call {
+                    func: "rate".to_string(),
+                    args: vec![Expr::MatrixSelector {
+                        selector: selector("http_requests_total", &[]),
+                        range: 300_000,
+                    }],
+                }),
+                grouping: Grouping::By(vec!["job".to_string()]),
+            }
+        );
+        // `^` is right associative
+        let Expr::Binary { op, rhs, .. } = *rhs else {
+            panic!("expected binary expression");
+        };
+        assert_eq!(op, BinaryOp::Pow);
+        assert!(matches!(
+            *rhs,
+            Expr::Binary {
+                op: BinaryOp::Pow,
+                ..
+            }
+        ));
+
+        let expr = parse("topk(3, up) without (instance)").unwrap();
+        assert!(matches!(
+            expr,
+            Expr::Aggregate {
+                op: AggregateOp::Topk,
+                param: Some(_),
+                grouping: Grouping::Without(_),
+                ..
+            }
+        ));
+
+        let expr = parse("a > bool on (job) group_left (env) b or c").unwrap();
+        let Expr::Binary { op, lhs, .. } = expr else {
+            panic!("expected binary expression");
+        };
+        assert_eq!(op, BinaryOp::Or);
+        assert_eq!(
+            *lhs,
+            Expr::Binary {
+                op: BinaryOp::Gt,
+                lhs: Box::new(Expr::VectorSelector(selector("a", &[]))),
+                rhs: Box::new(Expr::VectorSelector(selector("b", &[]))),
+                return_bool: true,
+                matching: VectorMatching {
+                    card: Cardinality::ManyToOne(vec!["env".to_string()]),
+                    on: true,
+                    labels: vec!["job".to_string()],
+                },
+            }
+        );
+
+        assert_eq!(
+            parse("-2 ^ 2").unwrap(),
+            Expr::Neg(Box::new(Expr::Binary {
+                op: BinaryOp::Pow,
+                lhs: Box::new(Expr::Number(2.0)),
+                rhs: Box::new(Expr::Number(2.0)),
+                return_bool: false,
+                matching: VectorMatching::default(),
+            }))
+        );
+    }
+}
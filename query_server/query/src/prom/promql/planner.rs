@@ -0,0 +1,1949 @@
+//! Plans the PromQL expressions as logical plans of DataFusion.
+//!
+//! An instant vector is planned as the rows of its series at every step, the columns are the
+//! labels, `__step` and `__value`, an absent label is null. A scalar is planned as the rows of
+//! every step with the columns `__step` and `__value`.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, Float64Array, Int64Array, StringArray};
+use datafusion::arrow::datatypes::{DataType, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::Column;
+use datafusion::logical_expr::expr::{self, ScalarFunction};
+use datafusion::logical_expr::{
+    aggregate_function, binary_expr, window_function, BuiltinScalarFunction, Expr as DFExpr,
+    JoinType, LogicalPlan, LogicalPlanBuilder, Operator,
+};
+use datafusion::prelude::{and, avg, cast, coalesce, count, lit, max, min, sum, when};
+use datafusion::scalar::ScalarValue;
+use models::schema::TIME_FIELD_NAME;
+use regex::Regex;
+use spi::server::prom::{
+    PromLabels, PromPoint, PromQueryData, PromQueryRequest, PromSample, PromSeries,
+};
+use spi::{QueryError, QueryResult};
+
+use super::ast::{
+    AggregateOp, BinaryOp, Cardinality, Expr, Grouping, LabelMatcher, MatchOp, VectorMatching,
+    VectorSelector,
+};
+use super::functions::{histogram_quantile_udaf, quantile_udaf, range_udaf, RangeFunction};
+use super::{DEFAULT_LOOKBACK_DELTA_MS, MAX_POINTS_PER_SERIES};
+use crate::extension::analyse::transform_time_window::window_func_expr;
+use crate::extension::expr::expr_fn::{
+    divide, ge, gt, is_not_null, le, lt, minus, modulo, multiply, plus,
+};
+use crate::prom::{METRIC_NAME_LABEL, METRIC_SAMPLE_COLUMN_NAME};
+
+/// The step of the rows, in milliseconds.
+pub const STEP_COLUMN: &str = "__step";
+/// The time of the samples, in milliseconds.
+pub const TIME_COLUMN: &str = "__time";
+pub const VALUE_COLUMN: &str = "__value";
+/// The index of a step in the steps whose window contains a sample.
+const OFFSET_COLUMN: &str = "__offset";
+/// The rank of a series in the group of `topk` and `bottomk`.
+const RANK_COLUMN: &str = "__rank";
+/// The parameter of functions and aggregations when it's joined to the rows of the steps.
+const PARAM_COLUMN: &str = "__param";
+/// The scalar of a binary operation when it's joined to the rows of the steps.
+const SCALAR_COLUMN: &str = "__scalar";
+/// The aliases of the two sides of a binary operation between vectors.
+const LHS_ALIAS: &str = "lhs";
+const RHS_ALIAS: &str = "rhs";
+
+/// A table written by the remote write api, the table name is the metric name
+/// and the tags are the labels.
+#[derive(Debug, Clone)]
+pub struct MetricTable {
+    pub name: String,
+    pub tags: Vec<String>,
+    /// The scan of all the columns of the table.
+    pub scan: LogicalPlan,
+}
+
+/// The tables of the series selected by the label matchers of every selector.
+pub type SelectedTables = HashMap<Vec<LabelMatcher>, Vec<MetricTable>>;
+
+/// The logical plan of a PromQL query, and how its result is returned.
+#[derive(Debug, Clone)]
+pub enum PromPlan {
+    /// A constant which doesn't need to be executed.
+    Number(f64),
+    String(String),
+    /// The columns are `__step` and `__value`.
+    Scalar(LogicalPlan),
+    /// The columns are the labels, `__step` and `__value`.
+    Vector {
+        plan: LogicalPlan,
+        labels: Vec<String>,
+    },
+    /// The raw samples of a range vector, the columns are the labels, `__time` and `__value`.
+    Samples {
+        plan: LogicalPlan,
+        labels: Vec<String>,
+    },
+}
+
+impl PromPlan {
+    pub fn logical_plan(&self) -> Option<&LogicalPlan> {
+        match self {
+            Self::Number(_) | Self::String(_) => None,
+            Self::Scalar(plan) | Self::Vector { plan, .. } | Self::Samples { plan, .. } => {
+                Some(plan)
+            }
+        }
+    }
+
+    /// Converts the result of the logical plan to the data of the query api.
+    pub fn query_data(
+        &self,
+        batches: &[RecordBatch],
+        req: &PromQueryRequest,
+    ) -> QueryResult<PromQueryData> {
+        let instant = req.step.is_none();
+        let data = match self {
+            Self::Number(value) if instant => {
+                PromQueryData::Scalar(PromPoint::new(req.end, *value))
+            }
+            Self::Number(value) => PromQueryData::Matrix(vec![PromSeries {
+                metric: PromLabels::new(),
+                values: Steps::try_new(req)?
+                    .iter()
+                    .map(|t| PromPoint::new(t, *value))
+                    .collect(),
+            }]),
+            Self::String(value) => {
+                PromQueryData::String(PromPoint(req.end as f64 / 1000.0, value.clone()))
+            }
+            Self::Scalar(_) => {
+                let points = read_points(batches, &[], STEP_COLUMN)?;
+                if instant {
+                    let value = points.first().map(|(_, _, v)| *v).unwrap_or(f64::NAN);
+                    PromQueryData::Scalar(PromPoint::new(req.end, value))
+                } else {
+                    PromQueryData::Matrix(into_series(points))
+                }
+            }
+            Self::Vector { labels, .. } => {
+                let points = read_points(batches, labels, STEP_COLUMN)?;
+                if instant {
+                    // The order of the rows is kept for `sort()`.
+                    let mut seen = HashSet::with_capacity(points.len());
+                    let samples = points
+                        .into_iter()
+                        .filter(|(labels, _, _)| seen.insert(labels.clone()))
+                        .map(|(metric, time, value)| PromSample {
+                            metric,
+                            value: PromPoint::new(time, value),
+                        })
+                        .collect();
+                    PromQueryData::Vector(samples)
+                } else {
+                    PromQueryData::Matrix(into_series(points))
+                }
+            }
+            Self::Samples { labels, .. } => {
+                PromQueryData::Matrix(into_series(read_points(batches, labels, TIME_COLUMN)?))
+            }
+        };
+        Ok(data)
+    }
+}
+
+/// Plans a PromQL query on the tables selected by its selectors.
+pub fn plan_query(
+    expr: &Expr,
+    tables: &SelectedTables,
+    req: &PromQueryRequest,
+) -> QueryResult<PromPlan> {
+    let planner = Planner {
+        tables,
+        steps: Steps::try_new(req)?,
+        lookback_delta: DEFAULT_LOOKBACK_DELTA_MS,
+    };
+
+    // An instant query of a range vector returns the raw samples.
+    if let (None, Expr::MatrixSelector { selector, range }) = (req.step, expr) {
+        let end = req.end - selector.offset;
+        let samples = plan_samples(
+            planner.selected(selector),
+            &selector.matchers,
+            end - range,
+            end,
+        )?;
+        let plan = samples
+            .plan
+            .sort(sort_exprs(&samples.labels, TIME_COLUMN))?
+            .build()?;
+        return Ok(PromPlan::Samples {
+            plan,
+            labels: samples.labels,
+        });
+    }
+
+    let plan = match planner.plan(expr)? {
+        Value::Number(value) => PromPlan::Number(value),
+        Value::String(value) => PromPlan::String(value),
+        Value::Scalar(plan) => PromPlan::Scalar(
+            plan.sort(vec![column(STEP_COLUMN).sort(true, false)])?
+                .build()?,
+        ),
+        Value::Vector(vector) => {
+            let plan = if is_sort(expr) {
+                vector.plan
+            } else {
+                vector.plan.sort(sort_exprs(&vector.labels, STEP_COLUMN))?
+            };
+            PromPlan::Vector {
+                plan: plan.build()?,
+                labels: vector.labels,
+            }
+        }
+    };
+    Ok(plan)
+}
+
+/// Plans the label sets of the series selected by the matchers, which have samples
+/// in `[start, end]`, returns the plan and its label columns.
+pub fn plan_series(
+    tables: &SelectedTables,
+    matchers: &[LabelMatcher],
+    start: i64,
+    end: i64,
+) -> QueryResult<(LogicalPlan, Vec<String>)> {
+    let tables = tables.get(matchers).map(Vec::as_slice).unwrap_or_default();
+    let samples = plan_samples(tables, matchers, start.saturating_sub(1), end)?;
+    let group_by = samples.labels.iter().map(|l| column(l)).collect::<Vec<_>>();
+    let plan = samples
+        .plan
+        .aggregate(group_by, Vec::<DFExpr>::new())?
+        .build()?;
+    Ok((plan, samples.labels))
+}
+
+/// Plans the values of the tag of a table, which have samples in `[start, end]`.
+pub fn plan_label_values(
+    table: &MetricTable,
+    label: &str,
+    start: i64,
+    end: i64,
+) -> QueryResult<LogicalPlan> {
+    let plan = LogicalPlanBuilder::from(table.scan.clone())
+        .filter(time_filter(&table.scan, start.saturating_sub(1), end)?)?
+        .aggregate(vec![column(label)], Vec::<DFExpr>::new())?
+        .build()?;
+    Ok(plan)
+}
+
+/// Reads the label sets of the result of [`plan_series`] or [`plan_label_values`].
+pub fn read_label_sets(batches: &[RecordBatch], labels: &[String]) -> QueryResult<Vec<PromLabels>> {
+    let mut result = vec![];
+    for batch in batches {
+        let columns = label_columns(batch, labels)?;
+        result.extend((0..batch.num_rows()).map(|row| row_labels(&columns, row)));
+    }
+    Ok(result)
+}
+
+pub fn label_matches(matcher: &LabelMatcher, value: &str) -> QueryResult<bool> {
+    match matcher.op {
+        MatchOp::Equal => Ok(matcher.value == value),
+        MatchOp::NotEqual => Ok(matcher.value != value),
+        MatchOp::Re => Ok(anchored_regex(&matcher.value)?.is_match(value)),
+        MatchOp::NotRe => Ok(!anchored_regex(&matcher.value)?.is_match(value)),
+    }
+}
+
+fn execution_error(err: impl Into<String>) -> QueryError {
+    QueryError::PromQLExecution { err: err.into() }
+}
+
+/// The steps of a query, in milliseconds.
+#[derive(Debug, Clone, Copy)]
+struct Steps {
+    start: i64,
+    end: i64,
+    interval: i64,
+}
+
+impl Steps {
+    fn try_new(req: &PromQueryRequest) -> QueryResult<Self> {
+        let Some(interval) = req.step else {
+            return Ok(Self {
+                start: req.end,
+                end: req.end,
+                interval: 1,
+            });
+        };
+        if interval <= 0 {
+            return Err(QueryError::InvalidPromQL {
+                err: "zero or negative query resolution step widths are not accepted".to_string(),
+            });
+        }
+        if req.end < req.start {
+            return Err(QueryError::InvalidPromQL {
+                err: "end timestamp must not be before start time".to_string(),
+            });
+        }
+        if (req.end - req.start) / interval >= MAX_POINTS_PER_SERIES {
+            return Err(QueryError::InvalidPromQL {
+                err: format!(
+                    "exceeded maximum resolution of {} points per timeseries, try decreasing the query resolution",
+                    MAX_POINTS_PER_SERIES
+                ),
+            });
+        }
+        Ok(Self {
+            start: req.start,
+            end: req.end,
+            interval,
+        })
+    }
+
+    fn count(&self) -> i64 {
+        (self.end - self.start) / self.interval + 1
+    }
+
+    fn iter(&self) -> impl Iterator<Item = i64> {
+        let Self {
+            start, interval, ..
+        } = *self;
+        (0..self.count()).map(move |i| start + i * interval)
+    }
+
+    /// The rows of the steps, the column is `__step`.
+    fn plan(&self) -> QueryResult<LogicalPlanBuilder> {
+        let steps = self.iter().map(|t| vec![lit(t)]).collect();
+        Ok(LogicalPlanBuilder::values(steps)?
+            .project(vec![column("column1").alias(STEP_COLUMN)])?)
+    }
+}
+
+/// The plan of a PromQL expression.
+enum Value {
+    Number(f64),
+    String(String),
+    Scalar(LogicalPlanBuilder),
+    Vector(Vector),
+}
+
+struct Vector {
+    plan: LogicalPlanBuilder,
+    /// The label columns of the plan, in order.
+    labels: Vec<String>,
+}
+
+struct Planner<'a> {
+    tables: &'a SelectedTables,
+    steps: Steps,
+    lookback_delta: i64,
+}
+
+impl Planner<'_> {
+    fn selected(&self, selector: &VectorSelector) -> &[MetricTable] {
+        self.tables
+            .get(&selector.matchers)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn plan(&self, expr: &Expr) -> QueryResult<Value> {
+        match expr {
+            Expr::Number(value) => Ok(Value::Number(*value)),
+            Expr::String(value) => Ok(Value::String(value.clone())),
+            Expr::Paren(expr) => self.plan(expr),
+            Expr::Neg(expr) => match self.plan(expr)? {
+                Value::Number(value) => Ok(Value::Number(-value)),
+                Value::Scalar(plan) => Ok(Value::Scalar(plan.project(vec![
+                    column(STEP_COLUMN),
+                    negative(column(VALUE_COLUMN)).alias(VALUE_COLUMN),
+                ])?)),
+                Value::Vector(vector) => Ok(Value::Vector(map_vector(
+                    vector,
+                    negative(column(VALUE_COLUMN)),
+                )?)),
+                Value::String(_) => Err(execution_error("unary minus on a string")),
+            },
+            Expr::VectorSelector(selector) => {
+                // The latest sample in the lookback window of every step.
+                let windows = self.plan_windows(selector, self.lookback_delta)?;
+                let vector = plan_range_function(
+                    windows,
+                    RangeFunction::LastOverTime,
+                    self.lookback_delta,
+                    None,
+                    false,
+                )?;
+                Ok(Value::Vector(vector))
+            }
+            Expr::MatrixSelector { .. } => Err(execution_error(
+                "range vector can only be used as the argument of range functions",
+            )),
+            Expr::Call { func, args } => self.plan_call(func, args),
+            Expr::Aggregate {
+                op,
+                param,
+                expr,
+                grouping,
+            } => {
+                let param = match param {
+                    Some(param) => Some(self.plan_scalar(param)?),
+                    None => None,
+                };
+                let vector = self.plan_vector(expr)?;
+                Ok(Value::Vector(plan_aggregate(*op, param, vector, grouping)?))
+            }
+            Expr::Binary {
+                op,
+                lhs,
+                rhs,
+                return_bool,
+                matching,
+            } => self.plan_binary(*op, lhs, rhs, *return_bool, matching),
+        }
+    }
+
+    fn plan_scalar(&self, expr: &Expr) -> QueryResult<Value> {
+        match self.plan(expr)? {
+            value @ (Value::Number(_) | Value::Scalar(_)) => Ok(value),
+            _ => Err(execution_error(format!("expected a scalar: {expr:?}"))),
+        }
+    }
+
+    fn plan_vector(&self, expr: &Expr) -> QueryResult<Vector> {
+        match self.plan(expr)? {
+            Value::Vector(vector) => Ok(vector),
+            _ => Err(execution_error(format!(
+                "expected an instant vector: {expr:?}"
+            ))),
+        }
+    }
+
+    /// The rows of a scalar at every step.
+    fn scalar_plan(&self, value: Value) -> QueryResult<LogicalPlanBuilder> {
+        match value {
+            Value::Number(value) => Ok(self
+                .steps
+                .plan()?
+                .project(vec![column(STEP_COLUMN), lit(value).alias(VALUE_COLUMN)])?),
+            Value::Scalar(plan) => Ok(plan),
+            _ => Err(execution_error("expected a scalar")),
+        }
+    }
+
+    /// The samples in the window `(step - range, step]` of every step, the time of the samples
+    /// is shifted by the offset of the selector, the columns are the labels, `__step`, `__time`
+    /// and `__value`.
+    fn plan_windows(&self, selector: &VectorSelector, range: i64) -> QueryResult<Vector> {
+        let Steps { start, end, .. } = self.steps;
+        let samples = plan_samples(
+            self.selected(selector),
+            &selector.matchers,
+            start - selector.offset - range,
+            end - selector.offset,
+        )?;
+
+        // A sample is in the windows of the steps from the first step not before it, the steps
+        // of an instant query are spaced by the range so that every sample is in the only window.
+        let interval = if self.steps.count() == 1 {
+            range.max(1)
+        } else {
+            self.steps.interval
+        };
+        let offsets = (0..=(range / interval).min(self.steps.count() - 1))
+            .map(|i| vec![lit(i)])
+            .collect();
+        let offsets = LogicalPlanBuilder::values(offsets)?
+            .project(vec![column("column1").alias(OFFSET_COLUMN)])?
+            .build()?;
+
+        let time = plus(column(TIME_COLUMN), lit(selector.offset));
+        let since_start = minus(time.clone(), lit(start));
+        // Rounded up, the division of negative numbers is truncated towards zero.
+        let first_step = when(
+            gt(since_start.clone(), lit(0_i64)),
+            divide(plus(since_start.clone(), lit(interval - 1)), lit(interval)),
+        )
+        .otherwise(divide(since_start, lit(interval)))?;
+        let step = plus(
+            lit(start),
+            multiply(plus(first_step, column(OFFSET_COLUMN)), lit(interval)),
+        );
+
+        let columns = samples.labels.iter().map(|l| column(l).alias(l)).chain([
+            step.alias(STEP_COLUMN),
+            time.alias(TIME_COLUMN),
+            column(VALUE_COLUMN),
+        ]);
+        let plan = samples
+            .plan
+            .cross_join(offsets)?
+            .project(columns)?
+            .filter(and(
+                and(
+                    ge(column(STEP_COLUMN), lit(start)),
+                    le(column(STEP_COLUMN), lit(end)),
+                ),
+                gt(column(TIME_COLUMN), minus(column(STEP_COLUMN), lit(range))),
+            ))?;
+        Ok(Vector {
+            plan,
+            labels: samples.labels,
+        })
+    }
+
+    fn plan_call(&self, func: &str, args: &[Expr]) -> QueryResult<Value> {
+        if let Some(range_function) = RangeFunction::from_name(func) {
+            let num_args = if range_function.has_param() { 2 } else { 1 };
+            check_args(func, args, num_args, num_args)?;
+            let param = match range_function.has_param() {
+                true => Some(self.plan_scalar(&args[0])?),
+                false => None,
+            };
+            let (selector, range) = match &args[num_args - 1] {
+                Expr::MatrixSelector { selector, range } => (selector, *range),
+                arg => return Err(execution_error(format!("expected a range vector: {arg:?}"))),
+            };
+            let windows = self.plan_windows(selector, range)?;
+            let vector = plan_range_function(windows, range_function, range, param, true)?;
+            return Ok(Value::Vector(vector));
+        }
+
+        let vector = match func {
+            "abs" | "ceil" | "floor" | "exp" | "ln" | "log2" | "log10" | "sqrt" => {
+                check_args(func, args, 1, 1)?;
+                let value = scalar_function(func, vec![column(VALUE_COLUMN)])?;
+                map_vector(self.plan_vector(&args[0])?, value)?
+            }
+            "sgn" => {
+                check_args(func, args, 1, 1)?;
+                let value = column(VALUE_COLUMN);
+                let value = when(gt(value.clone(), lit(0.0)), lit(1.0))
+                    .when(lt(value.clone(), lit(0.0)), lit(-1.0))
+                    .otherwise(value)?;
+                map_vector(self.plan_vector(&args[0])?, value)?
+            }
+            "round" => {
+                check_args(func, args, 1, 2)?;
+                let vector = self.plan_vector(&args[0])?;
+                let to_nearest = match args.get(1) {
+                    Some(arg) => self.plan_scalar(arg)?,
+                    None => Value::Number(1.0),
+                };
+                let (plan, to_nearest) = join_scalar(vector.plan, to_nearest, PARAM_COLUMN)?;
+                // round half up like prometheus
+                let inverse = divide(lit(1.0), to_nearest);
+                let value = divide(
+                    scalar_function(
+                        "floor",
+                        vec![plus(
+                            multiply(column(VALUE_COLUMN), inverse.clone()),
+                            lit(0.5),
+                        )],
+                    )?,
+                    inverse,
+                );
+                map_vector(
+                    Vector {
+                        plan,
+                        labels: vector.labels,
+                    },
+                    value,
+                )?
+            }
+            "clamp_min" | "clamp_max" => {
+                check_args(func, args, 2, 2)?;
+                let vector = self.plan_vector(&args[0])?;
+                let (plan, bound) =
+                    join_scalar(vector.plan, self.plan_scalar(&args[1])?, PARAM_COLUMN)?;
+                let value = column(VALUE_COLUMN);
+                let out_of_bound = if func == "clamp_min" {
+                    lt(value.clone(), bound.clone())
+                } else {
+                    gt(value.clone(), bound.clone())
+                };
+                let value = when(out_of_bound, bound).otherwise(value)?;
+                map_vector(
+                    Vector {
+                        plan,
+                        labels: vector.labels,
+                    },
+                    value,
+                )?
+            }
+            "clamp" => {
+                check_args(func, args, 3, 3)?;
+                let vector = self.plan_vector(&args[0])?;
+                let (plan, min) = join_scalar(vector.plan, self.plan_scalar(&args[1])?, "__min")?;
+                let (plan, max) = join_scalar(plan, self.plan_scalar(&args[2])?, "__max")?;
+                let value = column(VALUE_COLUMN);
+                let value = when(lt(value.clone(), min.clone()), min.clone())
+                    .when(gt(value.clone(), max.clone()), max.clone())
+                    .otherwise(value)?;
+                // empty result if min > max
+                let plan = plan.filter(le(min, max))?;
+                map_vector(
+                    Vector {
+                        plan,
+                        labels: vector.labels,
+                    },
+                    value,
+                )?
+            }
+            "time" => {
+                check_args(func, args, 0, 0)?;
+                let value = divide(cast(column(STEP_COLUMN), DataType::Float64), lit(1000.0));
+                let plan = self
+                    .steps
+                    .plan()?
+                    .project(vec![column(STEP_COLUMN), value.alias(VALUE_COLUMN)])?;
+                return Ok(Value::Scalar(plan));
+            }
+            "vector" => {
+                check_args(func, args, 1, 1)?;
+                Vector {
+                    plan: self.scalar_plan(self.plan_scalar(&args[0])?)?,
+                    labels: vec![],
+                }
+            }
+            "scalar" => {
+                check_args(func, args, 1, 1)?;
+                let vector = self.plan_vector(&args[0])?;
+                // NaN if there isn't exactly one series at the step.
+                let step = format!("{SCALAR_COLUMN}_step");
+                let values = vector
+                    .plan
+                    .aggregate(
+                        vec![column(STEP_COLUMN)],
+                        vec![
+                            count(column(VALUE_COLUMN)).alias("__count"),
+                            max(column(VALUE_COLUMN)).alias("__max"),
+                        ],
+                    )?
+                    .project(vec![
+                        column(STEP_COLUMN).alias(&step),
+                        when(column("__count").eq(lit(1_i64)), column("__max"))
+                            .otherwise(lit(f64::NAN))?
+                            .alias(SCALAR_COLUMN),
+                    ])?
+                    .build()?;
+                let plan = self
+                    .steps
+                    .plan()?
+                    .join_detailed(
+                        values,
+                        JoinType::Left,
+                        (
+                            vec![Column::from_name(STEP_COLUMN)],
+                            vec![Column::from_name(step)],
+                        ),
+                        None,
+                        false,
+                    )?
+                    .project(vec![
+                        column(STEP_COLUMN),
+                        coalesce(vec![column(SCALAR_COLUMN), lit(f64::NAN)]).alias(VALUE_COLUMN),
+                    ])?;
+                return Ok(Value::Scalar(plan));
+            }
+            "absent" => {
+                check_args(func, args, 1, 1)?;
+                let vector = self.plan_vector(&args[0])?;
+                let step = format!("{SCALAR_COLUMN}_step");
+                let present = vector
+                    .plan
+                    .aggregate(vec![column(STEP_COLUMN)], Vec::<DFExpr>::new())?
+                    .project(vec![column(STEP_COLUMN).alias(&step)])?
+                    .build()?;
+                let labels = absent_labels(&args[0]);
+                let columns = labels
+                    .iter()
+                    .map(|(name, value)| lit(value.clone()).alias(name))
+                    .chain([column(STEP_COLUMN), lit(1.0).alias(VALUE_COLUMN)]);
+                let plan = self
+                    .steps
+                    .plan()?
+                    .join_detailed(
+                        present,
+                        JoinType::LeftAnti,
+                        (
+                            vec![Column::from_name(STEP_COLUMN)],
+                            vec![Column::from_name(step)],
+                        ),
+                        None,
+                        false,
+                    )?
+                    .project(columns)?;
+                Vector {
+                    plan,
+                    labels: labels.into_keys().collect(),
+                }
+            }
+            "sort" | "sort_desc" => {
+                check_args(func, args, 1, 1)?;
+                let vector = self.plan_vector(&args[0])?;
+                // only meaningful for instant queries, NaN is the last one
+                let plan = vector.plan.sort(vec![
+                    is_nan(column(VALUE_COLUMN)).sort(true, false),
+                    column(VALUE_COLUMN).sort(func == "sort", false),
+                ])?;
+                Vector {
+                    plan,
+                    labels: vector.labels,
+                }
+            }
+            "histogram_quantile" => {
+                check_args(func, args, 2, 2)?;
+                let q = self.plan_scalar(&args[0])?;
+                plan_histogram_quantile(q, self.plan_vector(&args[1])?)?
+            }
+            "label_replace" => {
+                check_args(func, args, 5, 5)?;
+                let vector = self.plan_vector(&args[0])?;
+                let [dst, replacement, src, regex] = [1, 2, 3, 4].map(|i| string_arg(&args[i]));
+                let (dst, replacement, src, regex) = (dst?, replacement?, src?, regex?);
+                let regex = format!("^(?:{regex})$");
+                Regex::new(&regex).map_err(|e| {
+                    execution_error(format!(
+                        "invalid regular expression in label_replace(): {e}"
+                    ))
+                })?;
+
+                let src = label_or_empty(&vector.labels, src);
+                let replaced = scalar_function(
+                    "regexp_replace",
+                    vec![src.clone(), lit(regex.clone()), lit(replacement)],
+                )?;
+                let value = when(
+                    binary_expr(src, Operator::RegexMatch, lit(regex)),
+                    scalar_function("nullif", vec![replaced, lit("")])?,
+                )
+                .otherwise(label_or_null(&vector.labels, dst))?;
+                set_label(vector, dst, value)?
+            }
+            "label_join" => {
+                check_args(func, args, 3, usize::MAX)?;
+                let vector = self.plan_vector(&args[0])?;
+                let dst = string_arg(&args[1])?;
+                let separator = string_arg(&args[2])?;
+                let src = args[3..]
+                    .iter()
+                    .map(|arg| Ok(label_or_empty(&vector.labels, string_arg(arg)?)))
+                    .collect::<QueryResult<Vec<_>>>()?;
+                let value = if src.is_empty() {
+                    lit(ScalarValue::Utf8(None))
+                } else {
+                    let joined = scalar_function(
+                        "concat_ws",
+                        std::iter::once(lit(separator)).chain(src).collect(),
+                    )?;
+                    scalar_function("nullif", vec![joined, lit("")])?
+                };
+                set_label(vector, dst, value)?
+            }
+            _ => return Err(execution_error(format!("unknown function {func}"))),
+        };
+        Ok(Value::Vector(vector))
+    }
+
+    fn plan_binary(
+        &self,
+        op: BinaryOp,
+        lhs: &Expr,
+        rhs: &Expr,
+        return_bool: bool,
+        matching: &VectorMatching,
+    ) -> QueryResult<Value> {
+        let lhs = self.plan(lhs)?;
+        let rhs = self.plan(rhs)?;
+
+        let check_scalars = || {
+            if op.is_comparison() && !return_bool {
+                return Err(execution_error(
+                    "comparisons between scalars must use bool modifier",
+                ));
+            }
+            if op.is_set_operator() {
+                return Err(execution_error(format!(
+                    "set operator {op:?} not allowed in binary scalar expression"
+                )));
+            }
+            Ok(())
+        };
+
+        match (lhs, rhs) {
+            (Value::Number(lhs), Value::Number(rhs)) => {
+                check_scalars()?;
+                Ok(Value::Number(number_binary(op, lhs, rhs)))
+            }
+            (
+                lhs @ (Value::Number(_) | Value::Scalar(_)),
+                rhs @ (Value::Number(_) | Value::Scalar(_)),
+            ) => {
+                check_scalars()?;
+                let (plan, rhs) = join_scalar(self.scalar_plan(lhs)?, rhs, SCALAR_COLUMN)?;
+                let value = binary_value(op, column(VALUE_COLUMN), rhs)?;
+                let value = if op.is_comparison() {
+                    bool_value(value)?
+                } else {
+                    value
+                };
+                Ok(Value::Scalar(plan.project(vec![
+                    column(STEP_COLUMN),
+                    value.alias(VALUE_COLUMN),
+                ])?))
+            }
+            (Value::Vector(vector), scalar @ (Value::Number(_) | Value::Scalar(_))) => Ok(
+                Value::Vector(plan_vector_scalar(op, vector, scalar, false, return_bool)?),
+            ),
+            (scalar @ (Value::Number(_) | Value::Scalar(_)), Value::Vector(vector)) => Ok(
+                Value::Vector(plan_vector_scalar(op, vector, scalar, true, return_bool)?),
+            ),
+            (Value::Vector(lhs), Value::Vector(rhs)) => {
+                let vector = match op {
+                    BinaryOp::And | BinaryOp::Unless => {
+                        plan_vector_and_unless(op, lhs, rhs, matching)?
+                    }
+                    BinaryOp::Or => plan_vector_or(lhs, rhs, matching)?,
+                    _ => plan_vector_binary(op, lhs, rhs, matching, return_bool)?,
+                };
+                Ok(Value::Vector(vector))
+            }
+            _ => Err(execution_error(format!(
+                "binary operator {op:?} is not allowed on strings"
+            ))),
+        }
+    }
+}
+
+/// The samples of the series selected by the matchers with time in `(start, end]`,
+/// the columns are the labels, `__time` and `__value`.
+fn plan_samples(
+    tables: &[MetricTable],
+    matchers: &[LabelMatcher],
+    start: i64,
+    end: i64,
+) -> QueryResult<Vector> {
+    let mut selected = Vec::with_capacity(tables.len());
+    'tables: for table in tables {
+        if !has_column(&table.scan, METRIC_SAMPLE_COLUMN_NAME) {
+            continue;
+        }
+        let mut filters = vec![time_filter(&table.scan, start, end)?];
+        for m in matchers {
+            if m.name == METRIC_NAME_LABEL {
+                if !label_matches(m, &table.name)? {
+                    continue 'tables;
+                }
+            } else if table.tags.contains(&m.name) {
+                filters.push(matcher_expr(m)?);
+            } else if !label_matches(m, "")? {
+                // An absent label is matched as an empty string.
+                continue 'tables;
+            }
+        }
+        selected.push((table, filters));
+    }
+
+    let labels = selected
+        .iter()
+        .flat_map(|(table, _)| table.tags.iter().cloned())
+        .chain([METRIC_NAME_LABEL.to_string()])
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let mut plan: Option<LogicalPlanBuilder> = None;
+    for (table, filters) in selected {
+        let columns = labels
+            .iter()
+            .map(|l| {
+                // The metric name is the table name.
+                if l == METRIC_NAME_LABEL {
+                    lit(table.name.clone()).alias(l)
+                } else if table.tags.contains(l) {
+                    column(l).alias(l)
+                } else {
+                    lit(ScalarValue::Utf8(None)).alias(l)
+                }
+            })
+            .chain([
+                cast(
+                    cast(
+                        column(TIME_FIELD_NAME),
+                        DataType::Timestamp(TimeUnit::Millisecond, None),
+                    ),
+                    DataType::Int64,
+                )
+                .alias(TIME_COLUMN),
+                cast(column(METRIC_SAMPLE_COLUMN_NAME), DataType::Float64).alias(VALUE_COLUMN),
+            ]);
+        let filter = filters.into_iter().reduce(and).unwrap_or_else(|| lit(true));
+        let samples = LogicalPlanBuilder::from(table.scan.clone())
+            .filter(filter)?
+            .project(columns)?
+            .build()?;
+        plan = Some(match plan {
+            Some(plan) => plan.union(samples)?,
+            None => LogicalPlanBuilder::from(samples),
+        });
+    }
+
+    let plan = match plan {
+        Some(plan) => plan,
+        None => {
+            // No series are selected.
+            let columns = labels
+                .iter()
+                .map(|l| lit(ScalarValue::Utf8(None)).alias(l))
+                .chain([lit(0_i64).alias(TIME_COLUMN), lit(0.0).alias(VALUE_COLUMN)]);
+            LogicalPlanBuilder::empty(false).project(columns)?
+        }
+    };
+    Ok(Vector { plan, labels })
+}
+
+/// Aggregates the samples in the window of every step by a range function.
+fn plan_range_function(
+    windows: Vector,
+    func: RangeFunction,
+    range: i64,
+    param: Option<Value>,
+    drop_name: bool,
+) -> QueryResult<Vector> {
+    let (plan, param) = match param {
+        Some(param) => {
+            let (plan, param) = join_scalar(windows.plan, param, PARAM_COLUMN)?;
+            (plan, Some(param))
+        }
+        None => (windows.plan, None),
+    };
+    let args = [
+        column(TIME_COLUMN),
+        column(VALUE_COLUMN),
+        column(STEP_COLUMN),
+        lit(range),
+    ]
+    .into_iter()
+    .chain(param)
+    .collect();
+    let value = DFExpr::AggregateUDF(expr::AggregateUDF {
+        fun: Arc::new(range_udaf(func)),
+        args,
+        filter: None,
+        order_by: None,
+    });
+
+    let group_by = windows
+        .labels
+        .iter()
+        .map(|l| column(l))
+        .chain([column(STEP_COLUMN)])
+        .collect::<Vec<_>>();
+    let plan = plan
+        .aggregate(group_by, vec![value.alias(VALUE_COLUMN)])?
+        .filter(is_not_null(column(VALUE_COLUMN)))?;
+
+    let mut labels = windows.labels;
+    if drop_name {
+        drop_metric_name(&mut labels);
+    }
+    Ok(Vector {
+        plan: project_vector(plan, &labels, column(VALUE_COLUMN))?,
+        labels,
+    })
+}
+
+fn plan_aggregate(
+    op: AggregateOp,
+    param: Option<Value>,
+    vector: Vector,
+    grouping: &Grouping,
+) -> QueryResult<Vector> {
+    let group_labels = vector
+        .labels
+        .iter()
+        .filter(|l| match grouping {
+            Grouping::By(by) => by.contains(l),
+            Grouping::Without(without) => l.as_str() != METRIC_NAME_LABEL && !without.contains(l),
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    let group_by = group_labels
+        .iter()
+        .map(|l| column(l))
+        .chain([column(STEP_COLUMN)])
+        .collect::<Vec<_>>();
+
+    let (plan, param) = match param {
+        Some(param) => {
+            let (plan, param) = join_scalar(vector.plan, param, PARAM_COLUMN)?;
+            (plan, Some(param))
+        }
+        None => (vector.plan, None),
+    };
+    let param = || param.clone().unwrap_or_else(|| lit(f64::NAN));
+
+    if matches!(op, AggregateOp::Topk | AggregateOp::Bottomk) {
+        // NaN is always the last one
+        let order_by = vec![
+            is_nan(column(VALUE_COLUMN)).sort(true, false),
+            column(VALUE_COLUMN).sort(op == AggregateOp::Bottomk, false),
+        ];
+        let rank = window_func_expr(
+            window_function::WindowFunction::BuiltInWindowFunction(
+                window_function::BuiltInWindowFunction::RowNumber,
+            ),
+            vec![],
+            group_by,
+            order_by,
+        );
+        let plan = plan
+            .window(vec![rank.alias(RANK_COLUMN)])?
+            .filter(le(cast(column(RANK_COLUMN), DataType::Float64), param()))?;
+        return Ok(Vector {
+            plan: project_vector(plan, &vector.labels, column(VALUE_COLUMN))?,
+            labels: vector.labels,
+        });
+    }
+
+    let value = column(VALUE_COLUMN);
+    let aggregate = match op {
+        AggregateOp::Sum => sum(value),
+        AggregateOp::Avg => avg(value),
+        AggregateOp::Min => min(value),
+        AggregateOp::Max => max(value),
+        AggregateOp::Count | AggregateOp::Group => count(value),
+        AggregateOp::Stddev => {
+            builtin_aggregate(aggregate_function::AggregateFunction::StddevPop, value)
+        }
+        AggregateOp::Stdvar => {
+            builtin_aggregate(aggregate_function::AggregateFunction::VariancePop, value)
+        }
+        AggregateOp::Quantile => DFExpr::AggregateUDF(expr::AggregateUDF {
+            fun: Arc::new(quantile_udaf()),
+            args: vec![param(), value],
+            filter: None,
+            order_by: None,
+        }),
+        AggregateOp::Topk | AggregateOp::Bottomk => unreachable!("planned by row numbers"),
+    };
+    let value = match op {
+        AggregateOp::Count => cast(column(VALUE_COLUMN), DataType::Float64),
+        AggregateOp::Group => lit(1.0),
+        _ => column(VALUE_COLUMN),
+    };
+    let plan = plan.aggregate(group_by, vec![aggregate.alias(VALUE_COLUMN)])?;
+    Ok(Vector {
+        plan: project_vector(plan, &group_labels, value)?,
+        labels: group_labels,
+    })
+}
+
+/// The buckets of a histogram are the series with the same labels other than `le`.
+fn plan_histogram_quantile(q: Value, vector: Vector) -> QueryResult<Vector> {
+    let labels = vector
+        .labels
+        .iter()
+        .filter(|l| l.as_str() != "le" && l.as_str() != METRIC_NAME_LABEL)
+        .cloned()
+        .collect::<Vec<_>>();
+    let group_by = labels
+        .iter()
+        .map(|l| column(l))
+        .chain([column(STEP_COLUMN)])
+        .collect::<Vec<_>>();
+
+    let (plan, q) = join_scalar(vector.plan, q, PARAM_COLUMN)?;
+    let value = DFExpr::AggregateUDF(expr::AggregateUDF {
+        fun: Arc::new(histogram_quantile_udaf()),
+        args: vec![q, label_or_null(&vector.labels, "le"), column(VALUE_COLUMN)],
+        filter: None,
+        order_by: None,
+    });
+    let plan = plan
+        .aggregate(group_by, vec![value.alias(VALUE_COLUMN)])?
+        .filter(is_not_null(column(VALUE_COLUMN)))?;
+    Ok(Vector {
+        plan: project_vector(plan, &labels, column(VALUE_COLUMN))?,
+        labels,
+    })
+}
+
+fn plan_vector_scalar(
+    op: BinaryOp,
+    vector: Vector,
+    scalar: Value,
+    swap: bool,
+    return_bool: bool,
+) -> QueryResult<Vector> {
+    if op.is_set_operator() {
+        return Err(execution_error(format!(
+            "set operator {op:?} not allowed in binary scalar expression"
+        )));
+    }
+
+    let (plan, scalar) = join_scalar(vector.plan, scalar, SCALAR_COLUMN)?;
+    let (lhs, rhs) = if swap {
+        (scalar, column(VALUE_COLUMN))
+    } else {
+        (column(VALUE_COLUMN), scalar)
+    };
+    let value = binary_value(op, lhs, rhs)?;
+    let mut labels = vector.labels;
+    let plan = if op.is_comparison() && !return_bool {
+        // the sample of the vector is kept when filtered by comparisons
+        let plan = plan.filter(value)?;
+        project_vector(plan, &labels, column(VALUE_COLUMN))?
+    } else {
+        drop_metric_name(&mut labels);
+        let value = if op.is_comparison() {
+            bool_value(value)?
+        } else {
+            value
+        };
+        project_vector(plan, &labels, value)?
+    };
+    Ok(Vector { plan, labels })
+}
+
+/// The labels used to match the series of the two sides of a binary operation.
+fn matching_labels(lhs: &[String], rhs: &[String], matching: &VectorMatching) -> Vec<String> {
+    if matching.on {
+        return matching.labels.clone();
+    }
+    lhs.iter()
+        .chain(rhs)
+        .filter(|l| l.as_str() != METRIC_NAME_LABEL && !matching.labels.contains(l))
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Adds the labels that the vector doesn't have as null columns.
+fn pad_labels(vector: Vector, labels: &[String]) -> QueryResult<Vector> {
+    let missing = labels.iter().any(|l| !vector.labels.contains(l));
+    if !missing {
+        return Ok(vector);
+    }
+    let labels = vector
+        .labels
+        .iter()
+        .chain(labels)
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let columns = labels
+        .iter()
+        .map(|l| label_or_null(&vector.labels, l).alias(l))
+        .chain([column(STEP_COLUMN), column(VALUE_COLUMN)]);
+    Ok(Vector {
+        plan: vector.plan.project(columns)?,
+        labels,
+    })
+}
+
+/// The join keys of the two sides, the matching labels and the step.
+fn join_keys(labels: &[String]) -> (Vec<Column>, Vec<Column>) {
+    let keys = |alias: &'static str| {
+        labels
+            .iter()
+            .map(|l| l.as_str())
+            .chain([STEP_COLUMN])
+            .map(|k| Column::new(Some(alias), k))
+            .collect::<Vec<_>>()
+    };
+    (keys(LHS_ALIAS), keys(RHS_ALIAS))
+}
+
+fn plan_vector_and_unless(
+    op: BinaryOp,
+    lhs: Vector,
+    rhs: Vector,
+    matching: &VectorMatching,
+) -> QueryResult<Vector> {
+    let keys = matching_labels(&lhs.labels, &rhs.labels, matching);
+    let lhs = pad_labels(lhs, &keys)?;
+    let rhs = pad_labels(rhs, &keys)?;
+    let join_type = if op == BinaryOp::And {
+        JoinType::LeftSemi
+    } else {
+        JoinType::LeftAnti
+    };
+    let plan = lhs.plan.alias(LHS_ALIAS)?.join_detailed(
+        rhs.plan.alias(RHS_ALIAS)?.build()?,
+        join_type,
+        join_keys(&keys),
+        None,
+        // An absent label matches the absent label of the other side.
+        true,
+    )?;
+    let columns = lhs
+        .labels
+        .iter()
+        .map(|l| qualified(LHS_ALIAS, l).alias(l))
+        .chain([
+            qualified(LHS_ALIAS, STEP_COLUMN).alias(STEP_COLUMN),
+            qualified(LHS_ALIAS, VALUE_COLUMN).alias(VALUE_COLUMN),
+        ]);
+    Ok(Vector {
+        plan: plan.project(columns)?,
+        labels: lhs.labels,
+    })
+}
+
+/// The series of the right side are added at the steps that the left side has no series
+/// with the same matching labels.
+fn plan_vector_or(lhs: Vector, rhs: Vector, matching: &VectorMatching) -> QueryResult<Vector> {
+    let keys = matching_labels(&lhs.labels, &rhs.labels, matching);
+    let labels = lhs
+        .labels
+        .iter()
+        .chain(&rhs.labels)
+        .chain(&keys)
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let lhs = pad_labels(lhs, &labels)?;
+    let rhs = pad_labels(rhs, &labels)?;
+
+    let rhs_only = plan_vector_and_unless(BinaryOp::Unless, rhs, lhs_clone(&lhs)?, matching)?;
+    let plan = lhs.plan.union(rhs_only.plan.build()?)?;
+    Ok(Vector { plan, labels })
+}
+
+fn lhs_clone(vector: &Vector) -> QueryResult<Vector> {
+    Ok(Vector {
+        plan: LogicalPlanBuilder::from(vector.plan.build()?),
+        labels: vector.labels.clone(),
+    })
+}
+
+fn plan_vector_binary(
+    op: BinaryOp,
+    lhs: Vector,
+    rhs: Vector,
+    matching: &VectorMatching,
+    return_bool: bool,
+) -> QueryResult<Vector> {
+    let keys = matching_labels(&lhs.labels, &rhs.labels, matching);
+    let lhs = pad_labels(lhs, &keys)?;
+    let rhs = pad_labels(rhs, &keys)?;
+
+    // The labels of the result are those of the "many" side, with the included labels
+    // of the "one" side.
+    let (many, one, include) = match &matching.card {
+        Cardinality::OneToOne => ((LHS_ALIAS, &lhs.labels), (RHS_ALIAS, &rhs.labels), None),
+        Cardinality::ManyToOne(include) => (
+            (LHS_ALIAS, &lhs.labels),
+            (RHS_ALIAS, &rhs.labels),
+            Some(include),
+        ),
+        Cardinality::OneToMany(include) => (
+            (RHS_ALIAS, &rhs.labels),
+            (LHS_ALIAS, &lhs.labels),
+            Some(include),
+        ),
+    };
+    let drop_name = !op.is_comparison() || return_bool;
+    let mut labels = many
+        .1
+        .iter()
+        .filter(|l| !(drop_name && l.as_str() == METRIC_NAME_LABEL))
+        .map(|l| (l.clone(), qualified(many.0, l)))
+        .collect::<BTreeMap<_, _>>();
+    match include {
+        None => labels.retain(|l, _| matching.labels.contains(l) == matching.on),
+        Some(include) => {
+            for l in include {
+                labels.insert(l.clone(), label_or_null_of(one.0, one.1, l));
+            }
+        }
+    }
+
+    let plan = lhs.plan.alias(LHS_ALIAS)?.join_detailed(
+        rhs.plan.alias(RHS_ALIAS)?.build()?,
+        JoinType::Inner,
+        join_keys(&keys),
+        None,
+        true,
+    )?;
+    let lhs_value = qualified(LHS_ALIAS, VALUE_COLUMN);
+    let value = binary_value(op, lhs_value.clone(), qualified(RHS_ALIAS, VALUE_COLUMN))?;
+    let (plan, value) = if op.is_comparison() && !return_bool {
+        (plan.filter(value)?, lhs_value)
+    } else if op.is_comparison() {
+        (plan, bool_value(value)?)
+    } else {
+        (plan, value)
+    };
+
+    let columns = labels.iter().map(|(l, expr)| expr.clone().alias(l)).chain([
+        qualified(many.0, STEP_COLUMN).alias(STEP_COLUMN),
+        value.alias(VALUE_COLUMN),
+    ]);
+    Ok(Vector {
+        plan: plan.project(columns)?,
+        labels: labels.into_keys().collect(),
+    })
+}
+
+/// Joins the scalar to the rows of the same step, returns the expression of its value.
+fn join_scalar(
+    plan: LogicalPlanBuilder,
+    scalar: Value,
+    name: &str,
+) -> QueryResult<(LogicalPlanBuilder, DFExpr)> {
+    match scalar {
+        Value::Number(value) => Ok((plan, lit(value))),
+        Value::Scalar(scalar) => {
+            let step = format!("{name}_step");
+            let scalar = scalar
+                .project(vec![
+                    column(STEP_COLUMN).alias(&step),
+                    column(VALUE_COLUMN).alias(name),
+                ])?
+                .build()?;
+            let plan = plan.join_detailed(
+                scalar,
+                JoinType::Inner,
+                (
+                    vec![Column::from_name(STEP_COLUMN)],
+                    vec![Column::from_name(step)],
+                ),
+                None,
+                false,
+            )?;
+            Ok((plan, column(name)))
+        }
+        _ => Err(execution_error("expected a scalar")),
+    }
+}
+
+/// Applies the expression to every value of the vector, the metric name is dropped.
+fn map_vector(mut vector: Vector, value: DFExpr) -> QueryResult<Vector> {
+    drop_metric_name(&mut vector.labels);
+    Ok(Vector {
+        plan: project_vector(vector.plan, &vector.labels, value)?,
+        labels: vector.labels,
+    })
+}
+
+/// Sets the value of a label, which is removed if the value is null.
+fn set_label(vector: Vector, name: &str, value: DFExpr) -> QueryResult<Vector> {
+    let mut labels = vector.labels;
+    if !labels.iter().any(|l| l == name) {
+        labels.push(name.to_string());
+        labels.sort();
+    }
+    let columns = labels
+        .iter()
+        .map(|l| {
+            if l == name {
+                value.clone().alias(l)
+            } else {
+                column(l).alias(l)
+            }
+        })
+        .chain([column(STEP_COLUMN), column(VALUE_COLUMN)]);
+    Ok(Vector {
+        plan: vector.plan.project(columns)?,
+        labels,
+    })
+}
+
+fn project_vector(
+    plan: LogicalPlanBuilder,
+    labels: &[String],
+    value: DFExpr,
+) -> QueryResult<LogicalPlanBuilder> {
+    let columns = labels
+        .iter()
+        .map(|l| column(l).alias(l))
+        .chain([column(STEP_COLUMN), value.alias(VALUE_COLUMN)]);
+    Ok(plan.project(columns)?)
+}
+
+fn drop_metric_name(labels: &mut Vec<String>) {
+    labels.retain(|l| l != METRIC_NAME_LABEL);
+}
+
+/// Returns the value of `lhs op rhs` of arithmetic operators, or the condition of comparisons.
+fn binary_value(op: BinaryOp, lhs: DFExpr, rhs: DFExpr) -> QueryResult<DFExpr> {
+    let value = match op {
+        BinaryOp::Add => plus(lhs, rhs),
+        BinaryOp::Sub => minus(lhs, rhs),
+        BinaryOp::Mul => multiply(lhs, rhs),
+        BinaryOp::Div => {
+            // Division by zero is infinity or NaN as prometheus, but not null.
+            let by_zero = when(gt(lhs.clone(), lit(0.0)), lit(f64::INFINITY))
+                .when(lt(lhs.clone(), lit(0.0)), lit(f64::NEG_INFINITY))
+                .otherwise(lit(f64::NAN))?;
+            when(rhs.clone().eq(lit(0.0)), by_zero).otherwise(divide(lhs, rhs))?
+        }
+        BinaryOp::Mod => {
+            when(rhs.clone().eq(lit(0.0)), lit(f64::NAN)).otherwise(modulo(lhs, rhs))?
+        }
+        BinaryOp::Pow => scalar_function("power", vec![lhs, rhs])?,
+        BinaryOp::Eq => lhs.eq(rhs),
+        BinaryOp::Ne => lhs.not_eq(rhs),
+        BinaryOp::Gt => gt(lhs, rhs),
+        BinaryOp::Lt => lt(lhs, rhs),
+        BinaryOp::Ge => ge(lhs, rhs),
+        BinaryOp::Le => le(lhs, rhs),
+        BinaryOp::And | BinaryOp::Or | BinaryOp::Unless => {
+            return Err(execution_error(format!(
+                "set operator {op:?} is only allowed between vectors"
+            )))
+        }
+    };
+    Ok(value)
+}
+
+fn bool_value(condition: DFExpr) -> QueryResult<DFExpr> {
+    Ok(when(condition, lit(1.0)).otherwise(lit(0.0))?)
+}
+
+/// The binary operation between two numbers, comparisons return 1 or 0.
+fn number_binary(op: BinaryOp, lhs: f64, rhs: f64) -> f64 {
+    let keep = match op {
+        BinaryOp::Add => return lhs + rhs,
+        BinaryOp::Sub => return lhs - rhs,
+        BinaryOp::Mul => return lhs * rhs,
+        BinaryOp::Div => return lhs / rhs,
+        BinaryOp::Mod => return lhs % rhs,
+        BinaryOp::Pow => return lhs.powf(rhs),
+        BinaryOp::Eq => lhs == rhs,
+        BinaryOp::Ne => lhs != rhs,
+        BinaryOp::Gt => lhs > rhs,
+        BinaryOp::Lt => lhs < rhs,
+        BinaryOp::Ge => lhs >= rhs,
+        BinaryOp::Le => lhs <= rhs,
+        BinaryOp::And | BinaryOp::Or | BinaryOp::Unless => return lhs,
+    };
+    if keep {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn matcher_expr(matcher: &LabelMatcher) -> QueryResult<DFExpr> {
+    let label = coalesce(vec![column(&matcher.name), lit("")]);
+    let value = || lit(matcher.value.clone());
+    let regex = || -> QueryResult<DFExpr> {
+        anchored_regex(&matcher.value)?;
+        Ok(lit(format!("^(?:{})$", matcher.value)))
+    };
+    let expr = match matcher.op {
+        MatchOp::Equal => label.eq(value()),
+        MatchOp::NotEqual => label.not_eq(value()),
+        // Regular expressions of PromQL are fully anchored.
+        MatchOp::Re => binary_expr(label, Operator::RegexMatch, regex()?),
+        MatchOp::NotRe => binary_expr(label, Operator::RegexNotMatch, regex()?),
+    };
+    Ok(expr)
+}
+
+fn anchored_regex(regex: &str) -> QueryResult<Regex> {
+    Regex::new(&format!("^(?:{regex})$")).map_err(|err| QueryError::InvalidPromQL {
+        err: err.to_string(),
+    })
+}
+
+/// The condition of the time column to be in `(start, end]`, which are milliseconds.
+fn time_filter(scan: &LogicalPlan, start: i64, end: i64) -> QueryResult<DFExpr> {
+    let time_type = scan
+        .schema()
+        .field_with_unqualified_name(TIME_FIELD_NAME)?
+        .data_type()
+        .clone();
+    Ok(and(
+        gt(column(TIME_FIELD_NAME), time_literal(start, &time_type)?),
+        le(column(TIME_FIELD_NAME), time_literal(end, &time_type)?),
+    ))
+}
+
+/// Converts the milliseconds to a literal of the time column, rounded down.
+fn time_literal(millis: i64, time_type: &DataType) -> QueryResult<DFExpr> {
+    let DataType::Timestamp(unit, tz) = time_type else {
+        return Err(QueryError::Internal {
+            reason: format!("unexpected type of the time column: {time_type}"),
+        });
+    };
+    let value = match unit {
+        TimeUnit::Second => ScalarValue::TimestampSecond(Some(millis.div_euclid(1000)), tz.clone()),
+        TimeUnit::Millisecond => ScalarValue::TimestampMillisecond(Some(millis), tz.clone()),
+        TimeUnit::Microsecond => {
+            ScalarValue::TimestampMicrosecond(Some(millis.saturating_mul(1000)), tz.clone())
+        }
+        TimeUnit::Nanosecond => {
+            ScalarValue::TimestampNanosecond(Some(millis.saturating_mul(1_000_000)), tz.clone())
+        }
+    };
+    Ok(lit(value))
+}
+
+fn has_column(plan: &LogicalPlan, name: &str) -> bool {
+    plan.schema().field_with_unqualified_name(name).is_ok()
+}
+
+/// The column of the name, which is not parsed as a qualified name.
+fn column(name: &str) -> DFExpr {
+    DFExpr::Column(Column::from_name(name))
+}
+
+fn qualified(alias: &'static str, name: &str) -> DFExpr {
+    DFExpr::Column(Column::new(Some(alias), name))
+}
+
+fn label_or_null(labels: &[String], name: &str) -> DFExpr {
+    if labels.iter().any(|l| l == name) {
+        column(name)
+    } else {
+        lit(ScalarValue::Utf8(None))
+    }
+}
+
+fn label_or_null_of(alias: &'static str, labels: &[String], name: &str) -> DFExpr {
+    if labels.iter().any(|l| l == name) {
+        qualified(alias, name)
+    } else {
+        lit(ScalarValue::Utf8(None))
+    }
+}
+
+fn label_or_empty(labels: &[String], name: &str) -> DFExpr {
+    coalesce(vec![label_or_null(labels, name), lit("")])
+}
+
+fn negative(expr: DFExpr) -> DFExpr {
+    DFExpr::Negative(Box::new(expr))
+}
+
+/// NaN equals NaN in the comparisons of arrow, so it's compared as text.
+fn is_nan(expr: DFExpr) -> DFExpr {
+    cast(expr, DataType::Utf8).eq(lit("NaN"))
+}
+
+fn scalar_function(name: &str, args: Vec<DFExpr>) -> QueryResult<DFExpr> {
+    let fun = BuiltinScalarFunction::from_str(name)?;
+    Ok(DFExpr::ScalarFunction(ScalarFunction::new(fun, args)))
+}
+
+fn builtin_aggregate(fun: aggregate_function::AggregateFunction, arg: DFExpr) -> DFExpr {
+    DFExpr::AggregateFunction(expr::AggregateFunction {
+        fun,
+        args: vec![arg],
+        distinct: false,
+        filter: None,
+        order_by: None,
+    })
+}
+
+/// Orders the rows by the labels and then the time.
+fn sort_exprs(labels: &[String], time_column: &str) -> Vec<DFExpr> {
+    labels
+        .iter()
+        .map(|l| column(l).sort(true, true))
+        .chain([column(time_column).sort(true, false)])
+        .collect()
+}
+
+/// Whether the result of the expression is ordered by `sort()` or `sort_desc()`.
+fn is_sort(expr: &Expr) -> bool {
+    match expr {
+        Expr::Paren(expr) => is_sort(expr),
+        Expr::Call { func, .. } => func == "sort" || func == "sort_desc",
+        _ => false,
+    }
+}
+
+fn check_args(func: &str, args: &[Expr], min: usize, max: usize) -> QueryResult<()> {
+    if args.len() < min || args.len() > max {
+        return Err(execution_error(format!(
+            "wrong number of arguments for function {func}, got {}",
+            args.len()
+        )));
+    }
+    Ok(())
+}
+
+fn string_arg(expr: &Expr) -> QueryResult<&str> {
+    match expr {
+        Expr::String(s) => Ok(s),
+        Expr::Paren(expr) => string_arg(expr),
+        _ => Err(execution_error(format!("expected a string: {expr:?}"))),
+    }
+}
+
+/// The labels of `absent()`, taken from the equality matchers of a selector.
+fn absent_labels(expr: &Expr) -> BTreeMap<String, String> {
+    match expr {
+        Expr::VectorSelector(selector) => selector
+            .matchers
+            .iter()
+            .filter(|m| m.op == MatchOp::Equal && m.name != METRIC_NAME_LABEL)
+            .map(|m| (m.name.clone(), m.value.clone()))
+            .collect(),
+        _ => BTreeMap::new(),
+    }
+}
+
+/// Reads the labels, the time and the value of the rows.
+fn read_points(
+    batches: &[RecordBatch],
+    labels: &[String],
+    time_column: &str,
+) -> QueryResult<Vec<(PromLabels, i64, f64)>> {
+    let mut points = vec![];
+    for batch in batches {
+        let columns = label_columns(batch, labels)?;
+        let times = typed_column::<Int64Array>(batch, time_column)?;
+        let values = typed_column::<Float64Array>(batch, VALUE_COLUMN)?;
+        for row in 0..batch.num_rows() {
+            if times.is_null(row) || values.is_null(row) {
+                continue;
+            }
+            points.push((
+                row_labels(&columns, row),
+                times.value(row),
+                values.value(row),
+            ));
+        }
+    }
+    Ok(points)
+}
+
+/// Groups the points by the labels, the points of a series are in the order of time,
+/// the duplicate points of the series with the same labels are ignored.
+fn into_series(points: Vec<(PromLabels, i64, f64)>) -> Vec<PromSeries> {
+    let mut series = BTreeMap::<PromLabels, Vec<(i64, f64)>>::new();
+    for (labels, time, value) in points {
+        let values = series.entry(labels).or_default();
+        if values.last().map_or(true, |(last, _)| *last < time) {
+            values.push((time, value));
+        }
+    }
+    series
+        .into_iter()
+        .map(|(metric, values)| PromSeries {
+            metric,
+            values: values
+                .into_iter()
+                .map(|(time, value)| PromPoint::new(time, value))
+                .collect(),
+        })
+        .collect()
+}
+
+fn label_columns<'a>(
+    batch: &'a RecordBatch,
+    labels: &'a [String],
+) -> QueryResult<Vec<(&'a String, &'a StringArray)>> {
+    labels
+        .iter()
+        .map(|l| Ok((l, typed_column::<StringArray>(batch, l)?)))
+        .collect()
+}
+
+/// The labels of the row, the null and empty values are absent labels.
+fn row_labels(columns: &[(&String, &StringArray)], row: usize) -> PromLabels {
+    columns
+        .iter()
+        .filter(|(_, values)| values.is_valid(row) && !values.value(row).is_empty())
+        .map(|(name, values)| (name.to_string(), values.value(row).to_string()))
+        .collect()
+}
+
+fn typed_column<'a, T: 'static>(batch: &'a RecordBatch, name: &str) -> QueryResult<&'a T> {
+    batch
+        .column_by_name(name)
+        .and_then(|c| c.as_any().downcast_ref::<T>())
+        .ok_or_else(|| QueryError::Internal {
+            reason: format!("unexpected column {name} of the result of promql"),
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use datafusion::arrow::array::{ArrayRef, TimestampNanosecondArray};
+    use datafusion::arrow::datatypes::{Field, Schema};
+    use datafusion::datasource::{provider_as_source, MemTable};
+    use datafusion::prelude::SessionContext;
+
+    use super::*;
+    use crate::prom::promql::parser;
+
+    /// A sample every 15s in the first 10 minutes.
+    fn table(name: &str, series: Vec<(Vec<(&str, &str)>, fn(i64) -> f64)>) -> MetricTable {
+        let mut tags = series
+            .iter()
+            .flat_map(|(labels, _)| labels.iter().map(|(k, _)| k.to_string()))
+            .collect::<BTreeSet<_>>();
+        tags.insert(METRIC_NAME_LABEL.to_string());
+        let tags = tags.into_iter().collect::<Vec<_>>();
+
+        let fields = [Field::new(
+            TIME_FIELD_NAME,
+            DataType::Timestamp(TimeUnit::Nanosecond, None),
+            false,
+        )]
+        .into_iter()
+        .chain(tags.iter().map(|t| Field::new(t, DataType::Utf8, true)))
+        .chain([Field::new(
+            METRIC_SAMPLE_COLUMN_NAME,
+            DataType::Float64,
+            true,
+        )])
+        .collect::<Vec<_>>();
+        let schema = Arc::new(Schema::new(fields));
+
+        let mut times = vec![];
+        let mut values = vec![];
+        let mut tag_values = vec![vec![]; tags.len()];
+        for (labels, f) in series.iter() {
+            for i in 0..=40 {
+                times.push(i * 15_000_000_000);
+                values.push(f(i));
+                for (tag, column) in tags.iter().zip(tag_values.iter_mut()) {
+                    let value = match tag.as_str() {
+                        METRIC_NAME_LABEL => Some(name),
+                        tag => labels.iter().find(|(k, _)| *k == tag).map(|(_, v)| *v),
+                    };
+                    column.push(value);
+                }
+            }
+        }
+        let columns = [Arc::new(TimestampNanosecondArray::from(times)) as ArrayRef]
+            .into_iter()
+            .chain(
+                tag_values
+                    .into_iter()
+                    .map(|v| Arc::new(StringArray::from(v)) as ArrayRef),
+            )
+            .chain([Arc::new(Float64Array::from(values)) as ArrayRef])
+            .collect();
+        let batch = RecordBatch::try_new(schema.clone(), columns).unwrap();
+        let provider = MemTable::try_new(schema, vec![vec![batch]]).unwrap();
+        let scan = LogicalPlanBuilder::scan(name, provider_as_source(Arc::new(provider)), None)
+            .unwrap()
+            .build()
+            .unwrap();
+        MetricTable {
+            name: name.to_string(),
+            tags,
+            scan,
+        }
+    }
+
+    fn tables() -> Vec<MetricTable> {
+        vec![
+            table(
+                "http_requests_total",
+                vec![
+                    (vec![("job", "api"), ("instance", "a")], |i| (i * 15) as f64),
+                    (vec![("job", "api"), ("instance", "b")], |i| (i * 30) as f64),
+                    (vec![("job", "web"), ("instance", "c")], |i| (i * 3) as f64),
+                ],
+            ),
+            table(
+                "latency_bucket",
+                vec![
+                    (vec![("le", "0.1")], |i| (i * 5) as f64),
+                    (vec![("le", "0.5")], |i| (i * 8) as f64),
+                    (vec![("le", "+Inf")], |i| (i * 10) as f64),
+                ],
+            ),
+        ]
+    }
+
+    /// All the tables are selected, the metric name is matched by the planner.
+    fn selected_tables(expr: &Expr) -> SelectedTables {
+        let mut selected = SelectedTables::new();
+        expr.walk_selectors(&mut |selector, _| {
+            selected.insert(selector.matchers.clone(), tables());
+        });
+        selected
+    }
+
+    async fn query(req: PromQueryRequest) -> PromQueryData {
+        let expr = parser::parse(&req.query).unwrap();
+        let plan = plan_query(&expr, &selected_tables(&expr), &req).unwrap();
+        let batches = match plan.logical_plan() {
+            Some(logical_plan) => SessionContext::new()
+                .execute_logical_plan(logical_plan.clone())
+                .await
+                .unwrap()
+                .collect()
+                .await
+                .unwrap(),
+            None => vec![],
+        };
+        plan.query_data(&batches, &req).unwrap()
+    }
+
+    async fn instant(query_text: &str, time: i64) -> Vec<(PromLabels, f64)> {
+        let req = PromQueryRequest::instant(query_text.to_string(), time);
+        match query(req).await {
+            PromQueryData::Vector(samples) => {
+                let mut result = samples
+                    .into_iter()
+                    .map(|s| (s.metric, s.value.1.parse().unwrap()))
+                    .collect::<Vec<_>>();
+                result.sort_by(|a: &(PromLabels, f64), b| a.0.cmp(&b.0));
+                result
+            }
+            data => panic!("unexpected result {data:?}"),
+        }
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> PromLabels {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_selector_and_rate() {
+        let result = instant(r#"http_requests_total{instance=~"a|b"}"#, 300_000).await;
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].1, 300.0);
+
+        // 1 per second, no extrapolation needed since samples cover the whole range
+        let result = instant(r#"rate(http_requests_total{instance="a"}[1m])"#, 300_000).await;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].0, labels(&[("instance", "a"), ("job", "api")]));
+        assert!((result[0].1 - 1.0).abs() < 1e-9);
+        let result = instant(
+            r#"increase(http_requests_total{instance="b"}[5m])"#,
+            600_000,
+        )
+        .await;
+        assert!((result[0].1 - 600.0).abs() < 1e-9);
+
+        let result = instant("sum by (job) (rate(http_requests_total[1m]))", 300_000).await;
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].0, labels(&[("job", "api")]));
+        assert!((result[0].1 - 3.0).abs() < 1e-9);
+        assert_eq!(result[1].0, labels(&[("job", "web")]));
+        assert!((result[1].1 - 0.2).abs() < 1e-9);
+
+        let result = instant("topk(1, http_requests_total)", 300_000).await;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].1, 600.0);
+    }
+
+    #[tokio::test]
+    async fn test_binary_and_histogram() {
+        let result = instant(
+            r#"http_requests_total / on(job) group_left sum by (job) (http_requests_total)"#,
+            300_000,
+        )
+        .await;
+        assert_eq!(result.len(), 3);
+        assert!((result[0].1 - 1.0 / 3.0).abs() < 1e-9);
+
+        let result = instant("http_requests_total > 300", 300_000).await;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].0["__name__"], "http_requests_total");
+        assert_eq!(result[0].1, 600.0);
+
+        let result = instant(
+            r#"http_requests_total unless http_requests_total{job="api"}"#,
+            300_000,
+        )
+        .await;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].0["instance"], "c");
+
+        // 50 of 100 observations are <= 0.1, 80 are <= 0.5
+        let result = instant("histogram_quantile(0.7, latency_bucket)", 150_000).await;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].0, PromLabels::new());
+        assert!((result[0].1 - (0.1 + 0.4 * 20.0 / 30.0)).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_range_query() {
+        let req = PromQueryRequest::range(
+            r#"http_requests_total{instance="c"} * 2"#.to_string(),
+            0,
+            60_000,
+            30_000,
+        );
+        assert_eq!(
+            query(req).await,
+            PromQueryData::Matrix(vec![PromSeries {
+                metric: labels(&[("instance", "c"), ("job", "web")]),
+                values: vec![
+                    PromPoint::new(0, 0.0),
+                    PromPoint::new(30_000, 12.0),
+                    PromPoint::new(60_000, 24.0)
+                ],
+            }])
+        );
+
+        let req = PromQueryRequest::instant("1 + 2".to_string(), 1000);
+        assert_eq!(
+            query(req).await,
+            PromQueryData::Scalar(PromPoint::new(1000, 3.0))
+        );
+
+        let req = PromQueryRequest::range("time()".to_string(), 0, 30_000, 15_000);
+        assert_eq!(
+            query(req).await,
+            PromQueryData::Matrix(vec![PromSeries {
+                metric: PromLabels::new(),
+                values: vec![
+                    PromPoint::new(0, 0.0),
+                    PromPoint::new(15_000, 15.0),
+                    PromPoint::new(30_000, 30.0)
+                ],
+            }])
+        );
+    }
+
+    #[test]
+    fn test_plan_query() {
+        let req = PromQueryRequest::range(
+            r#"sum by (job) (rate(http_requests_total{job=~"a.*"}[5m] offset 1m))"#.to_string(),
+            0,
+            600_000,
+            60_000,
+        );
+        let expr = parser::parse(&req.query).unwrap();
+        let PromPlan::Vector { plan, labels } =
+            plan_query(&expr, &selected_tables(&expr), &req).unwrap()
+        else {
+            panic!("expected a vector");
+        };
+        assert_eq!(labels, vec!["job"]);
+        let text = plan.display_indent().to_string();
+        assert!(
+            text.contains("rate(__time, __value, __step, Int64(300000))"),
+            "{text}"
+        );
+        assert!(text.contains("Utf8(\"^(?:a.*)$\")"), "{text}");
+        assert!(text.contains("CrossJoin"), "{text}");
+        // The metric name is matched with the table name.
+        assert!(!text.contains("latency_bucket"), "{text}");
+
+        let req = PromQueryRequest::instant("http_requests_total[1m]".to_string(), 60_000);
+        let expr = parser::parse(&req.query).unwrap();
+        let plan = plan_query(&expr, &selected_tables(&expr), &req).unwrap();
+        assert!(matches!(plan, PromPlan::Samples { .. }));
+
+        let req = PromQueryRequest::instant("1 > 2".to_string(), 0);
+        let expr = parser::parse(&req.query).unwrap();
+        assert!(plan_query(&expr, &selected_tables(&expr), &req).is_err());
+    }
+}
@@ -1,10 +1,12 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use async_trait::async_trait;
 use bytes::Bytes;
 use coordinator::service::CoordinatorRef;
 use datafusion::arrow::datatypes::ToByteSlice;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::logical_expr::LogicalPlan;
 use futures::future::join_all;
 use meta::error::MetaError;
 use meta::model::MetaClientRef;
@@ -21,14 +23,20 @@ use protos::prompb::prometheus::{
 use protos::FieldValue;
 use regex::Regex;
 use snafu::ResultExt;
+use spi::query::logical_planner::{Plan, QueryPlan};
 use spi::server::dbms::DBMSRef;
-use spi::server::prom::PromRemoteServer;
+use spi::server::prom::{
+    PromLabels, PromMetadataRequest, PromQueryData, PromQueryRequest, PromRemoteServer,
+};
 use spi::service::protocol::{Context, Query, QueryHandle};
 use spi::{MetaSnafu, QueryError, QueryResult, SnappySnafu};
 use tokio::task;
 use trace::span_ext::SpanExt;
 use trace::{debug, warn, Span, SpanContext};
 
+use super::promql::ast::{LabelMatcher, MatchOp};
+use super::promql::parser;
+use super::promql::planner::{self, MetricTable, SelectedTables};
 use super::time_series::writer::WriterBuilder;
 use super::{METRIC_NAME_LABEL, METRIC_SAMPLE_COLUMN_NAME};
 use crate::prom::DEFAULT_PROM_TABLE_NAME;
@@ -112,6 +120,110 @@ impl PromRemoteServer for PromRemoteSqlServer {
 
         Ok(lines)
     }
+
+    async fn query(
+        &self,
+        ctx: &Context,
+        req: PromQueryRequest,
+        span_ctx: Option<&SpanContext>,
+    ) -> QueryResult<PromQueryData> {
+        let span = Span::from_context("process promql query", span_ctx);
+        debug!("Received promql query: {:?}", req);
+        let context = self.query_context(ctx, &span).await?;
+
+        let expr = parser::parse(&req.query)?;
+        let mut selectors = vec![];
+        expr.walk_selectors(&mut |selector, _| selectors.push(selector.matchers.clone()));
+        let tables = context.select_tables(selectors).await?;
+
+        let plan = planner::plan_query(&expr, &tables, &req)?;
+        let batches = match plan.logical_plan() {
+            Some(logical_plan) => context.execute(logical_plan.clone(), &req.query).await?,
+            None => vec![],
+        };
+        plan.query_data(&batches, &req)
+    }
+
+    async fn series(
+        &self,
+        ctx: &Context,
+        req: PromMetadataRequest,
+        span_ctx: Option<&SpanContext>,
+    ) -> QueryResult<Vec<PromLabels>> {
+        if req.matchers.is_empty() {
+            return Err(QueryError::InvalidPromQL {
+                err: "no match[] parameter provided".to_string(),
+            });
+        }
+        let span = Span::from_context("process prom series", span_ctx);
+        let context = self.query_context(ctx, &span).await?;
+        let labels = context
+            .select_series(&req)
+            .await?
+            .into_iter()
+            .collect::<BTreeSet<_>>();
+        Ok(labels.into_iter().collect())
+    }
+
+    async fn label_names(
+        &self,
+        ctx: &Context,
+        req: PromMetadataRequest,
+        span_ctx: Option<&SpanContext>,
+    ) -> QueryResult<Vec<String>> {
+        let span = Span::from_context("process prom label names", span_ctx);
+        let context = self.query_context(ctx, &span).await?;
+
+        let mut names = BTreeSet::new();
+        if req.matchers.is_empty() {
+            names.insert(METRIC_NAME_LABEL.to_string());
+            for table in context.tables()? {
+                for column in table.columns().iter().filter(|c| c.column_type.is_tag()) {
+                    names.insert(column.name.clone());
+                }
+            }
+        } else {
+            for labels in context.select_series(&req).await? {
+                names.extend(labels.into_keys());
+            }
+        }
+        Ok(names.into_iter().collect())
+    }
+
+    async fn label_values(
+        &self,
+        ctx: &Context,
+        label: &str,
+        req: PromMetadataRequest,
+        span_ctx: Option<&SpanContext>,
+    ) -> QueryResult<Vec<String>> {
+        let span = Span::from_context("process prom label values", span_ctx);
+        let context = self.query_context(ctx, &span).await?;
+
+        let mut values = BTreeSet::new();
+        if !req.matchers.is_empty() {
+            for mut labels in context.select_series(&req).await? {
+                values.extend(labels.remove(label));
+            }
+        } else if label == METRIC_NAME_LABEL {
+            values.extend(context.tables()?.into_iter().map(|t| t.name.to_string()));
+        } else {
+            let label = label.to_string();
+            for table in context.tables()? {
+                if !table.column(&label).is_some_and(|c| c.column_type.is_tag()) {
+                    continue;
+                }
+                let table = context.metric_table(&table).await?;
+                let plan = planner::plan_label_values(&table, &label, req.start, req.end)?;
+                let batches = context.execute(plan, &label).await?;
+                let label_sets = planner::read_label_sets(&batches, std::slice::from_ref(&label))?;
+                for mut labels in label_sets {
+                    values.extend(labels.remove(&label));
+                }
+            }
+        }
+        Ok(values.into_iter().collect())
+    }
 }
 
 impl PromRemoteSqlServer {
@@ -123,6 +235,28 @@ impl PromRemoteSqlServer {
         }
     }
 
+    async fn query_context<'a>(
+        &'a self,
+        ctx: &'a Context,
+        span: &Span,
+    ) -> QueryResult<PromQueryContext<'a>> {
+        let meta = self
+            .coord
+            .meta_manager()
+            .tenant_meta(ctx.tenant())
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: ctx.tenant().to_string(),
+            })
+            .context(MetaSnafu)?;
+        Ok(PromQueryContext {
+            db: &self.db,
+            ctx,
+            meta,
+            span_ctx: span.context(),
+        })
+    }
+
     async fn deserialize_read_request(&self, req: Bytes) -> QueryResult<ReadRequest> {
         let mut decompressed = Vec::new();
         let compressed = req.to_byte_slice();
@@ -364,6 +498,160 @@ fn build_sql_with_table(
     Ok(result)
 }
 
+/// Returns the schemas of the tables whose name is matched by the `__name__` matchers.
+fn match_tables(
+    ctx: &Context,
+    meta: &MetaClientRef,
+    matchers: &[LabelMatcher],
+) -> QueryResult<Vec<TskvTableSchemaRef>> {
+    let name_matchers = matchers
+        .iter()
+        .filter(|m| m.name == METRIC_NAME_LABEL)
+        .collect::<Vec<_>>();
+
+    let table_names = match name_matchers.iter().find(|m| m.op == MatchOp::Equal) {
+        Some(m) => vec![m.value.clone()],
+        None => meta.list_tables(ctx.database()).context(MetaSnafu)?,
+    };
+
+    let mut tables = Vec::with_capacity(table_names.len());
+    for table_name in table_names {
+        let mut matched = true;
+        for m in name_matchers.iter() {
+            matched &= planner::label_matches(m, &table_name)?;
+        }
+        if !matched {
+            continue;
+        }
+
+        match meta.get_tskv_table_schema(ctx.database(), &table_name) {
+            Ok(Some(table)) => tables.push(table),
+            Ok(None) => {}
+            Err(_) => {
+                warn!(
+                    "The table {} may have just been dropped, or it may be a bug.",
+                    table_name
+                );
+            }
+        }
+    }
+
+    Ok(tables)
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Plans and executes the PromQL queries on the tables of the current database.
+struct PromQueryContext<'a> {
+    db: &'a DBMSRef,
+    ctx: &'a Context,
+    meta: MetaClientRef,
+    span_ctx: Option<SpanContext>,
+}
+
+impl PromQueryContext<'_> {
+    fn tables(&self) -> QueryResult<Vec<TskvTableSchemaRef>> {
+        match_tables(self.ctx, &self.meta, &[])
+    }
+
+    /// Selects the tables of the label matchers of every selector,
+    /// the tables without samples are skipped.
+    async fn select_tables(
+        &self,
+        selectors: Vec<Vec<LabelMatcher>>,
+    ) -> QueryResult<SelectedTables> {
+        let mut scans = HashMap::<String, MetricTable>::new();
+        let mut selected = SelectedTables::with_capacity(selectors.len());
+        for matchers in selectors {
+            if selected.contains_key(&matchers) {
+                continue;
+            }
+            let mut tables = vec![];
+            for table in match_tables(self.ctx, &self.meta, &matchers)? {
+                if table.column(METRIC_SAMPLE_COLUMN_NAME).is_none() {
+                    continue;
+                }
+                let table = match scans.get(&table.name) {
+                    Some(table) => table.clone(),
+                    None => {
+                        let metric_table = self.metric_table(&table).await?;
+                        scans.insert(table.name.clone(), metric_table.clone());
+                        metric_table
+                    }
+                };
+                tables.push(table);
+            }
+            selected.insert(matchers, tables);
+        }
+        Ok(selected)
+    }
+
+    /// Selects the label sets of the series matched by any of the selectors of a metadata request.
+    async fn select_series(&self, req: &PromMetadataRequest) -> QueryResult<Vec<PromLabels>> {
+        let mut result = vec![];
+        for matcher in req.matchers.iter() {
+            let selector = parser::parse_selector(matcher)?;
+            let tables = self.select_tables(vec![selector.matchers.clone()]).await?;
+            let (plan, labels) =
+                planner::plan_series(&tables, &selector.matchers, req.start, req.end)?;
+            let batches = self.execute(plan, matcher).await?;
+            result.append(&mut planner::read_label_sets(&batches, &labels)?);
+        }
+        Ok(result)
+    }
+
+    async fn metric_table(&self, table: &TskvTableSchemaRef) -> QueryResult<MetricTable> {
+        let tags = table
+            .columns()
+            .iter()
+            .filter(|c| c.column_type.is_tag())
+            .map(|c| c.name.clone())
+            .collect();
+        Ok(MetricTable {
+            name: table.name.to_string(),
+            tags,
+            scan: self.plan_scan(&table.name).await?,
+        })
+    }
+
+    /// Plans the scan of all the columns of the table by the sql planner,
+    /// which checks the privileges of the user.
+    async fn plan_scan(&self, table_name: &str) -> QueryResult<LogicalPlan> {
+        let sql = format!("SELECT * FROM {}", quote_identifier(table_name));
+        let query = Query::new(self.ctx.clone(), sql);
+        let query_state_machine = self
+            .db
+            .build_query_state_machine(query, self.span_ctx.as_ref())
+            .await?;
+        match self.db.build_logical_plan(query_state_machine).await? {
+            Some(Plan::Query(QueryPlan { df_plan, .. })) => Ok(df_plan),
+            _ => Err(QueryError::Internal {
+                reason: format!("unexpected plan of the scan of {}", table_name),
+            }),
+        }
+    }
+
+    async fn execute(&self, plan: LogicalPlan, query: &str) -> QueryResult<Vec<RecordBatch>> {
+        debug!("Prepare to execute promql plan: {}", plan.display_indent());
+        let query = Query::new(self.ctx.clone(), query.to_string());
+        let query_state_machine = self
+            .db
+            .build_query_state_machine(query, self.span_ctx.as_ref())
+            .await?;
+        let plan = Plan::Query(QueryPlan {
+            df_plan: plan,
+            is_tag_scan: false,
+        });
+        let result = self
+            .db
+            .execute_logical_plan(plan, query_state_machine)
+            .await?;
+        result.result().chunk_result().await
+    }
+}
+
 /// Convert the execution result of query to TimeSeries list of prometheus
 async fn transform_time_series(
     query_handle: QueryHandle,
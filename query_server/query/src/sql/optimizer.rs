@@ -15,12 +15,17 @@ use trace::span_ext::SpanExt;
 use super::logical::optimizer::{DefaultLogicalOptimizer, LogicalOptimizer};
 use super::physical::optimizer::PhysicalOptimizer;
 use super::physical::planner::DefaultPhysicalPlanner;
+use crate::data_source::result_cache::ResultCacheRef;
+use crate::extension::physical::optimizer_rule::add_result_cache::{
+    result_cache_key, AddResultCache,
+};
 use crate::extension::physical::optimizer_rule::add_traced_proxy::AddTracedProxy;
 
 pub struct CascadeOptimizer {
     logical_optimizer: Arc<dyn LogicalOptimizer + Send + Sync>,
     physical_planner: Arc<dyn PhysicalPlanner + Send + Sync>,
     physical_optimizer: Arc<dyn PhysicalOptimizer + Send + Sync>,
+    result_cache: Option<ResultCacheRef>,
 }
 
 #[async_trait]
@@ -90,10 +95,27 @@ impl Optimizer for CascadeOptimizer {
                 })?
         };
 
+        let cached_plan = match &self.result_cache {
+            Some(result_cache) => {
+                let key = result_cache_key(
+                    &optimized_logical_plan,
+                    session.tenant(),
+                    session.default_database(),
+                );
+                AddResultCache::new(
+                    key,
+                    session.tenant(),
+                    session.default_database(),
+                    result_cache.clone(),
+                )
+                .optimize(optimized_physical_plan, &ConfigOptions::default())?
+            }
+            None => optimized_physical_plan,
+        };
+
         let traced_plan = {
             let span = session.get_child_span("add traced proxy");
-            AddTracedProxy::new(span.context())
-                .optimize(optimized_physical_plan, &ConfigOptions::default())?
+            AddTracedProxy::new(span.context()).optimize(cached_plan, &ConfigOptions::default())?
         };
 
         debug!(
@@ -110,6 +132,7 @@ pub struct CascadeOptimizerBuilder {
     logical_optimizer: Option<Arc<dyn LogicalOptimizer + Send + Sync>>,
     physical_planner: Option<Arc<dyn PhysicalPlanner + Send + Sync>>,
     physical_optimizer: Option<Arc<dyn PhysicalOptimizer + Send + Sync>>,
+    result_cache: Option<ResultCacheRef>,
 }
 
 impl CascadeOptimizerBuilder {
@@ -137,6 +160,11 @@ impl CascadeOptimizerBuilder {
         self
     }
 
+    pub fn with_result_cache(mut self, result_cache: ResultCacheRef) -> Self {
+        self.result_cache = Some(result_cache);
+        self
+    }
+
     pub fn build(self) -> CascadeOptimizer {
         let default_logical_optimizer = Arc::new(DefaultLogicalOptimizer::default());
         let default_physical_planner = Arc::new(DefaultPhysicalPlanner::default());
@@ -151,6 +179,7 @@ impl CascadeOptimizerBuilder {
             logical_optimizer,
             physical_planner,
             physical_optimizer,
+            result_cache: self.result_cache,
         }
     }
 }
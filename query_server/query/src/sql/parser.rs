@@ -17,13 +17,13 @@ use models::meta_data::{NodeId, ReplicationSetId, VnodeId};
 use snafu::ResultExt;
 use spi::query::ast::{
     self, parse_string_value, Action, AlterDatabase, AlterTable, AlterTableAction, AlterTenant,
-    AlterTenantOperation, AlterUser, AlterUserOperation, ChecksumGroup, ColumnOption,
-    CompactDatabase, CompactVnode, CopyIntoLocation, CopyIntoTable, CopyTarget, CopyVnode,
-    CreateDatabase, CreateRole, CreateStream, CreateTable, CreateTenant, CreateUser,
+    AlterTenantOperation, AlterUser, AlterUserOperation, BackupDatabase, ChecksumGroup,
+    ColumnOption, CompactDatabase, CompactVnode, CopyIntoLocation, CopyIntoTable, CopyTarget,
+    CopyVnode, CreateDatabase, CreateRole, CreateStream, CreateTable, CreateTenant, CreateUser,
     DatabaseConfig, DatabaseOptions, DescribeDatabase, DescribeTable, DropDatabaseObject,
     DropGlobalObject, DropTenantObject, DropVnode, Explain, ExtStatement, GrantRevoke, MoveVnode,
-    OutputMode, Privilege, RecoverDatabase, RecoverTenant, ShowSeries, ShowTagBody, ShowTagValues,
-    Trigger, UriLocation, With,
+    OutputMode, Privilege, RecoverDatabase, RecoverTenant, RestoreDatabase, ShowSeries,
+    ShowSeriesCardinality, ShowTagBody, ShowTagValues, Trigger, UriLocation, With,
 };
 use spi::query::logical_planner::{DatabaseObjectType, GlobalObjectType, TenantObjectType};
 use spi::query::parser::Parser as CnosdbParser;
@@ -138,6 +138,24 @@ enum CnosKeyWord {
     STRICT_WRITE,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     MAX_CACHE_READERS,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    ROLLUP,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    ROLLUPS,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    REENCODE,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    BACKUP,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    RESTORE,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    INCREMENTAL,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    UNTIL,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    CARDINALITY,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    EXACT,
 }
 
 impl FromStr for CnosKeyWord {
@@ -192,6 +210,15 @@ impl FromStr for CnosKeyWord {
             "WAL_SYNC" => Ok(CnosKeyWord::WAL_SYNC),
             "STRICT_WRITE" => Ok(CnosKeyWord::STRICT_WRITE),
             "MAX_CACHE_READERS" => Ok(CnosKeyWord::MAX_CACHE_READERS),
+            "ROLLUP" => Ok(CnosKeyWord::ROLLUP),
+            "ROLLUPS" => Ok(CnosKeyWord::ROLLUPS),
+            "REENCODE" => Ok(CnosKeyWord::REENCODE),
+            "BACKUP" => Ok(CnosKeyWord::BACKUP),
+            "RESTORE" => Ok(CnosKeyWord::RESTORE),
+            "INCREMENTAL" => Ok(CnosKeyWord::INCREMENTAL),
+            "UNTIL" => Ok(CnosKeyWord::UNTIL),
+            "CARDINALITY" => Ok(CnosKeyWord::CARDINALITY),
+            "EXACT" => Ok(CnosKeyWord::EXACT),
             _ => Err(ParserError::ParserError(format!(
                 "fail parse {} to CnosKeyWord",
                 s
@@ -343,6 +370,14 @@ impl<'a> ExtParser<'a> {
                                 self.parser.next_token();
                                 self.parse_replica()
                             }
+                            CnosKeyWord::BACKUP => {
+                                self.parser.next_token();
+                                self.parse_backup()
+                            }
+                            CnosKeyWord::RESTORE => {
+                                self.parser.next_token();
+                                self.parse_restore()
+                            }
                             _ => Ok(ExtStatement::SqlStatement(Box::new(
                                 self.parser.parse_statement()?,
                             ))),
@@ -373,10 +408,28 @@ impl<'a> ExtParser<'a> {
             self.parse_show_tables()
         } else if self.parse_cnos_keyword(CnosKeyWord::DATABASES) {
             self.parse_show_databases()
+        } else if self.parse_cnos_keyword(CnosKeyWord::EXACT) {
+            if self.parse_cnos_keyword(CnosKeyWord::SERIES) {
+                self.expect_cnos_keyword(CnosKeyWord::CARDINALITY)?;
+                self.parse_show_series_cardinality(true, false)
+            } else if self.parse_cnos_keyword(CnosKeyWord::TAG) {
+                self.parser.expect_keyword(Keyword::KEY)?;
+                self.expect_cnos_keyword(CnosKeyWord::CARDINALITY)?;
+                self.parse_show_series_cardinality(true, true)
+            } else {
+                self.expected("SERIES or TAG", self.parser.peek_token())
+            }
         } else if self.parse_cnos_keyword(CnosKeyWord::SERIES) {
-            self.parse_show_series()
+            if self.parse_cnos_keyword(CnosKeyWord::CARDINALITY) {
+                self.parse_show_series_cardinality(false, false)
+            } else {
+                self.parse_show_series()
+            }
         } else if self.parse_cnos_keyword(CnosKeyWord::TAG) {
-            if self.parser.parse_keyword(Keyword::VALUES) {
+            if self.parser.parse_keyword(Keyword::KEY) {
+                self.expect_cnos_keyword(CnosKeyWord::CARDINALITY)?;
+                self.parse_show_series_cardinality(false, true)
+            } else if self.parser.parse_keyword(Keyword::VALUES) {
                 self.parse_show_tag_values()
             } else {
                 self.expected("VALUES", self.parser.peek_token())
@@ -392,6 +445,8 @@ impl<'a> ExtParser<'a> {
             Ok(ExtStatement::ShowStreams(ast::ShowStreams { verbose }))
         } else if self.parse_cnos_keyword(CnosKeyWord::REPLICAS) {
             self.parse_show_replicas()
+        } else if self.parse_cnos_keyword(CnosKeyWord::ROLLUPS) {
+            Ok(ExtStatement::ShowRollups)
         } else {
             parser_err!(format!("nonsupport: {}", self.parser.peek_token()))
         }
@@ -490,6 +545,32 @@ impl<'a> ExtParser<'a> {
         })))
     }
 
+    fn parse_show_series_cardinality(
+        &mut self,
+        exact: bool,
+        tag_keys: bool,
+    ) -> Result<ExtStatement> {
+        let database_name = self.parse_on_database()?;
+        let table = if self.parser.parse_keyword(Keyword::FROM) {
+            Some(self.parser.parse_identifier()?)
+        } else {
+            None
+        };
+        let group_by = if !tag_keys && self.parser.parse_keywords(&[Keyword::GROUP, Keyword::BY]) {
+            Some(self.parser.parse_identifier()?)
+        } else {
+            None
+        };
+
+        Ok(ExtStatement::ShowSeriesCardinality(ShowSeriesCardinality {
+            database_name,
+            table,
+            group_by,
+            exact,
+            tag_keys,
+        }))
+    }
+
     fn parse_show_tag_values(&mut self) -> Result<ExtStatement> {
         let database_name = self.parse_on_database()?;
         self.parser.expect_keyword(Keyword::FROM)?;
@@ -606,8 +687,16 @@ impl<'a> ExtParser<'a> {
         } else if self.parser.parse_keyword(Keyword::RENAME) {
             let alter_tbl = self.parse_alter_table_rename(table_name)?;
             Ok(ExtStatement::AlterTable(alter_tbl))
+        } else if self.parse_cnos_keyword(CnosKeyWord::REENCODE) {
+            Ok(ExtStatement::AlterTable(AlterTable {
+                table_name,
+                alter_action: AlterTableAction::Reencode,
+            }))
         } else {
-            self.expected("ADD or ALTER or DROP or RENAME", self.parser.peek_token())
+            self.expected(
+                "ADD or ALTER or DROP or RENAME or REENCODE",
+                self.parser.peek_token(),
+            )
         }
     }
 
@@ -1291,6 +1380,60 @@ impl<'a> ExtParser<'a> {
         }))
     }
 
+    /// Parse a SQL CREATE ROLLUP statement
+    ///
+    /// CREATE ROLLUP [IF NOT EXISTS] [db.]name
+    ///     INTERVAL = '1m'
+    ///     [WATERMARK = '10s']
+    ///     [TTL = '90d']
+    /// AS INSERT INTO target SELECT ... FROM (
+    ///     SELECT time_window(time, interval '1 minute') AS window, ... FROM source
+    ///     GROUP BY time_window(time, interval '1 minute'), ...);
+    fn parse_create_rollup(&mut self) -> Result<ExtStatement> {
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+
+        let name = self.parser.parse_object_name()?;
+
+        let mut interval = None;
+        let mut watermark = None;
+        let mut ttl = None;
+
+        loop {
+            if self.parser.parse_keyword(Keyword::INTERVAL) {
+                self.parser.expect_token(&Token::Eq)?;
+                interval = Some(self.parse_string_value()?);
+            } else if self.parse_cnos_keyword(CnosKeyWord::WATERMARK) {
+                self.parser.expect_token(&Token::Eq)?;
+                watermark = Some(self.parse_string_value()?);
+            } else if self.parse_cnos_keyword(CnosKeyWord::TTL) {
+                self.parser.expect_token(&Token::Eq)?;
+                ttl = Some(self.parse_string_value()?);
+            } else {
+                break;
+            }
+        }
+
+        let interval = match interval {
+            Some(interval) => interval,
+            None => return self.expected("INTERVAL", self.parser.peek_token()),
+        };
+
+        self.parser.expect_keyword(Keyword::AS)?;
+        self.parser.expect_keyword(Keyword::INSERT)?;
+        let statement = Box::new(self.parser.parse_insert()?);
+
+        Ok(ExtStatement::CreateRollup(ast::CreateRollup {
+            if_not_exists,
+            name,
+            interval,
+            watermark,
+            ttl,
+            statement,
+        }))
+    }
+
     /// Parse a SQL CREATE statement
     fn parse_create(&mut self) -> Result<ExtStatement> {
         // Currently only supports the creation of external tables
@@ -1311,6 +1454,8 @@ impl<'a> ExtParser<'a> {
             self.parse_create_role()
         } else if self.parse_cnos_keyword(CnosKeyWord::STREAM) {
             self.parse_create_stream()
+        } else if self.parse_cnos_keyword(CnosKeyWord::ROLLUP) {
+            self.parse_create_rollup()
         } else {
             self.expected("an object type after CREATE", self.parser.peek_token())
         }
@@ -1513,6 +1658,56 @@ impl<'a> ExtParser<'a> {
         Ok(ast)
     }
 
+    /// BACKUP DATABASE <name> TO '<location>' [INCREMENTAL] [CONNECTION = (...)]
+    fn parse_backup(&mut self) -> Result<ExtStatement> {
+        self.parser.expect_keyword(Keyword::DATABASE)?;
+        let database_name = self.parser.parse_identifier()?;
+        self.parser.expect_keyword(Keyword::TO)?;
+        let path = self.parser.parse_literal_string()?;
+        let incremental = self.parse_cnos_keyword(CnosKeyWord::INCREMENTAL);
+        let connection_options = self.parse_connection_options()?;
+
+        Ok(ExtStatement::BackupDatabase(BackupDatabase {
+            database_name,
+            location: UriLocation {
+                path,
+                connection_options,
+            },
+            incremental,
+        }))
+    }
+
+    /// RESTORE DATABASE <name> FROM '<location>' [UNTIL '<time>'] [CONNECTION = (...)]
+    fn parse_restore(&mut self) -> Result<ExtStatement> {
+        self.parser.expect_keyword(Keyword::DATABASE)?;
+        let database_name = self.parser.parse_identifier()?;
+        self.parser.expect_keyword(Keyword::FROM)?;
+        let path = self.parser.parse_literal_string()?;
+        let until = if self.parse_cnos_keyword(CnosKeyWord::UNTIL) {
+            Some(self.parser.parse_literal_string()?)
+        } else {
+            None
+        };
+        let connection_options = self.parse_connection_options()?;
+
+        Ok(ExtStatement::RestoreDatabase(RestoreDatabase {
+            database_name,
+            location: UriLocation {
+                path,
+                connection_options,
+            },
+            until,
+        }))
+    }
+
+    fn parse_connection_options(&mut self) -> Result<Vec<SqlOption>> {
+        if self.parser.parse_keyword(Keyword::CONNECTION) {
+            self.parse_options()
+        } else {
+            Ok(vec![])
+        }
+    }
+
     /// Parse a SQL DROP statement
     fn parse_drop(&mut self) -> Result<ExtStatement> {
         let ast = if self.parser.parse_keyword(Keyword::TABLE) {
@@ -1574,9 +1769,13 @@ impl<'a> ExtParser<'a> {
             let if_exist = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
             let name = self.parser.parse_identifier()?;
             ExtStatement::DropStream(ast::DropStream { if_exist, name })
+        } else if self.parse_cnos_keyword(CnosKeyWord::ROLLUP) {
+            let if_exist = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+            let name = self.parser.parse_object_name()?;
+            ExtStatement::DropRollup(ast::DropRollup { if_exist, name })
         } else {
             return self.expected(
-                "TABLE,DATABASE,TENANT,USER,ROLE,VNODE,STREAM after DROP",
+                "TABLE,DATABASE,TENANT,USER,ROLE,VNODE,STREAM,ROLLUP after DROP",
                 self.parser.peek_token(),
             );
         };
@@ -2018,6 +2217,66 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_backup_restore() {
+        let sql = "backup database test_db to 's3://bucket/backup' incremental connection = (region = 'us-east-1')";
+        match parse_sql(sql) {
+            ExtStatement::BackupDatabase(BackupDatabase {
+                database_name,
+                location,
+                incremental,
+            }) => {
+                assert_eq!(database_name.to_string(), "test_db");
+                assert_eq!(location.path, "s3://bucket/backup");
+                assert_eq!(location.connection_options.len(), 1);
+                assert!(incremental);
+            }
+            _ => panic!("failed"),
+        }
+
+        let sql = "backup database test_db to 'file:///tmp/backup'";
+        match parse_sql(sql) {
+            ExtStatement::BackupDatabase(BackupDatabase {
+                location,
+                incremental,
+                ..
+            }) => {
+                assert_eq!(location.path, "file:///tmp/backup");
+                assert!(location.connection_options.is_empty());
+                assert!(!incremental);
+            }
+            _ => panic!("failed"),
+        }
+
+        let sql = "restore database test_db2 from 'file:///tmp/backup'";
+        match parse_sql(sql) {
+            ExtStatement::RestoreDatabase(RestoreDatabase {
+                database_name,
+                location,
+                until,
+            }) => {
+                assert_eq!(database_name.to_string(), "test_db2");
+                assert_eq!(location.path, "file:///tmp/backup");
+                assert!(until.is_none());
+            }
+            _ => panic!("failed"),
+        }
+
+        let sql = "restore database test_db from 's3://bucket/backup' until '2026-01-01T00:00:00Z' connection = (region = 'us-east-1')";
+        match parse_sql(sql) {
+            ExtStatement::RestoreDatabase(RestoreDatabase {
+                location, until, ..
+            }) => {
+                assert_eq!(location.connection_options.len(), 1);
+                assert_eq!(until.as_deref(), Some("2026-01-01T00:00:00Z"));
+            }
+            _ => panic!("failed"),
+        }
+
+        assert!(ExtParser::parse_sql("backup database test_db 'file:///tmp/backup'").is_err());
+        assert!(ExtParser::parse_sql("restore database test_db to 'file:///tmp/backup'").is_err());
+    }
+
     #[test]
     fn test_recover() {
         let sql = "recover database test_db";
@@ -2381,6 +2640,8 @@ mod tests {
             ALTER TABLE m DROP f;
             ALTER TABLE m ALTER f SET CODEC(DEFAULT);
             ALTER TABLE m ALTER TIME SET CODEC(NULL);
+            ALTER TABLE m ALTER f SET CODEC(AUTO);
+            ALTER TABLE m REENCODE;
         "#;
         let statement = ExtParser::parse_sql(sql).unwrap();
         let statement: Vec<AlterTable> = statement
@@ -2440,6 +2701,17 @@ mod tests {
                         column_name: Ident::from("TIME"),
                         encoding: Encoding::Null
                     }
+                },
+                AlterTable {
+                    table_name: ObjectName(vec![Ident::from("m")]),
+                    alter_action: AlterTableAction::AlterColumnEncoding {
+                        column_name: Ident::from("f"),
+                        encoding: Encoding::Auto
+                    }
+                },
+                AlterTable {
+                    table_name: ObjectName(vec![Ident::from("m")]),
+                    alter_action: AlterTableAction::Reencode
                 }
             ]
         );
@@ -2565,6 +2837,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_show_series_cardinality() {
+        let sql = "show series cardinality from air group by station";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ShowSeriesCardinality(ShowSeriesCardinality {
+                database_name: None,
+                table: Some(Ident::new("air")),
+                group_by: Some(Ident::new("station")),
+                exact: false,
+                tag_keys: false,
+            })
+        );
+
+        let sql = "show exact series cardinality on public";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ShowSeriesCardinality(ShowSeriesCardinality {
+                database_name: Some(Ident::new("public")),
+                table: None,
+                group_by: None,
+                exact: true,
+                tag_keys: false,
+            })
+        );
+
+        let sql = "show exact tag key cardinality from air";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ShowSeriesCardinality(ShowSeriesCardinality {
+                database_name: None,
+                table: Some(Ident::new("air")),
+                group_by: None,
+                exact: true,
+                tag_keys: true,
+            })
+        );
+
+        assert!(
+            ExtParser::parse_sql("show tag key cardinality from air group by station").is_err()
+        );
+        assert!(ExtParser::parse_sql("show exact cardinality").is_err());
+    }
+
     #[test]
     fn test_parse_copy_into_table_no_error() {
         let sql = r#"
@@ -2683,6 +3002,50 @@ mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn test_create_rollup() {
+        let statement = parse_sql(
+            "create rollup if not exists db1.cpu_1m interval = '1m' watermark = '10s' ttl = '90d' \
+            as insert into cpu_1m select date_bin(interval '1 minute', time) as time, host, avg(usage) as usage \
+            from cpu group by date_bin(interval '1 minute', time), host;",
+        );
+
+        match statement {
+            ExtStatement::CreateRollup(s) => {
+                let ast::CreateRollup {
+                    if_not_exists,
+                    name,
+                    interval,
+                    watermark,
+                    ttl,
+                    statement,
+                } = s;
+
+                assert!(if_not_exists);
+                assert_eq!(name.to_string(), "db1.cpu_1m");
+                assert_eq!(interval, "1m");
+                assert_eq!(watermark, Some("10s".into()));
+                assert_eq!(ttl, Some("90d".into()));
+                assert!(matches!(statement.deref(), Statement::Insert { .. }));
+            }
+            _ => panic!("expect CreateRollup"),
+        }
+
+        assert!(ExtParser::parse_sql("create rollup r1 as insert into t select 1;").is_err());
+    }
+
+    #[test]
+    fn test_drop_and_show_rollups() {
+        let result = parse_sql("drop rollup if exists cpu_1m;");
+        let expected = ExtStatement::DropRollup(ast::DropRollup {
+            if_exist: true,
+            name: ObjectName(vec![Ident::new("cpu_1m")]),
+        });
+        assert_eq!(expected, result);
+
+        assert_eq!(parse_sql("show rollups;"), ExtStatement::ShowRollups);
+    }
+
     #[test]
     fn test_create_stream_table() {
         let statement = parse_sql(
@@ -25,6 +25,7 @@ use spi::QueryResult;
 use super::optimizer::PhysicalOptimizer;
 use crate::extension::physical::optimizer_rule::add_assert::AddAssertExec;
 use crate::extension::physical::optimizer_rule::add_sort::AddSortExec;
+use crate::extension::physical::optimizer_rule::push_down_spatial_filter::PushDownSpatialFilter;
 use crate::extension::physical::transform_rule::expand::ExpandPlanner;
 use crate::extension::physical::transform_rule::table_writer::TableWriterPlanner;
 use crate::extension::physical::transform_rule::tag_scan::TagScanPlanner;
@@ -115,6 +116,7 @@ impl Default for DefaultPhysicalPlanner {
             // given query plan; i.e. it only acts as a final gatekeeping rule.
             Arc::new(PipelineChecker::new()),
             // CnosDB
+            Arc::new(PushDownSpatialFilter::new()),
             Arc::new(AddAssertExec::new()),
             Arc::new(AddSortExec::new()),
         ];
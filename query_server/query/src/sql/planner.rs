@@ -6,10 +6,11 @@ use std::{iter, vec};
 
 use async_recursion::async_recursion;
 use async_trait::async_trait;
+use chrono::DateTime;
 use datafusion::arrow::datatypes::{DataType, SchemaRef, TimeUnit};
 use datafusion::arrow::error::ArrowError;
 use datafusion::common::parsers::CompressionTypeVariant;
-use datafusion::common::tree_node::TreeNode;
+use datafusion::common::tree_node::{TreeNode, VisitRecursion};
 use datafusion::common::{
     Column, DFField, DFSchema, OwnedTableReference, Result as DFResult, ToDFSchema,
 };
@@ -43,8 +44,8 @@ use datafusion::sql::parser::CreateExternalTable as AstCreateExternalTable;
 use datafusion::sql::planner::{object_name_to_table_reference, PlannerContext, SqlToRel};
 use datafusion::sql::sqlparser::ast::{
     Assignment, DataType as SQLDataType, Expr as SQLExpr, Expr as ASTExpr, Ident, ObjectName,
-    Offset, OrderByExpr, Query, SqlOption, Statement, TableAlias, TableFactor, TableWithJoins,
-    TimezoneInfo,
+    Offset, OrderByExpr, Query, SetExpr, SqlOption, Statement, TableAlias, TableFactor,
+    TableWithJoins, TimezoneInfo,
 };
 use datafusion::sql::sqlparser::parser::ParserError;
 use datafusion::sql::TableReference;
@@ -60,6 +61,7 @@ use models::gis::data_type::{Geometry, GeometryType};
 use models::object_reference::{Resolve, ResolvedTable};
 use models::oid::{Identifier, Oid};
 use models::schema::database_schema::{DatabaseConfigBuilder, DatabaseOptionsBuilder};
+use models::schema::rollup_info::RollupInfo;
 use models::schema::stream_table_schema::Watermark;
 use models::schema::tenant::Tenant;
 use models::schema::tskv_table_schema::{
@@ -68,6 +70,8 @@ use models::schema::tskv_table_schema::{
 use models::schema::{DEFAULT_CATALOG, TIME_FIELD_NAME};
 use models::utils::SeqIdGenerator;
 use models::{ColumnId, ValueType};
+use object_store::local::LocalFileSystem;
+use object_store::path::Path as ObjectStorePath;
 use object_store::ObjectStore;
 use snafu::ResultExt;
 use spi::query::ast;
@@ -84,19 +88,22 @@ use spi::query::ast::{
     ReplicaPromote as ASTReplicaPromote, ReplicaRemove as ASTReplicaRemove,
     ShowSeries as ASTShowSeries, ShowTagBody, ShowTagValues as ASTShowTagValues, UriLocation, With,
 };
+use spi::query::config::StreamTriggerInterval;
 use spi::query::datasource::{self, UriSchema};
 use spi::query::logical_planner::{
     normalize_sql_object_name_to_string, parse_connection_options,
     sql_option_to_alter_tenant_action, sql_options_to_map, sql_options_to_tenant_options,
     sql_options_to_user_options, unset_option_to_alter_tenant_action, AlterDatabase, AlterTable,
     AlterTableAction, AlterTenant, AlterTenantAction, AlterTenantAddUser, AlterTenantSetUser,
-    AlterUser, AlterUserAction, ChecksumGroup, CompactVnode, CopyOptions, CopyOptionsBuilder,
-    CopyVnode, CreateDatabase, CreateRole, CreateStreamTable, CreateTable, CreateTenant,
-    CreateUser, DDLPlan, DMLPlan, DatabaseObjectType, DeleteFromTable, DropDatabaseObject,
-    DropGlobalObject, DropTenantObject, DropVnode, FileFormatOptions, FileFormatOptionsBuilder,
+    AlterUser, AlterUserAction, BackupDatabase, BackupLocation, ChecksumGroup, CompactVnode,
+    CopyOptions, CopyOptionsBuilder, CopyVnode, CreateDatabase, CreateRole, CreateRollup,
+    CreateStream, CreateStreamTable, CreateTable, CreateTenant, CreateUser, DDLPlan, DMLPlan,
+    DatabaseObjectType, DeleteFromTable, DropDatabaseObject, DropGlobalObject, DropRollup,
+    DropStream, DropTenantObject, DropVnode, FileFormatOptions, FileFormatOptionsBuilder,
     GlobalObjectType, GrantRevoke, LogicalPlanner, MoveVnode, Plan, PlanWithPrivileges, QueryPlan,
     RecoverDatabase, RecoverTenant, ReplicaAdd, ReplicaDestory, ReplicaPromote, ReplicaRemove,
-    SYSPlan, TenantObjectType, TENANT_OPTION_LIMITER,
+    RestoreDatabase, SYSPlan, ShowSeriesCardinality, ShowStreams, TenantObjectType,
+    TENANT_OPTION_LIMITER,
 };
 use spi::query::session::SessionCtx;
 use spi::{
@@ -112,8 +119,11 @@ use utils::precision::Precision;
 use crate::data_source::source_downcast_adapter;
 use crate::data_source::stream::{get_event_time_column, get_watermark_delay};
 use crate::data_source::table_source::{TableHandle, TableSourceAdapter, TEMP_LOCATION_TABLE_NAME};
+use crate::extension::expr::expr_utils::find_exprs_in_exprs_deeply_nested;
+use crate::extension::expr::TIME_WINDOW;
 use crate::extension::logical::logical_plan_builder::LogicalPlanBuilderExt;
 use crate::extension::logical::plan_node::update::UpdateNode;
+use crate::extension::logical::utils::extract_stream_providers;
 use crate::metadata::{
     is_system_database, ContextProviderExtension, DatabaseSet, COLUMNS_COLUMN_NAME,
     COLUMNS_COLUMN_TYPE, COLUMNS_COMPRESSION_CODEC, COLUMNS_DATABASE_NAME, COLUMNS_DATA_TYPE,
@@ -213,6 +223,9 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                 .await
             }
             ExtStatement::ShowTagValues(stmt) => self.show_tag_values(*stmt, session),
+            ExtStatement::ShowSeriesCardinality(stmt) => {
+                self.show_series_cardinality_to_plan(stmt, session)
+            }
             ExtStatement::AlterTable(stmt) => self.alter_table_to_plan(stmt, session),
             ExtStatement::AlterTenant(stmt) => self.alter_tenant_to_plan(stmt).await,
             ExtStatement::AlterUser(stmt) => {
@@ -227,20 +240,19 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             ExtStatement::CompactVnode(stmt) => self.compact_vnode_to_plan(stmt),
             ExtStatement::CompactDatabase(stmt) => self.compact_database_to_plan(stmt),
             ExtStatement::ChecksumGroup(stmt) => self.checksum_group_to_plan(stmt),
-            ExtStatement::CreateStream(_) => Err(QueryError::NotImplemented {
-                err: "CreateStream Planner.".to_string(),
-            }),
-            ExtStatement::DropStream(_) => Err(QueryError::NotImplemented {
-                err: "DropStream Planner.".to_string(),
-            }),
-            ExtStatement::ShowStreams(_) => Err(QueryError::NotImplemented {
-                err: "ShowStreams Planner.".to_string(),
-            }),
+            ExtStatement::CreateStream(stmt) => self.create_stream_to_plan(stmt, session).await,
+            ExtStatement::DropStream(stmt) => self.drop_stream_to_plan(stmt, session),
+            ExtStatement::ShowStreams(stmt) => self.show_streams_to_plan(stmt, session),
+            ExtStatement::CreateRollup(stmt) => self.create_rollup_to_plan(stmt, session).await,
+            ExtStatement::DropRollup(stmt) => self.drop_rollup_to_plan(stmt, session),
+            ExtStatement::ShowRollups => self.show_rollups_to_plan(session),
             ExtStatement::CreateStreamTable(stmt) => {
                 self.create_stream_table_to_plan(stmt, session)
             }
             ExtStatement::RecoverTenant(stmt) => self.recovertenant_to_plan(stmt),
             ExtStatement::RecoverDatabase(stmt) => self.recoverdatabase_to_plan(stmt, session),
+            ExtStatement::BackupDatabase(stmt) => self.backup_database_to_plan(stmt, session),
+            ExtStatement::RestoreDatabase(stmt) => self.restore_database_to_plan(stmt, session),
             ExtStatement::ShowReplicas => self.show_replicas_to_plan(),
             ExtStatement::ReplicaDestory(stmt) => self.replica_destory_to_plan(stmt),
             ExtStatement::ReplicaAdd(stmt) => self.replica_add_to_plan(stmt),
@@ -763,6 +775,83 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         })
     }
 
+    fn backup_database_to_plan(
+        &self,
+        stmt: ast::BackupDatabase,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ast::BackupDatabase {
+            database_name,
+            location,
+            incremental,
+        } = stmt;
+        let db_name = normalize_ident(database_name);
+        let location = build_backup_location(location)?;
+
+        let mut privileges = vec![Privilege::TenantObject(
+            TenantObjectPrivilege::Database(DatabasePrivilege::Read, Some(db_name.clone())),
+            Some(*session.tenant_id()),
+        )];
+        if location.local {
+            privileges.push(Privilege::Global(GlobalPrivilege::System));
+        }
+        let plan = DDLPlan::BackupDatabase(BackupDatabase {
+            tenant_name: session.tenant().to_string(),
+            db_name,
+            location,
+            incremental,
+        });
+
+        Ok(PlanWithPrivileges {
+            plan: Plan::DDL(plan),
+            privileges,
+        })
+    }
+
+    fn restore_database_to_plan(
+        &self,
+        stmt: ast::RestoreDatabase,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ast::RestoreDatabase {
+            database_name,
+            location,
+            until,
+        } = stmt;
+
+        let until = until
+            .map(|time| {
+                DateTime::parse_from_rfc3339(&time)
+                    .ok()
+                    .and_then(|t| t.timestamp_nanos_opt())
+                    .ok_or_else(|| QueryError::Semantic {
+                        err: format!("Invalid time '{time}', expected a RFC 3339 time"),
+                    })
+            })
+            .transpose()?;
+        let location = build_backup_location(location)?;
+
+        // The database is created by the restore.
+        let mut privileges = vec![Privilege::TenantObject(
+            TenantObjectPrivilege::Database(DatabasePrivilege::Write, None),
+            Some(*session.tenant_id()),
+        )];
+        if location.local {
+            privileges.push(Privilege::Global(GlobalPrivilege::System));
+        }
+        let plan = DDLPlan::RestoreDatabase(RestoreDatabase {
+            tenant_name: session.tenant().to_string(),
+            db_name: normalize_ident(database_name),
+            location,
+            until,
+        });
+
+        Ok(PlanWithPrivileges {
+            plan: Plan::DDL(plan),
+            privileges,
+        })
+    }
+
     fn recoverdatabase_to_plan(
         &self,
         stmt: ast::RecoverDatabase,
@@ -1221,6 +1310,7 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                     new_column_name,
                 }
             }
+            ASTAlterTableAction::Reencode => AlterTableAction::Reencode,
         };
         let plan = Plan::DDL(DDLPlan::AlterTable(AlterTable {
             table_name,
@@ -1421,6 +1511,41 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         )
     }
 
+    fn show_series_cardinality_to_plan(
+        &self,
+        stmt: ast::ShowSeriesCardinality,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ast::ShowSeriesCardinality {
+            database_name,
+            table,
+            group_by,
+            exact,
+            tag_keys,
+        } = stmt;
+        let db_name = database_name
+            .map(normalize_ident)
+            .unwrap_or_else(|| session.default_database().to_string());
+
+        let plan = DDLPlan::ShowSeriesCardinality(ShowSeriesCardinality {
+            tenant_name: session.tenant().to_string(),
+            db_name: db_name.clone(),
+            table: table.map(normalize_ident),
+            group_by: group_by.map(normalize_ident),
+            exact,
+            tag_keys,
+        });
+        let privilege = Privilege::TenantObject(
+            TenantObjectPrivilege::Database(DatabasePrivilege::Read, Some(db_name)),
+            Some(*session.tenant_id()),
+        );
+
+        Ok(PlanWithPrivileges {
+            plan: Plan::DDL(plan),
+            privileges: vec![privilege],
+        })
+    }
+
     fn database_to_plan(
         &self,
         stmt: Box<ASTCreateDatabase>,
@@ -2201,6 +2326,215 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         })
     }
 
+    async fn create_stream_to_plan(
+        &self,
+        stmt: ast::CreateStream,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ast::CreateStream {
+            if_not_exists,
+            name,
+            trigger,
+            watermark,
+            output_mode,
+            statement,
+        } = stmt;
+
+        // The watermark is defined by the stream table, and the results of
+        // micro-batches are always appended to the target table.
+        if watermark.is_some() {
+            return Err(QueryError::NotImplemented {
+                err: "WATERMARK of stream, use the watermark option of the stream table instead"
+                    .to_string(),
+            });
+        }
+        if matches!(
+            output_mode,
+            Some(ast::OutputMode::Complete | ast::OutputMode::Update)
+        ) {
+            return Err(QueryError::NotImplemented {
+                err: "OUTPUT_MODE of stream other than APPEND".to_string(),
+            });
+        }
+
+        let trigger = match trigger {
+            Some(ast::Trigger::Once) => Some("once".to_string()),
+            Some(ast::Trigger::Interval(interval)) => {
+                let _ = StreamTriggerInterval::from_str(&interval).map_err(|err| {
+                    QueryError::Analyzer {
+                        err: format!("Invalid TRIGGER of stream '{interval}': {err}"),
+                    }
+                })?;
+                Some(interval)
+            }
+            None => None,
+        };
+
+        let name = object_name_to_resolved_table(session, ObjectName(vec![name]))?;
+        // Plan the INSERT statement to check it, the privileges of stream
+        // are the same as the statement.
+        let PlanWithPrivileges { plan, privileges } = self
+            .df_sql_to_plan(statement.as_ref().clone(), session)
+            .await?;
+        let is_stream_query = match &plan {
+            Plan::Query(query_plan) => !extract_stream_providers(query_plan).is_empty(),
+            _ => false,
+        };
+        if !is_stream_query {
+            return Err(QueryError::Analyzer {
+                err: "The query of stream must be INSERT INTO ... SELECT ... FROM a stream table"
+                    .to_string(),
+            });
+        }
+
+        let plan = Plan::DDL(DDLPlan::CreateStream(CreateStream {
+            if_not_exists,
+            name,
+            trigger,
+            query: statement.to_string(),
+        }));
+        Ok(PlanWithPrivileges { plan, privileges })
+    }
+
+    fn drop_stream_to_plan(
+        &self,
+        stmt: ast::DropStream,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ast::DropStream { if_exist, name } = stmt;
+
+        let name = object_name_to_resolved_table(session, ObjectName(vec![name]))?;
+        let privilege = Privilege::TenantObject(
+            TenantObjectPrivilege::Database(
+                DatabasePrivilege::Write,
+                Some(name.database().to_string()),
+            ),
+            Some(*session.tenant_id()),
+        );
+
+        Ok(PlanWithPrivileges {
+            plan: Plan::DDL(DDLPlan::DropStream(DropStream { if_exist, name })),
+            privileges: vec![privilege],
+        })
+    }
+
+    fn show_streams_to_plan(
+        &self,
+        stmt: ast::ShowStreams,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let privilege = Privilege::TenantObject(
+            TenantObjectPrivilege::Database(DatabasePrivilege::Read, None),
+            Some(*session.tenant_id()),
+        );
+
+        Ok(PlanWithPrivileges {
+            plan: Plan::DDL(DDLPlan::ShowStreams(ShowStreams {
+                verbose: stmt.verbose,
+            })),
+            privileges: vec![privilege],
+        })
+    }
+
+    async fn create_rollup_to_plan(
+        &self,
+        stmt: ast::CreateRollup,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ast::CreateRollup {
+            if_not_exists,
+            name,
+            interval,
+            watermark,
+            ttl,
+            statement,
+        } = stmt;
+
+        let name = object_name_to_resolved_table(session, name)?;
+        let interval = self.str_to_duration(&interval)?;
+        if interval.to_nanoseconds() <= 0 {
+            return Err(QueryError::Analyzer {
+                err: "The INTERVAL of rollup must be greater than 0".to_string(),
+            });
+        }
+        let delay = match watermark {
+            Some(watermark) => self.str_to_duration(&watermark)?,
+            None => CnosDuration::new_with_duration(std::time::Duration::ZERO),
+        };
+        let ttl = ttl.map(|ttl| self.str_to_duration(&ttl)).transpose()?;
+
+        let mut statement = *statement;
+        let (source_table, target_table) = qualify_rollup_statement(&mut statement, session)?;
+        // Plan the INSERT statement to check it, the privileges of rollup
+        // are the same as the statement.
+        let PlanWithPrivileges { plan, privileges } =
+            self.df_sql_to_plan(statement.clone(), session).await?;
+        // The stream of rollup only outputs the windows that are closed by the watermark.
+        let groups_by_time_window = match &plan {
+            Plan::Query(query_plan) => groups_by_time_window(&query_plan.df_plan)?,
+            _ => false,
+        };
+        if !groups_by_time_window {
+            return Err(QueryError::Analyzer {
+                err: format!("The query of rollup must group by {TIME_WINDOW}(time, ...)"),
+            });
+        }
+
+        // The stream of rollup reads the source table through the stream table.
+        let mut stream_query = statement.clone();
+        *rollup_source_table(&mut stream_query)? = ObjectName(vec![
+            Ident::with_quote('"', name.database()),
+            Ident::with_quote('"', RollupInfo::stream_table(name.table())),
+        ]);
+
+        let plan = Plan::DDL(DDLPlan::CreateRollup(CreateRollup {
+            if_not_exists,
+            name,
+            source_table,
+            target_table,
+            interval,
+            delay,
+            ttl,
+            query: statement.to_string(),
+            stream_query: stream_query.to_string(),
+        }));
+        Ok(PlanWithPrivileges { plan, privileges })
+    }
+
+    fn drop_rollup_to_plan(
+        &self,
+        stmt: ast::DropRollup,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ast::DropRollup { if_exist, name } = stmt;
+
+        let name = object_name_to_resolved_table(session, name)?;
+        let privilege = Privilege::TenantObject(
+            TenantObjectPrivilege::Database(
+                DatabasePrivilege::Write,
+                Some(name.database().to_string()),
+            ),
+            Some(*session.tenant_id()),
+        );
+
+        Ok(PlanWithPrivileges {
+            plan: Plan::DDL(DDLPlan::DropRollup(DropRollup { if_exist, name })),
+            privileges: vec![privilege],
+        })
+    }
+
+    fn show_rollups_to_plan(&self, session: &SessionCtx) -> QueryResult<PlanWithPrivileges> {
+        let privilege = Privilege::TenantObject(
+            TenantObjectPrivilege::Database(DatabasePrivilege::Read, None),
+            Some(*session.tenant_id()),
+        );
+
+        Ok(PlanWithPrivileges {
+            plan: Plan::DDL(DDLPlan::ShowRollups),
+            privileges: vec![privilege],
+        })
+    }
+
     fn show_replicas_to_plan(&self) -> QueryResult<PlanWithPrivileges> {
         let plan = Plan::DDL(DDLPlan::ShowReplicas);
         Ok(PlanWithPrivileges {
@@ -2657,7 +2991,7 @@ fn build_and_register_object_store(
     Ok(())
 }
 
-fn build_object_store(
+pub(crate) fn build_object_store(
     schema: &str,
     bucket: Option<&str>,
     connection_options: Vec<SqlOption>,
@@ -2670,6 +3004,26 @@ fn build_object_store(
         .map_err(|e| ObjectStoreSnafu { msg: e.to_string() }.build())
 }
 
+fn build_backup_location(location: UriLocation) -> QueryResult<BackupLocation> {
+    let UriLocation {
+        path,
+        connection_options,
+    } = location;
+    let url = Url::parse(&path).map_err(|e| QueryError::Semantic {
+        err: format!("Invalid backup location '{path}': {e}"),
+    })?;
+
+    // local file will not object_store
+    let store = build_object_store(url.scheme(), url.host_str(), connection_options)?;
+    // Only the system admin can access the files of the server.
+    let local = store.is_none();
+    let store = store.unwrap_or_else(|| Arc::new(LocalFileSystem::new()));
+    let path = ObjectStorePath::from_url_path(url.path())
+        .map_err(|e| ObjectStoreSnafu { msg: e.to_string() }.build())?;
+
+    Ok(BackupLocation { store, path, local })
+}
+
 async fn build_external_location_table_source(
     ctx: &SessionCtx,
     table_path: ListingTableUrl,
@@ -2717,7 +3071,7 @@ async fn build_listing_table(
     Ok(Arc::new(ListingTable::try_new(config)?))
 }
 
-fn build_file_format(
+pub(crate) fn build_file_format(
     file_format_options: FileFormatOptions,
 ) -> datafusion::common::Result<Arc<dyn FileFormat>> {
     let FileFormatOptions {
@@ -2907,7 +3261,7 @@ fn show_tag_value_projections(
     Ok(union_distinct)
 }
 
-fn check_privilege(user: &User, privileges: Vec<Privilege<Oid>>) -> QueryResult<()> {
+pub(crate) fn check_privilege(user: &User, privileges: Vec<Privilege<Oid>>) -> QueryResult<()> {
     let privileges_str = privileges
         .iter()
         .map(|e| format!("{:?}", e))
@@ -2925,7 +3279,7 @@ fn check_privilege(user: &User, privileges: Vec<Privilege<Oid>>) -> QueryResult<
     Ok(())
 }
 
-fn databases_privileges(
+pub(crate) fn databases_privileges(
     db_priv: DatabasePrivilege,
     tenant_id: Oid,
     databases: DatabaseSet,
@@ -2966,6 +3320,90 @@ fn object_name_to_resolved_table(
     Ok(table)
 }
 
+fn resolved_table_to_object_name(table: &ResolvedTable) -> ObjectName {
+    ObjectName(vec![
+        Ident::with_quote('"', table.database()),
+        Ident::with_quote('"', table.table()),
+    ])
+}
+
+/// Check the statement of rollup is `INSERT INTO target SELECT ... FROM source ...`,
+/// and qualify the table names with database, returns the source and target table.
+fn qualify_rollup_statement(
+    statement: &mut Statement,
+    session: &SessionCtx,
+) -> QueryResult<(ResolvedTable, ResolvedTable)> {
+    let table_name = match statement {
+        Statement::Insert { table_name, .. } => table_name,
+        _ => {
+            return Err(QueryError::Analyzer {
+                err: "The query of rollup must be INSERT INTO ... SELECT ...".to_string(),
+            })
+        }
+    };
+    let target_table = object_name_to_resolved_table(session, table_name.clone())?;
+    *table_name = resolved_table_to_object_name(&target_table);
+
+    let name = rollup_source_table(statement)?;
+    let source_table = object_name_to_resolved_table(session, name.clone())?;
+    *name = resolved_table_to_object_name(&source_table);
+
+    Ok((source_table, target_table))
+}
+
+/// Returns the name of the table that the rollup reads, the SELECT may read from
+/// subqueries, but only one table is read eventually.
+fn rollup_source_table(statement: &mut Statement) -> QueryResult<&mut ObjectName> {
+    match statement {
+        Statement::Insert { source, .. } => query_source_table(source),
+        _ => Err(QueryError::Analyzer {
+            err: "The query of rollup must be INSERT INTO ... SELECT ...".to_string(),
+        }),
+    }
+}
+
+fn query_source_table(query: &mut Query) -> QueryResult<&mut ObjectName> {
+    let select = match query.body.as_mut() {
+        SetExpr::Select(select) => select,
+        _ => {
+            return Err(QueryError::Analyzer {
+                err: "The query of rollup must be a SELECT statement".to_string(),
+            })
+        }
+    };
+    match select.from.as_mut_slice() {
+        [TableWithJoins { relation, joins }] if joins.is_empty() => match relation {
+            TableFactor::Table { name, .. } => Ok(name),
+            TableFactor::Derived { subquery, .. } => query_source_table(subquery),
+            _ => Err(QueryError::Analyzer {
+                err: "The query of rollup must select from a table or subquery".to_string(),
+            }),
+        },
+        _ => Err(QueryError::Analyzer {
+            err: "The query of rollup must select from exactly one table".to_string(),
+        }),
+    }
+}
+
+fn groups_by_time_window(plan: &LogicalPlan) -> QueryResult<bool> {
+    let is_time_window =
+        |expr: &Expr| matches!(expr, Expr::ScalarUDF(udf) if udf.fun.name == TIME_WINDOW);
+    let mut found = false;
+    plan.apply(&mut |plan| {
+        if let LogicalPlan::Aggregate(aggregate) = plan {
+            found = !find_exprs_in_exprs_deeply_nested(&aggregate.group_expr, &is_time_window)
+                .is_empty();
+        }
+        Ok(if found {
+            VisitRecursion::Stop
+        } else {
+            VisitRecursion::Continue
+        })
+    })?;
+
+    Ok(found)
+}
+
 // merge "catalog.db" and "db.table" to "catalog.db.table"
 // if a.b and b.c => a.b.c
 // if a.b and c.d => None
@@ -3515,6 +3953,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_rollup_source_table() {
+        let sql = "INSERT INTO cpu_1m SELECT window.start, host, usage FROM (\
+            SELECT time_window(time, interval '1 minute') AS window, host, avg(usage) AS usage \
+            FROM db1.cpu GROUP BY time_window(time, interval '1 minute'), host)";
+        let mut statement = match ExtParser::parse_sql(sql).unwrap().pop_front() {
+            Some(ExtStatement::SqlStatement(statement)) => *statement,
+            _ => panic!("expect SqlStatement"),
+        };
+        let name = rollup_source_table(&mut statement).unwrap();
+        assert_eq!(name.to_string(), "db1.cpu");
+        *name = ObjectName(vec![Ident::new("db1"), Ident::new("__rollup_cpu_1m")]);
+        assert!(statement
+            .to_string()
+            .contains("FROM db1.__rollup_cpu_1m GROUP BY"));
+
+        let sql = "INSERT INTO cpu_1m SELECT * FROM cpu JOIN mem ON cpu.host = mem.host";
+        let mut statement = match ExtParser::parse_sql(sql).unwrap().pop_front() {
+            Some(ExtStatement::SqlStatement(statement)) => *statement,
+            _ => panic!("expect SqlStatement"),
+        };
+        assert!(rollup_source_table(&mut statement).is_err());
+    }
+
     #[tokio::test]
     async fn test_insert_select() {
         let sql = "insert test_tb(field_int, field_string)
@@ -0,0 +1,152 @@
+//! Plan the queries sent as [Substrait](https://substrait.io) plans instead of sql text.
+//!
+//! The plan is consumed by DataFusion's substrait consumer, the tables referenced by the plan
+//! are resolved lazily through the [`ContextProviderExtension`] of the session,
+//! so the same privileges are required as the equivalent sql query.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::catalog::catalog::CatalogProvider;
+use datafusion::catalog::schema::SchemaProvider;
+use datafusion::datasource::{TableProvider, ViewTable};
+use datafusion::execution::context::{SessionContext, SessionState};
+use datafusion::logical_expr::TableSource;
+use datafusion::sql::TableReference;
+use datafusion_substrait::logical_plan::consumer::from_substrait_plan;
+use datafusion_substrait::serializer::deserialize_bytes;
+use models::auth::privilege::DatabasePrivilege;
+use models::object_reference::Resolve;
+use spi::query::logical_planner::{Plan, QueryPlan};
+use spi::query::session::SessionCtx;
+use spi::{QueryError, QueryResult};
+use trace::debug;
+
+use crate::metadata::ContextProviderExtension;
+use crate::sql::planner::{check_privilege, databases_privileges};
+
+/// Converts the serialized substrait plan to a query plan of the session.
+pub async fn substrait_to_plan<S>(
+    schema_provider: Arc<S>,
+    substrait_plan: &[u8],
+    session: &SessionCtx,
+) -> QueryResult<Plan>
+where
+    S: ContextProviderExtension + Send + Sync + 'static,
+{
+    let substrait_plan = deserialize_bytes(substrait_plan.to_vec())
+        .await
+        .map_err(|e| QueryError::InvalidSubstraitPlan { err: e.to_string() })?;
+
+    // The unqualified table names are resolved in the tenant and database of the session
+    let config = session
+        .inner()
+        .config()
+        .clone()
+        .with_default_catalog_and_schema(session.tenant(), session.default_database());
+    let state = SessionState::with_config_rt(config, session.inner().runtime_env().clone());
+    let mut ctx = SessionContext::with_state(state);
+    ctx.register_catalog(
+        session.tenant(),
+        Arc::new(TenantCatalog {
+            tenant: session.tenant().to_string(),
+            schema_provider: schema_provider.clone(),
+        }),
+    );
+
+    let df_plan = from_substrait_plan(&mut ctx, &substrait_plan)
+        .await
+        .map_err(|e| QueryError::InvalidSubstraitPlan { err: e.to_string() })?;
+    debug!("Substrait plan:\n{}", df_plan.display_indent_schema());
+
+    let access_databases = schema_provider.reset_access_databases();
+    let privileges = databases_privileges(
+        DatabasePrivilege::Read,
+        *session.tenant_id(),
+        access_databases,
+    );
+    check_privilege(session.user(), privileges)?;
+
+    Ok(Plan::Query(QueryPlan {
+        df_plan,
+        is_tag_scan: false,
+    }))
+}
+
+/// The databases of the current tenant.
+struct TenantCatalog<S> {
+    tenant: String,
+    schema_provider: Arc<S>,
+}
+
+impl<S> CatalogProvider for TenantCatalog<S>
+where
+    S: ContextProviderExtension + Send + Sync + 'static,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema_names(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn schema(&self, name: &str) -> Option<Arc<dyn SchemaProvider>> {
+        Some(Arc::new(DatabaseSchema {
+            tenant: self.tenant.clone(),
+            database: name.to_string(),
+            schema_provider: self.schema_provider.clone(),
+        }))
+    }
+}
+
+/// The tables of a database, every table is a view of the plan that the sql planner builds for it.
+struct DatabaseSchema<S> {
+    tenant: String,
+    database: String,
+    schema_provider: Arc<S>,
+}
+
+#[async_trait]
+impl<S> SchemaProvider for DatabaseSchema<S>
+where
+    S: ContextProviderExtension + Send + Sync + 'static,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        vec![]
+    }
+
+    async fn table(&self, name: &str) -> Option<Arc<dyn TableProvider>> {
+        let table_ref = TableReference::partial(self.database.as_str(), name);
+        let table_source = match self.schema_provider.get_table_source(table_ref) {
+            Ok(table_source) => table_source,
+            Err(e) => {
+                debug!(
+                    "Table {}.{} of substrait plan not found: {}",
+                    self.database, name, e
+                );
+                return None;
+            }
+        };
+        let plan = table_source.get_logical_plan()?.clone();
+        ViewTable::try_new(plan, None)
+            .ok()
+            .map(|view| Arc::new(view) as Arc<dyn TableProvider>)
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        TableReference::partial(self.database.as_str(), name)
+            .resolve_object(&self.tenant, &self.database)
+            .map(|table| {
+                self.schema_provider
+                    .database_table_exist(&self.database, Some(&table))
+                    .is_ok()
+            })
+            .unwrap_or(false)
+    }
+}
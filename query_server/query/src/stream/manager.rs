@@ -0,0 +1,208 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use meta::error::MetaError;
+use models::schema::query_info::QueryId;
+use models::schema::rollup_info::RollupInfo;
+use models::schema::stream_info::{StreamInfo, StreamStatus};
+use snafu::ResultExt;
+use spi::query::config::StreamTriggerInterval;
+use spi::query::dispatcher::QueryDispatcher;
+use spi::service::protocol::{ContextBuilder, Query};
+use spi::{MetaSnafu, QueryError, QueryResult};
+use tokio::time::Instant;
+use trace::{error, info};
+
+use crate::dispatcher::manager::SimpleQueryDispatcher;
+use crate::stream::rollup::RollupExpiration;
+
+const STREAM_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// The progress of the streams is written to meta at most once in the interval,
+/// the changes of the status are written at once.
+const STREAM_SYNC_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Keeps the streams owned by the current node running, and records their status in meta.
+///
+/// The streams of the current node are started again after the node restarts, and the
+/// streams of a dead node are moved to the node that takes over its persistent queries.
+pub struct StreamJobManager {
+    dispatcher: Arc<SimpleQueryDispatcher>,
+    /// The streams started by the current node, owner -> query id.
+    running: HashMap<String, QueryId>,
+    /// The ttl of the rollups run by the streams of the current node, owner -> expiration.
+    rollups: HashMap<String, RollupExpiration>,
+    /// When the progress of the streams was written to meta.
+    synced_at: Instant,
+}
+
+impl StreamJobManager {
+    pub fn new(dispatcher: Arc<SimpleQueryDispatcher>) -> Self {
+        Self {
+            dispatcher,
+            running: HashMap::new(),
+            rollups: HashMap::new(),
+            synced_at: Instant::now(),
+        }
+    }
+
+    pub async fn run(mut self) {
+        // Wait for the persistent queries to be re-executed when the node starts.
+        let mut ticker = tokio::time::interval_at(
+            Instant::now() + STREAM_CHECK_INTERVAL,
+            STREAM_CHECK_INTERVAL,
+        );
+        loop {
+            ticker.tick().await;
+            if let Err(err) = self.run_once().await {
+                error!("Failed to check streams: {}", err);
+            }
+        }
+    }
+
+    async fn run_once(&mut self) -> QueryResult<()> {
+        let coord = self.dispatcher.coord();
+        let streams = coord
+            .meta_manager()
+            .read_streams_by_nodeid(coord.node_id())
+            .await
+            .context(MetaSnafu)?;
+
+        // Stop the streams that are dropped or moved to other nodes.
+        let owners = streams.iter().map(|s| s.owner()).collect::<HashSet<_>>();
+        self.running.retain(|owner, query_id| {
+            if owners.contains(owner) {
+                return true;
+            }
+            info!("Stop stream {}", owner);
+            let _ = self.dispatcher.cancel_query(query_id);
+            false
+        });
+        self.rollups.retain(|owner, _| owners.contains(owner));
+
+        let sync_progress = self.synced_at.elapsed() >= STREAM_SYNC_INTERVAL;
+        let mut updates = vec![];
+        for stream in streams {
+            let current = self.check_stream(&stream).await;
+            let status_changed = current.status != stream.status || current.error != stream.error;
+            if status_changed || (sync_progress && current != stream) {
+                updates.push(current);
+            }
+        }
+        if !updates.is_empty() {
+            coord
+                .meta_manager()
+                .update_streams(updates)
+                .await
+                .context(MetaSnafu)?;
+        }
+        if sync_progress {
+            self.synced_at = Instant::now();
+        }
+
+        for (owner, expiration) in self.rollups.iter_mut() {
+            if let Err(err) = expiration.run(&self.dispatcher).await {
+                error!("Failed to expire rollup {}: {}", owner, err);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Starts the stream if it's not running, returns the current status of the stream.
+    async fn check_stream(&mut self, stream: &StreamInfo) -> StreamInfo {
+        let owner = stream.owner();
+        let mut current = stream.clone();
+
+        let query = self.dispatcher.query_tracker().query(&stream.query_id);
+        match query {
+            Some(query) if self.running.contains_key(&owner) => {
+                let status = query.status();
+                current.status = StreamStatus::Running;
+                current.watermark_ns = status.watermark_ns().or(stream.watermark_ns);
+                current.processed_count = status.processed_count();
+                current.error_count = status.error_count();
+                if let Some(err) = status.last_error() {
+                    current.error = Some(err.to_string());
+                }
+            }
+            _ => {
+                // The persistent query re-executed after restart or failover doesn't
+                // have the trigger of the stream, start it again.
+                if query.is_some() {
+                    let _ = self.dispatcher.cancel_query(&stream.query_id);
+                }
+                match self.start_stream(stream).await {
+                    Ok(_) => {
+                        info!("Start stream {}", owner);
+                        match self.rollup_of(stream).await {
+                            Ok(Some(rollup)) => {
+                                let expiration = RollupExpiration::new(rollup, stream.user.clone());
+                                let _ = self.rollups.insert(owner.clone(), expiration);
+                            }
+                            Ok(None) => {}
+                            Err(err) => error!("Failed to get rollup {}: {}", owner, err),
+                        }
+                        let _ = self.running.insert(owner, stream.query_id);
+                        current.status = StreamStatus::Running;
+                        current.error = None;
+                    }
+                    Err(err) => {
+                        error!("Failed to start stream {}: {}", owner, err);
+                        current.status = StreamStatus::Failed;
+                        current.error = Some(err.to_string());
+                    }
+                }
+            }
+        }
+
+        current
+    }
+
+    /// Returns the rollup run by the stream if it has a ttl.
+    async fn rollup_of(&self, stream: &StreamInfo) -> QueryResult<Option<RollupInfo>> {
+        let client = self
+            .dispatcher
+            .coord()
+            .meta_manager()
+            .tenant_meta(&stream.tenant_name)
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: stream.tenant_name.clone(),
+            })
+            .context(MetaSnafu)?;
+        let rollup = client
+            .rollups()
+            .await
+            .context(MetaSnafu)?
+            .into_iter()
+            .find(|r| r.database_name == stream.database_name && r.name == stream.name);
+
+        Ok(rollup.filter(|r| r.ttl.is_some()))
+    }
+
+    async fn start_stream(&self, stream: &StreamInfo) -> QueryResult<()> {
+        let trigger = stream
+            .trigger
+            .as_deref()
+            .map(StreamTriggerInterval::from_str)
+            .transpose()
+            .map_err(|err| QueryError::Analyzer { err })?;
+        // The watermark saved by the query id or in meta is resumed, and the stream
+        // continues from the offsets of the watermark.
+        let ctx = ContextBuilder::new(stream.user.clone())
+            .with_tenant(Some(stream.tenant_name.clone()))
+            .with_database(Some(stream.database_name.clone()))
+            .with_stream_trigger_interval(trigger)
+            .with_stream_watermark_ns(stream.watermark_ns)
+            .with_is_old(Some(true))
+            .build();
+        let query = Query::new(ctx, stream.query.clone());
+        self.dispatcher
+            .execute_query(stream.tenant_id, stream.query_id, &query, None)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+pub mod manager;
 pub mod offset_tracker;
+pub mod rollup;
 pub mod state_store;
 pub mod watermark_tracker;
@@ -21,6 +21,11 @@ impl OffsetTracker {
         }
     }
 
+    /// Marks the data of the source up to the offset as processed, for resuming a stream.
+    pub fn resume_processed_offset(&self, topic: String, offset: Offset) {
+        self.processed_offsets.write().insert(topic, offset);
+    }
+
     pub fn has_available_offsets(&self) -> bool {
         !self.available_offsets.read().is_empty()
     }
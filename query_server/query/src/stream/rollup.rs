@@ -0,0 +1,67 @@
+use chrono::{SecondsFormat, TimeZone, Utc};
+use models::auth::user::User;
+use models::schema::rollup_info::RollupInfo;
+use models::utils::now_timestamp_nanos;
+use spi::query::dispatcher::QueryDispatcher;
+use spi::service::protocol::{ContextBuilder, Query};
+use spi::QueryResult;
+use trace::debug;
+
+use crate::dispatcher::manager::SimpleQueryDispatcher;
+
+/// Removes the aggregated data older than the ttl of a rollup from its target table,
+/// it's run by the node that runs the stream of the rollup.
+pub struct RollupExpiration {
+    rollup: RollupInfo,
+    /// The user of the stream.
+    user: User,
+    /// The aggregated data before it has been removed.
+    expired_before: i64,
+}
+
+impl RollupExpiration {
+    pub fn new(rollup: RollupInfo, user: User) -> Self {
+        Self {
+            rollup,
+            user,
+            expired_before: i64::MIN,
+        }
+    }
+
+    /// Removes the expired data, at most once in an interval of the rollup.
+    pub async fn run(&mut self, dispatcher: &SimpleQueryDispatcher) -> QueryResult<()> {
+        let expired_before = match self.rollup.expired_before(now_timestamp_nanos()) {
+            Some(expired_before) => expired_before,
+            None => return Ok(()),
+        };
+        if expired_before.saturating_sub(self.expired_before)
+            < self.rollup.interval.to_nanoseconds()
+        {
+            return Ok(());
+        }
+
+        let sql = format!(
+            "DELETE FROM {} WHERE time < '{}'",
+            self.rollup.target_table,
+            timestamp_to_rfc3339(expired_before)
+        );
+        debug!("Rollup {} expires: {}", self.rollup.owner(), sql);
+        let ctx = ContextBuilder::new(self.user.clone())
+            .with_tenant(Some(self.rollup.tenant_name.clone()))
+            .with_database(Some(self.rollup.database_name.clone()))
+            .build();
+        let query = Query::new(ctx, sql);
+        let query_id = dispatcher.create_query_id();
+        dispatcher
+            .execute_query(self.rollup.tenant_id, query_id, &query, None)
+            .await?;
+
+        self.expired_before = expired_before;
+        Ok(())
+    }
+}
+
+fn timestamp_to_rfc3339(ts: i64) -> String {
+    Utc.timestamp_nanos(ts)
+        .to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
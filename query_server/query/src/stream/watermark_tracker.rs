@@ -7,6 +7,7 @@ use datafusion::arrow::array::{Array, StringArray, TimestampNanosecondArray};
 use datafusion::arrow::record_batch::RecordBatch;
 use futures::TryStreamExt;
 use models::arrow::TimeUnit;
+use models::consistency_level::ConsistencyLevel;
 use models::predicate::domain::Predicate;
 use models::schema::query_info::QueryId;
 use models::schema::tskv_table_schema::{ColumnType, TableColumn, TskvTableSchema};
@@ -169,6 +170,13 @@ impl WatermarkTracker {
         self.global_watermark_ns.load(Ordering::Relaxed)
     }
 
+    /// Resumes the watermark saved by the stream if it's later than the persisted one.
+    pub fn resume(&self, watermark_ns: i64) {
+        let _ = self
+            .global_watermark_ns
+            .fetch_max(watermark_ns, Ordering::Relaxed);
+    }
+
     pub fn update_watermark(&self, event_time: i64, _delay: i64) {
         // TODO _delay needs to be processed after kv supports offset
         // self.global_watermark_ns
@@ -213,6 +221,7 @@ impl WatermarkTracker {
                 CLUSTER_SCHEMA,
                 Precision::NS,
                 vec![line],
+                ConsistencyLevel::Quorum,
                 None,
             )
             .await
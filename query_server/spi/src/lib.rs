@@ -590,6 +590,43 @@ pub enum QueryError {
     Models {
         source: ModelError,
     },
+
+    #[snafu(display("Invalid PromQL: {}", err))]
+    #[error_code(code = 80)]
+    InvalidPromQL {
+        err: String,
+    },
+
+    #[snafu(display("Failed to evaluate PromQL: {}", err))]
+    #[error_code(code = 81)]
+    PromQLExecution {
+        err: String,
+    },
+
+    #[snafu(display("The transaction has been committed or rolled back"))]
+    #[error_code(code = 82)]
+    TransactionClosed,
+
+    #[snafu(display("Invalid substrait plan: {}", err))]
+    #[error_code(code = 83)]
+    InvalidSubstraitPlan {
+        err: String,
+    },
+
+    #[snafu(display("Invalid InfluxQL: {}", err))]
+    #[error_code(code = 84)]
+    InvalidInfluxQL {
+        err: String,
+    },
+
+    #[snafu(display(
+        "The writes of the transaction exceed the limit of {} bytes, commit or roll back the transaction",
+        max_size
+    ))]
+    #[error_code(code = 85)]
+    TransactionBufferFull {
+        max_size: usize,
+    },
 }
 
 impl From<DataFusionError> for QueryError {
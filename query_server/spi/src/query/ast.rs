@@ -32,6 +32,10 @@ pub enum ExtStatement {
     DropStream(DropStream),
     ShowStreams(ShowStreams),
 
+    CreateRollup(CreateRollup),
+    DropRollup(DropRollup),
+    ShowRollups,
+
     DropDatabaseObject(DropDatabaseObject),
     DropTenantObject(DropTenantObject),
     DropGlobalObject(DropGlobalObject),
@@ -44,6 +48,7 @@ pub enum ExtStatement {
     ShowTables(Option<Ident>),
     ShowSeries(Box<ShowSeries>),
     ShowTagValues(Box<ShowTagValues>),
+    ShowSeriesCardinality(ShowSeriesCardinality),
     Explain(Explain),
 
     // system cmd
@@ -65,6 +70,10 @@ pub enum ExtStatement {
     RecoverTenant(RecoverTenant),
     RecoverDatabase(RecoverDatabase),
 
+    // backup cmd
+    BackupDatabase(BackupDatabase),
+    RestoreDatabase(RestoreDatabase),
+
     // replica cmd
     ShowReplicas,
     ReplicaDestory(ReplicaDestory),
@@ -191,6 +200,8 @@ pub enum AlterTableAction {
         old_column_name: Ident,
         new_column_name: Ident,
     },
+    /// `REENCODE`, rewrite the existing data with the current encodings of the columns
+    Reencode,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -252,6 +263,21 @@ pub struct RecoverDatabase {
     pub if_exist: bool,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupDatabase {
+    pub database_name: Ident,
+    pub location: UriLocation,
+    pub incremental: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestoreDatabase {
+    pub database_name: Ident,
+    pub location: UriLocation,
+    /// Recover to this time by replaying the archived WAL after the backup.
+    pub until: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RecoverTenant {
     pub object_name: Ident,
@@ -431,6 +457,18 @@ pub struct ShowSeries {
     pub body: ShowTagBody,
 }
 
+/// `SHOW [EXACT] SERIES CARDINALITY [ON db] [FROM table] [GROUP BY tag]` or
+/// `SHOW [EXACT] TAG KEY CARDINALITY [ON db] [FROM table]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShowSeriesCardinality {
+    pub database_name: Option<Ident>,
+    pub table: Option<Ident>,
+    pub group_by: Option<Ident>,
+    pub exact: bool,
+    /// Count the tag keys instead of the series.
+    pub tag_keys: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum With {
     Equal(Ident),
@@ -490,6 +528,27 @@ pub struct ShowStreams {
     pub verbose: bool,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CreateRollup {
+    pub if_not_exists: bool,
+    /// [database.]rollup_name
+    pub name: ObjectName,
+    /// width of the aggregated buckets, e.g. '1m'
+    pub interval: String,
+    /// how long to wait for late data before a bucket is aggregated
+    pub watermark: Option<String>,
+    /// how long to keep the aggregated data
+    pub ttl: Option<String>,
+    /// INSERT INTO target SELECT ... FROM source GROUP BY ...
+    pub statement: Box<Statement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DropRollup {
+    pub if_exist: bool,
+    pub name: ObjectName,
+}
+
 impl fmt::Display for ObjectType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(match self {
@@ -101,6 +101,13 @@ pub trait StreamProvider {
     /// equal to `end` and will only request offsets greater than `end` in the future.
     async fn commit(&self, end: Self::Offset) -> Result<()>;
 
+    /// Returns the offset up to which the data with event time not later than `event_time_ns`
+    /// has been read, it's used to resume the stream after restart or failover.
+    /// None means the source tracks the processed offsets by itself.
+    fn resumed_offset(&self, _event_time_ns: i64) -> Option<Self::Offset> {
+        None
+    }
+
     fn schema(&self) -> SchemaRef;
 
     /// Tests whether the table provider can make use of a filter expression
@@ -35,6 +35,12 @@ pub trait QueryDispatcher: Send + Sync {
         query_state_machine: Arc<QueryStateMachine>,
     ) -> QueryResult<Option<Plan>>;
 
+    async fn build_substrait_plan(
+        &self,
+        substrait_plan: &[u8],
+        query_state_machine: Arc<QueryStateMachine>,
+    ) -> QueryResult<Plan>;
+
     async fn execute_logical_plan(
         &self,
         logical_plan: Plan,
@@ -53,7 +59,8 @@ pub trait QueryDispatcher: Send + Sync {
 
     fn running_query_status(&self) -> Vec<QueryStatus>;
 
-    fn cancel_query(&self, id: &QueryId);
+    /// Cancel the running query like `KILL QUERY`, returns false if the query is not running
+    fn cancel_query(&self, id: &QueryId) -> bool;
 }
 
 #[derive(Debug)]
@@ -62,6 +69,8 @@ pub struct QueryStatus {
     duration: Duration,
     processed_count: u64,
     error_count: u64,
+    last_error: Option<String>,
+    watermark_ns: Option<i64>,
 }
 
 impl QueryStatus {
@@ -71,6 +80,8 @@ impl QueryStatus {
             duration,
             processed_count: 0,
             error_count: 0,
+            last_error: None,
+            watermark_ns: None,
         }
     }
 
@@ -89,6 +100,16 @@ impl QueryStatus {
     pub fn error_count(&self) -> u64 {
         self.error_count
     }
+
+    /// The last error of the micro-batches of a stream query.
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    /// The event time that a stream query has processed until.
+    pub fn watermark_ns(&self) -> Option<i64> {
+        self.watermark_ns
+    }
 }
 
 pub struct QueryStatusBuilder {
@@ -96,6 +117,8 @@ pub struct QueryStatusBuilder {
     duration: Duration,
     processed_count: u64,
     error_count: u64,
+    last_error: Option<String>,
+    watermark_ns: Option<i64>,
 }
 
 impl QueryStatusBuilder {
@@ -105,6 +128,8 @@ impl QueryStatusBuilder {
             duration,
             processed_count: 0,
             error_count: 0,
+            last_error: None,
+            watermark_ns: None,
         }
     }
 
@@ -118,12 +143,24 @@ impl QueryStatusBuilder {
         self
     }
 
+    pub fn with_last_error(mut self, last_error: Option<String>) -> Self {
+        self.last_error = last_error;
+        self
+    }
+
+    pub fn with_watermark_ns(mut self, watermark_ns: Option<i64>) -> Self {
+        self.watermark_ns = watermark_ns;
+        self
+    }
+
     pub fn build(self) -> QueryStatus {
         QueryStatus {
             state: self.state,
             duration: self.duration,
             processed_count: self.processed_count,
             error_count: self.error_count,
+            last_error: self.last_error,
+            watermark_ns: self.watermark_ns,
         }
     }
 }
@@ -30,6 +30,8 @@ use models::schema::query_info::QueryId;
 use models::schema::stream_table_schema::Watermark;
 use models::schema::tenant::{Tenant, TenantOptions, TenantOptionsBuilder};
 use models::schema::tskv_table_schema::TableColumn;
+use object_store::path::Path as ObjectStorePath;
+use object_store::ObjectStore;
 use snafu::{IntoError, ResultExt};
 use tempfile::NamedTempFile;
 use utils::duration::CnosDuration;
@@ -156,10 +158,16 @@ pub enum DDLPlan {
 
     ChecksumGroup(ChecksumGroup),
 
+    ShowSeriesCardinality(ShowSeriesCardinality),
+
     RecoverDatabase(RecoverDatabase),
 
     RecoverTenant(RecoverTenant),
 
+    BackupDatabase(BackupDatabase),
+
+    RestoreDatabase(RestoreDatabase),
+
     ShowReplicas,
 
     ReplicaDestory(ReplicaDestory),
@@ -169,6 +177,18 @@ pub enum DDLPlan {
     ReplicaRemove(ReplicaRemove),
 
     ReplicaPromote(ReplicaPromote),
+
+    CreateRollup(CreateRollup),
+
+    DropRollup(DropRollup),
+
+    ShowRollups,
+
+    CreateStream(CreateStream),
+
+    DropStream(DropStream),
+
+    ShowStreams(ShowStreams),
 }
 
 impl DDLPlan {
@@ -178,6 +198,14 @@ impl DDLPlan {
                 Field::new("vnode_id", DataType::UInt32, false),
                 Field::new("check_sum", DataType::Utf8, false),
             ])),
+            DDLPlan::ShowSeriesCardinality(plan) => {
+                let mut fields = vec![Field::new("table_name", DataType::Utf8, false)];
+                if let Some(tag) = &plan.group_by {
+                    fields.push(Field::new(tag, DataType::Utf8, true));
+                }
+                fields.push(Field::new("count", DataType::UInt64, false));
+                Arc::new(Schema::new(fields))
+            }
             _ => Arc::new(Schema::empty()),
         }
     }
@@ -188,6 +216,17 @@ pub struct ChecksumGroup {
     pub replication_set_id: ReplicationSetId,
 }
 
+#[derive(Debug, Clone)]
+pub struct ShowSeriesCardinality {
+    pub tenant_name: String,
+    pub db_name: String,
+    pub table: Option<String>,
+    pub group_by: Option<String>,
+    pub exact: bool,
+    /// Count the tag keys instead of the series.
+    pub tag_keys: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct CompactVnode {
     pub vnode_ids: Vec<VnodeId>,
@@ -297,6 +336,33 @@ pub struct RecoverDatabase {
     pub if_exist: bool,
 }
 
+/// A directory of a local file system or an object store that holds a backup.
+#[derive(Debug, Clone)]
+pub struct BackupLocation {
+    pub store: Arc<dyn ObjectStore>,
+    pub path: ObjectStorePath,
+    /// The location is on the local file system of the server.
+    pub local: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct BackupDatabase {
+    pub tenant_name: String,
+    pub db_name: String,
+    pub location: BackupLocation,
+    /// Only copy the files that are not in the previous backup of the location.
+    pub incremental: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct RestoreDatabase {
+    pub tenant_name: String,
+    pub db_name: String,
+    pub location: BackupLocation,
+    /// Nanoseconds since epoch, the writes until it are recovered from the archived WAL.
+    pub until: Option<i64>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RecoverTable {
     pub table: ResolvedTable,
@@ -351,6 +417,52 @@ pub struct CreateTenant {
     pub options: TenantOptions,
 }
 
+#[derive(Debug, Clone)]
+pub struct CreateRollup {
+    pub if_not_exists: bool,
+    /// tenant.database.rollup_name
+    pub name: ResolvedTable,
+    pub source_table: ResolvedTable,
+    pub target_table: ResolvedTable,
+    pub interval: CnosDuration,
+    pub delay: CnosDuration,
+    pub ttl: Option<CnosDuration>,
+    /// The INSERT statement with qualified table names.
+    pub query: String,
+    /// The INSERT statement that reads the stream table of the rollup instead.
+    pub stream_query: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct DropRollup {
+    pub if_exist: bool,
+    /// tenant.database.rollup_name
+    pub name: ResolvedTable,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateStream {
+    pub if_not_exists: bool,
+    /// tenant.database.stream_name
+    pub name: ResolvedTable,
+    /// `once` or the interval between two micro-batches.
+    pub trigger: Option<String>,
+    /// The INSERT statement with qualified table names.
+    pub query: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct DropStream {
+    pub if_exist: bool,
+    /// tenant.database.stream_name
+    pub name: ResolvedTable,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShowStreams {
+    pub verbose: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct ReplicaDestory {
     pub replica_id: ReplicationSetId,
@@ -631,6 +743,7 @@ pub enum AlterTableAction {
         old_column_name: String,
         new_column_name: String,
     },
+    Reencode,
 }
 
 #[async_trait]
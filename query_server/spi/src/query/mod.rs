@@ -15,6 +15,7 @@ pub mod physical_planner;
 pub mod recordbatch;
 pub mod scheduler;
 pub mod session;
+pub mod transaction;
 pub mod variable;
 
 pub const AFFECTED_ROWS: (&str, DataType) = ("rows", DataType::UInt64);
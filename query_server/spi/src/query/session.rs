@@ -11,11 +11,13 @@ use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
 use datafusion::prelude::{SessionConfig, SessionContext};
 use datafusion::variable::VarType;
 use models::auth::user::User;
+use models::consistency_level::ConsistencyLevel;
 use models::oid::Oid;
 use trace::span_ext::SpanExt;
 use trace::{Span, SpanContext};
 
 use super::config::StreamTriggerInterval;
+use super::transaction::TransactionWriteBuffer;
 use super::variable::VarProviderRef;
 use crate::service::protocol::Context;
 use crate::QueryResult;
@@ -212,4 +214,23 @@ impl CnosSessionConfig {
         self.inner = self.inner.with_extension(Arc::new(interval));
         self
     }
+
+    /// Consistency level of writes and reads in this session
+    pub fn with_consistency_level(mut self, level: ConsistencyLevel) -> Self {
+        self.inner = self.inner.with_extension(Arc::new(level));
+        self
+    }
+
+    pub fn consistency_level(&self) -> ConsistencyLevel {
+        self.inner
+            .get_extension::<ConsistencyLevel>()
+            .map(|e| *e)
+            .unwrap_or_default()
+    }
+
+    /// Buffer the writes of this session in the transaction until it's committed
+    pub fn with_transaction(mut self, transaction: Arc<TransactionWriteBuffer>) -> Self {
+        self.inner = self.inner.with_extension(transaction);
+        self
+    }
 }
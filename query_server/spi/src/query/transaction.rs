@@ -0,0 +1,197 @@
+use std::sync::{Mutex, MutexGuard};
+
+use datafusion::arrow::record_batch::RecordBatch;
+use models::schema::tskv_table_schema::TskvTableSchemaRef;
+
+use crate::{QueryError, QueryResult};
+
+/// The writes of an explicit transaction.
+///
+/// The record batches written by the statements of the transaction are buffered here
+/// instead of being written to tskv, they are written together when the transaction is committed,
+/// or discarded when it is rolled back.
+#[derive(Debug)]
+pub struct TransactionWriteBuffer {
+    /// The max memory size of the buffered record batches, in bytes.
+    max_size: usize,
+    inner: Mutex<WriteBufferInner>,
+}
+
+#[derive(Debug, Default)]
+struct WriteBufferInner {
+    batches: Vec<(TskvTableSchemaRef, RecordBatch)>,
+    /// The memory size of the buffered record batches.
+    size: usize,
+    closed: bool,
+    /// (savepoint id, number of buffered batches when the savepoint is created)
+    savepoints: Vec<(Vec<u8>, usize)>,
+}
+
+impl TransactionWriteBuffer {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            max_size,
+            inner: Mutex::default(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, WriteBufferInner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn append(
+        &self,
+        table_schema: TskvTableSchemaRef,
+        record_batch: RecordBatch,
+    ) -> QueryResult<()> {
+        let mut inner = self.lock();
+        if inner.closed {
+            return Err(QueryError::TransactionClosed);
+        }
+        let size = record_batch.get_array_memory_size();
+        if inner.size.saturating_add(size) > self.max_size {
+            return Err(QueryError::TransactionBufferFull {
+                max_size: self.max_size,
+            });
+        }
+        inner.size += size;
+        inner.batches.push((table_schema, record_batch));
+        Ok(())
+    }
+
+    /// Take all the buffered record batches and close the transaction,
+    /// the following writes are rejected.
+    pub fn close(&self) -> Vec<(TskvTableSchemaRef, RecordBatch)> {
+        let mut inner = self.lock();
+        inner.closed = true;
+        inner.size = 0;
+        inner.savepoints.clear();
+        std::mem::take(&mut inner.batches)
+    }
+
+    pub fn num_rows(&self) -> usize {
+        let inner = self.lock();
+        inner.batches.iter().map(|(_, b)| b.num_rows()).sum()
+    }
+
+    pub fn begin_savepoint(&self, savepoint_id: Vec<u8>) {
+        let mut inner = self.lock();
+        let position = inner.batches.len();
+        inner.savepoints.push((savepoint_id, position));
+    }
+
+    /// Release the savepoint and the savepoints created after it,
+    /// returns false if the savepoint does not exist.
+    pub fn release_savepoint(&self, savepoint_id: &[u8]) -> bool {
+        let mut inner = self.lock();
+        match inner.savepoint_index(savepoint_id) {
+            Some(idx) => {
+                inner.savepoints.truncate(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Discard the writes after the savepoint was created, then release the savepoint,
+    /// returns false if the savepoint does not exist.
+    pub fn rollback_to_savepoint(&self, savepoint_id: &[u8]) -> bool {
+        let mut inner = self.lock();
+        match inner.savepoint_index(savepoint_id) {
+            Some(idx) => {
+                let (_, position) = inner.savepoints[idx];
+                inner.batches.truncate(position);
+                inner.size = inner
+                    .batches
+                    .iter()
+                    .map(|(_, b)| b.get_array_memory_size())
+                    .sum();
+                inner.savepoints.truncate(idx);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl WriteBufferInner {
+    fn savepoint_index(&self, savepoint_id: &[u8]) -> Option<usize> {
+        self.savepoints
+            .iter()
+            .rposition(|(id, _)| id.as_slice() == savepoint_id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::Int64Array;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use models::schema::tskv_table_schema::TskvTableSchema;
+
+    use super::TransactionWriteBuffer;
+
+    fn record_batch(rows: i64) -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Int64, false)]));
+        RecordBatch::try_new(
+            schema,
+            vec![Arc::new(Int64Array::from_iter_values(0..rows))],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_savepoint() {
+        let table_schema = Arc::new(TskvTableSchema::default());
+        let buffer = TransactionWriteBuffer::new(usize::MAX);
+
+        buffer
+            .append(table_schema.clone(), record_batch(1))
+            .unwrap();
+        buffer.begin_savepoint(b"sp1".to_vec());
+        buffer
+            .append(table_schema.clone(), record_batch(2))
+            .unwrap();
+        buffer.begin_savepoint(b"sp2".to_vec());
+        buffer
+            .append(table_schema.clone(), record_batch(4))
+            .unwrap();
+        assert_eq!(buffer.num_rows(), 7);
+
+        assert!(buffer.release_savepoint(b"sp2"));
+        assert!(!buffer.rollback_to_savepoint(b"sp2"));
+        assert_eq!(buffer.num_rows(), 7);
+
+        assert!(buffer.rollback_to_savepoint(b"sp1"));
+        assert_eq!(buffer.num_rows(), 1);
+
+        buffer
+            .append(table_schema.clone(), record_batch(8))
+            .unwrap();
+        let batches = buffer.close();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(buffer.num_rows(), 0);
+        assert!(buffer.append(table_schema, record_batch(1)).is_err());
+    }
+
+    #[test]
+    fn test_max_size() {
+        let table_schema = Arc::new(TskvTableSchema::default());
+        let batch = record_batch(1024);
+        let buffer = TransactionWriteBuffer::new(batch.get_array_memory_size() * 2);
+
+        buffer.append(table_schema.clone(), batch.clone()).unwrap();
+        buffer.begin_savepoint(b"sp1".to_vec());
+        buffer.append(table_schema.clone(), batch.clone()).unwrap();
+        assert!(buffer.append(table_schema.clone(), batch.clone()).is_err());
+
+        // The rolled back writes are not counted
+        assert!(buffer.rollback_to_savepoint(b"sp1"));
+        buffer.append(table_schema, batch).unwrap();
+        assert_eq!(buffer.num_rows(), 2048);
+    }
+}
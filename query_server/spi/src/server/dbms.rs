@@ -36,13 +36,20 @@ pub trait DatabaseManagerSystem {
         &self,
         query_state_machine: QueryStateMachineRef,
     ) -> QueryResult<Option<Plan>>;
+    /// Build the query plan from a serialized substrait plan
+    async fn build_substrait_plan(
+        &self,
+        substrait_plan: &[u8],
+        query_state_machine: QueryStateMachineRef,
+    ) -> QueryResult<Plan>;
     async fn execute_logical_plan(
         &self,
         logical_plan: Plan,
         query_state_machine: QueryStateMachineRef,
     ) -> QueryResult<QueryHandle>;
     fn metrics(&self) -> String;
-    fn cancel(&self, query_id: &QueryId);
+    /// Cancel the running query, returns false if the query is not running
+    fn cancel(&self, query_id: &QueryId) -> bool;
 }
 
 pub struct DatabaseManagerSystemMock {}
@@ -118,6 +125,16 @@ impl DatabaseManagerSystem for DatabaseManagerSystemMock {
         Ok(None)
     }
 
+    async fn build_substrait_plan(
+        &self,
+        _substrait_plan: &[u8],
+        _query_state_machine: QueryStateMachineRef,
+    ) -> QueryResult<Plan> {
+        Err(crate::QueryError::NotImplemented {
+            err: "substrait plan of DatabaseManagerSystemMock".to_string(),
+        })
+    }
+
     async fn execute_logical_plan(
         &self,
         _logical_plan: Plan,
@@ -138,7 +155,8 @@ impl DatabaseManagerSystem for DatabaseManagerSystemMock {
         "todo!()".to_string()
     }
 
-    fn cancel(&self, query_id: &QueryId) {
+    fn cancel(&self, query_id: &QueryId) -> bool {
         println!("DatabaseManagerSystemMock::cancel({:?})", query_id);
+        false
     }
 }
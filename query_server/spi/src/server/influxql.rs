@@ -0,0 +1,70 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use trace::SpanContext;
+
+use crate::service::protocol::Context;
+use crate::QueryResult;
+
+pub type InfluxQLServerRef = Arc<dyn InfluxQLServer + Send + Sync>;
+
+#[async_trait]
+pub trait InfluxQLServer {
+    /// Executes the `;` separated InfluxQL statements.
+    ///
+    /// Returns an error if the query can't be parsed, the error of a statement is
+    /// returned in its result and the statements after it are not executed.
+    async fn query(
+        &self,
+        ctx: &Context,
+        req: InfluxQLRequest,
+        span_ctx: Option<&SpanContext>,
+    ) -> QueryResult<Vec<InfluxQLStatementResult>>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InfluxQLRequest {
+    pub query: String,
+    /// The unit of the returned timestamps: `ns`, `u`, `µ`, `ms`, `s`, `m` or `h`,
+    /// the timestamps are returned as RFC3339 strings if it's `None`.
+    pub epoch: Option<String>,
+}
+
+/// The result of a statement in the format of InfluxDB.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InfluxQLStatementResult {
+    pub statement_id: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub series: Vec<InfluxQLSeries>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl InfluxQLStatementResult {
+    pub fn new(statement_id: usize, series: Vec<InfluxQLSeries>) -> Self {
+        Self {
+            statement_id,
+            series,
+            error: None,
+        }
+    }
+
+    pub fn error(statement_id: usize, error: String) -> Self {
+        Self {
+            statement_id,
+            series: vec![],
+            error: Some(error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InfluxQLSeries {
+    pub name: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+    pub columns: Vec<String>,
+    pub values: Vec<Vec<serde_json::Value>>,
+}
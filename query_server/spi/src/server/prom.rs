@@ -1,9 +1,11 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use bytes::Bytes;
 use protocol_parser::Line;
 use protos::prompb::prometheus::WriteRequest;
+use serde::Serialize;
 use trace::SpanContext;
 
 use crate::service::protocol::Context;
@@ -11,6 +13,8 @@ use crate::QueryResult;
 
 pub type PromRemoteServerRef = Arc<dyn PromRemoteServer + Send + Sync>;
 
+pub type PromLabels = BTreeMap<String, String>;
+
 #[async_trait]
 pub trait PromRemoteServer {
     async fn remote_read(
@@ -23,4 +27,116 @@ pub trait PromRemoteServer {
     fn remote_write(&self, req: Bytes) -> QueryResult<WriteRequest>;
 
     fn prom_write_request_to_lines<'a>(&self, req: &'a WriteRequest) -> QueryResult<Vec<Line<'a>>>;
+
+    /// Evaluates a PromQL expression, at a single instant or over a range of time.
+    async fn query(
+        &self,
+        ctx: &Context,
+        req: PromQueryRequest,
+        span_ctx: Option<&SpanContext>,
+    ) -> QueryResult<PromQueryData>;
+
+    /// Returns the label sets of the series that match any of the selectors.
+    async fn series(
+        &self,
+        ctx: &Context,
+        req: PromMetadataRequest,
+        span_ctx: Option<&SpanContext>,
+    ) -> QueryResult<Vec<PromLabels>>;
+
+    async fn label_names(
+        &self,
+        ctx: &Context,
+        req: PromMetadataRequest,
+        span_ctx: Option<&SpanContext>,
+    ) -> QueryResult<Vec<String>>;
+
+    async fn label_values(
+        &self,
+        ctx: &Context,
+        label: &str,
+        req: PromMetadataRequest,
+        span_ctx: Option<&SpanContext>,
+    ) -> QueryResult<Vec<String>>;
+}
+
+/// Timestamps and step are in milliseconds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromQueryRequest {
+    pub query: String,
+    pub start: i64,
+    pub end: i64,
+    /// `None` means an instant query evaluated at `end`.
+    pub step: Option<i64>,
+}
+
+impl PromQueryRequest {
+    pub fn instant(query: String, time: i64) -> Self {
+        Self {
+            query,
+            start: time,
+            end: time,
+            step: None,
+        }
+    }
+
+    pub fn range(query: String, start: i64, end: i64, step: i64) -> Self {
+        Self {
+            query,
+            start,
+            end,
+            step: Some(step),
+        }
+    }
+}
+
+/// Request of the series and labels apis, timestamps are in milliseconds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromMetadataRequest {
+    /// Series selectors, e.g. `up{job="prometheus"}`.
+    pub matchers: Vec<String>,
+    pub start: i64,
+    pub end: i64,
+}
+
+/// The `data` of a query response of the Prometheus HTTP API.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "resultType", content = "result", rename_all = "lowercase")]
+pub enum PromQueryData {
+    Matrix(Vec<PromSeries>),
+    Vector(Vec<PromSample>),
+    Scalar(PromPoint),
+    String(PromPoint),
+}
+
+/// A `[<unix seconds>, "<value>"]` pair.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PromPoint(pub f64, pub String);
+
+impl PromPoint {
+    pub fn new(timestamp_ms: i64, value: f64) -> Self {
+        Self(timestamp_ms as f64 / 1000.0, format_value(value))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PromSeries {
+    pub metric: PromLabels,
+    pub values: Vec<PromPoint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PromSample {
+    pub metric: PromLabels,
+    pub value: PromPoint,
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
 }
@@ -1,10 +1,14 @@
+use std::sync::Arc;
+
 use models::auth::user::User;
+use models::consistency_level::ConsistencyLevel;
 use models::schema::query_info::QueryId;
 use models::schema::{DEFAULT_CATALOG, DEFAULT_DATABASE, DEFAULT_PRECISION};
 
 use crate::query::config::StreamTriggerInterval;
 use crate::query::execution::Output;
 use crate::query::session::CnosSessionConfig;
+use crate::query::transaction::TransactionWriteBuffer;
 
 #[derive(Clone)]
 pub struct Context {
@@ -19,6 +23,7 @@ pub struct Context {
     chunked: bool,
     session_config: CnosSessionConfig,
     is_old: bool,
+    stream_watermark_ns: Option<i64>,
 }
 
 impl Context {
@@ -41,12 +46,19 @@ impl Context {
     pub fn session_config(&self) -> &CnosSessionConfig {
         &self.session_config
     }
+    pub fn consistency_level(&self) -> ConsistencyLevel {
+        self.session_config.consistency_level()
+    }
     pub fn chunked(&self) -> bool {
         self.chunked
     }
     pub fn is_old(&self) -> bool {
         self.is_old
     }
+    /// The watermark saved by the stream that the query resumes from
+    pub fn stream_watermark_ns(&self) -> Option<i64> {
+        self.stream_watermark_ns
+    }
 }
 
 pub struct ContextBuilder {
@@ -57,6 +69,7 @@ pub struct ContextBuilder {
     chunked: bool,
     session_config: CnosSessionConfig,
     is_old: bool,
+    stream_watermark_ns: Option<i64>,
 }
 
 impl ContextBuilder {
@@ -69,6 +82,7 @@ impl ContextBuilder {
             chunked: Default::default(),
             session_config: Default::default(),
             is_old: Default::default(),
+            stream_watermark_ns: None,
         }
     }
 
@@ -109,6 +123,20 @@ impl ContextBuilder {
         self
     }
 
+    pub fn with_consistency_level(mut self, level: Option<ConsistencyLevel>) -> Self {
+        if let Some(level) = level {
+            self.session_config = self.session_config.with_consistency_level(level);
+        }
+        self
+    }
+
+    pub fn with_transaction(mut self, transaction: Option<Arc<TransactionWriteBuffer>>) -> Self {
+        if let Some(transaction) = transaction {
+            self.session_config = self.session_config.with_transaction(transaction);
+        }
+        self
+    }
+
     pub fn with_chunked(mut self, chunked: Option<bool>) -> Self {
         if let Some(chunked) = chunked {
             self.chunked = chunked;
@@ -123,6 +151,13 @@ impl ContextBuilder {
         self
     }
 
+    pub fn with_stream_watermark_ns(mut self, watermark_ns: Option<i64>) -> Self {
+        if let Some(watermark_ns) = watermark_ns {
+            self.stream_watermark_ns = Some(watermark_ns);
+        }
+        self
+    }
+
     pub fn build(self) -> Context {
         Context {
             user: self.user,
@@ -132,6 +167,7 @@ impl ContextBuilder {
             chunked: self.chunked,
             session_config: self.session_config,
             is_old: self.is_old,
+            stream_watermark_ns: self.stream_watermark_ns,
         }
     }
 }
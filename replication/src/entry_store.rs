@@ -2,13 +2,14 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use async_trait::async_trait;
+use futures::future::BoxFuture;
 use heed::byteorder::BigEndian;
 use heed::types::*;
 use heed::{Database, Env};
 use openraft::Entry;
 use snafu::ResultExt;
 
-use crate::errors::{HeedSnafu, IOErrSnafu, MsgInvalidSnafu, ReplicationResult};
+use crate::errors::{HeedSnafu, IOErrSnafu, MsgInvalidSnafu, ReplicationError, ReplicationResult};
 use crate::{EntriesMetrics, EntryStorage, TypeConfig};
 
 // --------------------------------------------------------------------------- //
@@ -153,6 +154,12 @@ impl EntryStorage for HeedEntryStorage {
     async fn sync(&mut self) -> ReplicationResult<()> {
         Ok(())
     }
+
+    async fn archive(&mut self) -> ReplicationResult<BoxFuture<'static, ReplicationResult<()>>> {
+        Err(ReplicationError::RaftInternalErr {
+            msg: "archiving the entries is not supported".to_string(),
+        })
+    }
 }
 
 mod test {
@@ -5,6 +5,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use errors::ReplicationResult;
+use futures::future::BoxFuture;
 use openraft::{Entry, TokioRuntime};
 use tokio::sync::RwLock;
 
@@ -130,5 +131,9 @@ pub trait EntryStorage: Send + Sync {
     async fn metrics(&mut self) -> ReplicationResult<EntriesMetrics>;
 
     async fn sync(&mut self) -> ReplicationResult<()>;
+
+    // Archive the entries written, the returned future completes when they are archived,
+    // so the storage needn't be locked meanwhile.
+    async fn archive(&mut self) -> ReplicationResult<BoxFuture<'static, ReplicationResult<()>>>;
 }
 pub type EntryStorageRef = Arc<RwLock<dyn EntryStorage>>;
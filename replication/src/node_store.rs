@@ -186,6 +186,12 @@ impl NodeStorage {
         let mut entry_storage = self.raft_logs.write().await;
         let _ = entry_storage.sync().await;
     }
+
+    pub async fn archive_wal(&self) -> ReplicationResult<()> {
+        // The raft logs are not locked while archiving, so the writes go on.
+        let archived = self.raft_logs.write().await.archive().await?;
+        archived.await
+    }
 }
 
 type StorageResult<T> = Result<T, StorageError<RaftNodeId>>;
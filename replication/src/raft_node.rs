@@ -203,4 +203,9 @@ impl RaftNode {
     pub async fn sync_wal_writer(&self) {
         let _ = self.storage.sync_wal_writer().await;
     }
+
+    /// Archives the raft logs written, so they can be replayed by other nodes.
+    pub async fn archive_wal(&self) -> ReplicationResult<()> {
+        self.storage.archive_wal().await
+    }
 }
@@ -5,7 +5,7 @@ use arrow_schema::SchemaRef;
 use datafusion::physical_plan::metrics::ExecutionPlanMetricsSet;
 use futures::StreamExt;
 use models::predicate::domain::TimeRange;
-use models::schema::tskv_table_schema::{TableColumn, TskvTableSchema};
+use models::schema::tskv_table_schema::{TableColumn, TskvTableSchema, TskvTableSchemaRef};
 use models::schema::TIME_FIELD_NAME;
 use models::SeriesId;
 use snafu::{OptionExt, ResultExt};
@@ -54,6 +54,7 @@ impl CompactingBlockMetaGroup {
         previous_block: Option<CompactingBlock>,
         max_block_size: usize,
         time_range: &TimeRange,
+        reencode_schema: Option<&TskvTableSchemaRef>,
         compacting_files: &mut [CompactingFile],
         metrics: &mut VnodeCompactionMetrics,
     ) -> TskvResult<Vec<CompactingBlock>> {
@@ -68,8 +69,12 @@ impl CompactingBlockMetaGroup {
                 self.blk_metas[0].meta().table_name()
             ),
         })?;
+        // The data blocks of the table to re-encode are always decoded, and then
+        // encoded with the columns of the current table schema.
+        let reencode_schema = reencode_schema.filter(|schema| schema.name == table_schema.name);
 
-        if self.blk_metas.len() == 1
+        if reencode_schema.is_none()
+            && self.blk_metas.len() == 1
             && !compacting_files[self.blk_metas[0].compacting_file_index()].has_tombstone()
             && self.blk_metas[0].included_in_time_range(time_range)?
         {
@@ -156,13 +161,24 @@ impl CompactingBlockMetaGroup {
                 metrics.read_end();
                 record_batches
             };
-            let record_batches = {
+            let mut record_batches = {
                 metrics.merge_begin();
                 let record_batches =
                     Self::merge_record_batches(record_batches, max_block_size).await?;
                 metrics.merge_end();
                 record_batches
             };
+            let table_schema = match reencode_schema {
+                Some(schema) => {
+                    let target_schema = schema.to_record_data_schema();
+                    record_batches = record_batches
+                        .into_iter()
+                        .map(|rb| Self::record_batch_project_schema(rb, target_schema.clone()))
+                        .collect::<TskvResult<Vec<_>>>()?;
+                    schema.clone()
+                }
+                None => table_schema,
+            };
             let merged_blks = record_batches
                 .into_iter()
                 .map(|rb| {
@@ -533,7 +533,7 @@ pub async fn run_normal_compaction_job(
     }
 
     let (mut version_edit, file_metas) =
-        compact_files(request, ctx, tsm_readers, TimeRange::all(), metrics).await?;
+        compact_files(request, ctx, tsm_readers, TimeRange::all(), None, metrics).await?;
 
     // Level 0 files that can be deleted after compaction.
     version_edit.del_files = tsm_file_metas_will_delete;
@@ -542,6 +542,46 @@ pub async fn run_normal_compaction_job(
     Ok(Some((version_edit, file_metas)))
 }
 
+/// Rewrite the files of the request, the data blocks of the table are decoded and then
+/// encoded with the columns of `table_schema`, other data blocks are copied if possible.
+pub async fn run_reencode_job(
+    request: CompactReq,
+    table_schema: TskvTableSchemaRef,
+    ctx: Arc<GlobalContext>,
+    metrics: VnodeCompactionMetrics,
+) -> TskvResult<Option<(VersionEdit, HashMap<ColumnFileId, Arc<BloomFilter>>)>> {
+    info!(
+        "Compaction({}): Running reencode job of table {} on {request}",
+        request.compact_task, table_schema.name
+    );
+
+    if request.files.is_empty() {
+        return Ok(None);
+    }
+    let compact_task = request.compact_task;
+    let mut tsm_file_metas_will_delete: Vec<CompactMeta> = Vec::new();
+    let mut tsm_readers = Vec::with_capacity(request.files.len());
+    for file in request.files.iter() {
+        tsm_file_metas_will_delete.push(CompactMeta::from(file.as_ref()));
+        let tsm_reader = request.version.get_tsm_reader(file.file_path()).await?;
+        tsm_readers.push(tsm_reader);
+    }
+
+    let (mut version_edit, file_metas) = compact_files(
+        request,
+        ctx,
+        tsm_readers,
+        TimeRange::all(),
+        Some(table_schema),
+        metrics,
+    )
+    .await?;
+    version_edit.del_files = tsm_file_metas_will_delete;
+
+    info!("Compaction({compact_task}): Reencode finished, version edits: {version_edit:?}");
+    Ok(Some((version_edit, file_metas)))
+}
+
 pub async fn run_delta_compaction_job(
     request: CompactReq,
     ctx: Arc<GlobalContext>,
@@ -593,7 +633,7 @@ pub async fn run_delta_compaction_job(
     }
 
     let (mut version_edit, file_metas) =
-        compact_files(request, ctx, tsm_readers, out_time_range, metrics).await?;
+        compact_files(request, ctx, tsm_readers, out_time_range, None, metrics).await?;
 
     // Level 0 files that can be deleted after compaction.
     version_edit.del_files = l0_file_metas_will_delete;
@@ -613,6 +653,7 @@ async fn compact_files(
     ctx: Arc<GlobalContext>,
     tsm_readers: Vec<Arc<TsmReader>>,
     out_time_range: TimeRange,
+    reencode_schema: Option<TskvTableSchemaRef>,
     mut metrics: VnodeCompactionMetrics,
 ) -> TskvResult<(VersionEdit, HashMap<ColumnFileId, Arc<BloomFilter>>)> {
     let max_block_size = request.version.storage_opt().max_datablock_size as usize;
@@ -652,6 +693,7 @@ async fn compact_files(
                     previous_merged_block.take(),
                     max_block_size,
                     &out_time_range,
+                    reencode_schema.as_ref(),
                     &mut state.compacting_files,
                     &mut metrics,
                 )
@@ -5,6 +5,7 @@ mod compacting_block_meta;
 mod flush;
 pub mod job;
 pub mod metrics;
+pub mod offload;
 mod picker;
 mod utils;
 mod writer_wrapper;
@@ -89,6 +90,10 @@ pub struct CompactReq {
 }
 
 impl CompactReq {
+    pub fn files(&self) -> &[Arc<ColumnFile>] {
+        &self.files
+    }
+
     /// Split the `files` into delta files and an optional level-1~4 file. Only for delta compaction.
     pub fn split_delta_and_level_files(&self) -> (Vec<Arc<ColumnFile>>, Option<Arc<ColumnFile>>) {
         debug_assert!(self.in_level == 0);
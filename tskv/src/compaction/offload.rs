@@ -0,0 +1,294 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use models::utils::now_timestamp_nanos;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::{oneshot, RwLock};
+use trace::{error, info};
+use utils::precision::{timestamp_convert, Precision};
+
+use crate::error::{CommonSnafu, TskvResult};
+use crate::file_system::remote_filesystem::RemoteFileSystem;
+use crate::summary::{CompactMeta, FileLocation, SummaryTask, VersionEdit};
+use crate::tsfamily::column_file::ColumnFile;
+use crate::tsfamily::tseries_family::TseriesFamily;
+use crate::ColumnFileId;
+
+/// Pick the local column files of a vnode that are cold enough to be offloaded:
+/// - the level of file is not less than `min_level`;
+/// - the newest data in file is older than `offload_after`.
+///
+/// Picked files are marked as compacting, so they won't be compacted while uploading.
+pub async fn pick_offload_files(
+    ts_family: &TseriesFamily,
+    remote: &RemoteFileSystem,
+) -> Vec<Arc<ColumnFile>> {
+    let config = remote.config();
+    let offload_after = config.offload_after.as_nanos() as i64;
+    let precision = *ts_family.db_config().precision();
+    let expire_ts = match timestamp_convert(
+        Precision::NS,
+        precision,
+        now_timestamp_nanos() - offload_after,
+    ) {
+        Some(ts) => ts,
+        None => return vec![],
+    };
+
+    let version = ts_family.version();
+    let mut files = Vec::new();
+    for level in version.levels_info().iter() {
+        if level.level < config.min_level as u32 {
+            continue;
+        }
+        for file in level.files.iter() {
+            if file.is_deleted() || file.is_delta() || file.is_remote() {
+                continue;
+            }
+            if file.time_range().max_ts >= expire_ts {
+                continue;
+            }
+            if file.mark_compacting().await {
+                files.push(file.clone());
+            }
+        }
+    }
+    files
+}
+
+/// Upload cold column files of a vnode to the remote file system, and then
+/// replace them with the remote ones in a new version.
+///
+/// Returns the number of offloaded files.
+pub async fn offload_cold_files(
+    ts_family: Arc<RwLock<TseriesFamily>>,
+    remote: Arc<RemoteFileSystem>,
+    summary_task_sender: Sender<SummaryTask>,
+) -> TskvResult<usize> {
+    let (files, version, tsf_id, owner) = {
+        let tsf = ts_family.read().await;
+        let files = pick_offload_files(&tsf, &remote).await;
+        (files, tsf.version(), tsf.tf_id(), tsf.owner())
+    };
+    if files.is_empty() {
+        return Ok(0);
+    }
+
+    let mut version_edit =
+        VersionEdit::new_update_vnode(tsf_id, owner.as_ref().clone(), version.last_seq());
+    let mut file_metas: HashMap<ColumnFileId, _> = HashMap::with_capacity(files.len());
+    let mut uploaded_files = Vec::with_capacity(files.len());
+    for file in files.iter() {
+        let bloom_filter = match file.load_bloom_filter().await {
+            Ok(f) => f,
+            Err(e) => {
+                error!("Offload: failed to load bloom filter of file {file}: {e}");
+                continue;
+            }
+        };
+        if let Err(e) = remote.upload(file.file_path()).await {
+            error!("Offload: failed to upload file {file}: {e}");
+            continue;
+        }
+        info!("Offload: uploaded file {file}");
+
+        let mut meta = CompactMeta::from(file.as_ref());
+        meta.tsf_id = tsf_id;
+        meta.location = FileLocation::Remote;
+        version_edit.del_file(meta.level, meta.file_id, meta.is_delta);
+        version_edit.add_file(meta, version.max_level_ts());
+        file_metas.insert(file.file_id(), bloom_filter);
+        uploaded_files.push(file.clone());
+    }
+
+    let file_ids: HashSet<ColumnFileId> = files.iter().map(|f| f.file_id()).collect();
+    if uploaded_files.is_empty() {
+        version.unmark_compacting_files(&file_ids).await;
+        return Ok(0);
+    }
+
+    let (summary_tx, summary_rx) = oneshot::channel();
+    let task = SummaryTask::new(
+        ts_family.clone(),
+        version_edit,
+        Some(file_metas),
+        None,
+        summary_tx,
+    );
+    let result = match summary_task_sender.send(task).await {
+        Ok(_) => summary_rx.await.unwrap_or_else(|e| {
+            Err(CommonSnafu {
+                reason: format!("failed to receive summary task result: {e}"),
+            }
+            .build())
+        }),
+        Err(e) => Err(CommonSnafu {
+            reason: format!("failed to send summary task: {e}"),
+        }
+        .build()),
+    };
+    if let Err(e) = result {
+        version.unmark_compacting_files(&file_ids).await;
+        for file in uploaded_files.iter() {
+            if let Err(e) = remote.delete(file.file_path()).await {
+                error!("Offload: failed to remove uploaded file {file}: {e}");
+            }
+        }
+        return Err(e);
+    }
+
+    Ok(uploaded_files.len())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use arrow_array::RecordBatch;
+    use arrow_schema::TimeUnit;
+    use cache::ShardedAsyncCache;
+    use config::tskv::TieredStorageConfig;
+    use memory_pool::{GreedyMemoryPool, MemoryPool};
+    use metrics::metric_register::MetricsRegister;
+    use models::codec::Encoding;
+    use models::schema::tskv_table_schema::{ColumnType, TableColumn, TskvTableSchema};
+    use models::ValueType;
+    use tokio::sync::{mpsc, RwLock};
+
+    use super::offload_cold_files;
+    use crate::compaction::compact::test::{
+        i64_column, timestamp_column, write_data_blocks_to_column_file,
+    };
+    use crate::kv_option::Options;
+    use crate::mem_cache::memcache::MemCache;
+    use crate::summary::{CompactMeta, SummaryRequest, SummaryTask, VersionEdit};
+    use crate::tsfamily::level_info::LevelInfo;
+    use crate::tsfamily::tseries_family::TseriesFamily;
+    use crate::tsfamily::version::Version;
+
+    #[tokio::test]
+    async fn test_offload_and_read() {
+        let dir = "/tmp/test/compaction/offload";
+        let _ = std::fs::remove_dir_all(dir);
+        let mut config = config::tskv::get_config_for_test();
+        config.storage.path = format!("{dir}/data");
+        config.storage.tiered = TieredStorageConfig {
+            enable: true,
+            url: format!("file://{dir}/remote"),
+            min_level: 1,
+            offload_after: Duration::ZERO,
+            ..Default::default()
+        };
+        let mut opt = Options::from(&config);
+        opt.open_tiered_storage().unwrap();
+        let opt = Arc::new(opt);
+        let remote = opt.storage.tiered.clone().unwrap();
+
+        let schema = Arc::new(TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "public".to_string(),
+            "test0".to_string(),
+            vec![
+                TableColumn::new_time_column(0, TimeUnit::Nanosecond),
+                TableColumn::new(
+                    1,
+                    "f1".to_string(),
+                    ColumnType::Field(ValueType::Integer),
+                    Encoding::default(),
+                ),
+            ],
+        ));
+        let data = RecordBatch::try_new(
+            schema.to_record_data_schema(),
+            vec![
+                timestamp_column(vec![1, 2, 3]),
+                i64_column(vec![11, 12, 13]),
+            ],
+        )
+        .unwrap();
+
+        let owner = Arc::new("cnosdb.public".to_string());
+        let tsm_dir = opt.storage.tsm_dir(&owner, 1);
+        let (_, files) = write_data_blocks_to_column_file(
+            &tsm_dir,
+            vec![HashMap::from([(1, data.clone())])],
+            schema,
+            1,
+        )
+        .await;
+        let version = Version::new(
+            1,
+            owner.clone(),
+            opt.storage.clone(),
+            1,
+            LevelInfo::init_levels(owner.clone(), 1, opt.storage.clone()),
+            3,
+            Arc::new(ShardedAsyncCache::create_lru_sharded_cache(1)),
+        );
+        let mut ve = VersionEdit::new_update_vnode(1, owner.to_string(), 1);
+        let mut meta = CompactMeta::from(files[0].as_ref());
+        meta.tsf_id = 1;
+        ve.add_file(meta, 3);
+        let version = version.copy_apply_version_edits(ve, &mut HashMap::new());
+
+        let memory_pool: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(1024 * 1024 * 1024));
+        let ts_family = Arc::new(RwLock::new(TseriesFamily::new(
+            1,
+            owner.clone(),
+            MemCache::new(1, 2, 1000, 1, 1, &memory_pool),
+            Arc::new(version),
+            Arc::new(Default::default()),
+            opt.storage.clone(),
+            memory_pool,
+            &Arc::new(MetricsRegister::default()),
+        )));
+
+        // Apply the version edits like the summary does.
+        let (summary_task_sender, mut summary_task_receiver) = mpsc::channel::<SummaryTask>(1);
+        tokio::spawn(async move {
+            while let Some(task) = summary_task_receiver.recv().await {
+                let SummaryRequest {
+                    version_edit,
+                    ts_family,
+                    file_metas,
+                    ..
+                } = task.request;
+                let mut file_metas = file_metas.unwrap_or_default();
+                let version = ts_family
+                    .read()
+                    .await
+                    .version()
+                    .copy_apply_version_edits(version_edit, &mut file_metas);
+                ts_family.write().await.new_version(Arc::new(version), None);
+                let _ = task.call_back.send(Ok(()));
+            }
+        });
+
+        let offloaded = offload_cold_files(ts_family.clone(), remote, summary_task_sender)
+            .await
+            .unwrap();
+        assert_eq!(offloaded, 1);
+
+        let version = ts_family.read().await.version();
+        let files = &version.levels_info()[1].files;
+        assert_eq!(files.len(), 1);
+        assert!(files[0].is_remote());
+        // Make sure the data is read from the remote file.
+        let _ = std::fs::remove_file(files[0].file_path());
+
+        let reader = files[0].open_tsm_reader().await.unwrap();
+        let mut record_batches = vec![];
+        for (sid, chunk) in reader.chunk() {
+            for column_group_id in chunk.column_group().keys() {
+                let batch = reader
+                    .read_record_batch(*sid, *column_group_id)
+                    .await
+                    .unwrap();
+                record_batches.push(batch);
+            }
+        }
+        assert_eq!(record_batches, vec![data]);
+    }
+}
@@ -11,7 +11,7 @@ use crate::tsfamily::column_file::ColumnFile;
 use crate::tsfamily::level_info::LevelInfo;
 use crate::tsfamily::version::Version;
 use crate::tsm::tombstone::TsmTombstoneCache;
-use crate::{LevelId, TskvResult};
+use crate::{LevelId, TskvResult, VnodeId};
 
 pub async fn pick_compaction(
     compact_task: CompactTask,
@@ -36,6 +36,37 @@ pub async fn pick_compaction(
     }
 }
 
+/// Pick the files of level-1 to level-4 to rewrite them, files of a level are compacted
+/// into the same level, so the levels are unchanged after the files are rewritten.
+pub async fn pick_reencode(vnode_id: VnodeId, version: Arc<Version>) -> Vec<CompactReq> {
+    let mut requests = Vec::with_capacity(4);
+    for level_info in version.levels_info()[1..].iter() {
+        let mut files = Vec::with_capacity(level_info.files.len());
+        for file in level_info.files.iter() {
+            if file.mark_compacting().await {
+                files.push(file.clone());
+            }
+        }
+        if files.is_empty() {
+            continue;
+        }
+        debug!(
+            "Picker(reencode): picked {} files of level {}",
+            files.len(),
+            level_info.level
+        );
+        requests.push(CompactReq {
+            compact_task: CompactTask::Manual(vnode_id),
+            version: version.clone(),
+            files,
+            in_level: level_info.level,
+            out_level: level_info.level,
+            out_time_range: level_info.time_range,
+        });
+    }
+    requests
+}
+
 /// Compaction picker for picking a level from level-1 to level-4, and then
 /// pick inner files of the level.
 #[derive(Debug)]
@@ -495,11 +526,12 @@ impl DeltaCompactionPicker {
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashSet;
     use std::sync::Arc;
 
     use models::predicate::domain::TimeRange;
 
-    use super::advise_out_level;
+    use super::{advise_out_level, pick_reencode};
     use crate::compaction::picker::{DeltaCompactionPicker, LevelCompactionPicker};
     use crate::compaction::test::{FileSketch, VersionSketch};
     use crate::compaction::{create_options, CompactTask};
@@ -786,4 +818,34 @@ mod test {
         assert_eq!(compact_req.out_level, 4);
         assert_eq!(compact_req.out_time_range, (-100, -1).into());
     }
+
+    /// The files picked for reencoding are marked as compacting until they are unmarked.
+    #[tokio::test]
+    async fn test_pick_reencode() {
+        let dir = "/tmp/test/pick/reencode";
+        let _ = std::fs::remove_dir_all(dir);
+        let opt = create_options(dir.to_string(), 1);
+
+        let version = VersionSketch::new(dir, Arc::new("dba".to_string()), 1)
+            .add(0, FileSketch(5, (1, 100), 10, false))
+            .add(1, FileSketch(3, (101, 200), 100, false))
+            .add(1, FileSketch(4, (201, 300), 100, false))
+            .add(2, FileSketch(1, (1, 100), 200, false))
+            .add(2, FileSketch(2, (101, 200), 200, false))
+            .to_version(opt.storage.clone())
+            .await;
+        let version = Arc::new(version);
+
+        let reqs = pick_reencode(1, version.clone()).await;
+        assert_eq!(reqs.len(), 2);
+        let file_ids = reqs
+            .iter()
+            .flat_map(|r| r.files().iter().map(|f| f.file_id()))
+            .collect::<HashSet<_>>();
+        assert_eq!(file_ids, HashSet::from([1, 2, 3, 4]));
+        assert!(pick_reencode(1, version.clone()).await.is_empty());
+
+        version.unmark_compacting_files(&file_ids).await;
+        assert_eq!(pick_reencode(1, version).await.len(), 2);
+    }
 }
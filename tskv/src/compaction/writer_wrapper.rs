@@ -6,7 +6,7 @@ use utils::BloomFilter;
 
 use crate::compaction::{CompactReq, CompactTask, CompactingBlock};
 use crate::context::GlobalContext;
-use crate::summary::CompactMeta;
+use crate::summary::{CompactMeta, FileLocation};
 use crate::tsm::writer::TsmWriter;
 use crate::{ColumnFileId, LevelId, TskvResult, VersionEdit};
 
@@ -69,6 +69,7 @@ impl WriterWrapper {
                 min_ts: tsm_writer.min_ts(),
                 max_ts: tsm_writer.max_ts(),
                 is_delta: false,
+                location: FileLocation::Local,
             };
             self.version_edit.add_file(cm, self.max_level_ts);
             let bloom_filter = tsm_writer.into_series_bloom_filter();
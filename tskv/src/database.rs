@@ -1,5 +1,5 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::mem::size_of;
 use std::path::Path;
 use std::sync::Arc;
@@ -13,23 +13,26 @@ use models::schema::database_schema::{DatabaseConfig, DatabaseSchema};
 use models::schema::tskv_table_schema::{TskvTableSchema, TskvTableSchemaRef};
 use models::{SeriesId, SeriesKey};
 use protos::models::{Column, ColumnType, FieldType, Table};
-use snafu::{OptionExt, ResultExt};
+use snafu::{IntoError, OptionExt, ResultExt};
 use tokio::sync::mpsc::Sender;
 use tokio::sync::{oneshot, RwLock};
 use trace::error;
+use utils::bitset::ImmutBitSet;
 use utils::precision::Precision;
 
 use crate::context::GlobalContext;
 use crate::error::{
-    CommonSnafu, IndexErrSnafu, ModelSnafu, SchemaSnafu, TableNotFoundSnafu, TskvResult,
+    CommonSnafu, IndexErrSnafu, ModelSnafu, SchemaSnafu, SeriesLimitExceededSnafu,
+    TableNotFoundSnafu, TskvResult,
 };
+use crate::index::cardinality::{SeriesCardinality, SeriesLimits};
 use crate::index::ts_index::TSIndex;
-use crate::index::IndexResult;
+use crate::index::{IndexError, IndexResult};
 use crate::kv_option::Options;
 use crate::mem_cache::row_data::{OrderedRowsData, RowData};
 use crate::mem_cache::series_data::RowGroup;
 use crate::schema::schemas::DBschemas;
-use crate::summary::{SummaryTask, VersionEdit};
+use crate::summary::{FileLocation, SummaryTask, VersionEdit};
 use crate::tsfamily::level_info::LevelInfo;
 use crate::tsfamily::tseries_family::{TseriesFamily, TsfFactory};
 use crate::tsfamily::version::Version;
@@ -50,6 +53,8 @@ pub struct Database {
     ts_indexes: HashMap<VnodeId, Arc<RwLock<TSIndex>>>,
     ts_families: HashMap<VnodeId, Arc<RwLock<TseriesFamily>>>,
     tsf_factory: TsfFactory,
+    /// Number of the series in the indexes of all the vnodes of the database on the node.
+    series_cardinality: Arc<SeriesCardinality>,
 }
 
 #[derive(Debug)]
@@ -113,6 +118,7 @@ impl Database {
             memory_pool.clone(),
             metrics_register.clone(),
         );
+        let series_cardinality = Arc::new(SeriesCardinality::new(metrics_register, &owner));
 
         let db = Self {
             opt,
@@ -123,6 +129,7 @@ impl Database {
             ts_indexes: HashMap::new(),
             ts_families: HashMap::new(),
             tsf_factory,
+            series_cardinality,
         };
 
         Ok(db)
@@ -188,6 +195,8 @@ impl Database {
             file_metas.insert(new_file_id, bloom_filter.clone());
 
             f.file_id = new_file_id;
+            // Files offloaded on the source node are downloaded to the local directory.
+            f.location = FileLocation::Local;
         }
         for f in ve.del_files.iter_mut() {
             let new_file_id = ctx.global_ctx.file_id_next();
@@ -394,6 +403,89 @@ impl Database {
         Ok(res_sids)
     }
 
+    /// Checks that the new series of the tables don't exceed the series limits of the tenant.
+    ///
+    /// It's checked on the leader before the points are proposed, rather than when the
+    /// raft log is applied, so that all the replicas apply the same entries.
+    pub async fn check_series_limits(
+        &self,
+        tables: FlatBufferTable<'_>,
+        ts_index: Arc<RwLock<TSIndex>>,
+    ) -> TskvResult<()> {
+        let series_limits = self.series_limits().await?;
+        if series_limits.is_unlimited() {
+            return Ok(());
+        }
+
+        // table -> number of the new series
+        let mut new_series = HashMap::new();
+        let ts_index = ts_index.read().await;
+        for table in tables {
+            let table_name = table.tab_ext()?;
+            let columns = table.columns().context(CommonSnafu {
+                reason: "table missing columns".to_string(),
+            })?;
+            let fb_schema = FbSchema::from_fb_column(table_name, columns)?;
+            let schema = self
+                .schemas
+                .get_table_schema(fb_schema.table)
+                .await
+                .context(SchemaSnafu)?;
+
+            let mut new_keys = HashSet::new();
+            for row_count in 0..table.num_rows() as usize {
+                // The series key can't be built if the table or a tag is not created yet,
+                // such series must be new.
+                let series_key = schema.as_ref().and_then(|schema| {
+                    SeriesKey::build_series_key(
+                        fb_schema.table,
+                        &columns,
+                        schema,
+                        &fb_schema.tag_indexes,
+                        row_count,
+                    )
+                    .ok()
+                });
+                if let Some(series_key) = series_key {
+                    let exists = ts_index
+                        .get_series_id(&series_key)
+                        .await
+                        .context(IndexErrSnafu)?
+                        .is_some();
+                    if !exists {
+                        new_keys.insert(row_tags(&fb_schema, &columns, row_count)?);
+                    }
+                } else {
+                    new_keys.insert(row_tags(&fb_schema, &columns, row_count)?);
+                }
+            }
+            if !new_keys.is_empty() {
+                *new_series.entry(table_name.to_string()).or_default() += new_keys.len() as u64;
+            }
+        }
+
+        self.series_cardinality
+            .check(&new_series, &series_limits)
+            .map_err(|e| match e {
+                IndexError::SeriesLimitExceeded { reason, .. } => {
+                    SeriesLimitExceededSnafu { reason }.build()
+                }
+                e => IndexErrSnafu.into_error(e),
+            })
+    }
+
+    async fn series_limits(&self) -> TskvResult<SeriesLimits> {
+        let config = self
+            .schemas
+            .tenant_object_config()
+            .await
+            .context(SchemaSnafu)?;
+        Ok(SeriesLimits {
+            max_series_per_database: config.and_then(|c| c.max_series_per_database),
+            max_series_per_table: config.and_then(|c| c.max_series_per_table),
+        })
+    }
+
     pub async fn get_series_key(
         &self,
         vnode_id: u32,
@@ -418,6 +510,10 @@ impl Database {
     ) -> TskvResult<Arc<RwLock<TSIndex>>> {
         let id = ts_family.read().await.tf_id();
         let ts_index = ts_family.read().await.rebuild_index().await?;
+        ts_index
+            .write()
+            .await
+            .set_cardinality(self.series_cardinality.clone());
 
         self.ts_indexes.insert(id, ts_index.clone());
 
@@ -475,6 +571,9 @@ impl Database {
         let idx = TSIndex::new(path, self.opt.storage.index_cache_capacity)
             .await
             .context(IndexErrSnafu)?;
+        idx.write()
+            .await
+            .set_cardinality(self.series_cardinality.clone());
 
         self.ts_indexes.insert(id, idx.clone());
 
@@ -489,6 +588,10 @@ impl Database {
         Ok(self.schemas.db_schema().await?)
     }
 
+    pub fn series_cardinality(&self) -> Arc<SeriesCardinality> {
+        self.series_cardinality.clone()
+    }
+
     pub fn owner(&self) -> Arc<String> {
         self.owner.clone()
     }
@@ -505,6 +608,29 @@ impl Database {
     }
 }
 
+/// Names and values of the non-null tags of the row, which identify the series of the row
+/// even if the table or the tags are not created yet.
+fn row_tags(
+    fb_schema: &FbSchema,
+    columns: &Vector<ForwardsUOffset<Column>>,
+    row_count: usize,
+) -> TskvResult<Vec<(String, String)>> {
+    let mut tags = Vec::with_capacity(fb_schema.tag_indexes.len());
+    for (idx, name) in fb_schema.tag_indexes.iter().zip(&fb_schema.tag_names) {
+        let column = columns.get(*idx);
+        let nullbits = column.nullbit_ext()?;
+        let nullbits =
+            ImmutBitSet::new_without_check(column.string_values_len()?, nullbits.bytes());
+        if nullbits.get(row_count) {
+            let value = column.string_values()?.get(row_count);
+            tags.push((name.to_string(), value.to_string()));
+        }
+    }
+    tags.sort();
+
+    Ok(tags)
+}
+
 #[derive(Debug)]
 pub struct FbSchema<'a> {
     pub table: &'a str,
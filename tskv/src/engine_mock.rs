@@ -1,6 +1,7 @@
 #![allow(dead_code, unused_variables)]
 
 use std::fmt::Debug;
+use std::path::Path;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -10,10 +11,11 @@ use models::predicate::domain::ColumnDomains;
 use models::{SeriesId, SeriesKey};
 
 use crate::error::TskvResult;
+use crate::index::cardinality::{CardinalityGroup, CardinalityOptions};
 use crate::kv_option::StorageOptions;
 use crate::tsfamily::super_version::SuperVersion;
 use crate::vnode_store::VnodeStorage;
-use crate::Engine;
+use crate::{Engine, VnodeSnapshot};
 
 #[derive(Debug, Default)]
 pub struct MockEngine {}
@@ -85,6 +87,26 @@ impl Engine for MockEngine {
         Ok(vec![])
     }
 
+    async fn series_cardinality(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        options: &CardinalityOptions,
+    ) -> TskvResult<Vec<CardinalityGroup>> {
+        Ok(vec![])
+    }
+
+    async fn check_series_limits(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        points: &[u8],
+    ) -> TskvResult<()> {
+        Ok(())
+    }
+
     async fn get_db_version(
         &self,
         tenant: &str,
@@ -106,6 +128,32 @@ impl Engine for MockEngine {
         todo!()
     }
 
+    async fn reencode(
+        &self,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        vnode_ids: Vec<VnodeId>,
+    ) -> TskvResult<()> {
+        todo!()
+    }
+
+    async fn backup_vnode(&self, vnode_id: VnodeId) -> TskvResult<Option<VnodeSnapshot>> {
+        todo!()
+    }
+
+    async fn restore_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        snapshot: VnodeSnapshot,
+        snapshot_dir: &Path,
+        until: Option<i64>,
+    ) -> TskvResult<()> {
+        todo!()
+    }
+
     async fn get_vnode_hash_tree(&self, vnode_ids: VnodeId) -> TskvResult<RecordBatch> {
         todo!()
     }
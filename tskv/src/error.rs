@@ -417,6 +417,14 @@ pub enum TskvError {
         source: FileSystemError,
     },
 
+    #[error_code(code = 59)]
+    #[snafu(display("{}, new series are rejected", reason))]
+    SeriesLimitExceeded {
+        reason: String,
+        location: Location,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("ModelError: {}", source))]
     #[error_code(code = 89)]
     ModelError {
@@ -38,6 +38,21 @@ pub enum FileSystemError {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Object store error: {}", source))]
+    ObjectStore {
+        source: object_store::Error,
+        location: Location,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid remote storage url '{}': {}", url, reason))]
+    InvalidRemoteUrl {
+        url: String,
+        reason: String,
+        location: Location,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("async file system stopped"))]
     Cancel {
         location: Location,
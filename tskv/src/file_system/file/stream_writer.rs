@@ -91,12 +91,18 @@ impl FileStreamWriter {
         Ok(())
     }
 
-    pub async fn flush(&mut self) -> Result<()> {
+    /// Writes the buffered data to the file without syncing it.
+    pub async fn flush_buffer(&mut self) -> Result<()> {
         let size = self
             .file
             .write_at(self.pos, &self.buf.consume_data())
             .await?;
         self.set_pos(self.pos + size);
+        Ok(())
+    }
+
+    pub async fn flush(&mut self) -> Result<()> {
+        self.flush_buffer().await?;
         self.file.sync_data().await?;
         Ok(())
     }
@@ -143,6 +149,14 @@ impl FileStreamWriter {
         &self.path
     }
 
+    /// Returns a handle of the file to sync the data written by this writer from other tasks.
+    pub fn shared_writable_file(&self) -> Option<Box<dyn WritableFile>> {
+        self.file
+            .as_any()
+            .downcast_ref::<AsyncFile>()
+            .map(|file| Box::new(file.clone()) as Box<dyn WritableFile>)
+    }
+
     pub fn shared_file(&self) -> Option<Box<FileStreamReader>> {
         self.file.as_any().downcast_ref::<AsyncFile>().map(|file| {
             Box::new(FileStreamReader::new(
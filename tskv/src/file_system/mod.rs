@@ -10,6 +10,7 @@ pub mod async_filesystem;
 pub mod error;
 pub(crate) mod file;
 pub mod file_info;
+pub mod remote_filesystem;
 
 /// File system operations
 /// S3 / HDFS / GCS / Azure / local filesystem
@@ -0,0 +1,398 @@
+use std::fmt::{Debug, Formatter};
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use cache::{AsyncCache, ShardedAsyncCache};
+use config::tskv::TieredStorageConfig;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use object_store::aws::AmazonS3Builder;
+use object_store::azure::MicrosoftAzureBuilder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::path::Path as ObjectPath;
+use object_store::ObjectStore;
+use snafu::ResultExt;
+use tokio::io::AsyncWriteExt;
+use url::Url;
+
+use crate::file_system::error::{
+    FileSystemResult, InvalidRemoteUrlSnafu, ObjectStoreSnafu, StdIOSnafu,
+};
+use crate::file_system::file::stream_reader::FileStreamReader;
+use crate::file_system::file::ReadableFile;
+
+type BlockCache = ShardedAsyncCache<(String, usize), Bytes>;
+
+/// File system of the cold tier, files are stored in an object store
+/// (S3 / GCS / Azure / local directory).
+///
+/// A remote file is addressed by the local path it had under `root`,
+/// so the path of a column file does not change after it is offloaded.
+pub struct RemoteFileSystem {
+    config: TieredStorageConfig,
+    root: PathBuf,
+    prefix: String,
+    store: Arc<dyn ObjectStore>,
+    block_cache: Arc<BlockCache>,
+}
+
+impl RemoteFileSystem {
+    pub fn new(root: impl AsRef<Path>, config: &TieredStorageConfig) -> FileSystemResult<Self> {
+        let url = Url::parse(&config.url).map_err(|e| {
+            InvalidRemoteUrlSnafu {
+                url: config.url.clone(),
+                reason: e.to_string(),
+            }
+            .build()
+        })?;
+        let bucket = url.host_str().unwrap_or_default();
+        let (store, prefix): (Arc<dyn ObjectStore>, String) = match url.scheme() {
+            "s3" => {
+                let mut builder = AmazonS3Builder::new()
+                    .with_bucket_name(bucket)
+                    .with_allow_http(true);
+                if let Some(region) = &config.region {
+                    builder = builder.with_region(region);
+                }
+                if let Some(endpoint_url) = &config.endpoint_url {
+                    builder = builder.with_endpoint(endpoint_url);
+                }
+                if let Some(access_key_id) = &config.access_key_id {
+                    builder = builder.with_access_key_id(access_key_id);
+                }
+                if let Some(secret_access_key) = &config.secret_access_key {
+                    builder = builder.with_secret_access_key(secret_access_key);
+                }
+                let store = builder.build().context(ObjectStoreSnafu)?;
+                (Arc::new(store), url.path().to_string())
+            }
+            "gcs" => {
+                let mut builder = GoogleCloudStorageBuilder::new().with_bucket_name(bucket);
+                if let Some(path) = &config.gcs_service_account_path {
+                    builder = builder.with_service_account_path(path);
+                }
+                let store = builder.build().context(ObjectStoreSnafu)?;
+                (Arc::new(store), url.path().to_string())
+            }
+            "azblob" => {
+                let mut builder = MicrosoftAzureBuilder::new().with_container_name(bucket);
+                if let Some(account) = &config.azure_account_name {
+                    builder = builder.with_account(account);
+                }
+                if let Some(access_key) = &config.azure_access_key {
+                    builder = builder.with_access_key(access_key);
+                }
+                let store = builder.build().context(ObjectStoreSnafu)?;
+                (Arc::new(store), url.path().to_string())
+            }
+            "file" => {
+                let dir = url.to_file_path().map_err(|_| {
+                    InvalidRemoteUrlSnafu {
+                        url: config.url.clone(),
+                        reason: "not a valid local directory".to_string(),
+                    }
+                    .build()
+                })?;
+                std::fs::create_dir_all(&dir).context(StdIOSnafu)?;
+                let store = object_store::local::LocalFileSystem::new_with_prefix(dir)
+                    .context(ObjectStoreSnafu)?;
+                (Arc::new(store), String::new())
+            }
+            scheme => {
+                return Err(InvalidRemoteUrlSnafu {
+                    url: config.url.clone(),
+                    reason: format!("unsupported scheme '{scheme}'"),
+                }
+                .build())
+            }
+        };
+
+        let block_num = (config.cache_size / config.block_size.max(1)).max(1) as usize;
+        Ok(Self {
+            config: config.clone(),
+            root: root.as_ref().to_path_buf(),
+            prefix: prefix.trim_matches('/').to_string(),
+            store,
+            block_cache: Arc::new(BlockCache::create_lru_sharded_cache(block_num)),
+        })
+    }
+
+    pub fn config(&self) -> &TieredStorageConfig {
+        &self.config
+    }
+
+    /// Get the object location of a file that was stored at `path` locally.
+    pub fn object_path(&self, path: impl AsRef<Path>) -> FileSystemResult<ObjectPath> {
+        let path = path.as_ref();
+        let relative = path.strip_prefix(&self.root).map_err(|_| {
+            InvalidRemoteUrlSnafu {
+                url: path.display().to_string(),
+                reason: format!("file is not in '{}'", self.root.display()),
+            }
+            .build()
+        })?;
+        let relative = relative.to_string_lossy();
+        if self.prefix.is_empty() {
+            Ok(ObjectPath::from(relative.as_ref()))
+        } else {
+            Ok(ObjectPath::from(format!("{}/{}", self.prefix, relative)))
+        }
+    }
+
+    /// Upload the local file to the object store, returns the uploaded size.
+    pub async fn upload(&self, path: impl AsRef<Path>) -> FileSystemResult<u64> {
+        let location = self.object_path(&path)?;
+        let mut file = tokio::fs::File::open(path.as_ref())
+            .await
+            .context(StdIOSnafu)?;
+        let (multipart_id, mut writer) = self
+            .store
+            .put_multipart(&location)
+            .await
+            .context(ObjectStoreSnafu)?;
+        let res = async {
+            let size = tokio::io::copy(&mut file, &mut writer).await?;
+            writer.shutdown().await?;
+            Ok::<u64, Error>(size)
+        }
+        .await;
+        match res {
+            Ok(size) => Ok(size),
+            Err(e) => {
+                if let Err(abort_err) = self.store.abort_multipart(&location, &multipart_id).await {
+                    trace::error!("Failed to abort uploading '{location}': {abort_err}");
+                }
+                Err(e).context(StdIOSnafu)
+            }
+        }
+    }
+
+    pub async fn open_file_reader(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> FileSystemResult<Box<FileStreamReader>> {
+        let location = self.object_path(&path)?;
+        let meta = self.store.head(&location).await.context(ObjectStoreSnafu)?;
+        let file = ObjectFile {
+            store: self.store.clone(),
+            location,
+            size: meta.size,
+            block_size: self.config.block_size.max(1) as usize,
+            block_cache: self.block_cache.clone(),
+        };
+        Ok(Box::new(FileStreamReader::new(
+            Box::new(file),
+            path.as_ref().to_path_buf(),
+        )))
+    }
+
+    /// Read the whole remote file as a stream of bytes, bypassing the block cache.
+    pub async fn open_stream(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> FileSystemResult<BoxStream<'static, FileSystemResult<Bytes>>> {
+        let location = self.object_path(&path)?;
+        let stream = self
+            .store
+            .get(&location)
+            .await
+            .context(ObjectStoreSnafu)?
+            .into_stream()
+            .map(|res| res.context(ObjectStoreSnafu));
+        Ok(stream.boxed())
+    }
+
+    /// Download the remote file to the local path `dst`.
+    pub async fn download(
+        &self,
+        path: impl AsRef<Path>,
+        dst: impl AsRef<Path>,
+    ) -> FileSystemResult<u64> {
+        let dst = dst.as_ref();
+        if let Some(dir) = dst.parent() {
+            tokio::fs::create_dir_all(dir).await.context(StdIOSnafu)?;
+        }
+        let mut file = tokio::fs::File::create(dst).await.context(StdIOSnafu)?;
+        let mut stream = self.open_stream(path).await?;
+        let mut size = 0_u64;
+        while let Some(data) = stream.next().await {
+            let data = data?;
+            file.write_all(&data).await.context(StdIOSnafu)?;
+            size += data.len() as u64;
+        }
+        file.sync_all().await.context(StdIOSnafu)?;
+        Ok(size)
+    }
+
+    /// Write the bytes to the remote file, replacing it if exists.
+    pub async fn put(&self, path: impl AsRef<Path>, data: Bytes) -> FileSystemResult<()> {
+        let location = self.object_path(&path)?;
+        self.store
+            .put(&location, data)
+            .await
+            .context(ObjectStoreSnafu)?;
+        Ok(())
+    }
+
+    /// Read the whole remote file, returns `None` if not exists.
+    pub async fn get(&self, path: impl AsRef<Path>) -> FileSystemResult<Option<Bytes>> {
+        let location = self.object_path(&path)?;
+        match self.store.get(&location).await {
+            Ok(result) => Ok(Some(result.bytes().await.context(ObjectStoreSnafu)?)),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e).context(ObjectStoreSnafu),
+        }
+    }
+
+    /// List the local paths of the remote files in the directory `dir`.
+    pub async fn list(&self, dir: impl AsRef<Path>) -> FileSystemResult<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        let prefix = self.object_path(dir)?;
+        let objects = self
+            .store
+            .list(Some(&prefix))
+            .await
+            .context(ObjectStoreSnafu)?
+            .try_collect::<Vec<_>>()
+            .await
+            .context(ObjectStoreSnafu)?;
+
+        let mut paths = Vec::with_capacity(objects.len());
+        for object in objects {
+            if let Some(name) = object.location.filename() {
+                paths.push(dir.join(name));
+            }
+        }
+        Ok(paths)
+    }
+
+    pub async fn delete(&self, path: impl AsRef<Path>) -> FileSystemResult<()> {
+        let location = self.object_path(&path)?;
+        self.store
+            .delete(&location)
+            .await
+            .context(ObjectStoreSnafu)?;
+        Ok(())
+    }
+}
+
+impl Debug for RemoteFileSystem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteFileSystem")
+            .field("url", &self.config.url)
+            .field("root", &self.root)
+            .finish()
+    }
+}
+
+impl PartialEq for RemoteFileSystem {
+    fn eq(&self, other: &Self) -> bool {
+        self.config == other.config && self.root == other.root
+    }
+}
+
+impl Eq for RemoteFileSystem {}
+
+/// A readonly file in the object store, reads are split into blocks
+/// of `block_size` and served from the block cache if possible.
+struct ObjectFile {
+    store: Arc<dyn ObjectStore>,
+    location: ObjectPath,
+    size: usize,
+    block_size: usize,
+    block_cache: Arc<BlockCache>,
+}
+
+impl ObjectFile {
+    async fn read_block(&self, block_idx: usize) -> std::io::Result<Bytes> {
+        let key = (self.location.to_string(), block_idx);
+        if let Some(block) = self.block_cache.get(&key).await {
+            return Ok(block);
+        }
+        let start = block_idx * self.block_size;
+        let end = (start + self.block_size).min(self.size);
+        let block = self
+            .store
+            .get_range(&self.location, start..end)
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, e))?;
+        self.block_cache.insert(key, block.clone()).await;
+        Ok(block)
+    }
+}
+
+#[async_trait::async_trait]
+impl ReadableFile for ObjectFile {
+    async fn read_at(&self, pos: usize, data: &mut [u8]) -> std::io::Result<usize> {
+        if pos >= self.size {
+            return Ok(0);
+        }
+        let end = (pos + data.len()).min(self.size);
+        let mut cur = pos;
+        while cur < end {
+            let block_idx = cur / self.block_size;
+            let block = self.read_block(block_idx).await?;
+            let offset = cur - block_idx * self.block_size;
+            let len = block.len().saturating_sub(offset).min(end - cur);
+            if len == 0 {
+                break;
+            }
+            data[cur - pos..cur - pos + len].copy_from_slice(&block[offset..offset + len]);
+            cur += len;
+        }
+        Ok(cur - pos)
+    }
+
+    fn file_size(&self) -> usize {
+        self.size
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use config::tskv::TieredStorageConfig;
+
+    use super::RemoteFileSystem;
+
+    #[tokio::test]
+    async fn test_upload_and_read() {
+        let dir = "/tmp/test/file_system/remote_filesystem";
+        let _ = std::fs::remove_dir_all(dir);
+        let local_dir = format!("{dir}/local");
+        let remote_dir = format!("{dir}/remote");
+        std::fs::create_dir_all(format!("{local_dir}/tsm")).unwrap();
+
+        let config = TieredStorageConfig {
+            enable: true,
+            url: format!("file://{remote_dir}"),
+            block_size: 7,
+            cache_size: 70,
+            ..Default::default()
+        };
+        let remote = RemoteFileSystem::new(&local_dir, &config).unwrap();
+
+        let path = format!("{local_dir}/tsm/_000001.tsm");
+        let data = (0..100_u8).collect::<Vec<_>>();
+        std::fs::write(&path, &data).unwrap();
+        assert_eq!(remote.upload(&path).await.unwrap(), 100);
+        std::fs::remove_file(&path).unwrap();
+
+        let reader = remote.open_file_reader(&path).await.unwrap();
+        assert_eq!(reader.len(), 100);
+        let mut buf = [0_u8; 20];
+        assert_eq!(reader.read_at(5, &mut buf).await.unwrap(), 20);
+        assert_eq!(&buf, &data[5..25]);
+        assert_eq!(reader.read_at(90, &mut buf).await.unwrap(), 10);
+        assert_eq!(&buf[..10], &data[90..]);
+        assert_eq!(reader.read_at(100, &mut buf).await.unwrap(), 0);
+
+        let download_path = format!("{local_dir}/download.tsm");
+        assert_eq!(remote.download(&path, &download_path).await.unwrap(), 100);
+        assert_eq!(std::fs::read(&download_path).unwrap(), data);
+
+        remote.delete(&path).await.unwrap();
+        assert!(remote.open_file_reader(&path).await.is_err());
+    }
+}
@@ -236,6 +236,31 @@ impl IndexMemCache {
         bitmap
     }
 
+    pub fn get_inverted_by_prefix(
+        &self,
+        tab: &str,
+        tag_key: &str,
+        prefix: &[u8],
+        filter: impl Fn(&[u8]) -> bool,
+    ) -> roaring::RoaringBitmap {
+        let tag_map = self
+            .inverted
+            .get(tab)
+            .and_then(|item| item.get(tag_key.as_bytes()));
+        let mut bitmap = roaring::RoaringBitmap::new();
+        if let Some(bt) = tag_map {
+            for (val, rb) in bt
+                .range(prefix.to_vec()..)
+                .take_while(|(val, _)| val.starts_with(prefix))
+            {
+                if filter(val) {
+                    bitmap = bitmap.bitor(rb);
+                }
+            }
+        }
+        bitmap
+    }
+
     pub fn get_inverted_by_tags(&self, tab: &str, tags: &[models::Tag]) -> roaring::RoaringBitmap {
         if tags.is_empty() {
             let mut bitmap = roaring::RoaringBitmap::new();
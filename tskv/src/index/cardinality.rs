@@ -0,0 +1,294 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use metrics::gauge::U64Gauge;
+use metrics::metric_register::MetricsRegister;
+use models::schema::database_schema::split_owner;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use utils::HyperLogLog;
+
+use super::{IndexResult, SeriesLimitExceededSnafu};
+
+const DATABASE_SERIES_METRIC: &str = "database_series";
+const TABLE_SERIES_METRIC: &str = "table_series";
+
+/// Limits of the number of the series, configured by the object limiter of the tenant.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SeriesLimits {
+    pub max_series_per_database: Option<usize>,
+    pub max_series_per_table: Option<usize>,
+}
+
+impl SeriesLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_series_per_database.is_none() && self.max_series_per_table.is_none()
+    }
+}
+
+#[derive(Debug, Default)]
+struct SeriesCounts {
+    database: u64,
+    tables: HashMap<String, u64>,
+}
+
+#[derive(Debug)]
+struct SeriesMetrics {
+    register: Arc<MetricsRegister>,
+    tenant: String,
+    database: String,
+    database_gauge: U64Gauge,
+    table_gauges: HashMap<String, U64Gauge>,
+}
+
+impl SeriesMetrics {
+    fn new(register: Arc<MetricsRegister>, owner: &str) -> Self {
+        let (tenant, database) = split_owner(owner);
+        let database_gauge = register
+            .metric::<U64Gauge>(DATABASE_SERIES_METRIC, "series number of database")
+            .recorder([("tenant", tenant), ("database", database)]);
+
+        Self {
+            tenant: tenant.to_string(),
+            database: database.to_string(),
+            register,
+            database_gauge,
+            table_gauges: HashMap::new(),
+        }
+    }
+
+    fn record(&mut self, counts: &SeriesCounts, table: &str) {
+        self.database_gauge.set(counts.database);
+        let metric = self
+            .register
+            .metric::<U64Gauge>(TABLE_SERIES_METRIC, "series number of table");
+        match counts.tables.get(table) {
+            Some(count) => {
+                let gauge = self
+                    .table_gauges
+                    .entry(table.to_string())
+                    .or_insert_with(|| {
+                        metric.recorder([
+                            ("tenant", self.tenant.as_str()),
+                            ("database", self.database.as_str()),
+                            ("table", table),
+                        ])
+                    });
+                gauge.set(*count);
+            }
+            None => {
+                if self.table_gauges.remove(table).is_some() {
+                    metric.remove([
+                        ("tenant", self.tenant.as_str()),
+                        ("database", self.database.as_str()),
+                        ("table", table),
+                    ]);
+                }
+            }
+        }
+    }
+}
+
+impl Drop for SeriesMetrics {
+    fn drop(&mut self) {
+        let metric = self
+            .register
+            .metric::<U64Gauge>(DATABASE_SERIES_METRIC, "series number of database");
+        metric.remove([
+            ("tenant", self.tenant.as_str()),
+            ("database", self.database.as_str()),
+        ]);
+
+        let metric = self
+            .register
+            .metric::<U64Gauge>(TABLE_SERIES_METRIC, "series number of table");
+        for table in self.table_gauges.keys() {
+            metric.remove([
+                ("tenant", self.tenant.as_str()),
+                ("database", self.database.as_str()),
+                ("table", table.as_str()),
+            ]);
+        }
+    }
+}
+
+/// Number of the series of a database on the node, it's the sum of the series in
+/// the indexes of the vnodes of the database.
+#[derive(Debug, Default)]
+pub struct SeriesCardinality {
+    inner: Mutex<(SeriesCounts, Option<SeriesMetrics>)>,
+}
+
+impl SeriesCardinality {
+    /// Creates a cardinality that records the numbers to the metrics
+    /// `database_series` and `table_series`.
+    pub fn new(register: Arc<MetricsRegister>, owner: &str) -> Self {
+        let metrics = SeriesMetrics::new(register, owner);
+        Self {
+            inner: Mutex::new((SeriesCounts::default(), Some(metrics))),
+        }
+    }
+
+    pub fn database_series(&self) -> u64 {
+        self.inner.lock().0.database
+    }
+
+    pub fn table_series(&self, table: &str) -> u64 {
+        self.inner.lock().0.tables.get(table).copied().unwrap_or(0)
+    }
+
+    /// Checks that the new series of the tables can be added without exceeding the limits.
+    pub fn check(
+        &self,
+        new_series: &HashMap<String, u64>,
+        limits: &SeriesLimits,
+    ) -> IndexResult<()> {
+        let inner = self.inner.lock();
+        let counts = &inner.0;
+        if let Some(max) = limits.max_series_per_database {
+            let total = counts.database + new_series.values().sum::<u64>();
+            if total > max as u64 {
+                return Err(SeriesLimitExceededSnafu {
+                    reason: format!("series of database reached the limit {max}"),
+                }
+                .build());
+            }
+        }
+        if let Some(max) = limits.max_series_per_table {
+            for (table, count) in new_series {
+                let total = counts.tables.get(table).copied().unwrap_or(0) + count;
+                if total > max as u64 {
+                    return Err(SeriesLimitExceededSnafu {
+                        reason: format!("series of table '{table}' reached the limit {max}"),
+                    }
+                    .build());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds the series of the tables, the limits are checked by [`SeriesCardinality::check`]
+    /// before the series are written.
+    pub fn add(&self, series: &HashMap<String, u64>) {
+        let mut inner = self.inner.lock();
+        let (counts, metrics) = &mut *inner;
+        for (table, count) in series {
+            counts.database += count;
+            *counts.tables.entry(table.clone()).or_default() += count;
+            if let Some(metrics) = metrics {
+                metrics.record(counts, table);
+            }
+        }
+    }
+
+    pub fn sub(&self, series: &HashMap<String, u64>) {
+        let mut inner = self.inner.lock();
+        let (counts, metrics) = &mut *inner;
+        for (table, count) in series {
+            counts.database = counts.database.saturating_sub(*count);
+            if let Some(c) = counts.tables.get_mut(table) {
+                *c = c.saturating_sub(*count);
+                if *c == 0 {
+                    counts.tables.remove(table);
+                }
+            }
+            if let Some(metrics) = metrics {
+                metrics.record(counts, table);
+            }
+        }
+    }
+}
+
+/// What `SHOW SERIES CARDINALITY` and `SHOW TAG KEY CARDINALITY` count in a vnode.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CardinalityOptions {
+    /// Count the series of this table, or of all the tables if `None`.
+    pub table: Option<String>,
+    /// Count the series by the values of this tag.
+    pub group_by: Option<String>,
+    /// Count the exact number instead of an estimation.
+    pub exact: bool,
+    /// Count the tag keys that the series have, instead of the series.
+    pub tag_keys: bool,
+}
+
+/// The distinct items of a group, it's mergeable so that the items of a series
+/// in several vnodes are counted once.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DistinctSketch {
+    /// Hashes of the items.
+    Exact(HashSet<u64>),
+    Estimated(HyperLogLog),
+}
+
+impl DistinctSketch {
+    pub fn new(exact: bool) -> Self {
+        match exact {
+            true => Self::Exact(HashSet::new()),
+            false => Self::Estimated(HyperLogLog::new()),
+        }
+    }
+
+    pub fn insert(&mut self, data: &[u8]) {
+        match self {
+            Self::Exact(hashes) => {
+                hashes.insert(HyperLogLog::hash(data));
+            }
+            Self::Estimated(hll) => hll.insert(data),
+        }
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        match (self, other) {
+            (Self::Exact(hashes), Self::Exact(other)) => hashes.extend(other),
+            (Self::Estimated(hll), Self::Estimated(other)) => hll.merge(&other),
+            (Self::Estimated(hll), Self::Exact(other)) => {
+                other.into_iter().for_each(|h| hll.insert_hash(h))
+            }
+            (this, Self::Estimated(mut other)) => {
+                if let Self::Exact(hashes) = &*this {
+                    hashes.iter().for_each(|h| other.insert_hash(*h));
+                }
+                *this = Self::Estimated(other);
+            }
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        match self {
+            Self::Exact(hashes) => hashes.len() as u64,
+            Self::Estimated(hll) => hll.count(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardinalityGroup {
+    pub table: String,
+    /// Value of the GROUP BY tag, `None` if not grouped or the series have no such tag.
+    pub tag_value: Option<String>,
+    pub sketch: DistinctSketch,
+}
+
+/// Merges the groups of the vnodes, returns the number of the distinct items
+/// of the groups ordered by the table and the tag value.
+pub fn merge_cardinality_groups(
+    groups: impl IntoIterator<Item = CardinalityGroup>,
+) -> Vec<(String, Option<String>, u64)> {
+    let mut merged: BTreeMap<(String, Option<String>), DistinctSketch> = BTreeMap::new();
+    for group in groups {
+        match merged.get_mut(&(group.table.clone(), group.tag_value.clone())) {
+            Some(sketch) => sketch.merge(group.sketch),
+            None => {
+                merged.insert((group.table, group.tag_value), group.sketch);
+            }
+        }
+    }
+
+    merged
+        .into_iter()
+        .map(|((table, tag_value), sketch)| (table, tag_value, sketch.count()))
+        .collect()
+}
@@ -212,6 +212,24 @@ impl IndexEngine {
         Ok(bitmap)
     }
 
+    /// Returns the union of the bitmaps of the keys starting with prefix and accepted by filter.
+    pub fn get_series_id_by_prefix(
+        &self,
+        prefix: &[u8],
+        filter: impl Fn(&[u8]) -> bool,
+    ) -> IndexResult<roaring::RoaringBitmap> {
+        let mut bitmap = roaring::RoaringBitmap::new();
+        for item in self.prefix(prefix)? {
+            let item = item.map_err(|e| IndexStorageSnafu { msg: e.to_string() }.build())?;
+            if filter(item.0.as_ref()) {
+                let rb = self.load_rb(&item.1)?;
+                bitmap = bitmap.bitor(rb);
+            }
+        }
+
+        Ok(bitmap)
+    }
+
     pub fn get_series_id_by_tags(
         &self,
         tab: &str,
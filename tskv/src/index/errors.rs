@@ -85,6 +85,13 @@ pub enum IndexError {
 
     #[snafu(display("file system error: {}", source))]
     FileSystemError { source: FileSystemError },
+
+    #[snafu(display("{}, new series are rejected", reason))]
+    SeriesLimitExceeded {
+        reason: String,
+        location: Location,
+        backtrace: Backtrace,
+    },
 }
 
 pub type IndexResult<T> = Result<T, IndexError>;
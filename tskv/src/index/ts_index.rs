@@ -6,14 +6,16 @@ use std::sync::Arc;
 
 use datafusion::arrow::datatypes::DataType;
 use datafusion::scalar::ScalarValue;
-use models::predicate::domain::{utf8_from, ColumnDomains, Domain, Range};
+use models::predicate::domain::{utf8_from, ColumnDomains, Domain, PatternValueSet, Range};
 use models::schema::tskv_table_schema::TskvTableSchema;
 use models::{tag, SeriesId, SeriesKey, Tag, TagKey, TagValue};
 use snafu::{OptionExt, ResultExt};
 use tokio::sync::RwLock;
+use utils::HyperLogLog;
 
 use super::cache::IndexCache;
-use super::{DecodeSeriesKeySnafu, IndexEngine, IndexResult};
+use super::cardinality::{CardinalityGroup, CardinalityOptions, DistinctSketch, SeriesCardinality};
+use super::{DecodeSeriesKeySnafu, IndexEngine, IndexResult, IndexStorageSnafu};
 use crate::error::{ColumnNotFoundSnafu, IndexErrSnafu};
 use crate::index::SeriesAlreadyExistsSnafu;
 use crate::{byte_utils, TskvError, UpdateSetValue};
@@ -85,6 +87,13 @@ pub struct TSIndex {
 
     storage: IndexEngine,
     cache: IndexCache,
+
+    /// Number of the series of the tables in this index.
+    series_count: HashMap<String, u64>,
+    cardinality: Arc<SeriesCardinality>,
+    /// Sketches of the series of the tables, the deleted series are still counted
+    /// until the index is opened again.
+    sketches: HashMap<String, HyperLogLog>,
 }
 
 impl TSIndex {
@@ -97,11 +106,18 @@ impl TSIndex {
             None => 0,
         };
 
+        let (series_count, sketches) = count_series(&storage)?;
+        let cardinality = Arc::new(SeriesCardinality::default());
+        cardinality.add(&series_count);
+
         let ts_index = Self {
             storage,
             incr_id: AtomicU32::new(incr_id),
             write_count: AtomicU32::new(0),
             cache: IndexCache::new(cap as usize),
+            series_count,
+            cardinality,
+            sketches,
         };
 
         trace::info!(
@@ -113,6 +129,35 @@ impl TSIndex {
         Ok(Arc::new(RwLock::new(ts_index)))
     }
 
+    /// Moves the series of this index to the cardinality shared by the indexes of the database.
+    pub fn set_cardinality(&mut self, cardinality: Arc<SeriesCardinality>) {
+        self.cardinality.sub(&self.series_count);
+        cardinality.add(&self.series_count);
+        self.cardinality = cardinality;
+    }
+
+    fn count_new_series(&mut self, key: &SeriesKey) {
+        let table = key.table();
+        self.cardinality
+            .add(&HashMap::from([(table.to_string(), 1)]));
+        *self.series_count.entry(table.to_string()).or_default() += 1;
+        self.sketches
+            .entry(table.to_string())
+            .or_default()
+            .insert(&encode_series_key(table, key.tags()));
+    }
+
+    fn count_deleted_series(&mut self, table: &str) {
+        if let Some(count) = self.series_count.get_mut(table) {
+            *count -= 1;
+            if *count == 0 {
+                self.series_count.remove(table);
+            }
+            self.cardinality
+                .sub(&HashMap::from([(table.to_string(), 1)]));
+        }
+    }
+
     pub async fn add_series_for_rebuild(
         &mut self,
         id: SeriesId,
@@ -127,6 +172,9 @@ impl TSIndex {
             self.incr_id.store(id + 1, Ordering::Relaxed);
         }
 
+        if self.cache.get_series_id_by_key(key).is_none() {
+            self.count_new_series(key);
+        }
         self.cache.write(id, key.clone());
         Ok(())
     }
@@ -148,6 +196,7 @@ impl TSIndex {
                 self.cache.cache(id, series_key);
                 continue;
             }
+            self.count_new_series(&series_key);
             let id = self.incr_id.fetch_add(1, Ordering::Relaxed) + 1;
             ids.push((id, series_key.clone()));
 
@@ -227,6 +276,7 @@ impl TSIndex {
         let series_key = self.get_series_key(sid).await?;
         let _ = self.storage.delete(&encode_series_id_key(sid));
         if let Some(series_key) = series_key {
+            self.count_deleted_series(series_key.table());
             self.cache.del(sid, &series_key);
             let key_buf = encode_series_key(series_key.table(), series_key.tags());
             let _ = self.storage.delete(&key_buf);
@@ -269,6 +319,7 @@ impl TSIndex {
             self.del_series_info(*sid).await?;
             self.add_tombstone_series(*sid, old_series).await?;
 
+            self.count_new_series(new_series);
             self.cache.write(*sid, new_series.clone());
 
             let _ = self.check_to_flush(false).await;
@@ -479,6 +530,11 @@ impl TSIndex {
                     bitmap = self.get_series_id_bitmap(tab, &[]).await?;
                 }
             }
+            Domain::Pattern(pattern_set) => {
+                bitmap = self
+                    .get_series_ids_by_patterns(tab, tag_key, pattern_set)
+                    .await?;
+            }
             Domain::None => {
                 // Normally, it will not go here unless no judgment is made at the ColumnDomains level
                 // If you go here, you will directly return an empty series, because the tag condition in the map is' and '
@@ -494,11 +550,117 @@ impl TSIndex {
         Ok(bitmap)
     }
 
+    /// Scans the tag values starting with the literal prefix of the patterns,
+    /// and collects the series of the values matching all the patterns.
+    async fn get_series_ids_by_patterns(
+        &self,
+        tab: &str,
+        tag_key: &str,
+        pattern_set: &PatternValueSet,
+    ) -> IndexResult<roaring::RoaringBitmap> {
+        let regexes = match pattern_set
+            .patterns()
+            .iter()
+            .map(|p| regex::bytes::Regex::new(&p.to_regex()))
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(regexes) => regexes,
+            Err(e) => {
+                // The filter will report the invalid pattern, just don't filter here
+                trace::debug!("Index get sids: invalid pattern {pattern_set}: {e}");
+                return self.get_series_id_bitmap(tab, &[]).await;
+            }
+        };
+        let is_match = |value: &[u8]| regexes.iter().all(|r| r.is_match(value));
+
+        let prefix = pattern_set.literal_prefix();
+        let mut bitmap = self.cache.write_cache.get_inverted_by_prefix(
+            tab,
+            tag_key,
+            prefix.as_bytes(),
+            is_match,
+        );
+
+        let key_prefix = encode_inverted_index_key(tab, tag_key.as_bytes(), prefix.as_bytes());
+        let value_offset = key_prefix.len() - prefix.len();
+        let engine_rb = self
+            .storage
+            .get_series_id_by_prefix(&key_prefix, |key| is_match(&key[value_offset..]))?;
+        bitmap = bitmap.bitor(engine_rb);
+
+        Ok(bitmap)
+    }
+
     pub async fn flush(&mut self) -> IndexResult<()> {
         self.check_to_flush(true).await?;
 
         Ok(())
     }
+
+    /// Returns the distinct series, or the tag keys of the series, of the tables by the groups.
+    /// The series are read from the index unless an estimation of the series of the
+    /// tables is asked, which is returned from the sketches.
+    pub async fn cardinality(
+        &self,
+        options: &CardinalityOptions,
+    ) -> IndexResult<Vec<CardinalityGroup>> {
+        let tables = match &options.table {
+            Some(table) => vec![table.clone()],
+            None => self.series_count.keys().cloned().collect(),
+        };
+
+        let mut groups = Vec::new();
+        for table in tables {
+            if !options.exact && !options.tag_keys && options.group_by.is_none() {
+                if let Some(sketch) = self.sketches.get(&table) {
+                    groups.push(CardinalityGroup {
+                        table,
+                        tag_value: None,
+                        sketch: DistinctSketch::Estimated(sketch.clone()),
+                    });
+                }
+                continue;
+            }
+
+            let mut table_groups: HashMap<Option<String>, DistinctSketch> = HashMap::new();
+            if options.tag_keys {
+                // The tag keys are few, they are always counted exactly.
+                table_groups.insert(None, DistinctSketch::new(true));
+            }
+            for sid in self.get_series_id_list(&table, &[]).await? {
+                let Some(key) = self.get_series_key(sid).await? else {
+                    continue;
+                };
+                if options.tag_keys {
+                    if let Some(sketch) = table_groups.get_mut(&None) {
+                        key.tags().iter().for_each(|tag| sketch.insert(&tag.key));
+                    }
+                    continue;
+                }
+
+                let tag_value = options
+                    .group_by
+                    .as_ref()
+                    .and_then(|tag_key| key.tag_val(tag_key))
+                    .map(|value| String::from_utf8_lossy(&value).to_string());
+                table_groups
+                    .entry(tag_value)
+                    .or_insert_with(|| DistinctSketch::new(options.exact))
+                    .insert(&encode_series_key(key.table(), key.tags()));
+            }
+            groups.extend(
+                table_groups
+                    .into_iter()
+                    .map(|(tag_value, sketch)| CardinalityGroup {
+                        table: table.clone(),
+                        tag_value,
+                        sketch,
+                    }),
+            );
+        }
+
+        Ok(groups)
+    }
 }
 
 impl std::fmt::Debug for TSIndex {
@@ -507,6 +669,12 @@ impl std::fmt::Debug for TSIndex {
     }
 }
 
+impl Drop for TSIndex {
+    fn drop(&mut self) {
+        self.cardinality.sub(&self.series_count);
+    }
+}
+
 pub fn filter_range_to_index_key_range(
     tab: &str,
     tag_key: &str,
@@ -636,15 +804,54 @@ fn encode_series_key_with_prefix(prefix: &str, tab: &str, tags: &[Tag]) -> Vec<u
     buf
 }
 
+/// Counts the series of the tables in the storage by the keys `_key_<table len><table>...`,
+/// and builds the sketches of them.
+fn count_series(
+    storage: &IndexEngine,
+) -> IndexResult<(HashMap<String, u64>, HashMap<String, HyperLogLog>)> {
+    let mut series_count = HashMap::new();
+    let mut sketches: HashMap<String, HyperLogLog> = HashMap::new();
+    for item in storage.prefix(SERIES_KEY_PREFIX.as_bytes())? {
+        let item = item.map_err(|e| IndexStorageSnafu { msg: e.to_string() }.build())?;
+        let key_buf = item.0.as_ref();
+        let key = &key_buf[SERIES_KEY_PREFIX.len()..];
+        let table_len = byte_utils::decode_be_u16(key) as usize;
+        let table = String::from_utf8_lossy(&key[2..2 + table_len]);
+        match series_count.get_mut(table.as_ref()) {
+            Some(count) => *count += 1,
+            None => {
+                series_count.insert(table.to_string(), 1);
+            }
+        }
+        match sketches.get_mut(table.as_ref()) {
+            Some(sketch) => sketch.insert(key_buf),
+            None => {
+                let mut sketch = HyperLogLog::new();
+                sketch.insert(key_buf);
+                sketches.insert(table.into_owned(), sketch);
+            }
+        }
+    }
+
+    Ok((series_count, sketches))
+}
+
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
+    use std::sync::Arc;
     use std::time::Duration;
 
     use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use models::predicate::domain::{Domain, ValuePattern};
     use models::schema::external_table_schema::ExternalTableSchema;
     use models::{SeriesId, SeriesKey, Tag};
 
     use super::TSIndex;
+    use crate::index::cardinality::{
+        merge_cardinality_groups, CardinalityOptions, SeriesCardinality, SeriesLimits,
+    };
+    use crate::index::IndexError;
     use crate::UpdateSetValue;
 
     /// ( sid, database, table, [(tag_key, tag_value)] )
@@ -943,4 +1150,191 @@ mod test {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_pattern_domain() {
+        let table_name = "table";
+        let dir = "/tmp/test/cnosdb/ts_index/pattern_domain";
+        let _ = std::fs::remove_dir_all(dir);
+
+        let ts_index = TSIndex::new(dir, 10000).await.unwrap();
+        let mut ts_index = ts_index.write().await;
+
+        let series_keys = ["web-1", "web-2", "db1", "db22", "dbx"]
+            .into_iter()
+            .map(|host| SeriesKey {
+                tags: vec![Tag::new(b"host".to_vec(), host.as_bytes().to_vec())],
+                table: table_name.to_string(),
+            })
+            .collect::<Vec<_>>();
+        let sids = ts_index
+            .add_series_if_not_exists(series_keys)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(sid, _)| sid)
+            .collect::<Vec<_>>();
+
+        let like = Domain::of_pattern(ValuePattern::Like {
+            pattern: "web-%".to_string(),
+            escape_char: None,
+            case_insensitive: false,
+        });
+        let regex = Domain::of_pattern(ValuePattern::Regex {
+            pattern: "^db[0-9]+".to_string(),
+            case_insensitive: false,
+        });
+
+        for flush in [false, true] {
+            if flush {
+                ts_index.flush().await.unwrap();
+            }
+
+            let rb = ts_index
+                .get_series_ids_by_domain(table_name, "host", &like)
+                .await
+                .unwrap();
+            assert_eq!(rb.into_iter().collect::<Vec<_>>(), sids[0..2].to_vec());
+
+            let rb = ts_index
+                .get_series_ids_by_domain(table_name, "host", &regex)
+                .await
+                .unwrap();
+            assert_eq!(rb.into_iter().collect::<Vec<_>>(), sids[2..4].to_vec());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_series_limits() {
+        let dir = "/tmp/test/cnosdb/ts_index/series_limits";
+        let _ = std::fs::remove_dir_all(dir);
+
+        let series_key = |table: &str, host: &str| SeriesKey {
+            tags: vec![Tag::new(b"host".to_vec(), host.as_bytes().to_vec())],
+            table: table.to_string(),
+        };
+        let new_series = |series: &[(&str, u64)]| {
+            series
+                .iter()
+                .map(|(table, count)| (table.to_string(), *count))
+                .collect::<HashMap<_, _>>()
+        };
+        let limits = SeriesLimits {
+            max_series_per_database: Some(3),
+            max_series_per_table: Some(2),
+        };
+        let cardinality = Arc::new(SeriesCardinality::default());
+        {
+            let ts_index = TSIndex::new(dir, 10000).await.unwrap();
+            let mut ts_index = ts_index.write().await;
+            ts_index.set_cardinality(cardinality.clone());
+
+            let keys = vec![
+                series_key("cpu", "h1"),
+                series_key("cpu", "h2"),
+                series_key("cpu", "h1"),
+                series_key("mem", "h1"),
+            ];
+            ts_index.add_series_if_not_exists(keys).await.unwrap();
+            assert_eq!(cardinality.database_series(), 3);
+            assert_eq!(cardinality.table_series("cpu"), 2);
+
+            // The limits are only checked for the new series.
+            cardinality.check(&new_series(&[]), &limits).unwrap();
+            assert!(matches!(
+                cardinality.check(&new_series(&[("cpu", 1)]), &limits),
+                Err(IndexError::SeriesLimitExceeded { .. })
+            ));
+            assert!(matches!(
+                cardinality.check(&new_series(&[("disk", 1)]), &limits),
+                Err(IndexError::SeriesLimitExceeded { .. })
+            ));
+
+            let sid = ts_index
+                .get_series_id(&series_key("mem", "h1"))
+                .await
+                .unwrap()
+                .unwrap();
+            ts_index.del_series_info(sid).await.unwrap();
+            assert_eq!(cardinality.database_series(), 2);
+            cardinality
+                .check(&new_series(&[("mem", 1)]), &limits)
+                .unwrap();
+            ts_index.flush().await.unwrap();
+        }
+        assert_eq!(cardinality.database_series(), 0);
+
+        // The series are counted when the index is opened again.
+        let ts_index = TSIndex::new(dir, 10000).await.unwrap();
+        ts_index.write().await.set_cardinality(cardinality.clone());
+        assert_eq!(cardinality.database_series(), 2);
+        assert_eq!(cardinality.table_series("cpu"), 2);
+        assert_eq!(cardinality.table_series("mem"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_series_cardinality() {
+        let dir = "/tmp/test/cnosdb/ts_index/series_cardinality";
+        let _ = std::fs::remove_dir_all(dir);
+
+        let series_key = |region: &str, host: &str| SeriesKey {
+            tags: vec![
+                Tag::new(b"host".to_vec(), host.as_bytes().to_vec()),
+                Tag::new(b"region".to_vec(), region.as_bytes().to_vec()),
+            ],
+            table: "cpu".to_string(),
+        };
+        let ts_index = TSIndex::new(dir, 10000).await.unwrap();
+        let keys = vec![
+            series_key("r1", "h1"),
+            series_key("r1", "h2"),
+            series_key("r2", "h3"),
+        ];
+        ts_index
+            .write()
+            .await
+            .add_series_if_not_exists(keys)
+            .await
+            .unwrap();
+        let ts_index = ts_index.read().await;
+
+        for exact in [true, false] {
+            let options = CardinalityOptions {
+                exact,
+                ..Default::default()
+            };
+            let groups = ts_index.cardinality(&options).await.unwrap();
+            // The same series in another vnode is counted once.
+            let groups = groups.iter().chain(groups.iter()).cloned();
+            assert_eq!(
+                merge_cardinality_groups(groups),
+                vec![("cpu".to_string(), None, 3)]
+            );
+        }
+
+        let options = CardinalityOptions {
+            table: Some("cpu".to_string()),
+            group_by: Some("region".to_string()),
+            exact: true,
+            ..Default::default()
+        };
+        let groups = ts_index.cardinality(&options).await.unwrap();
+        assert_eq!(
+            merge_cardinality_groups(groups),
+            vec![
+                ("cpu".to_string(), Some("r1".to_string()), 2),
+                ("cpu".to_string(), Some("r2".to_string()), 1),
+            ]
+        );
+
+        let options = CardinalityOptions {
+            tag_keys: true,
+            ..Default::default()
+        };
+        let groups = ts_index.cardinality(&options).await.unwrap();
+        assert_eq!(
+            merge_cardinality_groups(groups),
+            vec![("cpu".to_string(), None, 2)]
+        );
+    }
 }
@@ -4,9 +4,13 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
-use config::tskv::Config;
+use config::tskv::{Config, TieredStorageConfig};
 use models::meta_data::{NodeId, VnodeId};
 
+use crate::error::{InvalidParamSnafu, TskvResult};
+use crate::file_system::error::FileSystemResult;
+use crate::file_system::remote_filesystem::RemoteFileSystem;
+
 const SUMMARY_PATH: &str = "summary";
 pub const INDEX_PATH: &str = "index";
 pub const DATA_PATH: &str = "data";
@@ -30,6 +34,25 @@ impl From<&Config> for Options {
     }
 }
 
+impl Options {
+    /// Opens the object store of tiered storage if it's enabled, returns an error if the
+    /// config of tiered storage is invalid.
+    pub fn open_tiered_storage(&mut self) -> TskvResult<()> {
+        let remote = match &self.storage.tiered_config {
+            Some(config) => RemoteFileSystem::new(&self.storage.path, config).map_err(|e| {
+                InvalidParamSnafu {
+                    reason: format!("invalid config 'storage.tiered': {e}"),
+                }
+                .build()
+            })?,
+            None => return Ok(()),
+        };
+        Arc::make_mut(&mut self.storage).tiered = Some(Arc::new(remote));
+
+        Ok(())
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct StorageOptions {
     pub path: PathBuf,
@@ -46,6 +69,11 @@ pub struct StorageOptions {
     pub snapshot_holding_time: i64,
     pub max_datablock_size: u64,
     pub index_cache_capacity: u64,
+    /// The config of tiered storage, `None` if tiered storage is disabled.
+    pub tiered_config: Option<TieredStorageConfig>,
+    /// The cold tier that column files are offloaded to, opened by
+    /// [`Options::open_tiered_storage`].
+    pub tiered: Option<Arc<RemoteFileSystem>>,
 }
 
 // database/data/ts_family_id/tsm
@@ -103,6 +131,12 @@ impl From<&Config> for StorageOptions {
             snapshot_holding_time: config.cluster.snapshot_holding_time.as_secs() as i64,
             max_datablock_size: config.storage.max_datablock_size,
             index_cache_capacity: config.storage.index_cache_capacity,
+            tiered_config: config
+                .storage
+                .tiered
+                .enable
+                .then(|| config.storage.tiered.clone()),
+            tiered: None,
         }
     }
 }
@@ -117,6 +151,8 @@ pub struct QueryOptions {
     pub write_timeout: Duration,
     pub stream_trigger_cpu: usize,
     pub stream_executor_cpu: usize,
+    pub result_cache_enabled: bool,
+    pub result_cache_max_size: u64,
 }
 
 impl From<&Config> for QueryOptions {
@@ -130,6 +166,8 @@ impl From<&Config> for QueryOptions {
             write_timeout: config.query.write_timeout,
             stream_trigger_cpu: config.query.stream_trigger_cpu,
             stream_executor_cpu: config.query.stream_executor_cpu,
+            result_cache_enabled: config.query.result_cache_enabled,
+            result_cache_max_size: config.query.result_cache_max_size,
         }
     }
 }
@@ -140,6 +178,10 @@ pub struct WalOptions {
     pub wal_max_file_size: u64,
     pub compress: String,
     pub wal_sync: bool,
+    pub sync_interval: Duration,
+    pub sync_max_size: u64,
+    /// The object store that the WAL files are archived to, `None` if disabled.
+    pub archive: Option<TieredStorageConfig>,
 }
 
 impl From<&Config> for WalOptions {
@@ -149,13 +191,36 @@ impl From<&Config> for WalOptions {
             wal_max_file_size: config.wal.max_file_size,
             compress: config.wal.compress.clone(),
             wal_sync: config.wal.sync,
+            sync_interval: config.wal.sync_interval,
+            sync_max_size: config.wal.sync_max_size,
+            archive: (!config.wal.archive_url.is_empty()).then(|| TieredStorageConfig {
+                enable: true,
+                url: config.wal.archive_url.clone(),
+                // The archived files are only read sequentially, without the block cache.
+                cache_size: config.storage.tiered.block_size,
+                ..config.storage.tiered.clone()
+            }),
         }
     }
 }
 
 /// database/data/ts_family_id/
 impl WalOptions {
+    /// Whether the fsync of the WAL writes are batched by a [`crate::wal::syncer::WalSyncer`].
+    pub fn group_commit(&self) -> bool {
+        self.wal_sync && !self.sync_interval.is_zero()
+    }
+
     pub fn wal_dir(&self, owner: &str, vnode_id: VnodeId) -> PathBuf {
         self.path.join(owner).join(vnode_id.to_string())
     }
+
+    /// Opens the object store of the archived WAL files, the archived files are
+    /// addressed by their paths in `wal_dir`. Returns `None` if the archive is disabled.
+    pub fn archive_store(&self) -> FileSystemResult<Option<Arc<RemoteFileSystem>>> {
+        match &self.archive {
+            Some(config) => Ok(Some(Arc::new(RemoteFileSystem::new(&self.path, config)?))),
+            None => Ok(None),
+        }
+    }
 }
@@ -1,4 +1,5 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -11,7 +12,7 @@ use models::meta_data::VnodeId;
 use models::predicate::domain::ColumnDomains;
 use models::schema::database_schema::{make_owner, split_owner};
 use models::{SeriesId, SeriesKey};
-use snafu::ResultExt;
+use snafu::{OptionExt, ResultExt};
 use tokio::runtime::Runtime;
 use tokio::sync::broadcast::{self, Sender as BroadcastSender};
 use tokio::sync::mpsc::{self, Receiver, Sender};
@@ -20,11 +21,15 @@ use trace::{debug, error, info, warn};
 
 use crate::compaction::job::CompactJob;
 use crate::compaction::metrics::{CompactionType, VnodeCompactionMetrics};
-use crate::compaction::{self, check, pick_compaction, CompactTask};
+use crate::compaction::{self, check, offload, pick_compaction, CompactTask};
 use crate::database::Database;
-use crate::error::{IndexErrSnafu, MetaSnafu, TskvResult};
+use crate::error::{
+    CommonSnafu, IndexErrSnafu, InvalidFlatbufferSnafu, InvalidPointTableSnafu, MetaSnafu,
+    TskvResult,
+};
 use crate::file_system::async_filesystem::LocalFileSystem;
 use crate::file_system::FileSystem;
+use crate::index::cardinality::{CardinalityGroup, CardinalityOptions};
 use crate::index::IndexResult;
 use crate::kv_option::{Options, StorageOptions};
 use crate::summary::{Summary, SummaryTask};
@@ -32,11 +37,14 @@ use crate::tsfamily::super_version::SuperVersion;
 use crate::tsfamily::tseries_family::TseriesFamily;
 use crate::version_set::VersionSet;
 use crate::vnode_store::VnodeStorage;
-use crate::{file_utils, Engine, TsKvContext};
+use crate::wal::archiver::ArchivedWal;
+use crate::{file_utils, ColumnFileId, Engine, TsKvContext, TskvError, VnodeSnapshot};
 
 // TODO: A small summay channel capacity can cause a block
 pub const COMPACT_REQ_CHANNEL_CAP: usize = 1024;
 pub const SUMMARY_REQ_CHANNEL_CAP: usize = 1024;
+/// The archived WAL files are downloaded to this directory in the snapshot directory.
+const ARCHIVED_WAL_DIR: &str = "archived_wal";
 
 pub struct TsKv {
     ctx: Arc<TsKvContext>,
@@ -52,11 +60,12 @@ pub struct TsKv {
 impl TsKv {
     pub async fn open(
         meta_manager: MetaRef,
-        options: Options,
+        mut options: Options,
         runtime: Arc<Runtime>,
         memory_pool: MemoryPoolRef,
         metrics: Arc<MetricsRegister>,
     ) -> TskvResult<TsKv> {
+        options.open_tiered_storage()?;
         let (compact_task_sender, compact_task_receiver) = mpsc::channel(COMPACT_REQ_CHANNEL_CAP);
         let (summary_task_sender, summary_task_receiver) = mpsc::channel(SUMMARY_REQ_CHANNEL_CAP);
 
@@ -94,6 +103,7 @@ impl TsKv {
 
         core.run_summary_job(summary, summary_task_receiver);
         core.run_flush_cold_vnode_job();
+        core.run_offload_cold_files_job();
         core.compact_job
             .start_merge_compact_task_job(compact_task_receiver)
             .await;
@@ -208,6 +218,43 @@ impl TsKv {
         });
     }
 
+    fn run_offload_cold_files_job(&self) {
+        let tskv_ctx = self.ctx.clone();
+        let remote = match tskv_ctx.options.storage.tiered.clone() {
+            Some(remote) => remote,
+            None => return,
+        };
+        let check_interval = remote.config().check_interval;
+        if check_interval == Duration::ZERO {
+            return;
+        }
+
+        self.runtime.spawn(async move {
+            let mut offload_check_interval = tokio::time::interval(check_interval);
+            loop {
+                offload_check_interval.tick().await;
+
+                let dbs = tskv_ctx.version_set.read().await.get_all_db().clone();
+                for (_, db) in dbs {
+                    let ts_families = db.read().await.ts_families().clone();
+                    for (tf_id, ts_family) in ts_families {
+                        match offload::offload_cold_files(
+                            ts_family,
+                            remote.clone(),
+                            tskv_ctx.summary_task_sender.clone(),
+                        )
+                        .await
+                        {
+                            Ok(0) => {}
+                            Ok(n) => info!("Offloaded {n} cold files of vnode {tf_id}"),
+                            Err(e) => error!("Failed to offload cold files of vnode {tf_id}: {e}"),
+                        }
+                    }
+                }
+            }
+        });
+    }
+
     async fn sync_indexs(&self) -> IndexResult<()> {
         let vnodes_guard = self.vnodes.read().await;
         for (_, vnode_storage) in vnodes_guard.iter() {
@@ -394,6 +441,51 @@ impl Engine for TsKv {
         }
     }
 
+    async fn series_cardinality(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        options: &CardinalityOptions,
+    ) -> TskvResult<Vec<CardinalityGroup>> {
+        let db = match self.ctx.version_set.read().await.get_db(tenant, database) {
+            Some(db) => db,
+            None => return Ok(vec![]),
+        };
+        let ts_index = db.read().await.get_ts_index(vnode_id);
+        match ts_index {
+            Some(ts_index) => Ok(ts_index
+                .read()
+                .await
+                .cardinality(options)
+                .await
+                .context(IndexErrSnafu)?),
+            None => Ok(vec![]),
+        }
+    }
+
+    async fn check_series_limits(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        points: &[u8],
+    ) -> TskvResult<()> {
+        let db = match self.ctx.version_set.read().await.get_db(tenant, database) {
+            Some(db) => db,
+            None => return Ok(()),
+        };
+        let ts_index = match db.read().await.get_ts_index(vnode_id) {
+            Some(ts_index) => ts_index,
+            None => return Ok(()),
+        };
+        let fb_points =
+            flatbuffers::root::<protos::models::Points>(points).context(InvalidFlatbufferSnafu)?;
+        let tables = fb_points.tables().context(InvalidPointTableSnafu)?;
+
+        db.read().await.check_series_limits(tables, ts_index).await
+    }
+
     async fn get_db_version(
         &self,
         tenant: &str,
@@ -495,6 +587,176 @@ impl Engine for TsKv {
         Ok(())
     }
 
+    async fn reencode(
+        &self,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        vnode_ids: Vec<VnodeId>,
+    ) -> TskvResult<()> {
+        let db = match self.ctx.version_set.read().await.get_db(tenant, database) {
+            Some(db) => db,
+            None => return Ok(()),
+        };
+        let table_schema = match db.read().await.get_table_schema(table).await? {
+            Some(table_schema) => table_schema,
+            None => {
+                info!("Table {tenant}.{database}.{table} not found, nothing to reencode.");
+                return Ok(());
+            }
+        };
+
+        let mut result = Ok(());
+        for vnode_id in vnode_ids {
+            let ts_family = match db.read().await.get_tsfamily(vnode_id) {
+                Some(ts_family) => ts_family,
+                None => continue,
+            };
+            if !ts_family.read().await.can_compaction() {
+                warn!("forbidden reencode on moving vnode {}", vnode_id);
+                continue;
+            }
+
+            // Flush the cache first, so the data in memory is written with the current encodings.
+            if let Err(e) = self.flush_tsfamily(tenant, database, vnode_id, true).await {
+                error!("Failed to flush vnode {}: {:?}", vnode_id, e);
+            }
+
+            let version = ts_family.read().await.version();
+            for req in compaction::pick_reencode(vnode_id, version.clone()).await {
+                // The picked files are marked as compacting, they must be unmarked
+                // if they are not replaced by a new version.
+                let file_ids: HashSet<ColumnFileId> =
+                    req.files().iter().map(|f| f.file_id()).collect();
+                let vnode_compaction_metrics = VnodeCompactionMetrics::new(
+                    &self.metrics,
+                    self.ctx.options.storage.node_id,
+                    vnode_id,
+                    CompactionType::Manual,
+                    self.ctx.options.storage.collect_compaction_metrics,
+                );
+                let job_result = match compaction::run_reencode_job(
+                    req,
+                    table_schema.clone(),
+                    self.ctx.global_ctx.clone(),
+                    vnode_compaction_metrics,
+                )
+                .await
+                {
+                    Ok(Some((version_edit, file_metas))) => {
+                        let (summary_tx, summary_rx) = oneshot::channel();
+                        let task = SummaryTask::new(
+                            ts_family.clone(),
+                            version_edit,
+                            Some(file_metas),
+                            None,
+                            summary_tx,
+                        );
+                        match self.ctx.summary_task_sender.send(task).await {
+                            Ok(_) => summary_rx.await.unwrap_or_else(|e| {
+                                Err(CommonSnafu {
+                                    reason: format!("failed to receive summary task result: {e}"),
+                                }
+                                .build())
+                            }),
+                            Err(e) => Err(CommonSnafu {
+                                reason: format!("failed to send summary task: {e}"),
+                            }
+                            .build()),
+                        }
+                        .map(|_| true)
+                    }
+                    Ok(None) => Ok(false),
+                    Err(e) => Err(e),
+                };
+
+                match job_result {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        info!("There is nothing to reencode.");
+                        version.unmark_compacting_files(&file_ids).await;
+                    }
+                    Err(e) => {
+                        error!("Reencode job of vnode {} failed: {:?}", vnode_id, e);
+                        version.unmark_compacting_files(&file_ids).await;
+                        // Go on with the other jobs, whose files are marked as compacting too.
+                        result = result.and(Err(e));
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    async fn backup_vnode(&self, vnode_id: VnodeId) -> TskvResult<Option<VnodeSnapshot>> {
+        let vnode_opt = self.vnodes.read().await.get(&vnode_id).cloned();
+        match vnode_opt {
+            Some(vnode) => vnode.flush(true, true, false).await?,
+            None => return Ok(None),
+        }
+
+        // The snapshot is kept by the vnode, so the files are not deleted by compactions
+        // before they are downloaded.
+        match self.vnodes.write().await.get_mut(&vnode_id) {
+            Some(vnode) => Ok(Some(vnode.create_snapshot().await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn restore_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        mut snapshot: VnodeSnapshot,
+        snapshot_dir: &Path,
+        until: Option<i64>,
+    ) -> TskvResult<()> {
+        // The raft logs after the snapshot are archived by the vnode that took it,
+        // they are checked before the vnode is replaced.
+        let archived_wal = match until {
+            Some(until) => {
+                let wal_options = &self.ctx.options.wal;
+                let store = wal_options
+                    .archive_store()
+                    .map_err(|e| TskvError::FileSystemError { source: e })?
+                    .ok_or_else(|| {
+                        CommonSnafu {
+                            reason: "wal.archive_url is not set".to_string(),
+                        }
+                        .build()
+                    })?;
+                let wal_dir =
+                    wal_options.wal_dir(&snapshot.version_edit.tsf_name, snapshot.vnode_id);
+                let archived_wal = ArchivedWal::load(
+                    &store,
+                    &wal_dir,
+                    &snapshot_dir.join(ARCHIVED_WAL_DIR),
+                    &wal_options.compress,
+                    snapshot.last_seq_no,
+                    until,
+                )
+                .await?;
+                Some(archived_wal)
+            }
+            None => None,
+        };
+        let mut vnode = self.open_tsfamily(tenant, database, vnode_id).await?;
+
+        // The snapshot may come from another database, and the raft logs of this vnode
+        // are replayed after the restored files, the files must not hide any of them.
+        snapshot.version_edit.tsf_name = make_owner(tenant, database);
+        snapshot.version_edit.seq_no = vnode.ts_family().read().await.last_seq();
+        vnode.apply_snapshot(snapshot, snapshot_dir).await?;
+        if let Some(archived_wal) = archived_wal {
+            archived_wal.replay(&vnode).await?;
+        }
+        self.vnodes.write().await.insert(vnode_id, vnode);
+
+        Ok(())
+    }
+
     async fn get_vnode_hash_tree(&self, vnode_id: VnodeId) -> TskvResult<RecordBatch> {
         for database in self.ctx.version_set.read().await.get_all_db().values() {
             let db = database.read().await;
@@ -1,6 +1,7 @@
 #![recursion_limit = "256"]
 
 use std::fmt::{Debug, Display, Formatter};
+use std::path::Path;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -21,6 +22,7 @@ use version_set::VersionSet;
 use vnode_store::VnodeStorage;
 
 pub use crate::error::{TskvError, TskvResult};
+use crate::index::cardinality::{CardinalityGroup, CardinalityOptions};
 pub use crate::kv_option::Options;
 use crate::kv_option::StorageOptions;
 pub use crate::kvcore::TsKv;
@@ -115,6 +117,25 @@ pub trait Engine: Send + Sync + Debug {
         series_id: &[SeriesId],
     ) -> TskvResult<Vec<SeriesKey>>;
 
+    /// Read index of a storage unit, count the distinct series or tag keys of the tables.
+    async fn series_cardinality(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        options: &CardinalityOptions,
+    ) -> TskvResult<Vec<CardinalityGroup>>;
+
+    /// Check that the new series of the points don't exceed the series limits of the tenant,
+    /// it's called by the leader before the points are proposed to the raft group.
+    async fn check_series_limits(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        points: &[u8],
+    ) -> TskvResult<()>;
+
     /// Get a `SuperVersion` that contains the latest version of caches and files
     /// of the storage unit.
     async fn get_db_version(
@@ -131,6 +152,35 @@ pub trait Engine: Send + Sync + Debug {
     /// files into larger files.
     async fn compact(&self, vnode_ids: Vec<VnodeId>) -> TskvResult<()>;
 
+    /// For the specified storage units, rewrite the files that contain the table, the data of
+    /// the table is encoded again with the current encodings of its columns.
+    async fn reencode(
+        &self,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        vnode_ids: Vec<VnodeId>,
+    ) -> TskvResult<()>;
+
+    /// Flush all caches of the storage unit, then take a snapshot of its files, the files
+    /// of the snapshot are kept for a while so that they can be downloaded.
+    /// Return `None` if the storage unit is not opened.
+    async fn backup_vnode(&self, vnode_id: VnodeId) -> TskvResult<Option<VnodeSnapshot>>;
+
+    /// Replace the data of the storage unit with the snapshot, the files of the
+    /// snapshot are moved from `snapshot_dir`. If `until` is set, the archived raft
+    /// logs after the snapshot are replayed until the logs proposed after it, returns
+    /// error if the archive doesn't reach `until`.
+    async fn restore_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        snapshot: VnodeSnapshot,
+        snapshot_dir: &Path,
+        until: Option<i64>,
+    ) -> TskvResult<()>;
+
     /// Get a compressed hash_tree(ID and checksum of each vnode) of engine.
     async fn get_vnode_hash_tree(&self, vnode_id: VnodeId) -> TskvResult<RecordBatch>;
 
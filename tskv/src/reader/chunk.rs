@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use arrow::datatypes::SchemaRef;
 use datafusion::physical_optimizer::pruning::PruningPredicate;
+use models::gis::bounding_box::SpatialFilter;
 
 use super::column_group::statistics::ColumnGroupsStatisticsWrapper;
 use super::Predicate;
@@ -32,20 +33,49 @@ fn filter_column_groups_indices(
     predicate: &Option<Arc<Predicate>>,
     chunk_schema: SchemaRef,
 ) -> TskvResult<Option<Vec<bool>>> {
-    if let Some(predicate) = predicate {
-        let new_predicate = reassign_predicate_columns(predicate.clone(), chunk_schema.clone())?;
-        let statistics = ColumnGroupsStatisticsWrapper(cgs);
-
-        if let Some(expr) = new_predicate {
+    let predicate = match predicate {
+        Some(predicate) => predicate,
+        None => return Ok(None),
+    };
+
+    let new_predicate = reassign_predicate_columns(predicate.clone(), chunk_schema.clone())?;
+    let mut indices = match new_predicate {
+        Some(expr) => {
+            let statistics = ColumnGroupsStatisticsWrapper(cgs);
             let pruning_predicate = PruningPredicate::try_new(expr, chunk_schema)?;
-            let indices = pruning_predicate.prune(&statistics)?;
-            Ok(Some(indices))
-        } else {
-            Ok(None)
+            Some(pruning_predicate.prune(&statistics)?)
         }
-    } else {
-        Ok(None)
+        None => None,
+    };
+
+    let spatial_filters = predicate.spatial_filters();
+    if !spatial_filters.is_empty() {
+        let spatial_indices = cgs
+            .iter()
+            .map(|cg| may_match_spatial_filters(cg, spatial_filters));
+        indices = Some(match indices {
+            Some(indices) => indices
+                .into_iter()
+                .zip(spatial_indices)
+                .map(|(a, b)| a && b)
+                .collect(),
+            None => spatial_indices.collect(),
+        });
     }
+
+    Ok(indices)
+}
+
+/// Whether the geometries in the column group may satisfy all the spatial filters,
+/// the column group is kept if the bounding box of a page is unknown.
+fn may_match_spatial_filters(cg: &ColumnGroup, spatial_filters: &[SpatialFilter]) -> bool {
+    spatial_filters.iter().all(|filter| {
+        cg.pages()
+            .iter()
+            .find(|e| e.meta.column.name == filter.column)
+            .and_then(|e| e.meta().statistics.bounding_box())
+            .map_or(true, |bbox| bbox.intersects(&filter.bbox))
+    })
 }
 
 #[cfg(test)]
@@ -58,6 +88,8 @@ mod tests {
     use datafusion::physical_plan::expressions::{lit, BinaryExpr, Column};
     use datafusion::physical_plan::functions::create_physical_expr;
     use datafusion::scalar::ScalarValue;
+    use models::gis::bounding_box::{BoundingBox, SpatialFilter};
+    use models::gis::data_type::{Geometry, GeometryType};
     use models::schema::tskv_table_schema::{ColumnType, TableColumn};
     use models::ValueType;
 
@@ -65,7 +97,7 @@ mod tests {
     use crate::reader::Predicate;
     use crate::tsm::column_group::ColumnGroup;
     use crate::tsm::page::{PageMeta, PageStatistics, PageWriteSpec};
-    use crate::tsm::statistics::ValueStatistics;
+    use crate::tsm::statistics::{GeometryStatistics, ValueStatistics};
 
     /// ```text
     ///                     time            tag1            field1
@@ -297,4 +329,54 @@ mod tests {
 
         assert!(cgs.is_err());
     }
+
+    #[test]
+    fn test_filter_spatial_column_groups_indices() {
+        let geo_column = TableColumn::new(
+            1,
+            "loc".to_string(),
+            ColumnType::Field(ValueType::Geometry(Geometry::new_with_srid(
+                GeometryType::Point,
+                0,
+            ))),
+            Default::default(),
+        );
+        let statistics = |bbox| {
+            PageStatistics::Geometry(GeometryStatistics::new(
+                ValueStatistics::new(None, None, None, 0),
+                bbox,
+            ))
+        };
+        let cgs = [
+            statistics(Some(BoundingBox::new(0.0, 0.0, 1.0, 1.0))),
+            statistics(Some(BoundingBox::new(10.0, 10.0, 11.0, 11.0))),
+            statistics(None),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(idx, statistics)| {
+            let mut cg = ColumnGroup::new(idx as u64);
+            cg.push(PageWriteSpec::new(
+                0,
+                0,
+                PageMeta {
+                    num_values: 1,
+                    column: geo_column.clone(),
+                    statistics,
+                },
+            ));
+            Arc::new(cg)
+        })
+        .collect::<Vec<_>>();
+        let schema = Arc::new(Schema::new(vec![Field::new("loc", DataType::Utf8, true)]));
+
+        let predicate = Predicate::new(None, schema.clone(), None).with_spatial_filters(vec![
+            SpatialFilter::new("loc", BoundingBox::new(0.5, 0.5, 2.0, 2.0)),
+        ]);
+        let indices = filter_column_groups_indices(&cgs, &Some(Arc::new(predicate)), schema)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(indices, vec![true, false, true]);
+    }
 }
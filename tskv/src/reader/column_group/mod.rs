@@ -11,8 +11,10 @@ use arrow_array::{ArrayRef, RecordBatch};
 use datafusion::physical_plan::metrics::{Count, ExecutionPlanMetricsSet, MetricBuilder, Time};
 use futures::{ready, Stream, StreamExt};
 use models::arrow::stream::BoxStream;
+use models::codec::Encoding;
 use models::predicate::domain::TimeRange;
-use models::{ColumnId, SeriesId};
+use models::schema::tskv_table_schema::PhysicalCType;
+use models::{ColumnId, PhysicalDType, SeriesId};
 use snafu::ResultExt;
 
 use super::metrics::BaselineMetrics;
@@ -41,6 +43,7 @@ impl ColumnGroupReader {
         projection: &[ColumnId],
         schema_meta: HashMap<String, String>,
         _batch_size: usize,
+        keep_dictionary: bool,
         metrics: Arc<ExecutionPlanMetricsSet>,
     ) -> TskvResult<Self> {
         let columns = projection.iter().filter_map(|e| {
@@ -61,12 +64,22 @@ impl ColumnGroupReader {
 
         let page_readers = columns
             .clone()
-            .map(|e| reader_builder.build(e))
+            .map(|e| reader_builder.build(e, keep_dictionary && is_dictionary_page(e)))
             .collect::<TskvResult<Vec<_>>>()?;
 
         let fields = columns
-            .map(|e| &e.meta().column)
-            .map(Field::from)
+            .map(|e| {
+                let field = Field::from(&e.meta().column);
+                if keep_dictionary && is_dictionary_page(e) {
+                    let data_type = DataType::Dictionary(
+                        Box::new(DataType::Int32),
+                        Box::new(field.data_type().clone()),
+                    );
+                    field.with_data_type(data_type)
+                } else {
+                    field
+                }
+            })
             .collect::<Vec<_>>();
 
         let schema = Arc::new(Schema::new_with_metadata(fields, schema_meta));
@@ -235,6 +248,16 @@ impl ColumnGroupReaderMetrics {
     }
 }
 
+/// Whether the page is a string page declared with `Encoding::Dictionary`.
+fn is_dictionary_page(page: &PageWriteSpec) -> bool {
+    let column = &page.meta().column;
+    column.encoding == Encoding::Dictionary
+        && matches!(
+            column.column_type.to_physical_type(),
+            PhysicalCType::Field(PhysicalDType::String) | PhysicalCType::Tag
+        )
+}
+
 fn convert_data_type_if_necessary(array: ArrayRef, target_type: &DataType) -> TskvResult<ArrayRef> {
     if array.data_type() != target_type {
         cast::cast(&array, target_type).context(ArrowSnafu)
@@ -268,13 +291,18 @@ impl ReaderBuilder {
         }
     }
 
-    pub fn build(&self, page_meta: &PageWriteSpec) -> TskvResult<PageReaderRef> {
+    pub fn build(
+        &self,
+        page_meta: &PageWriteSpec,
+        keep_dictionary: bool,
+    ) -> TskvResult<PageReaderRef> {
         Ok(Arc::new(PrimitiveArrayReader::new(
             self.reader.clone(),
             page_meta,
             self.series_id,
             self.time_page_meta.clone(),
             self.time_range,
+            keep_dictionary,
             self.metrics.clone(),
         )))
     }
@@ -34,6 +34,14 @@ impl<'a> PruningStatistics for ColumnGroupsStatisticsWrapper<'a> {
                         let str = v.min().as_ref().and_then(|e| std::str::from_utf8(e).ok());
                         ScalarValue::from(str)
                     }
+                    PageStatistics::Geometry(v) => {
+                        let str = v
+                            .value()
+                            .min()
+                            .as_ref()
+                            .and_then(|e| std::str::from_utf8(e).ok());
+                        ScalarValue::from(str)
+                    }
                 })
                 .unwrap_or(null_value.clone())
         });
@@ -64,6 +72,14 @@ impl<'a> PruningStatistics for ColumnGroupsStatisticsWrapper<'a> {
                         let str = v.max().as_ref().and_then(|e| std::str::from_utf8(e).ok());
                         ScalarValue::from(str)
                     }
+                    PageStatistics::Geometry(v) => {
+                        let str = v
+                            .value()
+                            .max()
+                            .as_ref()
+                            .and_then(|e| std::str::from_utf8(e).ok());
+                        ScalarValue::from(str)
+                    }
                 })
                 .unwrap_or(null_value.clone())
         });
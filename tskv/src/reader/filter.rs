@@ -3,19 +3,23 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use arrow::compute::filter_record_batch;
-use arrow::datatypes::SchemaRef;
+use arrow::compute::kernels::cast;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::error::ArrowError;
 use arrow_array::RecordBatch;
 use datafusion::common::cast::as_boolean_array;
 use datafusion::error::DataFusionError;
 use datafusion::physical_plan::metrics::ExecutionPlanMetricsSet;
 use datafusion::physical_plan::PhysicalExpr;
 use futures::{Stream, StreamExt};
+use snafu::ResultExt;
 use trace::debug;
 
 use super::{
     BatchReader, BatchReaderRef, Predicate, SchemableTskvRecordBatchStream,
     SendableSchemableTskvRecordBatchStream,
 };
+use crate::error::ArrowSnafu;
 use crate::reader::metrics::BaselineMetrics;
 use crate::reader::utils::reassign_predicate_columns;
 use crate::TskvResult;
@@ -49,8 +53,12 @@ impl BatchReader for DataFilter {
         let new_predicate = reassign_predicate_columns(self.predicate.clone(), schema.clone())?;
         debug!("Reassigned columns predicate : {:?}", new_predicate);
 
+        // The dictionary encoded string columns are read as dictionary arrays to be filtered
+        // by comparing the dictionary keys, they are unpacked to strings after filtering.
+        let output_schema = unpack_dictionary_schema(&schema);
         Ok(Box::pin(DataFilterStream {
             schema,
+            output_schema,
             predicate: new_predicate,
             input,
             metrics: BaselineMetrics::new(self.metrics.as_ref()),
@@ -75,6 +83,7 @@ impl BatchReader for DataFilter {
 
 struct DataFilterStream {
     schema: SchemaRef,
+    output_schema: SchemaRef,
     predicate: Option<Arc<dyn PhysicalExpr>>,
     input: SendableSchemableTskvRecordBatchStream,
     // runtime metrics recording
@@ -83,7 +92,7 @@ struct DataFilterStream {
 
 impl SchemableTskvRecordBatchStream for DataFilterStream {
     fn schema(&self) -> SchemaRef {
-        self.schema.clone()
+        self.output_schema.clone()
     }
 }
 
@@ -95,11 +104,22 @@ impl DataFilterStream {
                     Some(Ok(batch)) => {
                         // 记录过滤数据所用时间
                         let _timer = self.metrics.elapsed_compute().timer();
-                        let batch = if let Some(filter) = self.predicate.as_ref() {
-                            batch_filter(&batch, filter)?
-                        } else {
-                            batch
+                        let batch = match self.predicate.as_ref() {
+                            Some(filter) => match batch_filter(&batch, filter) {
+                                Ok(batch) => batch,
+                                // Not every expression supports dictionary arrays,
+                                // evaluate it on the unpacked strings then.
+                                Err(_) if self.schema != self.output_schema => {
+                                    let batch = unpack_dictionary(batch, &self.output_schema)
+                                        .context(ArrowSnafu)?;
+                                    batch_filter(&batch, filter)?
+                                }
+                                Err(e) => return Poll::Ready(Some(Err(e.into()))),
+                            },
+                            None => batch,
                         };
+                        let batch =
+                            unpack_dictionary(batch, &self.output_schema).context(ArrowSnafu)?;
                         // skip entirely filtered batches
                         if batch.num_rows() == 0 {
                             continue;
@@ -141,12 +161,57 @@ fn batch_filter(
         })
 }
 
+fn unpack_dictionary_schema(schema: &SchemaRef) -> SchemaRef {
+    if !schema
+        .fields()
+        .iter()
+        .any(|f| matches!(f.data_type(), DataType::Dictionary(_, _)))
+    {
+        return schema.clone();
+    }
+
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|f| match f.data_type() {
+            DataType::Dictionary(_, value_type) => {
+                Field::clone(f).with_data_type(value_type.as_ref().clone())
+            }
+            _ => Field::clone(f),
+        })
+        .collect::<Vec<_>>();
+    Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone()))
+}
+
+fn unpack_dictionary(batch: RecordBatch, schema: &SchemaRef) -> Result<RecordBatch, ArrowError> {
+    if batch.schema().fields() == schema.fields() {
+        return Ok(batch);
+    }
+
+    let columns = batch
+        .columns()
+        .iter()
+        .zip(schema.fields())
+        .map(|(array, field)| {
+            if array.data_type() == field.data_type() {
+                Ok(array.clone())
+            } else {
+                cast::cast(array, field.data_type())
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    RecordBatch::try_new(schema.clone(), columns)
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
     use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
-    use arrow_array::{Float64Array, Int64Array, RecordBatch, StringArray, UInt64Array};
+    use arrow_array::types::Int32Type;
+    use arrow_array::{
+        DictionaryArray, Float64Array, Int64Array, RecordBatch, StringArray, UInt64Array,
+    };
     use datafusion::assert_batches_eq;
     use datafusion::logical_expr::Operator;
     use datafusion::physical_plan::expressions::{binary, Column, Literal};
@@ -156,7 +221,9 @@ mod tests {
     use futures::TryStreamExt;
 
     use crate::reader::filter::DataFilter;
-    use crate::reader::{BatchReader, MemoryBatchReader, Predicate};
+    use crate::reader::{
+        BatchReader, MemoryBatchReader, Predicate, SchemableTskvRecordBatchStream,
+    };
 
     fn file_record_batchs() -> Vec<RecordBatch> {
         let batch = RecordBatch::try_new(
@@ -251,4 +318,59 @@ mod tests {
 
         assert_batches_eq!(expected, &result);
     }
+
+    #[tokio::test]
+    async fn test_dictionary_column() {
+        let dictionary_type =
+            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("time", DataType::Int64, true),
+            Field::new("c3", dictionary_type, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3, 4, 5])),
+                Arc::new(DictionaryArray::<Int32Type>::from_iter(vec![
+                    Some("x"),
+                    Some("y"),
+                    Some("x"),
+                    None,
+                    Some("z"),
+                ])),
+            ],
+        )
+        .expect("create record batch");
+        let reader = MemoryBatchReader::new(schema, vec![batch]);
+
+        // c3 = 'x'
+        let lhs = Arc::new(Column::new("c3", 3));
+        let rhs = Arc::new(Literal::new(ScalarValue::Utf8(Some("x".to_string()))));
+        let predicate =
+            binary(lhs, Operator::Eq, rhs, output_schema().as_ref()).expect("binary predicate");
+        let predicate = Arc::new(Predicate::new(Some(predicate), output_schema(), None));
+
+        let filter = DataFilter::new(
+            predicate,
+            Arc::new(reader),
+            Arc::new(ExecutionPlanMetricsSet::new()),
+        );
+
+        let stream = filter.process().expect("filter");
+        assert_eq!(stream.schema().field(1).data_type(), &DataType::Utf8);
+
+        let result = stream.try_collect::<Vec<_>>().await.unwrap();
+
+        let expected = [
+            "+------+----+",
+            "| time | c3 |",
+            "+------+----+",
+            "| 1    | x  |",
+            "| 3    | x  |",
+            "+------+----+",
+        ];
+
+        assert_batches_eq!(expected, &result);
+        assert_eq!(result[0].column(1).data_type(), &DataType::Utf8);
+    }
 }
@@ -380,6 +380,8 @@ impl SeriesGroupBatchReaderFactory {
                                 projection,
                                 chunk_schema.metadata().clone(),
                                 batch_size,
+                                // only the DataFilter is able to unpack the dictionary arrays
+                                predicate.is_some(),
                                 self.column_group_reader_metrics_set.clone(),
                             )?;
                             Ok(Arc::new(column_group_reader) as BatchReaderRef)
@@ -939,11 +941,10 @@ async fn build_stream(
         Some(parse_physical_expr(expr, &NoRegistry, &arrow_schema)?)
     };
 
-    let predicate = PredicateRef::new(Predicate::new(
-        physical_expr,
-        arrow_schema,
-        query_option.split.limit(),
-    ));
+    let predicate = PredicateRef::new(
+        Predicate::new(physical_expr, arrow_schema, query_option.split.limit())
+            .with_spatial_filters(query_option.split.spatial_filters().to_vec()),
+    );
 
     if series_ids.is_empty() {
         if query_option.aggregates.is_some() {
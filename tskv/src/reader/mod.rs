@@ -15,6 +15,7 @@ use futures::stream::BoxStream;
 use futures::{Stream, StreamExt};
 pub use iterator::QueryOption;
 use models::field_value::DataType;
+use models::gis::bounding_box::SpatialFilter;
 use models::predicate::domain::{TimeRange, TimeRanges};
 use models::schema::tskv_table_schema::{PhysicalCType, TskvTableSchema};
 use models::schema::TIME_FIELD_NAME;
@@ -63,6 +64,7 @@ pub type PredicateRef = Arc<Predicate>;
 pub struct Predicate {
     expr: Option<Arc<dyn PhysicalExpr>>,
     schema: SchemaRef,
+    spatial_filters: Vec<SpatialFilter>,
     limit: Option<usize>,
 }
 
@@ -75,10 +77,16 @@ impl Predicate {
         Self {
             expr,
             schema,
+            spatial_filters: vec![],
             limit,
         }
     }
 
+    pub fn with_spatial_filters(mut self, spatial_filters: Vec<SpatialFilter>) -> Self {
+        self.spatial_filters = spatial_filters;
+        self
+    }
+
     pub fn schema(&self) -> SchemaRef {
         self.schema.clone()
     }
@@ -87,6 +95,10 @@ impl Predicate {
         self.expr.clone()
     }
 
+    pub fn spatial_filters(&self) -> &[SpatialFilter] {
+        &self.spatial_filters
+    }
+
     pub fn limit(&self) -> Option<usize> {
         self.limit
     }
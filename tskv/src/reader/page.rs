@@ -26,6 +26,9 @@ pub struct PrimitiveArrayReader {
     time_page_meta: Arc<PageWriteSpec>,
     time_range: TimeRange,
 
+    // read the dictionary encoded string page into DictionaryArray
+    keep_dictionary: bool,
+
     metrics: Arc<ExecutionPlanMetricsSet>,
 }
 
@@ -36,6 +39,7 @@ impl PrimitiveArrayReader {
         series_id: SeriesId,
         time_page_meta: Arc<PageWriteSpec>,
         time_range: TimeRange,
+        keep_dictionary: bool,
         metrics: Arc<ExecutionPlanMetricsSet>,
     ) -> Self {
         Self {
@@ -45,6 +49,7 @@ impl PrimitiveArrayReader {
             series_id,
             time_page_meta,
             time_range,
+            keep_dictionary,
             metrics,
         }
     }
@@ -60,6 +65,7 @@ impl PageReader for PrimitiveArrayReader {
             self.series_id,
             self.time_page_meta.clone(),
             self.time_range,
+            self.keep_dictionary,
             metrics,
         ))))
     }
@@ -71,6 +77,7 @@ async fn read(
     series_id: SeriesId,
     time_page_meta: Arc<PageWriteSpec>,
     time_range: TimeRange,
+    keep_dictionary: bool,
     metrics: ColumnGroupReaderMetrics,
 ) -> TskvResult<ArrayRef> {
     let page = {
@@ -81,7 +88,15 @@ async fn read(
     };
 
     let _timer = metrics.elapsed_page_to_array_time().timer();
-    page_to_arrow_array_with_tomb(page, reader, series_id, time_page_meta, time_range).await
+    page_to_arrow_array_with_tomb(
+        page,
+        reader,
+        series_id,
+        time_page_meta,
+        time_range,
+        keep_dictionary,
+    )
+    .await
 }
 
 #[cfg(test)]
@@ -13,6 +13,7 @@ use crate::error::{
 };
 use crate::file_system::async_filesystem::{LocalFileSystem, LocalFileType};
 use crate::file_system::file::stream_writer::FileStreamWriter;
+use crate::file_system::file::WritableFile;
 use crate::file_system::FileSystem;
 
 pub struct Writer {
@@ -172,6 +173,19 @@ impl Writer {
         self.sync().await
     }
 
+    /// Writes the buffered records to the file without syncing it.
+    pub async fn flush_buffer(&mut self) -> TskvResult<()> {
+        self.file.flush_buffer().await.context(WriteFileSnafu {
+            path: self.path.clone(),
+        })
+    }
+
+    /// Returns a handle of the file to sync the written records without the writer,
+    /// the records must have been written to the file by [`Writer::flush_buffer`].
+    pub fn shared_writable_file(&self) -> Option<Box<dyn WritableFile>> {
+        self.file.shared_writable_file()
+    }
+
     pub async fn new_reader(&mut self) -> TskvResult<reader::Reader> {
         self.file.flush().await.context(error::SyncFileSnafu)?;
         if let Some(r) = self.file.shared_file() {
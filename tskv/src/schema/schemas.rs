@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 
 use async_recursion::async_recursion;
+use config::common::TenantObjectLimiterConfig;
 use meta::error::{MetaError, TenantNotFoundSnafu};
 use meta::model::{MetaClientRef, MetaRef};
 use models::codec::Encoding;
@@ -215,6 +216,11 @@ impl DBschemas {
         Ok(schema)
     }
 
+    pub async fn tenant_object_config(&self) -> SchemaResult<Option<TenantObjectLimiterConfig>> {
+        let client = self.tenant_meta().await?;
+        Ok(client.tenant().options().object_config().copied())
+    }
+
     pub async fn list_tables(&self) -> SchemaResult<Vec<String>> {
         let tables = self.tenant_meta().await?.list_tables(&self.database_name)?;
 
@@ -20,13 +20,13 @@ use utils::BloomFilter;
 
 use crate::context::GlobalContext;
 use crate::error::{
-    IOSnafu, MetaSnafu, RecordFileDecodeSnafu, RecordFileEncodeSnafu, RecordFileIOSnafu, TskvError,
-    TskvResult,
+    CommonSnafu, IOSnafu, MetaSnafu, RecordFileDecodeSnafu, RecordFileEncodeSnafu,
+    RecordFileIOSnafu, TskvError, TskvResult,
 };
 use crate::file_system::async_filesystem::LocalFileSystem;
 use crate::kv_option::{Options, StorageOptions, DELTA_PATH, TSM_PATH};
 use crate::mem_cache::memcache::MemCache;
-use crate::record_file::{Reader, RecordDataType, RecordDataVersion, Writer};
+use crate::record_file::{Reader, Record, RecordDataType, RecordDataVersion, Writer};
 use crate::tsfamily::column_file::ColumnFile;
 use crate::tsfamily::level_info::LevelInfo;
 use crate::tsfamily::tseries_family::TseriesFamily;
@@ -34,6 +34,25 @@ use crate::tsfamily::version::Version;
 use crate::version_set::VersionSet;
 use crate::{byte_utils, file_utils, ColumnFileId, LevelId, VnodeId};
 
+/// Where the data of a column file lives.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum FileLocation {
+    /// In the tsm or delta directory of `StorageOptions.path`.
+    #[default]
+    Local,
+    /// Offloaded to the object store of tiered storage.
+    Remote,
+}
+
+impl Display for FileLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileLocation::Local => write!(f, "local"),
+            FileLocation::Remote => write!(f, "remote"),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct CompactMeta {
     pub file_id: ColumnFileId,
@@ -43,6 +62,7 @@ pub struct CompactMeta {
     pub min_ts: Timestamp,
     pub max_ts: Timestamp,
     pub is_delta: bool,
+    pub location: FileLocation,
 }
 
 impl Default for CompactMeta {
@@ -55,6 +75,7 @@ impl Default for CompactMeta {
             min_ts: Timestamp::MAX,
             max_ts: Timestamp::MIN,
             is_delta: false,
+            location: FileLocation::Local,
         }
     }
 }
@@ -72,6 +93,7 @@ impl From<&ColumnFile> for CompactMeta {
             min_ts: file.time_range().min_ts,
             max_ts: file.time_range().max_ts,
             is_delta: file.is_delta(),
+            location: file.location(),
             ..Default::default()
         }
     }
@@ -94,6 +116,7 @@ impl CompactMeta {
             min_ts,
             max_ts,
             is_delta: level == 0,
+            location: FileLocation::Local,
         }
     }
 
@@ -164,6 +187,63 @@ impl CompactMeta {
     }
 }
 
+/// `CompactMeta` of the summary records of `RecordDataVersion::V1`, which were written
+/// before column files could be offloaded, all the files are local.
+#[derive(Deserialize)]
+struct CompactMetaV1 {
+    file_id: ColumnFileId,
+    file_size: u64,
+    tsf_id: VnodeId,
+    level: LevelId,
+    min_ts: Timestamp,
+    max_ts: Timestamp,
+    is_delta: bool,
+}
+
+impl From<CompactMetaV1> for CompactMeta {
+    fn from(meta: CompactMetaV1) -> Self {
+        Self {
+            file_id: meta.file_id,
+            file_size: meta.file_size,
+            tsf_id: meta.tsf_id,
+            level: meta.level,
+            min_ts: meta.min_ts,
+            max_ts: meta.max_ts,
+            is_delta: meta.is_delta,
+            location: FileLocation::Local,
+        }
+    }
+}
+
+/// `VersionEdit` of the summary records of `RecordDataVersion::V1`.
+#[derive(Deserialize)]
+struct VersionEditV1 {
+    seq_no: u64,
+    file_id: ColumnFileId,
+    max_level_ts: Timestamp,
+    add_files: Vec<CompactMetaV1>,
+    del_files: Vec<CompactMetaV1>,
+    act_tsf: VnodeAction,
+    tsf_id: VnodeId,
+    tsf_name: String,
+}
+
+impl From<VersionEditV1> for VersionEdit {
+    fn from(edit: VersionEditV1) -> Self {
+        Self {
+            seq_no: edit.seq_no,
+            file_id: edit.file_id,
+            max_level_ts: edit.max_level_ts,
+            add_files: edit.add_files.into_iter().map(CompactMeta::from).collect(),
+            del_files: edit.del_files.into_iter().map(CompactMeta::from).collect(),
+            partly_del_files: vec![],
+            act_tsf: edit.act_tsf,
+            tsf_id: edit.tsf_id,
+            tsf_name: edit.tsf_name,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct VersionEdit {
     pub seq_no: u64,
@@ -256,6 +336,24 @@ impl VersionEdit {
         bincode::deserialize(buf).context(RecordFileDecodeSnafu)
     }
 
+    /// Decode a summary record by its data version, the records written by older
+    /// versions are migrated to the current `VersionEdit`.
+    fn decode_record(record: &Record) -> TskvResult<Self> {
+        match RecordDataVersion::try_from(record.data_version) {
+            Ok(RecordDataVersion::V1) => bincode::deserialize::<VersionEditV1>(&record.data)
+                .map(VersionEdit::from)
+                .context(RecordFileDecodeSnafu),
+            Ok(RecordDataVersion::V2) => Self::decode(&record.data),
+            Err(_) => Err(CommonSnafu {
+                reason: format!(
+                    "Unknown data version {} of summary record",
+                    record.data_version
+                ),
+            }
+            .build()),
+        }
+    }
+
     pub fn encode_vec(data: &[Self]) -> TskvResult<Vec<u8>> {
         let mut buf: Vec<u8> = Vec::with_capacity(data.len() * 32);
         for ve in data {
@@ -369,7 +467,7 @@ impl Summary {
         let buf = db.encode()?;
         let _ = w
             .write_record(
-                RecordDataVersion::V1.into(),
+                RecordDataVersion::V2.into(),
                 RecordDataType::Summary.into(),
                 &[&buf],
             )
@@ -462,7 +560,7 @@ impl Summary {
             let res = reader.read_record().await;
             match res {
                 Ok(result) => {
-                    let ed = VersionEdit::decode(&result.data)?;
+                    let ed = VersionEdit::decode_record(&result)?;
                     if ed.act_tsf == VnodeAction::Add {
                         let owner_ref = owner_map
                             .entry(ed.tsf_name.clone())
@@ -561,7 +659,7 @@ impl Summary {
         let _ = self
             .writer
             .write_record(
-                RecordDataVersion::V1.into(),
+                RecordDataVersion::V2.into(),
                 RecordDataType::Summary.into(),
                 &[&buf],
             )
@@ -721,7 +819,7 @@ pub async fn print_summary_statistics(path: impl AsRef<Path>) {
     loop {
         match reader.read_record().await {
             Ok(record) => {
-                let ve = VersionEdit::decode(&record.data).unwrap();
+                let ve = VersionEdit::decode_record(&record).unwrap();
                 println!("VersionEdit #{}, vnode_id: {}", i, ve.tsf_id);
                 println!("------------------------------------------------------------");
                 i += 1;
@@ -820,7 +918,10 @@ mod test {
     use utils::BloomFilter;
 
     use crate::kv_option::{self, Options};
-    use crate::summary::{CompactMeta, Summary, SummaryTask, VersionEdit};
+    use crate::record_file::{Record, RecordDataType, RecordDataVersion};
+    use crate::summary::{
+        CompactMeta, FileLocation, Summary, SummaryTask, VersionEdit, VnodeAction,
+    };
     use crate::{Engine, TsKv, VnodeId};
 
     #[test]
@@ -849,6 +950,45 @@ mod test {
         assert_eq!(ves, ves_2);
     }
 
+    #[test]
+    fn test_decode_version_edit_v1() {
+        // A version edit of the summary files written before `CompactMeta.location`.
+        let add_file = (100_u64, 1024_u64, 1_u32, 1_u32, 1_i64, 10_i64, false);
+        let v1_buf = bincode::serialize(&(
+            5_u64,
+            101_u64,
+            10_i64,
+            vec![add_file],
+            Vec::<(u64, u64, u32, u32, i64, i64, bool)>::new(),
+            VnodeAction::Update,
+            1_u32,
+            "cnosdb.db".to_string(),
+        ))
+        .unwrap();
+        let record = Record {
+            data_type: RecordDataType::Summary.into(),
+            data_version: RecordDataVersion::V1.into(),
+            data: v1_buf,
+            pos: 0,
+        };
+        let ve = VersionEdit::decode_record(&record).unwrap();
+        assert_eq!(ve.seq_no, 5);
+        assert_eq!(ve.file_id, 101);
+        assert_eq!(ve.tsf_name, "cnosdb.db");
+        assert_eq!(ve.add_files.len(), 1);
+        assert_eq!(ve.add_files[0].file_id, 100);
+        assert_eq!(ve.add_files[0].file_size, 1024);
+        assert!(!ve.add_files[0].is_delta);
+        assert_eq!(ve.add_files[0].location, FileLocation::Local);
+
+        let record = Record {
+            data_version: RecordDataVersion::V2.into(),
+            data: ve.encode().unwrap(),
+            ..record
+        };
+        assert_eq!(VersionEdit::decode_record(&record).unwrap(), ve);
+    }
+
     struct SummaryTestHelper {
         tskv: TsKv,
         config: Config,
@@ -1127,6 +1267,7 @@ mod test {
                 min_ts: 1,
                 max_ts: 1,
                 tsf_id: VNODE_ID,
+                location: FileLocation::Local,
             };
 
             let mut version = version.inner();
@@ -9,10 +9,11 @@ use tokio::sync::{RwLock as AsyncRwLock, RwLockWriteGuard as AsyncRwLockWriteGua
 use trace::{debug, error, info};
 use utils::BloomFilter;
 
-use crate::error::TskvResult;
+use crate::error::{CommonSnafu, TskvResult};
 use crate::file_system::async_filesystem::LocalFileSystem;
+use crate::file_system::remote_filesystem::RemoteFileSystem;
 use crate::file_system::FileSystem;
-use crate::summary::CompactMeta;
+use crate::summary::{CompactMeta, FileLocation};
 use crate::tsm::reader::TsmReader;
 use crate::tsm::tombstone::tombstone_compact_tmp_path;
 use crate::tsm::TsmTombstone;
@@ -26,9 +27,15 @@ pub struct ColumnFile {
     size: u64,
     series_id_filter: AsyncRwLock<Option<Arc<BloomFilter>>>,
     deleted: AtomicBool,
+    /// The file is replaced by a file with the same id in another location,
+    /// only the data file will be removed when dropped, tombstone is kept.
+    relocated: AtomicBool,
     compacting: Arc<AsyncRwLock<bool>>,
 
+    /// Local path of the file, the path is also used to address a remote file.
     path: PathBuf,
+    location: FileLocation,
+    remote: Option<Arc<RemoteFileSystem>>,
     tsm_reader_cache: Weak<ShardedAsyncCache<String, Arc<TsmReader>>>,
 }
 
@@ -36,6 +43,7 @@ impl ColumnFile {
     pub fn with_compact_data(
         meta: &CompactMeta,
         path: impl AsRef<Path>,
+        remote: Option<Arc<RemoteFileSystem>>,
         series_id_filter: AsyncRwLock<Option<Arc<BloomFilter>>>,
         tsm_reader_cache: Weak<ShardedAsyncCache<String, Arc<TsmReader>>>,
     ) -> Self {
@@ -46,8 +54,11 @@ impl ColumnFile {
             size: meta.file_size,
             series_id_filter,
             deleted: AtomicBool::new(false),
+            relocated: AtomicBool::new(false),
             compacting: Arc::new(AsyncRwLock::new(false)),
             path: path.as_ref().into(),
+            location: meta.location,
+            remote,
             tsm_reader_cache,
         }
     }
@@ -76,6 +87,49 @@ impl ColumnFile {
         &self.path
     }
 
+    pub fn location(&self) -> FileLocation {
+        self.location
+    }
+
+    pub fn is_remote(&self) -> bool {
+        self.location == FileLocation::Remote
+    }
+
+    /// Open a new `TsmReader` for this file whether it's local or remote.
+    pub async fn open_tsm_reader(&self) -> TskvResult<TsmReader> {
+        match (self.location, self.remote.as_ref()) {
+            (FileLocation::Local, _) => TsmReader::open(&self.path).await,
+            (FileLocation::Remote, Some(remote)) => {
+                TsmReader::open_remote(&self.path, remote).await
+            }
+            (FileLocation::Remote, None) => Err(CommonSnafu {
+                reason: format!(
+                    "tsm file {} is offloaded but tiered storage is not enabled",
+                    self.path.display()
+                ),
+            }
+            .build()),
+        }
+    }
+
+    /// Get the `TsmReader` of this file from the tsm_reader_cache,
+    /// open and put it into the cache if not found.
+    pub async fn tsm_reader(&self) -> TskvResult<Arc<TsmReader>> {
+        let tsm_reader_cache = match self.tsm_reader_cache.upgrade() {
+            Some(cache) => cache,
+            None => return Ok(Arc::new(self.open_tsm_reader().await?)),
+        };
+        let key = format!("{}", self.path.display());
+        match tsm_reader_cache.get(&key).await {
+            Some(r) => Ok(r),
+            None => {
+                let reader = Arc::new(self.open_tsm_reader().await?);
+                tsm_reader_cache.insert(key, reader.clone()).await;
+                Ok(reader)
+            }
+        }
+    }
+
     pub fn tombstone_path(&self) -> PathBuf {
         let mut path = self.path.clone();
         path.set_extension(tsm::TOMBSTONE_FILE_SUFFIX);
@@ -102,31 +156,8 @@ impl ColumnFile {
         if let Some(filter) = filter_w.as_ref() {
             return Ok(filter.clone());
         }
-        let bloom_filter = if let Some(tsm_reader_cache) = self.tsm_reader_cache.upgrade() {
-            let reader = match tsm_reader_cache
-                .get(&format!("{}", self.path.display()))
-                .await
-            {
-                Some(r) => r,
-                None => {
-                    let reader = TsmReader::open(&self.path).await?;
-                    let reader = Arc::new(reader);
-                    tsm_reader_cache
-                        .insert(self.path.display().to_string(), reader.clone())
-                        .await;
-                    reader
-                }
-            };
-            reader.footer().series().bloom_filter().clone()
-        } else {
-            TsmReader::open(&self.path)
-                .await?
-                .footer()
-                .series()
-                .bloom_filter()
-                .clone()
-        };
-        let bloom_filter = Arc::new(bloom_filter);
+        let reader = self.tsm_reader().await?;
+        let bloom_filter = Arc::new(reader.footer().series().bloom_filter().clone());
         filter_w.replace(bloom_filter.clone());
         Ok(bloom_filter)
     }
@@ -171,6 +202,17 @@ impl ColumnFile {
         self.deleted.store(true, Ordering::Release);
     }
 
+    pub fn is_relocated(&self) -> bool {
+        self.relocated.load(Ordering::Acquire)
+    }
+
+    /// Mark the file as moved to another location, the data file will be removed
+    /// when dropped but the tombstone still belongs to the moved file.
+    pub fn mark_relocated(&self) {
+        self.relocated.store(true, Ordering::Release);
+        self.deleted.store(true, Ordering::Release);
+    }
+
     pub async fn is_compacting(&self) -> bool {
         *self.compacting.read().await
     }
@@ -204,14 +246,35 @@ impl Drop for ColumnFile {
                     cache.remove(&k).await;
                 });
             }
-            if let Err(e) = std::fs::remove_file(path) {
-                error!(
-                    "Failed to remove tsm file {} at '{}': {e}",
-                    self.file_id,
-                    path.display()
-                );
-            } else {
-                info!("Removed tsm file {} at '{}", self.file_id, path.display());
+            match (self.location, self.remote.clone()) {
+                (FileLocation::Remote, Some(remote)) => {
+                    let file_id = self.file_id;
+                    let path = path.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = remote.delete(&path).await {
+                            error!(
+                                "Failed to remove remote tsm file {file_id} at '{}': {e}",
+                                path.display()
+                            );
+                        } else {
+                            info!("Removed remote tsm file {file_id} at '{}", path.display());
+                        }
+                    });
+                }
+                _ => {
+                    if let Err(e) = std::fs::remove_file(path) {
+                        error!(
+                            "Failed to remove tsm file {} at '{}': {e}",
+                            self.file_id,
+                            path.display()
+                        );
+                    } else {
+                        info!("Removed tsm file {} at '{}", self.file_id, path.display());
+                    }
+                }
+            }
+            if self.is_relocated() {
+                return;
             }
 
             let tombstone_path = self.tombstone_path();
@@ -256,8 +319,13 @@ impl std::fmt::Display for ColumnFile {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{{ level:{}, file_id:{}, time_range:{}-{}, file size:{} }}",
-            self.level, self.file_id, self.time_range.min_ts, self.time_range.max_ts, self.size,
+            "{{ level:{}, file_id:{}, time_range:{}-{}, file size:{}, location:{} }}",
+            self.level,
+            self.file_id,
+            self.time_range.min_ts,
+            self.time_range.max_ts,
+            self.size,
+            self.location,
         )
     }
 }
@@ -279,8 +347,11 @@ impl ColumnFile {
             size,
             series_id_filter: AsyncRwLock::new(Some(Arc::new(BloomFilter::default()))),
             deleted: AtomicBool::new(false),
+            relocated: AtomicBool::new(false),
             compacting: Arc::new(AsyncRwLock::new(false)),
             path: path.as_ref().into(),
+            location: FileLocation::Local,
+            remote: None,
             tsm_reader_cache: Weak::new(),
         }
     }
@@ -80,6 +80,7 @@ impl LevelInfo {
         self.files.push(Arc::new(ColumnFile::with_compact_data(
             compact_meta,
             file_path,
+            self.storage_opt.tiered.clone(),
             series_filter,
             tsm_reader_cache,
         )));
@@ -426,6 +426,10 @@ impl TseriesFamily {
         self.storage_opt.clone()
     }
 
+    pub fn db_config(&self) -> Arc<DatabaseConfig> {
+        self.db_config.clone()
+    }
+
     pub fn get_delta_dir(&self) -> PathBuf {
         self.storage_opt.delta_dir(&self.owner, self.tf_id)
     }
@@ -491,7 +495,7 @@ pub mod test_tseries_family {
 
     use crate::file_utils::make_tsm_file;
     use crate::kv_option::Options;
-    use crate::summary::{CompactMeta, VersionEdit};
+    use crate::summary::{CompactMeta, FileLocation, VersionEdit};
     use crate::tsfamily::column_file::ColumnFile;
     use crate::tsfamily::level_info::LevelInfo;
     use crate::tsfamily::version::Version;
@@ -576,6 +580,7 @@ pub mod test_tseries_family {
                 min_ts: 3051,
                 max_ts: 3150,
                 is_delta: false,
+                location: FileLocation::Local,
             },
             3100,
         );
@@ -669,6 +674,7 @@ pub mod test_tseries_family {
                 min_ts: 3001,
                 max_ts: 3150,
                 is_delta: false,
+                location: FileLocation::Local,
             },
             3150,
         );
@@ -682,6 +688,7 @@ pub mod test_tseries_family {
                 min_ts: 1,
                 max_ts: 2000,
                 is_delta: false,
+                location: FileLocation::Local,
             },
             3150,
         );
@@ -83,7 +83,14 @@ impl Version {
         for level in self.levels_info.iter() {
             for file in level.files.iter() {
                 if deleted_files[file.level() as usize].contains(&file.file_id()) {
-                    file.mark_deleted();
+                    let relocated = added_files[file.level() as usize]
+                        .iter()
+                        .any(|f| f.file_id == file.file_id() && f.location != file.location());
+                    if relocated {
+                        file.mark_relocated();
+                    } else {
+                        file.mark_deleted();
+                    }
                     continue;
                 }
                 new_levels[level.level as usize].push_column_file(file.clone());
@@ -160,7 +167,7 @@ impl Version {
             None => match self.tsm_reader_cache.get(&path).await {
                 Some(val) => val,
                 None => {
-                    let tsm_reader = Arc::new(TsmReader::open(&path).await?);
+                    let tsm_reader = Arc::new(self.open_tsm_reader(&path).await?);
                     self.tsm_reader_cache.insert(path, tsm_reader.clone()).await;
                     tsm_reader
                 }
@@ -169,6 +176,21 @@ impl Version {
         Ok(tsm_reader)
     }
 
+    /// Open the tsm file from the remote file system if it was offloaded,
+    /// otherwise from the local file system.
+    async fn open_tsm_reader(&self, path: impl AsRef<Path>) -> TskvResult<TsmReader> {
+        let path = path.as_ref();
+        let remote_file = self
+            .levels_info
+            .iter()
+            .flat_map(|l| l.files.iter())
+            .find(|f| f.is_remote() && f.file_path() == path);
+        match remote_file {
+            Some(file) => file.open_tsm_reader().await,
+            None => TsmReader::open(path).await,
+        }
+    }
+
     pub fn max_level_ts(&self) -> i64 {
         self.max_level_ts
     }
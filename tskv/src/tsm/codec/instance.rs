@@ -17,7 +17,8 @@ use crate::tsm::codec::integer::{
     i64_without_compress_encode, i64_zigzag_simple8b_decode_to_array, i64_zigzag_simple8b_encode,
 };
 use crate::tsm::codec::string::{
-    str_bzip_decode, str_bzip_decode_to_array, str_bzip_encode, str_gzip_decode,
+    str_bzip_decode, str_bzip_decode_to_array, str_bzip_encode, str_dictionary_decode,
+    str_dictionary_decode_to_array, str_dictionary_encode, str_gzip_decode,
     str_gzip_decode_to_array, str_gzip_encode, str_snappy_decode, str_snappy_decode_to_array,
     str_snappy_encode, str_without_compress_decode, str_without_compress_decode_to_array,
     str_without_compress_encode, str_zlib_decode, str_zlib_decode_to_array, str_zlib_encode,
@@ -355,6 +356,182 @@ impl StringCodec for ZlibStringCodec {
     }
 }
 
+struct DictionaryStringCodec();
+
+impl StringCodec for DictionaryStringCodec {
+    fn encode(&self, src: &[&[u8]], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        str_dictionary_encode(src, dst)
+    }
+
+    fn decode(&self, src: &[u8], dst: &mut Vec<MiniVec<u8>>) -> Result<(), CodecError> {
+        str_dictionary_decode(src, dst)
+    }
+
+    fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+        str_dictionary_decode_to_array(src, bit_set)
+    }
+}
+
+/// The number of leading values of a page that are encoded by every candidate codec
+/// when choosing the codec for `Encoding::Auto`.
+const AUTO_ENCODING_SAMPLE_SIZE: usize = 1024;
+
+const AUTO_TIMESTAMP_CANDIDATES: [Encoding; 4] = [
+    Encoding::DeltaTs,
+    Encoding::Delta,
+    Encoding::Quantile,
+    Encoding::Null,
+];
+const AUTO_INTEGER_CANDIDATES: [Encoding; 3] =
+    [Encoding::Delta, Encoding::Quantile, Encoding::Null];
+const AUTO_UNSIGNED_CANDIDATES: [Encoding; 3] =
+    [Encoding::Delta, Encoding::Quantile, Encoding::Null];
+const AUTO_FLOAT_CANDIDATES: [Encoding; 3] =
+    [Encoding::Gorilla, Encoding::Quantile, Encoding::Null];
+const AUTO_STRING_CANDIDATES: [Encoding; 5] = [
+    Encoding::Dictionary,
+    Encoding::Snappy,
+    Encoding::Zstd,
+    Encoding::Zlib,
+    Encoding::Null,
+];
+const AUTO_BOOLEAN_CANDIDATES: [Encoding; 2] = [Encoding::BitPack, Encoding::Null];
+
+/// Encode a sample of `src` with every candidate encoding, then encode `src` with the one
+/// that produces the smallest output, earlier candidates win the ties.
+///
+/// Every codec writes its encoding as the first byte of the encoded data,
+/// so the chosen encoding is decoded without any extra metadata.
+fn encode_with_smallest<T>(
+    src: &[T],
+    dst: &mut Vec<u8>,
+    candidates: &[Encoding],
+    encode: impl Fn(Encoding, &[T], &mut Vec<u8>) -> Result<(), CodecError>,
+) -> Result<(), CodecError> {
+    let sample = &src[..src.len().min(AUTO_ENCODING_SAMPLE_SIZE)];
+    let mut smallest: Option<(Encoding, Vec<u8>)> = None;
+    let mut last_error = None;
+    for encoding in candidates {
+        let mut buf = Vec::new();
+        if let Err(e) = encode(*encoding, sample, &mut buf) {
+            last_error = Some(e);
+            continue;
+        }
+        if smallest.as_ref().map_or(true, |(_, b)| buf.len() < b.len()) {
+            smallest = Some((*encoding, buf));
+        }
+    }
+
+    match smallest {
+        // The sample is the whole page, the encoded sample is the result.
+        Some((_, buf)) if sample.len() == src.len() => {
+            dst.extend_from_slice(&buf);
+            Ok(())
+        }
+        Some((encoding, _)) => encode(encoding, src, dst),
+        None => Err(last_error.unwrap_or_else(|| "no candidate encoding".into())),
+    }
+}
+
+/// Returns the encoding of the encoded data for the codecs of `Encoding::Auto`.
+fn auto_decoding(src: &[u8]) -> Result<Encoding, CodecError> {
+    match get_encoding(src) {
+        Encoding::Auto => Err("unexpected encoding AUTO in encoded data".into()),
+        encoding => Ok(encoding),
+    }
+}
+
+struct AutoTimestampCodec();
+
+impl TimestampCodec for AutoTimestampCodec {
+    fn encode(&self, src: &[i64], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        encode_with_smallest(
+            src,
+            dst,
+            &AUTO_TIMESTAMP_CANDIDATES,
+            |encoding, src, dst| get_ts_codec(encoding).encode(src, dst),
+        )
+    }
+
+    fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+        get_ts_codec(auto_decoding(src)?).decode_to_array(src, bit_set)
+    }
+}
+
+struct AutoIntegerCodec();
+
+impl IntegerCodec for AutoIntegerCodec {
+    fn encode(&self, src: &[i64], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        encode_with_smallest(src, dst, &AUTO_INTEGER_CANDIDATES, |encoding, src, dst| {
+            get_i64_codec(encoding).encode(src, dst)
+        })
+    }
+
+    fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+        get_i64_codec(auto_decoding(src)?).decode_to_array(src, bit_set)
+    }
+}
+
+struct AutoUnsignedCodec();
+
+impl UnsignedCodec for AutoUnsignedCodec {
+    fn encode(&self, src: &[u64], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        encode_with_smallest(src, dst, &AUTO_UNSIGNED_CANDIDATES, |encoding, src, dst| {
+            get_u64_codec(encoding).encode(src, dst)
+        })
+    }
+
+    fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+        get_u64_codec(auto_decoding(src)?).decode_to_array(src, bit_set)
+    }
+}
+
+struct AutoFloatCodec();
+
+impl FloatCodec for AutoFloatCodec {
+    fn encode(&self, src: &[f64], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        encode_with_smallest(src, dst, &AUTO_FLOAT_CANDIDATES, |encoding, src, dst| {
+            get_f64_codec(encoding).encode(src, dst)
+        })
+    }
+
+    fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+        get_f64_codec(auto_decoding(src)?).decode_to_array(src, bit_set)
+    }
+}
+
+struct AutoStringCodec();
+
+impl StringCodec for AutoStringCodec {
+    fn encode(&self, src: &[&[u8]], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        encode_with_smallest(src, dst, &AUTO_STRING_CANDIDATES, |encoding, src, dst| {
+            get_str_codec(encoding).encode(src, dst)
+        })
+    }
+
+    fn decode(&self, src: &[u8], dst: &mut Vec<MiniVec<u8>>) -> Result<(), CodecError> {
+        get_str_codec(auto_decoding(src)?).decode(src, dst)
+    }
+
+    fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+        get_str_codec(auto_decoding(src)?).decode_to_array(src, bit_set)
+    }
+}
+
+struct AutoBooleanCodec();
+
+impl BooleanCodec for AutoBooleanCodec {
+    fn encode(&self, src: &[bool], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+        encode_with_smallest(src, dst, &AUTO_BOOLEAN_CANDIDATES, |encoding, src, dst| {
+            get_bool_codec(encoding).encode(src, dst)
+        })
+    }
+
+    fn decode_to_array(&self, src: &[u8], bit_set: &NullBuffer) -> Result<ArrayRef, CodecError> {
+        get_bool_codec(auto_decoding(src)?).decode_to_array(src, bit_set)
+    }
+}
+
 pub fn get_encoding(src: &[u8]) -> Encoding {
     if src.is_empty() {
         return Encoding::Unknown;
@@ -368,6 +545,7 @@ pub fn get_ts_codec(algo: Encoding) -> Box<dyn TimestampCodec + Send + Sync> {
         Encoding::Delta => Box::new(DeltaIntegerCodec()),
         Encoding::DeltaTs => Box::new(DeltaTsTimestampCodec()),
         Encoding::Quantile => Box::new(QuantileTimestampCodec()),
+        Encoding::Auto => Box::new(AutoTimestampCodec()),
         _ => Box::new(DeltaTsTimestampCodec()),
     }
 }
@@ -378,6 +556,7 @@ pub fn get_i64_codec(algo: Encoding) -> Box<dyn IntegerCodec + Send + Sync> {
         Encoding::Delta => Box::new(DeltaIntegerCodec()),
         Encoding::DeltaTs => Box::new(DeltaTsTimestampCodec()),
         Encoding::Quantile => Box::new(QuantileIntegerCodec()),
+        Encoding::Auto => Box::new(AutoIntegerCodec()),
         _ => Box::new(DeltaIntegerCodec()),
     }
 }
@@ -387,6 +566,7 @@ pub fn get_u64_codec(algo: Encoding) -> Box<dyn UnsignedCodec + Send + Sync> {
         Encoding::Null => Box::new(NullUnsignedCodec()),
         Encoding::Delta => Box::new(DeltaUnsignedCodec()),
         Encoding::Quantile => Box::new(QuantileUnsignedCodec()),
+        Encoding::Auto => Box::new(AutoUnsignedCodec()),
         _ => Box::new(DeltaUnsignedCodec()),
     }
 }
@@ -396,6 +576,7 @@ pub fn get_f64_codec(algo: Encoding) -> Box<dyn FloatCodec + Send + Sync> {
         Encoding::Null => Box::new(NullFloatCodec()),
         Encoding::Gorilla => Box::new(GorillaFloatCodec()),
         Encoding::Quantile => Box::new(QuantileFloatCodec()),
+        Encoding::Auto => Box::new(AutoFloatCodec()),
         _ => Box::new(GorillaFloatCodec()),
     }
 }
@@ -408,6 +589,8 @@ pub fn get_str_codec(algo: Encoding) -> Box<dyn StringCodec + Send + Sync> {
         Encoding::Snappy => Box::new(SnappyStringCodec()),
         Encoding::Zstd => Box::new(ZstdStringCodec()),
         Encoding::Zlib => Box::new(ZlibStringCodec()),
+        Encoding::Dictionary => Box::new(DictionaryStringCodec()),
+        Encoding::Auto => Box::new(AutoStringCodec()),
         _ => Box::new(SnappyStringCodec()),
     }
 }
@@ -416,6 +599,43 @@ pub fn get_bool_codec(algo: Encoding) -> Box<dyn BooleanCodec + Send + Sync> {
     match algo {
         Encoding::Null => Box::new(NullBooleanCodec()),
         Encoding::BitPack => Box::new(BitPackBooleanCodec()),
+        Encoding::Auto => Box::new(AutoBooleanCodec()),
         _ => Box::new(BitPackBooleanCodec()),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use arrow::buffer::NullBuffer;
+    use arrow_array::{Array, Int64Array, StringArray};
+    use models::codec::Encoding;
+
+    use super::{get_encoding, get_i64_codec, get_str_codec};
+
+    #[test]
+    fn test_auto_encoding() {
+        let src = (0..4096_i64).map(|i| i * 10).collect::<Vec<_>>();
+        let codec = get_i64_codec(Encoding::Auto);
+        let mut dst = vec![];
+        codec.encode(&src, &mut dst).unwrap();
+        assert_ne!(get_encoding(&dst), Encoding::Null);
+        assert_ne!(get_encoding(&dst), Encoding::Auto);
+        let array_ref = get_i64_codec(get_encoding(&dst))
+            .decode_to_array(&dst, &NullBuffer::new_valid(src.len()))
+            .unwrap();
+        let array = array_ref.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(array, &Int64Array::from(src));
+
+        let src = vec![b"cnosdb".as_slice(); 100];
+        let codec = get_str_codec(Encoding::Auto);
+        let mut dst = vec![];
+        codec.encode(&src, &mut dst).unwrap();
+        assert_ne!(get_encoding(&dst), Encoding::Null);
+        let array_ref = codec
+            .decode_to_array(&dst, &NullBuffer::new_valid(src.len()))
+            .unwrap();
+        let array = array_ref.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(array.len(), 100);
+        assert!(array.iter().all(|v| v == Some("cnosdb")));
+    }
+}
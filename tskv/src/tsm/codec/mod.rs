@@ -11,6 +11,7 @@ use std::error::Error;
 
 pub use instance::*;
 use models::codec::Encoding;
+pub use string::str_dictionary_decode_to_dictionary_array;
 
 /// Max number of bytes needed to store a varint-encoded 32-bit integer.
 const MAX_VAR_INT_32: usize = 5;
@@ -1,10 +1,12 @@
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::io::Write;
 use std::sync::Arc;
 
 use arrow::buffer::NullBuffer;
 use arrow_array::builder::StringBuilder;
-use arrow_array::{ArrayRef, StringArray};
+use arrow_array::types::Int32Type;
+use arrow_array::{ArrayRef, DictionaryArray, Int32Array, StringArray};
 use bzip2::write::{BzDecoder, BzEncoder};
 use bzip2::Compression as CompressionBzip;
 use flate2::write::{GzDecoder, GzEncoder, ZlibDecoder, ZlibEncoder};
@@ -471,6 +473,199 @@ pub fn str_without_compress_decode_to_array(
     split_stream_to_array(src, bit_set)
 }
 
+/// Encodes the strings with a dictionary of the distinct values in `src`,
+/// each value is stored as the bit-packed index of its dictionary entry.
+///
+/// The layout is `[encoding][num_values][num_entries][(len, bytes) of entries][bit_width][indices]`,
+/// the numbers and lengths are var-int encoded.
+pub fn str_dictionary_encode(src: &[&[u8]], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+    if src.is_empty() {
+        return Ok(());
+    }
+
+    let mut entries: Vec<&[u8]> = vec![];
+    let mut entry_indices: HashMap<&[u8], u32> = HashMap::new();
+    let mut indices = Vec::with_capacity(src.len());
+    for s in src {
+        let index = *entry_indices.entry(*s).or_insert_with(|| {
+            entries.push(*s);
+            (entries.len() - 1) as u32
+        });
+        indices.push(index);
+    }
+    // The indices are read back as the i32 keys of a DictionaryArray.
+    i32::try_from(entries.len())?;
+
+    dst.push(Encoding::Dictionary as u8);
+    let mut buf = [0_u8; 10];
+    let n = (src.len() as u64).encode_var(&mut buf);
+    dst.extend_from_slice(&buf[..n]);
+    let n = (entries.len() as u64).encode_var(&mut buf);
+    dst.extend_from_slice(&buf[..n]);
+    for entry in entries.iter() {
+        let n = (entry.len() as u64).encode_var(&mut buf);
+        dst.extend_from_slice(&buf[..n]);
+        dst.extend_from_slice(entry);
+    }
+
+    let bit_width = dictionary_bit_width(entries.len());
+    dst.push(bit_width);
+    let bit_width = bit_width as usize;
+    let start = dst.len();
+    dst.resize(start + (indices.len() * bit_width + 7) / 8, 0);
+    for (i, index) in indices.into_iter().enumerate() {
+        for bit in 0..bit_width {
+            if index >> bit & 1 == 1 {
+                let pos = i * bit_width + bit;
+                dst[start + pos / 8] |= 1 << (pos % 8);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Number of bits to store the indices of a dictionary with `num_entries` entries.
+fn dictionary_bit_width(num_entries: usize) -> u8 {
+    (usize::BITS - num_entries.saturating_sub(1).leading_zeros()) as u8
+}
+
+/// A string page encoded by `str_dictionary_encode`.
+struct DictionaryPage<'a> {
+    num_values: usize,
+    entries: Vec<&'a [u8]>,
+    bit_width: usize,
+    indices: &'a [u8],
+}
+
+impl<'a> DictionaryPage<'a> {
+    fn try_new(src: &'a [u8]) -> Result<Self, CodecError> {
+        let mut i = 1;
+        let mut read_var = |i: &mut usize| -> Result<usize, CodecError> {
+            let (value, n) = u64::decode_var(&src[*i..]).ok_or("invalid dictionary page")?;
+            *i += n;
+            Ok(value.try_into()?)
+        };
+
+        let num_values = read_var(&mut i)?;
+        let num_entries = read_var(&mut i)?;
+        let mut entries = Vec::with_capacity(num_entries);
+        for _ in 0..num_entries {
+            let len = read_var(&mut i)?;
+            let entry = src
+                .get(i..i + len)
+                .ok_or("dictionary entry out of bounds")?;
+            entries.push(entry);
+            i += len;
+        }
+
+        let bit_width = *src.get(i).ok_or("dictionary bit width is missing")? as usize;
+        let indices = &src[i + 1..];
+        if indices.len() < (num_values * bit_width + 7) / 8 {
+            return Err("dictionary indices out of bounds".into());
+        }
+
+        Ok(Self {
+            num_values,
+            entries,
+            bit_width,
+            indices,
+        })
+    }
+
+    fn index(&self, i: usize) -> Result<usize, CodecError> {
+        let mut index = 0;
+        for bit in 0..self.bit_width {
+            let pos = i * self.bit_width + bit;
+            if self.indices[pos / 8] >> (pos % 8) & 1 == 1 {
+                index |= 1 << bit;
+            }
+        }
+        if index >= self.entries.len() {
+            return Err("dictionary index out of bounds".into());
+        }
+        Ok(index)
+    }
+}
+
+pub fn str_dictionary_decode(src: &[u8], dst: &mut Vec<MiniVec<u8>>) -> Result<(), CodecError> {
+    if src.is_empty() {
+        return Ok(());
+    }
+    let page = DictionaryPage::try_new(src)?;
+    for i in 0..page.num_values {
+        dst.push(MiniVec::from(page.entries[page.index(i)?]));
+    }
+    Ok(())
+}
+
+pub fn str_dictionary_decode_to_array(
+    src: &[u8],
+    bit_set: &NullBuffer,
+) -> Result<ArrayRef, CodecError> {
+    if src.is_empty() {
+        let null_value: Vec<Option<String>> = vec![None; bit_set.len()];
+        let array = StringArray::from(null_value);
+        return Ok(Arc::new(array));
+    }
+    let page = DictionaryPage::try_new(src)?;
+    let entries = page
+        .entries
+        .iter()
+        .map(|e| std::str::from_utf8(e))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut builder = StringBuilder::new();
+    let mut i = 0;
+    for is_valid in bit_set.iter() {
+        if is_valid {
+            builder.append_value(entries[page.index(i)?]);
+            i += 1;
+        } else {
+            builder.append_null();
+        }
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Decodes the page into a `DictionaryArray`, the keys are the indices stored in the page,
+/// so the comparisons on the array only need to be evaluated on the dictionary entries.
+pub fn str_dictionary_decode_to_dictionary_array(
+    src: &[u8],
+    bit_set: &NullBuffer,
+) -> Result<ArrayRef, CodecError> {
+    if src.is_empty() {
+        let keys = Int32Array::from(vec![None; bit_set.len()]);
+        let array = DictionaryArray::<Int32Type>::try_new(
+            keys,
+            Arc::new(StringArray::from(Vec::<String>::new())),
+        )?;
+        return Ok(Arc::new(array));
+    }
+    let page = DictionaryPage::try_new(src)?;
+    let entries = page
+        .entries
+        .iter()
+        .map(|e| std::str::from_utf8(e))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut keys = Vec::with_capacity(bit_set.len());
+    let mut i = 0;
+    for is_valid in bit_set.iter() {
+        if is_valid {
+            keys.push(Some(page.index(i)? as i32));
+            i += 1;
+        } else {
+            keys.push(None);
+        }
+    }
+    let array = DictionaryArray::<Int32Type>::try_new(
+        Int32Array::from(keys),
+        Arc::new(StringArray::from(entries)),
+    )?;
+    Ok(Arc::new(array))
+}
+
 #[cfg(test)]
 mod tests {
     use arrow::buffer::BooleanBuffer;
@@ -495,6 +690,8 @@ mod tests {
         assert_eq!(dst.to_vec().len(), 0);
         str_without_compress_encode(&src, &mut dst).unwrap();
         assert_eq!(dst.to_vec().len(), 0);
+        str_dictionary_encode(&src, &mut dst).unwrap();
+        assert_eq!(dst.to_vec().len(), 0);
 
         // verify encoded no values.
     }
@@ -752,4 +949,78 @@ mod tests {
         let array = array_ref.as_any().downcast_ref::<StringArray>().unwrap();
         assert_eq!(*array, expected);
     }
+
+    #[test]
+    fn test_dictionary_encode_decode() {
+        let status = [
+            "ok", "warn", "error", "ok", "ok", "unknown", "warn", "ok", "ok",
+        ];
+        let data: Vec<&[u8]> = status.iter().map(|s| s.as_bytes()).collect();
+        let mut dst = vec![];
+        str_dictionary_encode(&data, &mut dst).unwrap();
+        assert_eq!(dst[0], Encoding::Dictionary as u8);
+
+        let mut got = vec![];
+        str_dictionary_decode(&dst, &mut got).unwrap();
+        let data_exp: Vec<MiniVec<u8>> = data.iter().map(|s| MiniVec::from(*s)).collect();
+        assert_eq!(data_exp, got);
+
+        // The null values are not encoded.
+        let mut validity = vec![true; status.len() + 2];
+        validity[1] = false;
+        validity[5] = false;
+        let null_bitset = NullBuffer::new(BooleanBuffer::from(validity.clone()));
+        let mut expected = status.iter().map(|s| Some(*s)).collect::<Vec<_>>();
+        expected.insert(1, None);
+        expected.insert(5, None);
+
+        let array_ref = str_dictionary_decode_to_array(&dst, &null_bitset).unwrap();
+        let array = array_ref.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(*array, StringArray::from(expected.clone()));
+
+        let array_ref = str_dictionary_decode_to_dictionary_array(&dst, &null_bitset).unwrap();
+        let array = array_ref
+            .as_any()
+            .downcast_ref::<DictionaryArray<Int32Type>>()
+            .unwrap();
+        assert_eq!(array.values().len(), 4);
+        assert_eq!(
+            array.keys(),
+            &Int32Array::from(vec![
+                Some(0),
+                None,
+                Some(1),
+                Some(2),
+                Some(0),
+                None,
+                Some(0),
+                Some(3),
+                Some(1),
+                Some(0),
+                Some(0)
+            ])
+        );
+        let array_ref =
+            arrow::compute::cast(&array_ref, &arrow::datatypes::DataType::Utf8).unwrap();
+        let array = array_ref.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(*array, StringArray::from(expected));
+    }
+
+    #[test]
+    fn test_dictionary_bit_width() {
+        assert_eq!(dictionary_bit_width(1), 0);
+        assert_eq!(dictionary_bit_width(2), 1);
+        assert_eq!(dictionary_bit_width(4), 2);
+        assert_eq!(dictionary_bit_width(5), 3);
+        assert_eq!(dictionary_bit_width(256), 8);
+
+        // A single distinct value is stored without indices.
+        let data: Vec<&[u8]> = vec![&b"ok"[..]; 100];
+        let mut dst = vec![];
+        str_dictionary_encode(&data, &mut dst).unwrap();
+        assert_eq!(dst.len(), 1 + 1 + 1 + 1 + 2 + 1);
+        let mut got = vec![];
+        str_dictionary_decode(&dst, &mut got).unwrap();
+        assert_eq!(got, vec![MiniVec::from(&b"ok"[..]); 100]);
+    }
 }
@@ -6,13 +6,15 @@ use arrow_array::{
 use arrow_schema::{DataType, TimeUnit};
 use models::column_data::PrimaryColumnData;
 use models::column_data_ref::PrimaryColumnDataRef;
+use models::gis::bounding_box::BoundingBox;
 use models::schema::tskv_table_schema::{ColumnType, TableColumn};
+use models::ValueType;
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 use utils::bitset::{BitSet, ImmutBitSet, NullBitset};
 
 use super::mutable_column_ref::MutableColumnRef;
-use super::statistics::ValueStatistics;
+use super::statistics::{GeometryStatistics, ValueStatistics};
 use crate::byte_utils::{decode_be_u32, decode_be_u64};
 use crate::error::{
     EncodeSnafu, TskvResult, TsmPageFileHashCheckFailedSnafu, TsmPageSnafu,
@@ -283,12 +285,16 @@ impl Page {
                 encoder
                     .encode(&target_column, &mut buf)
                     .context(EncodeSnafu)?;
-                PageStatistics::Bytes(ValueStatistics::new(
-                    min,
-                    max,
-                    None,
-                    (array.len() - target_column.len()) as u64,
-                ))
+                PageStatistics::bytes(
+                    &table_column,
+                    ValueStatistics::new(
+                        min,
+                        max,
+                        None,
+                        (array.len() - target_column.len()) as u64,
+                    ),
+                    &target_column,
+                )
             }
             _ => {
                 return Err(UnsupportedDataTypeSnafu {
@@ -416,12 +422,16 @@ impl Page {
                     .encode(&target_array, &mut buf)
                     .context(EncodeSnafu)?;
 
-                PageStatistics::Bytes(ValueStatistics::new(
-                    Some(min.as_bytes().to_vec()),
-                    Some(max.as_bytes().to_vec()),
-                    None,
-                    null_count,
-                ))
+                PageStatistics::bytes(
+                    column.column_desc(),
+                    ValueStatistics::new(
+                        Some(min.as_bytes().to_vec()),
+                        Some(max.as_bytes().to_vec()),
+                        None,
+                        null_count,
+                    ),
+                    &target_array,
+                )
             }
             PrimaryColumnData::Bool(array, min, max) => {
                 let target_array = array
@@ -527,12 +537,16 @@ impl Page {
             PrimaryColumnDataRef::String(values, min, max) => {
                 let encoder = get_str_codec(table_column.encoding());
                 encoder.encode(&values, &mut buffer).context(EncodeSnafu)?;
-                PageStatistics::Bytes(ValueStatistics::new(
-                    Some(min.to_vec()),
-                    Some(max.to_vec()),
-                    None,
-                    column_data_len - values.len() as u64,
-                ))
+                PageStatistics::bytes(
+                    &table_column,
+                    ValueStatistics::new(
+                        Some(min.to_vec()),
+                        Some(max.to_vec()),
+                        None,
+                        column_data_len - values.len() as u64,
+                    ),
+                    &values,
+                )
             }
         };
 
@@ -573,6 +587,40 @@ pub enum PageStatistics {
     I64(ValueStatistics<i64>),
     U64(ValueStatistics<u64>),
     Bytes(ValueStatistics<Vec<u8>>),
+    /// Statistics of the geometry column, with the bounding box of the geometries in the page.
+    Geometry(GeometryStatistics),
+}
+
+impl PageStatistics {
+    /// Statistics of the string values, the bounding box of the values is also computed
+    /// if the column is a geometry column.
+    fn bytes(
+        column: &TableColumn,
+        statistics: ValueStatistics<Vec<u8>>,
+        values: &[&[u8]],
+    ) -> PageStatistics {
+        if !matches!(
+            column.column_type,
+            ColumnType::Field(ValueType::Geometry(_))
+        ) {
+            return PageStatistics::Bytes(statistics);
+        }
+
+        let bbox = values
+            .iter()
+            .map(|v| std::str::from_utf8(v).ok())
+            .collect::<Option<Vec<_>>>()
+            .and_then(|wkts| BoundingBox::from_wkts(wkts.into_iter().map(Some)));
+        PageStatistics::Geometry(GeometryStatistics::new(statistics, bbox))
+    }
+
+    /// The bounding box of the geometries in the page, `None` if it's unknown.
+    pub fn bounding_box(&self) -> Option<&BoundingBox> {
+        match self {
+            PageStatistics::Geometry(statistics) => statistics.bounding_box(),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -603,7 +651,12 @@ impl PageWriteSpec {
 
 #[cfg(test)]
 mod test {
+    use std::sync::Arc;
+
     use arrow::datatypes::ToByteSlice;
+    use arrow_array::{ArrayRef, StringArray};
+    use models::gis::bounding_box::BoundingBox;
+    use models::gis::data_type::{Geometry, GeometryType};
     use models::schema::tskv_table_schema::{ColumnType, TableColumn};
     use models::ValueType;
     use utils::bitset::BitSet;
@@ -651,4 +704,43 @@ mod test {
         let result = page.crc_validation();
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_geometry_page_statistics() {
+        let geo_column = TableColumn::new(
+            1,
+            "loc".to_string(),
+            ColumnType::Field(ValueType::Geometry(Geometry::new_with_srid(
+                GeometryType::Point,
+                0,
+            ))),
+            Default::default(),
+        );
+        let array: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("POINT(1 2)"),
+            None,
+            Some("POINT(3 -1)"),
+        ]));
+        let page = Page::arrow_array_to_page(array, geo_column.clone()).unwrap();
+        assert_eq!(
+            page.meta().statistics.bounding_box(),
+            Some(&BoundingBox::new(1.0, -1.0, 3.0, 2.0))
+        );
+
+        let array: ArrayRef = Arc::new(StringArray::from(vec![Some("POINT(1 2)"), Some("bad")]));
+        let page = Page::arrow_array_to_page(array.clone(), geo_column).unwrap();
+        assert!(matches!(
+            page.meta().statistics,
+            PageStatistics::Geometry(ref statistics) if statistics.bounding_box().is_none()
+        ));
+
+        let string_column = TableColumn::new(
+            2,
+            "str".to_string(),
+            ColumnType::Field(ValueType::String),
+            Default::default(),
+        );
+        let page = Page::arrow_array_to_page(array, string_column).unwrap();
+        assert!(matches!(page.meta().statistics, PageStatistics::Bytes(_)));
+    }
 }
@@ -1,6 +1,6 @@
 use std::collections::BTreeMap;
 use std::fmt::{Debug, Formatter};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use arrow::array::ArrayData;
@@ -11,7 +11,7 @@ use arrow_array::types::{
     TimestampSecondType,
 };
 use arrow_array::{
-    Array, ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray,
+    make_array, Array, ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray,
     TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
     TimestampSecondArray, UInt64Array,
 };
@@ -27,12 +27,13 @@ use utils::bitset::{BitSet, NullBitset};
 use crate::error::{ArrowSnafu, CommonSnafu, DecodeSnafu, ReadTsmSnafu, TskvResult, TsmPageSnafu};
 use crate::file_system::async_filesystem::{LocalFileSystem, LocalFileType};
 use crate::file_system::file::stream_reader::FileStreamReader;
+use crate::file_system::remote_filesystem::RemoteFileSystem;
 use crate::file_system::FileSystem;
 use crate::tsm::chunk::Chunk;
 use crate::tsm::chunk_group::{ChunkGroup, ChunkGroupMeta};
 use crate::tsm::codec::{
     get_bool_codec, get_encoding, get_f64_codec, get_i64_codec, get_str_codec, get_ts_codec,
-    get_u64_codec,
+    get_u64_codec, str_dictionary_decode_to_dictionary_array,
 };
 use crate::tsm::footer::Footer;
 use crate::tsm::page::{Page, PageMeta, PageStatistics, PageWriteSpec};
@@ -115,7 +116,24 @@ impl TsmReader {
             .open_file_reader(&path)
             .await
             .map_err(|e| TskvError::FileSystemError { source: e })?;
+        Self::open_with_reader(path, reader).await
+    }
+
+    /// Open a tsm file that was offloaded to the remote file system,
+    /// the tombstone of the file is still in the local directory.
+    pub async fn open_remote(
+        tsm_path: impl AsRef<Path>,
+        remote: &RemoteFileSystem,
+    ) -> TskvResult<Self> {
+        let path = tsm_path.as_ref().to_path_buf();
+        let reader = remote
+            .open_file_reader(&path)
+            .await
+            .map_err(|e| TskvError::FileSystemError { source: e })?;
+        Self::open_with_reader(path, reader).await
+    }
 
+    async fn open_with_reader(path: PathBuf, reader: Box<FileStreamReader>) -> TskvResult<Self> {
         let file_id = file_utils::get_tsm_file_id_by_path(&path)?;
 
         let footer = Arc::new(read_footer(&reader).await?);
@@ -553,6 +571,7 @@ pub async fn page_to_arrow_array_with_tomb(
     series_id: SeriesId,
     time_page_meta: Arc<PageWriteSpec>,
     time_range: TimeRange,
+    keep_dictionary: bool,
 ) -> TskvResult<ArrayRef> {
     let tombstone = reader.tombstone();
     let null_bitset = if !page.meta.column.column_type.is_time()
@@ -573,7 +592,11 @@ pub async fn page_to_arrow_array_with_tomb(
         NullBitset::Ref(page.null_bitset())
     };
 
-    data_buf_to_arrow_array(&page, null_bitset)
+    if keep_dictionary {
+        data_buf_to_dictionary_array(&page, null_bitset)
+    } else {
+        data_buf_to_arrow_array(&page, null_bitset)
+    }
 }
 
 fn update_nullbits_by_tombstone(
@@ -714,6 +737,30 @@ pub fn data_buf_to_arrow_array(page: &Page, null_bitset: NullBitset) -> TskvResu
     updated_nullbuffer(array, &page_null_buffer, &null_buffer)
 }
 
+/// Same as `data_buf_to_arrow_array`, except that the dictionary encoded string pages
+/// are read into `DictionaryArray` instead of `StringArray`.
+pub fn data_buf_to_dictionary_array(page: &Page, null_bitset: NullBitset) -> TskvResult<ArrayRef> {
+    let data_buffer = page.data_buffer();
+    let is_string = matches!(
+        page.meta().column.column_type.to_physical_type(),
+        PhysicalCType::Field(PhysicalDType::String) | PhysicalCType::Tag
+    );
+    if !is_string || get_encoding(data_buffer) != Encoding::Dictionary {
+        return data_buf_to_arrow_array(page, null_bitset);
+    }
+
+    let num_values = page.meta.num_values as usize;
+    let buffer = Buffer::from_vec(null_bitset.null_bitset_slice());
+    let null_buffer = NullBuffer::new(BooleanBuffer::new(buffer, 0, num_values));
+
+    let page_buffer = Buffer::from_vec(page.null_bitset().bytes().to_vec());
+    let page_null_buffer = NullBuffer::new(BooleanBuffer::new(page_buffer, 0, num_values));
+
+    let array = str_dictionary_decode_to_dictionary_array(data_buffer, &page_null_buffer)
+        .context(DecodeSnafu)?;
+    updated_nullbuffer(array, &page_null_buffer, &null_buffer)
+}
+
 fn decode_to_arrow_timestamp(
     data: &[u8],
     encoding: Encoding,
@@ -788,6 +835,13 @@ fn updated_nullbuffer(
             .null_bit_buffer(Some(nulls))
             .build()
             .map(|d| Arc::new(StringArray::from(d)) as ArrayRef),
+        DataType::Dictionary(_, _) => ArrayData::builder(data_type.clone())
+            .len(data.len())
+            .buffers(data.buffers().to_vec())
+            .child_data(data.child_data().to_vec())
+            .null_bit_buffer(Some(nulls))
+            .build()
+            .map(make_array),
         DataType::Timestamp(time_unit, _) => ArrayData::builder(data_type.clone())
             .len(data.len())
             .buffers(data.buffers().to_vec())
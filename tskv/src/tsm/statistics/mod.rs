@@ -1,3 +1,4 @@
+use models::gis::bounding_box::BoundingBox;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,3 +40,27 @@ impl<T> ValueStatistics<T> {
         self.null_count
     }
 }
+
+/// Statistics of a geometry column, which extends the [`ValueStatistics`] of the WKT
+/// strings with the bounding box of the geometries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeometryStatistics {
+    value: ValueStatistics<Vec<u8>>,
+    bbox: Option<BoundingBox>,
+}
+
+impl GeometryStatistics {
+    pub fn new(value: ValueStatistics<Vec<u8>>, bbox: Option<BoundingBox>) -> Self {
+        Self { value, bbox }
+    }
+
+    pub fn value(&self) -> &ValueStatistics<Vec<u8>> {
+        &self.value
+    }
+
+    /// The bounding box of the geometries, `None` if it's unknown, e.g. there are
+    /// invalid geometries.
+    pub fn bounding_box(&self) -> Option<&BoundingBox> {
+        self.bbox.as_ref()
+    }
+}
@@ -0,0 +1,476 @@
+//! Archive of the WAL files for the point-in-time recovery.
+//!
+//! If `wal.archive_url` is set, the WAL files of a vnode are uploaded to the object store
+//! when they are rolled, truncated, closed, or before they are deleted, a file is addressed
+//! by its path in the WAL directory, so the archive of a vnode can be read by any node.
+//!
+//! The archive also records a time that all the raft logs proposed before it are archived,
+//! it's updated when the vnode is closed or its current WAL file is archived on request.
+//! [`ArchivedWal`] refuses to replay the logs until a time that the archive doesn't reach.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use openraft::EntryPayload;
+use protos::kv_service::RaftWriteCommand;
+use protos::models_helper::parse_prost_bytes;
+use snafu::IntoError;
+use tokio::sync::{mpsc, oneshot};
+use trace::{error, info};
+
+use super::reader::WalReader;
+use crate::error::{CommonSnafu, DecodeSnafu};
+use crate::file_system::error::{FileSystemError, FileSystemResult};
+use crate::file_system::remote_filesystem::RemoteFileSystem;
+use crate::vnode_store::VnodeStorage;
+use crate::{file_utils, TskvError, TskvResult};
+
+/// The file in the archive of a vnode that records the time that all the raft logs
+/// proposed before it are archived.
+const ARCHIVED_UNTIL_FILE_NAME: &str = "archived_until";
+
+struct ArchiveRequest {
+    path: PathBuf,
+    /// If set, all the raft logs proposed before this time are in the file or the files
+    /// requested before, it's recorded after they are archived.
+    archived_until: Option<i64>,
+    callback: Option<oneshot::Sender<FileSystemResult<()>>>,
+}
+
+pub struct WalArchiver {
+    sender: mpsc::UnboundedSender<ArchiveRequest>,
+}
+
+impl WalArchiver {
+    /// Starts an archiver of the WAL files of a vnode in the current tokio runtime,
+    /// the files are uploaded in the order of the requests.
+    pub fn start(store: Arc<RemoteFileSystem>) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(Self::run(store, receiver));
+        Self { sender }
+    }
+
+    /// Uploads the WAL file to the archive in background.
+    pub fn archive(&self, path: PathBuf) {
+        let _ = self.sender.send(ArchiveRequest {
+            path,
+            archived_until: None,
+            callback: None,
+        });
+    }
+
+    /// Waits until the WAL file and the files requested before are archived, or failed to.
+    pub async fn archive_and_wait(&self, path: PathBuf) {
+        let _ = self.archive_until(path, None).await;
+    }
+
+    /// Uploads the WAL file, then records that all the raft logs proposed before
+    /// `archived_until` are archived, they must have been written to the file or the
+    /// files requested before. The returned future completes when they are archived.
+    pub fn archive_until(
+        &self,
+        path: PathBuf,
+        archived_until: Option<i64>,
+    ) -> impl Future<Output = TskvResult<()>> + Send + 'static {
+        let (callback, receiver) = oneshot::channel();
+        let _ = self.sender.send(ArchiveRequest {
+            path,
+            archived_until,
+            callback: Some(callback),
+        });
+        async move {
+            match receiver.await {
+                Ok(result) => result.map_err(|e| TskvError::FileSystemError { source: e }),
+                Err(_) => Err(CommonSnafu {
+                    reason: "wal archiver is stopped".to_string(),
+                }
+                .build()),
+            }
+        }
+    }
+
+    async fn run(
+        store: Arc<RemoteFileSystem>,
+        mut receiver: mpsc::UnboundedReceiver<ArchiveRequest>,
+    ) {
+        // The files failed to upload are uploaded again before recording an archived time.
+        let mut failed = BTreeSet::new();
+        while let Some(request) = receiver.recv().await {
+            let mut result = Self::upload(&store, &request.path, &mut failed).await;
+            if let (Ok(()), Some(archived_until)) = (&result, request.archived_until) {
+                result =
+                    Self::record_archived_until(&store, &request.path, archived_until, &mut failed)
+                        .await;
+            }
+            if let Some(callback) = request.callback {
+                let _ = callback.send(result);
+            }
+        }
+    }
+
+    async fn upload(
+        store: &RemoteFileSystem,
+        path: &Path,
+        failed: &mut BTreeSet<PathBuf>,
+    ) -> FileSystemResult<()> {
+        match store.upload(path).await {
+            Ok(size) => {
+                info!("Archived wal '{}', {size} bytes", path.display());
+                failed.remove(path);
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to archive wal '{}': {e}", path.display());
+                failed.insert(path.to_path_buf());
+                Err(e)
+            }
+        }
+    }
+
+    async fn record_archived_until(
+        store: &RemoteFileSystem,
+        path: &Path,
+        archived_until: i64,
+        failed: &mut BTreeSet<PathBuf>,
+    ) -> FileSystemResult<()> {
+        for failed_path in failed.clone() {
+            Self::upload(store, &failed_path, failed).await?;
+        }
+        let data = Bytes::from(archived_until.to_string());
+        store
+            .put(path.with_file_name(ARCHIVED_UNTIL_FILE_NAME), data)
+            .await
+    }
+}
+
+/// The normal raft logs of a vnode in the archive, that are going to be replayed.
+pub struct ArchivedWal {
+    /// The local directory that the archived WAL files are downloaded to.
+    dir: PathBuf,
+    compress: String,
+    last_index: u64,
+    /// WAL file ids and positions of the logs in the order of the log indexes.
+    logs: Vec<(u64, u64)>,
+}
+
+impl ArchivedWal {
+    /// Downloads the archived WAL files of the vnode whose WAL directory is `wal_dir` to
+    /// `download_dir`, and finds the logs after `last_index` until the first one proposed
+    /// after `until`. Returns error if the archive is missing or doesn't reach `until`.
+    pub async fn load(
+        store: &RemoteFileSystem,
+        wal_dir: &Path,
+        download_dir: &Path,
+        compress: &str,
+        last_index: u64,
+        until: i64,
+    ) -> TskvResult<Self> {
+        let fs_error = |e: FileSystemError| TskvError::FileSystemError { source: e };
+        let mut wal_files = store
+            .list(wal_dir)
+            .await
+            .map_err(fs_error)?
+            .iter()
+            .filter_map(|path| path.file_name())
+            .filter_map(|name| file_utils::get_wal_file_id(&name.to_string_lossy()).ok())
+            .collect::<Vec<_>>();
+        if wal_files.is_empty() {
+            return Err(CommonSnafu {
+                reason: format!("no wal of '{}' is archived", wal_dir.display()),
+            }
+            .build());
+        }
+        wal_files.sort_unstable();
+        let archived_until = match store
+            .get(wal_dir.join(ARCHIVED_UNTIL_FILE_NAME))
+            .await
+            .map_err(fs_error)?
+        {
+            Some(data) => Some(
+                String::from_utf8_lossy(&data)
+                    .parse::<i64>()
+                    .map_err(|e| DecodeSnafu.into_error(Box::new(e)))?,
+            ),
+            None => None,
+        };
+
+        // A log may be in several files if it was truncated and appended again,
+        // the one in the latest file is valid.
+        let _ = tokio::fs::remove_dir_all(download_dir).await;
+        let mut locations = BTreeMap::new();
+        for wal_id in wal_files {
+            let path = file_utils::make_wal_file(download_dir, wal_id);
+            store
+                .download(file_utils::make_wal_file(wal_dir, wal_id), &path)
+                .await
+                .map_err(fs_error)?;
+            let mut reader = WalReader::open(&path, compress.to_string()).await?;
+            loop {
+                match reader.next_wal_entry().await {
+                    Ok(Some(record)) => {
+                        let log_id = record.block.log_id;
+                        if log_id.index <= last_index {
+                            continue;
+                        }
+                        let create_time = match record.block.payload {
+                            EntryPayload::Normal(ref req) => Some(
+                                parse_prost_bytes::<RaftWriteCommand>(req)
+                                    .map_err(|e| DecodeSnafu.into_error(Box::new(e)))?
+                                    .create_time,
+                            ),
+                            _ => None,
+                        };
+                        let location = (wal_id, record.pos, log_id.leader_id.term, create_time);
+                        locations.insert(log_id.index, location);
+                    }
+                    Ok(None) | Err(TskvError::Eof) => break,
+                    Err(TskvError::WalTruncated { .. }) => {
+                        info!("Archived wal '{}' is truncated", path.display());
+                        break;
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        let (mut logs, mut expected_index, mut last_term) = (vec![], last_index + 1, 0);
+        let mut reached = false;
+        for (index, (wal_id, pos, term, create_time)) in locations {
+            if index != expected_index {
+                return Err(CommonSnafu {
+                    reason: format!(
+                        "raft log {expected_index} of '{}' is not archived",
+                        wal_dir.display()
+                    ),
+                }
+                .build());
+            }
+            // Logs of an older term after a newer one were truncated, but not appended again.
+            if term < last_term {
+                break;
+            }
+            (expected_index, last_term) = (index + 1, term);
+            match create_time {
+                Some(create_time) if create_time > until => {
+                    reached = true;
+                    break;
+                }
+                Some(_) => logs.push((wal_id, pos)),
+                None => {}
+            }
+        }
+        if !reached && archived_until.map_or(true, |time| time < until) {
+            return Err(CommonSnafu {
+                reason: format!(
+                    "the archived wal of '{}' doesn't reach {until}, archived until: {archived_until:?}",
+                    wal_dir.display()
+                ),
+            }
+            .build());
+        }
+
+        Ok(Self {
+            dir: download_dir.to_path_buf(),
+            compress: compress.to_string(),
+            last_index,
+            logs,
+        })
+    }
+
+    /// Applies the logs to the vnode, the applied data is flushed.
+    /// Returns the number of the applied logs.
+    pub async fn replay(&self, vnode: &VnodeStorage) -> TskvResult<u64> {
+        // The replayed data is written with the sequence of the vnode, so that the raft logs
+        // of the vnode are not hidden by them.
+        let (vnode_id, last_seq) = {
+            let ts_family = vnode.ts_family();
+            let ts_family = ts_family.read().await;
+            (ts_family.tf_id(), ts_family.last_seq())
+        };
+        let ctx = replication::ApplyContext {
+            index: last_seq,
+            raft_id: vnode_id as u64,
+            apply_type: replication::APPLY_TYPE_WAL,
+        };
+
+        let mut applied = 0;
+        let mut current_reader: Option<(u64, WalReader)> = None;
+        for &(wal_id, pos) in self.logs.iter() {
+            // The logs are mostly in the order of the files, a file is opened once.
+            let opened = match current_reader.take() {
+                Some((id, reader)) if id == wal_id => reader,
+                _ => {
+                    let path = file_utils::make_wal_file(&self.dir, wal_id);
+                    WalReader::open(&path, self.compress.clone()).await?
+                }
+            };
+            let reader = &mut current_reader.insert((wal_id, opened)).1;
+            let Some(record) = reader.read_wal_record_data(pos).await? else {
+                break;
+            };
+            let EntryPayload::Normal(ref req) = record.block.payload else {
+                continue;
+            };
+            let request = parse_prost_bytes::<RaftWriteCommand>(req)
+                .map_err(|e| DecodeSnafu.into_error(Box::new(e)))?;
+            if let Some(command) = request.command {
+                vnode.apply(&ctx, command).await?;
+                applied += 1;
+            }
+        }
+
+        vnode.flush(true, true, false).await?;
+        info!(
+            "Replayed {applied} archived raft logs after {} to vnode {vnode_id}",
+            self.last_index
+        );
+
+        Ok(applied)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use config::tskv::TieredStorageConfig;
+    use models::schema::database_schema::make_owner;
+    use openraft::EntryPayload;
+    use protos::kv_service::{raft_write_command, RaftWriteCommand, WriteDataRequest};
+    use protos::models_helper::to_prost_bytes;
+    use replication::EntryStorage;
+
+    use super::{ArchivedWal, WalArchiver};
+    use crate::file_system::remote_filesystem::RemoteFileSystem;
+    use crate::kv_option::WalOptions;
+    use crate::wal::wal_store::{RaftEntry, RaftEntryStorage};
+    use crate::wal::VnodeWal;
+
+    #[tokio::test]
+    async fn test_archive_wal() {
+        let dir = PathBuf::from("/tmp/test/wal/archiver");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("wal")).unwrap();
+        let config = TieredStorageConfig {
+            enable: true,
+            url: format!("file://{}", dir.join("archive").display()),
+            ..Default::default()
+        };
+        let store = Arc::new(RemoteFileSystem::new(dir.join("wal"), &config).unwrap());
+
+        let wal = dir.join("wal").join("_000001.wal");
+        std::fs::write(&wal, b"12345").unwrap();
+        let archiver = WalArchiver::start(store.clone());
+        archiver.archive(wal.clone());
+        std::fs::write(dir.join("wal").join("_000002.wal"), b"").unwrap();
+        archiver
+            .archive_and_wait(dir.join("wal").join("_000002.wal"))
+            .await;
+        let archived = dir.join("archive").join("_000001.wal");
+        assert_eq!(std::fs::read(&archived).unwrap(), b"12345");
+        assert!(dir.join("archive").join("_000002.wal").exists());
+        assert!(!dir.join("archive").join("archived_until").exists());
+
+        // A truncated file is archived again.
+        std::fs::write(&wal, b"123").unwrap();
+        archiver.archive_and_wait(wal.clone()).await;
+        assert_eq!(std::fs::read(&archived).unwrap(), b"123");
+
+        archiver
+            .archive_until(wal.clone(), Some(100))
+            .await
+            .unwrap();
+        let archived_until = std::fs::read(dir.join("archive").join("archived_until")).unwrap();
+        assert_eq!(archived_until, b"100");
+
+        // The archived time is not recorded if a file failed to be archived.
+        let missing = dir.join("wal").join("_000003.wal");
+        archiver.archive_and_wait(missing.clone()).await;
+        assert!(archiver
+            .archive_until(wal.clone(), Some(200))
+            .await
+            .is_err());
+        std::fs::write(&missing, b"").unwrap();
+        archiver.archive_until(wal, Some(200)).await.unwrap();
+        let archived_until = std::fs::read(dir.join("archive").join("archived_until")).unwrap();
+        assert_eq!(archived_until, b"200");
+    }
+
+    fn write_entry(index: u64, create_time: i64) -> RaftEntry {
+        let command = RaftWriteCommand {
+            create_time,
+            command: Some(raft_write_command::Command::WriteData(
+                WriteDataRequest::default(),
+            )),
+            ..Default::default()
+        };
+        let mut entry = RaftEntry::default();
+        entry.log_id.index = index;
+        entry.payload = EntryPayload::Normal(to_prost_bytes(&command));
+        entry
+    }
+
+    #[tokio::test]
+    async fn test_load_archived_wal() {
+        let dir = PathBuf::from("/tmp/test/wal/archived_wal");
+        let _ = std::fs::remove_dir_all(&dir);
+        let config = TieredStorageConfig {
+            enable: true,
+            url: format!("file://{}", dir.join("archive").display()),
+            ..Default::default()
+        };
+        let wal_options = Arc::new(WalOptions {
+            path: dir.join("wal"),
+            // The entries are written to several files.
+            wal_max_file_size: 256,
+            compress: "zstd".to_string(),
+            wal_sync: false,
+            sync_interval: Duration::ZERO,
+            sync_max_size: 0,
+            archive: Some(config),
+        });
+        let owner = Arc::new(make_owner("cnosdb", "test_db"));
+        let wal_dir = wal_options.wal_dir(&owner, 1);
+        let download_dir = dir.join("download");
+        let store = wal_options.archive_store().unwrap().unwrap();
+        let load = |last_index, until| {
+            ArchivedWal::load(&store, &wal_dir, &download_dir, "zstd", last_index, until)
+        };
+
+        // Nothing is archived.
+        assert!(load(0, 100).await.is_err());
+
+        let wal = VnodeWal::new(wal_options.clone(), owner.clone(), 1, None)
+            .await
+            .unwrap();
+        let mut storage = RaftEntryStorage::new(wal);
+        for i in 1..=10 {
+            storage
+                .append(&[write_entry(i, i as i64 * 10)])
+                .await
+                .unwrap();
+        }
+        storage.archive().await.unwrap().await.unwrap();
+
+        // Stops before the first log proposed after `until`.
+        assert_eq!(load(0, 55).await.unwrap().logs.len(), 5);
+        assert_eq!(load(3, 55).await.unwrap().logs.len(), 2);
+        // All the logs proposed before the current file was archived are archived.
+        assert_eq!(load(0, 1000).await.unwrap().logs.len(), 10);
+        assert!(load(0, i64::MAX).await.is_err());
+
+        // Some of the logs are not archived.
+        let first_file = dir
+            .join("archive")
+            .join(owner.as_str())
+            .join("1")
+            .join("_000001.wal");
+        std::fs::remove_file(first_file).unwrap();
+        assert!(load(0, 1000).await.is_err());
+    }
+}
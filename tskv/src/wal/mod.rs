@@ -35,11 +35,14 @@
 //! +------------+---------------+--------------+--------------+
 //! ```
 
+pub mod archiver;
 mod reader;
+pub mod syncer;
 pub mod wal_store;
 pub mod writer;
 
 use std::fmt::Display;
+use std::future::Future;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
@@ -47,15 +50,18 @@ use std::sync::Arc;
 use minivec::MiniVec;
 use models::codec::Encoding;
 use models::meta_data::VnodeId;
+use models::utils::now_timestamp_nanos;
 use snafu::{IntoError, OptionExt, ResultExt};
 
+use self::archiver::WalArchiver;
 use self::reader::WalReader;
+use self::syncer::WalSyncerRef;
 use self::writer::WalWriter;
 use crate::error::{CommonSnafu, DecodeSnafu, EncodeSnafu};
 use crate::kv_option::WalOptions;
 use crate::tsm::codec::{get_str_codec, StringCodec};
 pub use crate::wal::reader::print_wal_statistics;
-use crate::{error, file_utils, TskvResult};
+use crate::{error, file_utils, TskvError, TskvResult};
 
 /// 9 = type(1) + sequence(8)
 const WAL_HEADER_LEN: usize = 9;
@@ -100,6 +106,12 @@ pub struct VnodeWal {
     owner: Arc<String>,
     vnode_id: VnodeId,
     current_wal: WalWriter,
+    /// Syncs the appended entries if the group commit is enabled.
+    syncer: Option<WalSyncerRef>,
+    /// Size of the entries appended to the current wal and not synced by the syncer yet.
+    unsynced_size: u64,
+    /// Archives the wal files if `wal.archive_url` is set.
+    archiver: Option<WalArchiver>,
 }
 
 impl VnodeWal {
@@ -107,15 +119,24 @@ impl VnodeWal {
         config: Arc<WalOptions>,
         owner: Arc<String>,
         vnode_id: VnodeId,
+        syncer: Option<WalSyncerRef>,
     ) -> TskvResult<Self> {
         let wal_dir = config.wal_dir(&owner, vnode_id);
         let writer_file = Self::open_writer(config.clone(), &wal_dir).await?;
+        let syncer = syncer.filter(|_| config.group_commit());
+        let archiver = config
+            .archive_store()
+            .map_err(|e| TskvError::FileSystemError { source: e })?
+            .map(WalArchiver::start);
         Ok(Self {
             config,
             wal_dir,
             owner,
             vnode_id,
             current_wal: writer_file,
+            syncer,
+            unsynced_size: 0,
+            archiver,
         })
     }
 
@@ -143,7 +164,12 @@ impl VnodeWal {
             let new_file = WalWriter::open(self.config.clone(), new_file_id, new_file_name).await?;
 
             let mut old_file = std::mem::replace(&mut self.current_wal, new_file);
+            // The entries appended to the old file are synced when closing.
             old_file.close().await?;
+            self.unsynced_size = 0;
+            if let Some(archiver) = &self.archiver {
+                archiver.archive(old_file.path());
+            }
         }
         Ok(())
     }
@@ -159,6 +185,9 @@ impl VnodeWal {
         let mut new_file = WalWriter::open(self.config.clone(), file_id, file_name).await?;
         new_file.truncate(pos).await;
         new_file.sync().await?;
+        if let Some(archiver) = &self.archiver {
+            archiver.archive(new_file.path());
+        }
 
         Ok(())
     }
@@ -166,6 +195,9 @@ impl VnodeWal {
     pub async fn rollback_wal_writer(&mut self, del_ids: &[u64]) -> TskvResult<()> {
         for wal_id in del_ids {
             let file_path = file_utils::make_wal_file(self.wal_dir(), *wal_id);
+            if let Some(archiver) = &self.archiver {
+                archiver.archive_and_wait(file_path.clone()).await;
+            }
             trace::info!("Removing wal file '{}'", file_path.display());
             if let Err(e) = tokio::fs::remove_file(&file_path).await {
                 trace::error!("Failed to remove file '{}': {:?}", file_path.display(), e);
@@ -188,11 +220,37 @@ impl VnodeWal {
 
         let wal_id = self.current_wal_id();
         let pos = self.current_wal_size();
-        self.current_wal.append_raft_entry(raft_entry).await?;
+        let written_size = self.current_wal.append_raft_entry(raft_entry).await?;
+        if self.syncer.is_some() {
+            self.unsynced_size += written_size as u64;
+        }
 
         Ok((wal_id, pos))
     }
 
+    /// Waits until the appended entries are synced by the group commit,
+    /// does nothing if the group commit is disabled.
+    pub async fn wait_synced(&mut self) -> TskvResult<()> {
+        let Some(syncer) = self.syncer.as_ref() else {
+            return Ok(());
+        };
+        if self.unsynced_size == 0 {
+            return Ok(());
+        }
+
+        // The syncer only syncs the file, so the buffered entries are written to it first.
+        self.current_wal.flush_buffer().await?;
+        let size = std::mem::take(&mut self.unsynced_size);
+        let result = match self.current_wal.shared_writable_file() {
+            Some(file) => syncer.sync(self.current_wal.path(), file, size).await,
+            None => self.current_wal.sync().await,
+        };
+        if result.is_err() {
+            self.unsynced_size += size;
+        }
+        result
+    }
+
     pub async fn wal_reader(&mut self, wal_id: u64) -> TskvResult<WalReader> {
         if wal_id == self.current_wal_id() {
             // Use the same wal as the writer.
@@ -212,7 +270,32 @@ impl VnodeWal {
 
     /// Close current record file, return count of bytes appended as footer.
     pub async fn close(&mut self) -> TskvResult<()> {
-        self.current_wal.close().await
+        let archived_until = now_timestamp_nanos();
+        self.current_wal.close().await?;
+        if let Some(archiver) = &self.archiver {
+            // The failure is logged by the archiver, it doesn't stop closing.
+            let _ = archiver
+                .archive_until(self.current_wal.path(), Some(archived_until))
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Archives the current wal file, so that all the raft logs proposed before are archived.
+    /// The returned future completes when the file is archived, the wal is not borrowed
+    /// meanwhile, so the writes are not blocked by it.
+    pub async fn archive_current(
+        &mut self,
+    ) -> TskvResult<impl Future<Output = TskvResult<()>> + Send + 'static> {
+        let Some(archiver) = &self.archiver else {
+            return Err(CommonSnafu {
+                reason: "wal.archive_url is not set".to_string(),
+            }
+            .build());
+        };
+        let archived_until = now_timestamp_nanos();
+        self.current_wal.flush_buffer().await?;
+        Ok(archiver.archive_until(self.current_wal.path(), Some(archived_until)))
     }
 
     pub fn config(&self) -> Arc<WalOptions> {
@@ -0,0 +1,166 @@
+//! Group commit of the WAL.
+//!
+//! When `wal.sync` is true, an fsync after every WAL write makes the throughput collapse
+//! under many small writes. With a `wal.sync_interval`, the writers of all the vnodes
+//! send the files they wrote to the [`WalSyncer`] and wait. The syncer collects the
+//! requests for at most `sync_interval`, or until they cover `sync_max_size` bytes,
+//! then syncs every file of the batch once and acknowledges all the writers.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::join_all;
+use snafu::IntoError;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
+
+use crate::error::{CommonSnafu, SyncFileSnafu};
+use crate::file_system::file::WritableFile;
+use crate::TskvResult;
+
+pub type WalSyncerRef = Arc<WalSyncer>;
+
+struct SyncRequest {
+    path: PathBuf,
+    file: Box<dyn WritableFile>,
+    size: u64,
+    callback: oneshot::Sender<Result<(), String>>,
+}
+
+pub struct WalSyncer {
+    sender: mpsc::UnboundedSender<SyncRequest>,
+}
+
+impl WalSyncer {
+    /// Starts a syncer in the current tokio runtime, it stops when all the references are dropped.
+    pub fn start(interval: Duration, max_size: u64) -> WalSyncerRef {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(Self::run(receiver, interval, max_size));
+        Arc::new(Self { sender })
+    }
+
+    /// Waits until the file is synced, then the `size` bytes written to
+    /// the file before the call are durable.
+    pub async fn sync(
+        &self,
+        path: PathBuf,
+        file: Box<dyn WritableFile>,
+        size: u64,
+    ) -> TskvResult<()> {
+        let (callback, receiver) = oneshot::channel();
+        let request = SyncRequest {
+            path,
+            file,
+            size,
+            callback,
+        };
+        let closed = || {
+            CommonSnafu {
+                reason: "wal syncer is closed".to_string(),
+            }
+            .build()
+        };
+        self.sender.send(request).map_err(|_| closed())?;
+        receiver
+            .await
+            .map_err(|_| closed())?
+            .map_err(|e| SyncFileSnafu.into_error(io::Error::new(io::ErrorKind::Other, e)))
+    }
+
+    async fn run(
+        mut receiver: mpsc::UnboundedReceiver<SyncRequest>,
+        interval: Duration,
+        max_size: u64,
+    ) {
+        while let Some(first) = receiver.recv().await {
+            // The batch is synced `interval` after its first request at the latest.
+            let deadline = Instant::now() + interval;
+            let mut size = first.size;
+            let mut batch = vec![first];
+            while size < max_size {
+                match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                    Ok(Some(request)) => {
+                        size += request.size;
+                        batch.push(request);
+                    }
+                    Ok(None) | Err(_) => break,
+                }
+            }
+            Self::sync_batch(batch).await;
+        }
+    }
+
+    async fn sync_batch(batch: Vec<SyncRequest>) {
+        let mut files: HashMap<PathBuf, (Box<dyn WritableFile>, Vec<_>)> = HashMap::new();
+        for request in batch {
+            files
+                .entry(request.path)
+                .or_insert_with(|| (request.file, vec![]))
+                .1
+                .push(request.callback);
+        }
+
+        join_all(
+            files
+                .into_iter()
+                .map(|(path, (file, callbacks))| async move {
+                    let result = file.sync_data().await.map_err(|e| {
+                        trace::error!("Failed to sync wal '{}': {}", path.display(), e);
+                        e.to_string()
+                    });
+                    for callback in callbacks {
+                        let _ = callback.send(result.clone());
+                    }
+                }),
+        )
+        .await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use super::WalSyncer;
+    use crate::record_file::Writer;
+
+    #[tokio::test]
+    async fn test_group_commit() {
+        let dir = PathBuf::from("/tmp/test/wal/syncer");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut writers = vec![];
+        for i in 0..2 {
+            let path = dir.join(format!("{i}.wal"));
+            writers.push((path.clone(), Writer::open(&path, 0).await.unwrap()));
+        }
+
+        let syncer = WalSyncer::start(Duration::from_millis(20), 1024 * 1024);
+        let mut handles = vec![];
+        for i in 0..8 {
+            let syncer = syncer.clone();
+            let (path, writer) = &writers[i % 2];
+            let (path, file) = (path.clone(), writer.shared_writable_file().unwrap());
+            handles.push(tokio::spawn(
+                async move { syncer.sync(path, file, 64).await },
+            ));
+        }
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+
+        // A batch that reaches the max size is synced without waiting for the interval.
+        let syncer = WalSyncer::start(Duration::from_secs(3600), 16);
+        let (path, writer) = &writers[0];
+        let file = writer.shared_writable_file().unwrap();
+        tokio::time::timeout(Duration::from_secs(10), syncer.sync(path.clone(), file, 16))
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
@@ -1,3 +1,5 @@
+use futures::future::BoxFuture;
+use futures::FutureExt;
 use openraft::{EntryPayload, LogId};
 use protos::kv_service::RaftWriteCommand;
 use protos::models_helper::parse_prost_bytes;
@@ -86,6 +88,13 @@ impl EntryStorage for RaftEntryStorage {
                 .await
                 .map_err(|e| ReplicationError::RaftInternalErr { msg: e.to_string() })?;
         }
+
+        // Entries are acknowledged after they are durable if the group commit is enabled.
+        self.inner
+            .wal
+            .wait_synced()
+            .await
+            .map_err(|e| ReplicationError::RaftInternalErr { msg: e.to_string() })?;
         Ok(())
     }
 
@@ -133,6 +142,21 @@ impl EntryStorage for RaftEntryStorage {
         let _ = self.inner.wal.sync().await;
         Ok(())
     }
+
+    async fn archive(&mut self) -> ReplicationResult<BoxFuture<'static, ReplicationResult<()>>> {
+        let archived = self
+            .inner
+            .wal
+            .archive_current()
+            .await
+            .map_err(|e| ReplicationError::RaftInternalErr { msg: e.to_string() })?;
+        Ok(async move {
+            archived
+                .await
+                .map_err(|e| ReplicationError::RaftInternalErr { msg: e.to_string() })
+        }
+        .boxed())
+    }
 }
 
 struct WalFileMeta {
@@ -515,6 +539,7 @@ mod test {
     use std::path::{Path, PathBuf};
     use std::sync::atomic::AtomicUsize;
     use std::sync::{atomic, Arc};
+    use std::time::Duration;
 
     use models::schema::database_schema::make_owner;
     use openraft::EntryPayload;
@@ -528,6 +553,7 @@ mod test {
     use crate::file_system::async_filesystem::LocalFileSystem;
     use crate::file_system::FileSystem;
     use crate::wal::reader::WalRecordData;
+    use crate::wal::syncer::WalSyncer;
     use crate::wal::wal_store::{RaftEntry, RaftEntryStorage};
     use crate::wal::VnodeWal;
     use crate::{file_utils, TskvResult};
@@ -541,9 +567,12 @@ mod test {
             wal_max_file_size: 1024 * 1024 * 1024,
             compress: "zstd".to_string(),
             wal_sync: false,
+            sync_interval: Duration::ZERO,
+            sync_max_size: 0,
+            archive: None,
         };
 
-        VnodeWal::new(Arc::new(wal_option), owner, 1234).await
+        VnodeWal::new(Arc::new(wal_option), owner, 1234, None).await
     }
 
     #[tokio::test]
@@ -673,6 +702,57 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_wal_group_commit() {
+        let dir = PathBuf::from("/tmp/test/wal/group_commit");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let wal_option = crate::kv_option::WalOptions {
+            path: dir.clone(),
+            wal_max_file_size: 1024 * 1024 * 1024,
+            compress: "zstd".to_string(),
+            wal_sync: true,
+            sync_interval: Duration::from_millis(5),
+            sync_max_size: 4 * 1024,
+            archive: None,
+        };
+        let syncer = WalSyncer::start(wal_option.sync_interval, wal_option.sync_max_size);
+        let owner = Arc::new(make_owner("cnosdb", "test_db"));
+        let wal = VnodeWal::new(Arc::new(wal_option), owner, 1234, Some(syncer))
+            .await
+            .unwrap();
+        let wal_dir = wal.wal_dir.clone();
+        let mut storage = RaftEntryStorage::new(wal);
+
+        for i in 0..100 {
+            let mut entry = RaftEntry::default();
+            entry.log_id.index = i;
+            entry.payload = EntryPayload::Normal(format!("payload_{}", i).as_bytes().to_vec());
+            storage.append(&[entry]).await.unwrap();
+        }
+        // Drop the storage without closing the wal, the entries acknowledged by
+        // the group commit must be in the file.
+        drop(storage);
+
+        let wal = get_vnode_wal(&dir).await.unwrap();
+        let mut storage = RaftEntryStorage::new(wal);
+        let mut indexes = vec![];
+        for file_name in LocalFileSystem::list_file_names(&wal_dir) {
+            let wal_id = file_utils::get_wal_file_id(&file_name).unwrap();
+            let reader = storage.inner.wal.wal_reader(wal_id).await.unwrap();
+            let mut record_reader = reader.take_record_reader();
+            while let Ok(record) = record_reader.read_record().await {
+                if record.data.len() < 9 {
+                    continue;
+                }
+                let wal_record = WalRecordData::new(record.data, record.pos, "zstd").unwrap();
+                indexes.push(wal_record.block.log_id.index);
+            }
+        }
+        assert_eq!(indexes, (0..100).collect::<Vec<_>>());
+    }
+
     pub async fn get_node_store(dir: impl AsRef<Path>) -> Arc<NodeStorage> {
         trace::debug!("----------------------------------------");
         let dir = dir.as_ref();
@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use crate::file_system::file::WritableFile;
 use crate::kv_option::WalOptions;
 use crate::record_file::{RecordDataType, RecordDataVersion, Writer};
 use crate::wal::reader::WalReader;
@@ -58,7 +59,8 @@ impl WalWriter {
             )
             .await?;
 
-        if self.config.wal_sync {
+        // With group commit, the entries are synced by the `WalSyncer` after appended.
+        if self.config.wal_sync && !self.config.group_commit() {
             self.inner.sync().await?;
         }
 
@@ -70,6 +72,14 @@ impl WalWriter {
         self.inner.sync().await
     }
 
+    pub async fn flush_buffer(&mut self) -> TskvResult<()> {
+        self.inner.flush_buffer().await
+    }
+
+    pub fn shared_writable_file(&self) -> Option<Box<dyn WritableFile>> {
+        self.inner.shared_writable_file()
+    }
+
     pub async fn close(&mut self) -> TskvResult<()> {
         trace::info!("Wal '{}' closing", self.path.display(),);
         self.inner.close().await?;
@@ -11,8 +11,10 @@ mod tests {
     use models::meta_data::VnodeId;
     use models::schema::database_schema::make_owner;
     use models::schema::tenant::TenantOptions;
-    use protos::kv_service::{raft_write_command, WriteDataRequest};
+    use openraft::EntryPayload;
+    use protos::kv_service::{raft_write_command, RaftWriteCommand, WriteDataRequest};
     use protos::models_helper;
+    use replication::EntryStorage;
     use serial_test::serial;
     use sysinfo::{ProcessRefreshKind, RefreshKind, System};
     use tokio::runtime;
@@ -21,6 +23,8 @@ mod tests {
     use trace::{debug, error, info, warn};
     use tskv::file_system::async_filesystem::LocalFileSystem;
     use tskv::file_system::FileSystem;
+    use tskv::wal::wal_store::{RaftEntry, RaftEntryStorage};
+    use tskv::wal::VnodeWal;
     use tskv::{file_utils, kv_option, Engine, TsKv};
     use utils::precision::Precision;
 
@@ -35,6 +39,7 @@ mod tests {
         let mut global_config = config::tskv::get_config_for_test();
         global_config.wal.path = dir.join("wal").to_str().unwrap().to_string();
         global_config.storage.path = dir.to_str().unwrap().to_string();
+        global_config.wal.archive_url = format!("file://{}", dir.join("wal_archive").display());
 
         global_config
     }
@@ -127,6 +132,7 @@ mod tests {
                 test_kvcore_flush_delta();
                 test_kvcore_build_row_data();
                 test_kvcore_snapshot_create_apply_delete();
+                test_kvcore_restore_vnode_until();
             })
             .await;
         });
@@ -455,6 +461,101 @@ mod tests {
         println!("Leave serial test: test_kvcore_snapshot_create_apply_delete");
     }
 
+    fn test_kvcore_restore_vnode_until() {
+        println!("Enter serial test: test_kvcore_restore_vnode_until");
+        let dir = PathBuf::from("/tmp/test/kvcore/kvcore_restore_vnode_until");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        init_default_global_tracing(dir.join("log"), "tskv.log", "debug");
+        let tenant = "cnosdb";
+        let database = "db_test_restore";
+        let table = "tab_test_restore";
+        let vnode_id = 21;
+        let write_request = |rows: usize| {
+            let mut fbb = flatbuffers::FlatBufferBuilder::new();
+            let points =
+                models_helper::create_random_points_include_delta(&mut fbb, database, table, rows);
+            fbb.finish(points, None);
+            WriteDataRequest {
+                data: fbb.finished_data().to_vec(),
+                precision: Precision::NS as u32,
+            }
+        };
+
+        let (runtime, tskv) = get_tskv(&dir, None);
+        tskv_write(
+            runtime.clone(),
+            &tskv,
+            tenant,
+            database,
+            vnode_id,
+            1,
+            write_request(20),
+        );
+        let snapshot = runtime
+            .block_on(tskv.backup_vnode(vnode_id))
+            .unwrap()
+            .unwrap();
+        let owner = make_owner(tenant, database);
+        let snapshot_dir = dir.join("snapshot");
+        let vnode_data_dir = tskv.get_storage_options().ts_family_dir(&owner, vnode_id);
+        dircpy::copy_dir(vnode_data_dir, &snapshot_dir).unwrap();
+
+        // The raft logs after the snapshot, proposed at 100 and 200.
+        let wal_options = Arc::new(kv_option::WalOptions::from(&get_config(&dir)));
+        runtime.block_on(async {
+            let wal = VnodeWal::new(wal_options, Arc::new(owner.clone()), vnode_id, None)
+                .await
+                .unwrap();
+            let mut raft_logs = RaftEntryStorage::new(wal);
+            for (i, create_time) in [100, 200].into_iter().enumerate() {
+                let command = RaftWriteCommand {
+                    tenant: tenant.to_string(),
+                    db_name: database.to_string(),
+                    create_time,
+                    command: Some(raft_write_command::Command::WriteData(write_request(10))),
+                    ..Default::default()
+                };
+                let mut entry = RaftEntry::default();
+                entry.log_id.index = snapshot.last_seq_no + 1 + i as u64;
+                entry.payload = EntryPayload::Normal(models_helper::to_prost_bytes(&command));
+                raft_logs.append(&[entry]).await.unwrap();
+            }
+            raft_logs.archive().await.unwrap().await.unwrap();
+        });
+
+        let new_vnode_id = 22;
+        let restore = |snapshot, until| {
+            runtime.block_on(tskv.restore_vnode(
+                tenant,
+                database,
+                new_vnode_id,
+                snapshot,
+                &snapshot_dir,
+                Some(until),
+            ))
+        };
+        // The archive doesn't reach the time.
+        assert!(restore(snapshot.clone(), i64::MAX).is_err());
+        // The archive of the vnode is missing.
+        let mut missing = snapshot.clone();
+        missing.vnode_id = 99;
+        assert!(restore(missing, 150).is_err());
+
+        restore(snapshot.clone(), 150).unwrap();
+        let vnode = runtime
+            .block_on(tskv.open_tsfamily(tenant, database, new_vnode_id))
+            .unwrap();
+        let version_edit =
+            runtime.block_on(async move { vnode.ts_family().read().await.build_version_edit() });
+        // The replayed log is flushed to a new file.
+        assert!(version_edit.add_files.len() > snapshot.version_edit.add_files.len());
+
+        runtime.block_on(tskv.close());
+        println!("Leave serial test: test_kvcore_restore_vnode_until");
+    }
+
     fn sleep_in_runtime(runtime: Arc<Runtime>, duration: Duration) {
         let rt = runtime.clone();
         runtime.block_on(async move {